@@ -0,0 +1,39 @@
+//! Parse arbitrary bytes as an MDF file and read everything it offers.
+//! Errors are expected; panics, aborts and hangs are bugs.
+//!
+//! ```bash
+//! cargo +nightly fuzz run parse
+//! ```
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::index::{MdfIndex, SliceRangeReader};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(index) = MdfIndex::from_reader(&mut SliceRangeReader::new(data.to_vec())) {
+        let mut reader = index.open(SliceRangeReader::new(data.to_vec()));
+        let names: Vec<String> = index
+            .channel_groups
+            .iter()
+            .flat_map(|group| group.channels.iter().filter_map(|channel| channel.name.clone()))
+            .collect();
+        for name in &names {
+            let _ = reader.values(name);
+            let _ = reader.values_f64(name);
+        }
+    }
+
+    let Ok(mdf) = MDF::from_bytes(data.to_vec()) else { return };
+    let _ = mdf.metadata();
+    let _ = mdf.file_layout();
+    let _ = mdf.events();
+    for group in mdf.channel_groups() {
+        let _ = group.time_values();
+        for channel in group.channels() {
+            let _ = channel.values();
+            let _ = channel.values_f64();
+        }
+    }
+});
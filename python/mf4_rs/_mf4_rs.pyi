@@ -2,6 +2,8 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import os
+import pathlib
 import typing
 from enum import Enum, auto
 
@@ -38,7 +40,8 @@ class ChannelInfo:
     Read-only metadata describing a single channel.
     
     Found on :py:attr:`GroupInfo.channels`, and returned by
-    :py:meth:`Mdf.channel` / :py:meth:`MdfIndex.channel`.
+    :py:meth:`Mdf.channel` / :py:meth:`MdfIndex.channel` /
+    :py:meth:`MdfIndex.channel_descriptor`.
     
     Attributes
     ----------
@@ -47,12 +50,15 @@ class ChannelInfo:
     unit : Optional[str]
         Engineering unit string, or ``None``.
     comment : Optional[str]
-        Free-form comment, or ``None``. Always ``None`` when obtained via the
-        index (not stored in `IndexedChannel`).
+        Free-form comment, or ``None``. Indexes saved before comments were
+        stored report ``None``.
     data_type : DataType
         The MDF data type of the raw samples.
     bit_count : int
         Width of the raw value in bits (e.g. 32 for f32, 64 for f64/u64).
+    conversion : Optional[str]
+        One-line summary of the conversion (e.g. ``"linear: 3 + 0.5 * x"``),
+        or ``None`` if the channel has none.
     """
     name: typing.Optional[builtins.str]
     unit: typing.Optional[builtins.str]
@@ -61,6 +67,7 @@ class ChannelInfo:
     bit_count: builtins.int
     is_master: builtins.bool
     is_vlsd: builtins.bool
+    conversion: typing.Optional[builtins.str]
     def __str__(self) -> builtins.str:
         ...
 
@@ -104,13 +111,13 @@ class FileLayout:
     blocks: builtins.list[BlockInfo]
     gaps: builtins.list[GapInfo]
     @staticmethod
-    def from_file(path:builtins.str) -> FileLayout:
+    def from_file(path:builtins.str | os.PathLike | pathlib.Path) -> FileLayout:
         r"""
         Build a layout by parsing an MDF file from disk.
         
         Parameters
         ----------
-        path : str
+        path : str or os.PathLike
         """
         ...
 
@@ -135,19 +142,19 @@ class FileLayout:
         """
         ...
 
-    def write_text_to_file(self, path:builtins.str) -> None:
+    def write_text_to_file(self, path:builtins.str | os.PathLike | pathlib.Path) -> None:
         r"""
         Write the flat text listing (see :py:meth:`to_text`) to ``path``.
         """
         ...
 
-    def write_tree_to_file(self, path:builtins.str) -> None:
+    def write_tree_to_file(self, path:builtins.str | os.PathLike | pathlib.Path) -> None:
         r"""
         Write the indented tree view (see :py:meth:`to_tree`) to ``path``.
         """
         ...
 
-    def write_json_to_file(self, path:builtins.str) -> None:
+    def write_json_to_file(self, path:builtins.str | os.PathLike | pathlib.Path) -> None:
         r"""
         Write the JSON representation (see :py:meth:`to_json`) to ``path``.
         """
@@ -253,7 +260,7 @@ class Mdf:
     source: builtins.str
     groups: builtins.list[GroupInfo]
     channel_names: builtins.list[builtins.str]
-    def __new__(cls,path:builtins.str): ...
+    def __new__(cls,path:builtins.str | os.PathLike | pathlib.Path, mmap:typing.Optional[builtins.bool]): ...
     def list_signals(self) -> builtins.list[tuple[builtins.str, typing.Optional[builtins.str], typing.Optional[builtins.str]]]:
         r"""
         A flat catalog of every channel as ``(source, group, channel)`` tuples.
@@ -279,6 +286,16 @@ class Mdf:
         """
         ...
 
+    def find_channels_matching(self, pattern:builtins.str) -> builtins.list[tuple[builtins.int, builtins.int, builtins.str]]:
+        r"""
+        Channels whose name matches the glob ``pattern`` as
+        ``[(group_index, channel_index, name), ...]`` in file order.
+        
+        ``*`` matches any characters (including ``.``), ``?`` one character
+        and ``[a-z]`` / ``[!a-z]`` one character of (or not of) a set.
+        """
+        ...
+
     def read(self, name:builtins.str, group:typing.Optional[builtins.str]) -> typing.Any:
         r"""
         Read a channel as a ``pandas.Series`` of values indexed by timestamps.
@@ -319,6 +336,56 @@ class Mdf:
         """
         ...
 
+    def group_arrays(self, group:builtins.str) -> typing.Any:
+        r"""
+        Decode every channel of a group into numpy arrays in one call.
+        
+        Decoding happens in Rust and each column is handed to numpy without a
+        copy, so no Python object is created per sample. The arrays implement
+        the buffer protocol; ``pandas.DataFrame(arrays)`` or
+        ``polars.DataFrame(arrays)`` build a frame from them directly.
+        
+        Unsigned integer channels without conversion are ``uint64``, other
+        integer channels ``int64`` and numeric channels ``float64`` with
+        ``NaN`` for invalid samples; channels with text or byte values are
+        lists with ``None`` for invalid samples. Unnamed channels are keyed
+        ``channel_<n>`` by their position in the group, as are channels whose
+        name an earlier channel of the group already has; use
+        :py:meth:`get_group_as_arrays` to keep the names as they are.
+        
+        Parameters
+        ----------
+        group : str
+            Name of the channel group.
+        
+        Returns
+        -------
+        dict[str, numpy.ndarray | list]
+            Channel name to samples, in channel order.
+        """
+        ...
+
+    def get_group_as_arrays(self, group_index:builtins.int) -> builtins.list[tuple[typing.Optional[builtins.str], typing.Any]]:
+        r"""
+        Decode every channel of the group at ``group_index`` into numpy
+        arrays in one call, as :py:meth:`group_arrays` does.
+        
+        The columns are returned as ``(name, array)`` pairs in channel order,
+        so channels sharing a name are all kept; unnamed channels have the
+        name ``None``.
+        
+        Parameters
+        ----------
+        group_index : int
+            Position of the channel group in the file, counted from zero as
+            in :py:attr:`groups`.
+        
+        Returns
+        -------
+        list[tuple[Optional[str], numpy.ndarray | list]]
+        """
+        ...
+
     def __getitem__(self, key:typing.Any) -> typing.Any:
         r"""
         ``mdf["Speed"]`` — shorthand for :py:meth:`read` (timestamp-indexed Series).
@@ -337,6 +404,14 @@ class Mdf:
         """
         ...
 
+    def metadata_json(self) -> builtins.str:
+        r"""
+        Describe the whole file as a JSON string — groups, channels, data
+        types, units, conversions with their parameters, sources and
+        attachments — without reading any sample data.
+        """
+        ...
+
 
 class MdfIndex:
     r"""
@@ -349,7 +424,8 @@ class MdfIndex:
     byte-range request happens on :py:meth:`read` / :py:meth:`values`, never at
     build time.
     
-    **Limitation:** compressed (``##DZ``) data blocks are not supported.
+    Compressed (``##DZ``) data blocks are inflated on read, but
+    :py:meth:`byte_ranges` cannot address samples inside them.
     
     Example
     -------
@@ -366,7 +442,7 @@ class MdfIndex:
     file_size: builtins.int
     source: typing.Optional[builtins.str]
     @staticmethod
-    def from_file(path:builtins.str) -> MdfIndex:
+    def from_file(path:builtins.str | os.PathLike | pathlib.Path) -> MdfIndex:
         r"""
         Build a fresh index by parsing an MDF file from disk.
         
@@ -375,13 +451,13 @@ class MdfIndex:
         
         Parameters
         ----------
-        path : str
+        path : str or os.PathLike
             Path to a ``.mf4`` file.
         """
         ...
 
     @staticmethod
-    def load(path:builtins.str) -> MdfIndex:
+    def load(path:builtins.str | os.PathLike | pathlib.Path) -> MdfIndex:
         r"""
         Load a previously saved JSON index (companion to :py:meth:`save`).
         
@@ -409,7 +485,7 @@ class MdfIndex:
         """
         ...
 
-    def save(self, path:builtins.str) -> None:
+    def save(self, path:builtins.str | os.PathLike | pathlib.Path) -> None:
         r"""
         Serialize the index to JSON at ``path`` (dependency-free).
         """
@@ -427,6 +503,31 @@ class MdfIndex:
         """
         ...
 
+    def time_range(self, group:builtins.str) -> typing.Optional[tuple[builtins.float, builtins.float]]:
+        r"""
+        ``(first, last)`` master (time) value of the group called ``group``,
+        stored when the index was built, or ``None`` if the group is unknown
+        or has no master channel or records. Reads no sample data.
+        """
+        ...
+
+    def channel_descriptor(self, group:builtins.int, index:builtins.int) -> typing.Optional[ChannelInfo]:
+        r"""
+        Metadata of channel ``index`` of group ``group`` (both counted from
+        zero), or ``None`` if either is out of range.
+        """
+        ...
+
+    def find_channels_matching(self, pattern:builtins.str) -> builtins.list[tuple[builtins.int, builtins.int, builtins.str]]:
+        r"""
+        Channels whose name matches the glob ``pattern`` as
+        ``[(group_index, channel_index, name), ...]`` in file order.
+        
+        ``*`` matches any characters (including ``.``), ``?`` one character
+        and ``[a-z]`` / ``[!a-z]`` one character of (or not of) a set.
+        """
+        ...
+
     def groups_with_channel(self, name:builtins.str) -> builtins.list[builtins.str]:
         r"""
         Names of the groups that contain a channel called ``name``.
@@ -436,7 +537,7 @@ class MdfIndex:
         """
         ...
 
-    def byte_ranges(self, name:builtins.str, group:typing.Optional[builtins.str]) -> builtins.list[tuple[builtins.int, builtins.int]]:
+    def byte_ranges(self, name:builtins.str, group:typing.Optional[builtins.str], invalidation:typing.Optional[builtins.bool]) -> builtins.list[tuple[builtins.int, builtins.int]]:
         r"""
         Byte ranges ``[(offset, length), ...]`` occupied by a channel.
         
@@ -448,12 +549,17 @@ class MdfIndex:
         name : str
         group : Optional[str]
             Disambiguate by group when the name is not unique.
+        invalidation : Optional[bool]
+            Also cover the channel's invalidation byte in every record, so the
+            samples' validity can be read from the fetched bytes.
         """
         ...
 
-    def byte_ranges_for_records(self, name:builtins.str, start_record:builtins.int, record_count:builtins.int) -> builtins.list[tuple[builtins.int, builtins.int]]:
+    def byte_ranges_for_records(self, name:builtins.str, start_record:builtins.int, record_count:builtins.int, invalidation:typing.Optional[builtins.bool]) -> builtins.list[tuple[builtins.int, builtins.int]]:
         r"""
         Byte ranges covering a record window ``[start, start+count)``.
+        
+        ``invalidation`` is as in :py:meth:`byte_ranges`.
         """
         ...
 
@@ -530,6 +636,35 @@ class MdfIndex:
         """
         ...
 
+    def read_channel_slice(self, group:builtins.str, channel:builtins.str, start:builtins.int, count:builtins.int) -> typing.Any:
+        r"""
+        Read records ``start .. start + count`` of a channel as a numpy array.
+        
+        Lazy partial read: only the bytes of the requested records are fetched
+        from uncompressed data blocks, so large channels can be paged through.
+        Integer channels come back as ``int64``, other numeric channels as
+        ``float64`` with ``NaN`` for invalid samples; channels with text or
+        byte values come back as a list with ``None`` for invalid samples.
+        
+        Parameters
+        ----------
+        group : str
+            Name of the channel group.
+        channel : str
+            Name of the channel within the group.
+        start : int
+            First record to read.
+        count : int
+            Number of records to read.
+        
+        Raises
+        ------
+        MdfException
+            If no source is attached, the channel is missing or VLSD, or the
+            records are out of range.
+        """
+        ...
+
     def __getitem__(self, key:typing.Any) -> typing.Any:
         r"""
         ``index["Speed"]`` — shorthand for :py:meth:`read` (timestamp-indexed Series).
@@ -564,6 +699,10 @@ class MdfWriter:
     them. Channels are auto-linked into a per-group linked list in the order
     they were added.
     
+    The writer is not tied to the thread that created it, so it can be
+    handed to a logging thread; calls from two threads at the same time
+    raise ``RuntimeError`` (the object is already borrowed).
+    
     Example
     -------
     >>> w = mf4_rs.MdfWriter("demo.mf4")
@@ -580,7 +719,7 @@ class MdfWriter:
     >>> w.finish_data_block(cg)
     >>> w.finalize()
     """
-    def __new__(cls,path:builtins.str): ...
+    def __new__(cls,path:builtins.str | os.PathLike | pathlib.Path): ...
     def init_mdf_file(self) -> None:
         r"""
         Write the MDF identification (``##ID``) and header (``##HD``) blocks.
@@ -737,6 +876,67 @@ class MdfWriter:
         """
         ...
 
+    def write_records(self, group_id:builtins.str, records:typing.Sequence[typing.Sequence[DecodedValue]]) -> None:
+        r"""
+        Append several records at once, each given as in
+        :py:meth:`write_record`.
+        
+        The encoded records are buffered and written in one go, which saves
+        the per-call overhead of looping over :py:meth:`write_record`.
+        """
+        ...
+
+    def write_record_u64(self, group_id:builtins.str, values:typing.Sequence[builtins.int]) -> None:
+        r"""
+        Append a single record of a group whose channels are all unsigned
+        integers, skipping :class:`DecodedValue` conversion.
+        
+        Raises
+        ------
+        MdfException
+            If a channel is not an unsigned integer or the value count does
+            not match the channel count.
+        """
+        ...
+
+    def set_record_template(self, group_id:builtins.str, values:typing.Sequence[DecodedValue]) -> None:
+        r"""
+        Set the record template of the group's open data block: the bytes
+        each record starts from before its values are encoded.
+        
+        ``values`` holds one entry per channel; ``DecodedValue.Unknown()``
+        leaves a channel zeroed. A channel given ``DecodedValue.Unknown()`` in
+        :py:meth:`write_record` / :py:meth:`write_records` keeps its template
+        value. Call after :py:meth:`start_data_block`; the template lasts
+        until :py:meth:`finish_data_block`.
+        """
+        ...
+
+    def set_constant_channels(self, group_id:builtins.str, constants:typing.Sequence[typing.Optional[DecodedValue]]) -> None:
+        r"""
+        Fix channels of a group to constant values for the rest of its open
+        data block.
+        
+        ``constants`` holds one entry per channel: a :class:`DecodedValue`
+        for a constant channel, ``None`` for a varying one. Afterwards
+        :py:meth:`write_varying_records` and :py:meth:`write_varying_columns`
+        take values for the varying channels only.
+        
+        Raises
+        ------
+        MdfException
+            If the entry count does not match the channel count or a VLSD
+            channel is given a constant.
+        """
+        ...
+
+    def write_varying_records(self, group_id:builtins.str, records:typing.Sequence[typing.Sequence[DecodedValue]]) -> None:
+        r"""
+        Append records giving values for the varying channels only (see
+        :py:meth:`set_constant_channels`), in channel order.
+        """
+        ...
+
     def finish_data_block(self, group_id:builtins.str) -> None:
         r"""
         Close the open ``##DT`` block for a channel group.
@@ -799,6 +999,54 @@ class MdfWriter:
         """
         ...
 
+    def write_columns_with_time(self, group_id:builtins.str, time:typing.Any, columns:typing.Sequence[typing.Any], dtypes:typing.Sequence[builtins.str]) -> None:
+        r"""
+        Bulk-write a group from a numpy time base and numpy data columns.
+        
+        Like :py:meth:`write_columns`, but the master channel's samples are
+        passed on their own as ``time`` and ``columns`` holds the other
+        channels, in channel order.
+        
+        Parameters
+        ----------
+        group_id : str
+        time : numpy.ndarray
+            Contiguous 1-D ``float64`` array of master channel values.
+        columns : list[numpy.ndarray]
+            One contiguous 1-D array per non-master channel, each as long as
+            ``time``.
+        dtypes : list[str]
+            One entry per column: ``"f64"``, ``"f32"``, ``"u64"`` or ``"i64"``.
+        
+        Raises
+        ------
+        MdfException
+            If the group has no master channel, or on any error
+            :py:meth:`write_columns` would raise.
+        """
+        ...
+
+    def write_varying_columns(self, group_id:builtins.str, columns:typing.Sequence[typing.Any], dtypes:typing.Sequence[builtins.str]) -> None:
+        r"""
+        Bulk-write the varying channels of a group from numpy arrays.
+        
+        Like :py:meth:`write_columns`, but with one column per channel left
+        ``None`` in :py:meth:`set_constant_channels` (all channels if it was
+        not called), in channel order. The constant channels are copied from
+        the record template, so a mostly static group costs little more than
+        a memory copy per record.
+        
+        Parameters
+        ----------
+        group_id : str
+        columns : list[numpy.ndarray]
+            One contiguous 1-D array per varying channel, all of identical
+            length.
+        dtypes : list[str]
+            One entry per column: ``"f64"``, ``"f32"``, ``"u64"`` or ``"i64"``.
+        """
+        ...
+
     def finalize(self) -> None:
         r"""
         Flush all buffered bytes to disk and close the file.
@@ -836,6 +1084,31 @@ class DecodedValue(Enum):
     ByteArray = auto()
     Unknown = auto()
 
+def concatenate(inputs:typing.Sequence[builtins.str | os.PathLike | pathlib.Path], output:builtins.str | os.PathLike | pathlib.Path) -> None:
+    r"""
+    Concatenate MDF files recorded one after another into ``output``.
+    
+    Unlike :py:func:`merge_many`, every input must have the same channel
+    groups, in the same order, with the same channel layouts; group ``k`` of
+    the output holds the records of group ``k`` of every input. Metadata and
+    the start time come from the first input, and the master channels of
+    later inputs are shifted by the difference of their start times so the
+    time stays continuous.
+    
+    Parameters
+    ----------
+    inputs : list[str or os.PathLike]
+        Source file paths, in recording order.
+    output : str or os.PathLike
+        Destination path for the concatenated file.
+    
+    Raises
+    ------
+    MdfException
+        If the channel group layouts of the inputs differ.
+    """
+    ...
+
 def create_data_type_float_le() -> DataType:
     r"""
     Return the :class:`DataType` for little-endian IEEE-754 floats.
@@ -895,7 +1168,7 @@ def create_uint_value(value:builtins.int) -> DecodedValue:
     """
     ...
 
-def cut_mdf_by_time(input_path:builtins.str, output_path:builtins.str, start_time:builtins.float, end_time:builtins.float) -> None:
+def cut_mdf_by_time(input_path:builtins.str | os.PathLike | pathlib.Path, output_path:builtins.str | os.PathLike | pathlib.Path, start_time:builtins.float, end_time:builtins.float, rename:typing.Optional[typing.Mapping[builtins.str, builtins.str]], units:typing.Optional[typing.Mapping[builtins.str, builtins.str]], preserve_blocks:typing.Optional[builtins.bool]) -> None:
     r"""
     Cut an MDF file by time, copying only records whose master channel value
     falls within the inclusive `[start_time, end_time]` window.
@@ -908,18 +1181,26 @@ def cut_mdf_by_time(input_path:builtins.str, output_path:builtins.str, start_tim
     
     Parameters
     ----------
-    input_path : str
+    input_path : str or os.PathLike
         Path to the source MF4 file.
-    output_path : str
+    output_path : str or os.PathLike
         Destination path for the trimmed file.
     start_time : float
         Start of the window in seconds (inclusive).
     end_time : float
         End of the window in seconds (inclusive).
+    rename : dict[str, str], optional
+        Source channel name → output channel name.
+    units : dict[str, str], optional
+        Output channel name → unit, replacing the source unit.
+    preserve_blocks : bool, optional
+        Also copy the header comment, file history, events, attachments and
+        blocks the library does not interpret, rewiring their links to the
+        output channels. Defaults to ``False``.
     """
     ...
 
-def cut_mdf_by_utc(input_path:builtins.str, output_path:builtins.str, start_utc:typing.Any, end_utc:typing.Any) -> None:
+def cut_mdf_by_utc(input_path:builtins.str | os.PathLike | pathlib.Path, output_path:builtins.str | os.PathLike | pathlib.Path, start_utc:typing.Any, end_utc:typing.Any) -> None:
     r"""
     Cut an MDF file by absolute UTC time. Accepts ISO 8601 strings (e.g.
     `"2024-01-15T12:34:56Z"`), `datetime.datetime` objects (naive values are
@@ -935,7 +1216,27 @@ def cut_mdf_by_utc(input_path:builtins.str, output_path:builtins.str, start_utc:
     """
     ...
 
-def file_layout_from_file(path:builtins.str) -> FileLayout:
+def extract_bus_logging(input_path:builtins.str | os.PathLike | pathlib.Path, dbc_path:builtins.str | os.PathLike | pathlib.Path, output_path:builtins.str | os.PathLike | pathlib.Path) -> None:
+    r"""
+    Decode the raw CAN frames of a recording with a DBC file.
+    
+    Every ``CAN_DataFrame`` group of the input is decoded and the output gets
+    one channel group per bus channel and DBC message, named after the
+    message, with a ``Timestamp`` master and one float channel per signal.
+    Multiplexed signals absent from a frame are stored as invalid samples.
+    
+    Parameters
+    ----------
+    input_path : str or os.PathLike
+        Path to the MF4 file holding raw CAN frames.
+    dbc_path : str or os.PathLike
+        Path to the DBC file with the message and signal definitions.
+    output_path : str or os.PathLike
+        Destination path for the decoded signals.
+    """
+    ...
+
+def file_layout_from_file(path:builtins.str | os.PathLike | pathlib.Path) -> FileLayout:
     r"""
     Build a :class:`FileLayout` from an MDF file on disk.
     
@@ -943,7 +1244,7 @@ def file_layout_from_file(path:builtins.str) -> FileLayout:
     
     Parameters
     ----------
-    path : str
+    path : str or os.PathLike
     
     Returns
     -------
@@ -951,7 +1252,7 @@ def file_layout_from_file(path:builtins.str) -> FileLayout:
     """
     ...
 
-def merge_files(output:builtins.str, first:builtins.str, second:builtins.str) -> None:
+def merge_files(output:builtins.str | os.PathLike | pathlib.Path, first:builtins.str | os.PathLike | pathlib.Path, second:builtins.str | os.PathLike | pathlib.Path, rename:typing.Optional[typing.Mapping[builtins.str, builtins.str]], units:typing.Optional[typing.Mapping[builtins.str, builtins.str]]) -> None:
     r"""
     Merge two MDF files into a new file at ``output``.
     
@@ -963,10 +1264,64 @@ def merge_files(output:builtins.str, first:builtins.str, second:builtins.str) ->
     
     Parameters
     ----------
-    output : str
+    output : str or os.PathLike
         Destination path for the merged file.
-    first, second : str
-        Source file paths. Must be uncompressed MDF 4.10+ files.
+    first, second : str or os.PathLike
+        Source file paths. Must be MDF 4.10+ files.
+    rename : dict[str, str], optional
+        Source channel name → output channel name, applied before the group
+        layouts are compared.
+    units : dict[str, str], optional
+        Output channel name → unit.
+    """
+    ...
+
+def merge_many(output:builtins.str | os.PathLike | pathlib.Path, inputs:typing.Sequence[builtins.str | os.PathLike | pathlib.Path], rename:typing.Optional[typing.Mapping[builtins.str, builtins.str]], units:typing.Optional[typing.Mapping[builtins.str, builtins.str]], clock_corrections:typing.Optional[typing.Sequence[tuple[builtins.float, builtins.float]]], preserve_blocks:typing.Optional[builtins.bool]) -> None:
+    r"""
+    Merge any number of MDF files into a new file at ``output``.
+    
+    Like :py:func:`merge_files` for a list of inputs, merged in the order
+    given: each group is concatenated to the first earlier group with the same
+    layout, or appended as a separate group.
+    
+    Parameters
+    ----------
+    output : str or os.PathLike
+        Destination path for the merged file.
+    inputs : list[str or os.PathLike]
+        Source file paths. Must be MDF 4.10+ files.
+    rename : dict[str, str], optional
+        Source channel name → output channel name, applied before the group
+        layouts are compared.
+    units : dict[str, str], optional
+        Output channel name → unit.
+    clock_corrections : list[tuple[float, float]], optional
+        ``(offset, factor)`` per input, in input order: the master channel
+        values ``t`` of that input are written as ``offset + factor * t``, to
+        line up logs of devices with offset or drifting clocks. Inputs past
+        the end of the list are merged unchanged.
+    preserve_blocks : bool, optional
+        Also copy each input's header comment, file history, events,
+        attachments and uninterpreted blocks, rewiring their links to the
+        merged channels. Defaults to ``False``.
+    """
+    ...
+
+def sort_mdf(input_path:builtins.str | os.PathLike | pathlib.Path, output_path:builtins.str | os.PathLike | pathlib.Path) -> None:
+    r"""
+    Rewrite an MDF file so that every data group holds a single channel group.
+    
+    Unsorted data groups, whose records of several channel groups are
+    interleaved and told apart by a record ID, are split into one data group
+    per channel group. Records are streamed from the input, so files larger
+    than memory can be sorted.
+    
+    Parameters
+    ----------
+    input_path : str or os.PathLike
+        Path to the source MF4 file.
+    output_path : str or os.PathLike
+        Destination path for the sorted file.
     """
     ...
 
@@ -1,16 +1,68 @@
+use std::borrow::Cow;
+
 use crate::error::MdfError;
 use crate::blocks::channel_block::ChannelBlock;
-use crate::parsing::decoder::{ DecodedValue, decode_channel_value, decode_channel_value_with_validity, decode_f64_from_record };
+use crate::blocks::conversion::SimplifiedConversion;
+use crate::parsing::decoder::{ DecodedChannelValue, DecodedValue, PrimitiveLayout, always_valid, check_value_validity, decode_channel_value, decode_channel_value_with_validity, decode_f64_from_record, extend_f64_column, extend_u64_column };
 use crate::parsing::raw_channel_group::RawChannelGroup;
 use crate::parsing::raw_data_group::RawDataGroup;
-use crate::parsing::raw_channel::RawChannel;
+use crate::parsing::raw_channel::{RawChannel, Records};
 use crate::parsing::source_info::SourceInfo;
-use crate::blocks::common::read_string_block;
+use crate::signal::{ChannelStatistics, StatisticsAccumulator};
+use crate::blocks::channel_array_block::{ArrayStorage, ChannelArrayBlock, CA_FLAG_INVERSE_LAYOUT};
+use crate::blocks::common::{bytes_from, read_string_block, string_block_eq, BlockParse, DataType};
+use crate::api::mime::{extension_for_mime_type, parse_mime_type};
+
+/// One embedded sample of a MIME channel, `None` where the record holds no sample.
+type MimeSample<'a> = Option<Cow<'a, [u8]>>;
+
+/// The samples of an array channel in one record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelArray {
+    /// Size of each dimension.
+    pub shape: Vec<usize>,
+    /// Elements in row-major order (last index changes fastest); `None` for
+    /// invalid or undecodable elements.
+    pub values: Vec<Option<DecodedValue>>,
+}
+
+/// An array channel sample as nested vectors, one level per dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NestedValue {
+    Scalar(Option<DecodedValue>),
+    Array(Vec<NestedValue>),
+}
+
+impl ChannelArray {
+    /// The element at `index` (one entry per dimension).
+    pub fn get(&self, index: &[usize]) -> Option<&DecodedValue> {
+        if index.len() != self.shape.len() || index.iter().zip(&self.shape).any(|(i, n)| i >= n) {
+            return None;
+        }
+        let flat = index.iter().zip(&self.shape).fold(0, |acc, (i, n)| acc * n + i);
+        self.values.get(flat)?.as_ref()
+    }
+
+    /// The elements as nested vectors, outermost dimension first.
+    pub fn to_nested(&self) -> NestedValue {
+        fn nest(shape: &[usize], values: &[Option<DecodedValue>]) -> NestedValue {
+            match shape.split_first() {
+                None => NestedValue::Scalar(values.first().cloned().flatten()),
+                Some((_, inner)) => {
+                    let stride = inner.iter().product::<usize>().max(1);
+                    NestedValue::Array(values.chunks(stride).map(|chunk| nest(inner, chunk)).collect())
+                }
+            }
+        }
+        nest(&self.shape, &self.values)
+    }
+}
 
 /// High level handle for a single channel within a group.
 ///
 /// It holds references to the raw blocks and allows convenient access to
 /// metadata and decoded values.
+#[derive(Clone, Copy)]
 pub struct Channel<'a> {
     block:          &'a ChannelBlock,
     raw_data_group:   &'a RawDataGroup,
@@ -61,6 +113,24 @@ impl<'a> Channel<'a> {
         SourceInfo::from_mmap(self.mmap, addr)
     }
 
+    /// The conversion of this channel with its nested conversions and texts
+    /// pre-composed, as used to decode the samples. `None` if the channel
+    /// has no conversion or its references cannot be resolved.
+    pub fn simplified_conversion(&self) -> Option<&'a SimplifiedConversion> {
+        self.raw_channel.simplified_conversion(self.mmap)
+    }
+
+    /// Range of the physical values, as `(min, max)`, computed from the raw
+    /// range of the channel and its conversion; see
+    /// [`ChannelBlock::physical_range`]. This is not a scan of the samples.
+    pub fn physical_range(&self) -> Option<(f64, f64)> {
+        let (raw_min, raw_max) = self.block.raw_range()?;
+        match &self.block.conversion {
+            None => Some((raw_min, raw_max)),
+            Some(_) => self.simplified_conversion()?.physical_range(raw_min, raw_max),
+        }
+    }
+
     /// Decode and convert all samples of this channel.
     ///
     /// This method decodes all channel values and applies conversions.
@@ -71,102 +141,595 @@ impl<'a> Channel<'a> {
     /// - `Some(value)` for valid samples
     /// - `None` for invalid samples (invalidation bit set or decoding failed)
     pub fn values(&self) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        self.typed_values(Some)
+    }
+
+    /// Decode all samples of this channel without applying its conversion.
+    ///
+    /// The values are the ones stored in the records, e.g. the integer
+    /// counts of a scaled signal or the keys of a value-to-text table, so
+    /// they can be inspected or re-encoded as they are. Invalid samples are
+    /// `None`, as in [`values`](Self::values).
+    pub fn raw_values(&self) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let mut out = Vec::with_capacity(self.record_capacity());
+        self.for_each_record(|rec| {
+            out.push(self.decode_raw(self.block, rec));
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// Decode and convert the samples of records `start..start + count`,
+    /// like [`values`](Self::values) but for one window of the group.
+    ///
+    /// The window is clamped to the records present. Data blocks before the
+    /// window are skipped by their length without being decoded, so paging
+    /// through a large channel costs only the records of each page (plus
+    /// inflating the `##DZ` blocks before the window, which are not kept).
+    pub fn values_between_records(
+        &self,
+        start: usize,
+        count: usize,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let mut values = Vec::new();
+        if self.is_vlsd() {
+            for rec in self.raw_records()?.skip(start).take(count) {
+                values.push(self.decode_record(&rec?)?);
+            }
+            return Ok(values);
+        }
+
         let record_id_len = self.raw_data_group.block.record_id_len as usize;
-        let cg_data_bytes = self.raw_channel_group.block.samples_byte_nr;
-        let invalidation_bytes_nr = self.raw_channel_group.block.invalidation_bytes_nr;
-        let capacity = self.raw_channel_group.block.cycles_nr as usize;
-        let mut out = Vec::with_capacity(capacity);
+        let sample_byte_len = self.raw_channel_group.block.samples_byte_nr as usize;
+        let invalidation_bytes = self.raw_channel_group.block.invalidation_bytes_nr as usize;
+        let record_size = record_id_len + sample_byte_len + invalidation_bytes;
+        if record_size == 0 {
+            return Ok(values);
+        }
 
-        // VLSD channels must use the boxed iterator path
-        if self.block.channel_type == 1 && self.block.data != 0 {
-            let records_iter = self
-                .raw_channel
-                .records(self.raw_data_group, self.raw_channel_group, self.mmap)?;
-            if invalidation_bytes_nr == 0 {
-                for rec_res in records_iter {
-                    let ref rec = rec_res?;
-                    if let Some(decoded) = decode_channel_value(rec, record_id_len, self.block) {
-                        let phys = self.block.apply_conversion_value(decoded, self.mmap)?;
-                        out.push(Some(phys));
-                    } else {
-                        out.push(None);
-                    }
+        let end = start.saturating_add(count);
+        let mut block_start = 0usize;
+        for data_block in self.raw_data_group.channel_group_blocks(self.raw_channel_group, self.mmap)? {
+            if block_start >= end {
+                break;
+            }
+            let data_block = data_block?;
+            let in_block = data_block.data.len() / record_size;
+            let from = start.max(block_start) - block_start;
+            let to = end.min(block_start + in_block).saturating_sub(block_start);
+            if from < to {
+                let window = &data_block.data[from * record_size..to * record_size];
+                for rec in window.chunks_exact(record_size) {
+                    values.push(self.decode_record(rec)?);
                 }
+            }
+            block_start += in_block;
+        }
+        Ok(values)
+    }
+
+    /// Decode and convert all samples of this channel, keeping invalid ones.
+    ///
+    /// Unlike [`values`](Self::values), samples marked invalid by their
+    /// invalidation bit (or by the channel's "all values invalid" flag) are
+    /// returned with `is_valid == false` instead of being dropped to `None`,
+    /// so masking can be done by the caller. Records too short to hold the
+    /// sample yield [`DecodedValue::Unknown`] marked invalid.
+    ///
+    /// # Returns
+    /// One [`DecodedChannelValue`] per record.
+    pub fn values_with_validity(&self) -> Result<Vec<DecodedChannelValue>, MdfError> {
+        let record_id_len = self.raw_data_group.block.record_id_len as usize;
+        let cg_data_bytes = self.raw_channel_group.block.samples_byte_nr;
+        let vlsd = self.is_vlsd();
+        let mut out = Vec::with_capacity(self.record_capacity());
+        self.for_each_record(|rec| {
+            let decoded = if vlsd {
+                // Signal data entries carry no invalidation bits; only the
+                // channel flags apply.
+                decode_channel_value(rec, record_id_len, self.block).map(|value| DecodedChannelValue {
+                    value,
+                    is_valid: check_value_validity(&[], 0, 0, self.block),
+                })
             } else {
-                for rec_res in records_iter {
-                    let ref rec = rec_res?;
-                    if let Some(decoded) = decode_channel_value_with_validity(
-                        rec, record_id_len, cg_data_bytes, self.block
-                    ) {
-                        if decoded.is_valid {
-                            let phys = self.block.apply_conversion_value(decoded.value, self.mmap)?;
-                            out.push(Some(phys));
-                        } else {
-                            out.push(None);
-                        }
-                    } else {
-                        out.push(None);
-                    }
-                }
+                decode_channel_value_with_validity(rec, record_id_len, cg_data_bytes, self.block)
+            };
+            out.push(match decoded {
+                Some(DecodedChannelValue { value, is_valid }) => DecodedChannelValue {
+                    value: self.convert(self.block, value)?,
+                    is_valid,
+                },
+                None => DecodedChannelValue { value: DecodedValue::Unknown, is_valid: false },
+            });
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// `true` if this is a structure channel, whose members are linked as a
+    /// `##CN` composition. The structure itself decodes as a byte array;
+    /// its members decode from the same records.
+    pub fn is_structure(&self) -> bool {
+        !self.raw_channel.components.is_empty()
+    }
+
+    /// The member channels of a structure channel, in file order; empty for
+    /// any other channel. Members may be structures themselves.
+    pub fn components(&self) -> Vec<Channel<'a>> {
+        self.raw_channel
+            .components
+            .iter()
+            .map(|component| self.component_channel(component))
+            .collect()
+    }
+
+    /// Find a member channel of a structure by its full name (first match).
+    pub fn component(&self, name: &str) -> Option<Channel<'a>> {
+        self.raw_channel
+            .components
+            .iter()
+            .find(|component| string_block_eq(self.mmap, component.block.name_addr, name))
+            .map(|component| self.component_channel(component))
+    }
+
+    fn component_channel(&self, component: &'a RawChannel) -> Channel<'a> {
+        Channel::new(&component.block, self.raw_data_group, self.raw_channel_group, component, self.mmap)
+    }
+
+    /// The `##CA` block describing this channel's array dimensions, if the
+    /// channel is an array channel.
+    pub fn array_block(&self) -> Result<Option<ChannelArrayBlock>, MdfError> {
+        match bytes_from(self.mmap, self.block.component_addr) {
+            Ok(bytes) if self.block.component_addr != 0 && bytes.starts_with(b"##CA") => {
+                ChannelArrayBlock::from_bytes(bytes).map(Some)
             }
-            return Ok(out);
+            _ => Ok(None),
         }
+    }
 
-        // Fast path: iterate over data blocks directly without Box<dyn Iterator>
-        let sample_byte_len = cg_data_bytes as usize;
-        let invalidation_bytes = invalidation_bytes_nr as usize;
-        let record_size = record_id_len + sample_byte_len + invalidation_bytes;
+    /// The dimension sizes of an array channel, or `None` for scalar channels.
+    pub fn array_shape(&self) -> Result<Option<Vec<usize>>, MdfError> {
+        Ok(self
+            .array_block()?
+            .map(|ca| ca.dim_sizes.iter().map(|&n| n as usize).collect()))
+    }
 
+    /// Decode and convert every sample of an array channel.
+    ///
+    /// Each record yields a [`ChannelArray`] holding the array's shape and its
+    /// elements in row-major order, regardless of the storage layout. Only
+    /// arrays stored in the channel's own record (CN template storage) are
+    /// supported.
+    ///
+    /// # Returns
+    /// One [`ChannelArray`] per record, or an [`MdfError`] if the channel is
+    /// not an array channel or uses an unsupported storage type.
+    pub fn array_values(&self) -> Result<Vec<ChannelArray>, MdfError> {
+        let ca = self.array_block()?.ok_or_else(|| {
+            MdfError::BlockLinkError("channel has no ##CA array block".to_string())
+        })?;
+        if ca.storage != ArrayStorage::ChannelTemplate {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "array storage {:?} is not supported, only CN template arrays are",
+                ca.storage
+            )));
+        }
+        // Every element takes at least one bit of the record.
+        let record_bits = u64::from(self.raw_channel_group.block.samples_byte_nr) * 8;
+        if ca.element_count() as u64 > record_bits {
+            return Err(MdfError::BlockSerializationError(format!(
+                "array of {} elements does not fit a {record_bits}-bit record",
+                ca.element_count()
+            )));
+        }
+        let shape: Vec<usize> = ca.dim_sizes.iter().map(|&n| n as usize).collect();
+
+        // One channel block per element (row-major), pointing at the
+        // element's bytes and invalidation bit.
+        let mut elements = Vec::with_capacity(ca.element_count());
+        for flat in 0..ca.element_count() {
+            let mut rest = flat;
+            let mut index = vec![0; shape.len()];
+            for (i, n) in index.iter_mut().zip(&shape).rev() {
+                *i = rest % n;
+                rest /= n;
+            }
+            let storage_index = if ca.flags & CA_FLAG_INVERSE_LAYOUT != 0 {
+                index.iter().zip(&shape).rev().fold(0, |acc, (i, n)| acc * n + i)
+            } else {
+                flat
+            } as i64;
+            let byte_offset = self.block.byte_offset as i64 + ca.byte_offset_base as i64 * storage_index;
+            let mut block = self.block.clone();
+            block.byte_offset = u32::try_from(byte_offset).map_err(|_| {
+                MdfError::BlockSerializationError(format!(
+                    "array element {} has negative byte offset {}",
+                    flat, byte_offset
+                ))
+            })?;
+            block.pos_invalidation_bit += ca.inval_bit_pos_base * storage_index as u32;
+            elements.push(block);
+        }
+
+        let mut out = Vec::with_capacity(self.record_capacity());
+        self.for_each_record(|rec| {
+            let values = elements
+                .iter()
+                .map(|block| self.decode_with(block, rec))
+                .collect::<Result<_, _>>()?;
+            out.push(ChannelArray { shape: shape.clone(), values });
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// Decode and convert all samples of this channel as `f64`.
+    ///
+    /// Integer and float samples are cast to `f64`; invalid samples and
+    /// samples of any other type (e.g. text from a value-to-text conversion)
+    /// are `None`. Numeric channels without a conversion skip the
+    /// [`DecodedValue`] step entirely, and go through
+    /// [`primitive_column_f64`](Self::primitive_column_f64) when possible.
+    pub fn values_f64(&self) -> Result<Vec<Option<f64>>, MdfError> {
+        if let Some(column) = self.primitive_column_f64()? {
+            return Ok(column.into_iter().map(Some).collect());
+        }
+        let mut out = Vec::with_capacity(self.record_capacity());
+        self.for_each_f64(|value| out.push(value))?;
+        Ok(out)
+    }
+
+    /// Count, minimum, maximum, mean and standard deviation of the valid
+    /// numeric samples, as [`values_f64`](Self::values_f64) yields them.
+    ///
+    /// The samples are folded in while the records are read, so no column
+    /// of values is built; this is meant for quick previews of large files.
+    pub fn statistics(&self) -> Result<ChannelStatistics, MdfError> {
+        let mut stats = StatisticsAccumulator::new();
+        self.for_each_f64(|value| {
+            if let Some(value) = value {
+                stats.push(value);
+            }
+        })?;
+        Ok(stats.finish())
+    }
+
+    /// Call `f` with every sample as in [`values_f64`](Self::values_f64),
+    /// without the column fast path.
+    fn for_each_f64(&self, mut f: impl FnMut(Option<f64>)) -> Result<(), MdfError> {
+        let numeric = matches!(
+            self.block.data_type,
+            DataType::UnsignedIntegerLE
+                | DataType::UnsignedIntegerBE
+                | DataType::SignedIntegerLE
+                | DataType::SignedIntegerBE
+                | DataType::FloatLE
+                | DataType::FloatBE
+        );
+        if !numeric || self.block.conversion.is_some() || self.is_vlsd() {
+            return self.for_each_record(|rec| {
+                f(self.decode_record(rec)?.and_then(|value| match value {
+                    DecodedValue::UnsignedInteger(v) => Some(v as f64),
+                    DecodedValue::SignedInteger(v) => Some(v as f64),
+                    DecodedValue::Float(v) => Some(v),
+                    _ => None,
+                }));
+                Ok(())
+            });
+        }
+
+        let record_id_len = self.raw_data_group.block.record_id_len as usize;
+        let cg_data_bytes = self.raw_channel_group.block.samples_byte_nr;
+        let check_validity = self.raw_channel_group.block.invalidation_bytes_nr != 0;
+        self.for_each_record(|rec| {
+            let valid = !check_validity
+                || check_value_validity(rec, record_id_len, cg_data_bytes, self.block);
+            f(valid.then(|| decode_f64_from_record(rec, record_id_len, self.block)));
+            Ok(())
+        })
+    }
+
+    /// Decode and convert all samples of this channel as `i64`.
+    ///
+    /// Signed and unsigned integer samples are returned as `i64`; invalid
+    /// samples, unsigned values above `i64::MAX` and non-integer samples
+    /// (floats, text, bytes) are `None`.
+    pub fn values_i64(&self) -> Result<Vec<Option<i64>>, MdfError> {
+        self.typed_values(|value| match value {
+            DecodedValue::SignedInteger(v) => Some(v),
+            DecodedValue::UnsignedInteger(v) => i64::try_from(v).ok(),
+            _ => None,
+        })
+    }
+
+    /// Decode and convert all samples of this channel as `u64`.
+    ///
+    /// Unsigned and non-negative signed integer samples are returned as
+    /// `u64`; invalid samples, negative values and non-integer samples are
+    /// `None`. Goes through [`primitive_column_u64`](Self::primitive_column_u64)
+    /// when possible.
+    pub fn values_u64(&self) -> Result<Vec<Option<u64>>, MdfError> {
+        if let Some(column) = self.primitive_column_u64()? {
+            return Ok(column.into_iter().map(Some).collect());
+        }
+        self.typed_values(|value| match value {
+            DecodedValue::UnsignedInteger(v) => Some(v),
+            DecodedValue::SignedInteger(v) => u64::try_from(v).ok(),
+            _ => None,
+        })
+    }
+
+    /// All samples as a dense `f64` column, copied straight out of the
+    /// records.
+    ///
+    /// Only available for channels whose samples are byte-aligned
+    /// little-endian integers or floats, with no conversion and no invalid
+    /// samples; returns `Ok(None)` for any other channel, which
+    /// [`values_f64`](Self::values_f64) handles.
+    pub fn primitive_column_f64(&self) -> Result<Option<Vec<f64>>, MdfError> {
+        self.primitive_column(|records, record_size, layout, out| {
+            extend_f64_column(records, record_size, layout, out);
+            true
+        })
+    }
+
+    /// All samples as a dense `u64` column, like
+    /// [`primitive_column_f64`](Self::primitive_column_f64) for unsigned
+    /// integer channels.
+    pub fn primitive_column_u64(&self) -> Result<Option<Vec<u64>>, MdfError> {
+        self.primitive_column(extend_u64_column)
+    }
+
+    /// Decode all samples column-wise with `extend` if the channel is a
+    /// valid-only primitive without conversion; `Ok(None)` otherwise, or if
+    /// `extend` rejects the layout.
+    fn primitive_column<T>(
+        &self,
+        extend: impl Fn(&[u8], usize, PrimitiveLayout, &mut Vec<T>) -> bool,
+    ) -> Result<Option<Vec<T>>, MdfError> {
+        let check_validity = self.raw_channel_group.block.invalidation_bytes_nr != 0;
+        if self.block.conversion.is_some() || (check_validity && !always_valid(self.block)) {
+            return Ok(None);
+        }
+        let Some((record_size, layout)) = self.primitive_layout() else {
+            return Ok(None);
+        };
+        let mut out = Vec::with_capacity(self.record_capacity());
+        for data_block in self.raw_data_group.channel_group_blocks(self.raw_channel_group, self.mmap)? {
+            if !extend(&data_block?.data, record_size, layout, &mut out) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(out))
+    }
+
+    /// Record size and [`PrimitiveLayout`] of this channel, if its samples
+    /// can be copied out of the records column-wise.
+    fn primitive_layout(&self) -> Option<(usize, PrimitiveLayout)> {
+        let record_id_len = self.raw_data_group.block.record_id_len as usize;
+        let record_size = record_id_len
+            + self.raw_channel_group.block.samples_byte_nr as usize
+            + self.raw_channel_group.block.invalidation_bytes_nr as usize;
         if record_size == 0 {
-            return Ok(out);
+            return None;
         }
+        PrimitiveLayout::of(self.block, record_id_len, record_size).map(|layout| (record_size, layout))
+    }
 
-        let blocks = self.raw_data_group.data_blocks(self.mmap)?;
-
-        if invalidation_bytes_nr == 0 {
-            // Fast path: no invalidation bytes
-            for data_block in &blocks {
-                let raw = data_block.data;
-                let valid_len = (raw.len() / record_size) * record_size;
-                let mut offset = 0;
-                while offset + record_size <= valid_len {
-                    let rec = &raw[offset..offset + record_size];
-                    if let Some(decoded) = decode_channel_value(rec, record_id_len, self.block) {
-                        let phys = self.block.apply_conversion_value(decoded, self.mmap)?;
-                        out.push(Some(phys));
-                    } else {
-                        out.push(None);
-                    }
-                    offset += record_size;
-                }
+    /// Decode and convert all samples of this channel as strings.
+    ///
+    /// String channels and channels with a value-to-text conversion yield
+    /// their text; invalid samples and non-text samples are `None`.
+    pub fn values_string(&self) -> Result<Vec<Option<String>>, MdfError> {
+        self.typed_values(|value| match value {
+            DecodedValue::String(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    /// The MIME type of a `MimeSample` or `MimeStream` channel, as stored in
+    /// its unit (e.g. `"image/jpeg"`, lowercase, parameters dropped);
+    /// `None` for other channels or without a unit.
+    pub fn mime_type(&self) -> Result<Option<String>, MdfError> {
+        if !matches!(self.block.data_type, DataType::MimeSample | DataType::MimeStream) {
+            return Ok(None);
+        }
+        Ok(self.unit()?.as_deref().and_then(parse_mime_type))
+    }
+
+    /// Lazily iterate over the samples of a `MimeSample`, `MimeStream` or
+    /// byte array channel, one item per record.
+    ///
+    /// The bytes are borrowed from the file instead of being copied into a
+    /// [`DecodedValue`] per record, which matters for video frames and
+    /// images; only samples in `##DZ` blocks (or in a file opened without
+    /// a memory map) are copied out of their block. Items are `None` for
+    /// invalid samples.
+    pub fn mime_samples(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<MimeSample<'a>, MdfError>> + use<'a>, MdfError> {
+        if !matches!(self.block.data_type, DataType::ByteArray | DataType::MimeSample | DataType::MimeStream) {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "{:?} channels hold no embedded samples",
+                self.block.data_type
+            )));
+        }
+        let channel = *self;
+        Ok(self.raw_records()?.map(move |rec| {
+            Ok(match rec? {
+                Cow::Borrowed(rec) => channel.sample_bytes(rec).map(Cow::Borrowed),
+                Cow::Owned(rec) => channel.sample_bytes(&rec).map(|bytes| Cow::Owned(bytes.to_vec())),
+            })
+        }))
+    }
+
+    /// Write the samples of a `MimeSample`, `MimeStream` or byte array
+    /// channel to files in `dir`, named after the channel with the
+    /// extension of its [`mime_type`](Self::mime_type) (`.bin` if unknown).
+    ///
+    /// Each valid sample of a `MimeSample` (or byte array) channel becomes
+    /// its own file, `<name>_<record>.<ext>` with the record index padded
+    /// to six digits; the samples of a `MimeStream` channel are fragments
+    /// of one stream and are concatenated into `<name>.<ext>`. Characters
+    /// that cannot appear in file names are replaced by `_`. The directory
+    /// is created if needed.
+    ///
+    /// Not available on `wasm32-unknown-unknown`.
+    ///
+    /// # Returns
+    /// The paths of the written files, in record order.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn extract_mime_samples(
+        &self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<Vec<std::path::PathBuf>, MdfError> {
+        use std::io::Write;
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let stem: String = self
+            .name()?
+            .unwrap_or_else(|| "channel".to_string())
+            .chars()
+            .map(|c| if c.is_alphanumeric() || "-_.".contains(c) { c } else { '_' })
+            .collect();
+        let extension = extension_for_mime_type(self.mime_type()?.as_deref().unwrap_or_default());
+
+        let samples = self.mime_samples()?;
+        if self.block.data_type == DataType::MimeStream {
+            let path = dir.join(format!("{stem}.{extension}"));
+            let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            for sample in samples {
+                file.write_all(&sample?.unwrap_or_default())?;
             }
-        } else {
-            // Slow path: must check invalidation bits per record
-            for data_block in &blocks {
-                let raw = data_block.data;
-                let valid_len = (raw.len() / record_size) * record_size;
-                let mut offset = 0;
-                while offset + record_size <= valid_len {
-                    let rec = &raw[offset..offset + record_size];
-                    if let Some(decoded) = decode_channel_value_with_validity(
-                        rec, record_id_len, cg_data_bytes, self.block
-                    ) {
-                        if decoded.is_valid {
-                            let phys = self.block.apply_conversion_value(decoded.value, self.mmap)?;
-                            out.push(Some(phys));
-                        } else {
-                            out.push(None);
-                        }
-                    } else {
-                        out.push(None);
-                    }
-                    offset += record_size;
-                }
+            file.flush()?;
+            return Ok(vec![path]);
+        }
+        let mut paths = Vec::new();
+        for (record, sample) in samples.enumerate() {
+            if let Some(bytes) = sample? {
+                let path = dir.join(format!("{stem}_{record:06}.{extension}"));
+                std::fs::write(&path, bytes)?;
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// The bytes of this byte-field channel's sample in `rec` (a signal data
+    /// entry for VLSD channels); `None` when the sample is invalid or does
+    /// not fit the record.
+    fn sample_bytes<'r>(&self, rec: &'r [u8]) -> Option<&'r [u8]> {
+        if self.is_vlsd() {
+            return check_value_validity(&[], 0, 0, self.block).then_some(rec);
+        }
+        let record_id_len = self.raw_data_group.block.record_id_len as usize;
+        if self.raw_channel_group.block.invalidation_bytes_nr != 0 {
+            let cg_data_bytes = self.raw_channel_group.block.samples_byte_nr;
+            if !check_value_validity(rec, record_id_len, cg_data_bytes, self.block) {
+                return None;
             }
         }
+        let start = record_id_len + self.block.byte_offset as usize;
+        rec.get(start..start + self.block.bit_count as usize / 8)
+    }
+
+    fn is_vlsd(&self) -> bool {
+        self.block.channel_type == 1 && self.block.data != 0
+    }
+
+    /// Samples to preallocate for: the group's cycle count, capped by the
+    /// file size so a corrupt count can't request a huge allocation.
+    fn record_capacity(&self) -> usize {
+        (self.raw_channel_group.block.cycles_nr as usize).min(self.mmap.len())
+    }
+
+    /// Decode and convert every sample, mapping valid values with `convert`.
+    /// Invalid or undecodable samples are `None`.
+    fn typed_values<T>(
+        &self,
+        mut convert: impl FnMut(DecodedValue) -> Option<T>,
+    ) -> Result<Vec<Option<T>>, MdfError> {
+        let mut out = Vec::with_capacity(self.record_capacity());
+        self.for_each_record(|rec| {
+            out.push(self.decode_record(rec)?.and_then(&mut convert));
+            Ok(())
+        })?;
         Ok(out)
     }
 
+    /// Decode and convert this channel's sample from one record; `None` when
+    /// the sample is invalid or cannot be decoded.
+    fn decode_record(&self, rec: &[u8]) -> Result<Option<DecodedValue>, MdfError> {
+        self.decode_with(self.block, rec)
+    }
+
+    /// [`decode_record`](Self::decode_record) with the layout of `block`,
+    /// e.g. one element of an array channel.
+    fn decode_with(&self, block: &ChannelBlock, rec: &[u8]) -> Result<Option<DecodedValue>, MdfError> {
+        self.decode_raw(block, rec).map(|raw| self.convert(block, raw)).transpose()
+    }
+
+    /// The unconverted sample of `block` in one record; `None` when it is
+    /// invalid or cannot be decoded.
+    fn decode_raw(&self, block: &ChannelBlock, rec: &[u8]) -> Option<DecodedValue> {
+        let record_id_len = self.raw_data_group.block.record_id_len as usize;
+        if self.raw_channel_group.block.invalidation_bytes_nr != 0 {
+            let cg_data_bytes = self.raw_channel_group.block.samples_byte_nr;
+            decode_channel_value_with_validity(rec, record_id_len, cg_data_bytes, block)
+                .filter(|decoded| decoded.is_valid)
+                .map(|decoded| decoded.value)
+        } else {
+            decode_channel_value(rec, record_id_len, block)
+        }
+    }
+
+    /// Apply the conversion of `block` to a decoded raw value, through the
+    /// channel's cached [`SimplifiedConversion`] when `block` shares it.
+    fn convert(&self, block: &ChannelBlock, raw: DecodedValue) -> Result<DecodedValue, MdfError> {
+        let shared = block.conversion.is_some() && block.conversion_addr == self.raw_channel.block.conversion_addr;
+        match self.raw_channel.simplified_conversion(self.mmap).filter(|_| shared) {
+            Some(conversion) => conversion.apply(raw),
+            None => block.apply_conversion_value(raw, self.mmap),
+        }
+    }
+
+    /// Lazily decode and convert the samples of this channel, one per record.
+    ///
+    /// Unlike [`values`](Self::values) nothing is collected: the data blocks
+    /// are read one at a time as the iterator advances (`##DZ` blocks are
+    /// inflated, unsorted groups demultiplexed on the way) and each record
+    /// is decoded in place, so memory use does not grow with the file size.
+    /// Items are `None` for invalid samples, like [`values`](Self::values).
+    pub fn iter_values(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Option<DecodedValue>, MdfError>> + use<'a>, MdfError> {
+        let channel = *self;
+        let mut records = self.raw_records()?;
+        Ok(std::iter::from_fn(move || {
+            records.next_record().map(|rec| channel.decode_record(rec?))
+        }))
+    }
+
+    /// Iterate over the raw records this channel is decoded from: the
+    /// signal data entries for VLSD channels, the group's records otherwise.
+    fn raw_records(&self) -> Result<Records<'a>, MdfError> {
+        self.raw_channel.records(self.raw_data_group, self.raw_channel_group, self.mmap)
+    }
+
+    /// Call `f` with every record of the channel group, in file order
+    /// (with the signal data entries for VLSD channels).
+    fn for_each_record(
+        &self,
+        mut f: impl FnMut(&[u8]) -> Result<(), MdfError>,
+    ) -> Result<(), MdfError> {
+        let mut records = self.raw_records()?;
+        while let Some(rec) = records.next_record() {
+            f(rec?)?;
+        }
+        Ok(())
+    }
+
     /// Decode all numeric samples as f64 values without enum wrapping.
     ///
     /// This is significantly faster than `values()` for numeric channels (int/float)
@@ -178,22 +741,19 @@ impl<'a> Channel<'a> {
     /// with non-identity conversions.
     pub fn values_as_f64(&self) -> Result<Vec<f64>, MdfError> {
         let record_id_len = self.raw_data_group.block.record_id_len as usize;
-        let capacity = self.raw_channel_group.block.cycles_nr as usize;
+        let capacity = self.record_capacity();
         let mut out = Vec::with_capacity(capacity);
 
-        // VLSD channels must use the boxed iterator path
-        if self.block.channel_type == 1 && self.block.data != 0 {
-            let records_iter = self
-                .raw_channel
-                .records(self.raw_data_group, self.raw_channel_group, self.mmap)?;
-            for rec_res in records_iter {
-                let rec = rec_res?;
+        // VLSD channels go through their signal data entries
+        if self.is_vlsd() {
+            self.for_each_record(|rec| {
                 out.push(decode_f64_from_record(rec, record_id_len, self.block));
-            }
+                Ok(())
+            })?;
             return Ok(out);
         }
 
-        // Fast path: iterate over data blocks directly without Box<dyn Iterator>
+        // Fast path: iterate over data blocks directly, record by record
         let sample_byte_len = self.raw_channel_group.block.samples_byte_nr as usize;
         let invalidation_bytes = self.raw_channel_group.block.invalidation_bytes_nr as usize;
         let record_size = record_id_len + sample_byte_len + invalidation_bytes;
@@ -202,9 +762,16 @@ impl<'a> Channel<'a> {
             return Ok(out);
         }
 
-        let blocks = self.raw_data_group.data_blocks(self.mmap)?;
-        for data_block in &blocks {
-            let raw = data_block.data;
+        let blocks = self.raw_data_group.channel_group_blocks(self.raw_channel_group, self.mmap)?;
+        if let Some((_, layout)) = self.primitive_layout() {
+            for data_block in blocks {
+                extend_f64_column(&data_block?.data, record_size, layout, &mut out);
+            }
+            return Ok(out);
+        }
+        for data_block in blocks {
+            let data_block = data_block?;
+            let raw = &data_block.data[..];
             let valid_len = (raw.len() / record_size) * record_size;
             let mut offset = 0;
             while offset + record_size <= valid_len {
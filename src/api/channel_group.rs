@@ -1,10 +1,12 @@
-use crate::blocks::common::read_string_block;
+use crate::blocks::common::{read_string_block, string_block_eq};
 use crate::parsing::raw_data_group::RawDataGroup;
 use crate::parsing::raw_channel_group::RawChannelGroup;
 use crate::parsing::source_info::SourceInfo;
 use crate::api::channel::Channel;
 use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
 use crate::signal::Signal;
+use crate::bus_logging::{CanFrame, FlexRayFrame, LinFrame};
 
 /// High level wrapper for a channel group.
 ///
@@ -50,6 +52,25 @@ impl<'a> ChannelGroup<'a> {
         SourceInfo::from_mmap(self.mmap, addr)
     }
 
+    /// The separator of the levels of hierarchical channel names (e.g. `.`
+    /// for `ECU1.Engine.Speed`), `None` if the group does not declare one.
+    pub fn path_separator(&self) -> Option<char> {
+        match self.raw_channel_group.block.path_separator {
+            0 => None,
+            unit => char::from_u32(u32::from(unit)),
+        }
+    }
+
+    /// `true` if the group shares its data group with other channel groups,
+    /// i.e. was recorded unsorted with records told apart by record ID.
+    ///
+    /// Such groups read transparently; their records are demultiplexed on
+    /// first access, which holds a copy of the data group's records in
+    /// memory.
+    pub fn is_unsorted(&self) -> bool {
+        !self.raw_data_group.is_sorted()
+    }
+
     /// Build all [`Channel`] objects for this group.
     ///
     /// No channel data is decoded; the returned channels simply reference the
@@ -72,45 +93,179 @@ impl<'a> ChannelGroup<'a> {
     }
 
     /// Find a channel in this group by name (first match).
+    ///
+    /// Only the name blocks of the channels up to the match are read, and
+    /// only the matching [`Channel`] is built.
     pub fn channel(&self, name: &str) -> Option<Channel<'a>> {
+        let raw_channel = self
+            .raw_channel_group
+            .raw_channels
+            .iter()
+            .find(|ch| string_block_eq(self.mmap, ch.block.name_addr, name))?;
+        Some(Channel::new(
+            &raw_channel.block,
+            self.raw_data_group,
+            self.raw_channel_group,
+            raw_channel,
+            self.mmap,
+        ))
+    }
+
+    /// The group's master (time) channel, i.e. the first channel with
+    /// channel type 2, if any.
+    pub fn master_channel(&self) -> Option<Channel<'a>> {
+        let raw_channel = self
+            .raw_channel_group
+            .raw_channels
+            .iter()
+            .find(|ch| ch.block.channel_type == 2)?;
+        Some(Channel::new(
+            &raw_channel.block,
+            self.raw_data_group,
+            self.raw_channel_group,
+            raw_channel,
+            self.mmap,
+        ))
+    }
+
+    /// Decode the master channel as physical values (its conversion is
+    /// applied), one per record, with `NaN` for invalid samples.
+    ///
+    /// Returns `Ok(None)` if the group has no master channel.
+    pub fn time_values(&self) -> Result<Option<Vec<f64>>, MdfError> {
+        let Some(master) = self.master_channel() else { return Ok(None) };
+        let values = master.values_f64()?;
+        Ok(Some(values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect()))
+    }
+
+    /// The master value of record `index`, or `None` if the group has no
+    /// master channel or fewer records.
+    ///
+    /// Decodes the whole master channel; for repeated lookups decode it once
+    /// with [`time_values`](Self::time_values).
+    pub fn time_at(&self, index: usize) -> Result<Option<f64>, MdfError> {
+        Ok(self.time_values()?.and_then(|times| times.get(index).copied()))
+    }
+
+    /// The index of the last record whose master value is at or before
+    /// `time`, or `None` if the group has no master channel or `time`
+    /// precedes its first record. Master values are assumed to be
+    /// monotonically increasing.
+    ///
+    /// Decodes the whole master channel, like [`time_at`](Self::time_at).
+    pub fn record_index_at(&self, time: f64) -> Result<Option<usize>, MdfError> {
+        Ok(self
+            .time_values()?
+            .and_then(|times| times.partition_point(|&t| t <= time).checked_sub(1)))
+    }
+
+    /// Decode and convert every channel for records `start..start + count`,
+    /// one column per channel in the order of [`channels`](Self::channels).
+    ///
+    /// The window is clamped to the records present; see
+    /// [`Channel::values_between_records`]. Use it to page through a group
+    /// instead of loading whole channels.
+    pub fn values_between_records(
+        &self,
+        start: usize,
+        count: usize,
+    ) -> Result<Vec<Vec<Option<DecodedValue>>>, MdfError> {
         self.channels()
-            .into_iter()
-            .find(|c| c.name().ok().flatten().as_deref() == Some(name))
+            .iter()
+            .map(|channel| channel.values_between_records(start, count))
+            .collect()
+    }
+
+    /// [`values_between_records`](Self::values_between_records) for the
+    /// records whose master value lies in `[start_time, end_time]`.
+    ///
+    /// Returns `Ok(None)` if the group has no master channel. The master
+    /// channel is decoded in full to locate the window, and its values are
+    /// assumed to be monotonically increasing.
+    pub fn values_between_times(
+        &self,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<Option<Vec<Vec<Option<DecodedValue>>>>, MdfError> {
+        let Some(times) = self.time_values()? else { return Ok(None) };
+        let start = times.partition_point(|&t| t < start_time);
+        let end = times.partition_point(|&t| t <= end_time).max(start);
+        self.values_between_records(start, end - start).map(Some)
+    }
+
+    /// Lazily decode the group record by record.
+    ///
+    /// Each item holds one decoded and converted value per channel, in the
+    /// order of [`channels`](Self::channels), with `None` for invalid
+    /// samples. Records are read as the iterator advances (see
+    /// [`Channel::iter_values`]), so whole files can be processed without
+    /// materializing their columns.
+    pub fn iter_records(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Vec<Option<DecodedValue>>, MdfError>> + use<'a>, MdfError> {
+        let mut columns = self
+            .channels()
+            .iter()
+            .map(|channel| channel.iter_values())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(std::iter::from_fn(move || {
+            if columns.is_empty() {
+                return None;
+            }
+            let mut row = Vec::with_capacity(columns.len());
+            for column in &mut columns {
+                match column.next()? {
+                    Ok(value) => row.push(value),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            Some(Ok(row))
+        }))
     }
 
     /// Read a channel by name as a [`Signal`] (values paired with the group's
     /// master/time axis).
     ///
     /// Returns `Ok(None)` if no channel with that name exists in this group.
-    /// `timestamps` is empty when the group has no master channel or when the
-    /// requested channel *is* the master.
+    /// `timestamps` holds the [`time_values`](Self::time_values) and is empty
+    /// when the group has no master channel or when the requested channel
+    /// *is* the master.
     pub fn signal(&self, name: &str) -> Result<Option<Signal>, MdfError> {
-        let channels = self.channels();
-        let mut target: Option<usize> = None;
-        let mut master: Option<usize> = None;
-        for (i, ch) in channels.iter().enumerate() {
-            if ch.block().channel_type == 2 {
-                master = Some(i);
-            }
-            if target.is_none() && ch.name()?.as_deref() == Some(name) {
-                target = Some(i);
-            }
-        }
-        let Some(ci) = target else { return Ok(None) };
+        let Some(channel) = self.channel(name) else { return Ok(None) };
 
-        let values = channels[ci].values()?;
-        let timestamps = match master {
-            Some(mi) if mi != ci => channels[mi].values_as_f64()?,
-            _ => Vec::new(),
+        let values = channel.values()?;
+        let timestamps = if channel.block().channel_type == 2 {
+            Vec::new()
+        } else {
+            self.time_values()?.unwrap_or_default()
         };
         Ok(Some(Signal {
             name: name.to_string(),
-            unit: channels[ci].unit()?,
+            unit: channel.unit()?,
             timestamps,
             values,
         }))
     }
 
+    /// The frames of a `CAN_DataFrame` or `CAN_ErrorFrame` bus logging
+    /// group, or `None` for other groups; see
+    /// [`read_can_frames`](crate::bus_logging::read_can_frames).
+    pub fn can_frames(&self) -> Result<Option<Vec<CanFrame>>, MdfError> {
+        crate::bus_logging::read_can_frames(self)
+    }
+
+    /// The frames of a `LIN_Frame` bus logging group, or `None` for other
+    /// groups.
+    pub fn lin_frames(&self) -> Result<Option<Vec<LinFrame>>, MdfError> {
+        crate::bus_logging::read_lin_frames(self)
+    }
+
+    /// The frames of a `FR_Frame` bus logging group, or `None` for other
+    /// groups.
+    pub fn flexray_frames(&self) -> Result<Option<Vec<FlexRayFrame>>, MdfError> {
+        crate::bus_logging::read_flexray_frames(self)
+    }
+
     /// Get the raw data group (for internal use)
     pub fn raw_data_group(&self) -> &RawDataGroup {
         self.raw_data_group
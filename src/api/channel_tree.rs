@@ -0,0 +1,62 @@
+//! Hierarchical view of channel names.
+//!
+//! A channel group can declare a path separator (`path_separator` of its
+//! `##CG` block), e.g. `.` for names like `ECU1.Engine.Speed`. [`ChannelTree`]
+//! splits the channel names at that separator into nested levels, as signal
+//! browsers display them. It is built by
+//! [`MDF::channel_tree`](crate::api::mdf::MDF::channel_tree).
+
+/// One level of the channel hierarchy.
+///
+/// The root has an empty name. A channel is listed in the `channels` of the
+/// level its name ends in, so `ECU1.Engine.Speed` is a channel of the level
+/// `Engine` below `ECU1`; names without a separator are channels of the
+/// root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelTree {
+    /// Name of this level (empty for the root).
+    pub name: String,
+    /// Sub-levels, in the order they first appear in the file.
+    pub children: Vec<ChannelTree>,
+    /// Channels at this level as `(group_index, channel_index, name)`, with
+    /// the full channel name, in file order.
+    pub channels: Vec<(usize, usize, String)>,
+}
+
+impl ChannelTree {
+    /// Add the channel `entry` below the levels `path`.
+    pub(crate) fn insert(&mut self, path: &[&str], entry: (usize, usize, String)) {
+        let Some((first, rest)) = path.split_first() else {
+            self.channels.push(entry);
+            return;
+        };
+        let i = match self.children.iter().position(|child| child.name == *first) {
+            Some(i) => i,
+            None => {
+                self.children.push(ChannelTree { name: first.to_string(), ..ChannelTree::default() });
+                self.children.len() - 1
+            }
+        };
+        self.children[i].insert(rest, entry);
+    }
+
+    /// The direct sub-level `name`, if any.
+    pub fn child(&self, name: &str) -> Option<&ChannelTree> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// The level reached by following `path` from this one, e.g.
+    /// `["ECU1", "Engine"]`; an empty path is this level.
+    pub fn get(&self, path: &[&str]) -> Option<&ChannelTree> {
+        path.iter().try_fold(self, |level, name| level.child(name))
+    }
+
+    /// The channels of this level and all levels below it, depth first.
+    pub fn all_channels(&self) -> Vec<&(usize, usize, String)> {
+        let mut channels: Vec<_> = self.channels.iter().collect();
+        for child in &self.children {
+            channels.extend(child.all_channels());
+        }
+        channels
+    }
+}
@@ -0,0 +1,79 @@
+use crate::blocks::common::read_string_block;
+use crate::blocks::event_block::{read_event_blocks, EventType};
+use crate::error::MdfError;
+
+/// What an event's scope link points at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventScope {
+    /// A channel group, with its acquisition name.
+    ChannelGroup(Option<String>),
+    /// A channel, with its name.
+    Channel(Option<String>),
+    /// A link to any other block, by file offset.
+    Other(u64),
+}
+
+/// Ergonomic view of an EVBLOCK: name, comment, type and timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// The event name (ev_tx_name)
+    pub name: Option<String>,
+    /// The event comment (ev_md_comment)
+    pub comment: Option<String>,
+    pub event_type: EventType,
+    /// Synchronization type (1=time, 2=angle, 3=distance, 4=index)
+    pub sync_type: u8,
+    /// Synchronization value; seconds relative to the start time for
+    /// time-synchronized events
+    pub time: f64,
+    /// Range type (0=point, 1=range begin, 2=range end)
+    pub range_type: u8,
+    /// Cause (0=OTHER, 1=ERROR, 2=TOOL, 3=SCRIPT, 4=USER)
+    pub cause: u8,
+    /// Channel groups and channels the event applies to; empty for the
+    /// whole file
+    pub scope: Vec<EventScope>,
+}
+
+impl Event {
+    /// Read the event chain starting at `address` (the header's
+    /// `first_event_addr`), in link order.
+    pub fn read_all(mmap: &[u8], address: u64) -> Result<Vec<Self>, MdfError> {
+        let mut events = Vec::new();
+        for (_, ev) in read_event_blocks(mmap, address)? {
+            let scope = ev
+                .scope_addrs
+                .iter()
+                .map(|&addr| read_scope(mmap, addr))
+                .collect::<Result<_, _>>()?;
+            events.push(Event {
+                name: read_string_block(mmap, ev.name_addr)?,
+                comment: read_string_block(mmap, ev.comment_addr)?,
+                event_type: ev.event_type,
+                sync_type: ev.sync_type,
+                time: ev.sync_value(),
+                range_type: ev.range_type,
+                cause: ev.cause,
+                scope,
+            });
+        }
+        Ok(events)
+    }
+}
+
+/// Resolve one `ev_scope` link to the group or channel it names.
+fn read_scope(mmap: &[u8], addr: u64) -> Result<EventScope, MdfError> {
+    let start = addr as usize;
+    // Name links: offset 40 in both ##CG (acq_name) and ##CN (cn_tx_name).
+    let name_link = |mmap: &[u8]| -> Result<Option<String>, MdfError> {
+        match mmap.get(start + 40..start + 48) {
+            Some(link) => read_string_block(mmap, u64::from_le_bytes(link.try_into().unwrap())),
+            None => Ok(None),
+        }
+    };
+    match mmap.get(start..start + 4) {
+        Some(b"##CG") => Ok(EventScope::ChannelGroup(name_link(mmap)?)),
+        Some(b"##CN") => Ok(EventScope::Channel(name_link(mmap)?)),
+        _ => Ok(EventScope::Other(addr)),
+    }
+}
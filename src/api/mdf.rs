@@ -1,77 +1,320 @@
+use std::path::Path;
+use std::sync::Arc;
+
 use crate::error::MdfError;
+use crate::finalize;
+use crate::parsing::mdf3;
 use crate::parsing::mdf_file::MdfFile;
 use crate::api::channel_group::ChannelGroup;
 use crate::api::channel::Channel;
+use crate::api::channel_tree::ChannelTree;
+use crate::api::event::Event;
+use crate::api::reader::ChannelReader;
 use crate::block_layout::FileLayout;
+use crate::blocks::common::{read_string_block, string_block_eq};
+use crate::metadata::{FileMetadata, HeaderInfo, HeaderMetadata, StartTime};
+use crate::pattern::NamePattern;
+
+/// How [`MDF::open_with`] reads a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenOptions {
+    /// Memory map the file (the default). When `false`, the file is read
+    /// with buffered, seek-based reads instead.
+    pub mmap: bool,
+    /// Minimum size in bytes of each read of the metadata when not memory
+    /// mapping; small blocks next to each other are served from one read.
+    pub buffer_size: u64,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        OpenOptions { mmap: true, buffer_size: 64 * 1024 }
+    }
+}
 
 #[derive(Debug)]
 /// High level representation of an MDF file.
 ///
 /// The struct stores the memory mapped file internally and lazily exposes
 /// [`ChannelGroup`] wrappers for easy inspection.
+///
+/// # Thread safety
+///
+/// `MDF` is `Send + Sync`. The mapping is only read, and the state built
+/// lazily while reading (the simplified conversions of channels) is kept in
+/// `OnceLock`s, so any number of threads may decode channels of the same
+/// file at once: share it by reference
+/// with scoped threads, or through an `Arc` and a [`ChannelReader`] per
+/// thread. A file mapped from disk must not be modified by another process
+/// while it is read.
 pub struct MDF {
     raw: MdfFile,
 }
 
+// Readers are shared across threads; keep it that way.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MDF>();
+    assert_send_sync::<ChannelGroup<'static>>();
+    assert_send_sync::<Channel<'static>>();
+    assert_send_sync::<ChannelReader>();
+};
+
 impl MDF {
-    /// Parse an MDF4 file from disk.
+    /// Parse an MDF file from disk.
+    ///
+    /// The file is memory mapped. Unfinalized MDF4 files are finalized in
+    /// a private copy-on-write view of the mapping, which copies only the
+    /// patched pages and leaves the file unchanged (see [`crate::finalize`]).
+    /// MDF 3.x files are detected from their version number and translated
+    /// from the mapping into an MDF4 image in memory (see
+    /// [`crate::parsing::mdf3`]).
+    ///
+    /// Not available on `wasm32-unknown-unknown`; use [`from_bytes`] instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        let file = std::fs::File::open(path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let raw = if mdf3::is_mdf3(&mmap) {
+            MdfFile::parse_from_bytes(mdf3::mdf3_to_mdf4(&mmap)?)?
+        } else if finalize::is_unfinalized(&mmap) {
+            drop(mmap);
+            MdfFile::parse_from_mmap(finalize::finalize_mapped(&file)?)?
+        } else {
+            MdfFile::parse_from_mmap(mmap)?
+        };
+        Ok(MDF { raw })
+    }
+
+    /// Open an MDF file from disk, choosing how it is read with `options`.
+    ///
+    /// With [`OpenOptions::mmap`] set this is [`from_file`]. Without it the
+    /// file is never mapped, so files larger than the address space (e.g.
+    /// on 32-bit systems) can be read: the metadata blocks are read with
+    /// buffered reads into memory, and the data blocks are read from the
+    /// file one at a time while a channel is decoded, without being kept
+    /// (see [`MdfFile::parse_buffered`]). MDF 3.x and unfinalized files need
+    /// translating or patching first and are rejected with
+    /// [`MdfError::UnsupportedFeature`]; open them with [`from_file`], or
+    /// finalize them with [`finalize_file`](crate::finalize::finalize_file).
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`from_bytes`] instead.
+    ///
+    /// ```no_run
+    /// # use mf4_rs::api::mdf::{OpenOptions, MDF};
+    /// let mdf = MDF::open_with("recording.mf4", OpenOptions { mmap: false, ..OpenOptions::default() })?;
+    /// let speed = mdf.channel("Speed").map(|ch| ch.values_f64()).transpose()?;
+    /// # Ok::<(), mf4_rs::error::MdfError>(())
+    /// ```
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn from_file(path: &str) -> Result<Self, MdfError> {
-        let raw = MdfFile::parse_from_file(path)?;
+    pub fn open_with(path: impl AsRef<Path>, options: OpenOptions) -> Result<Self, MdfError> {
+        use std::io::Read;
+
+        if options.mmap {
+            return Self::from_file(&path);
+        }
+        let mut id = Vec::with_capacity(64);
+        std::fs::File::open(&path)?.take(64).read_to_end(&mut id)?;
+        if mdf3::is_mdf3(&id) || finalize::is_unfinalized(&id) {
+            return Err(MdfError::UnsupportedFeature(
+                "MDF 3.x and unfinalized files can only be read with a memory map".into(),
+            ));
+        }
+        let raw = MdfFile::parse_buffered(path, options.buffer_size)?;
         Ok(MDF { raw })
     }
 
-    /// Parse an MDF4 file from an owned byte buffer.
+    /// Parse an MDF file from an owned byte buffer. MDF 3.x data is
+    /// translated and unfinalized data finalized as with [`from_file`].
     ///
     /// This is the primary entry point on `wasm32-unknown-unknown` where
     /// filesystem access is unavailable.  On native targets the caller can
     /// populate the buffer from `std::fs::read` or a memory-mapped file.
     pub fn from_bytes(data: Vec<u8>) -> Result<Self, MdfError> {
+        let data = if mdf3::is_mdf3(&data) {
+            mdf3::mdf3_to_mdf4(&data)?
+        } else if finalize::is_unfinalized(&data) {
+            finalize::finalize_bytes(data)?
+        } else {
+            data
+        };
         let raw = MdfFile::parse_from_bytes(data)?;
         Ok(MDF { raw })
     }
 
+    /// Parse an MDF file from any `Read + Seek` source, e.g. a `Cursor`
+    /// over a downloaded object or a file handle.
+    ///
+    /// The source is read as with [`open_with`] without a memory map: the
+    /// metadata blocks are read into memory, and the data blocks are read
+    /// from `reader`, which the returned `MDF` keeps, one block at a time
+    /// while channels are decoded. MDF 3.x and unfinalized files cannot be
+    /// read this way; they are read from their start into memory and parsed
+    /// as with [`from_bytes`].
+    pub fn from_reader<R>(mut reader: R) -> Result<Self, MdfError>
+    where
+        R: std::io::Read + std::io::Seek + Send + 'static,
+    {
+        use std::io::{Read, SeekFrom};
+
+        reader.seek(SeekFrom::Start(0))?;
+        let mut id = Vec::with_capacity(64);
+        (&mut reader).take(64).read_to_end(&mut id)?;
+        if mdf3::is_mdf3(&id) || finalize::is_unfinalized(&id) {
+            reader.seek(SeekFrom::Start(0))?;
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data)?;
+            return Self::from_bytes(data);
+        }
+        let raw = MdfFile::parse_buffered_reader(reader, OpenOptions::default().buffer_size)?;
+        Ok(MDF { raw })
+    }
+
     /// Retrieve channel groups contained in the file.
     ///
     /// Each [`ChannelGroup`] is created lazily and does not decode any samples.
     pub fn channel_groups(&self) -> Vec<ChannelGroup<'_>> {
-        let mut groups = Vec::new();
-
-        for raw_data_group in &self.raw.data_groups {
-            for raw_channel_group in &raw_data_group.channel_groups {
-                groups.push(ChannelGroup::new(
-                    raw_data_group,
-                    raw_channel_group,
-                    &self.raw.mmap,
-                ));
-            }
-        }
+        self.iter_channel_groups().collect()
+    }
+
+    /// Iterate over the channel groups in file order, building each
+    /// [`ChannelGroup`] only as the iterator advances.
+    pub fn iter_channel_groups(&self) -> impl Iterator<Item = ChannelGroup<'_>> + '_ {
+        self.raw.data_groups.iter().flat_map(move |raw_data_group| {
+            raw_data_group.channel_groups.iter().map(move |raw_channel_group| {
+                ChannelGroup::new(raw_data_group, raw_channel_group, &self.raw.mmap)
+            })
+        })
+    }
+
+    /// The raw bytes of the whole file.
+    pub(crate) fn mmap(&self) -> &[u8] {
+        &self.raw.mmap
+    }
 
-        groups
+    /// The parsed block structure of the file.
+    pub(crate) fn raw(&self) -> &MdfFile {
+        &self.raw
     }
 
     /// Find a channel group by name (first match).
     ///
-    /// Convenience over [`MDF::channel_groups`] for the common case of
-    /// addressing a group by its acquisition name.
+    /// Alias of [`MDF::channel_group_by_name`].
     pub fn group(&self, name: &str) -> Option<ChannelGroup<'_>> {
-        self.channel_groups()
-            .into_iter()
-            .find(|g| g.name().ok().flatten().as_deref() == Some(name))
+        self.channel_group_by_name(name)
+    }
+
+    /// Find a channel group by its acquisition name (first match).
+    ///
+    /// Only the name blocks of the groups up to the match are read; no
+    /// [`ChannelGroup`] or [`Channel`] is built for the others.
+    pub fn channel_group_by_name(&self, name: &str) -> Option<ChannelGroup<'_>> {
+        self.raw.data_groups.iter().find_map(|raw_data_group| {
+            raw_data_group
+                .channel_groups
+                .iter()
+                .find(|cg| string_block_eq(&self.raw.mmap, cg.block.acq_name_addr, name))
+                .map(|cg| ChannelGroup::new(raw_data_group, cg, &self.raw.mmap))
+        })
+    }
+
+    /// Find channel `name` in the group named `group` (first match of each).
+    ///
+    /// Walks only the group chain up to `group` and that group's channel
+    /// chain up to `name`; use this over [`MDF::channel`] when the same
+    /// channel name appears in several groups.
+    pub fn find_channel(&self, group: &str, name: &str) -> Option<Channel<'_>> {
+        self.channel_group_by_name(group)?.channel(name)
     }
 
     /// Find a channel by name across all groups (first match).
+    ///
+    /// Groups are searched in file order; only the matching [`Channel`] is
+    /// built.
     pub fn channel(&self, name: &str) -> Option<Channel<'_>> {
-        for group in self.channel_groups() {
-            for channel in group.channels() {
-                if channel.name().ok().flatten().as_deref() == Some(name) {
-                    return Some(channel);
+        self.iter_channel_groups().find_map(|group| group.channel(name))
+    }
+
+    /// All channels whose name matches `pattern`, as `(group_index,
+    /// channel_index, name)` in file order; the group index counts groups as
+    /// [`MDF::channel_groups`] does.
+    ///
+    /// A `&str` is a glob (see [`NamePattern`]); with the `regex` feature a
+    /// `regex::Regex` can be passed as well.
+    ///
+    /// ```no_run
+    /// # use mf4_rs::api::mdf::MDF;
+    /// let mdf = MDF::from_file("recording.mf4")?;
+    /// for (group, channel, name) in mdf.find_channels_matching("ECU1.Engine.*")? {
+    ///     println!("{group}/{channel}: {name}");
+    /// }
+    /// # Ok::<(), mf4_rs::error::MdfError>(())
+    /// ```
+    pub fn find_channels_matching(
+        &self,
+        pattern: impl Into<NamePattern>,
+    ) -> Result<Vec<(usize, usize, String)>, MdfError> {
+        let pattern = pattern.into();
+        let mut matches = Vec::new();
+        for (g, group) in self.iter_channel_groups().enumerate() {
+            for (c, channel) in group.channels().iter().enumerate() {
+                if let Some(name) = channel.name()?
+                    && pattern.is_match(&name)
+                {
+                    matches.push((g, c, name));
                 }
             }
         }
-        None
+        Ok(matches)
+    }
+
+    /// The channel names of all groups as a hierarchy, split at the path
+    /// separator each group declares (see
+    /// [`ChannelGroup::path_separator`]). Names of groups without a
+    /// separator are not split.
+    pub fn channel_tree(&self) -> Result<ChannelTree, MdfError> {
+        self.build_channel_tree(|group| group.path_separator())
+    }
+
+    /// As [`channel_tree`](Self::channel_tree), but splitting the names of
+    /// every group at `separator`, for files whose groups declare none.
+    pub fn channel_tree_with_separator(&self, separator: char) -> Result<ChannelTree, MdfError> {
+        self.build_channel_tree(|_| Some(separator))
+    }
+
+    fn build_channel_tree(
+        &self,
+        separator: impl Fn(&ChannelGroup<'_>) -> Option<char>,
+    ) -> Result<ChannelTree, MdfError> {
+        let mut tree = ChannelTree::default();
+        for (g, group) in self.iter_channel_groups().enumerate() {
+            let separator = separator(&group);
+            for (c, channel) in group.channels().iter().enumerate() {
+                let Some(name) = channel.name()? else { continue };
+                let levels: Vec<&str> = match separator {
+                    Some(sep) => name.split(sep).collect(),
+                    None => vec![&name],
+                };
+                // The last level is the channel itself.
+                tree.insert(&levels[..levels.len() - 1], (g, c, name.clone()));
+            }
+        }
+        Ok(tree)
+    }
+
+    /// A [`ChannelReader`] for channel `name` (first match across all
+    /// groups, as with [`MDF::channel`]), holding a clone of the `Arc` so it
+    /// can be moved to another thread.
+    pub fn channel_reader(self: &Arc<Self>, name: &str) -> Option<ChannelReader> {
+        self.raw.data_groups.iter().enumerate().find_map(|(dg, raw_data_group)| {
+            raw_data_group.channel_groups.iter().enumerate().find_map(|(cg, raw_channel_group)| {
+                let cn = raw_channel_group
+                    .raw_channels
+                    .iter()
+                    .position(|ch| string_block_eq(&self.raw.mmap, ch.block.name_addr, name))?;
+                Some(ChannelReader::new(Arc::clone(self), dg, cg, cn))
+            })
+        })
     }
 
     /// Read a channel by name as a [`Signal`] (values paired with the master
@@ -79,7 +322,7 @@ impl MDF {
     ///
     /// Returns `Ok(None)` if no channel with that name exists.
     pub fn signal(&self, name: &str) -> Result<Option<crate::signal::Signal>, MdfError> {
-        for group in self.channel_groups() {
+        for group in self.iter_channel_groups() {
             if let Some(sig) = group.signal(name)? {
                 return Ok(Some(sig));
             }
@@ -100,10 +343,61 @@ impl MDF {
         }
     }
 
+    /// Get the full start time of the measurement: timestamp, time zone and
+    /// daylight saving offsets, flags and time quality.
+    ///
+    /// Returns None if the start time is 0 (not set).
+    pub fn start_time(&self) -> Option<StartTime> {
+        let hd = &self.raw.header;
+        self.start_time_ns().map(|abs_time_ns| StartTime {
+            abs_time_ns,
+            tz_offset_min: hd.tz_offset,
+            dst_offset_min: hd.daylight_save_time,
+            time_flags: hd.time_flags,
+            time_quality: hd.time_quality,
+        })
+    }
+
+    /// Read the header comment as [`HeaderMetadata`].
+    ///
+    /// Returns `Ok(None)` when the file has no header comment; a plain-text
+    /// comment is returned in [`HeaderMetadata::comment`].
+    pub fn header_metadata(&self) -> Result<Option<HeaderMetadata>, MdfError> {
+        let comment = read_string_block(&self.raw.mmap, self.raw.header.comment_addr)?;
+        Ok(comment.map(|text| HeaderMetadata::from_xml(&text)))
+    }
+
+    /// Read the start time and header comment fields (author, department,
+    /// project, subject, measurement UUID) in one go.
+    pub fn header_info(&self) -> Result<HeaderInfo, MdfError> {
+        Ok(HeaderInfo {
+            start_time: self.start_time(),
+            metadata: self.header_metadata()?.unwrap_or_default(),
+        })
+    }
+
+    /// Read the file's events (`##EV` blocks) in link order.
+    pub fn events(&self) -> Result<Vec<Event>, MdfError> {
+        Event::read_all(&self.raw.mmap, self.raw.header.first_event_addr)
+    }
+
+    /// Describe the whole file — groups, channels, data types, units,
+    /// conversions, sources and attachments — without reading sample data.
+    pub fn metadata(&self) -> Result<FileMetadata, MdfError> {
+        FileMetadata::from_mdf_file(&self.raw)
+    }
+
+    /// [`MDF::metadata`] serialized as pretty-printed JSON.
+    pub fn metadata_json(&self) -> Result<String, MdfError> {
+        self.metadata()?.to_json()
+    }
+
     /// Build a [`FileLayout`] describing every block in the underlying file.
     ///
     /// The layout can be rendered as a flat table, an indented tree or JSON
-    /// for inspecting on-disk structure and link chains.
+    /// for inspecting on-disk structure and link chains. For a file opened
+    /// without a memory map it describes the in-memory image of the
+    /// metadata instead; use [`FileLayout::from_file`] for the file itself.
     pub fn file_layout(&self) -> Result<FileLayout, MdfError> {
         FileLayout::from_bytes(&self.raw.mmap)
     }
@@ -0,0 +1,56 @@
+/// File extensions of common MIME content types, as stored in the unit of
+/// `MimeSample` / `MimeStream` channels.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/bmp", "bmp"),
+    ("image/gif", "gif"),
+    ("image/tiff", "tiff"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+    ("video/mp4", "mp4"),
+    ("video/mpeg", "mpg"),
+    ("video/x-msvideo", "avi"),
+    ("video/quicktime", "mov"),
+    ("video/x-matroska", "mkv"),
+    ("video/webm", "webm"),
+    ("video/h264", "h264"),
+    ("audio/wav", "wav"),
+    ("audio/x-wav", "wav"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("audio/flac", "flac"),
+    ("application/json", "json"),
+    ("application/xml", "xml"),
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("text/plain", "txt"),
+    ("text/csv", "csv"),
+    ("text/xml", "xml"),
+    ("text/html", "html"),
+];
+
+/// The MIME type stored in a channel unit: the text of a `##TX` unit, or
+/// the `<TX>` element of a `##MD` one, without parameters such as
+/// `; codecs=...`. `None` if nothing is left.
+pub(crate) fn parse_mime_type(unit: &str) -> Option<String> {
+    let text = match (unit.find("<TX>"), unit.find("</TX>")) {
+        (Some(start), Some(end)) if start < end => &unit[start + 4..end],
+        _ => unit,
+    };
+    let essence = text.split(';').next().unwrap_or_default().trim();
+    (!essence.is_empty()).then(|| essence.to_ascii_lowercase())
+}
+
+/// File extension (without the dot) for `mime_type`; `"bin"` for unknown
+/// types.
+///
+/// Parameters are ignored and the comparison is case-insensitive, so
+/// `"Video/MP4; codecs=avc1"` maps to `"mp4"`.
+pub fn extension_for_mime_type(mime_type: &str) -> &'static str {
+    let Some(essence) = parse_mime_type(mime_type) else { return "bin" };
+    EXTENSIONS
+        .iter()
+        .find(|(mime, _)| *mime == essence)
+        .map_or("bin", |(_, ext)| ext)
+}
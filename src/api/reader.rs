@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use crate::api::channel::Channel;
+use crate::api::mdf::MDF;
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+
+/// Cursor over the samples of one channel of a shared [`MDF`].
+///
+/// The reader owns an `Arc` of the file, so it is `'static` and can be moved
+/// into a spawned thread; each thread keeps its own position while all of
+/// them decode from the same mapping. Create one with
+/// [`MDF::channel_reader`].
+///
+/// # Example
+/// ```no_run
+/// # fn run() -> Result<(), mf4_rs::error::MdfError> {
+/// use std::sync::Arc;
+/// use mf4_rs::api::mdf::MDF;
+///
+/// let mdf = Arc::new(MDF::from_file("recording.mf4")?);
+/// let threads: Vec<_> = ["Speed", "Torque"]
+///     .into_iter()
+///     .filter_map(|name| mdf.channel_reader(name))
+///     .map(|mut reader| {
+///         std::thread::spawn(move || -> Result<f64, mf4_rs::error::MdfError> {
+///             let mut sum = 0.0;
+///             loop {
+///                 let chunk = reader.read_f64(65_536)?;
+///                 if chunk.is_empty() {
+///                     return Ok(sum);
+///                 }
+///                 sum += chunk.into_iter().flatten().sum::<f64>();
+///             }
+///         })
+///     })
+///     .collect();
+/// for t in threads {
+///     println!("{}", t.join().unwrap()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ChannelReader {
+    mdf: Arc<MDF>,
+    /// Indices of the data group, channel group and channel in the file.
+    dg: usize,
+    cg: usize,
+    cn: usize,
+    position: usize,
+}
+
+impl ChannelReader {
+    pub(crate) fn new(mdf: Arc<MDF>, dg: usize, cg: usize, cn: usize) -> Self {
+        ChannelReader { mdf, dg, cg, cn, position: 0 }
+    }
+
+    /// The channel read by this cursor.
+    pub fn channel(&self) -> Channel<'_> {
+        let raw = self.mdf.raw();
+        let raw_data_group = &raw.data_groups[self.dg];
+        let raw_channel_group = &raw_data_group.channel_groups[self.cg];
+        let raw_channel = &raw_channel_group.raw_channels[self.cn];
+        Channel::new(&raw_channel.block, raw_data_group, raw_channel_group, raw_channel, &raw.mmap)
+    }
+
+    /// The file the channel belongs to.
+    pub fn mdf(&self) -> &Arc<MDF> {
+        &self.mdf
+    }
+
+    /// Index of the next record to read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of records of the channel's group, as stored in its
+    /// `cycles_nr`.
+    pub fn record_count(&self) -> usize {
+        self.mdf.raw().data_groups[self.dg].channel_groups[self.cg].block.cycles_nr as usize
+    }
+
+    /// Move the cursor to record `position`; reads past the last record
+    /// return nothing.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Decode and convert the next `count` samples (fewer at the end of the
+    /// channel, none once it is exhausted) and advance past them; see
+    /// [`Channel::values_between_records`].
+    pub fn read(&mut self, count: usize) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let values = self.channel().values_between_records(self.position, count)?;
+        self.position += values.len();
+        Ok(values)
+    }
+
+    /// Like [`read`](Self::read) with the samples cast to `f64` as in
+    /// [`Channel::values_f64`].
+    pub fn read_f64(&mut self, count: usize) -> Result<Vec<Option<f64>>, MdfError> {
+        Ok(self
+            .read(count)?
+            .into_iter()
+            .map(|value| match value? {
+                DecodedValue::UnsignedInteger(v) => Some(v as f64),
+                DecodedValue::SignedInteger(v) => Some(v as f64),
+                DecodedValue::Float(v) => Some(v),
+                _ => None,
+            })
+            .collect())
+    }
+}
@@ -9,13 +9,14 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 use std::fs;
+use std::path::Path;
 
 use byteorder::{ByteOrder, LittleEndian};
 use serde::{Deserialize, Serialize};
 
 use crate::blocks::channel_block::ChannelBlock;
 use crate::blocks::channel_group_block::ChannelGroupBlock;
-use crate::blocks::common::{BlockHeader, BlockParse};
+use crate::blocks::common::{bytes_at, bytes_from, BlockHeader, BlockParse};
 use crate::blocks::conversion::ConversionBlock;
 use crate::blocks::data_group_block::DataGroupBlock;
 use crate::blocks::data_list_block::DataListBlock;
@@ -79,7 +80,7 @@ pub struct FileLayout {
 impl FileLayout {
     /// Build a layout by reading an MDF file from disk into memory.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn from_file(path: &str) -> Result<Self, MdfError> {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MdfError> {
         let data = fs::read(path)?;
         Self::from_bytes(&data)
     }
@@ -195,21 +196,21 @@ impl FileLayout {
 
     /// Write the text listing to `path`.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn write_text_to_file(&self, path: &str) -> Result<(), MdfError> {
+    pub fn write_text_to_file(&self, path: impl AsRef<Path>) -> Result<(), MdfError> {
         fs::write(path, self.to_text())?;
         Ok(())
     }
 
     /// Write the tree view to `path`.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn write_tree_to_file(&self, path: &str) -> Result<(), MdfError> {
+    pub fn write_tree_to_file(&self, path: impl AsRef<Path>) -> Result<(), MdfError> {
         fs::write(path, self.to_tree())?;
         Ok(())
     }
 
     /// Write JSON to `path`.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn write_json_to_file(&self, path: &str) -> Result<(), MdfError> {
+    pub fn write_json_to_file(&self, path: impl AsRef<Path>) -> Result<(), MdfError> {
         fs::write(path, self.to_json()?)?;
         Ok(())
     }
@@ -338,7 +339,7 @@ impl<'a> Walker<'a> {
 
     fn walk(&mut self) -> Result<(), MdfError> {
         // ##ID (not a standard block header - exactly 64 bytes at offset 0).
-        let id = IdentificationBlock::from_bytes(&self.data[0..64])?;
+        let id = IdentificationBlock::from_bytes(bytes_at(self.data, 0, 64)?)?;
         self.blocks.push(BlockInfo {
             offset: 0,
             end_offset: 64,
@@ -365,19 +366,15 @@ impl<'a> Walker<'a> {
         if offset == 0 {
             return None;
         }
-        let o = offset as usize;
-        if o + 4 > self.data.len() {
-            return None;
-        }
-        Some(String::from_utf8_lossy(&self.data[o..o + 4]).to_string())
+        let id = bytes_at(self.data, offset, 4).ok()?;
+        Some(String::from_utf8_lossy(id).to_string())
     }
 
     fn walk_header(&mut self, offset: u64) -> Result<(), MdfError> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let hd = HeaderBlock::from_bytes(&self.data[o..o + 104])?;
+        let hd = HeaderBlock::from_bytes(bytes_at(self.data, offset, 104)?)?;
 
         let links = vec![
             self.make_link("first_dg_addr", hd.first_dg_addr),
@@ -415,8 +412,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(0);
         }
-        let o = offset as usize;
-        let dg = DataGroupBlock::from_bytes(&self.data[o..])?;
+        let dg = DataGroupBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = dg.header.block_len;
 
         let links = vec![
@@ -430,7 +426,7 @@ impl<'a> Walker<'a> {
         self.dg_counter += 1;
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##DG".to_string(),
             description: format!("Data Group #{} (record_id_len={})", index, dg.record_id_len),
@@ -477,8 +473,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok((0, 0, 0));
         }
-        let o = offset as usize;
-        let cg = ChannelGroupBlock::from_bytes(&self.data[o..])?;
+        let cg = ChannelGroupBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = cg.header.block_len;
         let record_size = record_id_len as usize
             + cg.samples_byte_nr as usize
@@ -497,7 +492,7 @@ impl<'a> Walker<'a> {
         self.cg_counter += 1;
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##CG".to_string(),
             description: format!(
@@ -530,8 +525,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(0);
         }
-        let o = offset as usize;
-        let cn = ChannelBlock::from_bytes(&self.data[o..])?;
+        let cn = ChannelBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = cn.header.block_len;
 
         let name = read_text_at(self.data, cn.name_addr).unwrap_or_default();
@@ -551,7 +545,7 @@ impl<'a> Walker<'a> {
         self.cn_counter += 1;
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##CN".to_string(),
             description: format!(
@@ -593,8 +587,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let cc = ConversionBlock::from_bytes(&self.data[o..])?;
+        let cc = ConversionBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = cc.header.block_len;
 
         let mut links = vec![
@@ -614,7 +607,7 @@ impl<'a> Walker<'a> {
 
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##CC".to_string(),
             description: format!("Conversion ({:?})", cc.cc_type),
@@ -644,8 +637,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let si = SourceBlock::from_bytes(&self.data[o..])?;
+        let si = SourceBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = si.header.block_len;
 
         let links = vec![
@@ -655,7 +647,7 @@ impl<'a> Walker<'a> {
         ];
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##SI".to_string(),
             description: format!(
@@ -680,17 +672,17 @@ impl<'a> Walker<'a> {
             return Ok(());
         }
         let o = offset as usize;
-        if o + 24 > self.data.len() {
+        if o.saturating_add(24) > self.data.len() {
             return Ok(());
         }
-        let header = BlockHeader::from_bytes(&self.data[o..o + 24])?;
+        let header = BlockHeader::from_bytes(bytes_at(self.data, offset, 24)?)?;
         match header.id.as_str() {
             "##TX" => {
-                let tx = TextBlock::from_bytes(&self.data[o..])?;
+                let tx = TextBlock::from_bytes(bytes_from(self.data, offset)?)?;
                 let preview = preview_string(&tx.text, 48);
                 self.blocks.push(BlockInfo {
                     offset,
-                    end_offset: offset + header.block_len,
+                    end_offset: offset.saturating_add(header.block_len),
                     size: header.block_len,
                     block_type: "##TX".to_string(),
                     description: format!("Text: \"{}\" ({} chars)", preview, tx.text.chars().count()),
@@ -699,11 +691,11 @@ impl<'a> Walker<'a> {
                 });
             }
             "##MD" => {
-                let md = MetadataBlock::from_bytes(&self.data[o..])?;
+                let md = MetadataBlock::from_bytes(bytes_from(self.data, offset)?)?;
                 let preview = preview_string(&md.xml.replace('\n', " "), 48);
                 self.blocks.push(BlockInfo {
                     offset,
-                    end_offset: offset + header.block_len,
+                    end_offset: offset.saturating_add(header.block_len),
                     size: header.block_len,
                     block_type: "##MD".to_string(),
                     description: format!("Metadata (XML, {} chars): {}", md.xml.len(), preview),
@@ -716,7 +708,7 @@ impl<'a> Walker<'a> {
                 // isn't there.
                 self.blocks.push(BlockInfo {
                     offset,
-                    end_offset: offset + header.block_len,
+                    end_offset: offset.saturating_add(header.block_len),
                     size: header.block_len,
                     block_type: header.id.clone(),
                     description: "Unknown block (not walked further)".to_string(),
@@ -754,11 +746,11 @@ impl<'a> Walker<'a> {
                 // Unrecognised data block id - record it flat.
                 if self.visited.insert(offset) {
                     let o = offset as usize;
-                    if o + 24 <= self.data.len() {
-                        let header = BlockHeader::from_bytes(&self.data[o..o + 24])?;
+                    if o.saturating_add(24) <= self.data.len() {
+                        let header = BlockHeader::from_bytes(bytes_at(self.data, offset, 24)?)?;
                         self.blocks.push(BlockInfo {
                             offset,
-                            end_offset: offset + header.block_len,
+                            end_offset: offset.saturating_add(header.block_len),
                             size: header.block_len,
                             block_type: other.to_string(),
                             description: "Unknown data block".to_string(),
@@ -783,8 +775,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let header = BlockHeader::from_bytes(&self.data[o..o + 24])?;
+        let header = BlockHeader::from_bytes(bytes_at(self.data, offset, 24)?)?;
         let size = header.block_len;
         let payload = size.saturating_sub(24);
 
@@ -811,7 +802,7 @@ impl<'a> Walker<'a> {
 
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: id.to_string(),
             description: format!("Data block ({} payload bytes)", payload),
@@ -825,12 +816,11 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let header = BlockHeader::from_bytes(&self.data[o..o + 24])?;
+        let header = BlockHeader::from_bytes(bytes_at(self.data, offset, 24)?)?;
         let size = header.block_len;
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##SD".to_string(),
             description: format!("Signal data (VLSD stream, {} payload bytes)", size.saturating_sub(24)),
@@ -850,8 +840,7 @@ impl<'a> Walker<'a> {
         if !self.visited.insert(offset) {
             return Ok(());
         }
-        let o = offset as usize;
-        let dl = DataListBlock::from_bytes(&self.data[o..])?;
+        let dl = DataListBlock::from_bytes(bytes_from(self.data, offset)?)?;
         let size = dl.header.block_len;
 
         let mut links = vec![self.make_link("next", dl.next)];
@@ -871,7 +860,7 @@ impl<'a> Walker<'a> {
 
         self.blocks.push(BlockInfo {
             offset,
-            end_offset: offset + size,
+            end_offset: offset.saturating_add(size),
             size,
             block_type: "##DL".to_string(),
             description: desc,
@@ -901,20 +890,12 @@ fn read_text_at(data: &[u8], addr: u64) -> Option<String> {
     if addr == 0 {
         return None;
     }
-    let o = addr as usize;
-    if o + 24 > data.len() {
-        return None;
-    }
-    let id = &data[o..o + 4];
-    if id != b"##TX" {
-        return None;
-    }
-    let block_len = LittleEndian::read_u64(&data[o + 8..o + 16]);
-    let end = o + block_len as usize;
-    if end > data.len() {
+    let block = bytes_from(data, addr).ok()?;
+    if block.len() < 24 || &block[..4] != b"##TX" {
         return None;
     }
-    let raw = &data[o + 24..end];
+    let block_len = LittleEndian::read_u64(&block[8..16]);
+    let raw = block.get(24..usize::try_from(block_len).ok()?)?;
     Some(
         String::from_utf8_lossy(raw)
             .trim_matches('\0')
@@ -0,0 +1,139 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::common::BlockHeader;
+use crate::blocks::common::BlockParse;
+use crate::error::MdfError;
+
+/// `at_flags` bit 0: the attachment data is embedded in the block.
+pub const AT_FLAG_EMBEDDED: u16 = 0x01;
+/// `at_flags` bit 1: the embedded data is zip-compressed.
+pub const AT_FLAG_COMPRESSED: u16 = 0x02;
+/// `at_flags` bit 2: `md5_checksum` is valid.
+pub const AT_FLAG_MD5_VALID: u16 = 0x04;
+
+/// Represents an ATBLOCK (“##AT”) from the MDF4 file.
+///
+/// - Links:
+///   • at_at_next     LINK → next ATBLOCK
+///   • at_tx_filename LINK → TXBLOCK (file name / path)
+///   • at_tx_mimetype LINK → TXBLOCK (MIME type)
+///   • at_md_comment  LINK → TXBLOCK/MDBLOCK (comment)
+/// - Data:
+///   • at_flags         UINT16 (bit 0 = embedded, bit 1 = compressed, bit 2 = MD5 valid)
+///   • at_creator_index UINT16 (index into the file history)
+///   • at_reserved      BYTE[4]
+///   • at_md5_checksum  BYTE[16]
+///   • at_original_size UINT64
+///   • at_embedded_size UINT64
+///   • at_embedded_data BYTE[at_embedded_size]
+#[derive(Debug, Clone)]
+pub struct AttachmentBlock {
+    pub header:         BlockHeader,
+    pub next_at_addr:   u64,
+    pub filename_addr:  u64,
+    pub mimetype_addr:  u64,
+    pub comment_addr:   u64,
+
+    pub flags:          u16,
+    pub creator_index:  u16,
+    pub md5_checksum:   [u8; 16],
+    pub original_size:  u64,
+    pub embedded_size:  u64,
+}
+
+impl BlockParse<'_> for AttachmentBlock {
+    const ID: &'static str = "##AT";
+    /// Parse an ATBLOCK from its raw bytes. The embedded payload is not
+    /// copied; use [`AttachmentBlock::embedded_data`] to borrow it.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header = Self::parse_header(bytes)?;
+
+        let expected_bytes = 96;
+        if bytes.len() < expected_bytes {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: expected_bytes,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+
+        let mut md5_checksum = [0u8; 16];
+        md5_checksum.copy_from_slice(&bytes[64..80]);
+
+        Ok(AttachmentBlock {
+            header,
+            next_at_addr:  LittleEndian::read_u64(&bytes[24..32]),
+            filename_addr: LittleEndian::read_u64(&bytes[32..40]),
+            mimetype_addr: LittleEndian::read_u64(&bytes[40..48]),
+            comment_addr:  LittleEndian::read_u64(&bytes[48..56]),
+            flags:         LittleEndian::read_u16(&bytes[56..58]),
+            creator_index: LittleEndian::read_u16(&bytes[58..60]),
+            md5_checksum,
+            original_size: LittleEndian::read_u64(&bytes[80..88]),
+            embedded_size: LittleEndian::read_u64(&bytes[88..96]),
+        })
+    }
+}
+
+impl AttachmentBlock {
+    /// `true` if the attachment data is stored inside the MDF file.
+    pub fn is_embedded(&self) -> bool {
+        self.flags & AT_FLAG_EMBEDDED != 0
+    }
+
+    /// `true` if the embedded data is compressed.
+    pub fn is_compressed(&self) -> bool {
+        self.flags & AT_FLAG_COMPRESSED != 0
+    }
+
+    /// The MD5 checksum of the original data, if the file marks it as valid.
+    pub fn md5(&self) -> Option<[u8; 16]> {
+        (self.flags & AT_FLAG_MD5_VALID != 0).then_some(self.md5_checksum)
+    }
+
+    /// Borrow the embedded payload from the block's raw bytes (starting at
+    /// the “##AT” header). Returns an empty slice for external attachments.
+    pub fn embedded_data<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8], MdfError> {
+        let end = (self.embedded_size as usize).saturating_add(96);
+        if bytes.len() < end {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: end,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+        Ok(&bytes[96..end])
+    }
+}
+
+/// Read the chain of [`ATBLOCK`](AttachmentBlock)s starting at `address`.
+///
+/// # Arguments
+/// * `mmap` - The entire MDF file mapped into memory.
+/// * `address` - File offset of the first `##AT` block (0 if none).
+///
+/// # Returns
+/// The attachments in link order paired with their file offsets, or an
+/// [`MdfError`] if a block could not be parsed.
+pub fn read_attachment_blocks(mmap: &[u8], mut address: u64) -> Result<Vec<(u64, AttachmentBlock)>, MdfError> {
+    let mut blocks = Vec::new();
+    while address != 0 {
+        let start = address as usize;
+        if start.saturating_add(24) > mmap.len() {
+            return Err(MdfError::BlockLinkError(format!(
+                "attachment link {address:#x} points past the end of the file"
+            )));
+        }
+        let block = AttachmentBlock::from_bytes(&mmap[start..])?;
+        let next = block.next_at_addr;
+        blocks.push((address, block));
+        if blocks.iter().any(|(a, _)| *a == next) {
+            return Err(MdfError::BlockLinkError(format!(
+                "attachment chain loops back to {next:#x}"
+            )));
+        }
+        address = next;
+    }
+    Ok(blocks)
+}
@@ -0,0 +1,225 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::common::BlockHeader;
+use crate::blocks::common::BlockParse;
+use crate::error::MdfError;
+
+/// `ca_flags` bit 4: the array has axes.
+pub const CA_FLAG_AXIS: u32 = 0x10;
+/// `ca_flags` bit 5: the axes are fixed and stored in `ca_axis_value`.
+pub const CA_FLAG_FIXED_AXES: u32 = 0x20;
+/// `ca_flags` bit 6: elements are stored column-oriented (last index
+/// changes slowest) instead of row-oriented.
+pub const CA_FLAG_INVERSE_LAYOUT: u32 = 0x40;
+
+/// `ca_storage`: how the array elements are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayStorage {
+    /// Elements live in the parent channel's record, at offsets derived from
+    /// the channel with `ca_byte_offset_base` / `ca_inval_bit_pos_base`.
+    ChannelTemplate,
+    /// Every element has a channel group of its own.
+    ChannelGroupTemplate,
+    /// Every element has a data group of its own.
+    DataGroupTemplate,
+    Unknown(u8),
+}
+
+impl ArrayStorage {
+    /// Map a `ca_storage` byte to the enum.
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ArrayStorage::ChannelTemplate,
+            1 => ArrayStorage::ChannelGroupTemplate,
+            2 => ArrayStorage::DataGroupTemplate,
+            other => ArrayStorage::Unknown(other),
+        }
+    }
+
+    /// The `ca_storage` byte stored in the block.
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ArrayStorage::ChannelTemplate => 0,
+            ArrayStorage::ChannelGroupTemplate => 1,
+            ArrayStorage::DataGroupTemplate => 2,
+            ArrayStorage::Unknown(other) => other,
+        }
+    }
+}
+
+/// Represents a CABLOCK (“##CA”) from the MDF4 file, describing the
+/// dimensions of an array channel. It is linked from the channel's
+/// `cn_composition` link.
+///
+/// - Links:
+///   • ca_composition    LINK → CABLOCK/CNBLOCK (nested composition)
+///   • further links     LINK[] → data, dynamic size, quantities, axes;
+///   presence depends on `ca_storage` and `ca_flags`
+/// - Data:
+///   • ca_type                UINT8 (0=array, 1=scaling axis, 2=look-up,
+///   3=interval axis, 4=classification result)
+///   • ca_storage             UINT8 (0=CN template, 1=CG template, 2=DG template)
+///   • ca_ndim                UINT16
+///   • ca_flags               UINT32
+///   • ca_byte_offset_base    INT32
+///   • ca_inval_bit_pos_base  UINT32
+///   • ca_dim_size            UINT64[ca_ndim]
+///   • ca_axis_value          REAL[Σ ca_dim_size] (fixed axes only)
+///   • ca_cycle_count         UINT64[Π ca_dim_size] (DG template only)
+#[derive(Debug, Clone)]
+pub struct ChannelArrayBlock {
+    pub header:             BlockHeader,
+    pub composition_addr:   u64,
+    /// Every link after `ca_composition`, in file order.
+    pub links:              Vec<u64>,
+
+    pub array_type:         u8,
+    pub storage:            ArrayStorage,
+    pub flags:              u32,
+    pub byte_offset_base:   i32,
+    pub inval_bit_pos_base: u32,
+    pub dim_sizes:          Vec<u64>,
+    /// Axis values per dimension, present with fixed axes.
+    pub axis_values:        Vec<Vec<f64>>,
+    /// Cycle count per element, present for DG template storage.
+    pub cycle_counts:       Vec<u64>,
+}
+
+impl BlockParse<'_> for ChannelArrayBlock {
+    const ID: &'static str = "##CA";
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header = Self::parse_header(bytes)?;
+
+        if header.links_nr < 1 {
+            return Err(MdfError::BlockLinkError(
+                "##CA block needs at least the composition link".to_string(),
+            ));
+        }
+        let links_end = (header.links_nr as usize).saturating_mul(8).saturating_add(24);
+        let too_short = |expected: usize| MdfError::TooShortBuffer {
+            actual:   bytes.len(),
+            expected,
+            file:     file!(),
+            line:     line!(),
+        };
+        if bytes.len() < links_end.saturating_add(16) {
+            return Err(too_short(links_end.saturating_add(16)));
+        }
+        let link = |i: usize| LittleEndian::read_u64(&bytes[24 + i * 8..32 + i * 8]);
+        let data = &bytes[links_end..];
+        let storage = ArrayStorage::from_u8(data[1]);
+        let ndim = LittleEndian::read_u16(&data[2..4]) as usize;
+        let flags = LittleEndian::read_u32(&data[4..8]);
+
+        let mut pos = 16;
+        let mut read_u64s = |count: usize| -> Result<Vec<u64>, MdfError> {
+            let end = count.saturating_mul(8).saturating_add(pos);
+            if data.len() < end {
+                return Err(too_short(links_end.saturating_add(end)));
+            }
+            let values = data[pos..end].chunks_exact(8).map(LittleEndian::read_u64).collect();
+            pos = end;
+            Ok(values)
+        };
+        let dim_sizes = read_u64s(ndim)?;
+        let axis_values = if flags & (CA_FLAG_AXIS | CA_FLAG_FIXED_AXES) == CA_FLAG_AXIS | CA_FLAG_FIXED_AXES {
+            dim_sizes
+                .iter()
+                .map(|&size| {
+                    read_u64s(size as usize).map(|raw| raw.into_iter().map(f64::from_bits).collect())
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+        let cycle_counts = if storage == ArrayStorage::DataGroupTemplate {
+            read_u64s(dim_sizes.iter().fold(1u64, |n, &size| n.saturating_mul(size)) as usize)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(ChannelArrayBlock {
+            composition_addr:   link(0),
+            links:              (1..header.links_nr as usize).map(link).collect(),
+            array_type:         data[0],
+            storage,
+            flags,
+            byte_offset_base:   LittleEndian::read_i32(&data[8..12]),
+            inval_bit_pos_base: LittleEndian::read_u32(&data[12..16]),
+            dim_sizes,
+            axis_values,
+            cycle_counts,
+            header,
+        })
+    }
+}
+
+impl ChannelArrayBlock {
+    /// Number of elements in the array (the product of the dimension sizes).
+    pub fn element_count(&self) -> usize {
+        self.dim_sizes.iter().fold(1u64, |n, &size| n.saturating_mul(size)) as usize
+    }
+
+    /// Serializes the ChannelArrayBlock; `header.links_nr` and
+    /// `header.block_len` must match the links and data arrays.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        let links_nr = 1 + self.links.len();
+        let values_nr = self.dim_sizes.len()
+            + self.axis_values.iter().map(Vec::len).sum::<usize>()
+            + self.cycle_counts.len();
+        let block_len = (24 + links_nr * 8 + 16 + values_nr * 8) as u64;
+        if self.header.id != "##CA"
+            || self.header.links_nr as usize != links_nr
+            || self.header.block_len != block_len
+        {
+            return Err(MdfError::BlockSerializationError(format!(
+                "ChannelArrayBlock must have ID '##CA', {} links and block_len={}, found '{}' / {} / {}",
+                links_nr, block_len, self.header.id, self.header.links_nr, self.header.block_len
+            )));
+        }
+        let mut buffer = Vec::with_capacity(block_len as usize);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(&self.composition_addr.to_le_bytes());
+        for link in &self.links {
+            buffer.extend_from_slice(&link.to_le_bytes());
+        }
+        buffer.push(self.array_type);
+        buffer.push(self.storage.to_u8());
+        buffer.extend_from_slice(&(self.dim_sizes.len() as u16).to_le_bytes());
+        buffer.extend_from_slice(&self.flags.to_le_bytes());
+        buffer.extend_from_slice(&self.byte_offset_base.to_le_bytes());
+        buffer.extend_from_slice(&self.inval_bit_pos_base.to_le_bytes());
+        for size in &self.dim_sizes {
+            buffer.extend_from_slice(&size.to_le_bytes());
+        }
+        for value in self.axis_values.iter().flatten() {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        for count in &self.cycle_counts {
+            buffer.extend_from_slice(&count.to_le_bytes());
+        }
+        Ok(buffer)
+    }
+}
+
+impl Default for ChannelArrayBlock {
+    fn default() -> Self {
+        ChannelArrayBlock {
+            header: BlockHeader {
+                id: String::from("##CA"),
+                reserved0: 0,
+                block_len: 24 + 8 + 16,
+                links_nr: 1,
+            },
+            composition_addr: 0,
+            links: Vec::new(),
+            array_type: 0,
+            storage: ArrayStorage::ChannelTemplate,
+            flags: 0,
+            byte_offset_base: 0,
+            inval_bit_pos_base: 0,
+            dim_sizes: Vec::new(),
+            axis_values: Vec::new(),
+            cycle_counts: Vec::new(),
+        }
+    }
+}
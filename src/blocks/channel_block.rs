@@ -2,11 +2,21 @@ use byteorder::{ByteOrder, LittleEndian};
 
 use crate::error::MdfError;
 use crate::blocks::common::BlockParse;
-use crate::blocks::common::{BlockHeader, DataType};
+use crate::blocks::common::{link_target, BlockHeader, DataType};
 use crate::blocks::text_block::TextBlock;
 use crate::blocks::conversion::ConversionBlock;
+use crate::parsing::source_info::SourceInfo;
 use crate::parsing::decoder::DecodedValue;
 
+/// `cn_flags` bit 0: all values of the channel are invalid.
+pub(crate) const CN_FLAG_ALL_INVALID: u32 = 0x01;
+/// `cn_flags` bit 1: the channel's invalidation bit is valid.
+pub(crate) const CN_FLAG_INVAL_BIT_VALID: u32 = 0x02;
+/// `cn_flags` bit 3: `min_raw_value` / `max_raw_value` are valid.
+pub const CN_FLAG_RAW_RANGE_VALID: u32 = 0x08;
+/// `cn_flags` bit 4: `lower_limit` / `upper_limit` are valid.
+pub const CN_FLAG_LIMIT_RANGE_VALID: u32 = 0x10;
+
 #[derive(Debug, Clone)]
 pub struct ChannelBlock {
     pub header: BlockHeader, // Common header
@@ -39,6 +49,9 @@ pub struct ChannelBlock {
 
     pub name: Option<String>,
     pub conversion: Option<ConversionBlock>,
+    /// Acquisition source; the writer stores it in a `##SI` block linked
+    /// from `source_addr`. Not filled in when parsing.
+    pub source: Option<SourceInfo>,
 }
 
 impl BlockParse<'_> for ChannelBlock {
@@ -88,6 +101,7 @@ impl BlockParse<'_> for ChannelBlock {
             upper_ext_limit: LittleEndian::read_f64(&bytes[152..160]),
             name: None,
             conversion: None,
+            source: None,
         })
     }
 }
@@ -229,19 +243,7 @@ impl ChannelBlock {
     /// read or parsed.
     pub fn resolve_conversion(&mut self, bytes: &[u8]) -> Result<(), MdfError> {
         if self.conversion.is_none() && self.conversion_addr != 0 {
-            let offset = self.conversion_addr as usize;
-            
-            let expected_bytes = offset + 24;
-            if bytes.len() < expected_bytes {
-                return Err(MdfError::TooShortBuffer {
-                    actual:   bytes.len(),
-                    expected: expected_bytes,
-                    file:     file!(),
-                    line:     line!(),
-                });
-            }
-
-            let mut conv_block = ConversionBlock::from_bytes(&bytes[offset..])?;
+            let mut conv_block = ConversionBlock::from_bytes(link_target(bytes, self.conversion_addr, "##CC")?)?;
 
             let _ = conv_block.resolve_formula(bytes);
             self.conversion = Some(conv_block);
@@ -274,6 +276,46 @@ impl ChannelBlock {
         };
         Ok(decoded)
     }
+
+    /// Range of the raw values, as `(min, max)`.
+    ///
+    /// The stored raw range when its flag is set, otherwise the range of the
+    /// integer type given by `data_type` and `bit_count`. `None` for floats
+    /// and non-numeric channels without a stored range.
+    pub fn raw_range(&self) -> Option<(f64, f64)> {
+        if self.flags & CN_FLAG_RAW_RANGE_VALID != 0 {
+            return Some((self.min_raw_value, self.max_raw_value));
+        }
+        let bits = self.bit_count.min(64) as i32;
+        if bits == 0 {
+            return None;
+        }
+        match self.data_type {
+            DataType::UnsignedIntegerLE | DataType::UnsignedIntegerBE => Some((0.0, 2f64.powi(bits) - 1.0)),
+            DataType::SignedIntegerLE | DataType::SignedIntegerBE => {
+                let half = 2f64.powi(bits - 1);
+                Some((-half, half - 1.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Range of the physical values, as `(min, max)`: the
+    /// [`raw_range`](Self::raw_range) mapped through the conversion.
+    ///
+    /// `None` if either range is unknown; see
+    /// [`SimplifiedConversion::physical_range`](crate::blocks::conversion::SimplifiedConversion::physical_range).
+    ///
+    /// # Arguments
+    /// * `file_data` - Memory mapped MDF bytes, used to resolve the references
+    ///   of the conversion.
+    pub fn physical_range(&self, file_data: &[u8]) -> Result<Option<(f64, f64)>, MdfError> {
+        let Some((raw_min, raw_max)) = self.raw_range() else { return Ok(None) };
+        let Some(conversion) = &self.conversion else { return Ok(Some((raw_min, raw_max))) };
+        let mut conversion = conversion.clone();
+        conversion.resolve_all_dependencies_with_address(file_data, self.conversion_addr)?;
+        Ok(conversion.simplify().physical_range(raw_min, raw_max))
+    }
 }
 
 impl Default for ChannelBlock {
@@ -314,6 +356,7 @@ impl Default for ChannelBlock {
             upper_ext_limit: 0.0,
             name: None,
             conversion: None,
+            source: None,
         }
     }
 }
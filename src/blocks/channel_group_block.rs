@@ -1,9 +1,15 @@
+use std::collections::HashSet;
+
 use byteorder::{ByteOrder, LittleEndian};
 
-use crate::blocks::common::BlockHeader;
+use crate::blocks::common::{link_target, BlockHeader};
 use crate::blocks::common::BlockParse;
 use crate::blocks::channel_block::ChannelBlock;
 use crate::error::MdfError;
+use crate::parsing::source_info::SourceInfo;
+
+/// `cg_flags` bit 0: the channel group holds VLSD records.
+pub(crate) const CG_FLAG_VLSD: u16 = 0x01;
 
 #[derive(Debug)]
 pub struct ChannelGroupBlock {
@@ -22,6 +28,12 @@ pub struct ChannelGroupBlock {
     pub samples_byte_nr: u32,             // 4 bytes
     pub invalidation_bytes_nr: u32,       // 4 bytes
 
+    /// Acquisition name; the writer stores it in a `##TX` block linked from
+    /// `acq_name_addr`. Not filled in when parsing.
+    pub acq_name: Option<String>,
+    /// Acquisition source; the writer stores it in a `##SI` block linked
+    /// from `acq_source_addr`. Not filled in when parsing.
+    pub acq_source: Option<SourceInfo>,
 }
 
 impl BlockParse<'_> for ChannelGroupBlock {
@@ -56,6 +68,8 @@ impl BlockParse<'_> for ChannelGroupBlock {
             reserved1: LittleEndian::read_u32(&bytes[92..96]),
             samples_byte_nr: LittleEndian::read_u32(&bytes[96..100]),
             invalidation_bytes_nr: LittleEndian::read_u32(&bytes[100..104]),
+            acq_name: None,
+            acq_source: None,
         })
     }
 }
@@ -144,11 +158,16 @@ impl ChannelGroupBlock {
     /// channel cannot be decoded.
     pub fn read_channels(&mut self, mmap: &[u8]) -> Result<Vec<ChannelBlock>, MdfError> {
         let mut channels = Vec::new();
+        let mut visited = HashSet::new();
         let mut current_ch_addr = self.first_ch_addr;
 
         while current_ch_addr != 0 {
-            let ch_offset = current_ch_addr as usize;
-            let mut channel = ChannelBlock::from_bytes(&mmap[ch_offset..])?;
+            if !visited.insert(current_ch_addr) {
+                return Err(MdfError::BlockLinkError(format!(
+                    "link cycle through the channel at {current_ch_addr:#x}"
+                )));
+            }
+            let mut channel = ChannelBlock::from_bytes(link_target(mmap, current_ch_addr, "##CN")?)?;
             channel.resolve_conversion(mmap)?;
             current_ch_addr = channel.next_ch_addr;
             channels.push(channel);
@@ -182,6 +201,8 @@ impl Default for ChannelGroupBlock {
             reserved1: 0,
             samples_byte_nr: 0,
             invalidation_bytes_nr: 0,
+            acq_name: None,
+            acq_source: None,
         }
     }
 }
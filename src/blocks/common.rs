@@ -5,6 +5,16 @@ use crate::blocks::text_block::TextBlock;
 use crate::blocks::metadata_block::MetadataBlock;
 use crate::error::MdfError;
 
+/// File offset of the `##HD` block, right after the identification block.
+pub(crate) const HD_OFFSET: u64 = 64;
+
+/// Blocks holding record or signal data; their data sections can be large
+/// and hold no links.
+pub(crate) const DATA_BLOCK_IDS: [&str; 8] = ["##DT", "##DV", "##DI", "##DZ", "##SD", "##RD", "##RV", "##RI"];
+
+/// Lists of data blocks, linking only to [`DATA_BLOCK_IDS`] and to each other.
+pub(crate) const DATA_LIST_IDS: [&str; 3] = ["##DL", "##HL", "##LD"];
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BlockHeader {
     pub id: String,       // 4-byte string
@@ -78,8 +88,9 @@ impl BlockHeader {
     /// * `bytes` - Slice containing at least 24 bytes from the MDF file.
     ///
     /// # Returns
-    /// A [`BlockHeader`] on success or [`MdfError::TooShortBuffer`] when the
-    /// slice is smaller than 24 bytes.
+    /// A [`BlockHeader`] on success, [`MdfError::TooShortBuffer`] when the
+    /// slice is smaller than 24 bytes or [`MdfError::BlockSerializationError`]
+    /// when the stored block length is smaller than the header itself.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
         let expected_bytes = 24;
         if bytes.len() < expected_bytes {
@@ -90,12 +101,20 @@ impl BlockHeader {
                 line:     line!(),
             });
         }
-        Ok(Self {
+        let header = Self {
             id: String::from_utf8_lossy(&bytes[0..4]).to_string(),
             reserved0: LittleEndian::read_u32(&bytes[4..8]),
             block_len: LittleEndian::read_u64(&bytes[8..16]),
             links_nr: LittleEndian::read_u64(&bytes[16..24]),
-        })
+        };
+        // Every block includes its own header.
+        if header.block_len < expected_bytes as u64 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "{} block length {} is shorter than its header",
+                header.id, header.block_len
+            )));
+        }
+        Ok(header)
     }
 }
 
@@ -103,7 +122,7 @@ pub trait BlockParse<'a>: Sized {
     const ID: &'static str;
 
     fn parse_header(bytes: &[u8]) -> Result<BlockHeader, MdfError> {
-        let header = BlockHeader::from_bytes(&bytes[0..24])?;
+        let header = BlockHeader::from_bytes(bytes)?;
         if header.id != Self::ID {
             return Err(MdfError::BlockIDError {
                 actual: header.id.clone(),
@@ -212,13 +231,62 @@ impl DataType {
             | DataType::ByteArray
             | DataType::MimeSample
             | DataType::MimeStream => 8,
-            DataType::CanOpenDate | DataType::CanOpenTime => 64,
+            DataType::CanOpenDate => 56,
+            DataType::CanOpenTime => 48,
             DataType::ComplexLE | DataType::ComplexBE => 64,
             DataType::Unknown(_) => 8,
         }
     }
 }
 
+/// The bytes of `data` from `offset` to the end, e.g. the block a link
+/// points at.
+///
+/// Returns [`MdfError::TooShortBuffer`] instead of panicking when a malformed
+/// link or length points past the end of `data`.
+#[track_caller]
+pub fn bytes_from(data: &[u8], offset: u64) -> Result<&[u8], MdfError> {
+    let start = usize::try_from(offset).unwrap_or(usize::MAX);
+    match data.get(start..) {
+        Some(bytes) => Ok(bytes),
+        None => Err(too_short(data.len(), start)),
+    }
+}
+
+/// `len` bytes of `data` starting at `offset`; see [`bytes_from`].
+#[track_caller]
+pub fn bytes_at(data: &[u8], offset: u64, len: u64) -> Result<&[u8], MdfError> {
+    let start = usize::try_from(offset).unwrap_or(usize::MAX);
+    let end = usize::try_from(len).ok().and_then(|len| start.checked_add(len)).unwrap_or(usize::MAX);
+    match data.get(start..end) {
+        Some(bytes) => Ok(bytes),
+        None => Err(too_short(data.len(), end)),
+    }
+}
+
+/// The bytes from the `block_id` block a link at `offset` points at to the
+/// end of `data`. Unlike [`bytes_from`], a link past the end of the file is
+/// reported as [`MdfError::LinkOutOfBounds`] naming the expected block.
+pub fn link_target<'a>(data: &'a [u8], offset: u64, block_id: &str) -> Result<&'a [u8], MdfError> {
+    usize::try_from(offset)
+        .ok()
+        .filter(|&start| start < data.len())
+        .map(|start| &data[start..])
+        .ok_or_else(|| MdfError::LinkOutOfBounds {
+            block_id: block_id.to_string(),
+            offset,
+            file_size: data.len() as u64,
+        })
+}
+
+/// [`MdfError::TooShortBuffer`] located at the caller of the (track_caller)
+/// function calling this.
+#[track_caller]
+fn too_short(actual: usize, expected: usize) -> MdfError {
+    let caller = std::panic::Location::caller();
+    MdfError::TooShortBuffer { actual, expected, file: caller.file(), line: caller.line() }
+}
+
 /// Read a text or metadata block at `address` and return its contents.
 ///
 /// # Arguments
@@ -233,16 +301,41 @@ pub fn read_string_block(mmap: &[u8], address: u64) -> Result<Option<String>, Md
         return Ok(None);
     }
 
-    let offset = address as usize;
-    let header = BlockHeader::from_bytes(&mmap[offset..offset + 24])?;
+    let block = link_target(mmap, address, "##TX/##MD")?;
+    let header = BlockHeader::from_bytes(block)?;
 
     match header.id.as_str() {
-        "##TX" => Ok(Some(TextBlock::from_bytes(&mmap[offset..])?.text)),
-        "##MD" => Ok(Some(MetadataBlock::from_bytes(&mmap[offset..])?.xml)),
+        "##TX" => Ok(Some(TextBlock::from_bytes(block)?.text)),
+        "##MD" => Ok(Some(MetadataBlock::from_bytes(block)?.xml)),
         _ => Ok(None),
     }
 }
 
+/// `true` if the string block at `address` reads as `text`.
+///
+/// Same result as comparing [`read_string_block`] with `text`, but `##TX`
+/// blocks are compared in place without decoding, which keeps name lookups
+/// over many groups and channels cheap.
+pub(crate) fn string_block_eq(mmap: &[u8], address: u64, text: &str) -> bool {
+    if address == 0 {
+        return false;
+    }
+    let offset = address as usize;
+    let Some(header) = mmap.get(offset..offset + 24).and_then(|b| BlockHeader::from_bytes(b).ok()) else {
+        return false;
+    };
+    if header.id != "##TX" {
+        return matches!(read_string_block(mmap, address), Ok(Some(s)) if s == text);
+    }
+    let data_len = (header.block_len as usize).saturating_sub(24);
+    let Some(data) = mmap.get(offset + 24..offset + 24 + data_len) else {
+        return false;
+    };
+    let start = data.iter().position(|&b| b != 0).unwrap_or(data.len());
+    let end = data.iter().rposition(|&b| b != 0).map_or(start, |i| i + 1);
+    &data[start..end] == text.as_bytes()
+}
+
 /// Read a text or metadata block via a [`ByteRangeReader`].
 ///
 /// Mirrors [`read_string_block`] but fetches the block bytes through a range
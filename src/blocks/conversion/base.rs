@@ -1,5 +1,5 @@
 use byteorder::{LittleEndian, ByteOrder};
-use crate::blocks::common::{BlockHeader, BlockParse};
+use crate::blocks::common::{bytes_at, bytes_from, BlockHeader, BlockParse};
 use crate::error::MdfError;
 use super::types::ConversionType;
 
@@ -28,7 +28,9 @@ pub struct ConversionBlock {
     
     // Resolved data for self-contained conversions (populated during index creation)
     /// Pre-resolved text strings for text-based conversions (ValueToText, RangeToText, etc.)
-    /// Maps cc_ref indices to their resolved text content
+    /// Maps cc_ref indices to their resolved text content. For BitfieldText,
+    /// whose references are all conversions, it holds the names
+    /// (`cc_tx_name`) of the referenced conversions instead.
     pub resolved_texts: Option<std::collections::HashMap<usize, String>>,
     
     /// Pre-resolved nested conversion blocks for chained conversions
@@ -49,37 +51,35 @@ impl BlockParse<'_> for ConversionBlock {
         let mut offset = 24;
 
         // Fixed links
-        let cc_tx_name = read_link(bytes, &mut offset);
-        let cc_md_unit = read_link(bytes, &mut offset);
-        let cc_md_comment = read_link(bytes, &mut offset);
-        let cc_cc_inverse = read_link(bytes, &mut offset);
+        let cc_tx_name = read_link(bytes, &mut offset)?;
+        let cc_md_unit = read_link(bytes, &mut offset)?;
+        let cc_md_comment = read_link(bytes, &mut offset)?;
+        let cc_cc_inverse = read_link(bytes, &mut offset)?;
 
         let fixed_links = 4;
         let additional_links = header.links_nr.saturating_sub(fixed_links);
-        let mut cc_ref = Vec::with_capacity(additional_links as usize);
+        // Bounded by the buffer so a corrupt link count cannot over-allocate.
+        let mut cc_ref = Vec::with_capacity((additional_links as usize).min(bytes.len() / 8));
         for _ in 0..additional_links {
             cc_ref.push(read_u64(bytes, &mut offset)?);
         }
 
         // Basic fields
-        let cc_type = ConversionType::from_u8(bytes[offset]);
-        offset += 1;
-        let cc_precision = bytes[offset];
-        offset += 1;
-        let cc_flags = LittleEndian::read_u16(&bytes[offset..offset + 2]);
-        offset += 2;
-        let cc_ref_count = LittleEndian::read_u16(&bytes[offset..offset + 2]);
-        offset += 2;
-        let cc_val_count = LittleEndian::read_u16(&bytes[offset..offset + 2]);
-        offset += 2;
+        let fields = bytes_at(bytes, offset as u64, 8)?;
+        let cc_type = ConversionType::from_u8(fields[0]);
+        let cc_precision = fields[1];
+        let cc_flags = LittleEndian::read_u16(&fields[2..4]);
+        let cc_ref_count = LittleEndian::read_u16(&fields[4..6]);
+        let cc_val_count = LittleEndian::read_u16(&fields[6..8]);
+        offset += 8;
 
         // IMPORTANT: Some vendors (like dSPACE) always write the physical range fields
         // even when cc_flags bit 1 is not set. We need to detect this by checking if
         // there's enough data in the block for the range fields.
         // Calculate expected sizes:
-        let size_without_range = 24 + (header.links_nr as usize * 8) + 8 + (cc_val_count as usize * 8);
-        let size_with_range = size_without_range + 16;
-        let has_range_data = header.block_len as usize >= size_with_range;
+        let size_without_range = 24 + header.links_nr.saturating_mul(8).saturating_add(8 + cc_val_count as u64 * 8);
+        let size_with_range = size_without_range.saturating_add(16);
+        let has_range_data = header.block_len >= size_with_range;
         
         let cc_phy_range_min = if has_range_data {
             let val = f64::from_bits(read_u64(bytes, &mut offset)?);
@@ -124,10 +124,9 @@ impl BlockParse<'_> for ConversionBlock {
     }
 }
 
-fn read_link(bytes: &[u8], offset: &mut usize) -> Option<u64> {
-    let link = LittleEndian::read_u64(&bytes[*offset..*offset + 8]);
-    *offset += 8;
-    if link == 0 { None } else { Some(link) }
+fn read_link(bytes: &[u8], offset: &mut usize) -> Result<Option<u64>, MdfError> {
+    let link = read_u64(bytes, offset)?;
+    Ok(if link == 0 { None } else { Some(link) })
 }
 
 fn read_u64(bytes: &[u8], offset: &mut usize) -> Result<u64, MdfError> {
@@ -235,7 +234,7 @@ impl ConversionBlock {
             }
             
             let offset = link_addr as usize;
-            if offset + 24 > file_data.len() {
+            if offset.saturating_add(24) > file_data.len() {
                 continue; // Skip invalid offsets
             }
             
@@ -253,6 +252,11 @@ impl ConversionBlock {
                     // Nested conversion block - resolve recursively
                     let mut nested_conversion = ConversionBlock::from_bytes(&file_data[offset..])?;
                     nested_conversion.resolve_all_dependencies_recursive(file_data, depth + 1, visited, link_addr)?;
+                    if self.cc_type == crate::blocks::conversion::types::ConversionType::BitfieldText
+                        && let Some(name) = read_string_block(file_data, nested_conversion.cc_tx_name.unwrap_or(0))?
+                    {
+                        resolved_texts.insert(i, name);
+                    }
                     
                     // Check if this should be stored as default conversion
                     if Some(i) == default_ref_index {
@@ -367,6 +371,11 @@ impl ConversionBlock {
                     let block_bytes = reader.read_range(link_addr, header.block_len)?;
                     let mut nested = ConversionBlock::from_bytes(&block_bytes)?;
                     nested.resolve_recursive_via_reader(reader, depth + 1, visited, link_addr)?;
+                    if self.cc_type == crate::blocks::conversion::types::ConversionType::BitfieldText
+                        && let Some(name) = read_string_block_via_reader(reader, nested.cc_tx_name.unwrap_or(0))?
+                    {
+                        resolved_texts.insert(i, name);
+                    }
 
                     if Some(i) == default_ref_index {
                         default_conversion = Some(Box::new(nested));
@@ -393,6 +402,15 @@ impl ConversionBlock {
         Ok(())
     }
 
+    /// Read the nested conversion at `address` with its whole chain resolved
+    /// up front (cycle-checked), so it can be applied without file data and a
+    /// malformed link can't recurse forever.
+    pub(crate) fn resolve_nested(file_data: &[u8], address: u64) -> Result<ConversionBlock, MdfError> {
+        let mut nested = ConversionBlock::from_bytes(bytes_from(file_data, address)?)?;
+        nested.resolve_all_dependencies_with_address(file_data, address)?;
+        Ok(nested)
+    }
+
     /// Get a resolved text string for a given cc_ref index.
     /// Returns the text if it was resolved during dependency resolution.
     pub fn get_resolved_text(&self, ref_index: usize) -> Option<&String> {
@@ -411,6 +429,39 @@ impl ConversionBlock {
         self.default_conversion.as_ref().map(|boxed| boxed.as_ref())
     }
 
+    /// One-line human-readable description, e.g. `linear: 3 + 0.5 * x` or
+    /// `value to text (4 entries)`, for channel listings.
+    pub fn summary(&self) -> String {
+        let v = |i: usize| self.cc_val.get(i).copied().unwrap_or(0.0);
+        let refs = self.cc_ref.len();
+        match self.cc_type {
+            ConversionType::Identity => "identity".to_string(),
+            ConversionType::Linear => format!("linear: {} + {} * x", v(0), v(1)),
+            ConversionType::Rational => format!(
+                "rational: ({} * x^2 + {} * x + {}) / ({} * x^2 + {} * x + {})",
+                v(0), v(1), v(2), v(3), v(4), v(5)
+            ),
+            ConversionType::Algebraic => {
+                format!("algebraic: {}", self.formula.as_deref().unwrap_or("?"))
+            }
+            ConversionType::TableLookupInterp => {
+                format!("table with interpolation ({} entries)", self.cc_val.len() / 2)
+            }
+            ConversionType::TableLookupNoInterp => {
+                format!("table without interpolation ({} entries)", self.cc_val.len() / 2)
+            }
+            ConversionType::RangeLookup => {
+                format!("range to value ({} ranges)", self.cc_val.len() / 3)
+            }
+            ConversionType::ValueToText => format!("value to text ({} entries)", self.cc_val.len()),
+            ConversionType::RangeToText => format!("range to text ({} ranges)", self.cc_val.len() / 2),
+            ConversionType::TextToValue => format!("text to value ({} entries)", refs),
+            ConversionType::TextToText => format!("text to text ({} entries)", refs / 2),
+            ConversionType::BitfieldText => format!("bitfield text ({} fields)", self.cc_val.len()),
+            ConversionType::Unknown(t) => format!("unknown conversion type {}", t),
+        }
+    }
+
     /// Serialize this conversion block back to bytes.
     ///
     /// # Returns
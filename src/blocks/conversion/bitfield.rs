@@ -1,5 +1,5 @@
 use crate::blocks::conversion::base::ConversionBlock;
-use crate::blocks::common::{BlockHeader, read_string_block, BlockParse};
+use crate::blocks::common::{BlockHeader, read_string_block};
 use crate::error::MdfError;
 use crate::parsing::decoder::DecodedValue;
 
@@ -24,15 +24,17 @@ pub fn apply_bitfield_text(block: &ConversionBlock, value: DecodedValue, file_da
         if let Some(resolved_conversion) = block.get_resolved_conversion(i) {
             let decoded_masked = resolved_conversion.apply_decoded(DecodedValue::UnsignedInteger(masked), &[])?;
             if let DecodedValue::String(s) = decoded_masked {
-                // Try to get the name from the resolved conversion
-                let part = if let Some(name) = resolved_conversion.cc_tx_name {
-                    if let Some(name_text) = read_string_block(file_data, name)? {
-                        format!("{} = {}", name_text, s)
-                    } else {
-                        s
-                    }
-                } else {
-                    s
+                // The name is resolved along with the conversion; fall back
+                // to reading it from the file for blocks resolved before.
+                let name_addr = resolved_conversion.cc_tx_name.unwrap_or(0);
+                let name = match block.get_resolved_text(i) {
+                    Some(name) => Some(name.clone()),
+                    None if name_addr as usize + 24 <= file_data.len() => read_string_block(file_data, name_addr)?,
+                    None => None,
+                };
+                let part = match name {
+                    Some(name) => format!("{} = {}", name, s),
+                    None => s,
                 };
                 parts.push(part);
             }
@@ -42,7 +44,7 @@ pub fn apply_bitfield_text(block: &ConversionBlock, value: DecodedValue, file_da
         // Fallback to legacy behavior if no resolved data (for backward compatibility)
         // Note: This should rarely be used now that we have deep resolution
         let off = link_addr as usize;
-        if off + 24 > file_data.len() { 
+        if off.saturating_add(24) > file_data.len() { 
             // If we can't access the data, try default conversion as last resort
             if let Some(default_conversion) = block.get_default_conversion() {
                 let decoded_masked = default_conversion.apply_decoded(DecodedValue::UnsignedInteger(masked), &[])?;
@@ -56,11 +58,8 @@ pub fn apply_bitfield_text(block: &ConversionBlock, value: DecodedValue, file_da
         let hdr = BlockHeader::from_bytes(&file_data[off..off+24])?;
         if &hdr.id != "##CC" { continue; }
         
-        // Create nested conversion but don't do deep resolution to avoid double work
-        // since this is fallback code that should rarely execute
-        let mut nested = ConversionBlock::from_bytes(&file_data[off..])?;
-        let _ = nested.resolve_formula(file_data);
-        let decoded_masked = nested.apply_decoded(DecodedValue::UnsignedInteger(masked), file_data)?;
+        let nested = ConversionBlock::resolve_nested(file_data, link_addr)?;
+        let decoded_masked = nested.apply_decoded(DecodedValue::UnsignedInteger(masked), &[])?;
         if let DecodedValue::String(s) = decoded_masked {
             let part = if let Some(name_ptr) = nested.cc_tx_name {
                 if let Some(name) = read_string_block(file_data, name_ptr)? {
@@ -1,7 +1,10 @@
 use crate::blocks::conversion::base::ConversionBlock;
 use crate::error::MdfError;
 use crate::parsing::decoder::DecodedValue;
-use meval::{Context, eval_str_with_context};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use meval::{Context, Expr};
 
 /// Attempts to extract a numeric value from a [`DecodedValue`].
 /// Returns `Some(f64)` if the input is numeric, or `None` otherwise.
@@ -54,15 +57,52 @@ pub fn apply_rational(block: &ConversionBlock, value: DecodedValue) -> Result<De
     }
 }
 
+/// Check that `formula` can be evaluated by [`apply_algebraic`]: it has to
+/// parse in the `meval` grammar and use no variable other than `X`.
+pub fn check_algebraic_formula(formula: &str) -> Result<(), MdfError> {
+    formula
+        .parse::<meval::Expr>()
+        .and_then(|expr| expr.bind("X").map(|_| ()))
+        .map_err(|e| {
+            MdfError::UnsupportedFeature(format!("unsupported algebraic formula '{}': {}", formula, e))
+        })
+}
+
+/// Distinct formulas kept per thread before the cache is cleared.
+const FORMULA_CACHE_LIMIT: usize = 1024;
+
+thread_local! {
+    /// Built-in constants and functions, cloned once per thread instead of
+    /// once per sample.
+    static BUILTINS: Context<'static> = Context::new();
+    /// Parsed formulas by source text; `None` for formulas that do not parse.
+    static FORMULAS: RefCell<HashMap<String, Option<Expr>>> = RefCell::new(HashMap::new());
+}
+
+/// Evaluate `formula` for `X = raw`, parsing it only on first use on this
+/// thread. `None` if the formula does not parse or evaluate.
+fn eval_formula(formula: &str, raw: f64) -> Option<f64> {
+    FORMULAS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if !cache.contains_key(formula) {
+            if cache.len() >= FORMULA_CACHE_LIMIT {
+                cache.clear();
+            }
+            cache.insert(formula.to_string(), formula.parse::<Expr>().ok());
+        }
+        let expr = cache.get(formula)?.as_ref()?;
+        BUILTINS.with(|builtins| expr.eval_with_context((("X", raw), builtins)).ok())
+    })
+}
+
 /// Apply an algebraic conversion using a stored formula.
+///
+/// The formula is parsed once per thread and cached, so decoding a channel
+/// does not re-parse it for every sample. Samples for which the formula
+/// cannot be evaluated keep their raw value.
 pub fn apply_algebraic(block: &ConversionBlock, value: DecodedValue) -> Result<DecodedValue, MdfError> {
     if let (Some(raw), Some(expr_str)) = (extract_numeric(&value), block.formula.as_ref()) {
-        let mut ctx = Context::new();
-        ctx.var("X", raw);
-        match eval_str_with_context(expr_str, ctx) {
-            Ok(res) => Ok(DecodedValue::Float(res)),
-            Err(_) => Ok(DecodedValue::Float(raw)),
-        }
+        Ok(DecodedValue::Float(eval_formula(expr_str, raw).unwrap_or(raw)))
     } else {
         Ok(value)
     }
@@ -6,6 +6,7 @@ pub mod linear;
 pub mod table_lookup;
 pub mod text;
 pub mod bitfield;
+pub mod simplify;
 
 pub use base::ConversionBlock;
 pub use types::ConversionType;
@@ -13,6 +14,7 @@ pub use linear::*;
 pub use table_lookup::*;
 pub use text::*;
 pub use bitfield::*;
+pub use simplify::{SimplifiedConversion, TableOutput};
 
 #[cfg(test)]
 mod test_deep_chains;
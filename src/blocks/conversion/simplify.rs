@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+
+use crate::blocks::common::BlockHeader;
+use crate::blocks::conversion::base::ConversionBlock;
+use crate::blocks::conversion::types::ConversionType;
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+use super::linear::extract_numeric;
+use super::table_lookup::lookup_table;
+
+/// `cc_flags` bit 1: the physical range (`cc_phy_range_min/max`) is valid.
+pub const CC_FLAG_PHYSICAL_RANGE_VALID: u16 = 0x02;
+
+/// What a text table yields for one of its entries.
+#[derive(Debug, Clone)]
+pub enum TableOutput {
+    Text(String),
+    /// A nested conversion applied to the raw value.
+    Conversion(SimplifiedConversion),
+    /// Neither text nor conversion: the sample decodes as
+    /// [`DecodedValue::Unknown`].
+    Unknown,
+}
+
+/// A conversion with its nested references pre-composed into a flat
+/// evaluator, built by [`from_block`](Self::from_block).
+///
+/// Evaluating it gives the same results as
+/// [`ConversionBlock::apply_decoded`] on the resolved block, but text tables
+/// become hash or range lookups with their texts and nested conversions
+/// already in place, so no referenced block is read or searched per
+/// sample. Numeric conversions can be chained with [`then`](Self::then),
+/// which folds linear and rational stages into one.
+#[derive(Debug, Clone)]
+pub enum SimplifiedConversion {
+    /// Values pass unchanged.
+    Identity,
+    /// `offset + factor * x`, as a float.
+    Linear { offset: f64, factor: f64 },
+    /// `(p1 * x² + p2 * x + p3) / (p4 * x² + p5 * x + p6)`, as a float; the
+    /// raw value where the denominator vanishes.
+    Rational([f64; 6]),
+    /// Value to text: exact keys (by their bits, `-0.0` folded into `0.0`)
+    /// and the output for any other value.
+    ValueToText {
+        entries: HashMap<u64, TableOutput>,
+        default: Box<TableOutput>,
+    },
+    /// Range to text: `[min, max]` ranges in file order (upper bound
+    /// exclusive for float values) and the output outside all of them.
+    RangeToText {
+        ranges: Vec<(f64, f64, TableOutput)>,
+        default: Box<TableOutput>,
+    },
+    /// Stages applied in order.
+    Chain(Vec<SimplifiedConversion>),
+    /// Any other conversion, evaluated by
+    /// [`apply_decoded`](ConversionBlock::apply_decoded) on the resolved
+    /// block.
+    Other(Box<ConversionBlock>),
+}
+
+impl SimplifiedConversion {
+    /// Simplify `block`, whose references have been resolved with
+    /// [`resolve_all_dependencies`](ConversionBlock::resolve_all_dependencies).
+    /// References left unresolved make text tables fall back to
+    /// [`Other`](Self::Other).
+    pub fn from_block(block: &ConversionBlock) -> Self {
+        let v = &block.cc_val;
+        match block.cc_type {
+            ConversionType::Identity => SimplifiedConversion::Identity,
+            ConversionType::Linear if v.len() >= 2 => SimplifiedConversion::Linear { offset: v[0], factor: v[1] },
+            ConversionType::Linear => SimplifiedConversion::Linear { offset: 0.0, factor: 1.0 },
+            ConversionType::Rational if v.len() >= 6 => {
+                // A rational with a constant unit denominator is linear, and
+                // evaluates to the same bits.
+                if v[0] == 0.0 && v[3] == 0.0 && v[4] == 0.0 && v[5] == 1.0 {
+                    SimplifiedConversion::Linear { offset: v[2], factor: v[1] }
+                } else {
+                    SimplifiedConversion::Rational([v[0], v[1], v[2], v[3], v[4], v[5]])
+                }
+            }
+            ConversionType::Rational => SimplifiedConversion::Linear { offset: 0.0, factor: 1.0 },
+            ConversionType::ValueToText if all_refs_resolved(block) => {
+                let mut entries = HashMap::with_capacity(v.len());
+                for (i, key) in v.iter().enumerate() {
+                    if !key.is_nan() {
+                        entries.entry(key_bits(*key)).or_insert_with(|| table_output(block, i));
+                    }
+                }
+                SimplifiedConversion::ValueToText {
+                    entries,
+                    default: Box::new(table_output(block, v.len())),
+                }
+            }
+            ConversionType::RangeToText if v.len() >= 2 && v.len().is_multiple_of(2) && all_refs_resolved(block) => {
+                let ranges = v
+                    .chunks_exact(2)
+                    .enumerate()
+                    .map(|(i, range)| (range[0], range[1], table_output(block, i)))
+                    .collect::<Vec<_>>();
+                let default = Box::new(table_output(block, ranges.len()));
+                SimplifiedConversion::RangeToText { ranges, default }
+            }
+            _ => SimplifiedConversion::Other(Box::new(block.clone())),
+        }
+    }
+
+    /// `true` if values pass unchanged.
+    pub fn is_identity(&self) -> bool {
+        match self {
+            SimplifiedConversion::Identity => true,
+            SimplifiedConversion::Chain(stages) => stages.iter().all(Self::is_identity),
+            _ => false,
+        }
+    }
+
+    /// Convert a decoded raw value to its physical value.
+    pub fn apply(&self, value: DecodedValue) -> Result<DecodedValue, MdfError> {
+        match self {
+            SimplifiedConversion::Identity => Ok(value),
+            SimplifiedConversion::Linear { offset, factor } => Ok(match extract_numeric(&value) {
+                Some(raw) => DecodedValue::Float(offset + factor * raw),
+                None => value,
+            }),
+            SimplifiedConversion::Rational(p) => Ok(match extract_numeric(&value) {
+                Some(raw) => DecodedValue::Float(eval_rational(p, raw).unwrap_or(raw)),
+                None => value,
+            }),
+            SimplifiedConversion::ValueToText { entries, default } => {
+                let Some(raw) = extract_numeric(&value) else { return Ok(value) };
+                entries.get(&key_bits(raw)).unwrap_or(default).apply(value)
+            }
+            SimplifiedConversion::RangeToText { ranges, default } => {
+                let Some(raw) = extract_numeric(&value) else { return Ok(value) };
+                let inclusive_upper = matches!(value, DecodedValue::UnsignedInteger(_) | DecodedValue::SignedInteger(_));
+                let output = ranges
+                    .iter()
+                    .find(|(min, max, _)| raw >= *min && if inclusive_upper { raw <= *max } else { raw < *max })
+                    .map_or(default.as_ref(), |(_, _, output)| output);
+                output.apply(value)
+            }
+            SimplifiedConversion::Chain(stages) => stages.iter().try_fold(value, |value, stage| stage.apply(value)),
+            SimplifiedConversion::Other(block) => block.apply_decoded(value, &[]),
+        }
+    }
+
+    /// This conversion followed by `outer`.
+    ///
+    /// Identity stages are dropped, and linear and rational stages are
+    /// folded into a single linear or rational conversion (up to rounding);
+    /// anything else becomes a [`Chain`](Self::Chain).
+    pub fn then(self, outer: SimplifiedConversion) -> Self {
+        use SimplifiedConversion::*;
+        match (self, outer) {
+            (inner, Identity) => inner,
+            (Identity, outer) => outer,
+            (Linear { offset: a1, factor: b1 }, Linear { offset: a2, factor: b2 }) => {
+                Linear { offset: a2 + b2 * a1, factor: b2 * b1 }
+            }
+            // a + b * N / D = (a * D + b * N) / D
+            (Rational(p), Linear { offset: a, factor: b }) => Rational([
+                b * p[0] + a * p[3],
+                b * p[1] + a * p[4],
+                b * p[2] + a * p[5],
+                p[3],
+                p[4],
+                p[5],
+            ]),
+            // Substitute x = a + b * t into both quadratics.
+            (Linear { offset: a, factor: b }, Rational(p)) => {
+                let substitute = |c2: f64, c1: f64, c0: f64| {
+                    [c2 * b * b, 2.0 * c2 * a * b + c1 * b, c2 * a * a + c1 * a + c0]
+                };
+                let [n2, n1, n0] = substitute(p[0], p[1], p[2]);
+                let [d2, d1, d0] = substitute(p[3], p[4], p[5]);
+                Rational([n2, n1, n0, d2, d1, d0])
+            }
+            (Chain(mut stages), outer) => match stages.pop() {
+                Some(last) => {
+                    let folded = last.then(outer);
+                    match folded {
+                        Chain(tail) => stages.extend(tail),
+                        folded => stages.push(folded),
+                    }
+                    if stages.len() == 1 { stages.pop().unwrap() } else { Chain(stages) }
+                }
+                None => outer,
+            },
+            (inner, Chain(outer)) => outer.into_iter().fold(inner, Self::then),
+            (inner, outer) => Chain(vec![inner, outer]),
+        }
+    }
+
+    /// The range of physical values for raw values in `[raw_min, raw_max]`,
+    /// as `(min, max)`.
+    ///
+    /// Text outputs do not count, and the default output of a table counts
+    /// over the whole raw range, so the result may be wider than the values
+    /// that actually occur. `None` if no numeric value can result or the
+    /// range cannot be bounded (algebraic formulas, a rational whose
+    /// denominator vanishes in the range).
+    pub fn physical_range(&self, raw_min: f64, raw_max: f64) -> Option<(f64, f64)> {
+        if raw_min.is_nan() || raw_max.is_nan() || raw_min > raw_max {
+            return None;
+        }
+        match self {
+            SimplifiedConversion::Identity => Some((raw_min, raw_max)),
+            SimplifiedConversion::Linear { offset, factor } => {
+                bounds([offset + factor * raw_min, offset + factor * raw_max])
+            }
+            SimplifiedConversion::Rational(p) => rational_range(p, raw_min, raw_max),
+            SimplifiedConversion::ValueToText { entries, default } => {
+                let mut range = default.physical_range(raw_min, raw_max);
+                for (key, output) in entries {
+                    let key = f64::from_bits(*key);
+                    if (raw_min..=raw_max).contains(&key) {
+                        range = union(range, output.physical_range(key, key));
+                    }
+                }
+                range
+            }
+            SimplifiedConversion::RangeToText { ranges, default } => {
+                let mut range = default.physical_range(raw_min, raw_max);
+                for (min, max, output) in ranges {
+                    let (min, max) = (min.max(raw_min), max.min(raw_max));
+                    if min <= max {
+                        range = union(range, output.physical_range(min, max));
+                    }
+                }
+                range
+            }
+            SimplifiedConversion::Chain(stages) => stages
+                .iter()
+                .try_fold((raw_min, raw_max), |(min, max), stage| stage.physical_range(min, max)),
+            SimplifiedConversion::Other(block) => other_range(block, raw_min, raw_max),
+        }
+    }
+
+    /// The conversion as a block of its own, for identity, linear and
+    /// rational conversions; `None` for the others.
+    pub fn to_block(&self) -> Option<ConversionBlock> {
+        let (cc_type, cc_val) = match self {
+            SimplifiedConversion::Identity => (ConversionType::Identity, Vec::new()),
+            SimplifiedConversion::Linear { offset, factor } => (ConversionType::Linear, vec![*offset, *factor]),
+            SimplifiedConversion::Rational(p) => (ConversionType::Rational, p.to_vec()),
+            _ => return None,
+        };
+        Some(ConversionBlock {
+            header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+            cc_tx_name: None,
+            cc_md_unit: None,
+            cc_md_comment: None,
+            cc_cc_inverse: None,
+            cc_ref: Vec::new(),
+            cc_type,
+            cc_precision: 0,
+            cc_flags: 0,
+            cc_ref_count: 0,
+            cc_val_count: cc_val.len() as u16,
+            cc_phy_range_min: None,
+            cc_phy_range_max: None,
+            cc_val,
+            formula: None,
+            resolved_texts: None,
+            resolved_conversions: None,
+            default_conversion: None,
+        })
+    }
+}
+
+impl TableOutput {
+    fn apply(&self, value: DecodedValue) -> Result<DecodedValue, MdfError> {
+        match self {
+            TableOutput::Text(text) => Ok(DecodedValue::String(text.clone())),
+            TableOutput::Conversion(conversion) => conversion.apply(value),
+            TableOutput::Unknown => Ok(DecodedValue::Unknown),
+        }
+    }
+
+    fn physical_range(&self, raw_min: f64, raw_max: f64) -> Option<(f64, f64)> {
+        match self {
+            TableOutput::Conversion(conversion) => conversion.physical_range(raw_min, raw_max),
+            TableOutput::Text(_) | TableOutput::Unknown => None,
+        }
+    }
+}
+
+impl ConversionBlock {
+    /// This conversion as a [`SimplifiedConversion`]; see
+    /// [`SimplifiedConversion::from_block`].
+    pub fn simplify(&self) -> SimplifiedConversion {
+        SimplifiedConversion::from_block(self)
+    }
+}
+
+/// `true` if every non-null reference of a text table was resolved to a
+/// text or a conversion.
+fn all_refs_resolved(block: &ConversionBlock) -> bool {
+    let default_index = block.default_conversion.as_ref().map(|_| block.cc_ref.len() - 1);
+    block.cc_ref.iter().enumerate().all(|(i, &link)| {
+        link == 0
+            || Some(i) == default_index
+            || block.get_resolved_text(i).is_some()
+            || block.get_resolved_conversion(i).is_some()
+    })
+}
+
+/// Output of reference `index` of a value/range to text table, with the
+/// fallbacks of `apply_value_to_text` / `apply_range_to_text`.
+fn table_output(block: &ConversionBlock, index: usize) -> TableOutput {
+    if let Some(text) = block.get_resolved_text(index) {
+        TableOutput::Text(text.clone())
+    } else if let Some(nested) = block.get_resolved_conversion(index) {
+        TableOutput::Conversion(SimplifiedConversion::from_block(nested))
+    } else if let Some(default) = block.get_default_conversion() {
+        TableOutput::Conversion(SimplifiedConversion::from_block(default))
+    } else {
+        TableOutput::Unknown
+    }
+}
+
+/// Hash key of a table key; equal floats get equal keys.
+fn key_bits(key: f64) -> u64 {
+    (key + 0.0).to_bits()
+}
+
+fn eval_rational(p: &[f64; 6], x: f64) -> Option<f64> {
+    let num = p[0] * x * x + p[1] * x + p[2];
+    let den = p[3] * x * x + p[4] * x + p[5];
+    (den.abs() > f64::EPSILON).then(|| num / den)
+}
+
+fn bounds(values: impl IntoIterator<Item = f64>) -> Option<(f64, f64)> {
+    values.into_iter().filter(|v| !v.is_nan()).fold(None, |range, v| union(range, Some((v, v))))
+}
+
+fn union(a: Option<(f64, f64)>, b: Option<(f64, f64)>) -> Option<(f64, f64)> {
+    match (a, b) {
+        (Some((a0, a1)), Some((b0, b1))) => Some((a0.min(b0), a1.max(b1))),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Real roots of `a * x² + b * x + c` (of `b * x + c` when `a` is 0).
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return if b == 0.0 { Vec::new() } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    let sqrt = discriminant.sqrt();
+    vec![(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+}
+
+/// Range of a rational function over `[min, max]`: its values at the ends
+/// and at the zeros of its derivative, unless the denominator vanishes.
+fn rational_range(p: &[f64; 6], min: f64, max: f64) -> Option<(f64, f64)> {
+    let inside = |x: &f64| (min..=max).contains(x);
+    let (d2, d1, d0) = (p[3], p[4], p[5]);
+    if d2 == 0.0 && d1 == 0.0 && d0 == 0.0 {
+        return None;
+    }
+    if quadratic_roots(d2, d1, d0).iter().any(inside) {
+        return None;
+    }
+    // Numerator of the derivative, N'D - ND'.
+    let (n2, n1, n0) = (p[0], p[1], p[2]);
+    let critical = quadratic_roots(n2 * d1 - n1 * d2, 2.0 * (n2 * d0 - n0 * d2), n1 * d0 - n0 * d1);
+    let points = [min, max].into_iter().chain(critical.into_iter().filter(inside));
+    let mut values = Vec::new();
+    for x in points {
+        values.push(eval_rational(p, x)?);
+    }
+    bounds(values)
+}
+
+/// Range of the table based conversions kept as blocks.
+fn other_range(block: &ConversionBlock, min: f64, max: f64) -> Option<(f64, f64)> {
+    let v = &block.cc_val;
+    match block.cc_type {
+        ConversionType::TableLookupInterp | ConversionType::TableLookupNoInterp => {
+            let interp = block.cc_type == ConversionType::TableLookupInterp;
+            let ends = [min, max].into_iter().filter_map(|x| lookup_table(v, x, interp));
+            let inner = v.chunks_exact(2).filter(|kv| kv[0] > min && kv[0] < max).map(|kv| kv[1]);
+            bounds(ends.chain(inner))
+        }
+        ConversionType::RangeLookup if v.len() >= 4 && (v.len() - 1).is_multiple_of(3) => {
+            let n = (v.len() - 1) / 3;
+            let hit = v[..3 * n].chunks_exact(3).filter(|r| r[0] <= max && r[1] >= min).map(|r| r[2]);
+            // The default applies unless the ranges are known to cover the
+            // whole raw range; keep it, as a bound that may be loose.
+            bounds(hit.chain([v[3 * n]]))
+        }
+        _ => None,
+    }
+}
@@ -3,6 +3,7 @@ mod tests {
     use crate::blocks::conversion::base::ConversionBlock;
     use crate::blocks::conversion::types::ConversionType;
     use crate::blocks::common::{BlockHeader, BlockParse};
+    use crate::parsing::decoder::DecodedValue;
 
     #[test]
     fn test_deep_conversion_chain_resolution() {
@@ -211,6 +212,39 @@ mod tests {
                 "Should have resolved either texts or default conversion");
     }
 
+    #[test]
+    fn test_bitfield_text_applies_without_file_data() {
+        // BitfieldText at 0 with two named ValueToText conversions.
+        let mut file_data = create_test_conversion_block(
+            ConversionType::BitfieldText,
+            vec![f64::from_bits(0b001), f64::from_bits(0b110)],
+            vec![100, 300],
+        );
+        let nested = [
+            (100, 500, vec![1.0], vec![600, 700]),
+            (300, 800, vec![2.0, 4.0], vec![900, 1000, 1100]),
+        ];
+        for (offset, name, cc_val, cc_ref) in nested {
+            file_data.resize(offset, 0);
+            let mut block = create_test_conversion_block(ConversionType::ValueToText, cc_val, cc_ref);
+            block[24..32].copy_from_slice(&(name as u64).to_le_bytes());
+            file_data.extend_from_slice(&block);
+        }
+        let texts = [(500, "Lamp"), (600, "On"), (700, "Off"), (800, "Mode"), (900, "Eco"), (1000, "Sport"), (1100, "-")];
+        for (offset, text) in texts {
+            file_data.resize(offset, 0);
+            file_data.extend_from_slice(&create_test_text_block(text));
+        }
+
+        let mut conv = ConversionBlock::from_bytes(&file_data).unwrap();
+        conv.resolve_all_dependencies(&file_data).unwrap();
+        // Resolved conversions carry everything, as in an index read remotely.
+        let value = conv.apply_decoded(DecodedValue::UnsignedInteger(0b101), &[]).unwrap();
+        assert_eq!(value, DecodedValue::String("Lamp = On|Mode = Sport".into()));
+        let value = conv.apply_decoded(DecodedValue::UnsignedInteger(0b010), &[]).unwrap();
+        assert_eq!(value, DecodedValue::String("Lamp = Off|Mode = Eco".into()));
+    }
+
     // Helper function to create a test conversion block
     fn create_test_conversion_block(
         conv_type: ConversionType, 
@@ -1,5 +1,5 @@
 use crate::blocks::conversion::base::ConversionBlock;
-use crate::blocks::common::{BlockHeader, read_string_block, BlockParse};
+use crate::blocks::common::{BlockHeader, read_string_block};
 use crate::error::MdfError;
 use crate::parsing::decoder::DecodedValue;
 use super::linear::extract_numeric;
@@ -54,7 +54,7 @@ pub fn apply_value_to_text(block: &ConversionBlock, value: DecodedValue, file_da
     }
     
     let off = link as usize;
-    if off + 24 > file_data.len() { 
+    if off.saturating_add(24) > file_data.len() { 
         // Try default conversion if link is invalid
         if let Some(default_conversion) = block.get_default_conversion() {
             return default_conversion.apply_decoded(value, &[]);
@@ -74,9 +74,7 @@ pub fn apply_value_to_text(block: &ConversionBlock, value: DecodedValue, file_da
         return Ok(DecodedValue::Unknown);
     }
     if hdr.id == "##CC" {
-        let mut nested = ConversionBlock::from_bytes(&file_data[off..])?;
-        let _ = nested.resolve_formula(file_data);
-        return nested.apply_decoded(value, file_data);
+        return ConversionBlock::resolve_nested(file_data, link)?.apply_decoded(value, &[]);
     }
     
     // Try default conversion for unrecognized block types
@@ -120,7 +118,7 @@ pub fn apply_range_to_text(block: &ConversionBlock, value: DecodedValue, file_da
     }
     
     let off = link as usize;
-    if off + 24 > file_data.len() {
+    if off.saturating_add(24) > file_data.len() {
         // Try default conversion if link is invalid
         if let Some(default_conversion) = block.get_default_conversion() {
             return default_conversion.apply_decoded(value, &[]);
@@ -143,9 +141,7 @@ pub fn apply_range_to_text(block: &ConversionBlock, value: DecodedValue, file_da
         };
     }
     if hdr.id == "##CC" {
-        let mut nested = ConversionBlock::from_bytes(&file_data[off..])?;
-        let _ = nested.resolve_formula(file_data);
-        return nested.apply_decoded(value, file_data);
+        return ConversionBlock::resolve_nested(file_data, link)?.apply_decoded(value, &[]);
     }
     
     // Try default conversion for unrecognized block types
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::blocks::common::BlockHeader;
 use crate::blocks::common::BlockParse;
 use crate::error::MdfError;
@@ -5,7 +7,9 @@ use crate::error::MdfError;
 #[derive(Debug)]
 pub struct DataBlock<'a> {
     pub header: BlockHeader,
-    pub data: &'a [u8],
+    /// The data section: borrowed from the file, or owned when it was
+    /// inflated from a `##DZ` block or read from a file that is not mapped.
+    pub data: Cow<'a, [u8]>,
 }
 
 impl<'a> BlockParse<'a> for DataBlock<'a> {
@@ -29,7 +33,7 @@ impl<'a> BlockParse<'a> for DataBlock<'a> {
                 line:     line!(),
             });
         }
-        let data = &bytes[24..24 + data_len];
+        let data = Cow::Borrowed(&bytes[24..24 + data_len]);
         Ok(Self { header, data })
     }
 }
@@ -42,7 +46,7 @@ impl<'a> DataBlock<'a> {
     ///
     /// # Returns
     /// An iterator yielding each raw record slice.
-    pub fn records(&self, record_size: usize) -> impl Iterator<Item = &'a [u8]> {
+    pub fn records(&self, record_size: usize) -> impl Iterator<Item = &[u8]> {
         self.data.chunks_exact(record_size)
     }
 }
@@ -25,7 +25,12 @@ impl BlockParse<'_> for DataListBlock {
 
         let header = Self::parse_header(bytes)?;
         
-        let min_len = 24 + (header.links_nr as usize * 8) + 1 + 3 + 4;
+        if header.links_nr < 1 {
+            return Err(MdfError::BlockLinkError(
+                "##DL block needs at least the next link".to_string(),
+            ));
+        }
+        let min_len = (header.links_nr as usize).saturating_mul(8).saturating_add(24 + 1 + 3 + 4);
         if bytes.len() < min_len {
             return Err(MdfError::TooShortBuffer {
                 actual: bytes.len(),
@@ -64,14 +69,15 @@ impl BlockParse<'_> for DataListBlock {
             let len = u64::from_le_bytes(bytes[off..off+8].try_into().unwrap());
             (Some(len), None)
         } else {
-            let mut offs = Vec::with_capacity(data_block_nr as usize);
-            if bytes.len() < off + (data_block_nr as usize * 8) {
+            let expected = off.saturating_add((data_block_nr as usize).saturating_mul(8));
+            if bytes.len() < expected {
                 return Err(MdfError::TooShortBuffer {
                     actual: bytes.len(),
-                    expected: off + data_block_nr as usize * 8,
+                    expected,
                     file: file!(), line: line!(),
                 });
             }
+            let mut offs = Vec::with_capacity(data_block_nr as usize);
             for _ in 0..data_block_nr {
                 let o = u64::from_le_bytes(bytes[off..off+8].try_into().unwrap());
                 offs.push(o);
@@ -0,0 +1,206 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::common::BlockHeader;
+use crate::blocks::common::BlockParse;
+use crate::error::MdfError;
+
+/// `dz_zip_type` value for plain deflate.
+pub const ZIP_TYPE_DEFLATE: u8 = 0;
+/// `dz_zip_type` value for byte transposition followed by deflate.
+pub const ZIP_TYPE_TRANSPOSE_DEFLATE: u8 = 1;
+
+/// DZBLOCK: Data Zipped Block, a compressed replacement for a `##DT`,
+/// `##SD` or other data block.
+///
+/// - Data:
+///   • dz_org_block_type  CHAR[2] (block type without "##", e.g. "DT")
+///   • dz_zip_type        UINT8   (0 = deflate, 1 = transposition + deflate)
+///   • dz_reserved        BYTE
+///   • dz_zip_parameter   UINT32  (column count for transposition, else 0)
+///   • dz_org_data_length UINT64
+///   • dz_data_length     UINT64
+///   • dz_data            BYTE[dz_data_length] (zlib stream)
+#[derive(Debug, Clone)]
+pub struct DataZippedBlock {
+    pub header: BlockHeader,
+    pub org_block_type: String,
+    pub zip_type: u8,
+    pub zip_parameter: u32,
+    pub org_data_length: u64,
+    pub data_length: u64,
+    pub data: Vec<u8>,
+}
+
+impl BlockParse<'_> for DataZippedBlock {
+    const ID: &'static str = "##DZ";
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header = Self::parse_header(bytes)?;
+
+        let expected_bytes = 48;
+        if bytes.len() < expected_bytes {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: expected_bytes,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+        let data_length = LittleEndian::read_u64(&bytes[40..48]);
+        let end = (data_length as usize).saturating_add(48);
+        if bytes.len() < end {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: end,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+
+        Ok(DataZippedBlock {
+            header,
+            org_block_type: String::from_utf8_lossy(&bytes[24..26]).into_owned(),
+            zip_type: bytes[26],
+            zip_parameter: LittleEndian::read_u32(&bytes[28..32]),
+            org_data_length: LittleEndian::read_u64(&bytes[32..40]),
+            data_length,
+            data: bytes[48..end].to_vec(),
+        })
+    }
+}
+
+impl DataZippedBlock {
+    /// The `dz_org_data_length` of the `##DZ` block at the start of `bytes`,
+    /// read without copying its compressed data.
+    pub fn read_org_data_length(bytes: &[u8]) -> Result<u64, MdfError> {
+        Self::parse_header(bytes)?;
+        if bytes.len() < 40 {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: 40,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+        Ok(LittleEndian::read_u64(&bytes[32..40]))
+    }
+
+    /// Compress the data section `org` of a block of type `org_block_type`
+    /// (e.g. `"DT"`).
+    ///
+    /// With [`ZIP_TYPE_TRANSPOSE_DEFLATE`], `org` is treated as rows of
+    /// `columns` bytes (one record each) and stored column by column before
+    /// deflating, which groups equal bytes of slowly changing signals; bytes
+    /// past the last full row are appended untransposed.
+    pub fn compress(org_block_type: &str, org: &[u8], zip_type: u8, columns: u32) -> Result<Self, MdfError> {
+        if org_block_type.len() != 2 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "DZ original block type must be 2 characters, got '{}'",
+                org_block_type
+            )));
+        }
+        let (zip_parameter, data) = match zip_type {
+            ZIP_TYPE_DEFLATE => (0, miniz_oxide::deflate::compress_to_vec_zlib(org, 6)),
+            ZIP_TYPE_TRANSPOSE_DEFLATE if columns > 0 => {
+                let transposed = transpose(org, columns as usize);
+                (columns, miniz_oxide::deflate::compress_to_vec_zlib(&transposed, 6))
+            }
+            _ => {
+                return Err(MdfError::UnsupportedFeature(format!(
+                    "unsupported DZ zip type {} with parameter {}",
+                    zip_type, columns
+                )));
+            }
+        };
+        Ok(DataZippedBlock {
+            header: BlockHeader {
+                id: String::from("##DZ"),
+                reserved0: 0,
+                block_len: 48 + data.len() as u64,
+                links_nr: 0,
+            },
+            org_block_type: org_block_type.to_string(),
+            zip_type,
+            zip_parameter,
+            org_data_length: org.len() as u64,
+            data_length: data.len() as u64,
+            data,
+        })
+    }
+
+    /// Serializes the DataZippedBlock (48-byte fixed part plus zlib stream).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        if self.header.id != "##DZ" || self.org_block_type.len() != 2 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "DataZippedBlock must have ID '##DZ' and a 2-character original type, found '{}' / '{}'",
+                self.header.id, self.org_block_type
+            )));
+        }
+        if self.header.block_len != 48 + self.data.len() as u64 || self.data_length != self.data.len() as u64 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "DataZippedBlock lengths (block_len {}, data_length {}) do not match {} data bytes",
+                self.header.block_len, self.data_length, self.data.len()
+            )));
+        }
+        let mut buffer = Vec::with_capacity(self.header.block_len as usize);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(self.org_block_type.as_bytes());
+        buffer.push(self.zip_type);
+        buffer.push(0);
+        buffer.extend_from_slice(&self.zip_parameter.to_le_bytes());
+        buffer.extend_from_slice(&self.org_data_length.to_le_bytes());
+        buffer.extend_from_slice(&self.data_length.to_le_bytes());
+        buffer.extend_from_slice(&self.data);
+        Ok(buffer)
+    }
+
+    /// Inflate the zlib stream and undo any transposition, returning the
+    /// data section of the original block.
+    pub fn decompress(&self) -> Result<Vec<u8>, MdfError> {
+        let inflated = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+            &self.data,
+            self.org_data_length as usize,
+        )
+        .map_err(|e| MdfError::BlockSerializationError(format!("DZ block inflate failed: {e}")))?;
+        if inflated.len() as u64 != self.org_data_length {
+            return Err(MdfError::BlockSerializationError(format!(
+                "DZ block inflated to {} bytes, expected {}",
+                inflated.len(),
+                self.org_data_length
+            )));
+        }
+        match (self.zip_type, self.zip_parameter) {
+            (ZIP_TYPE_DEFLATE, _) => Ok(inflated),
+            (ZIP_TYPE_TRANSPOSE_DEFLATE, columns) if columns > 0 => {
+                Ok(untranspose(&inflated, columns as usize))
+            }
+            (zip_type, parameter) => Err(MdfError::UnsupportedFeature(format!(
+                "unsupported DZ zip type {} with parameter {}",
+                zip_type, parameter
+            ))),
+        }
+    }
+}
+
+/// Store the full `columns`-byte rows of `data` column by column, followed
+/// by the trailing bytes of an incomplete row.
+fn transpose(data: &[u8], columns: usize) -> Vec<u8> {
+    let rows = data.len() / columns;
+    let mut out = Vec::with_capacity(data.len());
+    for c in 0..columns {
+        out.extend((0..rows).map(|r| data[r * columns + c]));
+    }
+    out.extend_from_slice(&data[rows * columns..]);
+    out
+}
+
+/// Inverse of [`transpose`].
+fn untranspose(data: &[u8], columns: usize) -> Vec<u8> {
+    let rows = data.len() / columns;
+    let mut out = vec![0u8; data.len()];
+    for c in 0..columns {
+        for r in 0..rows {
+            out[r * columns + c] = data[c * rows + r];
+        }
+    }
+    out[rows * columns..].copy_from_slice(&data[rows * columns..]);
+    out
+}
@@ -0,0 +1,253 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::common::BlockHeader;
+use crate::blocks::common::BlockParse;
+use crate::error::MdfError;
+
+/// `ev_type` of an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    Recording,
+    RecordingInterrupt,
+    AcquisitionInterrupt,
+    StartRecordingTrigger,
+    StopRecordingTrigger,
+    Trigger,
+    Marker,
+    Unknown(u8),
+}
+
+impl EventType {
+    /// Map an `ev_type` byte to the enum.
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => EventType::Recording,
+            1 => EventType::RecordingInterrupt,
+            2 => EventType::AcquisitionInterrupt,
+            3 => EventType::StartRecordingTrigger,
+            4 => EventType::StopRecordingTrigger,
+            5 => EventType::Trigger,
+            6 => EventType::Marker,
+            other => EventType::Unknown(other),
+        }
+    }
+
+    /// The `ev_type` byte stored in the block.
+    pub fn to_u8(self) -> u8 {
+        match self {
+            EventType::Recording => 0,
+            EventType::RecordingInterrupt => 1,
+            EventType::AcquisitionInterrupt => 2,
+            EventType::StartRecordingTrigger => 3,
+            EventType::StopRecordingTrigger => 4,
+            EventType::Trigger => 5,
+            EventType::Marker => 6,
+            EventType::Unknown(other) => other,
+        }
+    }
+}
+
+/// Represents an EVBLOCK (“##EV”) from the MDF4 file.
+///
+/// - Links:
+///   • ev_ev_next        LINK → next EVBLOCK
+///   • ev_ev_parent      LINK → parent EVBLOCK
+///   • ev_ev_range       LINK → EVBLOCK opening the range this event closes
+///   • ev_tx_name        LINK → TXBLOCK (event name)
+///   • ev_md_comment     LINK → TXBLOCK/MDBLOCK (comment)
+///   • ev_scope          LINK[ev_scope_count] → CGBLOCK/CNBLOCK
+///   • ev_at_reference   LINK[ev_attachment_count] → ATBLOCK
+/// - Data:
+///   • ev_type             UINT8 (0=recording, …, 5=trigger, 6=marker)
+///   • ev_sync_type        UINT8 (1=time, 2=angle, 3=distance, 4=index)
+///   • ev_range_type       UINT8 (0=point, 1=range begin, 2=range end)
+///   • ev_cause            UINT8 (0=OTHER, 1=ERROR, 2=TOOL, 3=SCRIPT, 4=USER)
+///   • ev_flags            UINT8 (bit 0 = post processing)
+///   • ev_reserved         BYTE[3]
+///   • ev_scope_count      UINT32
+///   • ev_attachment_count UINT16
+///   • ev_creator_index    UINT16
+///   • ev_sync_base_value  INT64
+///   • ev_sync_factor      REAL (sync value = base value × factor)
+#[derive(Debug, Clone)]
+pub struct EventBlock {
+    pub header:           BlockHeader,
+    pub next_ev_addr:     u64,
+    pub parent_ev_addr:   u64,
+    pub range_ev_addr:    u64,
+    pub name_addr:        u64,
+    pub comment_addr:     u64,
+    pub scope_addrs:      Vec<u64>,
+    pub attachment_addrs: Vec<u64>,
+
+    pub event_type:       EventType,
+    pub sync_type:        u8,
+    pub range_type:       u8,
+    pub cause:            u8,
+    pub flags:            u8,
+    pub creator_index:    u16,
+    pub sync_base_value:  i64,
+    pub sync_factor:      f64,
+}
+
+impl BlockParse<'_> for EventBlock {
+    const ID: &'static str = "##EV";
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header = Self::parse_header(bytes)?;
+
+        if header.links_nr < 5 {
+            return Err(MdfError::BlockLinkError(format!(
+                "##EV block needs at least 5 links, found {}",
+                header.links_nr
+            )));
+        }
+        let links_end = (header.links_nr as usize).saturating_mul(8).saturating_add(24);
+        let expected_bytes = links_end.saturating_add(32);
+        if bytes.len() < expected_bytes {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: expected_bytes,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+        let link = |i: usize| LittleEndian::read_u64(&bytes[24 + i * 8..32 + i * 8]);
+        let data = &bytes[links_end..];
+        let scope_count = LittleEndian::read_u32(&data[8..12]) as usize;
+        let attachment_count = LittleEndian::read_u16(&data[12..14]) as usize;
+        if 5 + scope_count as u64 + attachment_count as u64 > header.links_nr {
+            return Err(MdfError::BlockLinkError(format!(
+                "##EV block declares {} scope and {} attachment links but has only {} links",
+                scope_count, attachment_count, header.links_nr
+            )));
+        }
+
+        Ok(EventBlock {
+            next_ev_addr:     link(0),
+            parent_ev_addr:   link(1),
+            range_ev_addr:    link(2),
+            name_addr:        link(3),
+            comment_addr:     link(4),
+            scope_addrs:      (5..5 + scope_count).map(link).collect(),
+            attachment_addrs: (5 + scope_count..5 + scope_count + attachment_count).map(link).collect(),
+            event_type:       EventType::from_u8(data[0]),
+            sync_type:        data[1],
+            range_type:       data[2],
+            cause:            data[3],
+            flags:            data[4],
+            creator_index:    LittleEndian::read_u16(&data[14..16]),
+            sync_base_value:  LittleEndian::read_i64(&data[16..24]),
+            sync_factor:      LittleEndian::read_f64(&data[24..32]),
+            header,
+        })
+    }
+}
+
+impl EventBlock {
+    /// The event's synchronization value (`ev_sync_base_value × ev_sync_factor`),
+    /// in seconds for time-synchronized events.
+    pub fn sync_value(&self) -> f64 {
+        self.sync_base_value as f64 * self.sync_factor
+    }
+
+    /// Serializes the EventBlock; `header.links_nr` and `header.block_len`
+    /// must match the scope and attachment link counts.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        let links_nr = 5 + self.scope_addrs.len() + self.attachment_addrs.len();
+        let block_len = 24 + links_nr as u64 * 8 + 32;
+        if self.header.id != "##EV"
+            || self.header.links_nr as usize != links_nr
+            || self.header.block_len != block_len
+        {
+            return Err(MdfError::BlockSerializationError(format!(
+                "EventBlock must have ID '##EV', {} links and block_len={}, found '{}' / {} / {}",
+                links_nr, block_len, self.header.id, self.header.links_nr, self.header.block_len
+            )));
+        }
+        let mut buffer = Vec::with_capacity(block_len as usize);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        for link in [
+            self.next_ev_addr,
+            self.parent_ev_addr,
+            self.range_ev_addr,
+            self.name_addr,
+            self.comment_addr,
+        ]
+        .iter()
+        .chain(&self.scope_addrs)
+        .chain(&self.attachment_addrs)
+        {
+            buffer.extend_from_slice(&link.to_le_bytes());
+        }
+        buffer.push(self.event_type.to_u8());
+        buffer.push(self.sync_type);
+        buffer.push(self.range_type);
+        buffer.push(self.cause);
+        buffer.push(self.flags);
+        buffer.extend_from_slice(&[0u8; 3]);
+        buffer.extend_from_slice(&(self.scope_addrs.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&(self.attachment_addrs.len() as u16).to_le_bytes());
+        buffer.extend_from_slice(&self.creator_index.to_le_bytes());
+        buffer.extend_from_slice(&self.sync_base_value.to_le_bytes());
+        buffer.extend_from_slice(&self.sync_factor.to_le_bytes());
+        Ok(buffer)
+    }
+}
+
+impl Default for EventBlock {
+    fn default() -> Self {
+        EventBlock {
+            header: BlockHeader {
+                id: String::from("##EV"),
+                reserved0: 0,
+                block_len: 24 + 5 * 8 + 32,
+                links_nr: 5,
+            },
+            next_ev_addr: 0,
+            parent_ev_addr: 0,
+            range_ev_addr: 0,
+            name_addr: 0,
+            comment_addr: 0,
+            scope_addrs: Vec::new(),
+            attachment_addrs: Vec::new(),
+            event_type: EventType::Marker,
+            sync_type: 1,
+            range_type: 0,
+            cause: 0,
+            flags: 0,
+            creator_index: 0,
+            sync_base_value: 0,
+            sync_factor: 1.0,
+        }
+    }
+}
+
+/// Read the chain of [`EVBLOCK`](EventBlock)s starting at `address`.
+///
+/// # Arguments
+/// * `mmap` - The entire MDF file mapped into memory.
+/// * `address` - File offset of the first `##EV` block (0 if none).
+///
+/// # Returns
+/// The events in link order paired with their file offsets, or an
+/// [`MdfError`] if a block could not be parsed.
+pub fn read_event_blocks(mmap: &[u8], mut address: u64) -> Result<Vec<(u64, EventBlock)>, MdfError> {
+    let mut blocks = Vec::new();
+    while address != 0 {
+        let start = address as usize;
+        if start.saturating_add(24) > mmap.len() {
+            return Err(MdfError::BlockLinkError(format!(
+                "event link {address:#x} points past the end of the file"
+            )));
+        }
+        let block = EventBlock::from_bytes(&mmap[start..])?;
+        let next = block.next_ev_addr;
+        blocks.push((address, block));
+        if blocks.iter().any(|(a, _)| *a == next) {
+            return Err(MdfError::BlockLinkError(format!(
+                "event chain loops back to {next:#x}"
+            )));
+        }
+        address = next;
+    }
+    Ok(blocks)
+}
@@ -0,0 +1,94 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::common::BlockHeader;
+use crate::blocks::common::BlockParse;
+use crate::error::MdfError;
+
+/// Represents an FHBLOCK (“##FH”): one entry of the file's change history.
+///
+/// - Links:
+///   • fh_fh_next    LINK → next FHBLOCK
+///   • fh_md_comment LINK → MDBLOCK (`<FHcomment>` naming the writing tool)
+/// - Data:
+///   • fh_time_ns    UINT64 (time of the change, ns since the Unix epoch)
+///   • fh_tz_offset_min  INT16
+///   • fh_dst_offset_min INT16
+///   • fh_time_flags UINT8
+///   • fh_reserved   BYTE[3]
+#[derive(Debug, Clone)]
+pub struct FileHistoryBlock {
+    pub header:             BlockHeader,
+    pub next_fh_addr:       u64,
+    pub comment_addr:       u64,
+    pub time_ns:            u64,
+    pub tz_offset:          i16,
+    pub daylight_save_time: i16,
+    pub time_flags:         u8,
+}
+
+impl BlockParse<'_> for FileHistoryBlock {
+    const ID: &'static str = "##FH";
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header = Self::parse_header(bytes)?;
+
+        let expected_bytes = 56;
+        if bytes.len() < expected_bytes {
+            return Err(MdfError::TooShortBuffer {
+                actual:   bytes.len(),
+                expected: expected_bytes,
+                file:     file!(),
+                line:     line!(),
+            });
+        }
+
+        Ok(FileHistoryBlock {
+            header,
+            next_fh_addr:       LittleEndian::read_u64(&bytes[24..32]),
+            comment_addr:       LittleEndian::read_u64(&bytes[32..40]),
+            time_ns:            LittleEndian::read_u64(&bytes[40..48]),
+            tz_offset:          LittleEndian::read_i16(&bytes[48..50]),
+            daylight_save_time: LittleEndian::read_i16(&bytes[50..52]),
+            time_flags:         bytes[52],
+        })
+    }
+}
+
+impl FileHistoryBlock {
+    /// Serializes the FileHistoryBlock (56 bytes).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        if self.header.id != "##FH" || self.header.block_len != 56 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "FileHistoryBlock must have ID '##FH' and block_len=56, found '{}' / {}",
+                self.header.id, self.header.block_len
+            )));
+        }
+        let mut buffer = Vec::with_capacity(56);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(&self.next_fh_addr.to_le_bytes());
+        buffer.extend_from_slice(&self.comment_addr.to_le_bytes());
+        buffer.extend_from_slice(&self.time_ns.to_le_bytes());
+        buffer.extend_from_slice(&self.tz_offset.to_le_bytes());
+        buffer.extend_from_slice(&self.daylight_save_time.to_le_bytes());
+        buffer.push(self.time_flags);
+        buffer.extend_from_slice(&[0u8; 3]);
+        Ok(buffer)
+    }
+}
+
+impl Default for FileHistoryBlock {
+    fn default() -> Self {
+        FileHistoryBlock {
+            header: BlockHeader {
+                id: String::from("##FH"),
+                reserved0: 0,
+                block_len: 56,
+                links_nr: 2,
+            },
+            next_fh_addr: 0,
+            comment_addr: 0,
+            time_ns: 0,
+            tz_offset: 0,
+            daylight_save_time: 0,
+            time_flags: 0,
+        }
+    }
+}
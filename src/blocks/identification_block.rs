@@ -135,13 +135,13 @@ impl IdentificationBlock {
             });
         }
 
-        let file_identifier = str::from_utf8(&bytes[0..8]).unwrap().to_string();
+        let file_identifier = String::from_utf8_lossy(&bytes[0..8]).into_owned();
         if file_identifier != "MDF     " {
             return Err(MdfError::FileIdentifierError(file_identifier));
         }
 
         let (major, minor) = Self::parse_block_version(&bytes[8..16])?;
-        let version_u16 = major * 100 + minor;
+        let version_u16 = major.saturating_mul(100).saturating_add(minor);
 
         if version_u16 < 410 {
             return Err(MdfError::FileVersioningError(version_u16.to_string()));
@@ -149,8 +149,8 @@ impl IdentificationBlock {
 
         Ok(Self {
             file_identifier: file_identifier,
-            version_identifier: String::from(str::from_utf8(&bytes[8..16]).unwrap()),
-            program_identifier: String::from(str::from_utf8(&bytes[16..24]).unwrap()),
+            version_identifier: String::from_utf8_lossy(&bytes[8..16]).into_owned(),
+            program_identifier: String::from_utf8_lossy(&bytes[16..24]).into_owned(),
             // Reserved bytes between 24 and 28 are skipped
             // The version number immediately follows at bytes 28..30
             version_number: LittleEndian::read_u16(&bytes[28..30]),
@@ -33,3 +33,42 @@ impl BlockParse<'_> for MetadataBlock {
         Ok(Self { header, xml })
     }
 }
+
+impl MetadataBlock {
+    /// Creates a new MetadataBlock holding `xml`. The block length covers the
+    /// header, the zero-terminated XML and padding to an 8-byte boundary.
+    pub fn new(xml: &str) -> Self {
+        let unpadded_size = 24 + xml.len() + 1;
+        let block_len = unpadded_size + (8 - (unpadded_size % 8)) % 8;
+        MetadataBlock {
+            header: BlockHeader {
+                id: String::from("##MD"),
+                reserved0: 0,
+                block_len: block_len as u64,
+                links_nr: 0,
+            },
+            xml: xml.to_string(),
+        }
+    }
+
+    /// Serializes the MetadataBlock: header, zero-terminated XML, zero padding.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        if self.header.id != "##MD" {
+            return Err(MdfError::BlockSerializationError(
+                format!("MetadataBlock must have ID '##MD', found '{}'", self.header.id)
+            ));
+        }
+        let total_size = self.header.block_len as usize;
+        if total_size < 24 + self.xml.len() + 1 || !total_size.is_multiple_of(8) {
+            return Err(MdfError::BlockSerializationError(
+                format!("MetadataBlock header.block_len ({}) cannot hold {} XML bytes",
+                    total_size, self.xml.len())
+            ));
+        }
+        let mut buffer = Vec::with_capacity(total_size);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(self.xml.as_bytes());
+        buffer.resize(total_size, 0);
+        Ok(buffer)
+    }
+}
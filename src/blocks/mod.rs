@@ -11,4 +11,11 @@ pub mod conversion;
 pub mod metadata_block;
 pub mod source_block;
 pub mod data_list_block;
+pub mod data_zipped_block;
 pub mod signal_data_block;
+pub mod attachment_block;
+pub mod file_history_block;
+pub mod event_block;
+pub mod channel_array_block;
+pub mod walk;
+pub use walk::walk;
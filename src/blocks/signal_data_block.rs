@@ -16,7 +16,7 @@ impl<'a> BlockParse<'a> for SignalDataBlock<'a> {
         // 1) Parse the common 24-byte block header
         let header = Self::parse_header(bytes)?;
         // 2) Ensure we have the full SDBLOCK on‐disk
-        let expected_bytes = header.block_len as usize;
+        let expected_bytes = (header.block_len as usize).max(24);
         if bytes.len() < expected_bytes {
             return Err(MdfError::TooShortBuffer {
                 actual:   bytes.len(),
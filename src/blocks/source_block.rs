@@ -1,5 +1,5 @@
 use byteorder::{ByteOrder, LittleEndian};
-use crate::blocks::common::BlockHeader;
+use crate::blocks::common::{bytes_at, link_target, BlockHeader};
 use crate::error::MdfError;
 use crate::blocks::common::BlockParse;
 
@@ -43,8 +43,7 @@ impl BlockParse<'_> for SourceBlock {
         let mut comment_addr = 0;
         let link_count = header.links_nr as usize;
         for i in 0..link_count.min(3) {
-            let off = 24 + i * 8;
-            let link = LittleEndian::read_u64(&bytes[off..off + 8]);
+            let link = LittleEndian::read_u64(bytes_at(bytes, 24 + i as u64 * 8, 8)?);
             match i {
                 0 => name_addr    = link,
                 1 => path_addr    = link,
@@ -55,20 +54,11 @@ impl BlockParse<'_> for SourceBlock {
 
         // Data section immediately after all links:
         
-        let data_start = 24 + link_count * 8;
-
-        let expected_bytes = data_start + 2;
-        if bytes.len() < expected_bytes {
-            return Err(MdfError::TooShortBuffer {
-                actual:   bytes.len(),
-                expected: expected_bytes,
-                file:     file!(),
-                line:     line!(),
-            });
-        }
-        let source_type = bytes[data_start];
-        let bus_type    = bytes[data_start + 1];
-        let flags       = bytes[data_start + 2];
+        let data_start = header.links_nr.saturating_mul(8).saturating_add(24);
+        let data = bytes_at(bytes, data_start, 3)?;
+        let source_type = data[0];
+        let bus_type    = data[1];
+        let flags       = data[2];
         // bytes [data_start+3 .. data_start+8] are reserved/padding
 
         Ok(SourceBlock {
@@ -83,6 +73,47 @@ impl BlockParse<'_> for SourceBlock {
     }
 }
 
+impl SourceBlock {
+    /// Serializes the SourceBlock (3 links, 56 bytes total).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MdfError> {
+        if self.header.id != "##SI" || self.header.block_len != 56 || self.header.links_nr != 3 {
+            return Err(MdfError::BlockSerializationError(format!(
+                "SourceBlock must have ID '##SI', block_len=56 and 3 links, found '{}' / {} / {}",
+                self.header.id, self.header.block_len, self.header.links_nr
+            )));
+        }
+        let mut buffer = Vec::with_capacity(56);
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(&self.name_addr.to_le_bytes());
+        buffer.extend_from_slice(&self.path_addr.to_le_bytes());
+        buffer.extend_from_slice(&self.comment_addr.to_le_bytes());
+        buffer.push(self.source_type);
+        buffer.push(self.bus_type);
+        buffer.push(self.flags);
+        buffer.extend_from_slice(&[0u8; 5]);
+        Ok(buffer)
+    }
+}
+
+impl Default for SourceBlock {
+    fn default() -> Self {
+        SourceBlock {
+            header: BlockHeader {
+                id: String::from("##SI"),
+                reserved0: 0,
+                block_len: 56,
+                links_nr: 3,
+            },
+            name_addr: 0,
+            path_addr: 0,
+            comment_addr: 0,
+            source_type: 0,
+            bus_type: 0,
+            flags: 0,
+        }
+    }
+}
+
 /// Read an [`SIBLOCK`](SourceBlock) from the memory mapped file.
 ///
 /// # Arguments
@@ -93,10 +124,8 @@ impl BlockParse<'_> for SourceBlock {
 /// The parsed [`SourceBlock`] or an [`MdfError`] if decoding fails.
 pub fn read_source_block(mmap: &[u8], address: u64) -> Result<SourceBlock, MdfError> {
 
-    let start = address as usize;
-    let header = BlockHeader::from_bytes(&mmap[start..start+24])?;
+    let header = BlockHeader::from_bytes(link_target(mmap, address, "##SI")?)?;
     // We know the total length from the header:
-    let total_len = header.block_len as usize;
-    let slice = &mmap[start..start + total_len];
+    let slice = bytes_at(mmap, address, header.block_len)?;
     Ok(SourceBlock::from_bytes(slice)?)
 }
@@ -0,0 +1,101 @@
+//! Generic traversal of the block graph of an MDF 4 file.
+//!
+//! [`walk`] visits every block reachable from the header through link
+//! fields, whatever its type, so blocks the high-level API does not model
+//! can be found without re-implementing header parsing. [`header_at`],
+//! [`links_at`] and [`parse_at`] read a single block at a known offset.
+//!
+//! ```no_run
+//! # use mf4_rs::blocks::walk::{parse_at, walk};
+//! # use mf4_rs::blocks::text_block::TextBlock;
+//! let data = std::fs::read("recording.mf4")?;
+//! for block in walk(&data) {
+//!     let (offset, header) = block?;
+//!     if header.id == "##TX" {
+//!         let text: TextBlock = parse_at(&data, offset)?;
+//!         println!("{offset:#x}: {}", text.text);
+//!     }
+//! }
+//! # Ok::<(), mf4_rs::error::MdfError>(())
+//! ```
+
+use std::collections::HashSet;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::blocks::common::{bytes_at, link_target, BlockHeader, BlockParse, HD_OFFSET};
+use crate::error::MdfError;
+
+/// Iterator over the blocks of a file; see [`walk`].
+pub struct BlockWalk<'a> {
+    data: &'a [u8],
+    /// Offsets still to visit, the next one last.
+    pending: Vec<u64>,
+    seen: HashSet<u64>,
+}
+
+/// Walk the blocks reachable from the header of the MDF 4 file in `data`.
+///
+/// Yields the offset and header of each block once, depth first in link
+/// order starting with the `##HD` block; the 64-byte identification block
+/// is not a linked block and is not visited. Every link field is followed,
+/// so the walk also reaches data, list and vendor-specific blocks. A block
+/// whose header cannot be read, or whose links point past the end of
+/// `data`, is reported as an error and its links are not followed; the walk
+/// then continues with the remaining blocks.
+pub fn walk(data: &[u8]) -> BlockWalk<'_> {
+    BlockWalk { data, pending: vec![HD_OFFSET], seen: HashSet::new() }
+}
+
+impl Iterator for BlockWalk<'_> {
+    type Item = Result<(u64, BlockHeader), MdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = loop {
+            let offset = self.pending.pop()?;
+            if self.seen.insert(offset) {
+                break offset;
+            }
+        };
+        let visit = header_at(self.data, offset).and_then(|header| {
+            let links: Vec<u64> = links_at(self.data, offset)?.into_iter().filter(|&link| link != 0).collect();
+            for &link in &links {
+                link_target(self.data, link, &header.id)?;
+            }
+            self.pending.extend(links.into_iter().rev());
+            Ok((offset, header))
+        });
+        Some(visit)
+    }
+}
+
+/// The header of the block at `offset`.
+pub fn header_at(data: &[u8], offset: u64) -> Result<BlockHeader, MdfError> {
+    BlockHeader::from_bytes(bytes_at(data, offset, 24)?)
+}
+
+/// The link fields of the block at `offset`, in on-disk order.
+///
+/// Link `i` is stored at `offset + 24 + 8 * i`, where it can be patched;
+/// null links are returned as `0`.
+pub fn links_at(data: &[u8], offset: u64) -> Result<Vec<u64>, MdfError> {
+    let header = header_at(data, offset)?;
+    let len = header.links_nr.checked_mul(8).filter(|&len| 24 + len <= header.block_len).ok_or_else(|| {
+        MdfError::BlockSerializationError(format!(
+            "{} block at {offset:#x} has {} links but is only {} bytes long",
+            header.id, header.links_nr, header.block_len
+        ))
+    })?;
+    let links = bytes_at(data, offset + 24, len)?;
+    Ok(links.chunks_exact(8).map(LittleEndian::read_u64).collect())
+}
+
+/// Parse the block at `offset` as a `B`, e.g. a
+/// [`ChannelBlock`](crate::blocks::channel_block::ChannelBlock).
+///
+/// Fails with [`MdfError::BlockIDError`] if the block is of another type.
+/// Links to other blocks are left unresolved (e.g. a channel's name stays
+/// `None`).
+pub fn parse_at<'a, B: BlockParse<'a>>(data: &'a [u8], offset: u64) -> Result<B, MdfError> {
+    B::from_bytes(link_target(data, offset, B::ID)?)
+}
@@ -0,0 +1,543 @@
+//! Bus logging channel groups as laid out by the ASAM MDF bus logging
+//! standard.
+//!
+//! Each frame type is stored in a channel group of its own, named after the
+//! frame type (e.g. `CAN_DataFrame`). The group holds a `Timestamp` master
+//! channel and a byte-array channel of the same name whose composition lists
+//! the frame members (`CAN_DataFrame.ID`, `CAN_DataFrame.DataBytes`, ...).
+//! The group's acquisition source is a `##SI` block with the bus type.
+//!
+//! CAN (`CAN_DataFrame`, `CAN_ErrorFrame`), LIN (`LIN_Frame`) and FlexRay
+//! (`FR_Frame`) frames are supported.
+
+use crate::api::channel_group::ChannelGroup;
+use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::common::DataType;
+use crate::error::MdfError;
+use crate::parsing::decoder::{decode_channel_value, DecodedValue};
+use crate::parsing::raw_channel::RawChannel;
+
+/// `cg_flags` bit 2: the channel group holds bus events.
+pub const CG_FLAG_BUS_EVENT: u16 = 0x04;
+/// `cg_flags` bit 3: the bus events are stored without a nested structure
+/// beyond the frame channel.
+pub const CG_FLAG_PLAIN_BUS_EVENT: u16 = 0x08;
+/// `cn_flags` bit 10: the channel is part of a bus event.
+pub const CN_FLAG_BUS_EVENT: u32 = 0x400;
+
+/// `si_type` of a bus source.
+pub const SOURCE_TYPE_BUS: u8 = 2;
+/// `si_bus_type` of a CAN bus.
+pub const BUS_TYPE_CAN: u8 = 2;
+/// `si_bus_type` of a LIN bus.
+pub const BUS_TYPE_LIN: u8 = 3;
+/// `si_bus_type` of a FlexRay bus.
+pub const BUS_TYPE_FLEXRAY: u8 = 5;
+
+/// One member channel of a bus frame; offsets are within the record.
+#[derive(Debug, Clone)]
+pub(crate) struct BusMember {
+    pub(crate) name:        &'static str,
+    pub(crate) data_type:   DataType,
+    pub(crate) byte_offset: u32,
+    pub(crate) bit_offset:  u8,
+    pub(crate) bit_count:   u32,
+}
+
+const fn member(name: &'static str, data_type: DataType, byte_offset: u32, bit_offset: u8, bit_count: u32) -> BusMember {
+    BusMember { name, data_type, byte_offset, bit_offset, bit_count }
+}
+
+/// Record layout shared by CAN data and error frames: the `Timestamp`
+/// master takes bytes 0..8, the frame channel starts at byte 8.
+const CAN_MEMBERS: [BusMember; 10] = [
+    member("BusChannel", DataType::UnsignedIntegerLE, 8, 0, 8),
+    member("ID", DataType::UnsignedIntegerLE, 9, 0, 29),
+    member("IDE", DataType::UnsignedIntegerLE, 12, 7, 1),
+    member("DLC", DataType::UnsignedIntegerLE, 13, 0, 4),
+    member("DataLength", DataType::UnsignedIntegerLE, 14, 0, 8),
+    member("Dir", DataType::UnsignedIntegerLE, 15, 0, 1),
+    member("EDL", DataType::UnsignedIntegerLE, 15, 1, 1),
+    member("BRS", DataType::UnsignedIntegerLE, 15, 2, 1),
+    member("ESI", DataType::UnsignedIntegerLE, 15, 3, 1),
+    member("DataBytes", DataType::ByteArray, 16, 0, 64 * 8),
+];
+const CAN_ERROR_TYPE: BusMember = member("ErrorType", DataType::UnsignedIntegerLE, 80, 0, 8);
+
+/// Record layout of LIN frames.
+const LIN_MEMBERS: [BusMember; 6] = [
+    member("BusChannel", DataType::UnsignedIntegerLE, 8, 0, 8),
+    member("ID", DataType::UnsignedIntegerLE, 9, 0, 6),
+    member("Dir", DataType::UnsignedIntegerLE, 9, 6, 1),
+    member("DataLength", DataType::UnsignedIntegerLE, 10, 0, 4),
+    member("Checksum", DataType::UnsignedIntegerLE, 11, 0, 8),
+    member("DataBytes", DataType::ByteArray, 12, 0, 8 * 8),
+];
+
+/// Record layout of FlexRay frames.
+const FLEXRAY_MEMBERS: [BusMember; 8] = [
+    member("BusChannel", DataType::UnsignedIntegerLE, 8, 0, 8),
+    member("ID", DataType::UnsignedIntegerLE, 9, 0, 11),
+    member("FlexRayChannel", DataType::UnsignedIntegerLE, 10, 3, 1),
+    member("Dir", DataType::UnsignedIntegerLE, 10, 4, 1),
+    member("CycleCount", DataType::UnsignedIntegerLE, 11, 0, 6),
+    member("DataLength", DataType::UnsignedIntegerLE, 12, 0, 8),
+    member("HeaderCRC", DataType::UnsignedIntegerLE, 13, 0, 11),
+    member("DataBytes", DataType::ByteArray, 15, 0, 254 * 8),
+];
+
+/// Frame types of the bus logging standard supported by the writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusFrameType {
+    CanDataFrame,
+    CanErrorFrame,
+    LinFrame,
+    FlexRayFrame,
+}
+
+impl BusFrameType {
+    /// Name of the channel group and of its frame channel.
+    pub fn name(self) -> &'static str {
+        match self {
+            BusFrameType::CanDataFrame => "CAN_DataFrame",
+            BusFrameType::CanErrorFrame => "CAN_ErrorFrame",
+            BusFrameType::LinFrame => "LIN_Frame",
+            BusFrameType::FlexRayFrame => "FR_Frame",
+        }
+    }
+
+    /// `si_bus_type` of the group's acquisition source.
+    pub fn bus_type(self) -> u8 {
+        match self {
+            BusFrameType::CanDataFrame | BusFrameType::CanErrorFrame => BUS_TYPE_CAN,
+            BusFrameType::LinFrame => BUS_TYPE_LIN,
+            BusFrameType::FlexRayFrame => BUS_TYPE_FLEXRAY,
+        }
+    }
+
+    /// Prefix of the source name, followed by the bus channel number.
+    pub(crate) fn bus_name(self) -> &'static str {
+        match self {
+            BusFrameType::CanDataFrame | BusFrameType::CanErrorFrame => "CAN",
+            BusFrameType::LinFrame => "LIN",
+            BusFrameType::FlexRayFrame => "FlexRay",
+        }
+    }
+
+    /// The member channels of the frame channel.
+    pub(crate) fn members(self) -> Vec<BusMember> {
+        match self {
+            BusFrameType::CanDataFrame => CAN_MEMBERS.to_vec(),
+            BusFrameType::CanErrorFrame => {
+                let mut members = CAN_MEMBERS.to_vec();
+                members.push(CAN_ERROR_TYPE);
+                members
+            }
+            BusFrameType::LinFrame => LIN_MEMBERS.to_vec(),
+            BusFrameType::FlexRayFrame => FLEXRAY_MEMBERS.to_vec(),
+        }
+    }
+
+    /// Number of record bytes after the 8-byte `Timestamp`.
+    pub(crate) fn frame_bytes(self) -> u32 {
+        self.members()
+            .iter()
+            .map(|m| m.byte_offset + (m.bit_offset as u32 + m.bit_count).div_ceil(8))
+            .max()
+            .unwrap_or(8)
+            - 8
+    }
+}
+
+/// A CAN or CAN FD frame of a `CAN_DataFrame` or `CAN_ErrorFrame` group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CanFrame {
+    /// Master channel value, in seconds.
+    pub timestamp:   f64,
+    pub bus_channel: u8,
+    /// 11-bit standard or 29-bit extended identifier.
+    pub id:          u32,
+    /// Extended (29-bit) identifier.
+    pub extended:    bool,
+    /// Data length code.
+    pub dlc:         u8,
+    /// Payload, at most 64 bytes.
+    pub data:        Vec<u8>,
+    /// Transmitted by the logger (`Dir` = Tx) rather than received.
+    pub tx:          bool,
+    /// CAN FD frame (extended data length).
+    pub edl:         bool,
+    /// CAN FD bit rate switch.
+    pub brs:         bool,
+    /// CAN FD error state indicator.
+    pub esi:         bool,
+    /// `ErrorType` of an error frame; `None` for data frames.
+    pub error_type:  Option<u8>,
+}
+
+/// A frame of a `LIN_Frame` group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinFrame {
+    /// Master channel value, in seconds.
+    pub timestamp:   f64,
+    pub bus_channel: u8,
+    /// 6-bit frame identifier.
+    pub id:          u8,
+    /// Payload, at most 8 bytes.
+    pub data:        Vec<u8>,
+    pub checksum:    u8,
+    /// Transmitted by the logger (`Dir` = Tx) rather than received.
+    pub tx:          bool,
+}
+
+/// A frame of a `FR_Frame` group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlexRayFrame {
+    /// Master channel value, in seconds.
+    pub timestamp:   f64,
+    pub bus_channel: u8,
+    /// Received on FlexRay channel B rather than channel A.
+    pub channel_b:   bool,
+    /// 11-bit slot identifier.
+    pub id:          u16,
+    /// 6-bit cycle counter.
+    pub cycle:       u8,
+    /// Payload, at most 254 bytes.
+    pub data:        Vec<u8>,
+    /// 11-bit header CRC.
+    pub header_crc:  u16,
+    /// Transmitted by the logger (`Dir` = Tx) rather than received.
+    pub tx:          bool,
+}
+
+/// Payload length of a CAN FD data length code.
+pub fn can_dlc_to_len(dlc: u8) -> usize {
+    match dlc {
+        0..=8 => dlc as usize,
+        9 => 12,
+        10 => 16,
+        11 => 20,
+        12 => 24,
+        13 => 32,
+        14 => 48,
+        _ => 64,
+    }
+}
+
+/// Write `value` into the bits of `member` in `record`.
+pub(crate) fn put_member(record: &mut [u8], member: &BusMember, value: u64) {
+    for bit in 0..member.bit_count.min(64) {
+        if value >> bit & 1 != 0 {
+            let pos = member.bit_offset as u32 + bit;
+            record[(member.byte_offset + pos / 8) as usize] |= 1 << (pos % 8);
+        }
+    }
+}
+
+/// Encode a record of `frame_type`'s layout: `timestamp`, the `data`
+/// payload in `DataBytes` and every other member from `value` (0 when it
+/// returns `None`).
+fn frame_record(
+    frame_type: BusFrameType,
+    timestamp: f64,
+    data: &[u8],
+    value: impl Fn(&str) -> Option<u64>,
+) -> Result<Vec<u8>, MdfError> {
+    let mut record = vec![0u8; 8 + frame_type.frame_bytes() as usize];
+    record[..8].copy_from_slice(&timestamp.to_le_bytes());
+    for member in frame_type.members() {
+        if member.name == "DataBytes" {
+            let capacity = member.bit_count as usize / 8;
+            if data.len() > capacity {
+                return Err(MdfError::BlockSerializationError(format!(
+                    "{} payload of {} bytes exceeds {} bytes",
+                    frame_type.name(),
+                    data.len(),
+                    capacity
+                )));
+            }
+            let start = member.byte_offset as usize;
+            record[start..start + data.len()].copy_from_slice(data);
+        } else {
+            put_member(&mut record, &member, value(member.name).unwrap_or(0));
+        }
+    }
+    Ok(record)
+}
+
+impl CanFrame {
+    /// Encode the frame as a record of `frame_type`'s layout, with
+    /// `bus_channel` in place of the frame's own.
+    pub(crate) fn to_record(&self, frame_type: BusFrameType, bus_channel: u8) -> Result<Vec<u8>, MdfError> {
+        frame_record(frame_type, self.timestamp, &self.data, |name| match name {
+            "BusChannel" => Some(bus_channel as u64),
+            "ID" => Some((self.id & 0x1FFF_FFFF) as u64),
+            "IDE" => Some(self.extended as u64),
+            "DLC" => Some(self.dlc as u64),
+            "DataLength" => Some(self.data.len() as u64),
+            "Dir" => Some(self.tx as u64),
+            "EDL" => Some(self.edl as u64),
+            "BRS" => Some(self.brs as u64),
+            "ESI" => Some(self.esi as u64),
+            "ErrorType" => self.error_type.map(u64::from),
+            _ => None,
+        })
+    }
+}
+
+impl LinFrame {
+    /// Encode the frame as a `LIN_Frame` record with `bus_channel` in place
+    /// of the frame's own.
+    pub(crate) fn to_record(&self, bus_channel: u8) -> Result<Vec<u8>, MdfError> {
+        frame_record(BusFrameType::LinFrame, self.timestamp, &self.data, |name| match name {
+            "BusChannel" => Some(bus_channel as u64),
+            "ID" => Some((self.id & 0x3F) as u64),
+            "Dir" => Some(self.tx as u64),
+            "DataLength" => Some(self.data.len() as u64),
+            "Checksum" => Some(self.checksum as u64),
+            _ => None,
+        })
+    }
+}
+
+impl FlexRayFrame {
+    /// Encode the frame as a `FR_Frame` record with `bus_channel` in place
+    /// of the frame's own.
+    pub(crate) fn to_record(&self, bus_channel: u8) -> Result<Vec<u8>, MdfError> {
+        frame_record(BusFrameType::FlexRayFrame, self.timestamp, &self.data, |name| match name {
+            "BusChannel" => Some(bus_channel as u64),
+            "ID" => Some((self.id & 0x7FF) as u64),
+            "FlexRayChannel" => Some(self.channel_b as u64),
+            "Dir" => Some(self.tx as u64),
+            "CycleCount" => Some((self.cycle & 0x3F) as u64),
+            "DataLength" => Some(self.data.len() as u64),
+            "HeaderCRC" => Some((self.header_crc & 0x7FF) as u64),
+            _ => None,
+        })
+    }
+}
+
+/// The member channels of the frame channel `frame_name` in `group`, with
+/// their names shortened to the part after the last `.`; `None` if the group
+/// has no such channel.
+pub(crate) fn frame_members(group: &ChannelGroup, frame_name: &str) -> Result<Option<Vec<(String, ChannelBlock)>>, MdfError> {
+    let mmap = group.mmap();
+    let Some(frame) = group.raw_channel_group().raw_channels.iter().find(|ch| {
+        let mut block = ch.block.clone();
+        block.resolve_name(mmap).is_ok() && block.name.as_deref() == Some(frame_name)
+    }) else {
+        return Ok(None);
+    };
+    let mut members = Vec::new();
+    for component in &frame.components {
+        let mut block = component.block.clone();
+        block.resolve_name(mmap)?;
+        let name = block.name.as_deref().unwrap_or_default();
+        let short = name.rsplit('.').next().unwrap_or(name).to_string();
+        members.push((short, block));
+    }
+    Ok(Some(members))
+}
+
+/// Decoded records of a bus frame group: timestamps plus a lookup of the
+/// frame members by short name.
+pub(crate) struct FrameRecords<'a> {
+    group:         &'a ChannelGroup<'a>,
+    members:       Vec<(String, ChannelBlock)>,
+    record_id_len: usize,
+}
+
+impl<'a> FrameRecords<'a> {
+    pub(crate) fn new(group: &'a ChannelGroup<'a>, frame_name: &str) -> Result<Option<Self>, MdfError> {
+        Ok(frame_members(group, frame_name)?.map(|members| FrameRecords {
+            group,
+            members,
+            record_id_len: group.raw_data_group().block.record_id_len as usize,
+        }))
+    }
+
+    fn member(&self, name: &str) -> Option<&ChannelBlock> {
+        self.members.iter().find(|(n, _)| n == name).map(|(_, b)| b)
+    }
+
+    /// Master channel values, one per record (NaN when missing).
+    pub(crate) fn timestamps(&self) -> Result<Vec<f64>, MdfError> {
+        Ok(self.group.time_values()?.unwrap_or_default())
+    }
+
+    /// Call `f` with each record and, if `bytes_member` is a VLSD member, its
+    /// signal data entry.
+    pub(crate) fn for_each(
+        &self,
+        bytes_member: &str,
+        mut f: impl FnMut(&[u8], Option<&[u8]>) -> Result<(), MdfError>,
+    ) -> Result<(), MdfError> {
+        let dg = self.group.raw_data_group();
+        let cg = self.group.raw_channel_group();
+        let mmap = self.group.mmap();
+        let record_size = self.record_id_len
+            + cg.block.samples_byte_nr as usize
+            + cg.block.invalidation_bytes_nr as usize;
+        if record_size == 0 {
+            return Ok(());
+        }
+        let vlsd_member = self
+            .member(bytes_member)
+            .filter(|b| b.channel_type == 1 && b.data != 0)
+            .map(|b| RawChannel {
+                block: b.clone(),
+                components: Vec::new(),
+                source: dg.source.clone(),
+                simplified: Default::default(),
+            });
+        let mut entries = match &vlsd_member {
+            Some(raw) => Some(raw.records(dg, cg, mmap)?),
+            None => None,
+        };
+        for block in dg.channel_group_blocks(cg, mmap)? {
+            for rec in block?.data.chunks_exact(record_size) {
+                let entry = match entries.as_mut() {
+                    Some(it) => Some(it.next_record().transpose()?.ok_or_else(|| {
+                        MdfError::BlockSerializationError(
+                            "VLSD entry count fewer than parent records".into(),
+                        )
+                    })?),
+                    None => None,
+                };
+                f(rec, entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Integer value of member `name` in `record`; 0 if the member is absent.
+    pub(crate) fn uint(&self, record: &[u8], name: &str) -> u64 {
+        match self.member(name).and_then(|b| decode_channel_value(record, self.record_id_len, b)) {
+            Some(DecodedValue::UnsignedInteger(v)) => v,
+            Some(DecodedValue::SignedInteger(v)) => v as u64,
+            Some(DecodedValue::Float(v)) => v as u64,
+            _ => 0,
+        }
+    }
+
+    /// Bytes of member `name`: the VLSD `entry` if given, the fixed-size
+    /// field otherwise.
+    pub(crate) fn bytes(&self, record: &[u8], name: &str, entry: Option<&[u8]>) -> Vec<u8> {
+        if let Some(entry) = entry {
+            return entry.to_vec();
+        }
+        match self.member(name).and_then(|b| decode_channel_value(record, self.record_id_len, b)) {
+            Some(DecodedValue::ByteArray(b)) => b,
+            _ => Vec::new(),
+        }
+    }
+
+    pub(crate) fn has(&self, name: &str) -> bool {
+        self.member(name).is_some()
+    }
+}
+
+/// Read the frames of a `CAN_DataFrame` or `CAN_ErrorFrame` channel group.
+///
+/// Members are looked up by name in the composition of the frame channel,
+/// so groups written by other loggers are read as long as they use the
+/// standard member names. `DataBytes` may be fixed-size or VLSD.
+///
+/// # Returns
+/// `Ok(None)` if `group` holds neither frame type.
+pub fn read_can_frames(group: &ChannelGroup) -> Result<Option<Vec<CanFrame>>, MdfError> {
+    for frame_type in [BusFrameType::CanDataFrame, BusFrameType::CanErrorFrame] {
+        let Some(records) = FrameRecords::new(group, frame_type.name())? else {
+            continue;
+        };
+        let timestamps = records.timestamps()?;
+        let mut frames = Vec::with_capacity(timestamps.len());
+        records.for_each("DataBytes", |rec, entry| {
+            let dlc = records.uint(rec, "DLC") as u8;
+            let mut data = records.bytes(rec, "DataBytes", entry);
+            let len = if records.has("DataLength") {
+                records.uint(rec, "DataLength") as usize
+            } else {
+                can_dlc_to_len(dlc)
+            };
+            data.truncate(len);
+            frames.push(CanFrame {
+                timestamp: timestamps.get(frames.len()).copied().unwrap_or(f64::NAN),
+                bus_channel: records.uint(rec, "BusChannel") as u8,
+                id: records.uint(rec, "ID") as u32 & 0x1FFF_FFFF,
+                extended: records.uint(rec, "IDE") != 0,
+                dlc,
+                data,
+                tx: records.uint(rec, "Dir") != 0,
+                edl: records.uint(rec, "EDL") != 0,
+                brs: records.uint(rec, "BRS") != 0,
+                esi: records.uint(rec, "ESI") != 0,
+                error_type: (frame_type == BusFrameType::CanErrorFrame)
+                    .then(|| records.uint(rec, "ErrorType") as u8),
+            });
+            Ok(())
+        })?;
+        return Ok(Some(frames));
+    }
+    Ok(None)
+}
+
+/// Read the frames of a `LIN_Frame` channel group.
+///
+/// Like [`read_can_frames`], members are looked up by name and `DataBytes`
+/// may be fixed-size or VLSD.
+///
+/// # Returns
+/// `Ok(None)` if `group` holds no LIN frames.
+pub fn read_lin_frames(group: &ChannelGroup) -> Result<Option<Vec<LinFrame>>, MdfError> {
+    let Some(records) = FrameRecords::new(group, BusFrameType::LinFrame.name())? else {
+        return Ok(None);
+    };
+    let timestamps = records.timestamps()?;
+    let mut frames = Vec::with_capacity(timestamps.len());
+    records.for_each("DataBytes", |rec, entry| {
+        let mut data = records.bytes(rec, "DataBytes", entry);
+        data.truncate(records.uint(rec, "DataLength") as usize);
+        frames.push(LinFrame {
+            timestamp: timestamps.get(frames.len()).copied().unwrap_or(f64::NAN),
+            bus_channel: records.uint(rec, "BusChannel") as u8,
+            id: records.uint(rec, "ID") as u8 & 0x3F,
+            data,
+            checksum: records.uint(rec, "Checksum") as u8,
+            tx: records.uint(rec, "Dir") != 0,
+        });
+        Ok(())
+    })?;
+    Ok(Some(frames))
+}
+
+/// Read the frames of a `FR_Frame` channel group.
+///
+/// Like [`read_can_frames`], members are looked up by name and `DataBytes`
+/// may be fixed-size or VLSD.
+///
+/// # Returns
+/// `Ok(None)` if `group` holds no FlexRay frames.
+pub fn read_flexray_frames(group: &ChannelGroup) -> Result<Option<Vec<FlexRayFrame>>, MdfError> {
+    let Some(records) = FrameRecords::new(group, BusFrameType::FlexRayFrame.name())? else {
+        return Ok(None);
+    };
+    let timestamps = records.timestamps()?;
+    let mut frames = Vec::with_capacity(timestamps.len());
+    records.for_each("DataBytes", |rec, entry| {
+        let mut data = records.bytes(rec, "DataBytes", entry);
+        data.truncate(records.uint(rec, "DataLength") as usize);
+        frames.push(FlexRayFrame {
+            timestamp: timestamps.get(frames.len()).copied().unwrap_or(f64::NAN),
+            bus_channel: records.uint(rec, "BusChannel") as u8,
+            channel_b: records.uint(rec, "FlexRayChannel") != 0,
+            id: records.uint(rec, "ID") as u16 & 0x7FF,
+            cycle: records.uint(rec, "CycleCount") as u8 & 0x3F,
+            data,
+            header_crc: records.uint(rec, "HeaderCRC") as u16 & 0x7FF,
+            tx: records.uint(rec, "Dir") != 0,
+        });
+        Ok(())
+    })?;
+    Ok(Some(frames))
+}
@@ -1,136 +1,561 @@
 use std::collections::HashMap;
+use std::path::Path;
 
-use crate::blocks::common::{BlockHeader, BlockParse};
-use crate::blocks::conversion::ConversionBlock;
-use crate::blocks::source_block::SourceBlock;
+use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::common::read_string_block;
+use crate::blocks::text_block::TextBlock;
 use crate::error::MdfError;
 use crate::parsing::decoder::{decode_channel_value, DecodedValue};
 use crate::parsing::mdf_file::MdfFile;
-use crate::writer::MdfWriter;
+use crate::parsing::raw_channel::Records;
+use crate::parsing::raw_channel_group::RawChannelGroup;
+use crate::parsing::raw_data_group::RawDataGroup;
+use crate::transform::{physical_value, Record};
+use crate::writer::{ChannelGroupId, ChannelId, MdfWriter};
 
 /// Recursively copy a referenced block (`##TX`, `##MD`, `##SI`, or `##CC`)
-/// from the source MDF mmap into the writer, rewriting any link fields so
-/// the new block points at freshly written copies of its dependencies.
-///
-/// Returns the file offset of the new block, or `Ok(0)` when `src_addr` is
-/// `0`, the offset is out of range, or the block type is not one of the
-/// handled kinds. Already-cloned source addresses are deduplicated through
-/// `cache`.
+/// from the source MDF mmap into the writer; see
+/// `MdfWriter::copy_linked_block`.
 fn clone_block_to_writer(
     writer: &mut MdfWriter,
     mmap: &[u8],
     src_addr: u64,
     cache: &mut HashMap<u64, u64>,
 ) -> Result<u64, MdfError> {
-    if src_addr == 0 {
-        return Ok(0);
+    writer.copy_linked_block(&|addr| mmap.get(addr as usize..), src_addr, cache)
+}
+
+/// Channel renames and unit overrides applied while copying channels, so
+/// files with inconsistent channel naming can be harmonised in one pass.
+///
+/// ```
+/// # use mf4_rs::cut::ChannelMapping;
+/// let mut mapping = ChannelMapping::default();
+/// mapping.names.insert("VehSpd".into(), "Speed".into());
+/// mapping.units.insert("Speed".into(), "km/h".into());
+/// assert_eq!(mapping.name("VehSpd"), "Speed");
+/// assert_eq!(mapping.unit("Speed"), Some("km/h"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelMapping {
+    /// Source channel name → output channel name.
+    pub names: HashMap<String, String>,
+    /// Output channel name → unit, replacing the source unit.
+    pub units: HashMap<String, String>,
+}
+
+impl ChannelMapping {
+    /// The output name of the source channel `name`.
+    pub fn name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.names.get(name).map_or(name, String::as_str)
+    }
+
+    /// The unit override of the output channel `name`, if any.
+    pub fn unit(&self, name: &str) -> Option<&str> {
+        self.units.get(name).map(String::as_str)
+    }
+}
+
+/// Options for [`cut_mdf_by_time_with_options`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CutOptions {
+    /// Channel renames and unit overrides.
+    pub mapping: ChannelMapping,
+    /// Also copy the blocks that are not part of the channel groups: the
+    /// header comment, file history, channel hierarchy, attachments, events,
+    /// data group comments and blocks of types this crate does not model.
+    /// They are copied verbatim, with links to groups and channels rewired
+    /// to their copies and other links to the source structure or data
+    /// cleared.
+    pub preserve_blocks: bool,
+}
+
+/// Addresses of a channel group, its data group and its channels in a
+/// source file.
+#[derive(Debug, Clone)]
+pub(crate) struct SourceGroup {
+    pub dg: u64,
+    /// Comment link of the data group.
+    pub dg_comment: u64,
+    pub cg: u64,
+    pub channels: Vec<u64>,
+}
+
+/// The [`SourceGroup`] of every channel group of `mdf`, per data group in
+/// the order of `mdf.data_groups`.
+pub(crate) fn source_groups(mdf: &MdfFile) -> Vec<Vec<SourceGroup>> {
+    let mut dg_addr = mdf.header.first_dg_addr;
+    let mut groups = Vec::with_capacity(mdf.data_groups.len());
+    for dg in &mdf.data_groups {
+        let mut cg_addr = dg.block.first_cg_addr;
+        let mut dg_groups = Vec::with_capacity(dg.channel_groups.len());
+        for cg in &dg.channel_groups {
+            let mut cn_addr = cg.block.first_ch_addr;
+            let mut channels = Vec::with_capacity(cg.raw_channels.len());
+            for ch in &cg.raw_channels {
+                channels.push(cn_addr);
+                cn_addr = ch.block.next_ch_addr;
+            }
+            dg_groups.push(SourceGroup { dg: dg_addr, dg_comment: dg.block.comment_addr, cg: cg_addr, channels });
+            cg_addr = cg.block.next_cg_addr;
+        }
+        groups.push(dg_groups);
+        dg_addr = dg.block.next_dg_addr;
     }
-    if let Some(&dst) = cache.get(&src_addr) {
-        return Ok(dst);
+    groups
+}
+
+/// Record in `cache` that `source` was re-created as `cg_id` with the
+/// channels `cn_ids`, so preserved blocks linking to it are rewired. A
+/// source data group maps to the data group of its first re-created group.
+pub(crate) fn map_group(
+    writer: &MdfWriter,
+    cache: &mut HashMap<u64, u64>,
+    source: &SourceGroup,
+    cg_id: &ChannelGroupId,
+    cn_ids: &[ChannelId],
+) {
+    if let Some(dg_pos) = writer.data_group_of(cg_id).and_then(|dg| writer.get_block_position(dg)) {
+        cache.entry(source.dg).or_insert(dg_pos);
     }
-    let offset = src_addr as usize;
-    if offset + 24 > mmap.len() {
-        return Ok(0);
+    if let Some(cg_pos) = writer.get_block_position(cg_id) {
+        cache.insert(source.cg, cg_pos);
     }
-    let header = BlockHeader::from_bytes(&mmap[offset..offset + 24])?;
-    let total_len = header.block_len as usize;
-    if total_len < 24 || offset + total_len > mmap.len() {
-        return Ok(0);
+    for (&src, cn_id) in source.channels.iter().zip(cn_ids) {
+        if let Some(cn_pos) = writer.get_block_position(cn_id) {
+            cache.insert(src, cn_pos);
+        }
     }
+}
 
-    let dst = match header.id.as_str() {
-        "##TX" | "##MD" => {
-            // Leaf blocks with no outgoing links: copy raw bytes verbatim.
-            writer.write_block(&mmap[offset..offset + total_len])?
+/// Copy the data group comment of `source` to the data group of `cg_id`.
+pub(crate) fn copy_dg_comment(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    source: &SourceGroup,
+    cg_id: &ChannelGroupId,
+    cache: &mut HashMap<u64, u64>,
+) -> Result<(), MdfError> {
+    let comment = clone_block_to_writer(writer, mmap, source.dg_comment, cache)?;
+    if let Some(dg_pos) = writer.data_group_of(cg_id).and_then(|dg| writer.get_block_position(dg)) {
+        // Link offset 48 of the ##DG block: comment.
+        if comment != 0 {
+            writer.update_link(dg_pos + 48, comment)?;
         }
-        "##SI" => {
-            let src_block = SourceBlock::from_bytes(&mmap[offset..offset + total_len])?;
-            // Reserve the cache slot before recursing to break cycles.
-            cache.insert(src_addr, 0);
-            let new_name = clone_block_to_writer(writer, mmap, src_block.name_addr, cache)?;
-            let new_path = clone_block_to_writer(writer, mmap, src_block.path_addr, cache)?;
-            let new_comment =
-                clone_block_to_writer(writer, mmap, src_block.comment_addr, cache)?;
-            // SourceBlock has no `to_bytes`, so patch the original block's
-            // bytes in place. The link layout is fixed: name/path/comment at
-            // offsets 24/32/40 (only the slots actually referenced by
-            // `header.links_nr` are touched).
-            let mut bytes = mmap[offset..offset + total_len].to_vec();
-            let link_count = header.links_nr as usize;
-            if link_count >= 1 {
-                bytes[24..32].copy_from_slice(&new_name.to_le_bytes());
-            }
-            if link_count >= 2 {
-                bytes[32..40].copy_from_slice(&new_path.to_le_bytes());
-            }
-            if link_count >= 3 {
-                bytes[40..48].copy_from_slice(&new_comment.to_le_bytes());
-            }
-            writer.write_block(&bytes)?
+    }
+    Ok(())
+}
+
+/// The block chains linked from the output header, to which the chains of
+/// each source file's header are appended; see
+/// [`CutOptions::preserve_blocks`].
+pub(crate) struct HeaderChains {
+    /// Last link of the file history, channel hierarchy, attachment and
+    /// event chains.
+    link_pos: [u64; 4],
+    /// Header comment link, until a source with a comment fills it.
+    comment_pos: Option<u64>,
+}
+
+impl HeaderChains {
+    pub(crate) fn new(writer: &MdfWriter) -> Result<Self, MdfError> {
+        let hd_pos = writer
+            .get_block_position("hd_block")
+            .ok_or_else(|| MdfError::BlockLinkError("hd_block not found".into()))?;
+        // ##HD links: file history 32, channel hierarchy 40, attachments 48,
+        // events 56, comment 64.
+        Ok(HeaderChains {
+            link_pos: [hd_pos + 32, hd_pos + 40, hd_pos + 48, hd_pos + 56],
+            comment_pos: Some(hd_pos + 64),
+        })
+    }
+
+    /// Append the chains of the header of `mdf`, and its comment if no
+    /// earlier source had one. `cache` maps the groups and channels of
+    /// `mdf` to their copies (see [`map_group`]).
+    pub(crate) fn append(
+        &mut self,
+        writer: &mut MdfWriter,
+        mdf: &MdfFile,
+        cache: &mut HashMap<u64, u64>,
+    ) -> Result<(), MdfError> {
+        let fetch = |addr: u64| mdf.mmap.get(addr as usize..);
+        let hd = &mdf.header;
+        let chains = [hd.file_history_addr, hd.channel_tree_addr, hd.first_attachment_addr, hd.first_event_addr];
+        for (link_pos, src_addr) in self.link_pos.iter_mut().zip(chains) {
+            *link_pos = writer.append_block_chain(&fetch, src_addr, cache, *link_pos)?;
         }
-        "##CC" => {
-            let src_block = ConversionBlock::from_bytes(&mmap[offset..offset + total_len])?;
-            cache.insert(src_addr, 0);
-            let new_tx_name = clone_block_to_writer(
-                writer,
-                mmap,
-                src_block.cc_tx_name.unwrap_or(0),
-                cache,
-            )?;
-            let new_md_unit = clone_block_to_writer(
-                writer,
-                mmap,
-                src_block.cc_md_unit.unwrap_or(0),
-                cache,
-            )?;
-            let new_md_comment = clone_block_to_writer(
-                writer,
-                mmap,
-                src_block.cc_md_comment.unwrap_or(0),
-                cache,
-            )?;
-            let new_cc_inverse = clone_block_to_writer(
-                writer,
-                mmap,
-                src_block.cc_cc_inverse.unwrap_or(0),
-                cache,
-            )?;
-            let mut new_refs = Vec::with_capacity(src_block.cc_ref.len());
-            for &r in &src_block.cc_ref {
-                new_refs.push(clone_block_to_writer(writer, mmap, r, cache)?);
+        if let Some(comment_pos) = self.comment_pos {
+            let comment = writer.copy_linked_block(&fetch, hd.comment_addr, cache)?;
+            if comment != 0 {
+                writer.update_link(comment_pos, comment)?;
+                self.comment_pos = None;
             }
-            let new_cc = ConversionBlock {
-                header: src_block.header.clone(),
-                cc_tx_name: (new_tx_name != 0).then_some(new_tx_name),
-                cc_md_unit: (new_md_unit != 0).then_some(new_md_unit),
-                cc_md_comment: (new_md_comment != 0).then_some(new_md_comment),
-                cc_cc_inverse: (new_cc_inverse != 0).then_some(new_cc_inverse),
-                cc_ref: new_refs,
-                cc_type: src_block.cc_type.clone(),
-                cc_precision: src_block.cc_precision,
-                cc_flags: src_block.cc_flags,
-                cc_ref_count: src_block.cc_ref_count,
-                cc_val_count: src_block.cc_val_count,
-                cc_phy_range_min: src_block.cc_phy_range_min,
-                cc_phy_range_max: src_block.cc_phy_range_max,
-                cc_val: src_block.cc_val.clone(),
-                formula: None,
-                resolved_texts: None,
-                resolved_conversions: None,
-                default_conversion: None,
-            };
-            writer.write_block(&new_cc.to_bytes()?)?
         }
-        _ => 0,
+        Ok(())
+    }
+}
+
+/// A channel re-created by [`copy_group_structure`]:
+/// `(out_cn_id, source_channel_index, is_vlsd)`.
+pub(crate) type CopiedChannel = (ChannelId, usize, bool);
+
+/// Re-create `cg` from the source file in `writer` as the only channel group
+/// of a new data group.
+///
+/// The channel-group acquisition name, source and comment blocks are copied,
+/// and so is every channel with its source, conversion, unit and comment
+/// blocks (see [`copy_channel`]). Channel names and units follow `mapping`.
+///
+/// Returns the new channel group handle and the copied channels.
+pub(crate) fn copy_group_structure(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    cg: &RawChannelGroup,
+    mapping: &ChannelMapping,
+    block_cache: &mut HashMap<u64, u64>,
+) -> Result<(ChannelGroupId, Vec<CopiedChannel>), MdfError> {
+    let cg_id = copy_group_header(writer, mmap, cg, block_cache)?;
+    let mut prev_cn: Option<ChannelId> = None;
+    let mut out_channels: Vec<CopiedChannel> = Vec::new();
+    for (idx, ch) in cg.raw_channels.iter().enumerate() {
+        let (cn_id, is_vlsd) =
+            copy_channel(writer, mmap, &cg_id, prev_cn.as_ref(), ch.block.clone(), mapping, block_cache)?;
+        prev_cn = Some(cn_id);
+        out_channels.push((cn_id, idx, is_vlsd));
+    }
+    Ok((cg_id, out_channels))
+}
+
+/// Create a channel group without channels, in a new data group, carrying
+/// over the acquisition name, source and comment blocks of `cg`.
+pub(crate) fn copy_group_header(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    cg: &RawChannelGroup,
+    block_cache: &mut HashMap<u64, u64>,
+) -> Result<ChannelGroupId, MdfError> {
+    let cg_id = writer.add_channel_group(None, |out| out.path_separator = cg.block.path_separator)?;
+
+    // Link offsets in the ##CG block:
+    //   40 = acq_name_addr, 48 = acq_source_addr, 64 = comment_addr.
+    let cg_pos = writer
+        .get_block_position(cg_id)
+        .ok_or_else(|| MdfError::BlockLinkError(format!("cg '{}' not found", cg_id)))?;
+    let new_acq_name = clone_block_to_writer(writer, mmap, cg.block.acq_name_addr, block_cache)?;
+    if new_acq_name != 0 {
+        writer.update_link(cg_pos + 40, new_acq_name)?;
+    }
+    let new_acq_source = clone_block_to_writer(writer, mmap, cg.block.acq_source_addr, block_cache)?;
+    if new_acq_source != 0 {
+        writer.update_link(cg_pos + 48, new_acq_source)?;
+    }
+    let new_cg_comment = clone_block_to_writer(writer, mmap, cg.block.comment_addr, block_cache)?;
+    if new_cg_comment != 0 {
+        writer.update_link(cg_pos + 64, new_cg_comment)?;
+    }
+    Ok(cg_id)
+}
+
+/// Append the source channel `block` to the group `cg_id` after `prev_cn`,
+/// with its source, conversion, unit and comment blocks.
+///
+/// Stale link addresses pointing into the source file are zeroed out so the
+/// resulting channel block is self-contained. After the channel block is
+/// written, the linked blocks are cloned from the source file (recursively
+/// following nested links) and the channel's links are patched to point at
+/// the freshly written copies. Links that are not re-emitted (components,
+/// VLSD data) are cleared; the VLSD `data` link is set later by
+/// `finish_signal_data_block`. The name and unit follow `mapping`.
+///
+/// Returns the new channel handle and whether the channel is VLSD.
+pub(crate) fn copy_channel(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    cg_id: &ChannelGroupId,
+    prev_cn: Option<&ChannelId>,
+    mut block: ChannelBlock,
+    mapping: &ChannelMapping,
+    block_cache: &mut HashMap<u64, u64>,
+) -> Result<(ChannelId, bool), MdfError> {
+    block.resolve_name(mmap)?;
+    block.name = block.name.map(|name| mapping.name(&name).to_string());
+    let unit = block.name.as_deref().and_then(|name| mapping.unit(name));
+
+    let is_vlsd = block.channel_type == 1 && block.data != 0;
+
+    // Capture the source-file link addresses before zeroing them
+    // on the block we hand to `add_channel`.
+    let src_source_addr = block.source_addr;
+    let src_conversion_addr = block.conversion_addr;
+    let src_unit_addr = block.unit_addr;
+    let src_comment_addr = block.comment_addr;
+
+    // Drop links to source-file blocks we do not re-emit. Without
+    // this, the new file would carry pointers into garbage.
+    block.conversion_addr = 0;
+    block.conversion = None;
+    block.source_addr = 0;
+    block.unit_addr = 0;
+    block.comment_addr = 0;
+    block.component_addr = 0;
+    block.data = 0;
+
+    let cn_id = writer.add_channel(cg_id, prev_cn, |c| {
+        *c = block.clone();
+    })?;
+
+    // Channel block link offsets: source 48, conversion 56, unit 72,
+    // comment 80.
+    let cn_pos = writer.get_block_position(cn_id).ok_or_else(|| {
+        MdfError::BlockLinkError(format!("cn '{}' not found", cn_id))
+    })?;
+    let new_source = clone_block_to_writer(writer, mmap, src_source_addr, block_cache)?;
+    if new_source != 0 {
+        writer.update_link(cn_pos + 48, new_source)?;
+    }
+    let new_conv = clone_block_to_writer(writer, mmap, src_conversion_addr, block_cache)?;
+    if new_conv != 0 {
+        writer.update_link(cn_pos + 56, new_conv)?;
+    }
+    let new_unit = match unit {
+        Some(unit) => writer.write_block(&TextBlock::new(unit).to_bytes()?)?,
+        None => clone_block_to_writer(writer, mmap, src_unit_addr, block_cache)?,
     };
+    if new_unit != 0 {
+        writer.update_link(cn_pos + 72, new_unit)?;
+    }
+    let new_comment = clone_block_to_writer(writer, mmap, src_comment_addr, block_cache)?;
+    if new_comment != 0 {
+        writer.update_link(cn_pos + 80, new_comment)?;
+    }
+
+    Ok((cn_id, is_vlsd))
+}
+
+/// What [`copy_group_records`] does with a source record.
+pub(crate) enum RecordAction {
+    /// Write the record.
+    Keep,
+    /// Leave the record out.
+    Skip,
+    /// Leave this and all following records out.
+    Stop,
+}
+
+/// Copy the records of `cg` into the group `cg_id` created for it by
+/// [`copy_group_structure`], closing its data block afterwards.
+///
+/// `visit` is called with every source record and decides whether it is
+/// kept. Kept records are written with the source layout (including
+/// invalidation bytes) plus `extra_bytes` appended to the data bytes, which
+/// `visit` fills in through its second argument. VLSD channels get fresh
+/// `##SD` blocks holding the entries of the kept records.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn copy_group_records<F>(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    cg_id: &ChannelGroupId,
+    out_channels: &[CopiedChannel],
+    extra_bytes: u32,
+    visit: F,
+) -> Result<(), MdfError>
+where
+    F: FnMut(&mut [u8], &mut Vec<u8>) -> Result<RecordAction, MdfError>,
+{
+    let mut sd_offsets = start_group_records(writer, dg, cg, cg_id, out_channels, extra_bytes)?;
+    append_group_records(writer, mmap, dg, cg, cg_id, out_channels, extra_bytes, &mut sd_offsets, visit)?;
+    finish_group_records(writer, cg_id, out_channels)
+}
+
+/// Open the data block of `cg_id` with the record layout of `cg` widened
+/// by `extra_bytes`, and one `##SD` chain per VLSD channel.
+///
+/// Returns the running offsets into the `##SD` chains, in VLSD channel
+/// order, to pass to [`append_group_records`].
+pub(crate) fn start_group_records(
+    writer: &mut MdfWriter,
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    cg_id: &ChannelGroupId,
+    out_channels: &[CopiedChannel],
+    extra_bytes: u32,
+) -> Result<Vec<u64>, MdfError> {
+    writer.start_data_block_for_cg_raw(
+        cg_id,
+        dg.block.record_id_len,
+        cg.block.samples_byte_nr + extra_bytes,
+        cg.block.invalidation_bytes_nr,
+    )?;
+    let mut sd_offsets = Vec::new();
+    for (cn_id, _, _) in out_channels.iter().filter(|(_, _, is_vlsd)| *is_vlsd) {
+        writer.start_signal_data_block(cn_id)?;
+        sd_offsets.push(0);
+    }
+    Ok(sd_offsets)
+}
+
+/// Close the data block and `##SD` chains opened by [`start_group_records`].
+pub(crate) fn finish_group_records(
+    writer: &mut MdfWriter,
+    cg_id: &ChannelGroupId,
+    out_channels: &[CopiedChannel],
+) -> Result<(), MdfError> {
+    for (cn_id, _, _) in out_channels.iter().filter(|(_, _, is_vlsd)| *is_vlsd) {
+        writer.finish_signal_data_block(cn_id)?;
+    }
+    writer.finish_data_block(cg_id)
+}
+
+/// Write the records of `cg` kept by `visit` to the data block of `cg_id`
+/// opened by [`start_group_records`], as described for
+/// [`copy_group_records`].
+///
+/// `visit` gets a copy of each record it may modify in place. The records
+/// of several source groups with the same layout can be appended to one
+/// output group, sharing `sd_offsets`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn append_group_records<F>(
+    writer: &mut MdfWriter,
+    mmap: &[u8],
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    cg_id: &ChannelGroupId,
+    out_channels: &[CopiedChannel],
+    extra_bytes: u32,
+    sd_offsets: &mut [u64],
+    mut visit: F,
+) -> Result<(), MdfError>
+where
+    F: FnMut(&mut [u8], &mut Vec<u8>) -> Result<RecordAction, MdfError>,
+{
+    let record_id_len = dg.block.record_id_len;
+    let samples_byte_nr = cg.block.samples_byte_nr;
+    let invalidation_bytes_nr = cg.block.invalidation_bytes_nr;
+    let record_size = record_id_len as usize
+        + samples_byte_nr as usize
+        + invalidation_bytes_nr as usize;
+    let data_end = record_id_len as usize + samples_byte_nr as usize;
+
+    // Build VLSD source iterators (lockstep with parent records). For
+    // each VLSD channel we also record the inline-slot location and
+    // size in the parent record, plus a running offset into the new
+    // ##SD block. The inline slot in MDF4 is the byte offset within
+    // the SD data section where this entry lives — readers (e.g.
+    // asammdf) use it to look up the entry. Since we are writing a
+    // freshly numbered SD block, we must overwrite the source-file
+    // offsets with offsets valid in the output.
+    //
+    // Per spec, the slot starts at `record_id_len + byte_offset` and
+    // is `bit_count / 8` bytes wide. mf4-rs writes VLSD entries
+    // sequentially as `[u32 length][bytes]`, so each entry advances
+    // the running offset by `4 + payload.len()`.
+    struct VlsdState<'a, 'o> {
+        cn_id: ChannelId,
+        slot_off: usize,
+        slot_size: usize,
+        next_offset: &'o mut u64,
+        iter: Records<'a>,
+    }
+    let mut vlsd_states: Vec<VlsdState> = Vec::new();
+    let vlsd_channels = out_channels.iter().filter(|(_, _, is_vlsd)| *is_vlsd);
+    for ((cn_id, src_idx, _), next_offset) in vlsd_channels.zip(sd_offsets.iter_mut()) {
+        let ch_block = &cg.raw_channels[*src_idx].block;
+        let slot_size = (ch_block.bit_count / 8) as usize;
+        let slot_off = record_id_len as usize + ch_block.byte_offset as usize;
+        let it = cg.raw_channels[*src_idx].records(dg, cg, mmap)?;
+        vlsd_states.push(VlsdState {
+            cn_id: *cn_id,
+            slot_off,
+            slot_size,
+            next_offset,
+            iter: it,
+        });
+    }
+
+    // Iterate raw parent records from the source DT/DL chain.
+    let blocks = dg.channel_group_blocks(cg, mmap)?;
+    let mut record = Vec::with_capacity(record_size);
+    let mut extra = Vec::with_capacity(extra_bytes as usize);
+    'outer: for data_block in blocks {
+        let data_block = data_block?;
+        let raw = &data_block.data[..];
+        if record_size == 0 {
+            // Degenerate CG with no record bytes — nothing to do.
+            break;
+        }
+        let valid_len = (raw.len() / record_size) * record_size;
+        for record_chunk in raw[..valid_len].chunks_exact(record_size) {
+            // Pull one VLSD entry per VLSD channel in lockstep with
+            // the parent record, regardless of whether we keep the
+            // record. This keeps the iterators aligned.
+            let mut vlsd_payloads: Vec<Vec<u8>> = Vec::with_capacity(vlsd_states.len());
+            for state in vlsd_states.iter_mut() {
+                match state.iter.next_record() {
+                    Some(Ok(slice)) => vlsd_payloads.push(slice.to_vec()),
+                    Some(Err(e)) => return Err(e),
+                    None => {
+                        return Err(MdfError::BlockSerializationError(
+                            "VLSD entry count fewer than parent records".into(),
+                        ));
+                    }
+                }
+            }
+
+            record.clear();
+            record.extend_from_slice(record_chunk);
+            extra.clear();
+            match visit(&mut record, &mut extra)? {
+                RecordAction::Keep => {}
+                RecordAction::Skip => continue,
+                RecordAction::Stop => break 'outer,
+            }
+            extra.resize(extra_bytes as usize, 0);
 
-    if dst != 0 {
-        cache.insert(src_addr, dst);
-    } else {
-        // Drop the cycle-breaker placeholder if cloning ultimately failed.
-        cache.remove(&src_addr);
+            // Patch each VLSD inline slot in the parent record so
+            // the offset points at the entry's location in the
+            // freshly written ##SD block. Without this fix-up,
+            // mf4-rs's own reader still works (it walks SD entries
+            // sequentially) but spec-conformant readers like
+            // asammdf — which use the inline offset to locate the
+            // entry — will produce wrong/short results.
+            for state in &vlsd_states {
+                if state.slot_size == 0 {
+                    continue;
+                }
+                let end = state.slot_off + state.slot_size;
+                if end > data_end {
+                    continue;
+                }
+                let off_bytes = state.next_offset.to_le_bytes();
+                let copy_len = state.slot_size.min(off_bytes.len());
+                record[state.slot_off..state.slot_off + copy_len]
+                    .copy_from_slice(&off_bytes[..copy_len]);
+                // Zero any trailing bytes when the slot is
+                // wider than 8 (extremely unusual for VLSD).
+                for b in &mut record[state.slot_off + copy_len..end] {
+                    *b = 0;
+                }
+            }
+            if extra.is_empty() {
+                writer.write_raw_record(cg_id, &record)?;
+            } else {
+                let mut widened: Vec<u8> = Vec::with_capacity(record_size + extra.len());
+                widened.extend_from_slice(&record[..data_end]);
+                widened.extend_from_slice(&extra);
+                widened.extend_from_slice(&record[data_end..]);
+                writer.write_raw_record(cg_id, &widened)?;
+            }
+            for (state, payload) in vlsd_states.iter_mut().zip(vlsd_payloads.iter()) {
+                writer.write_signal_data(&state.cn_id, payload)?;
+                *state.next_offset =
+                    state.next_offset.saturating_add(4 + payload.len() as u64);
+            }
+        }
     }
-    Ok(dst)
+    Ok(())
 }
 
 /// Cut a segment of an MDF file using **absolute** UNIX-epoch timestamps.
@@ -151,15 +576,15 @@ fn clone_block_to_writer(
 /// * `start_ns` - Start of the window in UNIX-epoch nanoseconds (inclusive)
 /// * `end_ns` - End of the window in UNIX-epoch nanoseconds (inclusive)
 pub fn cut_mdf_by_utc_ns(
-    input_path: &str,
-    output_path: &str,
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
     start_ns: i64,
     end_ns: i64,
 ) -> Result<(), MdfError> {
     // Peek at the source file just to read its absolute start time. This
     // mirrors the parse the main cut routine performs immediately after, but
     // we keep the two parses separate so the time math is self-contained.
-    let mdf_for_anchor = MdfFile::parse_from_file(input_path)?;
+    let mdf_for_anchor = MdfFile::parse_from_file(&input_path)?;
     let file_start_ns: u64 = mdf_for_anchor.header.abs_time;
     if file_start_ns == 0 {
         return Err(MdfError::BlockSerializationError(
@@ -203,11 +628,41 @@ pub fn cut_mdf_by_utc_ns(
 /// # Returns
 /// `Ok(())` on success or an [`MdfError`] if reading or writing fails.
 pub fn cut_mdf_by_time(
-    input_path: &str,
-    output_path: &str,
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    start_time: f64,
+    end_time: f64,
+) -> Result<(), MdfError> {
+    cut_mdf_by_time_with_mapping(input_path, output_path, start_time, end_time, &ChannelMapping::default())
+}
+
+/// Cut a segment of an MDF file like [`cut_mdf_by_time`], renaming channels
+/// and overriding their units according to `mapping`.
+pub fn cut_mdf_by_time_with_mapping(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    start_time: f64,
+    end_time: f64,
+    mapping: &ChannelMapping,
+) -> Result<(), MdfError> {
+    let options = CutOptions { mapping: mapping.clone(), ..CutOptions::default() };
+    cut_mdf_by_time_with_options(input_path, output_path, start_time, end_time, &options)
+}
+
+/// Cut a segment of an MDF file like [`cut_mdf_by_time`] with the channel
+/// mapping of `options`, optionally preserving the blocks outside the
+/// channel groups (see [`CutOptions::preserve_blocks`]).
+///
+/// Preserved blocks are copied as they are: events and other blocks that
+/// refer to times outside the kept window are not adjusted.
+pub fn cut_mdf_by_time_with_options(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
     start_time: f64,
     end_time: f64,
+    options: &CutOptions,
 ) -> Result<(), MdfError> {
+    let mapping = &options.mapping;
     let mdf = MdfFile::parse_from_file(input_path)?;
     let mut writer = MdfWriter::new(output_path)?;
     writer.init_mdf_file()?;
@@ -229,175 +684,17 @@ pub fn cut_mdf_by_time(
     // emitted once.
     let mut block_cache: HashMap<u64, u64> = HashMap::new();
 
-    for dg in &mdf.data_groups {
+    let sources = source_groups(&mdf);
+    for (dg, dg_sources) in mdf.data_groups.iter().zip(&sources) {
         let record_id_len = dg.block.record_id_len;
 
-        let mut prev_cg: Option<String> = None;
-        for cg in &dg.channel_groups {
-            let samples_byte_nr = cg.block.samples_byte_nr;
-            let invalidation_bytes_nr = cg.block.invalidation_bytes_nr;
-            let record_size = record_id_len as usize
-                + samples_byte_nr as usize
-                + invalidation_bytes_nr as usize;
-
-            let cg_id = writer.add_channel_group(prev_cg.as_deref(), |_| {})?;
-            prev_cg = Some(cg_id.clone());
-
-            // Carry over the channel-group acq_name / acq_source / comment
-            // blocks from the source. Link offsets in the ##CG block:
-            //   40 = acq_name_addr, 48 = acq_source_addr, 64 = comment_addr.
-            let cg_pos = writer
-                .get_block_position(&cg_id)
-                .ok_or_else(|| MdfError::BlockLinkError(format!("cg '{}' not found", cg_id)))?;
-            let new_acq_name =
-                clone_block_to_writer(&mut writer, &mdf.mmap, cg.block.acq_name_addr, &mut block_cache)?;
-            if new_acq_name != 0 {
-                writer.update_link(cg_pos + 40, new_acq_name)?;
-            }
-            let new_acq_source = clone_block_to_writer(
-                &mut writer,
-                &mdf.mmap,
-                cg.block.acq_source_addr,
-                &mut block_cache,
-            )?;
-            if new_acq_source != 0 {
-                writer.update_link(cg_pos + 48, new_acq_source)?;
-            }
-            let new_cg_comment =
-                clone_block_to_writer(&mut writer, &mdf.mmap, cg.block.comment_addr, &mut block_cache)?;
-            if new_cg_comment != 0 {
-                writer.update_link(cg_pos + 64, new_cg_comment)?;
-            }
-
-            // Re-create channel blocks in the output. Stale link addresses
-            // pointing into the source file are zeroed out so the resulting
-            // channel block is self-contained. After the channel block is
-            // written, source/conversion/unit/comment blocks are cloned from
-            // the source file and the channel's links are patched to point
-            // at the freshly written copies. The VLSD `data` link is patched
-            // later by `finish_signal_data_block`.
-            let mut prev_cn: Option<String> = None;
-            // (out_cn_id, source_channel_index, is_vlsd)
-            let mut out_channels: Vec<(String, usize, bool)> = Vec::new();
-            for (idx, ch) in cg.raw_channels.iter().enumerate() {
-                let mut block = ch.block.clone();
-                block.resolve_name(&mdf.mmap)?;
-
-                let is_vlsd = block.channel_type == 1 && block.data != 0;
-
-                // Capture the source-file link addresses before zeroing them
-                // on the block we hand to `add_channel`.
-                let src_source_addr = block.source_addr;
-                let src_conversion_addr = block.conversion_addr;
-                let src_unit_addr = block.unit_addr;
-                let src_comment_addr = block.comment_addr;
-
-                // Drop links to source-file blocks we do not re-emit. Without
-                // this, the new file would carry pointers into garbage.
-                block.conversion_addr = 0;
-                block.conversion = None;
-                block.source_addr = 0;
-                block.unit_addr = 0;
-                block.comment_addr = 0;
-                block.component_addr = 0;
-                block.data = 0;
-
-                let cn_id = writer.add_channel(&cg_id, prev_cn.as_deref(), |c| {
-                    *c = block.clone();
-                })?;
-
-                // Clone source/conversion/unit/comment blocks (recursively
-                // following nested links) and patch the channel's links.
-                // Channel block link offsets: source 48, conversion 56,
-                // unit 72, comment 80.
-                let cn_pos = writer.get_block_position(&cn_id).ok_or_else(|| {
-                    MdfError::BlockLinkError(format!("cn '{}' not found", cn_id))
-                })?;
-                let new_source =
-                    clone_block_to_writer(&mut writer, &mdf.mmap, src_source_addr, &mut block_cache)?;
-                if new_source != 0 {
-                    writer.update_link(cn_pos + 48, new_source)?;
-                }
-                let new_conv = clone_block_to_writer(
-                    &mut writer,
-                    &mdf.mmap,
-                    src_conversion_addr,
-                    &mut block_cache,
-                )?;
-                if new_conv != 0 {
-                    writer.update_link(cn_pos + 56, new_conv)?;
-                }
-                let new_unit =
-                    clone_block_to_writer(&mut writer, &mdf.mmap, src_unit_addr, &mut block_cache)?;
-                if new_unit != 0 {
-                    writer.update_link(cn_pos + 72, new_unit)?;
-                }
-                let new_comment =
-                    clone_block_to_writer(&mut writer, &mdf.mmap, src_comment_addr, &mut block_cache)?;
-                if new_comment != 0 {
-                    writer.update_link(cn_pos + 80, new_comment)?;
-                }
-
-                prev_cn = Some(cn_id.clone());
-                out_channels.push((cn_id, idx, is_vlsd));
-            }
-
-            // Open the output DT block using the source's exact record
-            // layout (including invalidation bytes), and open one ##SD chain
-            // per VLSD channel. We must remember the channel ids before the
-            // mutable borrow of the writer ends.
-            writer.start_data_block_for_cg_raw(
-                &cg_id,
-                record_id_len,
-                samples_byte_nr,
-                invalidation_bytes_nr,
-            )?;
-
-            let vlsd_out_ids: Vec<String> = out_channels
-                .iter()
-                .filter_map(|(cn_id, _, is_vlsd)| {
-                    if *is_vlsd { Some(cn_id.clone()) } else { None }
-                })
-                .collect();
-            for cn_id in &vlsd_out_ids {
-                writer.start_signal_data_block(cn_id)?;
-            }
-
-            // Build VLSD source iterators (lockstep with parent records). For
-            // each VLSD channel we also record the inline-slot location and
-            // size in the parent record, plus a running offset into the new
-            // ##SD block. The inline slot in MDF4 is the byte offset within
-            // the SD data section where this entry lives — readers (e.g.
-            // asammdf) use it to look up the entry. Since we are writing a
-            // freshly numbered SD block, we must overwrite the source-file
-            // offsets with offsets valid in the output.
-            //
-            // Per spec, the slot starts at `record_id_len + byte_offset` and
-            // is `bit_count / 8` bytes wide. mf4-rs writes VLSD entries
-            // sequentially as `[u32 length][bytes]`, so each entry advances
-            // the running offset by `4 + payload.len()`.
-            struct VlsdState<'a> {
-                cn_id: String,
-                slot_off: usize,
-                slot_size: usize,
-                next_offset: u64,
-                iter: Box<dyn Iterator<Item = Result<&'a [u8], MdfError>> + 'a>,
-            }
-            let mut vlsd_states: Vec<VlsdState> = Vec::new();
-            for (cn_id, src_idx, is_vlsd) in &out_channels {
-                if *is_vlsd {
-                    let ch_block = &cg.raw_channels[*src_idx].block;
-                    let slot_size = (ch_block.bit_count / 8) as usize;
-                    let slot_off = record_id_len as usize + ch_block.byte_offset as usize;
-                    let it = cg.raw_channels[*src_idx].records(dg, cg, &mdf.mmap)?;
-                    vlsd_states.push(VlsdState {
-                        cn_id: cn_id.clone(),
-                        slot_off,
-                        slot_size,
-                        next_offset: 0,
-                        iter: it,
-                    });
-                }
+        for (cg, source) in dg.channel_groups.iter().zip(dg_sources) {
+            let (cg_id, out_channels) =
+                copy_group_structure(&mut writer, &mdf.mmap, cg, mapping, &mut block_cache)?;
+            if options.preserve_blocks {
+                let cn_ids: Vec<ChannelId> = out_channels.iter().map(|&(id, _, _)| id).collect();
+                map_group(&writer, &mut block_cache, source, &cg_id, &cn_ids);
+                copy_dg_comment(&mut writer, &mdf.mmap, source, &cg_id, &mut block_cache)?;
             }
 
             // Identify the master/time channel in the source CG.
@@ -405,118 +702,130 @@ pub fn cut_mdf_by_time(
                 c.block.channel_type == 2 && c.block.sync_type == 1
             });
 
-            // Iterate raw parent records from the source DT/DL chain.
-            let blocks = dg.data_blocks(&mdf.mmap)?;
-            'outer: for data_block in blocks {
-                let raw = data_block.data;
-                if record_size == 0 {
-                    // Degenerate CG with no record bytes — nothing to do.
-                    break;
-                }
-                let valid_len = (raw.len() / record_size) * record_size;
-                for record_chunk in raw[..valid_len].chunks_exact(record_size) {
-                    // Pull one VLSD entry per VLSD channel in lockstep with
-                    // the parent record, regardless of whether we keep the
-                    // record. This keeps the iterators aligned.
-                    let mut vlsd_payloads: Vec<Vec<u8>> = Vec::with_capacity(vlsd_states.len());
-                    for state in vlsd_states.iter_mut() {
-                        match state.iter.next() {
-                            Some(Ok(slice)) => vlsd_payloads.push(slice.to_vec()),
-                            Some(Err(e)) => return Err(e),
-                            None => {
-                                return Err(MdfError::BlockSerializationError(
-                                    "VLSD entry count fewer than parent records".into(),
-                                ));
-                            }
-                        }
-                    }
+            copy_group_records(&mut writer, &mdf.mmap, dg, cg, &cg_id, &out_channels, 0, |record_chunk, _| {
+                // Decide whether this record falls in the time window.
+                let Some(ti) = time_idx else {
+                    // No master channel — copy everything.
+                    return Ok(RecordAction::Keep);
+                };
+                let ch = &cg.raw_channels[ti].block;
+                let raw_val = decode_channel_value(record_chunk, record_id_len as usize, ch)
+                    .unwrap_or(DecodedValue::Unknown);
+                let phys = if let Some(conv) = &ch.conversion {
+                    conv.apply_decoded(raw_val, &mdf.mmap)?
+                } else {
+                    raw_val
+                };
+                let t = match phys {
+                    DecodedValue::Float(f) => f,
+                    DecodedValue::UnsignedInteger(u) => u as f64,
+                    DecodedValue::SignedInteger(i) => i as f64,
+                    _ => return Ok(RecordAction::Skip),
+                };
+                Ok(if t < start_time {
+                    RecordAction::Skip
+                } else if t - end_time > f64::EPSILON {
+                    // Match the legacy epsilon comparison so floats
+                    // produced by `i * 0.1` style timestamps remain
+                    // inclusive of the upper bound.
+                    RecordAction::Stop
+                } else {
+                    RecordAction::Keep
+                })
+            })?;
+        }
+    }
 
-                    // Decide whether this record falls in the time window.
-                    let keep = if let Some(ti) = time_idx {
-                        let ch = &cg.raw_channels[ti].block;
-                        let raw_val = decode_channel_value(
-                            record_chunk,
-                            record_id_len as usize,
-                            ch,
-                        )
-                        .unwrap_or(DecodedValue::Unknown);
-                        let phys = if let Some(conv) = &ch.conversion {
-                            conv.apply_decoded(raw_val, &mdf.mmap)?
-                        } else {
-                            raw_val
-                        };
-                        let t = match phys {
-                            DecodedValue::Float(f) => f,
-                            DecodedValue::UnsignedInteger(u) => u as f64,
-                            DecodedValue::SignedInteger(i) => i as f64,
-                            _ => continue,
-                        };
-                        if t < start_time {
-                            false
-                        } else if t - end_time > f64::EPSILON {
-                            // Match the legacy epsilon comparison so floats
-                            // produced by `i * 0.1` style timestamps remain
-                            // inclusive of the upper bound.
-                            break 'outer;
-                        } else {
-                            true
-                        }
-                    } else {
-                        // No master channel — copy everything.
-                        true
-                    };
-
-                    if keep {
-                        // Patch each VLSD inline slot in the parent record so
-                        // the offset points at the entry's location in the
-                        // freshly written ##SD block. Without this fix-up,
-                        // mf4-rs's own reader still works (it walks SD entries
-                        // sequentially) but spec-conformant readers like
-                        // asammdf — which use the inline offset to locate the
-                        // entry — will produce wrong/short results.
-                        let needs_patch = vlsd_states
-                            .iter()
-                            .any(|s| s.slot_size > 0 && s.slot_off + s.slot_size <= record_chunk.len());
-                        if needs_patch {
-                            let mut patched: Vec<u8> = record_chunk.to_vec();
-                            for (state, payload) in vlsd_states.iter().zip(vlsd_payloads.iter()) {
-                                if state.slot_size == 0 {
-                                    continue;
-                                }
-                                let end = state.slot_off + state.slot_size;
-                                if end > patched.len() {
-                                    continue;
-                                }
-                                let off_bytes = state.next_offset.to_le_bytes();
-                                let copy_len = state.slot_size.min(off_bytes.len());
-                                patched[state.slot_off..state.slot_off + copy_len]
-                                    .copy_from_slice(&off_bytes[..copy_len]);
-                                // Zero any trailing bytes when the slot is
-                                // wider than 8 (extremely unusual for VLSD).
-                                for b in &mut patched[state.slot_off + copy_len..end] {
-                                    *b = 0;
-                                }
-                                let _ = payload; // not used here; advance below
-                            }
-                            writer.write_raw_record(&cg_id, &patched)?;
-                        } else {
-                            writer.write_raw_record(&cg_id, record_chunk)?;
-                        }
-                        for (state, payload) in vlsd_states.iter_mut().zip(vlsd_payloads.iter()) {
-                            writer.write_signal_data(&state.cn_id, payload)?;
-                            state.next_offset =
-                                state.next_offset.saturating_add(4 + payload.len() as u64);
-                        }
-                    }
-                }
-            }
+    if options.preserve_blocks {
+        HeaderChains::new(&writer)?.append(&mut writer, &mdf, &mut block_cache)?;
+    }
+    writer.finalize()
+}
 
-            for cn_id in &vlsd_out_ids {
-                writer.finish_signal_data_block(cn_id)?;
+/// Cut an MDF file by a condition on its record values, keeping only the
+/// records for which `predicate` returns `true`.
+///
+/// `predicate` is called with every record of each channel group holding at
+/// least one of `channels`, and sees the physical values of those channels
+/// through a [`Record`]; a channel that is not in the group, is invalid in
+/// the record or is a VLSD channel reads as `None`. Groups without any of
+/// `channels` are copied in full. Records are streamed into new data blocks
+/// with their source layout as in [`cut_mdf_by_time`], and the channel
+/// groups' cycle counts follow the kept records.
+///
+/// Returns [`MdfError::ChannelNotFound`] if one of `channels` is in no group.
+///
+/// ```no_run
+/// # use mf4_rs::cut::cut_where;
+/// // Keep the records recorded while the engine was running.
+/// cut_where("drive.mf4", "running.mf4", &["EngineSpeed"], |r| {
+///     r.f64("EngineSpeed").is_some_and(|rpm| rpm > 0.0)
+/// })?;
+/// # Ok::<(), mf4_rs::error::MdfError>(())
+/// ```
+pub fn cut_where<F>(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    channels: &[&str],
+    mut predicate: F,
+) -> Result<(), MdfError>
+where
+    F: FnMut(&Record<'_>) -> bool,
+{
+    let mdf = MdfFile::parse_from_file(input_path)?;
+
+    // Per channel group, the indices and names of its selected channels.
+    let mut selections: Vec<(Vec<usize>, Vec<Option<String>>)> = Vec::new();
+    let mut found = vec![false; channels.len()];
+    for dg in &mdf.data_groups {
+        for cg in &dg.channel_groups {
+            let (mut indices, mut names) = (Vec::new(), Vec::new());
+            for (i, ch) in cg.raw_channels.iter().enumerate() {
+                let name = read_string_block(&mdf.mmap, ch.block.name_addr)?;
+                if let Some(k) = channels.iter().position(|c| name.as_deref() == Some(c)) {
+                    found[k] = true;
+                    indices.push(i);
+                    names.push(name);
+                }
             }
-            writer.finish_data_block(&cg_id)?;
+            selections.push((indices, names));
         }
     }
+    if let Some(k) = found.iter().position(|f| !f) {
+        return Err(MdfError::ChannelNotFound { name: channels[k].to_string(), group: None });
+    }
 
+    let mut writer = MdfWriter::new(output_path)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        mdf.header.abs_time,
+        mdf.header.tz_offset,
+        mdf.header.daylight_save_time,
+        mdf.header.time_flags,
+        mdf.header.time_quality,
+    )?;
+
+    let mut block_cache: HashMap<u64, u64> = HashMap::new();
+    let groups = mdf.data_groups.iter().flat_map(|dg| dg.channel_groups.iter().map(move |cg| (dg, cg)));
+    for ((dg, cg), (indices, names)) in groups.zip(&selections) {
+        let record_id_len = dg.block.record_id_len as usize;
+        let (cg_id, out_channels) =
+            copy_group_structure(&mut writer, &mdf.mmap, cg, &ChannelMapping::default(), &mut block_cache)?;
+        let mut values = Vec::with_capacity(indices.len());
+        copy_group_records(&mut writer, &mdf.mmap, dg, cg, &cg_id, &out_channels, 0, |record, _| {
+            if indices.is_empty() {
+                return Ok(RecordAction::Keep);
+            }
+            values.clear();
+            for &i in indices {
+                values.push(physical_value(record, record_id_len, cg, &cg.raw_channels[i].block, &mdf.mmap)?);
+            }
+            Ok(if predicate(&Record { names, values: &values }) {
+                RecordAction::Keep
+            } else {
+                RecordAction::Skip
+            })
+        })?;
+    }
     writer.finalize()
 }
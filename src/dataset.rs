@@ -0,0 +1,339 @@
+//! A virtual concatenation of files recorded back-to-back.
+//!
+//! [`Dataset`] opens several same-structured files (e.g. the parts of a
+//! logger recording split by size or duration) and reads them as one: group
+//! `k` of the dataset is group `k` of every file, and its channels decode
+//! to the samples of all files in order. Unlike
+//! [`merge::concatenate`](crate::merge::concatenate) nothing is written;
+//! the files are stitched together while decoding.
+//!
+//! ```no_run
+//! # use mf4_rs::dataset::Dataset;
+//! let dataset = Dataset::open(&["drive_001.mf4", "drive_002.mf4", "drive_003.mf4"])?;
+//! let groups = dataset.channel_groups()?;
+//! let speed = groups[0].channel("Speed").map(|ch| ch.values_f64()).transpose()?;
+//! # Ok::<(), mf4_rs::error::MdfError>(())
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use crate::api::channel::Channel;
+use crate::api::channel_group::ChannelGroup;
+use crate::api::mdf::MDF;
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+use crate::signal::Signal;
+
+/// Several files read as one recording.
+///
+/// The master values of every file are shifted by the difference between
+/// its start time and that of the first file, so the time is continuous
+/// across the files (files without a start time are not shifted). Records
+/// at the start of a file whose shifted master value does not lie after the
+/// last one of the previous files, as written by loggers that repeat the
+/// end of a part at the start of the next, are dropped.
+pub struct Dataset {
+    files: Vec<MDF>,
+    offsets: Vec<f64>,
+}
+
+/// How the records of one file continue a dataset group.
+#[derive(Debug, Clone, Copy)]
+struct Stitch {
+    /// Seconds added to the master values.
+    offset: f64,
+    /// Records dropped at the start of the file.
+    skip: usize,
+}
+
+impl Dataset {
+    /// Open the files at `paths`, given in recording order.
+    ///
+    /// Not available on `wasm32-unknown-unknown`; use [`from_mdfs`] instead.
+    ///
+    /// [`from_mdfs`]: Self::from_mdfs
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(paths: &[impl AsRef<Path>]) -> Result<Self, MdfError> {
+        let files = paths.iter().map(MDF::from_file).collect::<Result<Vec<_>, _>>()?;
+        Self::from_mdfs(files)
+    }
+
+    /// Build a dataset from files already opened, in recording order.
+    ///
+    /// Every file must have as many channel groups as the first one, with
+    /// the same channel names in the same order; otherwise
+    /// [`MdfError::LayoutMismatch`] names the first difference.
+    pub fn from_mdfs(files: Vec<MDF>) -> Result<Self, MdfError> {
+        let Some(first) = files.first() else {
+            return Err(MdfError::InvalidIndex { kind: "file", index: 0, len: 0 });
+        };
+        let expected = channel_names(first)?;
+        for (i, file) in files.iter().enumerate().skip(1) {
+            let found = channel_names(file)?;
+            if found.len() != expected.len() {
+                return Err(MdfError::LayoutMismatch(format!(
+                    "file {i}: {} channel groups, expected {}",
+                    found.len(),
+                    expected.len()
+                )));
+            }
+            for (k, (names, want)) in found.iter().zip(&expected).enumerate() {
+                if names != want {
+                    return Err(MdfError::LayoutMismatch(format!(
+                        "file {i}: group {k} has channels {names:?}, expected {want:?}"
+                    )));
+                }
+            }
+        }
+
+        let offsets = files
+            .iter()
+            .map(|file| match (first.start_time_ns(), file.start_time_ns()) {
+                (Some(t0), Some(t)) => (t as f64 - t0 as f64) / 1e9,
+                _ => 0.0,
+            })
+            .collect();
+        Ok(Dataset { files, offsets })
+    }
+
+    /// The files of the dataset, in recording order.
+    pub fn files(&self) -> &[MDF] {
+        &self.files
+    }
+
+    /// Start time of the dataset (that of the first file) in nanoseconds
+    /// since epoch, `None` if it is not set.
+    pub fn start_time_ns(&self) -> Option<u64> {
+        self.files[0].start_time_ns()
+    }
+
+    /// The channel groups of the dataset, in file order.
+    ///
+    /// The master channel of every group is decoded to work out where the
+    /// files join; no other samples are decoded.
+    pub fn channel_groups(&self) -> Result<Vec<DatasetGroup<'_>>, MdfError> {
+        let mut parts: Vec<Vec<ChannelGroup<'_>>> = Vec::new();
+        for file in &self.files {
+            for (k, group) in file.channel_groups().into_iter().enumerate() {
+                match parts.get_mut(k) {
+                    Some(groups) => groups.push(group),
+                    None => parts.push(vec![group]),
+                }
+            }
+        }
+        parts.into_iter().map(|parts| DatasetGroup::new(parts, &self.offsets)).collect()
+    }
+
+    /// Find a channel by name in any group of the dataset (first match).
+    pub fn channel(&self, name: &str) -> Result<Option<DatasetChannel<'_>>, MdfError> {
+        Ok(self.channel_groups()?.iter().find_map(|group| group.channel(name)))
+    }
+
+    /// The earliest and latest master value of all groups, in seconds
+    /// relative to the start of the first file; `None` if no group has a
+    /// master channel with valid samples.
+    pub fn time_range(&self) -> Result<Option<(f64, f64)>, MdfError> {
+        let mut range: Option<(f64, f64)> = None;
+        for group in self.channel_groups()? {
+            if let Some((start, end)) = group.time_range()? {
+                range = Some(match range {
+                    Some((lo, hi)) => (lo.min(start), hi.max(end)),
+                    None => (start, end),
+                });
+            }
+        }
+        Ok(range)
+    }
+}
+
+/// The channel names of every group of `file`.
+fn channel_names(file: &MDF) -> Result<Vec<Vec<Option<String>>>, MdfError> {
+    file.channel_groups()
+        .iter()
+        .map(|group| group.channels().iter().map(|ch| ch.name()).collect())
+        .collect()
+}
+
+/// One channel group of a [`Dataset`]: the matching group of every file.
+pub struct DatasetGroup<'a> {
+    parts: Vec<ChannelGroup<'a>>,
+    stitches: Vec<Stitch>,
+}
+
+impl<'a> DatasetGroup<'a> {
+    fn new(parts: Vec<ChannelGroup<'a>>, offsets: &[f64]) -> Result<Self, MdfError> {
+        let mut stitches = Vec::with_capacity(parts.len());
+        let mut last = f64::NEG_INFINITY;
+        for (part, &offset) in parts.iter().zip(offsets) {
+            let times = part.time_values()?.unwrap_or_default();
+            let skip = times.iter().take_while(|&&t| t + offset <= last).count();
+            if let Some(end) = times[skip..].iter().rev().find(|t| !t.is_nan()) {
+                last = end + offset;
+            }
+            stitches.push(Stitch { offset, skip });
+        }
+        Ok(DatasetGroup { parts, stitches })
+    }
+
+    /// The group of each file, in recording order.
+    pub fn parts(&self) -> &[ChannelGroup<'a>] {
+        &self.parts
+    }
+
+    /// The group name, taken from the first file.
+    pub fn name(&self) -> Result<Option<String>, MdfError> {
+        self.parts[0].name()
+    }
+
+    /// Number of records of the group across all files, without the
+    /// records dropped where the files overlap.
+    pub fn record_count(&self) -> u64 {
+        self.parts
+            .iter()
+            .zip(&self.stitches)
+            .map(|(part, stitch)| part.raw_channel_group().block.cycles_nr.saturating_sub(stitch.skip as u64))
+            .sum()
+    }
+
+    /// The channels of the group.
+    pub fn channels(&self) -> Vec<DatasetChannel<'a>> {
+        let mut channels: Vec<Vec<Channel<'a>>> = Vec::new();
+        for part in &self.parts {
+            for (i, channel) in part.channels().into_iter().enumerate() {
+                match channels.get_mut(i) {
+                    Some(parts) => parts.push(channel),
+                    None => channels.push(vec![channel]),
+                }
+            }
+        }
+        channels
+            .into_iter()
+            .map(|parts| DatasetChannel { parts, stitches: self.stitches.clone() })
+            .collect()
+    }
+
+    /// Find a channel of the group by name (first match).
+    pub fn channel(&self, name: &str) -> Option<DatasetChannel<'a>> {
+        let parts = self.parts.iter().map(|part| part.channel(name)).collect::<Option<Vec<_>>>()?;
+        Some(DatasetChannel { parts, stitches: self.stitches.clone() })
+    }
+
+    /// The group's master channel, if any.
+    pub fn master_channel(&self) -> Option<DatasetChannel<'a>> {
+        let parts = self.parts.iter().map(|part| part.master_channel()).collect::<Option<Vec<_>>>()?;
+        Some(DatasetChannel { parts, stitches: self.stitches.clone() })
+    }
+
+    /// The master values of all files in seconds relative to the start of
+    /// the first file, with `NaN` for invalid samples.
+    ///
+    /// Returns `Ok(None)` if the group has no master channel.
+    pub fn time_values(&self) -> Result<Option<Vec<f64>>, MdfError> {
+        let Some(master) = self.master_channel() else { return Ok(None) };
+        let values = master.values_f64()?;
+        Ok(Some(values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect()))
+    }
+
+    /// The first and last valid master value, in seconds relative to the
+    /// start of the first file; `None` if there is none.
+    pub fn time_range(&self) -> Result<Option<(f64, f64)>, MdfError> {
+        let Some(times) = self.time_values()? else { return Ok(None) };
+        let first = times.iter().copied().find(|t| !t.is_nan());
+        let last = times.iter().copied().rfind(|t| !t.is_nan());
+        Ok(first.zip(last))
+    }
+
+    /// The channel `name` of the group paired with the group's master
+    /// values, or `None` if the group has no such channel.
+    ///
+    /// As with [`MDF::signal`](crate::api::mdf::MDF::signal), the
+    /// timestamps are empty for the master channel itself.
+    pub fn signal(&self, name: &str) -> Result<Option<Signal>, MdfError> {
+        let Some(channel) = self.channel(name) else { return Ok(None) };
+        let timestamps = if channel.is_master() {
+            Vec::new()
+        } else {
+            self.time_values()?.unwrap_or_default()
+        };
+        Ok(Some(Signal {
+            name: name.to_string(),
+            unit: channel.unit()?,
+            timestamps,
+            values: channel.values()?,
+        }))
+    }
+}
+
+/// One channel of a [`Dataset`]: the matching channel of every file.
+pub struct DatasetChannel<'a> {
+    parts: Vec<Channel<'a>>,
+    stitches: Vec<Stitch>,
+}
+
+impl<'a> DatasetChannel<'a> {
+    /// The channel of each file, in recording order.
+    pub fn parts(&self) -> &[Channel<'a>] {
+        &self.parts
+    }
+
+    /// The channel name, taken from the first file.
+    pub fn name(&self) -> Result<Option<String>, MdfError> {
+        self.parts[0].name()
+    }
+
+    /// The physical unit, taken from the first file.
+    pub fn unit(&self) -> Result<Option<String>, MdfError> {
+        self.parts[0].unit()
+    }
+
+    /// `true` for the master (time) channel of the group, whose values are
+    /// shifted to the time base of the first file.
+    pub fn is_master(&self) -> bool {
+        let block = self.parts[0].block();
+        block.channel_type == 2 && block.sync_type == 1
+    }
+
+    /// Decode the samples of all files with conversions applied, `None`
+    /// marking invalid samples. Master values are returned as floats in
+    /// seconds relative to the start of the first file.
+    pub fn values(&self) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        if self.is_master() {
+            let values = self.values_f64()?;
+            return Ok(values.into_iter().map(|v| v.map(DecodedValue::Float)).collect());
+        }
+        self.collect(|channel, _| channel.values())
+    }
+
+    /// Decode the samples of all files as `f64` with conversions applied,
+    /// `None` marking invalid or non-numeric samples. Master values are
+    /// shifted to the time base of the first file.
+    pub fn values_f64(&self) -> Result<Vec<Option<f64>>, MdfError> {
+        let master = self.is_master();
+        self.collect(|channel, stitch| {
+            let mut values = channel.values_f64()?;
+            if master {
+                values.iter_mut().flatten().for_each(|v| *v += stitch.offset);
+            }
+            Ok(values)
+        })
+    }
+
+    /// Decode the samples of all files as strings, `None` marking invalid
+    /// or non-text samples.
+    pub fn values_string(&self) -> Result<Vec<Option<String>>, MdfError> {
+        self.collect(|channel, _| channel.values_string())
+    }
+
+    /// Decode every part with `decode` and concatenate the results without
+    /// the records dropped where the files overlap.
+    fn collect<T>(&self, decode: impl Fn(&Channel<'a>, &Stitch) -> Result<Vec<T>, MdfError>) -> Result<Vec<T>, MdfError> {
+        let mut values = Vec::new();
+        for (part, stitch) in self.parts.iter().zip(&self.stitches) {
+            let mut decoded = decode(part, stitch)?;
+            decoded.drain(..stitch.skip.min(decoded.len()));
+            values.extend(decoded);
+        }
+        Ok(values)
+    }
+}
@@ -0,0 +1,364 @@
+//! Minimal DBC support: parse CAN message and signal definitions and decode
+//! them from raw CAN frames.
+//!
+//! Only the `BO_` (message) and `SG_` (signal) statements are interpreted,
+//! including simple multiplexing (`M` / `m<n>`); every other statement is
+//! skipped. [`extract_bus_logging`] uses a [`Dbc`] to turn the
+//! `CAN_DataFrame` groups of a recording into one channel group per message.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::blocks::channel_block::CN_FLAG_INVAL_BIT_VALID;
+use crate::error::MdfError;
+
+/// Bit 31 of a DBC message ID marks a 29 bit extended identifier.
+const DBC_EXTENDED_ID: u32 = 0x8000_0000;
+
+/// Bit layout of a signal inside the frame payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalByteOrder {
+    /// Intel (`@1`): `start_bit` is the least significant bit.
+    LittleEndian,
+    /// Motorola (`@0`): `start_bit` is the most significant bit, counted in
+    /// the DBC "sawtooth" bit numbering.
+    BigEndian,
+}
+
+/// Role of a signal in a multiplexed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexing {
+    /// Present in every frame.
+    None,
+    /// The multiplexer switch (`M`).
+    Multiplexor,
+    /// Present only while the multiplexer has this raw value (`m<n>`).
+    Multiplexed(u64),
+}
+
+/// One `SG_` signal definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbcSignal {
+    pub name: String,
+    pub start_bit: u32,
+    pub length: u32,
+    pub byte_order: SignalByteOrder,
+    pub signed: bool,
+    pub factor: f64,
+    pub offset: f64,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub unit: String,
+    pub multiplexing: Multiplexing,
+}
+
+impl DbcSignal {
+    /// The raw, unscaled bits of the signal, or `None` when `data` is too
+    /// short to hold them.
+    pub fn raw_value(&self, data: &[u8]) -> Option<u64> {
+        if self.length == 0 || self.length > 64 {
+            return None;
+        }
+        let bit = |pos: u32| -> Option<u64> {
+            let byte = data.get(pos as usize / 8)?;
+            Some(u64::from(byte >> (pos % 8)) & 1)
+        };
+        let mut raw = 0u64;
+        match self.byte_order {
+            SignalByteOrder::LittleEndian => {
+                for i in 0..self.length {
+                    raw |= bit(self.start_bit + i)? << i;
+                }
+            }
+            SignalByteOrder::BigEndian => {
+                let mut pos = self.start_bit;
+                for _ in 0..self.length {
+                    raw = (raw << 1) | bit(pos)?;
+                    // Walk towards the LSB; at the end of a byte continue
+                    // with the MSB of the next one.
+                    pos = if pos.is_multiple_of(8) { pos + 15 } else { pos - 1 };
+                }
+            }
+        }
+        Some(raw)
+    }
+
+    /// The physical value `raw * factor + offset`, or `None` when `data` is
+    /// too short.
+    pub fn decode(&self, data: &[u8]) -> Option<f64> {
+        let raw = self.raw_value(data)?;
+        let value = if self.signed && self.length < 64 {
+            let shift = 64 - self.length;
+            (((raw << shift) as i64) >> shift) as f64
+        } else if self.signed {
+            raw as i64 as f64
+        } else {
+            raw as f64
+        };
+        Some(value * self.factor + self.offset)
+    }
+}
+
+/// One `BO_` message definition with its signals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbcMessage {
+    /// CAN identifier without the DBC extended-ID marker bit.
+    pub id: u32,
+    pub extended: bool,
+    pub name: String,
+    /// Payload size in bytes.
+    pub size: u32,
+    pub signals: Vec<DbcSignal>,
+}
+
+impl DbcMessage {
+    /// Decode every signal of the message from a frame payload, in the order
+    /// of [`signals`](Self::signals).
+    ///
+    /// A signal is `None` when the payload is too short for it or when it is
+    /// multiplexed and the multiplexer selects another value.
+    pub fn decode(&self, data: &[u8]) -> Vec<Option<f64>> {
+        let switch = self
+            .signals
+            .iter()
+            .find(|s| s.multiplexing == Multiplexing::Multiplexor)
+            .and_then(|s| s.raw_value(data));
+        self.signals
+            .iter()
+            .map(|signal| match signal.multiplexing {
+                Multiplexing::Multiplexed(value) if switch != Some(value) => None,
+                _ => signal.decode(data),
+            })
+            .collect()
+    }
+}
+
+/// A parsed DBC file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dbc {
+    pub messages: Vec<DbcMessage>,
+}
+
+impl Dbc {
+    /// Read and parse a DBC file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        // DBC files are frequently Windows-1252 encoded; non-UTF-8 bytes only
+        // ever occur in comments and units, so a lossy conversion is enough.
+        let bytes = std::fs::read(path)?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Parse the text of a DBC file.
+    pub fn parse(text: &str) -> Result<Self, MdfError> {
+        let mut messages: Vec<DbcMessage> = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| MdfError::DbcParseError {
+                line: idx + 1,
+                message: message.to_string(),
+            };
+            if let Some(rest) = line.strip_prefix("BO_ ") {
+                messages.push(parse_message(rest).ok_or_else(|| error("malformed BO_ statement"))?);
+            } else if let Some(rest) = line.strip_prefix("SG_ ") {
+                let signal = parse_signal(rest).ok_or_else(|| error("malformed SG_ statement"))?;
+                messages
+                    .last_mut()
+                    .ok_or_else(|| error("SG_ statement outside of a message"))?
+                    .signals
+                    .push(signal);
+            }
+        }
+        Ok(Dbc { messages })
+    }
+
+    /// Look up a message by CAN identifier.
+    pub fn message(&self, id: u32, extended: bool) -> Option<&DbcMessage> {
+        self.messages.iter().find(|m| m.id == id && m.extended == extended)
+    }
+}
+
+/// `<id> <name>: <size> <transmitter>`
+fn parse_message(rest: &str) -> Option<DbcMessage> {
+    let (head, tail) = rest.split_once(':')?;
+    let mut head = head.split_whitespace();
+    let raw_id: u32 = head.next()?.parse().ok()?;
+    let name = head.next()?.to_string();
+    let size = tail.split_whitespace().next()?.parse().ok()?;
+    Some(DbcMessage {
+        id: raw_id & !DBC_EXTENDED_ID,
+        extended: raw_id & DBC_EXTENDED_ID != 0,
+        name,
+        size,
+        signals: Vec::new(),
+    })
+}
+
+/// `<name> [M|m<n>] : <start>|<length>@<order><sign> (<factor>,<offset>)
+/// [<min>|<max>] "<unit>" <receivers>`
+fn parse_signal(rest: &str) -> Option<DbcSignal> {
+    let (head, tail) = rest.split_once(':')?;
+    let mut head = head.split_whitespace();
+    let name = head.next()?.to_string();
+    let multiplexing = match head.next() {
+        None => Multiplexing::None,
+        Some("M") => Multiplexing::Multiplexor,
+        // Extended multiplexing (`m<n>M`) is treated as plain `m<n>`.
+        Some(m) => Multiplexing::Multiplexed(
+            m.strip_prefix('m')?.trim_end_matches('M').parse().ok()?,
+        ),
+    };
+
+    let tail = tail.trim_start();
+    let (layout, tail) = tail.split_once(char::is_whitespace)?;
+    let (start, layout) = layout.split_once('|')?;
+    let (length, layout) = layout.split_once('@')?;
+    let mut layout = layout.chars();
+    let byte_order = match layout.next()? {
+        '1' => SignalByteOrder::LittleEndian,
+        '0' => SignalByteOrder::BigEndian,
+        _ => return None,
+    };
+    let signed = match layout.next()? {
+        '-' => true,
+        '+' => false,
+        _ => return None,
+    };
+
+    let (scaling, tail) = tail.trim_start().strip_prefix('(')?.split_once(')')?;
+    let (factor, offset) = scaling.split_once(',')?;
+    let (range, tail) = tail.trim_start().strip_prefix('[')?.split_once(']')?;
+    let (minimum, maximum) = range.split_once('|')?;
+    let (unit, _receivers) = tail.trim_start().strip_prefix('"')?.split_once('"')?;
+
+    Some(DbcSignal {
+        name,
+        start_bit: start.trim().parse().ok()?,
+        length: length.trim().parse().ok()?,
+        byte_order,
+        signed,
+        factor: factor.trim().parse().ok()?,
+        offset: offset.trim().parse().ok()?,
+        minimum: minimum.trim().parse().ok()?,
+        maximum: maximum.trim().parse().ok()?,
+        unit: unit.to_string(),
+        multiplexing,
+    })
+}
+
+/// Decode the CAN traffic of a recording with `dbc` and write the signals to
+/// a new file, one channel group per bus channel and message.
+///
+/// Every `CAN_DataFrame` group of the input is read; frames whose identifier
+/// matches a DBC message are decoded. Each output group is named after the
+/// message, has a bus source `CAN<n>`, a `Timestamp` master and one `f64`
+/// channel per signal carrying the signal's unit. Samples of multiplexed
+/// signals not present in a frame, and of signals beyond the frame's
+/// payload, are stored as invalid. Messages without frames produce no group.
+///
+/// # Arguments
+/// * `input_path` - Path to the MF4 file holding raw CAN frames
+/// * `dbc` - Message and signal definitions
+/// * `output_path` - Destination path for the decoded signals
+///
+/// # Returns
+/// `Ok(())` on success or an [`MdfError`] if reading or writing fails.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn extract_bus_logging(
+    input_path: impl AsRef<Path>,
+    dbc: &Dbc,
+    output_path: impl AsRef<Path>,
+) -> Result<(), MdfError> {
+    use crate::api::channel_group::ChannelGroup;
+    use crate::blocks::common::DataType;
+    use crate::blocks::text_block::TextBlock;
+    use crate::bus_logging::{read_can_frames, BUS_TYPE_CAN, SOURCE_TYPE_BUS};
+    use crate::parsing::decoder::DecodedValue;
+    use crate::parsing::mdf_file::MdfFile;
+    use crate::parsing::source_info::SourceInfo;
+    use crate::writer::MdfWriter;
+
+    let mdf = MdfFile::parse_from_file(input_path)?;
+
+    // (bus channel, message index) → (timestamps, decoded signal rows)
+    type Decoded = (Vec<f64>, Vec<Vec<Option<f64>>>);
+    let mut decoded: BTreeMap<(u8, usize), Decoded> = BTreeMap::new();
+    for dg in &mdf.data_groups {
+        for cg in &dg.channel_groups {
+            let group = ChannelGroup::new(dg, cg, &mdf.mmap);
+            let Some(frames) = read_can_frames(&group)? else {
+                continue;
+            };
+            for frame in frames.iter().filter(|f| f.error_type.is_none()) {
+                let Some(idx) = dbc
+                    .messages
+                    .iter()
+                    .position(|m| m.id == frame.id && m.extended == frame.extended)
+                else {
+                    continue;
+                };
+                let (timestamps, rows) = decoded.entry((frame.bus_channel, idx)).or_default();
+                timestamps.push(frame.timestamp);
+                rows.push(dbc.messages[idx].decode(&frame.data));
+            }
+        }
+    }
+
+    let mut writer = MdfWriter::new(output_path)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        mdf.header.abs_time,
+        mdf.header.tz_offset,
+        mdf.header.daylight_save_time,
+        mdf.header.time_flags,
+        mdf.header.time_quality,
+    )?;
+
+    for ((bus_channel, idx), (timestamps, rows)) in decoded {
+        let message = &dbc.messages[idx];
+        let cg_id = writer.add_channel_group(None, |cg| {
+            cg.acq_name = Some(message.name.clone());
+            cg.acq_source = Some(SourceInfo {
+                name: Some(format!("CAN{}", bus_channel)),
+                path: Some(format!("CAN{}", bus_channel)),
+                source_type: SOURCE_TYPE_BUS,
+                bus_type: BUS_TYPE_CAN,
+                ..SourceInfo::default()
+            });
+        })?;
+        let time_id = writer.add_channel(&cg_id, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.bit_count = 64;
+            ch.name = Some("Timestamp".to_string());
+        })?;
+        writer.set_time_channel(&time_id)?;
+        let mut prev = time_id;
+        for (i, signal) in message.signals.iter().enumerate() {
+            let cn_id = writer.add_channel(&cg_id, Some(&prev), |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.byte_offset = 8 * (i as u32 + 1);
+                ch.bit_count = 64;
+                ch.flags = CN_FLAG_INVAL_BIT_VALID;
+                ch.pos_invalidation_bit = i as u32;
+                ch.name = Some(signal.name.clone());
+            })?;
+            if !signal.unit.is_empty() {
+                let tx_id = format!("tx_unit_{}", cn_id);
+                writer.write_block_with_id(&TextBlock::new(&signal.unit).to_bytes()?, &tx_id)?;
+                // cn_md_unit
+                writer.update_block_link(cn_id, 72, &tx_id)?;
+            }
+            prev = cn_id;
+        }
+
+        writer.start_data_block_for_cg(&cg_id, 0)?;
+        for (t, row) in timestamps.into_iter().zip(rows) {
+            let mut values = Vec::with_capacity(row.len() + 1);
+            values.push(Some(DecodedValue::Float(t)));
+            values.extend(row.into_iter().map(|v| v.map(DecodedValue::Float)));
+            writer.write_record_with_validity(&cg_id, &values)?;
+        }
+        writer.finish_data_block(&cg_id)?;
+    }
+    writer.finalize()
+}
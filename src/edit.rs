@@ -0,0 +1,256 @@
+//! In-place edits of channel metadata in existing MDF 4 files.
+//!
+//! [`MdfEditor`] changes channel names, units, comments and conversions
+//! without copying the file. A `##TX` block is overwritten in place when the
+//! new text fits into it and no other block links to it; otherwise the new
+//! block is appended to the end of the file and the channel is relinked to it.
+//! Records are never read or moved, so edits to multi-GB files are cheap.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::blocks::common::{BlockHeader, BlockParse, DATA_BLOCK_IDS, DATA_LIST_IDS, HD_OFFSET};
+use crate::blocks::conversion::ConversionBlock;
+use crate::blocks::text_block::TextBlock;
+use crate::error::MdfError;
+
+/// Link offsets inside a `##CN` block.
+const CN_NAME: u64 = 40;
+const CN_CONVERSION: u64 = 56;
+const CN_UNIT: u64 = 72;
+const CN_COMMENT: u64 = 80;
+
+/// Editor for the channel metadata of an MDF 4 file on disk.
+///
+/// Channels are addressed by channel group index (in file order, as returned
+/// by [`MDF::channel_groups`](crate::api::mdf::MDF::channel_groups)) and
+/// channel name. Every edit is written to the file immediately.
+///
+/// ```no_run
+/// # use mf4_rs::edit::MdfEditor;
+/// let mut editor = MdfEditor::open("drive.mf4")?;
+/// editor.set_channel_name(0, "Spd", "VehicleSpeed")?;
+/// editor.set_channel_unit(0, "VehicleSpeed", "km/h")?;
+/// # Ok::<(), mf4_rs::error::MdfError>(())
+/// ```
+pub struct MdfEditor {
+    file: File,
+    /// `##CN` block addresses of every channel group, in file order.
+    groups: Vec<Vec<u64>>,
+    /// Number of links pointing at each block address.
+    references: HashMap<u64, usize>,
+}
+
+impl MdfEditor {
+    /// Open the MDF 4 file at `path` for editing.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut editor = MdfEditor { file, groups: Vec::new(), references: HashMap::new() };
+
+        let mut id = [0u8; 8];
+        editor.read_at(0, &mut id)?;
+        if &id != b"MDF     " {
+            return Err(MdfError::FileIdentifierError(String::from_utf8_lossy(&id).into_owned()));
+        }
+        let mut version = [0u8; 2];
+        editor.read_at(28, &mut version)?;
+        if u16::from_le_bytes(version) < 400 {
+            return Err(MdfError::FileVersioningError(format!(
+                "in-place editing needs MDF 4.x, found version {}",
+                u16::from_le_bytes(version)
+            )));
+        }
+
+        let mut dg = editor.link(64, 24)?;
+        while dg != 0 {
+            let mut cg = editor.link(dg, 32)?;
+            while cg != 0 {
+                let mut channels = Vec::new();
+                let mut cn = editor.link(cg, 32)?;
+                while cn != 0 {
+                    channels.push(cn);
+                    cn = editor.link(cn, 24)?;
+                }
+                editor.groups.push(channels);
+                cg = editor.link(cg, 24)?;
+            }
+            dg = editor.link(dg, 24)?;
+        }
+        editor.count_references()?;
+        Ok(editor)
+    }
+
+    /// Rename channel `channel` of group `group` to `name`.
+    pub fn set_channel_name(&mut self, group: usize, channel: &str, name: &str) -> Result<(), MdfError> {
+        let cn = self.find_channel(group, channel)?;
+        self.write_text(cn + CN_NAME, name)
+    }
+
+    /// Set the unit of channel `channel` of group `group`.
+    pub fn set_channel_unit(&mut self, group: usize, channel: &str, unit: &str) -> Result<(), MdfError> {
+        let cn = self.find_channel(group, channel)?;
+        self.write_text(cn + CN_UNIT, unit)
+    }
+
+    /// Set the comment of channel `channel` of group `group`. An existing
+    /// `##MD` comment is replaced by a `##TX` block.
+    pub fn set_channel_comment(&mut self, group: usize, channel: &str, comment: &str) -> Result<(), MdfError> {
+        let cn = self.find_channel(group, channel)?;
+        self.write_text(cn + CN_COMMENT, comment)
+    }
+
+    /// Replace the conversion of channel `channel` of group `group`, or remove
+    /// it with `None`.
+    ///
+    /// The links of `conversion` (name, unit, comment, inverse and references)
+    /// are written as given, so they must be `0` or addresses of blocks in
+    /// this file. The existing `##CC` block is overwritten when the new one
+    /// fits into it and no other block links to it.
+    pub fn set_channel_conversion(
+        &mut self,
+        group: usize,
+        channel: &str,
+        conversion: Option<&ConversionBlock>,
+    ) -> Result<(), MdfError> {
+        let cn = self.find_channel(group, channel)?;
+        let link = cn + CN_CONVERSION;
+        match conversion {
+            Some(conversion) => {
+                let mut conversion = conversion.clone();
+                conversion.header.id = "##CC".into();
+                let bytes = conversion.to_bytes()?;
+                self.write_block(link, "##CC", &bytes)
+            }
+            None => self.relink(link, 0),
+        }
+    }
+
+    /// Address of the `##CN` block named `channel` in group `group`.
+    fn find_channel(&mut self, group: usize, channel: &str) -> Result<u64, MdfError> {
+        let channels = self
+            .groups
+            .get(group)
+            .ok_or(MdfError::InvalidIndex { kind: "group", index: group, len: self.groups.len() })?
+            .clone();
+        for cn in channels {
+            let name = self.link(cn, CN_NAME)?;
+            if name != 0 && self.read_text(name)?.as_deref() == Some(channel) {
+                return Ok(cn);
+            }
+        }
+        Err(MdfError::ChannelNotFound { name: channel.to_string(), group: Some(group.to_string()) })
+    }
+
+    /// Store `text` in the `##TX` block linked at file offset `link`.
+    fn write_text(&mut self, link: u64, text: &str) -> Result<(), MdfError> {
+        let bytes = TextBlock::new(text).to_bytes()?;
+        self.write_block(link, "##TX", &bytes)
+    }
+
+    /// Overwrite the `id` block linked at file offset `link` with `bytes` if
+    /// it is large enough and not shared, or append `bytes` and relink.
+    fn write_block(&mut self, link: u64, id: &str, bytes: &[u8]) -> Result<(), MdfError> {
+        let addr = self.read_u64(link)?;
+        if addr != 0 && self.references.get(&addr) == Some(&1) {
+            let header = self.header(addr)?;
+            if header.id == id && bytes.len() as u64 <= header.block_len {
+                let mut block = bytes.to_vec();
+                if id == "##TX" {
+                    // Keep the block length; the text is zero padded.
+                    block.resize(header.block_len as usize, 0);
+                    block[8..16].copy_from_slice(&header.block_len.to_le_bytes());
+                }
+                return self.write_at(addr, &block);
+            }
+        }
+        let end = self.file.seek(SeekFrom::End(0))?;
+        let padding = (8 - end % 8) % 8;
+        self.write_at(end, &vec![0u8; padding as usize])?;
+        self.write_at(end + padding, bytes)?;
+        self.relink(link, end + padding)
+    }
+
+    /// Point the link at file offset `link` to `addr`.
+    fn relink(&mut self, link: u64, addr: u64) -> Result<(), MdfError> {
+        let old = self.read_u64(link)?;
+        if let Some(count) = self.references.get_mut(&old) {
+            *count = count.saturating_sub(1);
+        }
+        if addr != 0 {
+            *self.references.entry(addr).or_default() += 1;
+        }
+        self.write_at(link, &addr.to_le_bytes())
+    }
+
+    /// Count the links to every block reachable from the header, without
+    /// descending into data blocks.
+    fn count_references(&mut self) -> Result<(), MdfError> {
+        let mut visited = HashSet::new();
+        let mut pending = vec![HD_OFFSET];
+        while let Some(addr) = pending.pop() {
+            if !visited.insert(addr) {
+                continue;
+            }
+            let header = self.header(addr)?;
+            // The links of data blocks and their lists are not followed when
+            // counting references, which keeps opening independent of the
+            // file size.
+            let id = header.id.as_str();
+            if DATA_BLOCK_IDS.contains(&id) || DATA_LIST_IDS.contains(&id) {
+                continue;
+            }
+            let mut links = vec![0u8; header.links_nr as usize * 8];
+            self.read_at(addr + 24, &mut links)?;
+            for link in links.chunks_exact(8) {
+                let target = u64::from_le_bytes(link.try_into().unwrap());
+                if target != 0 {
+                    *self.references.entry(target).or_default() += 1;
+                    pending.push(target);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Text of the `##TX` block at `addr`.
+    fn read_text(&mut self, addr: u64) -> Result<Option<String>, MdfError> {
+        let header = self.header(addr)?;
+        if header.id != "##TX" {
+            return Ok(None);
+        }
+        let mut bytes = vec![0u8; header.block_len as usize];
+        self.read_at(addr, &mut bytes)?;
+        Ok(Some(TextBlock::from_bytes(&bytes)?.text))
+    }
+
+    fn header(&mut self, addr: u64) -> Result<BlockHeader, MdfError> {
+        let mut bytes = [0u8; 24];
+        self.read_at(addr, &mut bytes)?;
+        BlockHeader::from_bytes(&bytes)
+    }
+
+    /// The link at byte `offset` of the block at `addr`.
+    fn link(&mut self, addr: u64, offset: u64) -> Result<u64, MdfError> {
+        self.read_u64(addr + offset)
+    }
+
+    fn read_u64(&mut self, pos: u64) -> Result<u64, MdfError> {
+        let mut bytes = [0u8; 8];
+        self.read_at(pos, &mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), MdfError> {
+        self.file.seek(SeekFrom::Start(pos))?;
+        self.file.read_exact(buf)?;
+        Ok(())
+    }
+
+    fn write_at(&mut self, pos: u64, bytes: &[u8]) -> Result<(), MdfError> {
+        self.file.seek(SeekFrom::Start(pos))?;
+        self.file.write_all(bytes)?;
+        Ok(())
+    }
+}
@@ -39,4 +39,93 @@ pub enum MdfError {
 
     #[error("Conversion chain cycle detected at block address {address:#x}")]
     ConversionChainCycle { address: u64 },
+
+    #[error("Stale index: {0}")]
+    StaleIndex(String),
+
+    #[error("MDF 4.1 compliance violation: {0}")]
+    ComplianceViolation(String),
+
+    #[error("DBC parse error at line {line}: {message}")]
+    DbcParseError { line: usize, message: String },
+
+    #[error("Export error: {0}")]
+    ExportError(String),
+
+    /// A group, channel or request position past the end of its collection.
+    #[error("Invalid {kind} index {index}: only {len} available")]
+    InvalidIndex {
+        kind:  &'static str,
+        index: usize,
+        len:   usize,
+    },
+
+    /// No channel with the requested name, optionally within one group.
+    #[error("Channel '{name}' not found{}", group.as_ref().map(|g| format!(" in group '{g}'")).unwrap_or_default())]
+    ChannelNotFound {
+        name:  String,
+        group: Option<String>,
+    },
+
+    /// A record range that is reversed or reaches past the records of its
+    /// channel group.
+    #[error("Invalid record range {start}..{end} of {total} records")]
+    InvalidRecordRange {
+        start: u64,
+        end:   u64,
+        total: u64,
+    },
+
+    /// The number of values does not match the number of channels.
+    #[error("Value count mismatch: expected {expected}, got {actual}")]
+    ValueCountMismatch {
+        expected: usize,
+        actual:   usize,
+    },
+
+    /// A valid file or request using a feature mf4-rs does not implement
+    /// (yet), or one not available on the current target.
+    #[error("Unsupported feature: {0}")]
+    UnsupportedFeature(String),
+
+    /// An operation that only works on uncompressed (`##DT`) data blocks.
+    #[error("Compressed data blocks are not supported {0}")]
+    CompressedDataUnsupported(String),
+
+    /// A link pointing at or past the end of the file.
+    #[error("Link to a {block_id} block at {offset:#x} points past the end of the {file_size}-byte file")]
+    LinkOutOfBounds {
+        block_id:  String,
+        offset:    u64,
+        file_size: u64,
+    },
+
+    /// A writer call that does not fit the writer's current state, e.g.
+    /// writing records without an open data block.
+    #[error("Invalid writer state: {0}")]
+    InvalidWriterState(String),
+
+    /// Files whose channel group layouts differ where identical layouts are
+    /// required, e.g. when concatenating them, or raw records whose size
+    /// differs from the record size of their channel group.
+    #[error("Layout mismatch: {0}")]
+    LayoutMismatch(String),
+
+    /// A channel group whose channels cannot be laid out in a record, e.g.
+    /// overlapping channels, an empty record or a record ID that does not
+    /// fit its length.
+    #[error("Invalid record layout: {0}")]
+    InvalidRecordLayout(String),
+
+    /// A value, column or frame that does not fit the data type of the
+    /// channel (group) it is written to.
+    #[error("Value type mismatch: {0}")]
+    ValueTypeMismatch(String),
+
+    /// Columns of one write whose lengths differ.
+    #[error("Column length mismatch: expected {expected} values, got {actual}")]
+    ColumnLengthMismatch {
+        expected: usize,
+        actual:   usize,
+    },
 }
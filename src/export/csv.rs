@@ -0,0 +1,197 @@
+//! Export of selected channels of a channel group to CSV.
+//!
+//! The first column is the group's master (time) channel and every selected
+//! channel follows in its own column, so all values share one time base.
+//! Rows are either the group's records as stored or, with
+//! [`CsvExportOptions::resample_interval`], a fixed-interval grid onto which
+//! every channel is resampled (see [`Signal::align_to`]).
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::api::mdf::MDF;
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+use crate::signal::{fixed_grid, InvalidationPolicy, Signal};
+
+/// Options for [`write_group_to_csv`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvExportOptions {
+    /// Channels to write, in column order. Empty selects every channel of
+    /// the group except the master.
+    pub channels: Vec<String>,
+    /// Resample onto a grid from the first to the last master value with
+    /// this step (in master units, usually seconds). `None` writes one row
+    /// per record.
+    pub resample_interval: Option<f64>,
+    /// How invalid samples affect resampled values.
+    pub invalidation_policy: InvalidationPolicy,
+    /// Digits after the decimal point of float values, including the time
+    /// column. `None` writes the shortest representation that round-trips.
+    pub float_precision: Option<usize>,
+    /// Field separator.
+    pub delimiter: char,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        CsvExportOptions {
+            channels: Vec::new(),
+            resample_interval: None,
+            invalidation_policy: InvalidationPolicy::default(),
+            float_precision: None,
+            delimiter: ',',
+        }
+    }
+}
+
+/// Write channels of group `group_idx` of `mdf` (in file order, as returned
+/// by [`MDF::channel_groups`]) to a new CSV file at `path`.
+///
+/// The header row holds the channel names. Invalid samples are empty cells;
+/// text is quoted when needed and byte arrays are written as hex. Groups
+/// without a master channel have no time column and cannot be resampled.
+///
+/// # Returns
+/// The number of data rows written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_group_to_csv(
+    mdf: &MDF,
+    group_idx: usize,
+    path: impl AsRef<Path>,
+    options: &CsvExportOptions,
+) -> Result<u64, MdfError> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write_group_to_csv_writer(mdf, group_idx, file, options)
+}
+
+/// [`write_group_to_csv`] into any writer, e.g. an in-memory buffer.
+pub fn write_group_to_csv_writer<W: Write>(
+    mdf: &MDF,
+    group_idx: usize,
+    mut sink: W,
+    options: &CsvExportOptions,
+) -> Result<u64, MdfError> {
+    let group = mdf
+        .iter_channel_groups()
+        .nth(group_idx)
+        .ok_or_else(|| MdfError::InvalidIndex { kind: "group", index: group_idx, len: mdf.iter_channel_groups().count() })?;
+    let master_name = match group.master_channel() {
+        Some(ch) => Some(ch.name()?.unwrap_or_else(|| "time".to_string())),
+        None => None,
+    };
+    let master_times = group.time_values()?;
+
+    let names = if options.channels.is_empty() {
+        let mut names = Vec::new();
+        for ch in group.channels().iter().filter(|ch| ch.block().channel_type != 2) {
+            if let Some(name) = ch.name()? {
+                names.push(name);
+            }
+        }
+        names
+    } else {
+        options.channels.clone()
+    };
+    let mut signals = Vec::with_capacity(names.len());
+    for name in &names {
+        let mut signal = group.signal(name)?.ok_or_else(|| {
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
+        })?;
+        // The master indexes itself.
+        if let (false, Some(times)) = (signal.has_timestamps(), &master_times) {
+            signal.timestamps = times.clone();
+        }
+        signals.push(signal);
+    }
+
+    let (times, signals) = match options.resample_interval {
+        None => (master_times, signals),
+        Some(interval) => {
+            let times = master_times.ok_or_else(|| {
+                MdfError::BlockSerializationError(
+                    "cannot resample a channel group without a master channel".to_string(),
+                )
+            })?;
+            let grid = resample_grid(&times, interval)?;
+            let signals: Vec<Signal> = signals
+                .iter()
+                .map(|s| s.align_to(&grid, options.invalidation_policy))
+                .collect();
+            (Some(grid), signals)
+        }
+    };
+
+    let delimiter = options.delimiter.to_string();
+    let header: Vec<String> = master_name
+        .iter()
+        .chain(&names)
+        .map(|name| quote(name, options.delimiter))
+        .collect();
+    writeln!(sink, "{}", header.join(&delimiter))?;
+
+    let rows = times
+        .as_ref()
+        .map_or_else(|| signals.first().map_or(0, Signal::len), Vec::len);
+    let mut line = String::new();
+    for row in 0..rows {
+        line.clear();
+        if let Some(times) = &times {
+            line.push_str(&format_float(times[row], options.float_precision));
+            if !signals.is_empty() {
+                line.push(options.delimiter);
+            }
+        }
+        for (i, signal) in signals.iter().enumerate() {
+            if i > 0 {
+                line.push(options.delimiter);
+            }
+            if let Some(Some(value)) = signal.values.get(row) {
+                line.push_str(&format_value(value, options));
+            }
+        }
+        writeln!(sink, "{line}")?;
+    }
+    sink.flush()?;
+    Ok(rows as u64)
+}
+
+/// `first, first + interval, ...` up to the last master value.
+fn resample_grid(times: &[f64], interval: f64) -> Result<Vec<f64>, MdfError> {
+    let (Some(&first), Some(&last)) = (times.first(), times.last()) else {
+        return Ok(Vec::new());
+    };
+    fixed_grid(first, last, interval)
+}
+
+fn format_float(v: f64, precision: Option<usize>) -> String {
+    match precision {
+        Some(p) => format!("{:.*}", p, v),
+        None => v.to_string(),
+    }
+}
+
+fn format_value(value: &DecodedValue, options: &CsvExportOptions) -> String {
+    match value {
+        DecodedValue::Float(v) => format_float(*v, options.float_precision),
+        DecodedValue::UnsignedInteger(v) => v.to_string(),
+        DecodedValue::SignedInteger(v) => v.to_string(),
+        DecodedValue::String(s) => quote(s, options.delimiter),
+        DecodedValue::ByteArray(bytes) | DecodedValue::MimeSample(bytes) | DecodedValue::MimeStream(bytes) => {
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+        DecodedValue::CanOpenDate(date) => date.to_string(),
+        DecodedValue::CanOpenTime(time) => time.to_string(),
+        DecodedValue::Unknown => String::new(),
+    }
+}
+
+/// Quote a field per RFC 4180 if it contains the delimiter, a quote or a
+/// line break.
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,265 @@
+//! Export of a channel group to an Apache Parquet file.
+//!
+//! Every channel becomes one nullable column named after the channel, with
+//! its physical unit stored under the `unit` key of the column's field
+//! metadata. Records are decoded and converted as they stream in, one
+//! batch at a time, so groups larger than memory can be exported.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::basic::Compression;
+use ::parquet::file::properties::WriterProperties;
+use arrow_array::builder::{
+    BinaryBuilder, Float64Builder, Int64Builder, StringBuilder, TimestampNanosecondBuilder, UInt64Builder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType as ArrowType, Field, Schema, TimeUnit};
+
+use crate::api::channel::Channel;
+use crate::api::mdf::MDF;
+use crate::blocks::common::DataType;
+use crate::blocks::conversion::ConversionType;
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+
+/// Compression codec of the exported column chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParquetCompression {
+    Uncompressed,
+    #[default]
+    Snappy,
+}
+
+/// Options for [`write_group_to_parquet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetExportOptions {
+    pub compression: ParquetCompression,
+    /// Maximum number of rows per Parquet row group.
+    pub row_group_size: usize,
+    /// Number of records decoded before they are handed to the writer.
+    pub batch_size: usize,
+}
+
+impl Default for ParquetExportOptions {
+    fn default() -> Self {
+        ParquetExportOptions {
+            compression: ParquetCompression::Snappy,
+            row_group_size: 1024 * 1024,
+            batch_size: 64 * 1024,
+        }
+    }
+}
+
+/// Write channel group `group_idx` of `mdf` (in file order, as returned by
+/// [`MDF::channel_groups`]) to a new Parquet file at `path`.
+///
+/// Column types follow the channel's physical values:
+/// - integers without a conversion are `Int64` / `UInt64`
+/// - floats and numerically converted channels are `Float64`
+/// - strings and text conversions are `Utf8`
+/// - byte arrays and MIME samples are `Binary`
+///
+/// Invalid samples are written as nulls.
+///
+/// # Returns
+/// The number of rows written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_group_to_parquet(
+    mdf: &MDF,
+    group_idx: usize,
+    path: impl AsRef<Path>,
+    options: &ParquetExportOptions,
+) -> Result<u64, MdfError> {
+    let file = std::fs::File::create(path)?;
+    write_group_to_parquet_writer(mdf, group_idx, file, options)
+}
+
+/// [`write_group_to_parquet`] into any writer, e.g. an in-memory buffer.
+pub fn write_group_to_parquet_writer<W: Write + Send>(
+    mdf: &MDF,
+    group_idx: usize,
+    sink: W,
+    options: &ParquetExportOptions,
+) -> Result<u64, MdfError> {
+    let group = mdf
+        .iter_channel_groups()
+        .nth(group_idx)
+        .ok_or_else(|| MdfError::InvalidIndex { kind: "group", index: group_idx, len: mdf.iter_channel_groups().count() })?;
+    let channels = group.channels();
+
+    let mut fields = Vec::with_capacity(channels.len());
+    let mut names = HashMap::new();
+    for (i, channel) in channels.iter().enumerate() {
+        let name = channel.name()?.unwrap_or_else(|| format!("channel_{i}"));
+        // Parquet readers address columns by name, so keep them unique.
+        let seen = names.entry(name.clone()).or_insert(0);
+        *seen += 1;
+        let name = if *seen > 1 { format!("{name}_{i}") } else { name };
+        let mut field = Field::new(name, column_type(channel), true);
+        if let Some(unit) = channel.unit()?.filter(|u| !u.is_empty()) {
+            field = field.with_metadata(HashMap::from([("unit".to_string(), unit)]));
+        }
+        fields.push(field);
+    }
+    let schema = Arc::new(Schema::new(fields));
+
+    let compression = match options.compression {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Snappy => Compression::SNAPPY,
+    };
+    let properties = WriterProperties::builder()
+        .set_compression(compression)
+        .set_max_row_group_size(options.row_group_size.max(1))
+        .build();
+    let mut writer = ArrowWriter::try_new(sink, schema.clone(), Some(properties)).map_err(export_error)?;
+
+    let batch_size = options.batch_size.max(1);
+    let mut columns: Vec<ColumnBuilder> = schema
+        .fields()
+        .iter()
+        .map(|f| ColumnBuilder::new(f.data_type(), batch_size))
+        .collect();
+    let mut pending = 0;
+    let mut rows = 0u64;
+    for record in group.iter_records()? {
+        for (column, value) in columns.iter_mut().zip(record?) {
+            column.append(value);
+        }
+        pending += 1;
+        if pending == batch_size {
+            write_batch(&mut writer, &schema, &mut columns)?;
+            rows += pending as u64;
+            pending = 0;
+        }
+    }
+    if pending > 0 {
+        write_batch(&mut writer, &schema, &mut columns)?;
+        rows += pending as u64;
+    }
+    writer.close().map_err(export_error)?;
+    Ok(rows)
+}
+
+/// The Arrow type of a channel's physical values.
+fn column_type(channel: &Channel<'_>) -> ArrowType {
+    let block = channel.block();
+    match block.conversion.as_ref().map(|cc| &cc.cc_type) {
+        None | Some(ConversionType::Identity) => {}
+        Some(
+            ConversionType::ValueToText
+            | ConversionType::RangeToText
+            | ConversionType::TextToText
+            | ConversionType::BitfieldText,
+        ) => return ArrowType::Utf8,
+        Some(_) => return ArrowType::Float64,
+    }
+    match block.data_type {
+        DataType::UnsignedIntegerLE | DataType::UnsignedIntegerBE => ArrowType::UInt64,
+        DataType::SignedIntegerLE | DataType::SignedIntegerBE => ArrowType::Int64,
+        DataType::FloatLE | DataType::FloatBE => ArrowType::Float64,
+        DataType::StringLatin1 | DataType::StringUtf8 | DataType::StringUtf16LE | DataType::StringUtf16BE => {
+            ArrowType::Utf8
+        }
+        DataType::CanOpenDate | DataType::CanOpenTime => ArrowType::Timestamp(TimeUnit::Nanosecond, None),
+        _ => ArrowType::Binary,
+    }
+}
+
+/// Accumulates one column of a record batch.
+enum ColumnBuilder {
+    UInt64(UInt64Builder),
+    Int64(Int64Builder),
+    Float64(Float64Builder),
+    Utf8(StringBuilder),
+    Timestamp(TimestampNanosecondBuilder),
+    Binary(BinaryBuilder),
+}
+
+impl ColumnBuilder {
+    fn new(data_type: &ArrowType, capacity: usize) -> Self {
+        match data_type {
+            ArrowType::UInt64 => ColumnBuilder::UInt64(UInt64Builder::with_capacity(capacity)),
+            ArrowType::Int64 => ColumnBuilder::Int64(Int64Builder::with_capacity(capacity)),
+            ArrowType::Float64 => ColumnBuilder::Float64(Float64Builder::with_capacity(capacity)),
+            ArrowType::Utf8 => ColumnBuilder::Utf8(StringBuilder::new()),
+            ArrowType::Timestamp(..) => {
+                ColumnBuilder::Timestamp(TimestampNanosecondBuilder::with_capacity(capacity))
+            }
+            _ => ColumnBuilder::Binary(BinaryBuilder::new()),
+        }
+    }
+
+    /// Append one sample; values that do not fit the column type are null,
+    /// except that text columns take numbers (e.g. the scale fallback of a
+    /// value-to-text conversion) as their decimal form.
+    fn append(&mut self, value: Option<DecodedValue>) {
+        match self {
+            ColumnBuilder::UInt64(b) => b.append_option(match value {
+                Some(DecodedValue::UnsignedInteger(v)) => Some(v),
+                Some(DecodedValue::SignedInteger(v)) => u64::try_from(v).ok(),
+                _ => None,
+            }),
+            ColumnBuilder::Int64(b) => b.append_option(match value {
+                Some(DecodedValue::SignedInteger(v)) => Some(v),
+                Some(DecodedValue::UnsignedInteger(v)) => i64::try_from(v).ok(),
+                _ => None,
+            }),
+            ColumnBuilder::Float64(b) => b.append_option(match value {
+                Some(DecodedValue::Float(v)) => Some(v),
+                Some(DecodedValue::UnsignedInteger(v)) => Some(v as f64),
+                Some(DecodedValue::SignedInteger(v)) => Some(v as f64),
+                _ => None,
+            }),
+            ColumnBuilder::Utf8(b) => b.append_option(match value {
+                Some(DecodedValue::String(s)) => Some(s),
+                Some(DecodedValue::Float(v)) => Some(v.to_string()),
+                Some(DecodedValue::UnsignedInteger(v)) => Some(v.to_string()),
+                Some(DecodedValue::SignedInteger(v)) => Some(v.to_string()),
+                _ => None,
+            }),
+            ColumnBuilder::Timestamp(b) => b.append_option(match value {
+                Some(DecodedValue::CanOpenDate(date)) => Some(date.unix_nanos()),
+                Some(DecodedValue::CanOpenTime(time)) => Some(time.unix_nanos()),
+                _ => None,
+            }),
+            ColumnBuilder::Binary(b) => b.append_option(match value {
+                Some(
+                    DecodedValue::ByteArray(bytes)
+                    | DecodedValue::MimeSample(bytes)
+                    | DecodedValue::MimeStream(bytes),
+                ) => Some(bytes),
+                _ => None,
+            }),
+        }
+    }
+
+    /// Take the values appended so far as an Arrow array.
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ColumnBuilder::UInt64(b) => Arc::new(b.finish()),
+            ColumnBuilder::Int64(b) => Arc::new(b.finish()),
+            ColumnBuilder::Float64(b) => Arc::new(b.finish()),
+            ColumnBuilder::Utf8(b) => Arc::new(b.finish()),
+            ColumnBuilder::Timestamp(b) => Arc::new(b.finish()),
+            ColumnBuilder::Binary(b) => Arc::new(b.finish()),
+        }
+    }
+}
+
+fn write_batch<W: Write + Send>(
+    writer: &mut ArrowWriter<W>,
+    schema: &Arc<Schema>,
+    columns: &mut [ColumnBuilder],
+) -> Result<(), MdfError> {
+    let arrays = columns.iter_mut().map(ColumnBuilder::finish).collect();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(export_error)?;
+    writer.write(&batch).map_err(export_error)
+}
+
+fn export_error(err: impl std::fmt::Display) -> MdfError {
+    MdfError::ExportError(err.to_string())
+}
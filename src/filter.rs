@@ -0,0 +1,249 @@
+//! Extraction of a subset of channels into a new file.
+//!
+//! [`extract_channels`] streams the records of the selected channel groups
+//! into a new file, re-packed so that each record only holds the selected
+//! channels and their group's master channel.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::blocks::channel_block::CN_FLAG_INVAL_BIT_VALID;
+use crate::blocks::channel_group_block::CG_FLAG_VLSD;
+use crate::blocks::common::read_string_block;
+use crate::cut::{copy_channel, copy_group_header, ChannelMapping};
+use crate::error::MdfError;
+use crate::parsing::mdf_file::MdfFile;
+use crate::parsing::raw_channel_group::RawChannelGroup;
+use crate::sort::{RecordReader, VlsdSource};
+use crate::writer::{ChannelId, MdfWriter};
+
+/// The channels [`extract_channels`] keeps.
+///
+/// ```
+/// # use mf4_rs::filter::ChannelSelection;
+/// let mut selection = ChannelSelection::from_channels(["Speed", "EngineRPM"]);
+/// selection.groups.insert("GPS".into());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelSelection {
+    /// Names of channels to keep, in whichever group they are.
+    pub channels: HashSet<String>,
+    /// Names of channel groups to keep with all their channels.
+    pub groups: HashSet<String>,
+}
+
+impl ChannelSelection {
+    /// A selection of the channels `names`.
+    pub fn from_channels<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ChannelSelection {
+            channels: names.into_iter().map(Into::into).collect(),
+            groups: HashSet::new(),
+        }
+    }
+}
+
+/// Where a kept channel lies in the source and in the output record.
+struct KeptChannel {
+    src_index: usize,
+    /// Byte offset in the source record payload (after the record ID).
+    src_offset: usize,
+    out_offset: usize,
+    /// Bytes copied: those holding the channel's bits, or the inline offset
+    /// of a VLSD channel.
+    width: usize,
+    /// Source and output invalidation bit positions.
+    invalidation: Option<(u32, u32)>,
+}
+
+/// Write a new file at `output_path` holding only the channels of
+/// `input_path` chosen by `selection`.
+///
+/// Each channel group with at least one selected channel is copied with the
+/// selected channels and its master channel, so the output can still be
+/// read against time; groups without selected channels are left out. The
+/// records are re-packed to the smaller layout: the kept channels are laid
+/// out back to back in source order, and their invalidation bits are
+/// renumbered. Every group gets a data group of its own, so unsorted data
+/// groups come out sorted. VLSD channels get fresh `##SD` blocks.
+///
+/// Names, conversions, sources, units and comments of the kept channels and
+/// groups are carried over, as in
+/// [`cut_mdf_by_time`](crate::cut::cut_mdf_by_time). Records are streamed
+/// from the memory-mapped input one channel group at a time.
+///
+/// Returns an error if a selected channel or group name is not in the file.
+pub fn extract_channels(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    selection: &ChannelSelection,
+) -> Result<(), MdfError> {
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let mmap: &[u8] = &mdf.mmap;
+
+    // Channel indices to keep per (data group, channel group), in file order.
+    let mut plan: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+    let mut found_channels: HashSet<String> = HashSet::new();
+    let mut found_groups: HashSet<String> = HashSet::new();
+    for (d, dg) in mdf.data_groups.iter().enumerate() {
+        for (g, cg) in dg.channel_groups.iter().enumerate() {
+            if cg.block.flags & CG_FLAG_VLSD != 0 {
+                // Read through the VLSD channels referencing it.
+                continue;
+            }
+            let whole = match read_string_block(mmap, cg.block.acq_name_addr)? {
+                Some(name) if selection.groups.contains(&name) => {
+                    found_groups.insert(name);
+                    true
+                }
+                _ => false,
+            };
+            let mut selected = Vec::new();
+            let mut masters = Vec::new();
+            for (i, ch) in cg.raw_channels.iter().enumerate() {
+                let name = read_string_block(mmap, ch.block.name_addr)?;
+                let named = name.as_ref().is_some_and(|n| selection.channels.contains(n));
+                if let Some(name) = name.filter(|_| named) {
+                    found_channels.insert(name);
+                }
+                if whole || named {
+                    selected.push(i);
+                } else if matches!(ch.block.channel_type, 2 | 3) {
+                    masters.push(i);
+                }
+            }
+            if !selected.is_empty() {
+                selected.extend(masters);
+                selected.sort_unstable();
+                plan.push((d, g, selected));
+            }
+        }
+    }
+    let mut missing: Vec<&str> = selection
+        .channels
+        .difference(&found_channels)
+        .chain(selection.groups.difference(&found_groups))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        return Err(MdfError::BlockSerializationError(format!(
+            "selected channels or groups not found: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut writer = MdfWriter::new(output_path)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        mdf.header.abs_time,
+        mdf.header.tz_offset,
+        mdf.header.daylight_save_time,
+        mdf.header.time_flags,
+        mdf.header.time_quality,
+    )?;
+    let mut block_cache: HashMap<u64, u64> = HashMap::new();
+
+    for (d, g, selected) in plan {
+        let dg = &mdf.data_groups[d];
+        let cg = &dg.channel_groups[g];
+        let (kept, data_bytes, invalidation_bits) = repack(cg, &selected)?;
+
+        let cg_id = copy_group_header(&mut writer, mmap, cg, &mut block_cache)?;
+        let mut prev_cn: Option<ChannelId> = None;
+        let mut vlsd: Vec<(ChannelId, usize, usize, u64, VlsdSource)> = Vec::new();
+        for k in &kept {
+            let raw_channel = &cg.raw_channels[k.src_index];
+            let mut block = raw_channel.block.clone();
+            block.byte_offset = k.out_offset as u32;
+            if let Some((_, out_bit)) = k.invalidation {
+                block.pos_invalidation_bit = out_bit;
+            }
+            let (cn_id, is_vlsd) = copy_channel(
+                &mut writer,
+                mmap,
+                &cg_id,
+                prev_cn.as_ref(),
+                block,
+                &ChannelMapping::default(),
+                &mut block_cache,
+            )?;
+            if is_vlsd {
+                writer.start_signal_data_block(&cn_id)?;
+                let source = VlsdSource::open(dg, cg, raw_channel, mmap)?;
+                vlsd.push((cn_id, k.out_offset, k.width.min(8), 0, source));
+            }
+            prev_cn = Some(cn_id);
+        }
+
+        let src_data_bytes = cg.block.samples_byte_nr as usize;
+        let invalidation_bytes = invalidation_bits.div_ceil(8);
+        writer.start_data_block_for_cg_raw(&cg_id, 0, data_bytes as u32, invalidation_bytes)?;
+        let mut out = vec![0u8; data_bytes + invalidation_bytes as usize];
+        let mut records = RecordReader::new(dg, mmap, &cg.block)?;
+        while let Some(record) = records.next_record() {
+            let record = record?;
+            out.fill(0);
+            for k in &kept {
+                out[k.out_offset..k.out_offset + k.width]
+                    .copy_from_slice(&record[k.src_offset..k.src_offset + k.width]);
+                if let Some((src_bit, out_bit)) = k.invalidation {
+                    let byte = record.get(src_data_bytes + (src_bit >> 3) as usize).copied().unwrap_or(0);
+                    if byte >> (src_bit & 0x07) & 1 != 0 {
+                        out[data_bytes + (out_bit >> 3) as usize] |= 1 << (out_bit & 0x07);
+                    }
+                }
+            }
+            for (cn_id, slot_off, slot_size, next_offset, source) in vlsd.iter_mut() {
+                let entry = source.next_entry()?;
+                out[*slot_off..*slot_off + *slot_size].copy_from_slice(&next_offset.to_le_bytes()[..*slot_size]);
+                writer.write_signal_data(cn_id, entry)?;
+                *next_offset += 4 + entry.len() as u64;
+            }
+            writer.write_raw_record(&cg_id, &out)?;
+        }
+
+        writer.finish_data_block(&cg_id)?;
+        for (cn_id, ..) in &vlsd {
+            writer.finish_signal_data_block(cn_id)?;
+        }
+    }
+
+    writer.finalize()
+}
+
+/// Output layout of the channels `selected` of `cg`: the kept channels, the
+/// data bytes of a record and the number of invalidation bits.
+fn repack(cg: &RawChannelGroup, selected: &[usize]) -> Result<(Vec<KeptChannel>, usize, u32), MdfError> {
+    let src_data_bytes = cg.block.samples_byte_nr as usize;
+    let mut kept = Vec::with_capacity(selected.len());
+    let mut out_offset = 0;
+    let mut invalidation_bits = 0;
+    for &i in selected {
+        let block = &cg.raw_channels[i].block;
+        let is_vlsd = block.channel_type == 1 && block.data != 0;
+        // Virtual channels take no record bytes.
+        let width = match block.channel_type {
+            3 | 6 => 0,
+            _ if is_vlsd => block.bit_count as usize / 8,
+            _ => (block.bit_offset as usize + block.bit_count as usize).div_ceil(8),
+        };
+        let src_offset = block.byte_offset as usize;
+        if src_offset + width > src_data_bytes {
+            return Err(MdfError::BlockSerializationError(format!(
+                "channel {} lies outside the {}-byte record of its group",
+                i, src_data_bytes
+            )));
+        }
+        let invalidation = (block.flags & CN_FLAG_INVAL_BIT_VALID != 0).then(|| {
+            invalidation_bits += 1;
+            (block.pos_invalidation_bit, invalidation_bits - 1)
+        });
+        kept.push(KeptChannel { src_index: i, src_offset, out_offset, width, invalidation });
+        out_offset += width;
+    }
+    Ok((kept, out_offset, invalidation_bits))
+}
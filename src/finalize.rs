@@ -0,0 +1,398 @@
+//! Finalization of unfinalized MDF 4 files.
+//!
+//! A logger that is interrupted leaves a file with the identifier `UnFinMDF`
+//! and `id_unfin_flags` telling which bookkeeping it did not get to: cycle
+//! counters, the length of the last `##DT`/`##RD` block and the last `##DL`
+//! block of a list. [`finalize_file`] applies the finalization steps of the
+//! MDF 4 specification and writes a finalized copy;
+//! [`MDF::from_file`](crate::api::mdf::MDF::from_file) applies them to a
+//! private copy-on-write mapping when it opens an unfinalized file.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::blocks::channel_group_block::CG_FLAG_VLSD;
+use crate::blocks::data_zipped_block::DataZippedBlock;
+use crate::error::MdfError;
+
+/// `id_unfin_flags` bit 0: cycle counters of `##CG` blocks.
+const UPDATE_CG_CYCLES: u16 = 1 << 0;
+/// Bit 2: length of the last `##DT` block.
+const UPDATE_DT_LENGTH: u16 = 1 << 2;
+/// Bit 3: length of the last `##RD` block.
+const UPDATE_RD_LENGTH: u16 = 1 << 3;
+/// Bit 4: the last `##DL` block of each list.
+const UPDATE_DL: u16 = 1 << 4;
+
+/// `true` if `bytes` start with the identifier of an unfinalized MDF file.
+pub fn is_unfinalized(bytes: &[u8]) -> bool {
+    bytes.get(0..8) == Some(b"UnFinMDF")
+}
+
+/// Write a finalized copy of the unfinalized MDF 4 file at `input` to
+/// `output`.
+///
+/// The file is copied and the copy is patched in place: block lengths,
+/// `##DL` entry counts and cycle counters are derived from the data present
+/// in the file, a trailing partial record left by the interruption is
+/// dropped, and the identification block is marked as finalized. The
+/// vendor-specific `id_custom_unfin_flags` are cleared. Files with flags for
+/// sample reduction or VLSD channel group updates are rejected.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn finalize_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), MdfError> {
+    let (input, output) = (input.as_ref(), output.as_ref());
+    let patches = {
+        let file = std::fs::File::open(input)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        plan_finalization(&mmap)?
+    };
+    if input != output {
+        std::fs::copy(input, output)?;
+    }
+    let file = std::fs::OpenOptions::new().read(true).write(true).open(output)?;
+    let mut mmap = unsafe { memmap2::MmapMut::map_mut(&file)? };
+    apply(&mut mmap, patches);
+    mmap.flush()?;
+    Ok(())
+}
+
+/// Finalize an in-memory MDF 4 image; see [`finalize_file`].
+pub fn finalize_bytes(mut data: Vec<u8>) -> Result<Vec<u8>, MdfError> {
+    let patches = plan_finalization(&data)?;
+    apply(&mut data, patches);
+    Ok(data)
+}
+
+/// Finalize `file` in a private copy-on-write mapping: only the pages holding
+/// patched bytes are copied, and the file itself is left unchanged.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn finalize_mapped(file: &std::fs::File) -> Result<memmap2::Mmap, MdfError> {
+    let mut mmap = unsafe { memmap2::MmapOptions::new().map_copy(file)? };
+    let patches = plan_finalization(&mmap)?;
+    apply(&mut mmap, patches);
+    Ok(mmap.make_read_only()?)
+}
+
+/// Byte ranges to overwrite: `(file offset, new bytes)`.
+type Patches = Vec<(u64, Vec<u8>)>;
+
+fn apply(data: &mut [u8], patches: Patches) {
+    for (pos, bytes) in patches {
+        let pos = pos as usize;
+        data[pos..pos + bytes.len()].copy_from_slice(&bytes);
+    }
+}
+
+/// A channel group of a data group being finalized.
+struct Group {
+    /// File offset of the `##CG` data section.
+    fields: u64,
+    record_id: u64,
+    vlsd: bool,
+    /// Bytes after the record ID: data and invalidation bytes.
+    record_len: u64,
+}
+
+/// A data block of a data group: `(address, block ID, record bytes)`.
+type DataBlock = (u64, String, u64);
+
+fn plan_finalization(data: &[u8]) -> Result<Patches, MdfError> {
+    if !is_unfinalized(data) || data.len() < 64 {
+        let id = String::from_utf8_lossy(&data[..data.len().min(8)]).into_owned();
+        return Err(MdfError::FileIdentifierError(id));
+    }
+    let flags = LittleEndian::read_u16(&data[60..62]);
+    let unsupported = flags & !(UPDATE_CG_CYCLES | UPDATE_DT_LENGTH | UPDATE_RD_LENGTH | UPDATE_DL);
+    if unsupported != 0 {
+        return Err(MdfError::UnsupportedFeature(format!(
+            "cannot finalize: unsupported unfinalized flags 0x{unsupported:04x}"
+        )));
+    }
+
+    let starts = block_starts(data);
+    let mut patches = Patches::new();
+    let mut visited = HashSet::new();
+    let mut dg = read_u64(data, 64 + 24);
+    while dg != 0 && visited.insert(dg) {
+        let dg_links = header(data, dg)?.2;
+        let record_id_len = read_uint(data, dg + 24 + dg_links * 8, 1) as usize;
+
+        let mut groups = Vec::new();
+        let mut cg = read_u64(data, dg + 32);
+        while cg != 0 && visited.insert(cg) {
+            let fields = cg + 24 + header(data, cg)?.2 * 8;
+            let cg_flags = read_uint(data, fields + 16, 2) as u16;
+            groups.push(Group {
+                fields,
+                record_id: read_u64(data, fields),
+                vlsd: cg_flags & CG_FLAG_VLSD != 0,
+                record_len: read_uint(data, fields + 24, 4) + read_uint(data, fields + 28, 4),
+            });
+            cg = read_u64(data, cg + 24);
+        }
+
+        let mut blocks = Vec::new();
+        data_blocks(data, read_u64(data, dg + 40), flags & UPDATE_DL != 0, &mut blocks, &mut patches)?;
+
+        // The last block runs up to the next block or the end of the file.
+        let mut resized = false;
+        if let Some((addr, id, len)) = blocks.last_mut() {
+            let update = match id.as_str() {
+                "##DT" => flags & UPDATE_DT_LENGTH != 0,
+                "##RD" => flags & UPDATE_RD_LENGTH != 0,
+                _ => false,
+            };
+            if update {
+                let end = starts.iter().copied().find(|&s| s > *addr).unwrap_or(data.len() as u64);
+                *len = end.saturating_sub(*addr + 24);
+                resized = true;
+            }
+        }
+
+        let total: u64 = blocks.iter().map(|(_, _, len)| len).sum();
+        let (counts, complete) = if let [group] = groups.as_slice()
+            && !group.vlsd
+        {
+            let record_len = record_id_len as u64 + group.record_len;
+            let count = total.checked_div(record_len).unwrap_or(0);
+            (vec![count], count * record_len)
+        } else {
+            count_records(data, &blocks, &groups, record_id_len)?
+        };
+
+        if resized {
+            // Drop a trailing partial record.
+            let (addr, _, len) = blocks.last_mut().unwrap();
+            *len -= total - complete;
+            patches.push((*addr + 8, (*len + 24).to_le_bytes().to_vec()));
+        }
+        if flags & UPDATE_CG_CYCLES != 0 {
+            for (group, count) in groups.iter().zip(counts) {
+                patches.push((group.fields + 8, count.to_le_bytes().to_vec()));
+            }
+        }
+        dg = read_u64(data, dg + 24);
+    }
+
+    patches.push((0, b"MDF     ".to_vec()));
+    patches.push((60, vec![0; 4]));
+    Ok(patches)
+}
+
+/// Collect the data blocks linked at `addr` (a data block, `##DL` or `##HL`)
+/// in order. With `update_dl`, the last `##DL` of the list is shrunk to its
+/// used entries.
+fn data_blocks(
+    data: &[u8],
+    addr: u64,
+    update_dl: bool,
+    blocks: &mut Vec<DataBlock>,
+    patches: &mut Patches,
+) -> Result<(), MdfError> {
+    if addr == 0 {
+        return Ok(());
+    }
+    let (id, block_len, links_nr) = header(data, addr)?;
+    match id.as_str() {
+        "##HL" => data_blocks(data, read_u64(data, addr + 24), update_dl, blocks, patches),
+        "##DL" => {
+            let next = read_u64(data, addr + 24);
+            let entries: Vec<u64> = (1..links_nr).map(|i| read_u64(data, addr + 24 + i * 8)).collect();
+            let used = if update_dl && next == 0 {
+                let used = entries.iter().take_while(|&&l| l != 0).count();
+                patches.push((addr, shrink_dl(data, addr, links_nr, used)));
+                used
+            } else {
+                entries.len()
+            };
+            for &entry in &entries[..used] {
+                data_blocks(data, entry, update_dl, blocks, patches)?;
+            }
+            data_blocks(data, next, update_dl, blocks, patches)
+        }
+        "##DZ" => {
+            let org_data_length = DataZippedBlock::read_org_data_length(&data[addr as usize..])?;
+            blocks.push((addr, id, org_data_length));
+            Ok(())
+        }
+        _ => {
+            blocks.push((addr, id, block_len.saturating_sub(24 + links_nr * 8)));
+            Ok(())
+        }
+    }
+}
+
+/// The `##DL` block at `addr` rewritten with only its first `used` data
+/// links. The new block is never longer than the old one.
+fn shrink_dl(data: &[u8], addr: u64, links_nr: u64, used: usize) -> Vec<u8> {
+    let fields = addr + 24 + links_nr * 8;
+    let dl_flags = read_uint(data, fields, 1) as u8;
+    let mut block = Vec::new();
+    block.extend_from_slice(b"##DL");
+    block.extend_from_slice(&[0; 4]);
+    block.extend_from_slice(&[0; 8]); // block_len, set below
+    block.extend_from_slice(&(1 + used as u64).to_le_bytes());
+    for i in 0..=used as u64 {
+        block.extend_from_slice(&read_u64(data, addr + 24 + i * 8).to_le_bytes());
+    }
+    block.push(dl_flags);
+    block.extend_from_slice(&[0; 3]);
+    block.extend_from_slice(&(used as u32).to_le_bytes());
+    if dl_flags & 1 != 0 {
+        block.extend_from_slice(&read_u64(data, fields + 8).to_le_bytes());
+    } else {
+        for i in 0..used as u64 {
+            block.extend_from_slice(&read_u64(data, fields + 8 + i * 8).to_le_bytes());
+        }
+    }
+    let len = block.len() as u64;
+    block[8..16].copy_from_slice(&len.to_le_bytes());
+    block
+}
+
+/// Count the records of every group in an unsorted data group. Returns the
+/// counts and the number of bytes taken by complete records.
+fn count_records(
+    data: &[u8],
+    blocks: &[DataBlock],
+    groups: &[Group],
+    record_id_len: usize,
+) -> Result<(Vec<u64>, u64), MdfError> {
+    if record_id_len == 0 {
+        return Err(MdfError::BlockSerializationError(
+            "cannot finalize a data group with several channel groups but no record IDs".into(),
+        ));
+    }
+    if blocks.iter().any(|(_, id, _)| id == "##DZ") {
+        return Err(MdfError::CompressedDataUnsupported("when finalizing an unsorted data group".into()));
+    }
+    let mut records = Records {
+        data,
+        chunks: blocks.iter().map(|&(addr, _, len)| (addr + 24, len)).collect(),
+        chunk: 0,
+        pos: 0,
+        consumed: 0,
+    };
+    let mut counts = vec![0u64; groups.len()];
+    let mut complete = 0;
+    while let Some(id) = records.read(record_id_len) {
+        let index = groups.iter().position(|g| g.record_id == id).ok_or_else(|| {
+            MdfError::BlockSerializationError(format!("unknown record ID {id} at byte {complete}"))
+        })?;
+        let len = if groups[index].vlsd {
+            match records.read(4) {
+                Some(len) => len,
+                None => break,
+            }
+        } else {
+            groups[index].record_len
+        };
+        if !records.skip(len) {
+            break;
+        }
+        counts[index] += 1;
+        complete = records.consumed;
+    }
+    Ok((counts, complete))
+}
+
+/// A cursor over the record bytes spread across a group's data blocks.
+struct Records<'a> {
+    data: &'a [u8],
+    /// `(file offset, length)` of the record bytes of each block.
+    chunks: Vec<(u64, u64)>,
+    chunk: usize,
+    /// Position inside the current chunk.
+    pos: u64,
+    /// Bytes read so far.
+    consumed: u64,
+}
+
+impl Records<'_> {
+    /// Read a little-endian unsigned integer of `len` bytes (at most 8).
+    fn read(&mut self, len: usize) -> Option<u64> {
+        let mut bytes = [0u8; 8];
+        for byte in bytes.iter_mut().take(len) {
+            let &(start, _) = self.next_chunk()?;
+            *byte = *self.data.get((start + self.pos) as usize)?;
+            self.pos += 1;
+            self.consumed += 1;
+        }
+        Some(LittleEndian::read_u64(&bytes))
+    }
+
+    /// Skip `len` bytes; `false` if the data ends first.
+    fn skip(&mut self, mut len: u64) -> bool {
+        while len > 0 {
+            let Some(&(_, chunk_len)) = self.next_chunk() else { return false };
+            let step = len.min(chunk_len - self.pos);
+            self.pos += step;
+            self.consumed += step;
+            len -= step;
+        }
+        true
+    }
+
+    /// The chunk holding the next byte, moving past exhausted chunks.
+    fn next_chunk(&mut self) -> Option<&(u64, u64)> {
+        while self.chunks.get(self.chunk)?.1 <= self.pos {
+            self.chunk += 1;
+            self.pos = 0;
+        }
+        self.chunks.get(self.chunk)
+    }
+}
+
+/// Sorted addresses of every block reachable from `##HD`.
+fn block_starts(data: &[u8]) -> Vec<u64> {
+    let mut visited = HashSet::new();
+    let mut pending = vec![64u64];
+    while let Some(addr) = pending.pop() {
+        let Ok((_, _, links_nr)) = header(data, addr) else { continue };
+        if !visited.insert(addr) {
+            continue;
+        }
+        for i in 0..links_nr {
+            let target = read_u64(data, addr + 24 + i * 8);
+            if target != 0 && !visited.contains(&target) {
+                pending.push(target);
+            }
+        }
+    }
+    let mut starts: Vec<u64> = visited.into_iter().collect();
+    starts.sort_unstable();
+    starts
+}
+
+/// Block ID, `block_len` and `links_nr` of the block at `addr`.
+fn header(data: &[u8], addr: u64) -> Result<(String, u64, u64), MdfError> {
+    let bytes = usize::try_from(addr)
+        .ok()
+        .and_then(|a| data.get(a..a.checked_add(24)?))
+        .filter(|b| b.starts_with(b"##"))
+        .ok_or_else(|| MdfError::BlockLinkError(format!("no block at 0x{addr:x}")))?;
+    let links_nr = LittleEndian::read_u64(&bytes[16..24]);
+    if links_nr > (data.len() as u64 - addr) / 8 {
+        return Err(MdfError::BlockLinkError(format!("links of block at 0x{addr:x} exceed the file")));
+    }
+    Ok((
+        String::from_utf8_lossy(&bytes[0..4]).into_owned(),
+        LittleEndian::read_u64(&bytes[8..16]),
+        links_nr,
+    ))
+}
+
+fn read_u64(data: &[u8], pos: u64) -> u64 {
+    read_uint(data, pos, 8)
+}
+
+/// The little-endian unsigned integer of `len` bytes at `pos`, or `0` past
+/// the end of the file.
+fn read_uint(data: &[u8], pos: u64, len: usize) -> u64 {
+    usize::try_from(pos)
+        .ok()
+        .and_then(|p| data.get(p..p.checked_add(len)?))
+        .map_or(0, |bytes| LittleEndian::read_uint(bytes, len))
+}
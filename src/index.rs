@@ -4,13 +4,20 @@
 //! that can be serialized to JSON and used later to read specific channel data
 //! without parsing the entire file structure.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Serialize};
 use crate::api::mdf::MDF;
-use crate::blocks::common::{DataType, BlockParse};
+use crate::blocks::channel_block::CN_FLAG_INVAL_BIT_VALID;
+use crate::blocks::common::{bytes_at, link_target, BlockHeader, BlockParse, DataType};
 use crate::blocks::conversion::{ConversionBlock, ConversionType};
+use crate::blocks::data_zipped_block::DataZippedBlock;
 use crate::error::MdfError;
-use crate::parsing::decoder::{check_value_validity, decode_channel_value_with_validity, decode_f64_from_record, DecodedValue};
-use crate::signal::{decoded_opt_to_f64, Signal};
+use crate::pattern::NamePattern;
+use crate::parsing::decoder::{check_value_validity, decode_channel_value_with_validity, decode_f64_from_record, extend_f64_column, DecodedChannelValue, DecodedValue, PrimitiveLayout};
+use crate::signal::{decoded_opt_to_f64, ChannelStatistics, Signal, StatisticsAccumulator};
 
 /// Represents the location and metadata of data blocks in the file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +28,20 @@ pub struct DataBlockInfo {
     pub size: u64,
     /// Whether this is a compressed block (DZ)
     pub is_compressed: bool,
+    /// Digest of the whole block, header included, when the index was built
+    /// with [`IndexOptions::hash_data_blocks`]; checked by
+    /// [`MdfIndex::verify`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<u64>,
+}
+
+/// Options for [`MdfIndex::from_file_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IndexOptions {
+    /// Store a digest of every data block in [`DataBlockInfo::digest`], so
+    /// [`MdfIndex::verify`] also detects changed sample data. Costs one pass
+    /// over the data while indexing.
+    pub hash_data_blocks: bool,
 }
 
 /// Channel metadata needed for decoding values
@@ -30,6 +51,9 @@ pub struct IndexedChannel {
     pub name: Option<String>,
     /// Physical unit
     pub unit: Option<String>,
+    /// Channel comment (`##TX` text or `##MD` XML)
+    #[serde(default)]
+    pub comment: Option<String>,
     /// Data type of the channel
     pub data_type: DataType,
     /// Byte offset within each record
@@ -61,6 +85,19 @@ impl IndexedChannel {
         self.channel_type == 1 && self.vlsd_data_address.is_some()
     }
 
+    /// The channel's user-facing metadata as a [`ChannelDescriptor`].
+    pub fn descriptor(&self) -> ChannelDescriptor {
+        ChannelDescriptor {
+            name: self.name.clone(),
+            unit: self.unit.clone(),
+            comment: self.comment.clone(),
+            data_type: self.data_type.clone(),
+            bit_count: self.bit_count,
+            is_master: self.is_master(),
+            conversion: self.conversion.as_ref().map(ConversionBlock::summary),
+        }
+    }
+
     /// Create a temporary `ChannelBlock` for use with the decoder functions.
     /// This should be called once and reused across all records.
     fn to_channel_block(&self) -> crate::blocks::channel_block::ChannelBlock {
@@ -138,10 +175,31 @@ impl IndexedChannel {
             upper_ext_limit: 0.0,
             name,
             conversion,
+            source: None,
         }
     }
 }
 
+/// Descriptive metadata of one channel, returned by
+/// [`MdfIndex::channel_descriptor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelDescriptor {
+    /// Channel name
+    pub name: Option<String>,
+    /// Physical unit
+    pub unit: Option<String>,
+    /// Channel comment
+    pub comment: Option<String>,
+    /// Data type of the raw samples
+    pub data_type: DataType,
+    /// Width of the raw value in bits
+    pub bit_count: u32,
+    /// Whether this is the group's master channel
+    pub is_master: bool,
+    /// One-line summary of the conversion, see [`ConversionBlock::summary`]
+    pub conversion: Option<String>,
+}
+
 /// Channel group metadata and layout information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexedChannelGroup {
@@ -161,6 +219,10 @@ pub struct IndexedChannelGroup {
     pub channels: Vec<IndexedChannel>,
     /// Data block locations for this channel group
     pub data_blocks: Vec<DataBlockInfo>,
+    /// Master values of the first and last record, decoded (and converted)
+    /// when the index was built; `None` without a master channel or records.
+    #[serde(default)]
+    pub time_range: Option<(f64, f64)>,
 }
 
 impl IndexedChannelGroup {
@@ -183,6 +245,23 @@ impl IndexedChannelGroup {
     pub fn master_channel(&self) -> Option<&IndexedChannel> {
         self.channels.iter().find(|c| c.is_master())
     }
+
+    /// Time between the first and last record, from [`Self::time_range`].
+    pub fn duration(&self) -> Option<f64> {
+        self.time_range.map(|(first, last)| last - first)
+    }
+}
+
+/// [`ByteRangeReader`] over a borrowed buffer, used to share the
+/// range-reader code paths while building an index from a memory map.
+struct BorrowedRangeReader<'a>(&'a [u8]);
+
+impl ByteRangeReader for BorrowedRangeReader<'_> {
+    type Error = MdfError;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+        bytes_at(self.0, offset, length).map(<[u8]>::to_vec)
+    }
 }
 
 /// Where an [`MdfIndex`] reads sample data from when asked to.
@@ -194,17 +273,117 @@ impl IndexedChannelGroup {
 #[derive(Debug, Clone)]
 pub enum Source {
     /// A local file path, read via memory map.
-    File(String),
+    File(PathBuf),
     /// An HTTP/S3 URL, read via range requests.
     #[cfg(feature = "http")]
     Url(String),
 }
 
+/// One channel / record window in a batched read; see [`MdfIndex::plan_reads`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeRequest {
+    /// Group name to resolve `channel` in, or `None` for the first match.
+    pub group: Option<String>,
+    /// Channel name.
+    pub channel: String,
+    /// Record window (0-based, end exclusive), or `None` for all records.
+    pub records: Option<std::ops::Range<u64>>,
+    /// Also cover the channel's invalidation byte in every record; see
+    /// [`MdfIndex::byte_ranges_with_invalidation`].
+    pub invalidation: bool,
+}
+
+impl RangeRequest {
+    /// Request every record of `channel`, resolved by name.
+    pub fn channel(channel: impl Into<String>) -> Self {
+        RangeRequest { group: None, channel: channel.into(), records: None, invalidation: false }
+    }
+
+    /// Restrict the request to a group.
+    pub fn in_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Restrict the request to a record window.
+    pub fn records(mut self, records: std::ops::Range<u64>) -> Self {
+        self.records = Some(records);
+        self
+    }
+
+    /// Include the channel's invalidation byte in the requested ranges.
+    pub fn with_invalidation(mut self) -> Self {
+        self.invalidation = true;
+        self
+    }
+}
+
+/// A byte range needed by one request, located inside a merged read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedRange {
+    /// Index into [`ReadPlan::reads`].
+    pub read: usize,
+    /// Absolute file offset of the range.
+    pub offset: u64,
+    /// Length of the range in bytes.
+    pub length: u64,
+}
+
+/// Merged read plan for several [`RangeRequest`]s.
+///
+/// `reads` are disjoint `(offset, length)` ranges in ascending offset order;
+/// `requests[i]` lists, for the `i`-th request, where each of its byte ranges
+/// lives inside those reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadPlan {
+    pub reads: Vec<(u64, u64)>,
+    pub requests: Vec<Vec<PlannedRange>>,
+}
+
+impl ReadPlan {
+    /// Issue every read of the plan, in order. Returns one buffer per read.
+    pub fn fetch<R: ByteRangeReader<Error = MdfError>>(&self, reader: &mut R) -> Result<Vec<Vec<u8>>, MdfError> {
+        self.reads.iter().map(|&(offset, length)| reader.read_range(offset, length)).collect()
+    }
+
+    /// Slices of `fetched` (as returned by [`ReadPlan::fetch`]) holding the
+    /// bytes of request `request`, in the order of its byte ranges.
+    pub fn request_bytes<'a>(&self, request: usize, fetched: &'a [Vec<u8>]) -> Result<Vec<&'a [u8]>, MdfError> {
+        let ranges = self.requests.get(request).ok_or(MdfError::InvalidIndex {
+            kind: "request",
+            index: request,
+            len: self.requests.len(),
+        })?;
+        ranges
+            .iter()
+            .map(|r| {
+                let start = (r.offset - self.reads[r.read].0) as usize;
+                fetched
+                    .get(r.read)
+                    .and_then(|buf| buf.get(start..start + r.length as usize))
+                    .ok_or(MdfError::TooShortBuffer {
+                        actual: fetched.get(r.read).map_or(0, Vec::len),
+                        expected: start + r.length as usize,
+                        file: file!(),
+                        line: line!(),
+                    })
+            })
+            .collect()
+    }
+}
+
 /// Complete MDF file index
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MdfIndex {
     /// File size for validation
     pub file_size: u64,
+    /// Digest of the identification and header blocks of the file the index
+    /// was built from. Together with `file_size` this binds the index to one
+    /// file: reads against a rewritten file fail with
+    /// [`MdfError::StaleIndex`] instead of decoding garbage. `None` for
+    /// indexes saved before the digest existed.
+    #[serde(default)]
+    pub header_digest: Option<u64>,
     /// Start time of the measurement in nanoseconds since epoch (from MDF header)
     /// None if the start time is not set (0) in the file
     pub start_time_ns: Option<u64>,
@@ -217,6 +396,47 @@ pub struct MdfIndex {
     pub source: Option<Source>,
 }
 
+/// Number of leading file bytes covered by [`MdfIndex::header_digest`]: the
+/// 64-byte identification block plus the 104-byte header block.
+const IDENTITY_PREFIX_LEN: u64 = 64 + 104;
+
+/// FNV-1a over `bytes`; cheap and stable across platforms and releases.
+/// Used for the few bytes of [`MdfIndex::header_digest`].
+fn identity_digest(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// XXH3 over a whole data block for [`DataBlockInfo::digest`]; hashes
+/// several GB/s and, unlike FNV-1a, mixes every bit of long inputs.
+fn block_digest(bytes: &[u8]) -> u64 {
+    twox_hash::xxh3::hash64(bytes)
+}
+
+/// A read that ran off the end of the source means the file shrank since the
+/// index was built.
+fn stale_if_short(err: MdfError) -> MdfError {
+    match err {
+        MdfError::TooShortBuffer { actual, expected, .. } => MdfError::StaleIndex(format!(
+            "source ends at {actual} bytes but the index expects at least {expected}"
+        )),
+        MdfError::IOError(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            MdfError::StaleIndex("source is shorter than the indexed file".to_string())
+        }
+        other => other,
+    }
+}
+
+/// Leading bytes of an index written by [`MdfIndex::to_binary`].
+#[cfg(feature = "binary-index")]
+pub const BINARY_INDEX_MAGIC: &[u8; 8] = b"MF4RSIDX";
+
+/// Format version written after [`BINARY_INDEX_MAGIC`]; bumped whenever the
+/// binary layout changes incompatibly.
+#[cfg(feature = "binary-index")]
+pub const BINARY_INDEX_VERSION: u32 = 1;
+
 /// Trait for reading byte ranges from different sources (files, HTTP, etc.)
 pub trait ByteRangeReader {
     type Error;
@@ -224,6 +444,77 @@ pub trait ByteRangeReader {
     /// Read bytes from the specified range
     /// Returns the requested bytes or an error
     fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error>;
+
+    /// Total size of the source in bytes, if the reader can tell.
+    ///
+    /// Used by [`MdfIndex::from_reader`]; the default reports `None`.
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        Ok(None)
+    }
+}
+
+/// Async counterpart of [`ByteRangeReader`] for sources behind async HTTP
+/// or object-store clients; used through [`MdfIndex::open_async`].
+///
+/// The returned future must be `Send` so reads can run on multi-threaded
+/// runtimes. Implementations can use `async fn`:
+///
+/// ```
+/// use mf4_rs::error::MdfError;
+/// use mf4_rs::index::AsyncByteRangeReader;
+///
+/// struct InMemory(Vec<u8>);
+///
+/// impl AsyncByteRangeReader for InMemory {
+///     type Error = MdfError;
+///
+///     async fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+///         Ok(self.0[offset as usize..(offset + length) as usize].to_vec())
+///     }
+/// }
+/// ```
+pub trait AsyncByteRangeReader {
+    type Error;
+
+    /// Read bytes from the specified range
+    fn read_range(
+        &mut self,
+        offset: u64,
+        length: u64,
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, Self::Error>> + Send;
+}
+
+/// The data blocks of one channel group, fetched by [`AsyncMdfReader`] and
+/// served to the sync decoding path.
+struct FetchedRanges {
+    ranges: HashMap<(u64, u64), Vec<u8>>,
+}
+
+impl ByteRangeReader for FetchedRanges {
+    type Error = MdfError;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error> {
+        self.ranges.get(&(offset, length)).cloned().ok_or_else(|| {
+            MdfError::BlockSerializationError(format!(
+                "range {:#x}+{} was not fetched",
+                offset, length
+            ))
+        })
+    }
+}
+
+/// Read exactly `length` bytes from `source`. The buffer grows with the data
+/// actually read, so a corrupt block length fails with `UnexpectedEof`
+/// instead of allocating `length` bytes up front.
+fn read_exact_len(source: &mut impl std::io::Read, length: u64) -> Result<Vec<u8>, MdfError> {
+    use std::io::Read;
+
+    let mut buffer = Vec::new();
+    source.take(length).read_to_end(&mut buffer)?;
+    if (buffer.len() as u64) < length {
+        return Err(MdfError::IOError(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(buffer)
 }
 
 /// Local file reader implementation.
@@ -237,7 +528,7 @@ pub struct FileRangeReader {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl FileRangeReader {
-    pub fn new(file_path: &str) -> Result<Self, MdfError> {
+    pub fn new(file_path: impl AsRef<Path>) -> Result<Self, MdfError> {
         let file = std::fs::File::open(file_path)
             .map_err(|e| MdfError::IOError(e))?;
         Ok(Self { file })
@@ -249,16 +540,16 @@ impl ByteRangeReader for FileRangeReader {
     type Error = MdfError;
 
     fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error> {
-        use std::io::{Read, Seek, SeekFrom};
+        use std::io::{Seek, SeekFrom};
 
         self.file.seek(SeekFrom::Start(offset))
             .map_err(|e| MdfError::IOError(e))?;
 
-        let mut buffer = vec![0u8; length as usize];
-        self.file.read_exact(&mut buffer)
-            .map_err(|e| MdfError::IOError(e))?;
+        read_exact_len(&mut self.file, length)
+    }
 
-        Ok(buffer)
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        Ok(Some(self.file.metadata()?.len()))
     }
 }
 
@@ -272,7 +563,7 @@ pub struct MmapRangeReader {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl MmapRangeReader {
-    pub fn new(file_path: &str) -> Result<Self, MdfError> {
+    pub fn new(file_path: impl AsRef<Path>) -> Result<Self, MdfError> {
         let file = std::fs::File::open(file_path).map_err(MdfError::IOError)?;
         let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(MdfError::IOError)?;
         Ok(Self { mmap })
@@ -284,17 +575,44 @@ impl ByteRangeReader for MmapRangeReader {
     type Error = MdfError;
 
     fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error> {
-        let start = offset as usize;
-        let end = start + length as usize;
-        if end > self.mmap.len() {
-            return Err(MdfError::TooShortBuffer {
-                actual: self.mmap.len(),
-                expected: end,
-                file: file!(),
-                line: line!(),
-            });
-        }
-        Ok(self.mmap[start..end].to_vec())
+        bytes_at(&self.mmap, offset, length).map(<[u8]>::to_vec)
+    }
+
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        Ok(Some(self.mmap.len() as u64))
+    }
+}
+
+/// Reader over any `Read + Seek` source — available on all targets.
+///
+/// Each range is read with a seek and `read_exact`, so only the requested
+/// bytes are pulled from the source (e.g. a `Cursor`, a file or a
+/// `Seek`-capable object-store client).
+pub struct SeekRangeReader<R> {
+    inner: R,
+}
+
+impl<R: std::io::Read + std::io::Seek> SeekRangeReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Unwrap the underlying source.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: std::io::Read + std::io::Seek> ByteRangeReader for SeekRangeReader<R> {
+    type Error = MdfError;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error> {
+        self.inner.seek(std::io::SeekFrom::Start(offset))?;
+        read_exact_len(&mut self.inner, length)
+    }
+
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        Ok(Some(self.inner.seek(std::io::SeekFrom::End(0))?))
     }
 }
 
@@ -317,17 +635,11 @@ impl ByteRangeReader for SliceRangeReader {
     type Error = MdfError;
 
     fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, Self::Error> {
-        let start = offset as usize;
-        let end = start + length as usize;
-        if end > self.data.len() {
-            return Err(MdfError::TooShortBuffer {
-                actual: self.data.len(),
-                expected: end,
-                file: file!(),
-                line: line!(),
-            });
-        }
-        Ok(self.data[start..end].to_vec())
+        bytes_at(&self.data, offset, length).map(<[u8]>::to_vec)
+    }
+
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        Ok(Some(self.data.len() as u64))
     }
 }
 
@@ -489,6 +801,10 @@ impl<R: ByteRangeReader<Error = MdfError>> ByteRangeReader for CachingRangeReade
 
         Ok(out)
     }
+
+    fn size(&mut self) -> Result<Option<u64>, MdfError> {
+        self.inner.size()
+    }
 }
 
 /// HTTP range-request reader using the synchronous [`ureq`] client.
@@ -618,6 +934,10 @@ impl ByteRangeReader for HttpRangeReader {
             .map_err(MdfError::IOError)?;
         Ok(buf)
     }
+
+    fn size(&mut self) -> Result<Option<u64>, Self::Error> {
+        self.probe_size().map(Some)
+    }
 }
 
 impl MdfIndex {
@@ -625,13 +945,22 @@ impl MdfIndex {
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`from_bytes`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn from_file(file_path: &str) -> Result<Self, MdfError> {
+    pub fn from_file(file_path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        Self::from_file_with_options(file_path, IndexOptions::default())
+    }
+
+    /// [`MdfIndex::from_file`] with explicit [`IndexOptions`].
+    ///
+    /// Not available on `wasm32-unknown-unknown`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file_with_options(file_path: impl AsRef<Path>, options: IndexOptions) -> Result<Self, MdfError> {
+        let file_path = file_path.as_ref();
         let mdf = MDF::from_file(file_path)?;
         let file_size = std::fs::metadata(file_path)
             .map_err(|e| MdfError::IOError(e))?
             .len();
-        let mut index = Self::build_index(mdf, file_size)?;
-        index.source = Some(Source::File(file_path.to_string()));
+        let mut index = Self::build_index(mdf, file_size, options)?;
+        index.source = Some(Source::File(file_path.to_path_buf()));
         Ok(index)
     }
 
@@ -657,8 +986,10 @@ impl MdfIndex {
     }
 
     /// Shared index-building logic operating on an already-parsed [`MDF`].
-    fn build_index(mdf: MDF, file_size: u64) -> Result<Self, MdfError> {
+    fn build_index(mdf: MDF, file_size: u64, options: IndexOptions) -> Result<Self, MdfError> {
         let start_time_ns = mdf.start_time_ns();
+        let prefix_len = IDENTITY_PREFIX_LEN.min(mdf.mmap().len() as u64) as usize;
+        let header_digest = Some(identity_digest(&mdf.mmap()[..prefix_len]));
         let mut indexed_groups = Vec::new();
 
         for group in mdf.channel_groups() {
@@ -681,6 +1012,7 @@ impl MdfIndex {
                 indexed_channels.push(IndexedChannel {
                     name: channel.name()?,
                     unit: channel.unit()?,
+                    comment: channel.comment()?,
                     data_type: block.data_type.clone(),
                     byte_offset: block.byte_offset,
                     bit_offset: block.bit_offset,
@@ -697,9 +1029,22 @@ impl MdfIndex {
                 });
             }
 
-            let data_blocks = Self::extract_data_blocks(&group)?;
+            let mut data_blocks = Self::extract_data_blocks(&group)?;
+            Self::check_blocks_in_file(&data_blocks, file_size)?;
+            if options.hash_data_blocks {
+                for block in &mut data_blocks {
+                    let start = block.file_offset as usize;
+                    let bytes = mmap.get(start..start + block.size as usize).ok_or_else(|| {
+                        MdfError::BlockSerializationError(format!(
+                            "data block at offset {} runs past the end of the file",
+                            block.file_offset
+                        ))
+                    })?;
+                    block.digest = Some(block_digest(bytes));
+                }
+            }
 
-            indexed_groups.push(IndexedChannelGroup {
+            let mut indexed_group = IndexedChannelGroup {
                 name: group.name()?,
                 comment: group.comment()?,
                 record_id_len: group.raw_data_group().block.record_id_len,
@@ -708,10 +1053,23 @@ impl MdfIndex {
                 record_count: group.raw_channel_group().block.cycles_nr,
                 channels: indexed_channels,
                 data_blocks,
-            });
+                time_range: None,
+            };
+            // Records without a single byte carry no samples to read back.
+            if Self::full_record_size(&indexed_group) == 0 {
+                indexed_group.data_blocks.clear();
+            }
+            indexed_group.time_range = Self::master_time_range(&indexed_group, &mut BorrowedRangeReader(mmap))?;
+            indexed_groups.push(indexed_group);
         }
 
-        Ok(MdfIndex { file_size, start_time_ns, channel_groups: indexed_groups, source: None })
+        Ok(MdfIndex {
+            file_size,
+            header_digest,
+            start_time_ns,
+            channel_groups: indexed_groups,
+            source: None,
+        })
     }
 
     /// Extract data block information from a channel group
@@ -719,14 +1077,28 @@ impl MdfIndex {
         let mut data_blocks = Vec::new();
         let raw_data_group = group.raw_data_group();
         let mmap = group.mmap();
+        // Byte-range reads assume every record in the chain belongs to the
+        // group, which does not hold for unsorted data groups.
+        if !raw_data_group.is_sorted() {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "data group with {} channel groups is unsorted and cannot be indexed",
+                raw_data_group.channel_groups.len()
+            )));
+        }
         
         // Start at the group's primary data pointer
+        let mut visited = std::collections::HashSet::new();
         let mut current_block_address = raw_data_group.block.data_block_addr;
         while current_block_address != 0 {
-            let byte_offset = current_block_address as usize;
+            if !visited.insert(current_block_address) {
+                return Err(MdfError::BlockLinkError(format!(
+                    "##DL chain loops back to {current_block_address:#x}"
+                )));
+            }
+            let block = link_target(mmap, current_block_address, "data")?;
 
             // Read the block header
-            let block_header = crate::blocks::common::BlockHeader::from_bytes(&mmap[byte_offset..byte_offset + 24])?;
+            let block_header = crate::blocks::common::BlockHeader::from_bytes(block)?;
 
             match block_header.id.as_str() {
                 "##DT" | "##DV" => {
@@ -735,6 +1107,7 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.block_len,
                         is_compressed: false,
+                        digest: None,
                     };
                     data_blocks.push(data_block_info);
                     // No list to follow, we're done
@@ -746,24 +1119,25 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.block_len,
                         is_compressed: true,
+                        digest: None,
                     };
                     data_blocks.push(data_block_info);
                     current_block_address = 0;
                 }
                 "##DL" => {
                     // Fragmented list of data blocks
-                    let data_list_block = crate::blocks::data_list_block::DataListBlock::from_bytes(&mmap[byte_offset..])?;
+                    let data_list_block = crate::blocks::data_list_block::DataListBlock::from_bytes(block)?;
 
                     // Parse each fragment in this list
                     for &fragment_address in &data_list_block.data_links {
-                        let fragment_offset = fragment_address as usize;
-                        let fragment_header = crate::blocks::common::BlockHeader::from_bytes(&mmap[fragment_offset..fragment_offset + 24])?;
+                        let fragment_header = crate::blocks::common::BlockHeader::from_bytes(link_target(mmap, fragment_address, "data")?)?;
                         
                         let is_compressed = fragment_header.id == "##DZ";
                         let data_block_info = DataBlockInfo {
                             file_offset: fragment_address,
                             size: fragment_header.block_len,
                             is_compressed,
+                            digest: None,
                         };
                         data_blocks.push(data_block_info);
                     }
@@ -790,7 +1164,26 @@ impl MdfIndex {
     pub fn from_bytes(data: Vec<u8>) -> Result<Self, MdfError> {
         let file_size = data.len() as u64;
         let mdf = MDF::from_bytes(data)?;
-        Self::build_index(mdf, file_size)
+        Self::build_index(mdf, file_size, IndexOptions::default())
+    }
+
+    /// Build an index by following block links through `reader`, asking it
+    /// for the file size via [`ByteRangeReader::size`].
+    ///
+    /// Only metadata is read — typically a few KB regardless of how much
+    /// sample data the file holds — so indexes can be built directly
+    /// against S3/HTTP objects. Fails if the reader cannot report its size;
+    /// use [`MdfIndex::from_range_reader`] to pass it explicitly.
+    pub fn from_reader<R>(reader: &mut R) -> Result<Self, MdfError>
+    where
+        R: ByteRangeReader<Error = MdfError>,
+    {
+        let file_size = reader.size()?.ok_or_else(|| {
+            MdfError::BlockSerializationError(
+                "reader cannot report the file size; use from_range_reader".to_string(),
+            )
+        })?;
+        Self::from_range_reader(reader, file_size)
     }
 
     /// Build an [`MdfIndex`] using only [`ByteRangeReader`] calls.
@@ -824,12 +1217,20 @@ impl MdfIndex {
 
         let mut indexed_groups = Vec::with_capacity(walk.groups.len());
         for group in walk.groups {
+            // Same restriction as the mmap path in `extract_data_blocks`.
+            if group.record_id_len != 0 && group.dg_channel_groups > 1 {
+                return Err(MdfError::UnsupportedFeature(format!(
+                    "data group with {} channel groups is unsorted and cannot be indexed",
+                    group.dg_channel_groups
+                )));
+            }
             let mut indexed_channels = Vec::with_capacity(group.channels.len());
             for ch in group.channels {
                 let block = ch.block;
                 indexed_channels.push(IndexedChannel {
                     name: ch.name,
                     unit: ch.unit,
+                    comment: ch.comment,
                     data_type: block.data_type.clone(),
                     byte_offset: block.byte_offset,
                     bit_offset: block.bit_offset,
@@ -848,8 +1249,9 @@ impl MdfIndex {
 
             let data_blocks =
                 Self::extract_data_blocks_via_reader(reader, group.data_block_addr)?;
+            Self::check_blocks_in_file(&data_blocks, file_size)?;
 
-            indexed_groups.push(IndexedChannelGroup {
+            let mut indexed_group = IndexedChannelGroup {
                 name: group.cg_name,
                 comment: group.cg_comment,
                 record_id_len: group.record_id_len,
@@ -858,17 +1260,45 @@ impl MdfIndex {
                 record_count: group.cg.cycles_nr,
                 channels: indexed_channels,
                 data_blocks,
-            });
+                time_range: None,
+            };
+            // Records without a single byte carry no samples to read back.
+            if Self::full_record_size(&indexed_group) == 0 {
+                indexed_group.data_blocks.clear();
+            }
+            indexed_group.time_range = Self::master_time_range(&indexed_group, reader)?;
+            indexed_groups.push(indexed_group);
         }
 
+        let prefix = reader.read_range(0, IDENTITY_PREFIX_LEN.min(file_size))?;
+
         Ok(MdfIndex {
             file_size,
+            header_digest: Some(identity_digest(&prefix)),
             start_time_ns,
             channel_groups: indexed_groups,
             source: None,
         })
     }
 
+    /// Fail if a data block reaches past the end of the file. Reads size
+    /// their buffers from the recorded block lengths, so a corrupt length
+    /// must not get into the index.
+    fn check_blocks_in_file(data_blocks: &[DataBlockInfo], file_size: u64) -> Result<(), MdfError> {
+        for block in data_blocks {
+            let end = block.file_offset.saturating_add(block.size);
+            if end > file_size {
+                return Err(MdfError::TooShortBuffer {
+                    actual: file_size as usize,
+                    expected: end as usize,
+                    file: file!(),
+                    line: line!(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Mirror of [`Self::extract_data_blocks`] that fetches headers via a
     /// [`ByteRangeReader`] instead of slicing into a memory map.
     fn extract_data_blocks_via_reader<R>(
@@ -879,9 +1309,15 @@ impl MdfIndex {
         R: ByteRangeReader<Error = MdfError>,
     {
         let mut data_blocks = Vec::new();
+        let mut visited = std::collections::HashSet::new();
         let mut current_block_address = data_block_addr;
 
         while current_block_address != 0 {
+            if !visited.insert(current_block_address) {
+                return Err(MdfError::BlockLinkError(format!(
+                    "##DL chain loops back to {current_block_address:#x}"
+                )));
+            }
             let header_bytes = reader.read_range(current_block_address, 24)?;
             let block_header =
                 crate::blocks::common::BlockHeader::from_bytes(&header_bytes)?;
@@ -892,6 +1328,7 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.block_len,
                         is_compressed: false,
+                        digest: None,
                     });
                     current_block_address = 0;
                 }
@@ -900,6 +1337,7 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.block_len,
                         is_compressed: true,
+                        digest: None,
                     });
                     current_block_address = 0;
                 }
@@ -919,6 +1357,7 @@ impl MdfIndex {
                             file_offset: fragment_address,
                             size: fragment_header.block_len,
                             is_compressed,
+                            digest: None,
                         });
                     }
 
@@ -936,11 +1375,40 @@ impl MdfIndex {
         Ok(data_blocks)
     }
 
+    /// Master values of the first and last record of `group`, reading only
+    /// those two records (or, for compressed blocks, the blocks holding
+    /// them). `None` if the group has no records or no fixed-length master,
+    /// or if either value is invalid.
+    fn master_time_range<R: ByteRangeReader<Error = MdfError>>(
+        group: &IndexedChannelGroup,
+        reader: &mut R,
+    ) -> Result<Option<(f64, f64)>, MdfError> {
+        let Some(master) = group.master_channel() else { return Ok(None) };
+        if group.record_count == 0 || Self::full_record_size(group) == 0 || master.is_vlsd() {
+            return Ok(None);
+        }
+        let temp_cb = master.to_decode_only_channel_block();
+        let linear_coeffs = Self::get_linear_coeffs(master);
+        let has_conversion = master.conversion.is_some();
+        let mut values = Vec::with_capacity(2);
+        for record in [0, group.record_count - 1] {
+            let records = Self::read_record_window(group, record, 1, reader)?;
+            Self::decode_records_to_f64(
+                &records, Self::full_record_size(group), group, master, &temp_cb,
+                linear_coeffs, has_conversion, &mut values,
+            )?;
+        }
+        Ok(match values[..] {
+            [first, last] if !first.is_nan() && !last.is_nan() => Some((first, last)),
+            _ => None,
+        })
+    }
+
     /// Save the index to a JSON file.
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`to_json`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn save_to_file(&self, index_path: &str) -> Result<(), MdfError> {
+    pub fn save_to_file(&self, index_path: impl AsRef<Path>) -> Result<(), MdfError> {
         let json = self.to_json()?;
         std::fs::write(index_path, json)
             .map_err(|e| MdfError::IOError(e))?;
@@ -951,7 +1419,7 @@ impl MdfIndex {
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`from_json`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn load_from_file(index_path: &str) -> Result<Self, MdfError> {
+    pub fn load_from_file(index_path: impl AsRef<Path>) -> Result<Self, MdfError> {
         let json = std::fs::read_to_string(index_path)
             .map_err(|e| MdfError::IOError(e))?;
         Self::from_json(&json)
@@ -969,6 +1437,67 @@ impl MdfIndex {
             .map_err(|e| MdfError::BlockSerializationError(format!("JSON deserialization failed: {}", e)))
     }
 
+    /// Save the index in the compact binary format of [`MdfIndex::to_binary`].
+    ///
+    /// Requires the `binary-index` feature. Not available on
+    /// `wasm32-unknown-unknown`.
+    #[cfg(all(feature = "binary-index", not(target_arch = "wasm32")))]
+    pub fn save_to_file_binary(&self, index_path: impl AsRef<Path>) -> Result<(), MdfError> {
+        std::fs::write(index_path, self.to_binary()?)?;
+        Ok(())
+    }
+
+    /// Load an index written by [`MdfIndex::save_to_file_binary`].
+    ///
+    /// Requires the `binary-index` feature. Not available on
+    /// `wasm32-unknown-unknown`.
+    #[cfg(all(feature = "binary-index", not(target_arch = "wasm32")))]
+    pub fn load_from_file_binary(index_path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        Self::from_binary(&std::fs::read(index_path)?)
+    }
+
+    /// Serialize the index to a compact binary form: the bytes
+    /// [`BINARY_INDEX_MAGIC`], a little-endian `u32`
+    /// [`BINARY_INDEX_VERSION`], then the index as MessagePack.
+    ///
+    /// Much smaller and faster to load than JSON for files with many
+    /// channels; keep JSON where other tools need to read the index.
+    /// Requires the `binary-index` feature.
+    #[cfg(feature = "binary-index")]
+    pub fn to_binary(&self) -> Result<Vec<u8>, MdfError> {
+        let mut out = Vec::with_capacity(BINARY_INDEX_MAGIC.len() + 4);
+        out.extend_from_slice(BINARY_INDEX_MAGIC);
+        out.extend_from_slice(&BINARY_INDEX_VERSION.to_le_bytes());
+        rmp_serde::encode::write_named(&mut out, self)
+            .map_err(|e| MdfError::BlockSerializationError(format!("binary index serialization failed: {}", e)))?;
+        Ok(out)
+    }
+
+    /// Deserialize an index written by [`MdfIndex::to_binary`].
+    ///
+    /// Fails if the magic bytes are missing or the format version is not
+    /// [`BINARY_INDEX_VERSION`]. Requires the `binary-index` feature.
+    #[cfg(feature = "binary-index")]
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, MdfError> {
+        let header_len = BINARY_INDEX_MAGIC.len() + 4;
+        if bytes.len() < header_len || &bytes[..BINARY_INDEX_MAGIC.len()] != BINARY_INDEX_MAGIC {
+            return Err(MdfError::BlockSerializationError(
+                "not a binary mf4-rs index".to_string(),
+            ));
+        }
+        let mut version = [0u8; 4];
+        version.copy_from_slice(&bytes[BINARY_INDEX_MAGIC.len()..header_len]);
+        let version = u32::from_le_bytes(version);
+        if version != BINARY_INDEX_VERSION {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "binary index version {} is not supported (expected {})",
+                version, BINARY_INDEX_VERSION
+            )));
+        }
+        rmp_serde::from_slice(&bytes[header_len..])
+            .map_err(|e| MdfError::BlockSerializationError(format!("binary index deserialization failed: {}", e)))
+    }
+
     /// Read channel values using the index and a byte range reader.
     ///
     /// Internal positional helper — the public entry point is
@@ -984,11 +1513,7 @@ impl MdfIndex {
         channel_index: usize,
         reader: &mut R
     ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         // Handle VLSD channels differently
         if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
@@ -999,6 +1524,56 @@ impl MdfIndex {
         self.read_regular_channel_values(group, channel, reader)
     }
 
+    /// Read channel values together with their validity using the index and
+    /// a byte range reader.
+    ///
+    /// Internal positional helper — the public entry point is
+    /// [`MdfReader::values_with_validity`]. Invalid samples keep their
+    /// decoded value with `is_valid == false`.
+    pub(crate) fn read_channel_values_with_validity<R: ByteRangeReader<Error = MdfError>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        reader: &mut R,
+    ) -> Result<Vec<DecodedChannelValue>, MdfError> {
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
+        if channel.is_vlsd() {
+            return Err(MdfError::UnsupportedFeature(
+                "VLSD channels not yet supported in index reader".to_string()
+            ));
+        }
+
+        let record_size = group.record_id_len as usize + group.record_size as usize + group.invalidation_bytes as usize;
+        let total_records: usize = group.data_blocks.iter()
+            .map(|db| ((db.size - 24) / record_size as u64) as usize)
+            .sum();
+        let mut values = Vec::with_capacity(total_records);
+        let temp_cb = channel.to_channel_block();
+
+        for data_block in &group.data_blocks {
+            let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+            let block_data = Self::block_data(data_block, &block)?;
+            let record_id_len = group.record_id_len as usize;
+            for record in block_data.chunks_exact(record_size) {
+                let decoded = decode_channel_value_with_validity(
+                    record, record_id_len, group.record_size, &temp_cb,
+                );
+                values.push(match decoded {
+                    Some(DecodedChannelValue { value, is_valid }) => DecodedChannelValue {
+                        value: match &channel.conversion {
+                            Some(conversion) => conversion.apply_decoded(value, &[])?,
+                            None => value,
+                        },
+                        is_valid,
+                    },
+                    None => DecodedChannelValue { value: DecodedValue::Unknown, is_valid: false },
+                });
+            }
+        }
+
+        Ok(values)
+    }
+
     /// Extract linear conversion coefficients (a, b) for inline application.
     fn get_linear_coeffs(channel: &IndexedChannel) -> Option<(f64, f64)> {
         channel.conversion.as_ref().and_then(|conv| {
@@ -1025,13 +1600,8 @@ impl MdfIndex {
         let temp_cb = channel.to_channel_block();
 
         for data_block in &group.data_blocks {
-            if data_block.is_compressed {
-                return Err(MdfError::BlockSerializationError(
-                    "Compressed blocks not yet supported in index reader".to_string()
-                ));
-            }
-
-            let block_data = reader.read_range(data_block.file_offset + 24, data_block.size - 24)?;
+            let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+            let block_data = Self::block_data(data_block, &block)?;
             Self::decode_records_to_values(&block_data, record_size, group, channel, &temp_cb, &mut values)?;
         }
 
@@ -1090,10 +1660,16 @@ impl MdfIndex {
         let record_count = block_data.len() / record_size;
         let record_id_len = group.record_id_len as usize;
         let cg_data_bytes = group.record_size;
-        let has_invalidation = group.invalidation_bytes > 0;
+        // A channel flagged "all values invalid" needs the validity check
+        // even without invalidation bytes.
+        let has_invalidation = group.invalidation_bytes > 0 || channel.flags & 0x01 != 0;
 
         if !has_invalidation && !has_conversion {
             // Fastest path: no invalidation, no conversion - just decode f64 directly
+            if let Some(layout) = PrimitiveLayout::of(temp_cb, record_id_len, record_size) {
+                extend_f64_column(block_data, record_size, layout, values);
+                return Ok(());
+            }
             for i in 0..record_count {
                 let record = &block_data[i * record_size..(i + 1) * record_size];
                 values.push(decode_f64_from_record(record, record_id_len, temp_cb));
@@ -1172,7 +1748,7 @@ impl MdfIndex {
         _reader: &mut R,
     ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
         // TODO: Implement VLSD channel reading
-        Err(MdfError::BlockSerializationError(
+        Err(MdfError::UnsupportedFeature(
             "VLSD channels not yet supported in index reader".to_string()
         ))
     }
@@ -1197,6 +1773,12 @@ impl MdfIndex {
         self.channel_groups.get(g)?.channels.get(c)
     }
 
+    /// First and last master (time) value of the group called `group`, as
+    /// stored when the index was built; no sample data is read.
+    pub fn time_range(&self, group: &str) -> Option<(f64, f64)> {
+        self.group(group)?.time_range
+    }
+
     /// Look up a channel by group name + channel name.
     pub fn channel_in(&self, group: &str, name: &str) -> Option<&IndexedChannel> {
         self.group(group)?.channel(name)
@@ -1205,7 +1787,7 @@ impl MdfIndex {
     /// The attached data source rendered as a string (file path or URL).
     pub fn source_string(&self) -> Option<String> {
         match &self.source {
-            Some(Source::File(p)) => Some(p.clone()),
+            Some(Source::File(p)) => Some(p.to_string_lossy().into_owned()),
             #[cfg(feature = "http")]
             Some(Source::Url(u)) => Some(u.clone()),
             None => None,
@@ -1228,6 +1810,12 @@ impl MdfIndex {
         out
     }
 
+    /// Metadata of channel `idx` of group `group`, both counted from zero;
+    /// `None` if either is out of range.
+    pub fn channel_descriptor(&self, group: usize, idx: usize) -> Option<ChannelDescriptor> {
+        Some(self.channel_groups.get(group)?.channels.get(idx)?.descriptor())
+    }
+
     /// Every channel name across all groups, in file order (duplicates kept).
     pub fn channel_names(&self) -> Vec<&str> {
         self.channel_groups
@@ -1252,6 +1840,26 @@ impl MdfIndex {
         None
     }
 
+    /// Group `group_index` and its channel `channel_index`, or
+    /// [`MdfError::InvalidIndex`] for whichever is out of range.
+    pub(crate) fn group_channel(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+    ) -> Result<(&IndexedChannelGroup, &IndexedChannel), MdfError> {
+        let group = self.channel_groups.get(group_index).ok_or(MdfError::InvalidIndex {
+            kind: "group",
+            index: group_index,
+            len: self.channel_groups.len(),
+        })?;
+        let channel = group.channels.get(channel_index).ok_or(MdfError::InvalidIndex {
+            kind: "channel",
+            index: channel_index,
+            len: group.channels.len(),
+        })?;
+        Ok((group, channel))
+    }
+
     /// Resolve a `(group name, channel name)` pair to indices.
     pub(crate) fn locate_in(&self, group: &str, name: &str) -> Option<(usize, usize)> {
         let g = self
@@ -1281,21 +1889,129 @@ impl MdfIndex {
         matches
     }
 
+    /// All channels whose name matches `pattern`, as `(group_index,
+    /// channel_index, name)` in file order.
+    ///
+    /// A `&str` is a glob (see [`NamePattern`]); with the `regex` feature a
+    /// `regex::Regex` can be passed as well.
+    pub fn find_channels_matching(&self, pattern: impl Into<NamePattern>) -> Vec<(usize, usize, String)> {
+        let pattern = pattern.into();
+        let mut matches = Vec::new();
+        for (g, group) in self.channel_groups.iter().enumerate() {
+            for (c, channel) in group.channels.iter().enumerate() {
+                if let Some(name) = channel.name.as_deref()
+                    && pattern.is_match(name)
+                {
+                    matches.push((g, c, name.to_string()));
+                }
+            }
+        }
+        matches
+    }
+
+    /// Copy of this index whose channels carry no conversions, so values
+    /// read through it are the stored ones.
+    pub(crate) fn without_conversions(&self) -> MdfIndex {
+        let mut index = self.clone();
+        for group in &mut index.channel_groups {
+            for channel in &mut group.channels {
+                channel.conversion = None;
+            }
+        }
+        index
+    }
+
     /// Bind this index to a byte-range source for reading sample data.
     ///
     /// The returned [`MdfReader`] borrows the index and owns `reader`; read
     /// values by channel name without re-supplying the source each time.
+    ///
+    /// Every data block read through the reader has its header checked
+    /// against the index; call [`MdfIndex::verify`] first to also confirm the
+    /// source is the file the index was built from.
     pub fn open<R: ByteRangeReader<Error = MdfError>>(&self, reader: R) -> MdfReader<'_, R> {
-        MdfReader { index: self, reader }
+        MdfReader { index: self, raw_index: None, reader }
     }
 
-    /// Bind this index to a local file (via memory map) for reading.
-    ///
-    /// Convenience wrapper around [`MdfIndex::open`] using [`MmapRangeReader`].
-    /// Not available on `wasm32-unknown-unknown`.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn open_file(&self, path: &str) -> Result<MdfReader<'_, MmapRangeReader>, MdfError> {
-        Ok(self.open(MmapRangeReader::new(path)?))
+    /// Bind this index to an async byte-range source; the async counterpart
+    /// of [`MdfIndex::open`].
+    pub fn open_async<R: AsyncByteRangeReader<Error = MdfError>>(&self, reader: R) -> AsyncMdfReader<'_, R> {
+        AsyncMdfReader { index: self, reader }
+    }
+
+    /// [`MdfIndex::locate`], failing with a "not found" error.
+    fn require(&self, name: &str) -> Result<(usize, usize), MdfError> {
+        self.locate(name).ok_or_else(|| {
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
+        })
+    }
+
+    /// [`MdfIndex::locate_in`], failing with a "not found" error.
+    fn require_in(&self, group: &str, name: &str) -> Result<(usize, usize), MdfError> {
+        self.locate_in(group, name).ok_or_else(|| {
+            MdfError::ChannelNotFound { name: name.to_string(), group: Some(group.to_string()) }
+        })
+    }
+
+    /// Bind this index to a local file (via memory map) for reading.
+    ///
+    /// Convenience wrapper around [`MdfIndex::open`] using [`MmapRangeReader`].
+    /// Fails with [`MdfError::StaleIndex`] if the file's size or header
+    /// blocks differ from the file the index was built from.
+    /// Not available on `wasm32-unknown-unknown`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_file(&self, path: impl AsRef<Path>) -> Result<MdfReader<'_, MmapRangeReader>, MdfError> {
+        let reader = MmapRangeReader::new(path)?;
+        self.verify_bytes(&reader.mmap)?;
+        Ok(self.open(reader))
+    }
+
+    /// Check that `reader` still serves the file this index was built from.
+    ///
+    /// Compares the identification and header blocks against
+    /// [`MdfIndex::header_digest`] with a single small range read, then
+    /// re-reads every data block that has a [`DataBlockInfo::digest`] and
+    /// compares it too. Fails with [`MdfError::StaleIndex`] on a mismatch;
+    /// digests the index does not have are not checked.
+    pub fn verify<R: ByteRangeReader<Error = MdfError>>(&self, reader: &mut R) -> Result<(), MdfError> {
+        if self.header_digest.is_some() {
+            let prefix = reader
+                .read_range(0, IDENTITY_PREFIX_LEN.min(self.file_size))
+                .map_err(stale_if_short)?;
+            self.check_digest(&prefix)?;
+        }
+        for block in self.channel_groups.iter().flat_map(|g| &g.data_blocks) {
+            let Some(expected) = block.digest else { continue };
+            let bytes = reader.read_range(block.file_offset, block.size).map_err(stale_if_short)?;
+            if block_digest(&bytes) != expected {
+                return Err(MdfError::StaleIndex(format!(
+                    "data block at offset {} differs from the indexed file",
+                    block.file_offset
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// [`MdfIndex::verify`] for a fully mapped file, also checking its size.
+    fn verify_bytes(&self, data: &[u8]) -> Result<(), MdfError> {
+        if data.len() as u64 != self.file_size {
+            return Err(MdfError::StaleIndex(format!(
+                "file is {} bytes but the index was built from a {}-byte file",
+                data.len(),
+                self.file_size
+            )));
+        }
+        self.check_digest(&data[..IDENTITY_PREFIX_LEN.min(self.file_size) as usize])
+    }
+
+    fn check_digest(&self, prefix: &[u8]) -> Result<(), MdfError> {
+        match self.header_digest {
+            Some(expected) if identity_digest(prefix) != expected => Err(MdfError::StaleIndex(
+                "identification/header blocks differ from the indexed file".to_string(),
+            )),
+            _ => Ok(()),
+        }
     }
 
     /// The data source attached to this index, if any.
@@ -1309,7 +2025,7 @@ impl MdfIndex {
     }
 
     /// Attach a local file path as the data source for lazy reads.
-    pub fn set_file(&mut self, path: impl Into<String>) {
+    pub fn set_file(&mut self, path: impl Into<PathBuf>) {
         self.source = Some(Source::File(path.into()));
     }
 
@@ -1327,7 +2043,7 @@ impl MdfIndex {
     /// [`MdfIndex::set_url`]).
     pub fn read(&self, name: &str) -> Result<Signal, MdfError> {
         let (g, c) = self.locate(name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!("Channel '{}' not found", name))
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
         })?;
         self.read_signal(g, c)
     }
@@ -1335,14 +2051,45 @@ impl MdfIndex {
     /// [`MdfIndex::read`] addressed by group name + channel name.
     pub fn read_in(&self, group: &str, name: &str) -> Result<Signal, MdfError> {
         let (g, c) = self.locate_in(group, name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!(
-                "Channel '{}' not found in group '{}'",
-                name, group
-            ))
+            MdfError::ChannelNotFound { name: name.to_string(), group: Some(group.to_string()) }
         })?;
         self.read_signal(g, c)
     }
 
+    /// Read records `start_record..start_record + record_count` of a channel,
+    /// addressed by group name + channel name, using the attached [`Source`].
+    ///
+    /// Only the bytes of the requested records are fetched from uncompressed
+    /// data blocks (see [`MdfReader::values_for_records_in`]).
+    pub fn read_records_in(
+        &self,
+        group: &str,
+        name: &str,
+        start_record: u64,
+        record_count: u64,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = self.require_in(group, name)?;
+        self.read_values_for_records_via_source(g, c, start_record, record_count)
+    }
+
+    /// [`ChannelStatistics`] of a channel by name, read through the attached
+    /// [`Source`].
+    ///
+    /// The data blocks are fetched and folded into the statistics one at a
+    /// time, so memory use stays at one block however long the channel is.
+    /// Samples are taken as by [`MdfReader::values_f64`]. VLSD channels are
+    /// not supported.
+    pub fn channel_statistics(&self, name: &str) -> Result<ChannelStatistics, MdfError> {
+        let (g, c) = self.require(name)?;
+        self.read_statistics_via_source(g, c)
+    }
+
+    /// [`MdfIndex::channel_statistics`] addressed by group name + channel name.
+    pub fn channel_statistics_in(&self, group: &str, name: &str) -> Result<ChannelStatistics, MdfError> {
+        let (g, c) = self.require_in(group, name)?;
+        self.read_statistics_via_source(g, c)
+    }
+
     /// Decode a channel + its group master from the attached source.
     fn read_signal(&self, g: usize, c: usize) -> Result<Signal, MdfError> {
         let (name, unit, master) = {
@@ -1384,10 +2131,11 @@ impl MdfIndex {
             Source::File(path) => {
                 let file = std::fs::File::open(path).map_err(MdfError::IOError)?;
                 let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(MdfError::IOError)?;
+                self.verify_bytes(&mmap)?;
                 self.read_channel_values_from_slice(g, c, &mmap)
             }
             #[cfg(target_arch = "wasm32")]
-            Source::File(_) => Err(MdfError::BlockSerializationError(
+            Source::File(_) => Err(MdfError::UnsupportedFeature(
                 "file sources are not available on wasm32".to_string(),
             )),
             #[cfg(feature = "http")]
@@ -1395,6 +2143,7 @@ impl MdfIndex {
                 let http = HttpRangeReader::new(url)?;
                 let mut cached = CachingRangeReader::new(http);
                 cached.set_bypass(true);
+                self.verify(&mut cached)?;
                 self.read_channel_values(g, c, &mut cached)
             }
         }
@@ -1411,10 +2160,11 @@ impl MdfIndex {
             Source::File(path) => {
                 let file = std::fs::File::open(path).map_err(MdfError::IOError)?;
                 let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(MdfError::IOError)?;
+                self.verify_bytes(&mmap)?;
                 self.read_channel_values_from_slice_as_f64(g, c, &mmap)
             }
             #[cfg(target_arch = "wasm32")]
-            Source::File(_) => Err(MdfError::BlockSerializationError(
+            Source::File(_) => Err(MdfError::UnsupportedFeature(
                 "file sources are not available on wasm32".to_string(),
             )),
             #[cfg(feature = "http")]
@@ -1422,11 +2172,67 @@ impl MdfIndex {
                 let http = HttpRangeReader::new(url)?;
                 let mut cached = CachingRangeReader::new(http);
                 cached.set_bypass(true);
+                self.verify(&mut cached)?;
                 self.read_channel_values_as_f64(g, c, &mut cached)
             }
         }
     }
 
+    /// Read records `start_record..start_record + record_count` of one
+    /// channel lazily through the attached source.
+    fn read_values_for_records_via_source(
+        &self,
+        g: usize,
+        c: usize,
+        start_record: u64,
+        record_count: u64,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        match self.require_source()? {
+            #[cfg(not(target_arch = "wasm32"))]
+            Source::File(path) => {
+                let mut reader = MmapRangeReader::new(path)?;
+                self.verify(&mut reader)?;
+                self.read_channel_values_for_records(g, c, start_record, record_count, &mut reader)
+            }
+            #[cfg(target_arch = "wasm32")]
+            Source::File(_) => Err(MdfError::UnsupportedFeature(
+                "file sources are not available on wasm32".to_string(),
+            )),
+            #[cfg(feature = "http")]
+            Source::Url(url) => {
+                let http = HttpRangeReader::new(url)?;
+                let mut cached = CachingRangeReader::new(http);
+                cached.set_bypass(true);
+                self.verify(&mut cached)?;
+                self.read_channel_values_for_records(g, c, start_record, record_count, &mut cached)
+            }
+        }
+    }
+
+    /// Compute one channel's statistics lazily through the attached source.
+    fn read_statistics_via_source(&self, g: usize, c: usize) -> Result<ChannelStatistics, MdfError> {
+        match self.require_source()? {
+            #[cfg(not(target_arch = "wasm32"))]
+            Source::File(path) => {
+                let mut reader = MmapRangeReader::new(path)?;
+                self.verify(&mut reader)?;
+                self.read_channel_statistics(g, c, &mut reader)
+            }
+            #[cfg(target_arch = "wasm32")]
+            Source::File(_) => Err(MdfError::UnsupportedFeature(
+                "file sources are not available on wasm32".to_string(),
+            )),
+            #[cfg(feature = "http")]
+            Source::Url(url) => {
+                let http = HttpRangeReader::new(url)?;
+                let mut cached = CachingRangeReader::new(http);
+                cached.set_bypass(true);
+                self.verify(&mut cached)?;
+                self.read_channel_statistics(g, c, &mut cached)
+            }
+        }
+    }
+
     /// Get the exact byte ranges needed to read all data for a specific channel
     /// 
     /// Returns a vector of (file_offset, length) tuples representing the byte ranges
@@ -1435,6 +2241,8 @@ impl MdfIndex {
     /// # Arguments
     /// * `group_index` - Index of the channel group
     /// * `channel_index` - Index of the channel within the group
+    /// * `include_invalidation` - Extend each range over the channel's
+    ///   invalidation byte in the last record it covers
     /// 
     /// # Returns
     /// * `Ok(Vec<(u64, u64)>)` - Vector of (offset, length) byte ranges
@@ -1443,22 +2251,19 @@ impl MdfIndex {
         &self,
         group_index: usize,
         channel_index: usize,
+        include_invalidation: bool,
     ) -> Result<Vec<(u64, u64)>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         // Handle VLSD channels differently
         if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
-            return Err(MdfError::BlockSerializationError(
+            return Err(MdfError::UnsupportedFeature(
                 "VLSD channels not yet supported for byte range calculation".to_string()
             ));
         }
 
         // For regular channels, calculate byte ranges from data blocks
-        self.calculate_regular_channel_byte_ranges(group, channel)
+        self.calculate_regular_channel_byte_ranges(group, channel, include_invalidation)
     }
 
     /// Get the exact byte ranges for a specific record range of a channel
@@ -1470,6 +2275,7 @@ impl MdfIndex {
     /// * `channel_index` - Index of the channel within the group
     /// * `start_record` - Starting record index (0-based)
     /// * `record_count` - Number of records to read
+    /// * `include_invalidation` - As in [`Self::get_channel_byte_ranges`]
     /// 
     /// # Returns
     /// * `Ok(Vec<(u64, u64)>)` - Vector of (offset, length) byte ranges
@@ -1480,29 +2286,28 @@ impl MdfIndex {
         channel_index: usize,
         start_record: u64,
         record_count: u64,
+        include_invalidation: bool,
     ) -> Result<Vec<(u64, u64)>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         // Validate record range
-        if start_record + record_count > group.record_count {
-            return Err(MdfError::BlockSerializationError(
-                format!("Record range {}-{} exceeds total records {}", 
-                    start_record, start_record + record_count - 1, group.record_count)
-            ));
+        let end_record = start_record.saturating_add(record_count);
+        if end_record > group.record_count {
+            return Err(MdfError::InvalidRecordRange {
+                start: start_record,
+                end: end_record,
+                total: group.record_count,
+            });
         }
 
         // Handle VLSD channels differently
         if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
-            return Err(MdfError::BlockSerializationError(
+            return Err(MdfError::UnsupportedFeature(
                 "VLSD channels not yet supported for byte range calculation".to_string()
             ));
         }
 
-        self.calculate_channel_byte_ranges_for_records(group, channel, start_record, record_count)
+        self.calculate_channel_byte_ranges_for_records(group, channel, start_record, record_count, include_invalidation)
     }
 
     /// Calculate byte ranges for a regular (non-VLSD) channel for all records
@@ -1510,8 +2315,9 @@ impl MdfIndex {
         &self,
         group: &IndexedChannelGroup,
         channel: &IndexedChannel,
+        include_invalidation: bool,
     ) -> Result<Vec<(u64, u64)>, MdfError> {
-        self.calculate_channel_byte_ranges_for_records(group, channel, 0, group.record_count)
+        self.calculate_channel_byte_ranges_for_records(group, channel, 0, group.record_count, include_invalidation)
     }
 
     /// Calculate byte ranges for a regular channel for a specific record range
@@ -1521,6 +2327,7 @@ impl MdfIndex {
         channel: &IndexedChannel,
         start_record: u64,
         record_count: u64,
+        include_invalidation: bool,
     ) -> Result<Vec<(u64, u64)>, MdfError> {
         // Record structure: record_id + data_bytes + invalidation_bytes
         let record_size = group.record_id_len as usize + group.record_size as usize + group.invalidation_bytes as usize;
@@ -1535,15 +2342,25 @@ impl MdfIndex {
         } else {
             ((channel.bit_offset as usize + channel.bit_count as usize + 7) / 8).max(1)
         };
+        // Bytes from the channel's first byte to the end of what is needed of
+        // a record: its data, or up to its invalidation byte, which follows
+        // the data bytes of the record.
+        let uses_invalidation_bit = channel.flags & CN_FLAG_INVAL_BIT_VALID != 0 && group.invalidation_bytes > 0;
+        let channel_bytes_per_record = if include_invalidation && uses_invalidation_bit {
+            let invalidation_byte = group.record_id_len as usize
+                + group.record_size as usize
+                + (channel.pos_invalidation_bit as usize >> 3);
+            invalidation_byte + 1 - channel_offset_in_record
+        } else {
+            channel_bytes_per_record
+        };
 
         let mut byte_ranges = Vec::new();
         let mut records_processed = 0u64;
         
         for data_block in &group.data_blocks {
             if data_block.is_compressed {
-                return Err(MdfError::BlockSerializationError(
-                    "Compressed blocks not supported for byte range calculation".to_string()
-                ));
+                return Err(MdfError::CompressedDataUnsupported("for byte range calculation".into()));
             }
 
             let block_data_start = data_block.file_offset + 24; // Skip block header
@@ -1595,20 +2412,31 @@ impl MdfIndex {
     /// or S3 partial reads yourself.
     pub fn byte_ranges(&self, name: &str) -> Result<Vec<(u64, u64)>, MdfError> {
         let (g, c) = self.locate(name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!("Channel '{}' not found", name))
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
         })?;
-        self.get_channel_byte_ranges(g, c)
+        self.get_channel_byte_ranges(g, c, false)
+    }
+
+    /// [`MdfIndex::byte_ranges`], extended so every range also covers the
+    /// channel's invalidation byte in each record.
+    ///
+    /// Fetch these when the samples' validity matters: the invalidation
+    /// bytes follow the data bytes of each record, so the ranges then run to
+    /// the invalidation byte of the last record they cover. Channels without
+    /// an invalidation bit get the same ranges as [`MdfIndex::byte_ranges`].
+    pub fn byte_ranges_with_invalidation(&self, name: &str) -> Result<Vec<(u64, u64)>, MdfError> {
+        let (g, c) = self.locate(name).ok_or_else(|| {
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
+        })?;
+        self.get_channel_byte_ranges(g, c, true)
     }
 
     /// Byte ranges for a channel addressed by group name + channel name.
     pub fn byte_ranges_in(&self, group: &str, name: &str) -> Result<Vec<(u64, u64)>, MdfError> {
         let (g, c) = self.locate_in(group, name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!(
-                "Channel '{}' not found in group '{}'",
-                name, group
-            ))
+            MdfError::ChannelNotFound { name: name.to_string(), group: Some(group.to_string()) }
         })?;
-        self.get_channel_byte_ranges(g, c)
+        self.get_channel_byte_ranges(g, c, false)
     }
 
     /// Byte ranges for a record window of a channel, by name.
@@ -1622,9 +2450,176 @@ impl MdfIndex {
         record_count: u64,
     ) -> Result<Vec<(u64, u64)>, MdfError> {
         let (g, c) = self.locate(name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!("Channel '{}' not found", name))
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
         })?;
-        self.get_channel_byte_ranges_for_records(g, c, start_record, record_count)
+        self.get_channel_byte_ranges_for_records(g, c, start_record, record_count, false)
+    }
+
+    /// [`MdfIndex::byte_ranges_for_records`] including the invalidation
+    /// bytes, as in [`MdfIndex::byte_ranges_with_invalidation`].
+    pub fn byte_ranges_for_records_with_invalidation(
+        &self,
+        name: &str,
+        start_record: u64,
+        record_count: u64,
+    ) -> Result<Vec<(u64, u64)>, MdfError> {
+        let (g, c) = self.locate(name).ok_or_else(|| {
+            MdfError::ChannelNotFound { name: name.to_string(), group: None }
+        })?;
+        self.get_channel_byte_ranges_for_records(g, c, start_record, record_count, true)
+    }
+
+    /// Decode a channel from byte ranges fetched by the caller, e.g. with a
+    /// batched S3 request for the ranges of [`MdfIndex::byte_ranges`] or a
+    /// [`ReadPlan`].
+    ///
+    /// `group` names the group to resolve `channel` in, or `None` for the
+    /// first match. Each entry of `ranges` is an `(offset, length)` range as
+    /// returned by the `byte_ranges*` methods together with its bytes; the
+    /// values of all ranges are returned in order, converted as by
+    /// [`MdfReader::values`] (`None` = invalid sample). Ranges without the
+    /// channel's invalidation bytes (see
+    /// [`MdfIndex::byte_ranges_with_invalidation`]) decode every sample as
+    /// valid.
+    ///
+    /// Returns an error if a range does not start at the channel's bytes of
+    /// a record in an uncompressed data block of the group, or if its bytes
+    /// do not have the range's length.
+    pub fn decode_channel_from_ranges<B: AsRef<[u8]>>(
+        &self,
+        group: Option<&str>,
+        channel: &str,
+        ranges: &[((u64, u64), B)],
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = match group {
+            Some(group) => self.locate_in(group, channel).ok_or_else(|| {
+                MdfError::ChannelNotFound { name: channel.to_string(), group: Some(group.to_string()) }
+            })?,
+            None => self.locate(channel).ok_or_else(|| {
+                MdfError::ChannelNotFound { name: channel.to_string(), group: None }
+            })?,
+        };
+        let group = &self.channel_groups[g];
+        let channel = &group.channels[c];
+        if channel.is_vlsd() {
+            return Err(MdfError::UnsupportedFeature(
+                "VLSD channels not yet supported for byte range decoding".to_string()
+            ));
+        }
+
+        let record_size = group.record_id_len as usize + group.record_size as usize + group.invalidation_bytes as usize;
+        let channel_offset = group.record_id_len as usize + channel.byte_offset as usize;
+        let invalidation_byte = group.record_id_len as usize
+            + group.record_size as usize
+            + (channel.pos_invalidation_bit as usize >> 3);
+        let temp_cb = channel.to_channel_block();
+        let mut without_invalidation = temp_cb.clone();
+        without_invalidation.flags &= !CN_FLAG_INVAL_BIT_VALID;
+
+        let mut values = Vec::new();
+        let mut records = Vec::new();
+        for &((offset, length), ref bytes) in ranges {
+            let bytes = bytes.as_ref();
+            if bytes.len() as u64 != length {
+                return Err(MdfError::BlockSerializationError(format!(
+                    "range {:#x}+{} came with {} bytes", offset, length, bytes.len()
+                )));
+            }
+            let data_block = group.data_blocks.iter()
+                .find(|b| offset >= b.file_offset + 24 && offset + length <= b.file_offset + b.size)
+                .ok_or_else(|| MdfError::BlockSerializationError(format!(
+                    "range {:#x}+{} is not inside a data block of the channel's group", offset, length
+                )))?;
+            if data_block.is_compressed {
+                return Err(MdfError::CompressedDataUnsupported("for byte range decoding".into()));
+            }
+            if (offset - data_block.file_offset - 24) % record_size as u64 != channel_offset as u64 {
+                return Err(MdfError::BlockSerializationError(format!(
+                    "range {:#x}+{} does not start at the channel's bytes of a record", offset, length
+                )));
+            }
+
+            // Lay the bytes out as whole records, so the range's first byte
+            // sits at the channel's offset of the first record.
+            records.clear();
+            records.resize(channel_offset, 0);
+            records.extend_from_slice(bytes);
+            let covered = records.len();
+            records.resize(covered.next_multiple_of(record_size), 0);
+            let last_invalidation_byte = records.len() - record_size + invalidation_byte;
+            let cb = if last_invalidation_byte < covered { &temp_cb } else { &without_invalidation };
+            Self::decode_records_to_values(&records, record_size, group, channel, cb, &mut values)?;
+        }
+        Ok(values)
+    }
+
+    /// Plan the reads for several channels / record windows at once.
+    ///
+    /// Resolves each request, collects its byte ranges and merges them into
+    /// one ordered list of disjoint reads. Ranges that overlap, touch, or are
+    /// separated by at most `max_gap` bytes share a read, so a dashboard
+    /// refresh over a remote file costs a handful of range requests instead
+    /// of one per channel and data block. Identical ranges requested twice
+    /// are read once.
+    pub fn plan_reads(&self, requests: &[RangeRequest], max_gap: u64) -> Result<ReadPlan, MdfError> {
+        let mut per_request = Vec::with_capacity(requests.len());
+        for request in requests {
+            let (g, c) = match &request.group {
+                Some(group) => self.locate_in(group, &request.channel).ok_or_else(|| {
+                    MdfError::ChannelNotFound { name: request.channel.to_string(), group: Some(group.to_string()) }
+                })?,
+                None => self.locate(&request.channel).ok_or_else(|| {
+                    MdfError::ChannelNotFound { name: request.channel.to_string(), group: None }
+                })?,
+            };
+            let ranges = match &request.records {
+                None => self.get_channel_byte_ranges(g, c, request.invalidation)?,
+                Some(records) if records.start > records.end => {
+                    return Err(MdfError::InvalidRecordRange {
+                        start: records.start,
+                        end: records.end,
+                        total: self.channel_groups[g].record_count,
+                    });
+                }
+                Some(records) if records.is_empty() => Vec::new(),
+                Some(records) => self.get_channel_byte_ranges_for_records(
+                    g,
+                    c,
+                    records.start,
+                    records.end - records.start,
+                    request.invalidation,
+                )?,
+            };
+            per_request.push(ranges);
+        }
+
+        let mut all: Vec<(u64, u64)> = per_request.iter().flatten().copied().collect();
+        all.sort_unstable();
+        all.dedup();
+        let mut reads: Vec<(u64, u64)> = Vec::new();
+        for (offset, length) in all {
+            match reads.last_mut() {
+                Some((start, len)) if offset <= (*start + *len).saturating_add(max_gap) => {
+                    *len = (*len).max(offset + length - *start);
+                }
+                _ => reads.push((offset, length)),
+            }
+        }
+
+        let requests = per_request
+            .into_iter()
+            .map(|ranges| {
+                ranges
+                    .into_iter()
+                    .map(|(offset, length)| {
+                        // The last read starting at or before `offset` contains it.
+                        let read = reads.partition_point(|&(start, _)| start <= offset) - 1;
+                        PlannedRange { read, offset, length }
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(ReadPlan { reads, requests })
     }
 
     /// Fast path: read channel values as `Vec<f64>` using a byte range reader.
@@ -1638,10 +2633,7 @@ impl MdfIndex {
         channel_index: usize,
         reader: &mut R,
     ) -> Result<Vec<f64>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         let record_size = group.record_id_len as usize
             + group.record_size as usize
@@ -1657,18 +2649,168 @@ impl MdfIndex {
         let has_conversion = channel.conversion.is_some();
 
         for data_block in &group.data_blocks {
-            if data_block.is_compressed {
-                return Err(MdfError::BlockSerializationError(
-                    "Compressed blocks not yet supported in index reader".to_string()
-                ));
-            }
-            let block_data = reader.read_range(data_block.file_offset + 24, data_block.size - 24)?;
+            let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+            let block_data = Self::block_data(data_block, &block)?;
             Self::decode_records_to_f64(&block_data, record_size, group, channel, &temp_cb, linear_coeffs, has_conversion, &mut values)?;
         }
 
         Ok(values)
     }
 
+    /// Fold a channel's samples into [`ChannelStatistics`] one data block at
+    /// a time, decoding them as [`MdfIndex::read_channel_values_as_f64`].
+    pub(crate) fn read_channel_statistics<R: ByteRangeReader<Error = MdfError>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        reader: &mut R,
+    ) -> Result<ChannelStatistics, MdfError> {
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
+        if channel.is_vlsd() {
+            return Err(MdfError::UnsupportedFeature(
+                "VLSD channels not yet supported in index reader".to_string()
+            ));
+        }
+
+        let record_size = Self::full_record_size(group);
+        let temp_cb = channel.to_decode_only_channel_block();
+        let linear_coeffs = Self::get_linear_coeffs(channel);
+        let has_conversion = channel.conversion.is_some();
+        let mut stats = StatisticsAccumulator::new();
+        let mut values = Vec::new();
+        for data_block in &group.data_blocks {
+            let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+            let block_data = Self::block_data(data_block, &block)?;
+            values.clear();
+            Self::decode_records_to_f64(&block_data, record_size, group, channel, &temp_cb, linear_coeffs, has_conversion, &mut values)?;
+            for &value in &values {
+                stats.push(value);
+            }
+        }
+        Ok(stats.finish())
+    }
+
+    /// Read the values of records `start_record..start_record + record_count`
+    /// of a channel using a byte range reader.
+    ///
+    /// Internal positional helper — the public entry point is
+    /// [`MdfReader::values_for_records`]. Only the data blocks overlapping
+    /// the window are read, and of uncompressed blocks only the records in it.
+    pub(crate) fn read_channel_values_for_records<R: ByteRangeReader<Error = MdfError>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        start_record: u64,
+        record_count: u64,
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (group, channel) = self.record_window_channel(group_index, channel_index)?;
+        let records = Self::read_record_window(group, start_record, record_count, reader)?;
+        let record_size = Self::full_record_size(group);
+        let mut values = Vec::with_capacity(record_count as usize);
+        let temp_cb = channel.to_channel_block();
+        Self::decode_records_to_values(&records, record_size, group, channel, &temp_cb, &mut values)?;
+        Ok(values)
+    }
+
+    /// [`MdfIndex::read_channel_values_for_records`] on the `f64` fast path.
+    pub(crate) fn read_channel_values_for_records_as_f64<R: ByteRangeReader<Error = MdfError>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        start_record: u64,
+        record_count: u64,
+        reader: &mut R,
+    ) -> Result<Vec<f64>, MdfError> {
+        let (group, channel) = self.record_window_channel(group_index, channel_index)?;
+        let records = Self::read_record_window(group, start_record, record_count, reader)?;
+        let record_size = Self::full_record_size(group);
+        let mut values = Vec::with_capacity(record_count as usize);
+        let temp_cb = channel.to_decode_only_channel_block();
+        let linear_coeffs = Self::get_linear_coeffs(channel);
+        let has_conversion = channel.conversion.is_some();
+        Self::decode_records_to_f64(&records, record_size, group, channel, &temp_cb, linear_coeffs, has_conversion, &mut values)?;
+        Ok(values)
+    }
+
+    /// Group and channel at the given positions, rejecting VLSD channels.
+    fn record_window_channel(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+    ) -> Result<(&IndexedChannelGroup, &IndexedChannel), MdfError> {
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
+        if channel.is_vlsd() {
+            return Err(MdfError::UnsupportedFeature(
+                "VLSD channels not yet supported in index reader".to_string()
+            ));
+        }
+        Ok((group, channel))
+    }
+
+    /// Record ID + data + invalidation bytes of one record of `group`.
+    fn full_record_size(group: &IndexedChannelGroup) -> usize {
+        group.record_id_len as usize + group.record_size as usize + group.invalidation_bytes as usize
+    }
+
+    /// The whole records `start_record..start_record + record_count` of
+    /// `group`, concatenated across data blocks.
+    ///
+    /// Compressed blocks are read and inflated whole; of uncompressed blocks
+    /// the header is read for the staleness check, then only the window's
+    /// records.
+    fn read_record_window<R: ByteRangeReader<Error = MdfError>>(
+        group: &IndexedChannelGroup,
+        start_record: u64,
+        record_count: u64,
+        reader: &mut R,
+    ) -> Result<Vec<u8>, MdfError> {
+        let end_record = start_record.checked_add(record_count).filter(|&end| end <= group.record_count)
+            .ok_or(MdfError::InvalidRecordRange {
+                start: start_record,
+                end: start_record.saturating_add(record_count),
+                total: group.record_count,
+            })?;
+        let record_size = Self::full_record_size(group) as u64;
+        if record_size == 0 {
+            return Ok(Vec::new());
+        }
+        // Grown as blocks are read rather than preallocated, so corrupt sizes
+        // fail on the read instead of on a huge allocation.
+        let mut records = Vec::new();
+        let mut block_start = 0u64;
+        for data_block in &group.data_blocks {
+            if block_start >= end_record {
+                break;
+            }
+            if data_block.is_compressed {
+                let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+                let data = Self::block_data(data_block, &block)?;
+                let in_block = data.len() as u64 / record_size;
+                let from = start_record.max(block_start) - block_start;
+                let to = end_record.min(block_start + in_block).saturating_sub(block_start);
+                if from < to {
+                    records.extend_from_slice(&data[(from * record_size) as usize..(to * record_size) as usize]);
+                }
+                block_start += in_block;
+                continue;
+            }
+            let in_block = data_block.size.saturating_sub(24) / record_size;
+            let from = start_record.max(block_start) - block_start;
+            let to = end_record.min(block_start + in_block).saturating_sub(block_start);
+            if from < to {
+                let header = reader.read_range(data_block.file_offset, 24).map_err(stale_if_short)?;
+                Self::check_block_header(data_block, &header)?;
+                let bytes = reader
+                    .read_range(data_block.file_offset + 24 + from * record_size, (to - from) * record_size)
+                    .map_err(stale_if_short)?;
+                records.extend_from_slice(&bytes);
+            }
+            block_start += in_block;
+        }
+        Ok(records)
+    }
+
     /// Zero-copy fast path: read channel values directly from an `&[u8]` mmap slice.
     ///
     /// Avoids all per-block heap allocation by slicing directly into the provided
@@ -1681,10 +2823,7 @@ impl MdfIndex {
         channel_index: usize,
         file_data: &[u8],
     ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         let record_size = group.record_id_len as usize
             + group.record_size as usize
@@ -1696,13 +2835,8 @@ impl MdfIndex {
         let temp_cb = channel.to_channel_block();
 
         for data_block in &group.data_blocks {
-            if data_block.is_compressed {
-                return Err(MdfError::BlockSerializationError(
-                    "Compressed blocks not yet supported in index reader".to_string()
-                ));
-            }
             let block_data = Self::slice_data_block(file_data, data_block)?;
-            Self::decode_records_to_values(block_data, record_size, group, channel, &temp_cb, &mut values)?;
+            Self::decode_records_to_values(&block_data, record_size, group, channel, &temp_cb, &mut values)?;
         }
 
         Ok(values)
@@ -1720,10 +2854,7 @@ impl MdfIndex {
         channel_index: usize,
         file_data: &[u8],
     ) -> Result<Vec<f64>, MdfError> {
-        let group = self.channel_groups.get(group_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid group index".to_string()))?;
-        let channel = group.channels.get(channel_index)
-            .ok_or_else(|| MdfError::BlockSerializationError("Invalid channel index".to_string()))?;
+        let (group, channel) = self.group_channel(group_index, channel_index)?;
 
         let record_size = group.record_id_len as usize
             + group.record_size as usize
@@ -1737,32 +2868,69 @@ impl MdfIndex {
         let has_conversion = channel.conversion.is_some();
 
         for data_block in &group.data_blocks {
-            if data_block.is_compressed {
-                return Err(MdfError::BlockSerializationError(
-                    "Compressed blocks not yet supported in index reader".to_string()
-                ));
-            }
             let block_data = Self::slice_data_block(file_data, data_block)?;
-            Self::decode_records_to_f64(block_data, record_size, group, channel, &temp_cb, linear_coeffs, has_conversion, &mut values)?;
+            Self::decode_records_to_f64(&block_data, record_size, group, channel, &temp_cb, linear_coeffs, has_conversion, &mut values)?;
         }
 
         Ok(values)
     }
 
-    /// Slice a data block from file_data, skipping the 24-byte block header.
+    /// Data section of a data block sliced from file_data, checked against
+    /// the index; see [`MdfIndex::block_data`].
     #[allow(dead_code)] // used by the Python bindings (pyo3 feature)
-    fn slice_data_block<'a>(file_data: &'a [u8], data_block: &DataBlockInfo) -> Result<&'a [u8], MdfError> {
-        let data_start = (data_block.file_offset + 24) as usize;
-        let data_end = data_start + (data_block.size - 24) as usize;
+    fn slice_data_block<'a>(file_data: &'a [u8], data_block: &DataBlockInfo) -> Result<Cow<'a, [u8]>, MdfError> {
+        let block_start = data_block.file_offset as usize;
+        let data_end = block_start + data_block.size as usize;
         if data_end > file_data.len() {
-            return Err(MdfError::TooShortBuffer {
+            return Err(stale_if_short(MdfError::TooShortBuffer {
                 actual: file_data.len(),
                 expected: data_end,
                 file: file!(),
                 line: line!(),
-            });
+            }));
         }
-        Ok(&file_data[data_start..data_end])
+        Self::block_data(data_block, &file_data[block_start..data_end])
+    }
+
+    /// Fetch `data_block` through `reader` and return its data section, see
+    /// [`Self::block_data`].
+    pub(crate) fn read_block_data<R: ByteRangeReader<Error = MdfError>>(
+        data_block: &DataBlockInfo,
+        reader: &mut R,
+    ) -> Result<Vec<u8>, MdfError> {
+        let block = reader.read_range(data_block.file_offset, data_block.size).map_err(stale_if_short)?;
+        Ok(Self::block_data(data_block, &block)?.into_owned())
+    }
+
+    /// Data section of `block` (a whole data block, header included) after
+    /// checking its header against the index: borrowed for `##DT` / `##DV`,
+    /// inflated for `##DZ`.
+    fn block_data<'a>(data_block: &DataBlockInfo, block: &'a [u8]) -> Result<Cow<'a, [u8]>, MdfError> {
+        Self::check_block_header(data_block, block)?;
+        if data_block.is_compressed {
+            Ok(Cow::Owned(DataZippedBlock::from_bytes(block)?.decompress()?))
+        } else {
+            Ok(Cow::Borrowed(&block[24..]))
+        }
+    }
+
+    /// Check the header at the start of `block` against the data block the
+    /// index recorded there. This is the per-read guard against a file that
+    /// was rewritten after the index was built.
+    fn check_block_header(data_block: &DataBlockInfo, block: &[u8]) -> Result<(), MdfError> {
+        let header = BlockHeader::from_bytes(block).map_err(stale_if_short)?;
+        let id_matches = if data_block.is_compressed {
+            header.id == "##DZ"
+        } else {
+            header.id == "##DT" || header.id == "##DV"
+        };
+        if !id_matches || header.block_len != data_block.size {
+            return Err(MdfError::StaleIndex(format!(
+                "expected a {}-byte data block at {:#x}, found {:?} of {} bytes",
+                data_block.size, data_block.file_offset, header.id, header.block_len
+            )));
+        }
+        Ok(())
     }
 }
 
@@ -1775,6 +2943,9 @@ impl MdfIndex {
 /// channels.
 pub struct MdfReader<'a, R: ByteRangeReader<Error = MdfError>> {
     index: &'a MdfIndex,
+    /// Copy of `index` without conversions, which the values are read from
+    /// while [`MdfReader::set_raw_values`] is on.
+    raw_index: Option<MdfIndex>,
     reader: R,
 }
 
@@ -1795,19 +2966,28 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
         self.reader
     }
 
+    /// Read the stored values of channels from now on, without applying
+    /// their conversions (`false` switches conversions back on).
+    ///
+    /// Affects every read of channel values; the master values used for
+    /// time windows and as [`Signal`] timestamps stay converted. The reader
+    /// keeps a copy of the index with the conversions dropped meanwhile.
+    pub fn set_raw_values(&mut self, raw: bool) {
+        self.raw_index = raw.then(|| self.index.without_conversions());
+    }
+
+    /// The index channel values are read with (see
+    /// [`MdfReader::set_raw_values`]) and the byte-range source.
+    fn values_source(&mut self) -> (&MdfIndex, &mut R) {
+        (self.raw_index.as_ref().unwrap_or(self.index), &mut self.reader)
+    }
+
     fn locate(&self, name: &str) -> Result<(usize, usize), MdfError> {
-        self.index.locate(name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!("Channel '{}' not found", name))
-        })
+        self.index.require(name)
     }
 
     fn locate_in(&self, group: &str, name: &str) -> Result<(usize, usize), MdfError> {
-        self.index.locate_in(group, name).ok_or_else(|| {
-            MdfError::BlockSerializationError(format!(
-                "Channel '{}' not found in group '{}'",
-                name, group
-            ))
-        })
+        self.index.require_in(group, name)
     }
 
     /// Read all samples of a channel by name (first match across groups).
@@ -1815,7 +2995,8 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
     /// Conversions stored in the index are applied; invalid samples are `None`.
     pub fn values(&mut self, name: &str) -> Result<Vec<Option<DecodedValue>>, MdfError> {
         let (g, c) = self.locate(name)?;
-        self.index.read_channel_values(g, c, &mut self.reader)
+        let (index, reader) = self.values_source();
+        index.read_channel_values(g, c, reader)
     }
 
     /// Read all samples of a channel, addressed by group name + channel name.
@@ -1825,7 +3006,29 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
         name: &str,
     ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
         let (g, c) = self.locate_in(group, name)?;
-        self.index.read_channel_values(g, c, &mut self.reader)
+        let (index, reader) = self.values_source();
+        index.read_channel_values(g, c, reader)
+    }
+
+    /// Read all samples of a channel by name together with their validity.
+    ///
+    /// Like [`MdfReader::values`], but samples marked invalid by their
+    /// invalidation bit keep their decoded value with `is_valid == false`.
+    pub fn values_with_validity(&mut self, name: &str) -> Result<Vec<DecodedChannelValue>, MdfError> {
+        let (g, c) = self.locate(name)?;
+        let (index, reader) = self.values_source();
+        index.read_channel_values_with_validity(g, c, reader)
+    }
+
+    /// [`MdfReader::values_with_validity`] addressed by group name + channel name.
+    pub fn values_with_validity_in(
+        &mut self,
+        group: &str,
+        name: &str,
+    ) -> Result<Vec<DecodedChannelValue>, MdfError> {
+        let (g, c) = self.locate_in(group, name)?;
+        let (index, reader) = self.values_source();
+        index.read_channel_values_with_validity(g, c, reader)
     }
 
     /// Fast path: read a numeric channel by name as `Vec<f64>`.
@@ -1834,13 +3037,118 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
     /// a linear scale are applied inline.
     pub fn values_f64(&mut self, name: &str) -> Result<Vec<f64>, MdfError> {
         let (g, c) = self.locate(name)?;
-        self.index.read_channel_values_as_f64(g, c, &mut self.reader)
+        let (index, reader) = self.values_source();
+        index.read_channel_values_as_f64(g, c, reader)
     }
 
     /// Fast `f64` path addressed by group name + channel name.
     pub fn values_f64_in(&mut self, group: &str, name: &str) -> Result<Vec<f64>, MdfError> {
         let (g, c) = self.locate_in(group, name)?;
-        self.index.read_channel_values_as_f64(g, c, &mut self.reader)
+        let (index, reader) = self.values_source();
+        index.read_channel_values_as_f64(g, c, reader)
+    }
+
+    /// Read records `start_record..start_record + record_count` of a channel
+    /// by name (first match across groups).
+    ///
+    /// Only the bytes of the requested records are fetched from uncompressed
+    /// data blocks, so large files can be paged through.
+    pub fn values_for_records(
+        &mut self,
+        name: &str,
+        start_record: u64,
+        record_count: u64,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = self.locate(name)?;
+        let (index, reader) = self.values_source();
+        index.read_channel_values_for_records(g, c, start_record, record_count, reader)
+    }
+
+    /// [`MdfReader::values_for_records`] addressed by group name + channel name.
+    pub fn values_for_records_in(
+        &mut self,
+        group: &str,
+        name: &str,
+        start_record: u64,
+        record_count: u64,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = self.locate_in(group, name)?;
+        let (index, reader) = self.values_source();
+        index.read_channel_values_for_records(g, c, start_record, record_count, reader)
+    }
+
+    /// The records of channel `name`'s group whose master value lies in
+    /// `[start_time, end_time]`, as a `start..end` record range.
+    ///
+    /// The master channel is read in full and assumed to be non-decreasing.
+    pub fn records_in_time_window(
+        &mut self,
+        name: &str,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<std::ops::Range<u64>, MdfError> {
+        let (g, _) = self.locate(name)?;
+        self.time_window_records(g, start_time, end_time)
+    }
+
+    /// Read channel `name` as a [`Signal`] restricted to the records whose
+    /// master value lies in `[start_time, end_time]`.
+    pub fn signal_in_time_window(
+        &mut self,
+        name: &str,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<Signal, MdfError> {
+        let (g, c) = self.locate(name)?;
+        self.read_signal_window(g, c, start_time, end_time)
+    }
+
+    /// [`MdfReader::signal_in_time_window`] addressed by group name +
+    /// channel name.
+    pub fn signal_in_time_window_in(
+        &mut self,
+        group: &str,
+        name: &str,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<Signal, MdfError> {
+        let (g, c) = self.locate_in(group, name)?;
+        self.read_signal_window(g, c, start_time, end_time)
+    }
+
+    fn master_index(&self, g: usize) -> Result<usize, MdfError> {
+        self.index.channel_groups[g]
+            .channels
+            .iter()
+            .position(|ch| ch.is_master())
+            .ok_or_else(|| MdfError::BlockSerializationError(
+                "channel group has no master channel".to_string(),
+            ))
+    }
+
+    fn time_window_records(
+        &mut self,
+        g: usize,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<std::ops::Range<u64>, MdfError> {
+        let m = self.master_index(g)?;
+        let times = self.index.read_channel_values_as_f64(g, m, &mut self.reader)?;
+        let start = times.partition_point(|&t| t < start_time);
+        let end = times.partition_point(|&t| t <= end_time).max(start);
+        Ok(start as u64..end as u64)
+    }
+
+    fn read_signal_window(&mut self, g: usize, c: usize, start_time: f64, end_time: f64) -> Result<Signal, MdfError> {
+        let records = self.time_window_records(g, start_time, end_time)?;
+        let count = records.end - records.start;
+        let m = self.master_index(g)?;
+        let channel = &self.index.channel_groups[g].channels[c];
+        let (name, unit) = (channel.name.clone().unwrap_or_default(), channel.unit.clone());
+        let (index, reader) = self.values_source();
+        let values = index.read_channel_values_for_records(g, c, records.start, count, reader)?;
+        let timestamps = self.index.read_channel_values_for_records_as_f64(g, m, records.start, count, &mut self.reader)?;
+        Ok(Signal { name, unit, timestamps, values })
     }
 
     /// Read a channel by name as a [`Signal`] (values paired with the group's
@@ -1868,7 +3176,8 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
             (channel.name.clone().unwrap_or_default(), channel.unit.clone(), master)
         };
 
-        let values = self.index.read_channel_values(g, c, &mut self.reader)?;
+        let (index, reader) = self.values_source();
+        let values = index.read_channel_values(g, c, reader)?;
         let timestamps = match master {
             Some(m) => self
                 .index
@@ -1881,3 +3190,89 @@ impl<'a, R: ByteRangeReader<Error = MdfError>> MdfReader<'a, R> {
         Ok(Signal { name, unit, timestamps, values })
     }
 }
+
+/// A reader bound to an [`MdfIndex`] and an async byte-range source.
+///
+/// Obtained from [`MdfIndex::open_async`]. Each read fetches the data blocks
+/// of the channel's group with awaited range reads, then decodes them like
+/// [`MdfReader`].
+pub struct AsyncMdfReader<'a, R: AsyncByteRangeReader<Error = MdfError>> {
+    index: &'a MdfIndex,
+    reader: R,
+}
+
+impl<'a, R: AsyncByteRangeReader<Error = MdfError>> AsyncMdfReader<'a, R> {
+    /// The index this reader was opened against.
+    pub fn index(&self) -> &MdfIndex {
+        self.index
+    }
+
+    /// Mutable access to the underlying async byte-range reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume the reader, returning the underlying byte-range source.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Fetch every data block of group `g` and bind them to a sync reader.
+    async fn fetch_group(&mut self, g: usize) -> Result<MdfReader<'a, FetchedRanges>, MdfError> {
+        let mut ranges = HashMap::new();
+        for block in &self.index.channel_groups[g].data_blocks {
+            let bytes = self
+                .reader
+                .read_range(block.file_offset, block.size)
+                .await
+                .map_err(stale_if_short)?;
+            ranges.insert((block.file_offset, block.size), bytes);
+        }
+        Ok(self.index.open(FetchedRanges { ranges }))
+    }
+
+    /// Async [`MdfReader::values`].
+    pub async fn values(&mut self, name: &str) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = self.index.require(name)?;
+        let mut fetched = self.fetch_group(g).await?;
+        self.index.read_channel_values(g, c, &mut fetched.reader)
+    }
+
+    /// Async [`MdfReader::values_in`].
+    pub async fn values_in(
+        &mut self,
+        group: &str,
+        name: &str,
+    ) -> Result<Vec<Option<DecodedValue>>, MdfError> {
+        let (g, c) = self.index.require_in(group, name)?;
+        let mut fetched = self.fetch_group(g).await?;
+        self.index.read_channel_values(g, c, &mut fetched.reader)
+    }
+
+    /// Async [`MdfReader::values_f64`].
+    pub async fn values_f64(&mut self, name: &str) -> Result<Vec<f64>, MdfError> {
+        let (g, c) = self.index.require(name)?;
+        let mut fetched = self.fetch_group(g).await?;
+        self.index.read_channel_values_as_f64(g, c, &mut fetched.reader)
+    }
+
+    /// Async [`MdfReader::values_f64_in`].
+    pub async fn values_f64_in(&mut self, group: &str, name: &str) -> Result<Vec<f64>, MdfError> {
+        let (g, c) = self.index.require_in(group, name)?;
+        let mut fetched = self.fetch_group(g).await?;
+        self.index.read_channel_values_as_f64(g, c, &mut fetched.reader)
+    }
+
+    /// Async [`MdfReader::signal`]; the group's data blocks are fetched once
+    /// for both the values and the master channel.
+    pub async fn signal(&mut self, name: &str) -> Result<Signal, MdfError> {
+        let (g, c) = self.index.require(name)?;
+        self.fetch_group(g).await?.read_signal(g, c)
+    }
+
+    /// Async [`MdfReader::signal_in`].
+    pub async fn signal_in(&mut self, group: &str, name: &str) -> Result<Signal, MdfError> {
+        let (g, c) = self.index.require_in(group, name)?;
+        self.fetch_group(g).await?.read_signal(g, c)
+    }
+}
@@ -13,9 +13,39 @@ pub mod cut;
 /// File-merging utilities (native only; not available on `wasm32-unknown-unknown`).
 #[cfg(not(target_arch = "wasm32"))]
 pub mod merge;
+/// File-sorting utilities (native only; not available on `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod sort;
+/// Resampling of channel groups onto a common time base (native only; not
+/// available on `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod resample;
+/// Copies of files extended with derived channels (native only; not
+/// available on `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transform;
+/// In-place editing of channel metadata (native only; not available on
+/// `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod edit;
+/// Extraction of a subset of channels into a new file (native only; not
+/// available on `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod filter;
+/// Re-creation of files from an index and a data source (native only; not
+/// available on `wasm32-unknown-unknown`).
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transcode;
+pub mod dataset;
 pub mod index;
+pub mod pattern;
 pub mod signal;
+pub mod metadata;
+pub mod bus_logging;
+pub mod dbc;
 pub mod block_layout;
+pub mod validate;
+pub mod finalize;
 
 pub mod parsing {
     pub mod decoder;
@@ -24,13 +54,28 @@ pub mod parsing {
     pub mod raw_data_group;
     pub mod raw_channel;
     pub mod source_info;
+    pub mod mdf3;
+    pub mod canopen;
     pub(crate) mod reader_walk;
+    pub(crate) mod buffered;
 }
 
 pub mod api {
     pub mod mdf;
     pub mod channel_group;
     pub mod channel;
+    pub mod channel_tree;
+    pub mod event;
+    pub mod reader;
+    pub mod mime;
+}
+
+/// Conversion of channel groups to other file formats.
+pub mod export {
+    pub mod csv;
+    /// Parquet export (requires the `parquet` feature).
+    #[cfg(feature = "parquet")]
+    pub mod parquet;
 }
 
 // Python bindings module
@@ -87,7 +132,8 @@ use pyo3::prelude::*;
 ///     raw = idx.values("Speed")            # numpy, lazy
 ///
 /// Other utilities: :func:`merge_files`, :func:`cut_mdf_by_time`,
-/// :func:`cut_mdf_by_utc`, and the :class:`FileLayout` block-layout
+/// :func:`cut_mdf_by_utc`, :func:`sort_mdf`, :func:`extract_bus_logging`
+/// (DBC decoding of raw CAN frames), and the :class:`FileLayout` block-layout
 /// inspector.
 ///
 /// All errors raised by this module are subclasses of :class:`MdfException`.
@@ -1,11 +1,96 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use byteorder::{ByteOrder, BigEndian, LittleEndian};
 
+use crate::blocks::channel_block::{ChannelBlock, CN_FLAG_INVAL_BIT_VALID};
+use crate::blocks::conversion::types::ConversionType;
+use crate::blocks::text_block::TextBlock;
+use crate::cut::{
+    append_group_records, copy_dg_comment, copy_group_structure, finish_group_records, map_group,
+    source_groups, start_group_records, ChannelMapping, HeaderChains, RecordAction, SourceGroup,
+};
 use crate::error::MdfError;
-use crate::writer::MdfWriter;
+use crate::metadata::HeaderMetadata;
+use crate::writer::{ChannelId, MdfWriter};
 use crate::parsing::mdf_file::MdfFile;
-use crate::parsing::decoder::{decode_channel_value, DecodedValue};
+use crate::parsing::raw_channel_group::RawChannelGroup;
+use crate::parsing::raw_data_group::RawDataGroup;
+use crate::parsing::decoder::{decode_channel_value, decode_channel_value_with_validity, DecodedValue};
 use crate::blocks::common::{DataType, read_string_block};
 
+/// A linear correction of an input file's clock, applied to the master
+/// channels of its groups by [`merge_many_with_options`]:
+/// `corrected = offset + factor * t`.
+///
+/// ```
+/// # use mf4_rs::merge::ClockCorrection;
+/// // A logger whose clock started 2.5 s late and runs 100 ppm fast.
+/// let correction = ClockCorrection::linear(2.5, 1.0 / 1.0001);
+/// assert_eq!(ClockCorrection::offset(-1.0).factor, 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockCorrection {
+    /// Added to the scaled master values, in master units (usually seconds).
+    pub offset: f64,
+    /// Scale applied to the master values before the offset is added.
+    pub factor: f64,
+}
+
+impl Default for ClockCorrection {
+    fn default() -> Self {
+        ClockCorrection { offset: 0.0, factor: 1.0 }
+    }
+}
+
+impl ClockCorrection {
+    /// Shift the master values by `offset`.
+    pub fn offset(offset: f64) -> Self {
+        ClockCorrection { offset, factor: 1.0 }
+    }
+
+    /// Map master values `t` to `offset + factor * t`.
+    pub fn linear(offset: f64, factor: f64) -> Self {
+        ClockCorrection { offset, factor }
+    }
+
+    /// The corrected master value, in the type of `value`. Integer masters
+    /// are rounded to the nearest integer.
+    fn apply(&self, value: DecodedValue) -> Result<DecodedValue, MdfError> {
+        let corrected = |t: f64| self.offset + self.factor * t;
+        Ok(match value {
+            DecodedValue::Float(t) => DecodedValue::Float(corrected(t)),
+            DecodedValue::SignedInteger(t) => DecodedValue::SignedInteger(corrected(t as f64).round() as i64),
+            DecodedValue::UnsignedInteger(t) => {
+                let t = corrected(t as f64).round();
+                if t < 0.0 {
+                    return Err(MdfError::BlockSerializationError(format!(
+                        "corrected master value {t} does not fit an unsigned master channel"
+                    )));
+                }
+                DecodedValue::UnsignedInteger(t as u64)
+            }
+            other => other,
+        })
+    }
+}
+
+/// Options for [`merge_many_with_options`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeOptions {
+    /// Channel renames and units, as in [`merge_files_with_mapping`].
+    pub mapping: ChannelMapping,
+    /// Clock correction of each input, in the order of the inputs; inputs
+    /// past the end of the list are merged as they are.
+    pub clock_corrections: Vec<ClockCorrection>,
+    /// Also copy the blocks outside the channel groups of every input, as
+    /// described for [`CutOptions::preserve_blocks`](crate::cut::CutOptions::preserve_blocks).
+    /// Their chains (file history, events, ...) are concatenated in input
+    /// order; the header comment and data group comments come from the
+    /// first input that has one. Clock corrections are not applied to them.
+    pub preserve_blocks: bool,
+}
+
 #[derive(Debug, Clone)]
 struct ChannelMeta {
     name: Option<String>,
@@ -18,6 +103,10 @@ struct ChannelMeta {
     /// The raw `data` address differs between source files, so equality is
     /// reduced to a boolean.
     is_vlsd: bool,
+    /// Whether the channel carries an invalidation bit (`cn_flags` bit 1).
+    /// Not part of [`ChannelMeta::matches`]: a group with invalidation bits
+    /// can be concatenated with one without, and the output keeps the bit.
+    has_invalidation: bool,
 }
 
 impl ChannelMeta {
@@ -48,7 +137,9 @@ impl GroupMeta {
 
 struct MergedGroup {
     meta: GroupMeta,
-    data: Vec<Vec<DecodedValue>>, // per channel
+    data: Vec<Vec<Option<DecodedValue>>>, // per channel, `None` = invalid sample
+    /// Input index and addresses of the source groups, in merge order.
+    sources: Vec<(usize, SourceGroup)>,
 }
 
 fn vlsd_payload_to_value(bytes: &[u8], data_type: &DataType) -> DecodedValue {
@@ -87,7 +178,7 @@ fn vlsd_payload_to_value(bytes: &[u8], data_type: &DataType) -> DecodedValue {
     }
 }
 
-fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>, MdfError> {
+fn collect_groups(file: &MdfFile, mapping: &ChannelMapping) -> Result<Vec<MergedGroup>, MdfError> {
     let mut groups = Vec::new();
     let mmap = &file.mmap;
     for dg in &file.data_groups {
@@ -95,7 +186,8 @@ fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>, MdfError> {
         for cg in &dg.channel_groups {
             let mut metas = Vec::new();
             for ch in &cg.raw_channels {
-                let name = read_string_block(mmap, ch.block.name_addr)?;
+                let name = read_string_block(mmap, ch.block.name_addr)?
+                    .map(|name| mapping.name(&name).to_string());
                 metas.push(ChannelMeta {
                     name,
                     data_type: ch.block.data_type.clone(),
@@ -104,24 +196,33 @@ fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>, MdfError> {
                     bit_count: ch.block.bit_count,
                     channel_type: ch.block.channel_type,
                     is_vlsd: ch.block.channel_type == 1 && ch.block.data != 0,
+                    has_invalidation: ch.block.flags & CN_FLAG_INVAL_BIT_VALID != 0,
                 });
             }
-            let mut data: Vec<Vec<DecodedValue>> = metas.iter().map(|_| Vec::new()).collect();
+            let mut data: Vec<Vec<Option<DecodedValue>>> = metas.iter().map(|_| Vec::new()).collect();
             for (idx, ch) in cg.raw_channels.iter().enumerate() {
                 let is_vlsd = ch.block.channel_type == 1 && ch.block.data != 0;
                 let mut iter = ch.records(dg, cg, mmap)?;
-                while let Some(rec) = iter.next() {
+                while let Some(rec) = iter.next_record() {
                     let bytes = rec?;
                     let val = if is_vlsd {
-                        vlsd_payload_to_value(bytes, &ch.block.data_type)
+                        Some(vlsd_payload_to_value(bytes, &ch.block.data_type))
                     } else {
-                        decode_channel_value(bytes, record_id_len as usize, &ch.block)
-                            .unwrap_or(DecodedValue::Unknown)
+                        match decode_channel_value_with_validity(
+                            bytes,
+                            record_id_len as usize,
+                            cg.block.samples_byte_nr,
+                            &ch.block,
+                        ) {
+                            Some(v) if !v.is_valid => None,
+                            Some(v) => Some(v.value),
+                            None => Some(DecodedValue::Unknown),
+                        }
                     };
                     data[idx].push(val);
                 }
             }
-            groups.push(MergedGroup { meta: GroupMeta { record_id_len, channels: metas }, data });
+            groups.push(MergedGroup { meta: GroupMeta { record_id_len, channels: metas }, data, sources: Vec::new() });
         }
     }
     Ok(groups)
@@ -134,6 +235,10 @@ fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>, MdfError> {
 /// do not match are appended as new channel groups. The resulting file is
 /// written to `output`.
 ///
+/// Invalid samples are preserved: a channel that carries an invalidation bit
+/// in either input gets one in the output, and samples flagged invalid in
+/// the source are written with the bit set.
+///
 /// # Arguments
 /// * `output` - Path for the merged file
 /// * `first` - Path to the first input file
@@ -141,31 +246,107 @@ fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>, MdfError> {
 ///
 /// # Returns
 /// `Ok(())` on success or an [`MdfError`] otherwise.
-pub fn merge_files(output: &str, first: &str, second: &str) -> Result<(), MdfError> {
-    let mdf1 = MdfFile::parse_from_file(first)?;
-    let mdf2 = MdfFile::parse_from_file(second)?;
+pub fn merge_files(
+    output: impl AsRef<Path>,
+    first: impl AsRef<Path>,
+    second: impl AsRef<Path>,
+) -> Result<(), MdfError> {
+    merge_files_with_mapping(output, first, second, &ChannelMapping::default())
+}
 
-    let mut groups = collect_groups(&mdf1)?;
-    let other_groups = collect_groups(&mdf2)?;
+/// Merge two MDF files like [`merge_files`], renaming channels and setting
+/// units according to `mapping`.
+///
+/// Channels are renamed before the group layouts are compared, so groups
+/// whose channels only differ in name are concatenated.
+pub fn merge_files_with_mapping(
+    output: impl AsRef<Path>,
+    first: impl AsRef<Path>,
+    second: impl AsRef<Path>,
+    mapping: &ChannelMapping,
+) -> Result<(), MdfError> {
+    merge_many_with_mapping(output, &[first.as_ref(), second.as_ref()], mapping)
+}
+
+/// Merge any number of MDF files into a new file, in the order given.
+///
+/// Like [`merge_files`]: each group is concatenated to the first earlier
+/// group with the same layout, or appended as a new channel group.
+pub fn merge_many(output: impl AsRef<Path>, inputs: &[impl AsRef<Path>]) -> Result<(), MdfError> {
+    merge_many_with_mapping(output, inputs, &ChannelMapping::default())
+}
 
-    for og in other_groups {
-        if let Some(g1) = groups.iter_mut().find(|g| g.meta.matches(&og.meta)) {
-            for (vals1, vals2) in g1.data.iter_mut().zip(og.data.into_iter()) {
-                vals1.extend(vals2);
+/// [`merge_many`] with channels renamed and units set according to
+/// `mapping`, as in [`merge_files_with_mapping`].
+pub fn merge_many_with_mapping(
+    output: impl AsRef<Path>,
+    inputs: &[impl AsRef<Path>],
+    mapping: &ChannelMapping,
+) -> Result<(), MdfError> {
+    let options = MergeOptions { mapping: mapping.clone(), ..MergeOptions::default() };
+    merge_many_with_options(output, inputs, &options)
+}
+
+/// [`merge_many`] with the channel mapping and per-input clock corrections
+/// of `options`.
+///
+/// The master channel values of every group of an input are corrected with
+/// its [`ClockCorrection`] before the groups are concatenated, so logs of
+/// devices with offset or drifting clocks line up on one time base. Masters
+/// are re-encoded in their own data type, and the correction applies to
+/// their stored values.
+pub fn merge_many_with_options(
+    output: impl AsRef<Path>,
+    inputs: &[impl AsRef<Path>],
+    options: &MergeOptions,
+) -> Result<(), MdfError> {
+    let mut groups: Vec<MergedGroup> = Vec::new();
+    // Inputs whose blocks are copied once the groups are written.
+    let mut files = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        let mdf = MdfFile::parse_from_file(input)?;
+        let correction = options.clock_corrections.get(i).copied().unwrap_or_default();
+        let sources = source_groups(&mdf).into_iter().flatten();
+        for (mut og, source) in collect_groups(&mdf, &options.mapping)?.into_iter().zip(sources) {
+            og.sources.push((i, source));
+            if correction != ClockCorrection::default() {
+                for (meta, values) in og.meta.channels.iter().zip(og.data.iter_mut()) {
+                    if meta.channel_type != 2 {
+                        continue;
+                    }
+                    for value in values.iter_mut().flatten() {
+                        *value = correction.apply(std::mem::replace(value, DecodedValue::Unknown))?;
+                    }
+                }
+            }
+            if let Some(g1) = groups.iter_mut().find(|g| g.meta.matches(&og.meta)) {
+                for (m1, m2) in g1.meta.channels.iter_mut().zip(og.meta.channels.iter()) {
+                    m1.has_invalidation |= m2.has_invalidation;
+                }
+                for (vals1, vals2) in g1.data.iter_mut().zip(og.data) {
+                    vals1.extend(vals2);
+                }
+                g1.sources.append(&mut og.sources);
+            } else {
+                groups.push(og);
             }
-        } else {
-            groups.push(og);
+        }
+        if options.preserve_blocks {
+            files.push(mdf);
         }
     }
 
     let mut writer = MdfWriter::new(output)?;
     writer.init_mdf_file()?;
+    // Source block address → copy, per input.
+    let mut caches: Vec<HashMap<u64, u64>> = vec![HashMap::new(); files.len()];
 
     for group in groups {
         let cg_id = writer.add_channel_group(None, |_| {})?;
-        let mut last_cn: Option<String> = None;
+        let mut cn_ids: Vec<ChannelId> = Vec::new();
+        let mut next_inval_bit = 0u32;
         for ch in &group.meta.channels {
-            let id = writer.add_channel(&cg_id, last_cn.as_deref(), |cn| {
+            let id = writer.add_channel(&cg_id, cn_ids.last(), |cn| {
                 cn.data_type = ch.data_type.clone();
                 if let Some(n) = &ch.name {
                     cn.name = Some(n.clone());
@@ -184,9 +365,28 @@ pub fn merge_files(output: &str, first: &str, second: &str) -> Result<(), MdfErr
                     cn.bit_offset = ch.bit_offset;
                     cn.byte_offset = ch.byte_offset;
                     cn.bit_count = ch.bit_count;
+                    if ch.has_invalidation {
+                        cn.flags |= CN_FLAG_INVAL_BIT_VALID;
+                        cn.pos_invalidation_bit = next_inval_bit;
+                        next_inval_bit += 1;
+                    }
                 }
             })?;
-            last_cn = Some(id);
+            if let Some(unit) = ch.name.as_deref().and_then(|name| options.mapping.unit(name)) {
+                let tx_id = format!("tx_unit_{id}");
+                writer.write_block_with_id(&TextBlock::new(unit).to_bytes()?, &tx_id)?;
+                // Link offset 72 of the ##CN block: unit.
+                writer.update_block_link(id, 72, &tx_id)?;
+            }
+            cn_ids.push(id);
+        }
+        if options.preserve_blocks {
+            for (k, (input, source)) in group.sources.iter().enumerate() {
+                map_group(&writer, &mut caches[*input], source, &cg_id, &cn_ids);
+                if k == 0 {
+                    copy_dg_comment(&mut writer, &files[*input].mmap, source, &cg_id, &mut caches[*input])?;
+                }
+            }
         }
         writer.start_data_block_for_cg(&cg_id, group.meta.record_id_len)?;
         let record_count = group.data.get(0).map(|v| v.len()).unwrap_or(0);
@@ -195,10 +395,233 @@ pub fn merge_files(output: &str, first: &str, second: &str) -> Result<(), MdfErr
             for ch_data in &group.data {
                 vals.push(ch_data[i].clone());
             }
-            writer.write_record(&cg_id, &vals)?;
+            writer.write_record_with_validity(&cg_id, &vals)?;
         }
         writer.finish_data_block(&cg_id)?;
     }
 
+    if options.preserve_blocks {
+        let mut chains = HeaderChains::new(&writer)?;
+        for (mdf, cache) in files.iter().zip(&mut caches) {
+            chains.append(&mut writer, mdf, cache)?;
+        }
+    }
     writer.finalize()
 }
+
+/// Concatenate MDF files recorded one after another into a new file.
+///
+/// Unlike [`merge_many`], which appends groups without a matching layout
+/// side by side, every input must have the same channel groups in the same
+/// order, with the same channels at the same record positions; otherwise
+/// [`MdfError::LayoutMismatch`] describes the first difference. Group `k` of
+/// the output holds the records of group `k` of every input, in input
+/// order, and its cycle count covers all of them.
+///
+/// Group and channel metadata (names, units, conversions, sources, ...), the
+/// start time and the header comment are taken from the first input.
+/// Records are copied verbatim, except that the master channel of every
+/// later input is shifted by the difference between its start time and
+/// that of the first input, so the time stays continuous across the files.
+/// Inputs without a start time are not shifted.
+///
+/// # Arguments
+/// * `inputs` - Paths of the files to concatenate, in recording order
+/// * `output` - Path for the concatenated file
+pub fn concatenate(inputs: &[impl AsRef<Path>], output: impl AsRef<Path>) -> Result<(), MdfError> {
+    let files = inputs
+        .iter()
+        .map(MdfFile::parse_from_file)
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = files.first() else {
+        return Err(MdfError::InvalidIndex { kind: "input", index: 0, len: 0 });
+    };
+    for (input, file) in inputs.iter().zip(&files).skip(1) {
+        if let Some(difference) = layout_difference(first, file)? {
+            return Err(MdfError::LayoutMismatch(format!("{}: {difference}", input.as_ref().display())));
+        }
+    }
+
+    let mut writer = MdfWriter::new(output)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        first.header.abs_time,
+        first.header.tz_offset,
+        first.header.daylight_save_time,
+        first.header.time_flags,
+        first.header.time_quality,
+    )?;
+    if let Some(comment) = read_string_block(&first.mmap, first.header.comment_addr)? {
+        writer.set_header_metadata(&HeaderMetadata::from_xml(&comment))?;
+    }
+
+    let mut block_cache = HashMap::new();
+    for (k, (dg, cg)) in channel_groups(first).enumerate() {
+        let (cg_id, out_channels) =
+            copy_group_structure(&mut writer, &first.mmap, cg, &ChannelMapping::default(), &mut block_cache)?;
+        let mut sd_offsets = start_group_records(&mut writer, dg, cg, &cg_id, &out_channels, 0)?;
+        for file in &files {
+            let (dg, cg) = channel_groups(file).nth(k).expect("layouts were compared");
+            let record_id_len = dg.block.record_id_len as usize;
+            let master = cg.raw_channels.iter().map(|ch| &ch.block).find(|ch| ch.channel_type == 2 && ch.sync_type == 1);
+            let shift = match (first.header.abs_time, file.header.abs_time) {
+                (0, _) | (_, 0) => 0.0,
+                (t0, t) => (t as f64 - t0 as f64) / 1e9,
+            };
+            let shift = match master {
+                Some(ch) if shift != 0.0 => stored_master_shift(ch, shift)?,
+                _ => 0.0,
+            };
+            let correction = ClockCorrection::offset(shift);
+            append_group_records(
+                &mut writer,
+                &file.mmap,
+                dg,
+                cg,
+                &cg_id,
+                &out_channels,
+                0,
+                &mut sd_offsets,
+                |record, _| {
+                    if let Some(ch) = master.filter(|_| shift != 0.0)
+                        && let Some(value) = decode_channel_value(record, record_id_len, ch)
+                    {
+                        encode_master(record, record_id_len, ch, &correction.apply(value)?)?;
+                    }
+                    Ok(RecordAction::Keep)
+                },
+            )?;
+        }
+        finish_group_records(&mut writer, &cg_id, &out_channels)?;
+    }
+    writer.finalize()
+}
+
+/// The channel groups of `file` with their data groups, in file order.
+fn channel_groups(file: &MdfFile) -> impl Iterator<Item = (&RawDataGroup, &RawChannelGroup)> {
+    file.data_groups.iter().flat_map(|dg| dg.channel_groups.iter().map(move |cg| (dg, cg)))
+}
+
+/// The first difference between the channel group layouts of `first` and
+/// `other` that keeps the records of `other` from being appended to those
+/// of `first` as they are, or `None` if the layouts are identical.
+fn layout_difference(first: &MdfFile, other: &MdfFile) -> Result<Option<String>, MdfError> {
+    let expected = channel_groups(first).count();
+    let found = channel_groups(other).count();
+    if expected != found {
+        return Ok(Some(format!("{found} channel groups, expected {expected}")));
+    }
+    for (k, ((dg1, cg1), (dg2, cg2))) in channel_groups(first).zip(channel_groups(other)).enumerate() {
+        let record = |dg: &RawDataGroup, cg: &RawChannelGroup| {
+            (dg.block.record_id_len, cg.block.samples_byte_nr, cg.block.invalidation_bytes_nr)
+        };
+        if record(dg1, cg1) != record(dg2, cg2) {
+            return Ok(Some(format!(
+                "group {k} has records of {:?} (record ID, data, invalidation) bytes, expected {:?}",
+                record(dg2, cg2),
+                record(dg1, cg1)
+            )));
+        }
+        if cg1.raw_channels.len() != cg2.raw_channels.len() {
+            return Ok(Some(format!(
+                "group {k} has {} channels, expected {}",
+                cg2.raw_channels.len(),
+                cg1.raw_channels.len()
+            )));
+        }
+        for (i, (ch1, ch2)) in cg1.raw_channels.iter().zip(&cg2.raw_channels).enumerate() {
+            let name1 = read_string_block(&first.mmap, ch1.block.name_addr)?;
+            let name2 = read_string_block(&other.mmap, ch2.block.name_addr)?;
+            let describe = |name: &Option<String>| name.clone().unwrap_or_else(|| format!("#{i}"));
+            if name1 != name2 {
+                return Ok(Some(format!(
+                    "channel {i} of group {k} is '{}', expected '{}'",
+                    describe(&name2),
+                    describe(&name1)
+                )));
+            }
+            let (a, b) = (&ch1.block, &ch2.block);
+            let same = a.data_type == b.data_type
+                && a.channel_type == b.channel_type
+                && a.sync_type == b.sync_type
+                && a.byte_offset == b.byte_offset
+                && a.bit_offset == b.bit_offset
+                && a.bit_count == b.bit_count
+                && (a.data != 0) == (b.data != 0)
+                && a.flags & CN_FLAG_INVAL_BIT_VALID == b.flags & CN_FLAG_INVAL_BIT_VALID
+                && a.pos_invalidation_bit == b.pos_invalidation_bit;
+            if !same {
+                return Ok(Some(format!("channel '{}' of group {k} is stored differently", describe(&name1))));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// `shift` seconds of the master channel `ch` in its stored units: the
+/// identity and linear conversions are inverted, others are rejected.
+fn stored_master_shift(ch: &ChannelBlock, shift: f64) -> Result<f64, MdfError> {
+    match &ch.conversion {
+        None => Ok(shift),
+        Some(conv) if conv.cc_type == ConversionType::Identity => Ok(shift),
+        Some(conv) if conv.cc_type == ConversionType::Linear && conv.cc_val.len() >= 2 && conv.cc_val[1] != 0.0 => {
+            Ok(shift / conv.cc_val[1])
+        }
+        Some(_) => Err(MdfError::UnsupportedFeature(
+            "shifting a master channel with a non-linear conversion".into(),
+        )),
+    }
+}
+
+/// Store `value` in the bytes of the master channel `ch` of `record`.
+fn encode_master(
+    record: &mut [u8],
+    record_id_len: usize,
+    ch: &ChannelBlock,
+    value: &DecodedValue,
+) -> Result<(), MdfError> {
+    let bytes = (ch.bit_count / 8) as usize;
+    let start = record_id_len + ch.byte_offset as usize;
+    let unsupported = || {
+        MdfError::UnsupportedFeature(format!(
+            "shifting a {:?} master channel of {} bits at bit offset {}",
+            ch.data_type, ch.bit_count, ch.bit_offset
+        ))
+    };
+    if ch.bit_offset != 0 || !ch.bit_count.is_multiple_of(8) || bytes > 8 {
+        return Err(unsupported());
+    }
+    let field = record.get_mut(start..start + bytes).ok_or_else(unsupported)?;
+    let fits = |bits: u32| bytes == 8 || bits <= 8 * bytes as u32;
+    match (&ch.data_type, value, bytes) {
+        (DataType::FloatLE, DecodedValue::Float(v), 8) => field.copy_from_slice(&v.to_le_bytes()),
+        (DataType::FloatLE, DecodedValue::Float(v), 4) => field.copy_from_slice(&(*v as f32).to_le_bytes()),
+        (DataType::FloatBE, DecodedValue::Float(v), 8) => field.copy_from_slice(&v.to_be_bytes()),
+        (DataType::FloatBE, DecodedValue::Float(v), 4) => field.copy_from_slice(&(*v as f32).to_be_bytes()),
+        (DataType::UnsignedIntegerLE | DataType::UnsignedIntegerBE, DecodedValue::UnsignedInteger(v), _)
+            if fits(64 - v.leading_zeros()) =>
+        {
+            if ch.data_type == DataType::UnsignedIntegerLE {
+                field.copy_from_slice(&v.to_le_bytes()[..bytes]);
+            } else {
+                field.copy_from_slice(&v.to_be_bytes()[8 - bytes..]);
+            }
+        }
+        (DataType::SignedIntegerLE | DataType::SignedIntegerBE, DecodedValue::SignedInteger(v), _)
+            if fits(65 - v.unsigned_abs().leading_zeros()) =>
+        {
+            if ch.data_type == DataType::SignedIntegerLE {
+                field.copy_from_slice(&v.to_le_bytes()[..bytes]);
+            } else {
+                field.copy_from_slice(&v.to_be_bytes()[8 - bytes..]);
+            }
+        }
+        _ => {
+            return Err(MdfError::BlockSerializationError(format!(
+                "shifted master value {value:?} does not fit a {:?} channel of {} bits",
+                ch.data_type, ch.bit_count
+            )))
+        }
+    }
+    Ok(())
+}
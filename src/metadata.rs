@@ -0,0 +1,559 @@
+//! Self-describing metadata export.
+//!
+//! [`FileMetadata`] is a complete, machine-readable description of an MDF
+//! file — header, channel groups, channels, data types, units, conversions
+//! with their parameters, acquisition sources and attachments — without any
+//! sample data. It is produced by [`MDF::metadata`] and serialized to JSON by
+//! [`MDF::metadata_json`].
+//!
+//!
+//! [`HeaderMetadata`] holds the structured fields of the header comment
+//! (`<HDcomment>` XML): who produced a measurement, for which project, and
+//! any custom properties.
+//!
+//! [`MDF::metadata`]: crate::api::mdf::MDF::metadata
+//! [`MDF::metadata_json`]: crate::api::mdf::MDF::metadata_json
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::attachment_block::read_attachment_blocks;
+use crate::blocks::common::{read_string_block, DataType};
+use crate::blocks::conversion::{ConversionBlock, ConversionType};
+use crate::blocks::source_block::read_source_block;
+use crate::error::MdfError;
+use crate::parsing::mdf_file::MdfFile;
+
+/// Description of a whole MDF file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetadata {
+    /// Version string from the identification block (e.g. `"4.10"`).
+    pub version: String,
+    /// Program identifier from the identification block.
+    pub program: String,
+    /// Absolute start time in nanoseconds since the Unix epoch (0 = unset).
+    pub start_time_ns: u64,
+    /// Time zone offset in minutes.
+    pub tz_offset_min: i16,
+    /// Daylight saving time offset in minutes.
+    pub dst_offset_min: i16,
+    /// Header comment (plain text or `<HDcomment>` XML).
+    pub comment: Option<String>,
+    /// Channel groups in file order.
+    pub groups: Vec<GroupMetadata>,
+    /// Attachments in link order.
+    pub attachments: Vec<AttachmentMetadata>,
+}
+
+/// Description of a channel group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMetadata {
+    /// Acquisition name.
+    pub name: Option<String>,
+    /// Group comment.
+    pub comment: Option<String>,
+    /// Acquisition source.
+    pub source: Option<SourceMetadata>,
+    /// Record ID of the group within its data group.
+    pub record_id: u64,
+    /// Number of bytes used for the record ID in the data group.
+    pub record_id_len: u8,
+    /// Number of records.
+    pub record_count: u64,
+    /// Number of data bytes per record.
+    pub record_size: u32,
+    /// Number of invalidation bytes per record.
+    pub invalidation_bytes: u32,
+    /// Channels in link order.
+    pub channels: Vec<ChannelMetadata>,
+}
+
+/// Description of a single channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelMetadata {
+    /// Channel name.
+    pub name: Option<String>,
+    /// Physical unit.
+    pub unit: Option<String>,
+    /// Channel comment.
+    pub comment: Option<String>,
+    /// Raw data type.
+    pub data_type: DataType,
+    /// Channel type (0=data, 1=VLSD, 2=master, 3=virtual master, ...).
+    pub channel_type: u8,
+    /// Synchronization type (0=none, 1=time, 2=angle, 3=distance, 4=index).
+    pub sync_type: u8,
+    /// Byte offset within the record.
+    pub byte_offset: u32,
+    /// Bit offset within the first byte.
+    pub bit_offset: u8,
+    /// Number of bits.
+    pub bit_count: u32,
+    /// Channel flags.
+    pub flags: u32,
+    /// `true` for the group's master channel.
+    pub is_master: bool,
+    /// `true` for variable-length (VLSD) channels.
+    pub is_vlsd: bool,
+    /// Acquisition source.
+    pub source: Option<SourceMetadata>,
+    /// Conversion from raw to physical values.
+    pub conversion: Option<ConversionMetadata>,
+}
+
+/// Description of an SIBLOCK.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceMetadata {
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub comment: Option<String>,
+    /// Source type (0=OTHER, 1=ECU, 2=BUS, 3=I/O, 4=TOOL, 5=USER).
+    pub source_type: u8,
+    /// Bus type (0=NONE, 1=OTHER, 2=CAN, 3=LIN, ...).
+    pub bus_type: u8,
+    /// `true` if the source is simulated.
+    pub simulated: bool,
+}
+
+/// Description of a conversion, including referenced texts and nested
+/// conversions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionMetadata {
+    pub conversion_type: ConversionType,
+    /// `cc_val` parameters (coefficients, table keys, ranges, ...).
+    pub parameters: Vec<f64>,
+    /// Algebraic formula, if any.
+    pub formula: Option<String>,
+    /// Texts referenced by `cc_ref`, keyed by reference index.
+    pub texts: BTreeMap<usize, String>,
+    /// Nested conversions referenced by `cc_ref`, keyed by reference index.
+    pub nested: BTreeMap<usize, ConversionMetadata>,
+    /// Default (fallback) conversion.
+    pub default: Option<Box<ConversionMetadata>>,
+    /// Physical range `[min, max]`, if the limits are set.
+    pub physical_range: Option<[f64; 2]>,
+}
+
+/// Description of an ATBLOCK.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentMetadata {
+    /// File name or path of the attachment.
+    pub file_name: Option<String>,
+    /// MIME type.
+    pub mime_type: Option<String>,
+    pub comment: Option<String>,
+    /// `true` if the data is embedded in the MDF file.
+    pub embedded: bool,
+    /// `true` if the embedded data is compressed.
+    pub compressed: bool,
+    /// Size of the original data in bytes.
+    pub original_size: u64,
+    /// Size of the embedded data in bytes (0 for external attachments).
+    pub embedded_size: u64,
+    /// MD5 checksum of the original data as lowercase hex, if valid.
+    pub md5: Option<String>,
+}
+
+impl FileMetadata {
+    /// Collect the metadata of a parsed file. No sample data is read.
+    pub(crate) fn from_mdf_file(file: &MdfFile) -> Result<Self, MdfError> {
+        let mmap: &[u8] = &file.mmap;
+        let mut groups = Vec::new();
+        for dg in &file.data_groups {
+            for cg in &dg.channel_groups {
+                let mut channels = Vec::new();
+                for raw in &cg.raw_channels {
+                    let ch = &raw.block;
+                    let conversion = match ch.conversion.clone() {
+                        Some(mut cc) => {
+                            // Unresolvable references (cycles, dangling links) still
+                            // leave the conversion's own parameters worth reporting.
+                            let _ = cc.resolve_all_dependencies_with_address(mmap, ch.conversion_addr);
+                            Some(ConversionMetadata::from_block(&cc))
+                        }
+                        None => None,
+                    };
+                    channels.push(ChannelMetadata {
+                        name: read_string_block(mmap, ch.name_addr)?,
+                        unit: read_string_block(mmap, ch.unit_addr)?,
+                        comment: read_string_block(mmap, ch.comment_addr)?,
+                        data_type: ch.data_type.clone(),
+                        channel_type: ch.channel_type,
+                        sync_type: ch.sync_type,
+                        byte_offset: ch.byte_offset,
+                        bit_offset: ch.bit_offset,
+                        bit_count: ch.bit_count,
+                        flags: ch.flags,
+                        is_master: ch.channel_type == 2 || ch.channel_type == 3,
+                        is_vlsd: ch.channel_type == 1 && ch.data != 0,
+                        source: SourceMetadata::read(mmap, ch.source_addr)?,
+                        conversion,
+                    });
+                }
+                groups.push(GroupMetadata {
+                    name: read_string_block(mmap, cg.block.acq_name_addr)?,
+                    comment: read_string_block(mmap, cg.block.comment_addr)?,
+                    source: SourceMetadata::read(mmap, cg.block.acq_source_addr)?,
+                    record_id: cg.block.record_id,
+                    record_id_len: dg.block.record_id_len,
+                    record_count: cg.block.cycles_nr,
+                    record_size: cg.block.samples_byte_nr,
+                    invalidation_bytes: cg.block.invalidation_bytes_nr,
+                    channels,
+                });
+            }
+        }
+
+        let mut attachments = Vec::new();
+        for (_, at) in read_attachment_blocks(mmap, file.header.first_attachment_addr)? {
+            attachments.push(AttachmentMetadata {
+                file_name: read_string_block(mmap, at.filename_addr)?,
+                mime_type: read_string_block(mmap, at.mimetype_addr)?,
+                comment: read_string_block(mmap, at.comment_addr)?,
+                embedded: at.is_embedded(),
+                compressed: at.is_compressed(),
+                original_size: at.original_size,
+                embedded_size: at.embedded_size,
+                md5: at.md5().map(|d| d.iter().map(|b| format!("{b:02x}")).collect()),
+            });
+        }
+
+        Ok(FileMetadata {
+            version: file.identification.version_identifier.trim().to_string(),
+            program: file.identification.program_identifier.trim().to_string(),
+            start_time_ns: file.header.abs_time,
+            tz_offset_min: file.header.tz_offset,
+            dst_offset_min: file.header.daylight_save_time,
+            comment: read_string_block(mmap, file.header.comment_addr)?,
+            groups,
+            attachments,
+        })
+    }
+
+    /// Serialize to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, MdfError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| MdfError::BlockSerializationError(format!("JSON serialization failed: {}", e)))
+    }
+}
+
+impl SourceMetadata {
+    fn read(mmap: &[u8], address: u64) -> Result<Option<Self>, MdfError> {
+        if address == 0 {
+            return Ok(None);
+        }
+        let sb = read_source_block(mmap, address)?;
+        Ok(Some(SourceMetadata {
+            name: read_string_block(mmap, sb.name_addr)?,
+            path: read_string_block(mmap, sb.path_addr)?,
+            comment: read_string_block(mmap, sb.comment_addr)?,
+            source_type: sb.source_type,
+            bus_type: sb.bus_type,
+            simulated: sb.flags & 0x01 != 0,
+        }))
+    }
+}
+
+impl ConversionMetadata {
+    /// Build from a conversion whose dependencies have been resolved.
+    fn from_block(cc: &ConversionBlock) -> Self {
+        let texts = cc
+            .resolved_texts
+            .iter()
+            .flatten()
+            .map(|(k, v)| (*k, v.clone()))
+            .collect();
+        let nested = cc
+            .resolved_conversions
+            .iter()
+            .flatten()
+            .map(|(k, v)| (*k, ConversionMetadata::from_block(v)))
+            .collect();
+        let physical_range = match (cc.cc_phy_range_min, cc.cc_phy_range_max) {
+            (Some(min), Some(max)) if cc.cc_flags & 0b10 != 0 => Some([min, max]),
+            _ => None,
+        };
+        ConversionMetadata {
+            conversion_type: cc.cc_type,
+            parameters: cc.cc_val.clone(),
+            formula: cc.formula.clone(),
+            texts,
+            nested,
+            default: cc
+                .default_conversion
+                .as_ref()
+                .map(|d| Box::new(ConversionMetadata::from_block(d))),
+            physical_range,
+        }
+    }
+}
+
+/// Structured content of the header comment (`<HDcomment>` XML).
+///
+/// `author`, `department` (the author's organization unit), `project` and
+/// `subject` are the common properties named by the MDF 4 schema;
+/// `measurement_uuid` is stored as the `measurement_uuid` property, and
+/// `properties` holds any further `<e name="...">` entries of
+/// `<common_properties>` in document order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeaderMetadata {
+    /// Free text of the comment (`<TX>`).
+    pub comment: Option<String>,
+    pub author: Option<String>,
+    pub department: Option<String>,
+    pub project: Option<String>,
+    pub subject: Option<String>,
+    /// Identifier of the measurement, e.g. from [`new_measurement_uuid`].
+    pub measurement_uuid: Option<String>,
+    /// Custom `(name, value)` properties.
+    pub properties: Vec<(String, String)>,
+}
+
+impl HeaderMetadata {
+    /// Serialize to an `<HDcomment>` document for an `##MD` block.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<HDcomment xmlns=\"http://www.asam.net/mdf/v4\">");
+        xml.push_str(&format!("<TX>{}</TX>", xml_escape(self.comment.as_deref().unwrap_or(""))));
+        let named = [
+            ("author", &self.author),
+            ("department", &self.department),
+            ("project", &self.project),
+            ("subject", &self.subject),
+            ("measurement_uuid", &self.measurement_uuid),
+        ];
+        let entries: Vec<(&str, &str)> = named
+            .iter()
+            .filter_map(|(name, value)| value.as_deref().map(|v| (*name, v)))
+            .chain(self.properties.iter().map(|(n, v)| (n.as_str(), v.as_str())))
+            .collect();
+        if !entries.is_empty() {
+            xml.push_str("<common_properties>");
+            for (name, value) in entries {
+                xml.push_str(&format!("<e name=\"{}\">{}</e>", xml_escape(name), xml_escape(value)));
+            }
+            xml.push_str("</common_properties>");
+        }
+        xml.push_str("</HDcomment>");
+        xml
+    }
+
+    /// Parse a header comment. Plain text (not `<HDcomment>` XML) becomes
+    /// the `comment`; nested `<tree>` / `<list>` properties are skipped.
+    pub fn from_xml(text: &str) -> Self {
+        let Some(root) = text.find("<HDcomment") else {
+            return HeaderMetadata { comment: Some(text.to_string()), ..Default::default() };
+        };
+        let xml = &text[root..];
+        let mut meta = HeaderMetadata {
+            comment: element_text(xml, "TX").map(|tx| xml_unescape(&tx)),
+            ..Default::default()
+        };
+        let Some(props) = element_text(xml, "common_properties") else {
+            return meta;
+        };
+        // Walk the top-level children, skipping nested `<tree>` / `<list>`.
+        let mut rest = props.as_str();
+        let mut depth = 0usize;
+        while let Some(lt) = rest.find('<') {
+            rest = &rest[lt..];
+            let Some(tag_end) = rest.find('>') else { break };
+            let tag = &rest[1..tag_end];
+            let self_closing = tag.ends_with('/');
+            if tag.starts_with("tree") || tag.starts_with("list") {
+                if !self_closing {
+                    depth += 1;
+                }
+            } else if tag.starts_with("/tree") || tag.starts_with("/list") {
+                depth = depth.saturating_sub(1);
+            } else if depth == 0 && (tag == "e" || tag.starts_with("e ")) && !self_closing {
+                let Some(value_end) = rest.find("</e>") else { break };
+                let value = xml_unescape(&rest[tag_end + 1..value_end]);
+                rest = &rest[value_end + 4..];
+                let Some(name) = attribute(tag, "name") else { continue };
+                match name.as_str() {
+                    "author" => meta.author = Some(value),
+                    "department" => meta.department = Some(value),
+                    "project" => meta.project = Some(value),
+                    "subject" => meta.subject = Some(value),
+                    "measurement_uuid" => meta.measurement_uuid = Some(value),
+                    _ => meta.properties.push((name, value)),
+                }
+                continue;
+            }
+            rest = &rest[tag_end + 1..];
+        }
+        meta
+    }
+}
+
+/// A random (version 4) UUID in its hyphenated form, for
+/// [`HeaderMetadata::measurement_uuid`].
+///
+/// The bits come from the standard library's randomly keyed hasher, fed
+/// with the current time and a process-wide counter.
+pub fn new_measurement_uuid() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let mut halves = [0u64; 2];
+    for half in &mut halves {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u64(count);
+        hasher.write_u32(std::process::id());
+        *half = hasher.finish();
+    }
+    let mut bits = (u128::from(halves[0]) << 64) | u128::from(halves[1]);
+    // Version 4, variant 10xx.
+    bits = (bits & !(0xf << 76)) | (0x4 << 76);
+    bits = (bits & !(0x3 << 62)) | (0x2 << 62);
+    let hex = format!("{bits:032x}");
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Start time and header comment of a measurement, read with
+/// [`MDF::header_info`](crate::api::mdf::MDF::header_info) and written with
+/// `MdfWriter::set_header_info`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeaderInfo {
+    /// Start time from the `##HD` block; `None` if it is not set.
+    pub start_time: Option<StartTime>,
+    /// Fields of the header comment; all `None` without a comment.
+    pub metadata: HeaderMetadata,
+}
+
+/// Start time of a measurement, as stored in the `##HD` block.
+///
+/// Read with [`MDF::start_time`](crate::api::mdf::MDF::start_time) and
+/// written with `MdfWriter::set_start_time` or `MdfWriter::set_header_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartTime {
+    /// Nanoseconds since the Unix epoch (UTC, or local time if
+    /// [`is_local_time`](Self::is_local_time)).
+    pub abs_time_ns: u64,
+    /// Time zone offset in minutes.
+    pub tz_offset_min: i16,
+    /// Daylight saving time offset in minutes.
+    pub dst_offset_min: i16,
+    /// `HD` time flags: bit 0 = local time, bit 1 = offsets valid.
+    pub time_flags: u8,
+    /// `HD` time quality class: 0 = local PC clock, 10 = external source,
+    /// 16 = external absolute synchronized source.
+    pub time_quality: u8,
+}
+
+impl StartTime {
+    /// Whether `abs_time_ns` is local time rather than UTC.
+    pub fn is_local_time(&self) -> bool {
+        self.time_flags & 0x01 != 0
+    }
+
+    /// Total offset of local time from UTC in minutes, if the offsets are
+    /// marked valid.
+    pub fn utc_offset_min(&self) -> Option<i32> {
+        (self.time_flags & 0x02 != 0)
+            .then(|| i32::from(self.tz_offset_min) + i32::from(self.dst_offset_min))
+    }
+}
+
+/// Inner text of the first `<tag>` element of `xml`, if any.
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut search = 0;
+    while let Some(pos) = xml[search..].find(&open) {
+        let start = search + pos + open.len();
+        // Skip longer tag names sharing the prefix (e.g. `<TXT`).
+        match xml[start..].chars().next() {
+            Some('>') | Some(' ') | Some('/') => {}
+            _ => {
+                search = start;
+                continue;
+            }
+        }
+        let tag_end = start + xml[start..].find('>')?;
+        if xml[..tag_end].ends_with('/') {
+            return Some(String::new());
+        }
+        let end = tag_end + 1 + xml[tag_end + 1..].find(&close)?;
+        return Some(xml[tag_end + 1..end].to_string());
+    }
+    None
+}
+
+/// Unescaped value of attribute `name` in a tag's attribute list.
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let key = format!("{name}=");
+    let mut search = 0;
+    while let Some(pos) = attrs[search..].find(&key) {
+        let at = search + pos;
+        let preceded_by_space = at == 0 || attrs[..at].ends_with(char::is_whitespace);
+        let value = &attrs[at + key.len()..];
+        let quote = value.chars().next()?;
+        if preceded_by_space && (quote == '"' || quote == '\'') {
+            let end = value[1..].find(quote)?;
+            return Some(xml_unescape(&value[1..1 + end]));
+        }
+        search = at + key.len();
+    }
+    None
+}
+
+/// Escape the five XML special characters for use in an `##MD` block.
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Inverse of [`xml_escape`], also resolving numeric character references.
+fn xml_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else { break };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,174 @@
+//! Reading MDF 4 files through seek-based reads instead of a memory map.
+//!
+//! Mapping a file takes address space for all of it, which a 32-bit process
+//! does not have for multi-gigabyte recordings. [`read_image`] instead reads
+//! every block reachable from the header through a [`ByteRangeReader`] and
+//! packs them into an in-memory image with their links relocated, so the
+//! regular block parsers run on it unchanged. Data blocks (`##DT`, `##DZ`,
+//! `##SD`, ...) are the bulk of a file and stay on disk: the image holds only
+//! their headers, and [`BlockSource`] reads the data section of one block at
+//! a time while a data chain is walked, without keeping it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Mutex, PoisonError};
+
+use crate::blocks::common::{BlockHeader, BlockParse, DATA_BLOCK_IDS, HD_OFFSET};
+use crate::blocks::data_zipped_block::DataZippedBlock;
+use crate::blocks::walk::links_at;
+use crate::error::MdfError;
+use crate::index::{ByteRangeReader, SeekRangeReader};
+
+/// An in-memory image of the metadata of a file; see [`read_image`].
+pub(crate) struct Image {
+    pub bytes: Vec<u8>,
+    /// File offset and length of each data block, keyed by the offset of
+    /// its header in the image.
+    pub data_blocks: HashMap<u64, (u64, u64)>,
+}
+
+/// Read the blocks reachable from the header of the MDF 4 file behind
+/// `reader` into an [`Image`].
+///
+/// Blocks are laid out in the order they are found, each 8-byte aligned,
+/// with the identification block and `##HD` at their usual offsets 0 and 64.
+/// Every link is rewritten to the image offset of its target. Data blocks
+/// are reduced to their header, which keeps the original `block_len` but
+/// no links.
+pub(crate) fn read_image<R>(reader: &mut R) -> Result<Image, MdfError>
+where
+    R: ByteRangeReader<Error = MdfError>,
+{
+    let file_size = reader.size()?.unwrap_or(u64::MAX);
+    let mut bytes = reader.read_range(0, HD_OFFSET)?;
+    let mut data_blocks = HashMap::new();
+    // File offset → image offset of every block read.
+    let mut relocated: HashMap<u64, u64> = HashMap::new();
+    // Image offsets of the blocks whose links are rewritten at the end.
+    let mut linked = Vec::new();
+
+    let mut pending = vec![HD_OFFSET];
+    while let Some(addr) = pending.pop() {
+        if relocated.contains_key(&addr) {
+            continue;
+        }
+        let header = BlockHeader::from_bytes(&reader.read_range(addr, 24)?)?;
+        let at = bytes.len() as u64;
+        relocated.insert(addr, at);
+        if DATA_BLOCK_IDS.contains(&header.id.as_str()) {
+            bytes.extend_from_slice(&BlockHeader { links_nr: 0, ..header.clone() }.to_bytes()?);
+            data_blocks.insert(at, (addr, header.block_len));
+        } else {
+            let block = reader.read_range(addr, header.block_len)?;
+            let links = links_at(&block, 0)?;
+            for &link in links.iter().rev().filter(|&&link| link != 0) {
+                if link >= file_size {
+                    return Err(MdfError::LinkOutOfBounds { block_id: header.id.clone(), offset: link, file_size });
+                }
+                pending.push(link);
+            }
+            bytes.extend_from_slice(&block);
+            linked.push((at, links));
+        }
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+    }
+
+    for (at, links) in linked {
+        for (i, link) in links.into_iter().enumerate().filter(|&(_, link)| link != 0) {
+            let pos = (at + 24 + 8 * i as u64) as usize;
+            bytes[pos..pos + 8].copy_from_slice(&relocated[&link].to_le_bytes());
+        }
+    }
+    Ok(Image { bytes, data_blocks })
+}
+
+/// Range reader keeping a single window of at least `capacity` bytes of
+/// `inner`, so reads of neighbouring metadata blocks are served from one
+/// read of the file while memory use stays bounded.
+pub(crate) struct WindowReader<R> {
+    inner: R,
+    window: Vec<u8>,
+    window_offset: u64,
+    capacity: u64,
+}
+
+impl<R: Read + Seek> WindowReader<R> {
+    pub(crate) fn new(inner: R, capacity: u64) -> Self {
+        WindowReader { inner, window: Vec::new(), window_offset: 0, capacity }
+    }
+}
+
+impl<R: Read + Seek> ByteRangeReader for WindowReader<R> {
+    type Error = MdfError;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+        let start = offset.checked_sub(self.window_offset).map(|start| start as usize);
+        if let Some(bytes) = start.and_then(|start| self.window.get(start..start.checked_add(length as usize)?)) {
+            return Ok(bytes.to_vec());
+        }
+        self.inner.seek(SeekFrom::Start(offset))?;
+        self.window.clear();
+        (&mut self.inner).take(length.max(self.capacity)).read_to_end(&mut self.window)?;
+        self.window_offset = offset;
+        match self.window.get(..length as usize) {
+            Some(bytes) => Ok(bytes.to_vec()),
+            None => Err(MdfError::IOError(std::io::ErrorKind::UnexpectedEof.into())),
+        }
+    }
+
+    fn size(&mut self) -> Result<Option<u64>, MdfError> {
+        Ok(Some(self.inner.seek(SeekFrom::End(0))?))
+    }
+}
+
+/// A file or other seekable source a [`BlockSource`] reads from.
+pub(crate) trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// The file behind an [`Image`], from which the data sections of its data
+/// blocks are read.
+pub(crate) struct BlockSource {
+    file: Mutex<Box<dyn ReadSeek>>,
+    /// See [`Image::data_blocks`].
+    data_blocks: HashMap<u64, (u64, u64)>,
+}
+
+impl fmt::Debug for BlockSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockSource").field("data_blocks", &self.data_blocks.len()).finish_non_exhaustive()
+    }
+}
+
+impl BlockSource {
+    pub(crate) fn new(file: impl ReadSeek + 'static, data_blocks: HashMap<u64, (u64, u64)>) -> Self {
+        BlockSource { file: Mutex::new(Box::new(file)), data_blocks }
+    }
+
+    /// The data section of the data block at image offset `addr`, inflated
+    /// if it is a `##DZ` block; `None` if there is no data block there.
+    pub(crate) fn data_section(&self, addr: u64) -> Option<Result<Vec<u8>, MdfError>> {
+        let &(offset, len) = self.data_blocks.get(&addr)?;
+        Some(self.read_data_section(offset, len))
+    }
+
+    fn read_data_section(&self, offset: u64, len: u64) -> Result<Vec<u8>, MdfError> {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut block = SeekRangeReader::new(&mut *file).read_range(offset, len)?;
+        if block.starts_with(b"##DZ") {
+            return DataZippedBlock::from_bytes(&block)?.decompress();
+        }
+        let links_nr = BlockHeader::from_bytes(&block)?.links_nr;
+        let start = links_nr.checked_mul(8).and_then(|len| len.checked_add(24)).unwrap_or(u64::MAX);
+        if start > len {
+            return Err(MdfError::TooShortBuffer {
+                actual: len as usize,
+                expected: start as usize,
+                file: file!(),
+                line: line!(),
+            });
+        }
+        Ok(block.split_off(start as usize))
+    }
+}
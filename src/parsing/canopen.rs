@@ -0,0 +1,170 @@
+//! CANopen `DATE` and `TIME` samples (MDF data types 13 and 14).
+//!
+//! Both are fixed-size little-endian structures: a 7-byte calendar date with
+//! the time of day, and a 6-byte time since 1984-01-01. They decode to
+//! [`DecodedValue::CanOpenDate`](crate::parsing::decoder::DecodedValue::CanOpenDate)
+//! and [`DecodedValue::CanOpenTime`](crate::parsing::decoder::DecodedValue::CanOpenTime)
+//! and are written back from those values.
+
+use std::fmt;
+
+/// Days from 1970-01-01 to 1984-01-01, the epoch of [`CanOpenTime`].
+const CANOPEN_TIME_EPOCH_DAYS: i64 = 5_113;
+const NANOS_PER_MS: i64 = 1_000_000;
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Calendar date and time of day of a 7-byte CANopen `DATE` sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CanOpenDate {
+    /// Milliseconds within the minute, seconds included (0..=59999).
+    pub ms: u16,
+    /// Minute of the hour (0..=59).
+    pub minute: u8,
+    /// Hour of the day (0..=23).
+    pub hour: u8,
+    /// Whether the time is daylight saving time.
+    pub summer_time: bool,
+    /// Day of the month (1..=31).
+    pub day: u8,
+    /// Day of the week, 1 = Monday to 7 = Sunday, 0 when not set.
+    pub day_of_week: u8,
+    /// Month of the year (1..=12).
+    pub month: u8,
+    /// Years since 2000 (0..=99).
+    pub year: u8,
+}
+
+impl CanOpenDate {
+    /// Encoded size in bytes.
+    pub const BYTES: usize = 7;
+
+    /// Decode the first [`BYTES`](Self::BYTES) bytes of `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        CanOpenDate {
+            ms: u16::from_le_bytes([bytes[0], bytes[1]]),
+            minute: bytes[2] & 0x3F,
+            hour: bytes[3] & 0x1F,
+            summer_time: bytes[3] & 0x80 != 0,
+            day: bytes[4] & 0x1F,
+            day_of_week: bytes[4] >> 5,
+            month: bytes[5] & 0x3F,
+            year: bytes[6] & 0x7F,
+        }
+    }
+
+    /// Encode the fields, masked to their bit widths.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let ms = self.ms.to_le_bytes();
+        [
+            ms[0],
+            ms[1],
+            self.minute & 0x3F,
+            (self.hour & 0x1F) | if self.summer_time { 0x80 } else { 0 },
+            (self.day & 0x1F) | (self.day_of_week & 0x07) << 5,
+            self.month & 0x3F,
+            self.year & 0x7F,
+        ]
+    }
+
+    /// The date as nanoseconds since 1970-01-01, reading the fields as UTC.
+    pub fn unix_nanos(&self) -> i64 {
+        let days = days_from_civil(2000 + self.year as i64, self.month as i64, self.day as i64);
+        let ms = (self.hour as i64 * 60 + self.minute as i64) * 60_000 + self.ms as i64;
+        (days * MS_PER_DAY + ms) * NANOS_PER_MS
+    }
+}
+
+impl fmt::Display for CanOpenDate {
+    /// ISO 8601 date and time with milliseconds, e.g. `2024-05-01T12:30:15.250`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+            2000 + self.year as u32,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.ms / 1000,
+            self.ms % 1000
+        )
+    }
+}
+
+/// A 6-byte CANopen `TIME` sample: days since 1984-01-01 and the time of
+/// that day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CanOpenTime {
+    /// Milliseconds since midnight; only the low 28 bits are stored.
+    pub ms: u32,
+    /// Days since 1984-01-01.
+    pub days: u16,
+}
+
+impl CanOpenTime {
+    /// Encoded size in bytes.
+    pub const BYTES: usize = 6;
+
+    /// Decode the first [`BYTES`](Self::BYTES) bytes of `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        CanOpenTime {
+            ms: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) & 0x0FFF_FFFF,
+            days: u16::from_le_bytes([bytes[4], bytes[5]]),
+        }
+    }
+
+    /// Encode the fields, with `ms` masked to 28 bits.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let ms = (self.ms & 0x0FFF_FFFF).to_le_bytes();
+        let days = self.days.to_le_bytes();
+        [ms[0], ms[1], ms[2], ms[3], days[0], days[1]]
+    }
+
+    /// The time as nanoseconds since 1970-01-01.
+    pub fn unix_nanos(&self) -> i64 {
+        ((CANOPEN_TIME_EPOCH_DAYS + self.days as i64) * MS_PER_DAY + self.ms as i64) * NANOS_PER_MS
+    }
+}
+
+impl fmt::Display for CanOpenTime {
+    /// ISO 8601 date and time with milliseconds, e.g. `2024-05-01T12:30:15.250`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(CANOPEN_TIME_EPOCH_DAYS + self.days as i64);
+        let ms = self.ms as i64;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+            year,
+            month,
+            day,
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000
+        )
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`]: `(year, month, day)` of a day count.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -1,11 +1,8 @@
-use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::channel_block::{ChannelBlock, CN_FLAG_ALL_INVALID, CN_FLAG_INVAL_BIT_VALID};
 use crate::blocks::common::DataType;
+use crate::parsing::canopen::{CanOpenDate, CanOpenTime};
 use byteorder::{LittleEndian, BigEndian, ByteOrder};
 
-// Flag bit positions for cn_flags
-const CN_FLAG_ALL_INVALID: u32 = 0x01;  // Bit 0: All values are invalid
-const CN_FLAG_INVAL_BIT_VALID: u32 = 0x02;  // Bit 1: Invalidation bit is valid
-
 /// An enum representing the decoded value of a channel sample.
 #[derive(Debug, Clone, PartialEq)]
 pub enum DecodedValue {
@@ -16,6 +13,8 @@ pub enum DecodedValue {
     ByteArray(Vec<u8>),
     MimeSample(Vec<u8>),
     MimeStream(Vec<u8>),
+    CanOpenDate(CanOpenDate),
+    CanOpenTime(CanOpenTime),
     Unknown,
 }
 
@@ -146,6 +145,9 @@ pub fn decode_f64_from_record(
     if channel.channel_type == 1 && channel.data != 0 {
         return f64::NAN;
     }
+    if is_integer(&channel.data_type) && !integer_field_fits(bit_offset, bit_count) {
+        return f64::NAN;
+    }
 
     let num_bytes = ((bit_offset + bit_count + 7) / 8).max(1);
     if base_offset + num_bytes > record.len() {
@@ -163,7 +165,9 @@ pub fn decode_f64_from_record(
                 }
             }
             let raw = slice.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64);
-            if bit_count == 32 {
+            if bit_count == 16 {
+                half_to_f64(raw as u16)
+            } else if bit_count == 32 {
                 f32::from_bits(raw as u32) as f64
             } else if bit_count == 64 {
                 f64::from_bits(raw)
@@ -180,7 +184,9 @@ pub fn decode_f64_from_record(
                 }
             }
             let raw = slice.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
-            if bit_count == 32 {
+            if bit_count == 16 {
+                half_to_f64(raw as u16)
+            } else if bit_count == 32 {
                 f32::from_bits(raw as u32) as f64
             } else if bit_count == 64 {
                 f64::from_bits(raw)
@@ -264,6 +270,150 @@ pub fn decode_f64_from_record(
     }
 }
 
+fn is_integer(data_type: &DataType) -> bool {
+    matches!(data_type,
+        DataType::UnsignedIntegerLE | DataType::UnsignedIntegerBE |
+        DataType::SignedIntegerLE | DataType::SignedIntegerBE)
+}
+
+/// Whether an integer field of `bit_count` bits at `bit_offset` can be
+/// extracted through a `u64`. Valid channel blocks use bit offsets 0..=7
+/// and 1 to 64 bits; anything else comes from a corrupt file.
+fn integer_field_fits(bit_offset: usize, bit_count: usize) -> bool {
+    bit_offset < 8 && (1..=64).contains(&bit_count)
+}
+
+/// Widen an IEEE 754 half-precision value (16-bit float channels) to `f64`.
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10 & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f64;
+    match exponent {
+        0 => sign * mantissa * 2f64.powi(-24),
+        0x1F if mantissa == 0.0 => sign * f64::INFINITY,
+        0x1F => f64::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// `true` if the cn_flags alone say every sample of `channel` is valid, so
+/// [`check_value_validity`] never needs to look at the record.
+pub fn always_valid(channel: &ChannelBlock) -> bool {
+    channel.flags & (CN_FLAG_ALL_INVALID | CN_FLAG_INVAL_BIT_VALID) == 0
+}
+
+/// Storage type of a byte-aligned little-endian primitive sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+/// Where and how a primitive sample sits in each record.
+///
+/// Channels with this layout can be decoded column-wise by
+/// [`extend_f64_column`] / [`extend_u64_column`], which copy the sample bytes
+/// straight out of the record buffer instead of building a [`DecodedValue`]
+/// per sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimitiveLayout {
+    /// Byte offset of the sample from the start of the record (record ID
+    /// included).
+    pub offset: usize,
+    pub ty: PrimitiveType,
+}
+
+impl PrimitiveLayout {
+    /// The layout of `channel` in records of `record_size` bytes, or `None`
+    /// unless the channel is a fixed-length little-endian integer or float
+    /// that starts on a byte boundary and fills 1, 2, 4 or 8 whole bytes.
+    ///
+    /// Conversions are not considered; callers decide whether raw values are
+    /// what they need.
+    pub fn of(channel: &ChannelBlock, record_id_size: usize, record_size: usize) -> Option<Self> {
+        if channel.channel_type == 1 && channel.data != 0 || channel.bit_offset != 0 {
+            return None;
+        }
+        let ty = match (&channel.data_type, channel.bit_count) {
+            (DataType::UnsignedIntegerLE, 8) => PrimitiveType::U8,
+            (DataType::UnsignedIntegerLE, 16) => PrimitiveType::U16,
+            (DataType::UnsignedIntegerLE, 32) => PrimitiveType::U32,
+            (DataType::UnsignedIntegerLE, 64) => PrimitiveType::U64,
+            (DataType::SignedIntegerLE, 8) => PrimitiveType::I8,
+            (DataType::SignedIntegerLE, 16) => PrimitiveType::I16,
+            (DataType::SignedIntegerLE, 32) => PrimitiveType::I32,
+            (DataType::SignedIntegerLE, 64) => PrimitiveType::I64,
+            (DataType::FloatLE, 32) => PrimitiveType::F32,
+            (DataType::FloatLE, 64) => PrimitiveType::F64,
+            _ => return None,
+        };
+        let offset = record_id_size + channel.byte_offset as usize;
+        (offset + channel.bit_count as usize / 8 <= record_size).then_some(PrimitiveLayout { offset, ty })
+    }
+}
+
+/// Append the sample of every whole record in `records` to `out`, read as
+/// the `N` bytes at `offset` and mapped through `read`.
+#[inline(always)]
+fn extend_column<const N: usize, T>(
+    records: &[u8],
+    record_size: usize,
+    offset: usize,
+    out: &mut Vec<T>,
+    read: impl Fn([u8; N]) -> T,
+) {
+    out.extend(records.chunks_exact(record_size).map(|rec| {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&rec[offset..offset + N]);
+        read(bytes)
+    }));
+}
+
+/// Decode the samples of `layout` from consecutive records of `record_size`
+/// bytes as `f64`, appending them to `out`.
+///
+/// A trailing partial record is ignored. No conversion or validity check is
+/// applied.
+pub fn extend_f64_column(records: &[u8], record_size: usize, layout: PrimitiveLayout, out: &mut Vec<f64>) {
+    let offset = layout.offset;
+    match layout.ty {
+        PrimitiveType::U8 => extend_column(records, record_size, offset, out, |b| u8::from_le_bytes(b) as f64),
+        PrimitiveType::U16 => extend_column(records, record_size, offset, out, |b| u16::from_le_bytes(b) as f64),
+        PrimitiveType::U32 => extend_column(records, record_size, offset, out, |b| u32::from_le_bytes(b) as f64),
+        PrimitiveType::U64 => extend_column(records, record_size, offset, out, |b| u64::from_le_bytes(b) as f64),
+        PrimitiveType::I8 => extend_column(records, record_size, offset, out, |b| i8::from_le_bytes(b) as f64),
+        PrimitiveType::I16 => extend_column(records, record_size, offset, out, |b| i16::from_le_bytes(b) as f64),
+        PrimitiveType::I32 => extend_column(records, record_size, offset, out, |b| i32::from_le_bytes(b) as f64),
+        PrimitiveType::I64 => extend_column(records, record_size, offset, out, |b| i64::from_le_bytes(b) as f64),
+        PrimitiveType::F32 => extend_column(records, record_size, offset, out, |b| f32::from_le_bytes(b) as f64),
+        PrimitiveType::F64 => extend_column(records, record_size, offset, out, f64::from_le_bytes),
+    }
+}
+
+/// Like [`extend_f64_column`] for unsigned integer layouts, decoding to
+/// `u64` without loss.
+///
+/// Returns `false`, leaving `out` untouched, for signed and float layouts.
+pub fn extend_u64_column(records: &[u8], record_size: usize, layout: PrimitiveLayout, out: &mut Vec<u64>) -> bool {
+    let offset = layout.offset;
+    match layout.ty {
+        PrimitiveType::U8 => extend_column(records, record_size, offset, out, |b| u8::from_le_bytes(b) as u64),
+        PrimitiveType::U16 => extend_column(records, record_size, offset, out, |b| u16::from_le_bytes(b) as u64),
+        PrimitiveType::U32 => extend_column(records, record_size, offset, out, |b| u32::from_le_bytes(b) as u64),
+        PrimitiveType::U64 => extend_column(records, record_size, offset, out, u64::from_le_bytes),
+        _ => return false,
+    }
+    true
+}
+
 /// Internal function that performs the actual value decoding.
 ///
 /// This is the core decoding logic separated out so it can be used by both
@@ -278,21 +428,28 @@ fn decode_value_internal(
     let base_offset = record_id_size + channel.byte_offset as usize;
     let bit_offset = channel.bit_offset as usize;
     let bit_count = channel.bit_count as usize;
+    if is_integer(&channel.data_type) && !integer_field_fits(bit_offset, bit_count) {
+        return None;
+    }
+
+    // For non-numeric types, assume the field is stored in whole bytes.
+    let is_byte_field = matches!(channel.data_type,
+        DataType::StringLatin1 | DataType::StringUtf8 | DataType::StringUtf16LE | DataType::StringUtf16BE |
+        DataType::ByteArray | DataType::MimeSample | DataType::MimeStream);
+    let num_bytes = if is_byte_field {
+        bit_count / 8
+    } else {
+        (bit_offset + bit_count).div_ceil(8).max(1)
+    };
 
     let slice: &[u8] = if channel.channel_type == 1 && channel.data != 0 {
-        // VLSD: the entire record *is* the payload
+        // VLSD: the entire record *is* the payload; a numeric value still
+        // needs its full width.
+        if !is_byte_field && record.len() < num_bytes {
+            return None;
+        }
         record
     } else {
-        // For non-numeric types, assume the field is stored in whole bytes.
-        let num_bytes = if matches!(channel.data_type,
-            DataType::StringLatin1 | DataType::StringUtf8 | DataType::StringUtf16LE | DataType::StringUtf16BE |
-            DataType::ByteArray | DataType::MimeSample | DataType::MimeStream)
-        {
-            bit_count / 8
-        } else {
-            ((bit_offset + bit_count + 7) / 8).max(1)
-        };
-
         if base_offset + num_bytes > record.len() {
             return None;
         }
@@ -383,7 +540,9 @@ fn decode_value_internal(
                 }
             }
             let raw = slice.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64);
-            if bit_count == 32 {
+            if bit_count == 16 {
+                Some(DecodedValue::Float(half_to_f64(raw as u16)))
+            } else if bit_count == 32 {
                 Some(DecodedValue::Float(f32::from_bits(raw as u32) as f64))
             } else if bit_count == 64 {
                 Some(DecodedValue::Float(f64::from_bits(raw)))
@@ -400,7 +559,9 @@ fn decode_value_internal(
                 }
             }
             let raw = slice.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
-            if bit_count == 32 {
+            if bit_count == 16 {
+                Some(DecodedValue::Float(half_to_f64(raw as u16)))
+            } else if bit_count == 32 {
                 Some(DecodedValue::Float(f32::from_bits(raw as u32) as f64))
             } else if bit_count == 64 {
                 Some(DecodedValue::Float(f64::from_bits(raw)))
@@ -442,6 +603,12 @@ fn decode_value_internal(
         DataType::ByteArray => Some(DecodedValue::ByteArray(slice.to_vec())),
         DataType::MimeSample => Some(DecodedValue::MimeSample(slice.to_vec())),
         DataType::MimeStream => Some(DecodedValue::MimeStream(slice.to_vec())),
+        DataType::CanOpenDate if slice.len() >= CanOpenDate::BYTES => {
+            Some(DecodedValue::CanOpenDate(CanOpenDate::from_bytes(slice)))
+        }
+        DataType::CanOpenTime if slice.len() >= CanOpenTime::BYTES => {
+            Some(DecodedValue::CanOpenTime(CanOpenTime::from_bytes(slice)))
+        }
         _ => Some(DecodedValue::Unknown),
     }
 }
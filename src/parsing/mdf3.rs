@@ -0,0 +1,478 @@
+//! MDF 3.x read support.
+//!
+//! Files in the 3.x block layout (`HD`/`DG`/`CG`/`CN` blocks with 32-bit
+//! links) are translated on load into an equivalent in-memory MDF 4 image,
+//! which is then parsed as usual. Every channel group becomes its own data
+//! group holding a single `##DT` block with the group's records, so unsorted
+//! 3.x data groups are demultiplexed during the translation.
+//!
+//! Channel names, units, comments, the master channel and the conversion
+//! types that have an MDF 4 counterpart (linear, tabular, polynomial,
+//! rational, exponential, logarithmic, formula and text tables) are carried
+//! over; channel source and dependency blocks are not.
+
+use std::collections::HashSet;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::channel_group_block::ChannelGroupBlock;
+use crate::blocks::common::{BlockHeader, DataType};
+use crate::blocks::conversion::{ConversionBlock, ConversionType};
+use crate::blocks::data_group_block::DataGroupBlock;
+use crate::blocks::header_block::HeaderBlock;
+use crate::blocks::identification_block::IdentificationBlock;
+use crate::blocks::metadata_block::MetadataBlock;
+use crate::blocks::text_block::TextBlock;
+use crate::error::MdfError;
+use crate::metadata::HeaderMetadata;
+
+/// Whether `bytes` start with the identification block of an MDF 3.x file.
+pub fn is_mdf3(bytes: &[u8]) -> bool {
+    bytes.len() >= 64 && &bytes[0..3] == b"MDF" && LittleEndian::read_u16(&bytes[28..30]) < 400
+}
+
+/// Translate a complete MDF 3.x file into an MDF 4.10 image.
+pub fn mdf3_to_mdf4(bytes: &[u8]) -> Result<Vec<u8>, MdfError> {
+    if !is_mdf3(bytes) {
+        return Err(MdfError::FileIdentifierError(latin1(&bytes[..bytes.len().min(8)])));
+    }
+    let version = LittleEndian::read_u16(&bytes[28..30]);
+    let big_endian = LittleEndian::read_u16(&bytes[24..26]) != 0;
+
+    let hd = block(bytes, 64, "HD")?;
+    let mut groups = Vec::new();
+    let mut visited = HashSet::new();
+    let mut dg_addr = link(hd, 4);
+    while dg_addr != 0 {
+        visit(&mut visited, dg_addr)?;
+        let dg = block(bytes, dg_addr, "DG")?;
+        read_data_group(bytes, dg, &mut groups, &mut visited)?;
+        dg_addr = link(dg, 4);
+    }
+
+    let mut image = Image { bytes: vec![0; 64 + 104], big_endian };
+
+    let metadata = HeaderMetadata {
+        comment: text(bytes, link(hd, 8))?,
+        author: fixed_string(hd, 36, 32),
+        department: fixed_string(hd, 68, 32),
+        project: fixed_string(hd, 100, 32),
+        subject: fixed_string(hd, 132, 32),
+        ..Default::default()
+    };
+    let mut header = HeaderBlock {
+        comment_addr: image.push(&MetadataBlock::new(&metadata.to_xml()).to_bytes()?),
+        ..Default::default()
+    };
+    if version >= 320 && hd.len() >= 176 {
+        // Local time with the offset to UTC in hours.
+        let utc_offset = LittleEndian::read_i16(&hd[172..174]);
+        let local = LittleEndian::read_u64(&hd[164..172]) as i64;
+        header.abs_time = local.saturating_sub(utc_offset as i64 * 3_600_000_000_000).max(0) as u64;
+        header.tz_offset = utc_offset.saturating_mul(60);
+        header.time_flags = 0x02;
+    } else if let Some(local) = start_time(hd) {
+        header.abs_time = local;
+        header.time_flags = 0x01;
+    }
+
+    let mut next_dg = 0;
+    for group in groups.iter().rev() {
+        next_dg = image.push_group(bytes, group, next_dg)?;
+    }
+    header.first_dg_addr = next_dg;
+
+    let id = IdentificationBlock::default().to_bytes()?;
+    image.bytes[..64].copy_from_slice(&id);
+    image.bytes[64..168].copy_from_slice(&header.to_bytes()?);
+    Ok(image.bytes)
+}
+
+/// A channel group of the source file with its records demultiplexed.
+struct Group<'a> {
+    cg: &'a [u8],
+    record_size: usize,
+    records: Vec<u8>,
+}
+
+/// Collect the channel groups of the `DG` block `dg` together with their
+/// records.
+fn read_data_group<'a>(
+    bytes: &'a [u8],
+    dg: &'a [u8],
+    groups: &mut Vec<Group<'a>>,
+    visited: &mut HashSet<u64>,
+) -> Result<(), MdfError> {
+    let data_addr = link(dg, 16) as usize;
+    let record_ids = LittleEndian::read_u16(&dg[22..24]) as usize;
+
+    let first = groups.len();
+    let mut cg_addr = link(dg, 8);
+    while cg_addr != 0 {
+        visit(visited, cg_addr)?;
+        let cg = block(bytes, cg_addr, "CG")?;
+        groups.push(Group {
+            cg,
+            record_size: LittleEndian::read_u16(&cg[20..22]) as usize,
+            records: Vec::new(),
+        });
+        cg_addr = link(cg, 4);
+    }
+    let groups = &mut groups[first..];
+    if data_addr == 0 || groups.is_empty() {
+        return Ok(());
+    }
+
+    let cycles = |cg: &[u8]| LittleEndian::read_u32(&cg[22..26]) as usize;
+    if record_ids == 0 {
+        // Sorted: the records of the only channel group follow each other.
+        let group = &mut groups[0];
+        let len = group.record_size.saturating_mul(cycles(group.cg));
+        let end = data_addr.saturating_add(len).min(bytes.len());
+        group.records = bytes.get(data_addr..end).unwrap_or_default().to_vec();
+        let whole = group.records.len() - group.records.len() % group.record_size.max(1);
+        group.records.truncate(whole);
+        return Ok(());
+    }
+
+    // Every record starts with a one byte record ID, and with two record
+    // IDs also ends with a copy of it.
+    let mut remaining: usize = groups.iter().map(|g| cycles(g.cg)).sum();
+    let mut pos = data_addr;
+    while remaining > 0 && pos < bytes.len() {
+        let id = bytes[pos] as u16;
+        let Some(group) = groups.iter_mut().find(|g| LittleEndian::read_u16(&g.cg[16..18]) == id) else {
+            return Err(MdfError::BlockLinkError(format!(
+                "unknown record ID {} at offset {:#x}",
+                id, pos
+            )));
+        };
+        let start = pos + 1;
+        let end = start + group.record_size;
+        if end > bytes.len() {
+            break;
+        }
+        group.records.extend_from_slice(&bytes[start..end]);
+        pos = end + if record_ids == 2 { 1 } else { 0 };
+        remaining -= 1;
+    }
+    Ok(())
+}
+
+/// The MDF 4 image under construction; blocks are appended 8-byte aligned.
+struct Image {
+    bytes: Vec<u8>,
+    /// Byte order of signal values with a "default" data type.
+    big_endian: bool,
+}
+
+impl Image {
+    /// Append a serialized block and return its address.
+    fn push(&mut self, block: &[u8]) -> u64 {
+        self.bytes.resize(self.bytes.len().next_multiple_of(8), 0);
+        let addr = self.bytes.len() as u64;
+        self.bytes.extend_from_slice(block);
+        addr
+    }
+
+    /// Append a `##TX` block, or return 0 for a missing text.
+    fn push_text(&mut self, text: Option<&str>) -> Result<u64, MdfError> {
+        match text {
+            Some(text) => Ok(self.push(&TextBlock::new(text).to_bytes()?)),
+            None => Ok(0),
+        }
+    }
+
+    /// Append the blocks of one channel group, wrapped in its own data
+    /// group, and return the address of the `##DG` block.
+    fn push_group(&mut self, bytes: &[u8], group: &Group<'_>, next_dg: u64) -> Result<u64, MdfError> {
+        let cg = group.cg;
+        let mut cn_addrs = Vec::new();
+        let mut visited = HashSet::new();
+        let mut cn_addr = link(cg, 8);
+        while cn_addr != 0 {
+            visit(&mut visited, cn_addr)?;
+            cn_addrs.push(cn_addr);
+            cn_addr = link(block(bytes, cn_addr, "CN")?, 4);
+        }
+        let mut first_ch = 0;
+        for &addr in cn_addrs.iter().rev() {
+            first_ch = self.push_channel(bytes, block(bytes, addr, "CN")?, first_ch)?;
+        }
+
+        let data_addr = if group.records.is_empty() {
+            0
+        } else {
+            let header = BlockHeader {
+                id: "##DT".to_string(),
+                reserved0: 0,
+                block_len: 24 + group.records.len() as u64,
+                links_nr: 0,
+            };
+            let mut dt = header.to_bytes()?;
+            dt.extend_from_slice(&group.records);
+            self.push(&dt)
+        };
+
+        let cycles = (group.records.len() / group.record_size.max(1)) as u64;
+        let cg_block = ChannelGroupBlock {
+            first_ch_addr: first_ch,
+            comment_addr: self.push_text(text(bytes, link(cg, 12))?.as_deref())?,
+            cycles_nr: cycles,
+            samples_byte_nr: group.record_size as u32,
+            ..Default::default()
+        };
+        let cg_addr = self.push(&cg_block.to_bytes()?);
+
+        let dg_block = DataGroupBlock {
+            next_dg_addr: next_dg,
+            first_cg_addr: cg_addr,
+            data_block_addr: data_addr,
+            ..Default::default()
+        };
+        Ok(self.push(&dg_block.to_bytes()?))
+    }
+
+    /// Append the translation of the `CN` block `cn` and return the address
+    /// of the `##CN` block.
+    fn push_channel(&mut self, bytes: &[u8], cn: &[u8], next_ch: u64) -> Result<u64, MdfError> {
+        let long_name = if cn.len() >= 222 { text(bytes, link(cn, 218))? } else { None };
+        let name = long_name.or_else(|| fixed_string(cn, 26, 32)).unwrap_or_default();
+        let comment = text(bytes, link(cn, 20))?.or_else(|| fixed_string(cn, 58, 128));
+
+        let start_bit = LittleEndian::read_u16(&cn[186..188]) as u32;
+        let extra_bytes = if cn.len() >= 228 { LittleEndian::read_u16(&cn[226..228]) as u32 } else { 0 };
+        let bit_position = start_bit + extra_bytes * 8;
+        let bit_count = LittleEndian::read_u16(&cn[188..190]) as u32;
+        let data_type = data_type(LittleEndian::read_u16(&cn[190..192]), self.big_endian);
+
+        let (conversion_addr, unit) = match link(cn, 8) {
+            0 => (0, None),
+            cc_addr => self.push_conversion(bytes, block(bytes, cc_addr, "CC")?)?,
+        };
+
+        let is_master = LittleEndian::read_u16(&cn[24..26]) == 1;
+        let channel = ChannelBlock {
+            next_ch_addr: next_ch,
+            name_addr: self.push_text(Some(&name))?,
+            conversion_addr,
+            unit_addr: self.push_text(unit.as_deref())?,
+            comment_addr: self.push_text(comment.as_deref())?,
+            channel_type: if is_master { 2 } else { 0 },
+            sync_type: if is_master { 1 } else { 0 },
+            data_type,
+            bit_offset: (bit_position % 8) as u8,
+            byte_offset: bit_position / 8,
+            bit_count,
+            ..Default::default()
+        };
+        Ok(self.push(&channel.to_bytes()?))
+    }
+
+    /// Append the translation of the `CC` block `cc`. Returns the address of
+    /// the `##CC` block (0 for a 1:1 or unsupported conversion) and the
+    /// physical unit.
+    fn push_conversion(&mut self, bytes: &[u8], cc: &[u8]) -> Result<(u64, Option<String>), MdfError> {
+        let unit = fixed_string(cc, 22, 20);
+        let kind = LittleEndian::read_u16(&cc[42..44]);
+        let count = LittleEndian::read_u16(&cc[44..46]) as usize;
+        let param = |i: usize| cc.get(46 + i * 8..54 + i * 8).map_or(0.0, LittleEndian::read_f64);
+        let params = |n: usize| (0..n).map(param).collect::<Vec<_>>();
+
+        let (cc_type, cc_val, cc_ref) = match kind {
+            0 => (ConversionType::Linear, params(2), Vec::new()),
+            1 => (ConversionType::TableLookupInterp, params(2 * count), Vec::new()),
+            2 => (ConversionType::TableLookupNoInterp, params(2 * count), Vec::new()),
+            6 => {
+                let p = params(6);
+                let x = format!("(X - ({}) - ({}))", p[4], p[5]);
+                let formula = format!("(({}) - ({}) * {x}) / (({}) * {x} - ({}))", p[1], p[3], p[2], p[0]);
+                (ConversionType::Algebraic, Vec::new(), vec![self.push_text(Some(&formula))?])
+            }
+            7 | 8 => {
+                let p = params(7);
+                let f = if kind == 7 { "ln" } else { "exp" };
+                let formula = if p[3] == 0.0 {
+                    format!("{f}(((X - ({})) * ({}) - ({})) / ({})) / ({})", p[6], p[5], p[2], p[0], p[1])
+                } else {
+                    format!("{f}((({}) / (X - ({})) - ({})) / ({})) / ({})", p[2], p[6], p[5], p[3], p[4])
+                };
+                (ConversionType::Algebraic, Vec::new(), vec![self.push_text(Some(&formula))?])
+            }
+            9 => (ConversionType::Rational, params(6), Vec::new()),
+            10 => {
+                let formula = fixed_string(cc, 46, 256).unwrap_or_default();
+                (ConversionType::Algebraic, Vec::new(), vec![self.push_text(Some(&formula))?])
+            }
+            11 => {
+                // Entries of a raw value and a 32 character text; no default.
+                let mut values = Vec::with_capacity(count);
+                let mut refs = Vec::with_capacity(count + 1);
+                for i in 0..count {
+                    let entry = 46 + i * 40;
+                    let Some(raw) = cc.get(entry..entry + 8) else { break };
+                    values.push(LittleEndian::read_f64(raw));
+                    refs.push(self.push_text(Some(&fixed_string(cc, entry + 8, 32).unwrap_or_default()))?);
+                }
+                refs.push(0);
+                (ConversionType::ValueToText, values, refs)
+            }
+            12 => {
+                // Entries of a lower and upper raw value and a text link; the
+                // first entry only holds the default text.
+                let mut ranges = Vec::new();
+                let mut refs = Vec::new();
+                let mut default = 0;
+                for i in 0..count {
+                    let entry = 46 + i * 20;
+                    let Some(raw) = cc.get(entry..entry + 20) else { break };
+                    let tx = self.push_text(text(bytes, LittleEndian::read_u32(&raw[16..20]) as u64)?.as_deref())?;
+                    if i == 0 {
+                        default = tx;
+                    } else {
+                        ranges.push(LittleEndian::read_f64(&raw[0..8]));
+                        ranges.push(LittleEndian::read_f64(&raw[8..16]));
+                        refs.push(tx);
+                    }
+                }
+                refs.push(default);
+                (ConversionType::RangeToText, ranges, refs)
+            }
+            // 1:1, date and time conversions and unknown types.
+            _ => return Ok((0, unit)),
+        };
+
+        let block = ConversionBlock {
+            header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+            cc_tx_name: None,
+            cc_md_unit: None,
+            cc_md_comment: None,
+            cc_cc_inverse: None,
+            cc_ref_count: cc_ref.len() as u16,
+            cc_ref,
+            cc_type,
+            cc_precision: 0,
+            cc_flags: 0,
+            cc_val_count: cc_val.len() as u16,
+            cc_phy_range_min: None,
+            cc_phy_range_max: None,
+            cc_val,
+            formula: None,
+            resolved_texts: None,
+            resolved_conversions: None,
+            default_conversion: None,
+        };
+        Ok((self.push(&block.to_bytes()?), unit))
+    }
+}
+
+/// Map an MDF 3 signal data type to its MDF 4 equivalent. The VAX float
+/// formats are exposed as raw bytes.
+fn data_type(kind: u16, big_endian: bool) -> DataType {
+    match (kind, big_endian) {
+        (0, false) | (13, _) => DataType::UnsignedIntegerLE,
+        (0, true) | (9, _) => DataType::UnsignedIntegerBE,
+        (1, false) | (14, _) => DataType::SignedIntegerLE,
+        (1, true) | (10, _) => DataType::SignedIntegerBE,
+        (2 | 3, false) | (15 | 16, _) => DataType::FloatLE,
+        (2 | 3, true) | (11 | 12, _) => DataType::FloatBE,
+        (7, _) => DataType::StringLatin1,
+        _ => DataType::ByteArray,
+    }
+}
+
+/// The 3.x block at `addr` after checking its two character ID and length,
+/// including that it holds at least the fixed fields read from it.
+fn block<'a>(bytes: &'a [u8], addr: u64, id: &str) -> Result<&'a [u8], MdfError> {
+    let start = usize::try_from(addr).unwrap_or(usize::MAX);
+    let header = bytes.get(start..start.saturating_add(4)).ok_or(MdfError::TooShortBuffer {
+        actual: bytes.len(),
+        expected: start.saturating_add(4),
+        file: file!(),
+        line: line!(),
+    })?;
+    if &header[0..2] != id.as_bytes() {
+        return Err(MdfError::BlockIDError {
+            actual: latin1(&header[0..2]),
+            expected: id.to_string(),
+        });
+    }
+    let len = LittleEndian::read_u16(&header[2..4]) as usize;
+    let min_len = match id {
+        "HD" => 164,
+        "DG" => 28,
+        "CG" => 26,
+        "CN" => 218,
+        "CC" => 46,
+        _ => 4,
+    };
+    if len < min_len {
+        return Err(MdfError::BlockSerializationError(format!(
+            "{id} block at {addr:#x} is {len} bytes, expected at least {min_len}"
+        )));
+    }
+    bytes.get(start..start + len).ok_or(MdfError::TooShortBuffer {
+        actual: bytes.len(),
+        expected: start + len,
+        file: file!(),
+        line: line!(),
+    })
+}
+
+/// Record `addr` as visited, failing if it already was: a malformed file
+/// may link its blocks in a loop.
+fn visit(visited: &mut HashSet<u64>, addr: u64) -> Result<(), MdfError> {
+    if visited.insert(addr) {
+        Ok(())
+    } else {
+        Err(MdfError::BlockLinkError(format!("link cycle through the block at {addr:#x}")))
+    }
+}
+
+/// The 32-bit link at `offset` of a block, 0 if the block is too short.
+fn link(block: &[u8], offset: usize) -> u64 {
+    block.get(offset..offset + 4).map_or(0, |b| LittleEndian::read_u32(b) as u64)
+}
+
+/// The text of the `TX` block at `addr`, `None` for a nil link.
+fn text(bytes: &[u8], addr: u64) -> Result<Option<String>, MdfError> {
+    if addr == 0 {
+        return Ok(None);
+    }
+    Ok(Some(latin1(&block(bytes, addr, "TX")?[4..])))
+}
+
+/// A zero-terminated Latin-1 string field of `len` bytes at `offset`,
+/// `None` when empty.
+fn fixed_string(block: &[u8], offset: usize, len: usize) -> Option<String> {
+    let end = (offset + len).min(block.len());
+    let s = latin1(block.get(offset..end)?);
+    (!s.is_empty()).then_some(s)
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    bytes[..end].iter().map(|&b| b as char).collect::<String>().trim_end().to_string()
+}
+
+/// Start of recording from the `DD:MM:YYYY` date and `HH:MM:SS` time fields
+/// of the header, as nanoseconds since 1970 local time.
+fn start_time(hd: &[u8]) -> Option<u64> {
+    let date = fixed_string(hd, 18, 10)?;
+    let time = fixed_string(hd, 28, 8)?;
+    let date: Vec<i64> = date.split(':').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+    let time: Vec<i64> = time.split(':').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+    let (&[day, month, year], &[hour, minute, second]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+    // Days since 1970-01-01 of the proleptic Gregorian calendar.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    u64::try_from(seconds).ok()?.checked_mul(1_000_000_000)
+}
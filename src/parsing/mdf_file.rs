@@ -2,8 +2,13 @@ use crate::error::MdfError;
 use crate::parsing::raw_data_group::RawDataGroup;
 use crate::parsing::raw_channel_group::RawChannelGroup;
 use crate::parsing::raw_channel::RawChannel;
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::Arc;
+
 use crate::blocks::{
-    common::BlockParse,
+    common::{bytes_at, link_target, BlockParse},
     channel_group_block::ChannelGroupBlock,
     data_group_block::DataGroupBlock,
     header_block::HeaderBlock,
@@ -17,6 +22,8 @@ pub struct MdfFile {
     pub data_groups: Vec<RawDataGroup>,
     /// Backing byte store. On native targets this is a memory-mapped file;
     /// on wasm32 (and when using `parse_from_bytes`) it is an owned `Vec<u8>`.
+    /// After [`parse_buffered`](Self::parse_buffered) it holds the metadata
+    /// blocks only, at other offsets than in the file.
     #[cfg(not(target_arch = "wasm32"))]
     pub mmap: memmap2::Mmap,
     #[cfg(target_arch = "wasm32")]
@@ -28,12 +35,18 @@ impl MdfFile {
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`parse_from_bytes`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn parse_from_file(path: &str) -> Result<Self, MdfError> {
+    pub fn parse_from_file(path: impl AsRef<Path>) -> Result<Self, MdfError> {
         use memmap2::Mmap;
         use std::fs::File;
 
         let file = File::open(path)?;
-        let mmap = unsafe { Mmap::map(&file)? };
+        Self::parse_from_mmap(unsafe { Mmap::map(&file)? })
+    }
+
+    /// Parse an MDF file from an existing mapping, e.g. a finalized
+    /// copy-on-write view of an unfinalized file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn parse_from_mmap(mmap: memmap2::Mmap) -> Result<Self, MdfError> {
         Self::parse_from_slice(&mmap[..]).map(|(identification, header, data_groups)| Self {
             identification,
             header,
@@ -42,6 +55,40 @@ impl MdfFile {
         })
     }
 
+    /// Parse an MDF file from a given file path without memory mapping it.
+    ///
+    /// The metadata blocks are read with buffered, seek-based reads of at
+    /// least `buffer_size` bytes into an in-memory image, and the data blocks
+    /// of a data group or VLSD channel from the file when they are first
+    /// decoded; see [`crate::parsing::buffered`]. Block addresses, including
+    /// those in the parsed blocks, refer to the image.
+    ///
+    /// Not available on `wasm32-unknown-unknown`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn parse_buffered(path: impl AsRef<Path>, buffer_size: u64) -> Result<Self, MdfError> {
+        Self::parse_buffered_reader(std::fs::File::open(path)?, buffer_size)
+    }
+
+    /// [`parse_buffered`](Self::parse_buffered) for any seekable source,
+    /// which is kept to read the data blocks from.
+    pub fn parse_buffered_reader<R>(mut reader: R, buffer_size: u64) -> Result<Self, MdfError>
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        use crate::parsing::buffered::{read_image, BlockSource, WindowReader};
+
+        let image = read_image(&mut WindowReader::new(&mut reader, buffer_size))?;
+        let source = Arc::new(BlockSource::new(reader, image.data_blocks));
+        let mut mdf = Self::parse_from_bytes(image.bytes)?;
+        for data_group in &mut mdf.data_groups {
+            data_group.source = Some(Arc::clone(&source));
+            for channel in data_group.channel_groups.iter_mut().flat_map(|cg| &mut cg.raw_channels) {
+                channel.set_block_source(&source);
+            }
+        }
+        Ok(mdf)
+    }
+
     /// Parse an MDF file from an owned byte buffer.
     ///
     /// On native targets the bytes are copied into an anonymous memory mapping
@@ -77,28 +124,38 @@ impl MdfFile {
     fn parse_from_slice(
         data: &[u8],
     ) -> Result<(IdentificationBlock, HeaderBlock, Vec<RawDataGroup>), MdfError> {
-        let identification = IdentificationBlock::from_bytes(&data[0..64])?;
-        let header = HeaderBlock::from_bytes(&data[64..64 + 104])?;
+        let identification = IdentificationBlock::from_bytes(bytes_at(data, 0, 64)?)?;
+        let header = HeaderBlock::from_bytes(bytes_at(data, 64, 104)?)?;
+
+        // Malformed files may link blocks in a loop.
+        let mut visited = HashSet::new();
+        let mut check_cycle = |addr: u64| {
+            if visited.insert(addr) {
+                Ok(())
+            } else {
+                Err(MdfError::BlockLinkError(format!("link cycle through the block at {addr:#x}")))
+            }
+        };
 
         let mut data_groups = Vec::new();
         let mut dg_addr = header.first_dg_addr;
         while dg_addr != 0 {
-            let dg_offset = dg_addr as usize;
-            let data_group_block = DataGroupBlock::from_bytes(&data[dg_offset..])?;
+            check_cycle(dg_addr)?;
+            let data_group_block = DataGroupBlock::from_bytes(link_target(data, dg_addr, "##DG")?)?;
             let next_dg_addr = data_group_block.next_dg_addr;
 
             let mut next_cg_addr = data_group_block.first_cg_addr;
             let mut raw_channel_groups = Vec::new();
             while next_cg_addr != 0 {
-                let offset = next_cg_addr as usize;
-                let mut channel_group_block = ChannelGroupBlock::from_bytes(&data[offset..])?;
+                check_cycle(next_cg_addr)?;
+                let mut channel_group_block = ChannelGroupBlock::from_bytes(link_target(data, next_cg_addr, "##CG")?)?;
                 next_cg_addr = channel_group_block.next_cg_addr;
                 let channels = channel_group_block.read_channels(data)?;
 
-                let raw_channels: Vec<RawChannel> = channels
+                let raw_channels = channels
                     .into_iter()
-                    .map(|channel_block| RawChannel { block: channel_block })
-                    .collect();
+                    .map(|channel_block| RawChannel::with_components(channel_block, data))
+                    .collect::<Result<Vec<_>, _>>()?;
 
                 raw_channel_groups.push(RawChannelGroup {
                     block: channel_group_block,
@@ -108,6 +165,7 @@ impl MdfFile {
             data_groups.push(RawDataGroup {
                 block: data_group_block,
                 channel_groups: raw_channel_groups,
+                source: None,
             });
 
             dg_addr = next_dg_addr;
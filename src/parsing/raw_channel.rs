@@ -1,15 +1,102 @@
 use crate::blocks::channel_block::ChannelBlock;
-use crate::blocks::data_list_block::DataListBlock;
+use crate::blocks::channel_group_block::ChannelGroupBlock;
+use crate::blocks::data_block::DataBlock;
 use crate::blocks::signal_data_block::SignalDataBlock;
-use crate::blocks::common::BlockParse;
+use crate::blocks::common::{link_target, BlockParse};
 use crate::parsing::raw_channel_group::RawChannelGroup;
-use crate::parsing::raw_data_group::RawDataGroup;
+use crate::parsing::buffered::BlockSource;
+use crate::parsing::raw_data_group::{DataBlocks, RawDataGroup};
+use crate::blocks::conversion::SimplifiedConversion;
 use crate::error::MdfError;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
 
 /// A channel with lazy access to its raw record bytes (fixed-length or VLSD).
 #[derive(Debug)]
 pub struct RawChannel {
     pub block:  ChannelBlock,
+    /// Member channels of a structure channel, i.e. the `##CN` chain linked
+    /// from `component_addr`; empty for all other channels.
+    pub components: Vec<RawChannel>,
+    /// The file the VLSD signal data is read from when it is not memory
+    /// mapped; see [`crate::parsing::buffered`].
+    pub(crate) source: Option<Arc<BlockSource>>,
+    /// The conversion with its references resolved and pre-composed, built
+    /// on first use; `None` without a conversion or if it cannot be resolved.
+    pub(crate) simplified: OnceLock<Option<SimplifiedConversion>>,
+}
+
+/// Nesting limit for structure channels, so a malformed file cannot
+/// exhaust the stack.
+const MAX_COMPOSITION_DEPTH: usize = 32;
+
+impl RawChannel {
+    /// Wrap `block`, reading the member channels of its composition (and
+    /// theirs, for nested structures) from `mmap`.
+    ///
+    /// Array (`##CA`) compositions are left to the `Channel` API; only
+    /// `##CN` chains become [`components`](Self::components).
+    pub fn with_components(block: ChannelBlock, mmap: &[u8]) -> Result<Self, MdfError> {
+        Self::read_composition(block, mmap, &mut HashSet::new(), 0)
+    }
+
+    fn read_composition(
+        block: ChannelBlock,
+        mmap: &[u8],
+        visited: &mut HashSet<u64>,
+        depth: usize,
+    ) -> Result<Self, MdfError> {
+        let mut components = Vec::new();
+        let first = block.component_addr;
+        if first != 0 && link_target(mmap, first, "component")?.starts_with(b"##CN") {
+            if depth == MAX_COMPOSITION_DEPTH {
+                return Err(MdfError::BlockLinkError(format!(
+                    "structure channels nested deeper than {MAX_COMPOSITION_DEPTH} levels"
+                )));
+            }
+            let mut addr = first;
+            while addr != 0 {
+                if !visited.insert(addr) {
+                    return Err(MdfError::BlockLinkError(format!(
+                        "link cycle through the component channel at {addr:#x}"
+                    )));
+                }
+                let mut member = ChannelBlock::from_bytes(link_target(mmap, addr, "##CN")?)?;
+                member.resolve_conversion(mmap)?;
+                addr = member.next_ch_addr;
+                components.push(Self::read_composition(member, mmap, visited, depth + 1)?);
+            }
+        }
+        Ok(RawChannel { block, components, source: None, simplified: Default::default() })
+    }
+
+    /// The simplified conversion of the channel, built once from `mmap`.
+    ///
+    /// `None` for channels without a conversion or whose conversion
+    /// references cannot be resolved; those are converted through
+    /// [`ChannelBlock::apply_conversion_value`].
+    pub(crate) fn simplified_conversion(&self, mmap: &[u8]) -> Option<&SimplifiedConversion> {
+        self.simplified
+            .get_or_init(|| {
+                let mut conversion = self.block.conversion.clone()?;
+                conversion
+                    .resolve_all_dependencies_with_address(mmap, self.block.conversion_addr)
+                    .ok()?;
+                Some(conversion.simplify())
+            })
+            .as_ref()
+    }
+
+    /// Read the signal data of this channel and its components from the
+    /// file of `source`; see [`crate::parsing::buffered`].
+    pub(crate) fn set_block_source(&mut self, source: &Arc<BlockSource>) {
+        self.source = Some(Arc::clone(source));
+        for component in &mut self.components {
+            component.set_block_source(source);
+        }
+    }
 }
 
 impl<'a> RawChannel {
@@ -17,7 +104,8 @@ impl<'a> RawChannel {
     /// Return an iterator over raw record bytes for this channel.
     ///
     /// The iterator yields a `Result` for each record and transparently handles
-    /// both fixed-size and VLSD storage schemes.
+    /// both fixed-size and VLSD storage schemes. Data blocks are read one at
+    /// a time as it advances; see [`Records`].
     ///
     /// # Arguments
     /// * `data_group` - Parent data group owning the records
@@ -25,142 +113,135 @@ impl<'a> RawChannel {
     /// * `mmap` - Memory mapped MDF data
     ///
     /// # Returns
-    /// An iterator over byte slices containing each raw record, or an
-    /// [`MdfError`] if the underlying blocks could not be parsed.
+    /// An iterator over each raw record, or an [`MdfError`] if the
+    /// underlying blocks could not be parsed.
     pub fn records(
-        &self,
+        &'a self,
         data_group: &'a RawDataGroup,
         channel_group: &'a RawChannelGroup,
         mmap: &'a [u8],
-    ) -> Result<Box<dyn Iterator<Item = Result<&'a [u8], MdfError>> + 'a>, MdfError> {
-        // 1) VLSD path: channel has its own data pointer => SD/DL chain
+    ) -> Result<Records<'a>, MdfError> {
+        // VLSD path: channel has its own data pointer => SD/DL chain
         if self.block.channel_type == 1 && self.block.data != 0 {
-            // Capture the file bytes and channel pointer
-            let bytes = mmap;
-            let mut next_addr = self.block.data;
-            let mut data_links = Vec::new();
-            let mut link_idx = 0;
-            let mut current_sdb: Option<SignalDataBlock> = None;
-            let mut sdb_pos = 0;
-
-            // Build a from_fn iterator carrying that mutable state
-            let vlsd_iter = std::iter::from_fn(move || -> Option<Result<&'a [u8], MdfError>> {
-                loop {
-                    // 1) Yield from an open SDBLOCK if any
-                    if let Some(sdb) = &current_sdb {
-                        let buf = sdb.data;
-                        if sdb_pos + 4 <= buf.len() {
-                            let len = u32::from_le_bytes(
-                                buf[sdb_pos..sdb_pos+4].try_into().unwrap()
-                            ) as usize;
-                            let start = sdb_pos + 4;
-                            let end = start + len;
-                            if end > buf.len() {
-                                return Some(Err(MdfError::TooShortBuffer {
-                                    actual:   buf.len(),
-                                    expected: end,
-                                    file:     file!(),
-                                    line:     line!(),
-                                }));
-                            }
-                            let slice = &buf[start..end];
-                            sdb_pos = end;
-                            return Some(Ok(slice));
-                        }
-                        // exhausted
-                        current_sdb = None;
-                    }
-
-                    // 2) Next link in current DL batch?
-                    if link_idx < data_links.len() {
-                        let frag_addr = data_links[link_idx];
-                        link_idx += 1;
-                        let off = frag_addr as usize;
-                        match SignalDataBlock::from_bytes(&bytes[off..]) {
-                            Ok(sdb) => {
-                                // Prepare to yield from it on the next loop
-                                current_sdb = Some(sdb);
-                                sdb_pos = 0;
-                                continue;
-                            }
-                            Err(e) => return Some(Err(e.into())),
-                        }
-                    }
-
-                    // 3) If we have a next_addr, peek its ID to decide what it is
-                    if next_addr != 0 {
-                        let off = next_addr as usize;
-                        // read the 4-byte ID
-                        let id = &bytes[off..off+4];
-                        match id {
-                            b"##DL" => {
-                                // Data List Block
-                                match DataListBlock::from_bytes(&bytes[off..]) {
-                                    Ok(dl) => {
-                                        data_links = dl.data_links.clone();
-                                        link_idx = 0;
-                                        next_addr = dl.next;
-                                        continue;  // back to loop start
-                                    }
-                                    Err(e) => return Some(Err(e)),
-                                }
-                            }
-                            b"##SD" => {
-                                // Direct Signal Data Block
-                                match SignalDataBlock::from_bytes(&bytes[off..]) {
-                                    Ok(sdb) => {
-                                        current_sdb = Some(sdb);
-                                        sdb_pos = 0;
-                                        next_addr = 0; // no list chain
-                                        continue;
-                                    }
-                                    Err(e) => return Some(Err(e)),
-                                }
-                            }
-                            other => {
-                                // unexpected block type
-                                return Some(Err(MdfError::BlockIDError {
-                                    actual:   String::from_utf8_lossy(other).into(),
-                                    expected: "##DL or ##SD".to_string(),
-                                }));
-                            }
-                        }
-                    }
-
-                    // 4) Done
-                    return None;
-                }
-            });
-
-            return Ok(Box::new(vlsd_iter));
+            let addr = self.block.data;
+            let head = link_target(mmap, addr, "signal data")?;
+            if head.starts_with(b"##CG") {
+                // VLSD channel group: its records, split out of the unsorted
+                // data group, hold the entries
+                let record_id = ChannelGroupBlock::from_bytes(head)?.record_id;
+                return Ok(Records::new(Box::new(data_group.record_id_blocks(record_id, mmap)?), None));
+            }
+            let blocks = DataBlocks::new(mmap, self.source.as_deref(), addr, SignalDataBlock::ID);
+            return Ok(Records::new(Box::new(blocks), None));
         }
 
-        // Compute the size of each record:
         // Record structure: record_id + data_bytes + invalidation_bytes
         let record_id_len       = data_group.block.record_id_len as usize;
         let sample_byte_len     = channel_group.block.samples_byte_nr as usize;
         let invalidation_bytes  = channel_group.block.invalidation_bytes_nr as usize;
         let record_size         = record_id_len + sample_byte_len + invalidation_bytes;
+        if record_size == 0 {
+            return Ok(Records::new(Box::new(std::iter::empty()), Some(record_size)));
+        }
+        let blocks = data_group.channel_group_blocks(channel_group, mmap)?;
+        Ok(Records::new(Box::new(blocks), Some(record_size)))
+    }
+}
+
+/// Iterator over the raw records of one channel; see [`RawChannel::records`].
+///
+/// Data blocks are loaded one at a time and dropped once their records have
+/// been handed out. Items borrow from the file where a block is mapped as it
+/// is, and are copied out of blocks that had to be inflated or read; use
+/// [`next_record`](Self::next_record) to borrow every record instead.
+/// Fixed-length records never span data blocks: a partial record at the
+/// end of a block is skipped.
+pub struct Records<'a> {
+    blocks: Box<dyn Iterator<Item = Result<DataBlock<'a>, MdfError>> + 'a>,
+    block: Option<DataBlock<'a>>,
+    pos: usize,
+    /// Bytes per record, or `None` for `[u32 length][bytes]` signal data
+    /// entries.
+    record_size: Option<usize>,
+}
 
-        // Gather all DataBlock fragments (DT, DV or DZ):
-        let blocks = data_group.data_blocks(mmap)?;
-
-        // Build a single iterator that:
-        //  - goes block by block
-        //  - trims any partial record at the end of each block
-        //  - yields & [u8] of length `record_size`
-        let iter = blocks.into_iter().flat_map(move |data_block| {
-            // For DZBLOCK you already unzipped into DataBlock, so here data_block.data
-            let raw = data_block.data;
-            let valid_len = (raw.len() / record_size) * record_size;
-            // `chunks_exact` returns an iterator of &[u8] each exactly record_size
-            raw[..valid_len].chunks_exact(record_size)
-                // wrap each slice in Ok(...) so the overall Iterator<Item=Result<_,_>>
-                .map(Ok)
-                // If you wanted to handle an unexpected remainder, you could check raw.len() % record_size != 0 here.
-        });
-
-        Ok(Box::new(iter))
+impl<'a> Records<'a> {
+    fn new(
+        blocks: Box<dyn Iterator<Item = Result<DataBlock<'a>, MdfError>> + 'a>,
+        record_size: Option<usize>,
+    ) -> Self {
+        Records { blocks, block: None, pos: 0, record_size }
+    }
+
+    /// The next record, borrowed from the current data block until the
+    /// next call.
+    pub fn next_record(&mut self) -> Option<Result<&[u8], MdfError>> {
+        let range = match self.advance()? {
+            Ok(range) => range,
+            Err(e) => return Some(Err(e)),
+        };
+        self.block.as_ref().map(|block| Ok(&block.data[range]))
+    }
+
+    /// Move to the next record, loading data blocks as needed, and return
+    /// its byte range in the current block.
+    fn advance(&mut self) -> Option<Result<Range<usize>, MdfError>> {
+        loop {
+            if let Some(range) = self.next_in_block() {
+                return Some(range);
+            }
+            match self.blocks.next()? {
+                Ok(block) => {
+                    self.block = Some(block);
+                    self.pos = 0;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// The byte range of the next record in the current block, if it holds
+    /// another one.
+    fn next_in_block(&mut self) -> Option<Result<Range<usize>, MdfError>> {
+        let data = &self.block.as_ref()?.data;
+        let start = self.pos;
+        let range = match self.record_size {
+            Some(size) => start..start + size,
+            None => {
+                let len = u32::from_le_bytes(data.get(start..start + 4)?.try_into().unwrap()) as usize;
+                start + 4..start + 4 + len
+            }
+        };
+        if range.end > data.len() {
+            if self.record_size.is_some() {
+                return None;
+            }
+            let err = MdfError::TooShortBuffer {
+                actual:   data.len(),
+                expected: range.end,
+                file:     file!(),
+                line:     line!(),
+            };
+            self.block = None;
+            return Some(Err(err));
+        }
+        self.pos = range.end;
+        Some(Ok(range))
     }
 }
 
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Cow<'a, [u8]>, MdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = match self.advance()? {
+            Ok(range) => range,
+            Err(e) => return Some(Err(e)),
+        };
+        let block = self.block.as_ref()?;
+        Some(Ok(match block.data {
+            Cow::Borrowed(data) => Cow::Borrowed(&data[range]),
+            Cow::Owned(ref data) => Cow::Owned(data[range].to_vec()),
+        }))
+    }
+}
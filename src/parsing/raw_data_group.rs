@@ -1,10 +1,18 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use crate::error::MdfError;
+use crate::parsing::buffered::BlockSource;
 use crate::parsing::raw_channel_group::RawChannelGroup;
 use crate::blocks::{
+    channel_group_block::CG_FLAG_VLSD,
     data_block::DataBlock,
     data_group_block::DataGroupBlock,
     data_list_block::DataListBlock,
-    common::BlockHeader,
+    data_zipped_block::DataZippedBlock,
+    signal_data_block::SignalDataBlock,
+    common::{link_target, BlockHeader},
     common::BlockParse,
 };
 
@@ -12,66 +20,390 @@ use crate::blocks::{
 pub struct RawDataGroup {
     pub block: DataGroupBlock,
     pub channel_groups: Vec<RawChannelGroup>,
+    /// The file the data blocks are read from when it is not memory mapped;
+    /// see [`crate::parsing::buffered`].
+    pub(crate) source: Option<Arc<BlockSource>>,
+}
+
+/// Iterator over the data blocks of a data chain, i.e. a single data block
+/// or a `##DL` list of them, in file order.
+///
+/// Blocks are loaded one at a time as the iterator advances: borrowed from
+/// the mapping, inflated if they are `##DZ` blocks, or read from the file of
+/// a [`BlockSource`]. Nothing is kept once a block has been handed out.
+pub struct DataBlocks<'a> {
+    mmap: &'a [u8],
+    source: Option<&'a BlockSource>,
+    /// `##DT` for a chain of record data, `##SD` for one of signal data.
+    id: &'static str,
+    /// The next single block or `##DL` block of the chain; 0 at the end.
+    next: u64,
+    /// Blocks of the current `##DL` block still to be loaded.
+    fragments: std::vec::IntoIter<u64>,
+    visited: HashSet<u64>,
+}
+
+impl<'a> DataBlocks<'a> {
+    /// Walk the chain starting at `addr`, whose data blocks are `##DT`
+    /// (record data) or `##SD` (signal data) blocks as given by `id`.
+    pub(crate) fn new(mmap: &'a [u8], source: Option<&'a BlockSource>, addr: u64, id: &'static str) -> Self {
+        DataBlocks { mmap, source, id, next: addr, fragments: Vec::new().into_iter(), visited: HashSet::new() }
+    }
+
+    /// Load the data block at `addr`.
+    fn load(&self, addr: u64) -> Result<DataBlock<'a>, MdfError> {
+        let block = link_target(self.mmap, addr, "data")?;
+        if let Some(data) = self.source.and_then(|source| source.data_section(addr)) {
+            return Ok(DataBlock { header: BlockHeader::from_bytes(block)?, data: Cow::Owned(data?) });
+        }
+        if block.starts_with(b"##DZ") {
+            let dz = DataZippedBlock::from_bytes(block)?;
+            return Ok(DataBlock { data: Cow::Owned(dz.decompress()?), header: dz.header });
+        }
+        if self.id == SignalDataBlock::ID {
+            let sd = SignalDataBlock::from_bytes(block)?;
+            return Ok(DataBlock { header: sd.header, data: Cow::Borrowed(sd.data) });
+        }
+        DataBlock::from_bytes(block)
+    }
+
+    /// Error for a chain whose links revisit the block at `addr`.
+    fn loop_error(&self, addr: u64) -> MdfError {
+        let chain = if self.id == SignalDataBlock::ID { "signal data chain" } else { "##DL chain" };
+        MdfError::BlockLinkError(format!("{chain} loops back to {addr:#x}"))
+    }
+}
+
+impl<'a> Iterator for DataBlocks<'a> {
+    type Item = Result<DataBlock<'a>, MdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(addr) = self.fragments.next() {
+                return Some(self.load(addr));
+            }
+            let addr = std::mem::take(&mut self.next);
+            if addr == 0 {
+                return None;
+            }
+            if !self.visited.insert(addr) {
+                return Some(Err(self.loop_error(addr)));
+            }
+            let block = match link_target(self.mmap, addr, "data") {
+                Ok(block) => block,
+                Err(e) => return Some(Err(e)),
+            };
+            match (block.get(..4).unwrap_or(block), self.id) {
+                (b"##DL", _) => match DataListBlock::from_bytes(block) {
+                    Ok(list) => {
+                        self.fragments = list.data_links.into_iter();
+                        self.next = list.next;
+                    }
+                    Err(e) => return Some(Err(e)),
+                },
+                (b"##DT" | b"##DV" | b"##DZ", "##DT") | (b"##SD" | b"##DZ", "##SD") => return Some(self.load(addr)),
+                (other, id) => {
+                    return Some(Err(MdfError::BlockIDError {
+                        actual: String::from_utf8_lossy(other).into(),
+                        expected: if id == "##SD" { "##DL, ##SD, ##DZ or ##CG" } else { "##DT / ##DV / ##DL / ##DZ" }
+                            .to_string(),
+                    }));
+                }
+            }
+        }
+    }
+}
+
+/// Cursor over the bytes of a data chain as one stream, reading the
+/// underlying [`DataBlocks`] one at a time.
+///
+/// Records may straddle data block boundaries; [`take`](Self::take) hands
+/// out a slice of the current block where it can, and assembles the bytes of
+/// a straddling record in a scratch buffer otherwise. At most the current
+/// block and one record are held.
+pub(crate) struct RecordStream<'a> {
+    blocks: DataBlocks<'a>,
+    block: Option<DataBlock<'a>>,
+    /// Position in the data section of `block`.
+    pos: usize,
+    scratch: Vec<u8>,
+    /// Bytes of the stream consumed so far.
+    offset: usize,
+    blocks_read: usize,
+}
+
+impl<'a> RecordStream<'a> {
+    pub(crate) fn new(blocks: DataBlocks<'a>) -> Self {
+        RecordStream { blocks, block: None, pos: 0, scratch: Vec::new(), offset: 0, blocks_read: 0 }
+    }
+
+    /// Bytes of the stream consumed so far. Once [`take`](Self::take) ran
+    /// out of data this is the length of the stream.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Number of data blocks loaded so far.
+    pub(crate) fn blocks_read(&self) -> usize {
+        self.blocks_read
+    }
+
+    /// `true` if the next byte of the stream starts a new data block.
+    pub(crate) fn at_block_end(&self) -> bool {
+        self.block.as_ref().is_none_or(|block| self.pos == block.data.len())
+    }
+
+    /// The next `n` bytes of the stream, or `None` (consuming the rest of
+    /// the stream) if fewer than `n` are left.
+    pub(crate) fn take(&mut self, n: usize) -> Result<Option<&[u8]>, MdfError> {
+        if n == 0 {
+            return Ok(Some(&[]));
+        }
+        while self.at_block_end() {
+            match self.blocks.next() {
+                Some(block) => self.load(block?),
+                None => return Ok(None),
+            }
+        }
+        let start = self.pos;
+        let data = &self.block.as_ref().expect("a block with data left is loaded").data;
+        if data.len() - start >= n {
+            self.pos += n;
+            self.offset += n;
+            let data = &self.block.as_ref().expect("a block with data left is loaded").data;
+            return Ok(Some(&data[start..start + n]));
+        }
+        self.scratch.clear();
+        loop {
+            if let Some(block) = &self.block {
+                let rest = &block.data[self.pos..];
+                let count = rest.len().min(n - self.scratch.len());
+                self.scratch.extend_from_slice(&rest[..count]);
+                self.pos += count;
+                self.offset += count;
+            }
+            if self.scratch.len() == n {
+                return Ok(Some(&self.scratch));
+            }
+            match self.blocks.next() {
+                Some(block) => self.load(block?),
+                None => {
+                    self.block = None;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn load(&mut self, block: DataBlock<'a>) {
+        self.block = Some(block);
+        self.pos = 0;
+        self.blocks_read += 1;
+    }
+}
+
+/// Iterator over the records of one channel group of an unsorted data
+/// group; see [`RawDataGroup::channel_group_blocks`].
+///
+/// The record stream is demultiplexed as it is read: each yielded block
+/// holds the group's records found in (about) one data block of the chain,
+/// so memory use does not grow with the size of the data group.
+pub struct UnsortedBlocks<'a> {
+    stream: RecordStream<'a>,
+    channel_groups: &'a [RawChannelGroup],
+    record_id: u64,
+    record_id_len: usize,
+    /// Data blocks loaded when the last block was yielded.
+    blocks_read: usize,
+    done: bool,
+}
+
+impl<'a> UnsortedBlocks<'a> {
+    /// Copy the next record of the stream to `out` if it has the wanted
+    /// record ID. `false` at the end of the stream.
+    fn next_record(&mut self, out: &mut Vec<u8>) -> Result<bool, MdfError> {
+        let pos = self.stream.offset();
+        let Some(id) = self.stream.take(self.record_id_len)? else {
+            return Ok(false);
+        };
+        let mut id_bytes = [0u8; 8];
+        id_bytes[..id.len()].copy_from_slice(id);
+        let record_id = u64::from_le_bytes(id_bytes);
+        let group = self
+            .channel_groups
+            .iter()
+            .find(|cg| cg.block.record_id == record_id)
+            .ok_or_else(|| MdfError::BlockLinkError(format!(
+                "record ID {} at data offset {} matches no channel group",
+                record_id, pos
+            )))?;
+        let wanted = record_id == self.record_id;
+        if group.block.flags & CG_FLAG_VLSD != 0 {
+            // Stored as `[u32 length][bytes]`, like a `##SD` data section.
+            let start = self.stream.offset();
+            let Some(len_bytes) = self.stream.take(4)? else {
+                return Err(MdfError::TooShortBuffer {
+                    actual:   self.stream.offset(),
+                    expected: start + 4,
+                    file:     file!(),
+                    line:     line!(),
+                });
+            };
+            let len_bytes: [u8; 4] = len_bytes.try_into().unwrap();
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            let Some(data) = self.stream.take(len)? else {
+                return Err(MdfError::TooShortBuffer {
+                    actual:   self.stream.offset(),
+                    expected: start + 4 + len,
+                    file:     file!(),
+                    line:     line!(),
+                });
+            };
+            if wanted {
+                out.extend_from_slice(&len_bytes);
+                out.extend_from_slice(data);
+            }
+        } else {
+            let size = group.block.samples_byte_nr as usize + group.block.invalidation_bytes_nr as usize;
+            let Some(data) = self.stream.take(size)? else {
+                // Trailing partial record, as tolerated for sorted groups.
+                return Ok(false);
+            };
+            if wanted {
+                out.extend_from_slice(&id_bytes[..self.record_id_len]);
+                out.extend_from_slice(data);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a> Iterator for UnsortedBlocks<'a> {
+    type Item = Result<DataBlock<'a>, MdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut out = Vec::new();
+        while !self.done {
+            if !out.is_empty()
+                && (self.stream.at_block_end() || self.stream.blocks_read() != self.blocks_read)
+            {
+                self.blocks_read = self.stream.blocks_read();
+                return Some(Ok(records_block(out)));
+            }
+            match self.next_record(&mut out) {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        (!out.is_empty()).then(|| Ok(records_block(out)))
+    }
+}
+
+/// A `##DT` block holding `data`.
+fn records_block<'a>(data: Vec<u8>) -> DataBlock<'a> {
+    DataBlock {
+        header: BlockHeader {
+            id: String::from("##DT"),
+            reserved0: 0,
+            block_len: 24 + data.len() as u64,
+            links_nr: 0,
+        },
+        data: Cow::Owned(data),
+    }
+}
+
+/// Iterator over the data blocks holding the records of one channel group;
+/// see [`RawDataGroup::channel_group_blocks`].
+pub enum ChannelGroupBlocks<'a> {
+    /// The data blocks of a sorted data group.
+    Sorted(DataBlocks<'a>),
+    /// The records of an unsorted data group demultiplexed for the group.
+    Unsorted(UnsortedBlocks<'a>),
 }
+
+impl<'a> Iterator for ChannelGroupBlocks<'a> {
+    type Item = Result<DataBlock<'a>, MdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ChannelGroupBlocks::Sorted(blocks) => blocks.next(),
+            ChannelGroupBlocks::Unsorted(blocks) => blocks.next(),
+        }
+    }
+}
+
 impl RawDataGroup {
 
-    /// Collect all data blocks referenced by this data group.
+    /// Iterate over the data blocks of this data group.
     ///
-    /// The returned vector contains the `DT` or `DV` blocks in the order they
-    /// appear on disk, transparently following any `DL` list chains.
+    /// The blocks are the `DT` or `DV` blocks in the order they appear on
+    /// disk, transparently following any `DL` list chains. `DZ` blocks are
+    /// yielded with their inflated data section. Each block is loaded only
+    /// when the iterator reaches it.
     ///
     /// # Arguments
     /// * `mmap` - Memory mapped file containing the MDF data
     ///
     /// # Returns
-    /// A vector of [`DataBlock`] objects or an [`MdfError`] if parsing fails.
-    pub fn data_blocks<'a>(
-        &self,
-        mmap: &'a [u8],
-    ) -> Result<Vec<DataBlock<'a>>, MdfError> {
-        let mut collected_blocks = Vec::new();
-
-        // Start at the group’s primary data pointer
-        let mut current_block_address = self.block.data_block_addr;
-        while current_block_address != 0 {
-            let byte_offset = current_block_address as usize;
-
-            // Read the block header
-            let block_header = BlockHeader::from_bytes(&mmap[byte_offset..byte_offset + 24])?;
-
-            match block_header.id.as_str() {
-                "##DT" | "##DV" => {
-                    // Single contiguous DataBlock
-                    let data_block = DataBlock::from_bytes(&mmap[byte_offset..])?;
-                    collected_blocks.push(data_block);
-                    // No list to follow, we’re done
-                    current_block_address = 0;
-                }
-                "##DL" => {
-                    // Fragmented list of data blocks
-                    let data_list_block = DataListBlock::from_bytes(&mmap[byte_offset..])?;
-
-                    // Parse each fragment in this list
-                    for &fragment_address in &data_list_block.data_links {
-                        let fragment_offset = fragment_address as usize;
-                        let fragment_block = DataBlock::from_bytes(&mmap[fragment_offset..])?;
-
-                        collected_blocks.push(fragment_block);
-                    }
+    /// An iterator over the [`DataBlock`]s, yielding an [`MdfError`] for a
+    /// block that cannot be read.
+    pub fn data_blocks<'a>(&'a self, mmap: &'a [u8]) -> DataBlocks<'a> {
+        DataBlocks::new(mmap, self.source.as_deref(), self.block.data_block_addr, DataBlock::ID)
+    }
 
-                    // Move to the next DLBLOCK in the chain (0 = end)
-                    current_block_address = data_list_block.next;
-                }
+    /// `true` if the data group holds a single channel group, i.e. its data
+    /// blocks contain that group's records only.
+    ///
+    /// Unsorted groups interleave the records of several channel groups,
+    /// told apart by the record ID at the start of each record. Without
+    /// record IDs there is nothing to demultiplex by, so such a data group
+    /// is read as sorted.
+    pub fn is_sorted(&self) -> bool {
+        self.channel_groups.len() <= 1 || self.block.record_id_len == 0
+    }
 
-                unexpected_id => {
-                    return Err(MdfError::BlockIDError {
-                        actual: unexpected_id.to_string(),
-                        expected: "##DT / ##DV / ##DL".to_string(),
-                    });
-                }
-            }
+    /// Iterate over the data blocks holding the records of `channel_group`.
+    ///
+    /// For sorted groups this is [`data_blocks`](Self::data_blocks). For
+    /// unsorted groups the record stream is demultiplexed by record ID while
+    /// it is read, nothing is kept between calls; the blocks hold
+    /// `channel_group`'s records, each still starting with its record ID.
+    pub fn channel_group_blocks<'a>(
+        &'a self,
+        channel_group: &RawChannelGroup,
+        mmap: &'a [u8],
+    ) -> Result<ChannelGroupBlocks<'a>, MdfError> {
+        if self.is_sorted() {
+            return Ok(ChannelGroupBlocks::Sorted(self.data_blocks(mmap)));
         }
+        self.record_id_blocks(channel_group.block.record_id, mmap)
+    }
 
-        Ok(collected_blocks)
+    /// The records with ID `record_id` of this (unsorted) data group, as
+    /// [`channel_group_blocks`](Self::channel_group_blocks) yields them.
+    pub(crate) fn record_id_blocks<'a>(
+        &'a self,
+        record_id: u64,
+        mmap: &'a [u8],
+    ) -> Result<ChannelGroupBlocks<'a>, MdfError> {
+        let record_id_len = self.block.record_id_len as usize;
+        if !matches!(record_id_len, 1 | 2 | 4 | 8) {
+            return Err(MdfError::BlockSerializationError(format!(
+                "unsorted data group needs a record ID length of 1, 2, 4 or 8 bytes, found {}",
+                record_id_len
+            )));
+        }
+        Ok(ChannelGroupBlocks::Unsorted(UnsortedBlocks {
+            stream: RecordStream::new(self.data_blocks(mmap)),
+            channel_groups: &self.channel_groups,
+            record_id,
+            record_id_len,
+            blocks_read: 0,
+            done: false,
+        }))
     }
-}
\ No newline at end of file
+}
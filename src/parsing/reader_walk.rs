@@ -7,6 +7,8 @@
 //! foundation for building an [`crate::index::MdfIndex`] from a remote source
 //! such as an HTTP URL or S3 object without downloading the whole file.
 
+use std::collections::HashSet;
+
 use crate::blocks::channel_block::ChannelBlock;
 use crate::blocks::channel_group_block::ChannelGroupBlock;
 use crate::blocks::common::{read_string_block_via_reader, BlockHeader, BlockParse};
@@ -21,11 +23,14 @@ pub(crate) struct WalkedChannel {
     pub block: ChannelBlock,
     pub name: Option<String>,
     pub unit: Option<String>,
+    pub comment: Option<String>,
     pub conversion: Option<ConversionBlock>,
 }
 
 pub(crate) struct WalkedGroup {
     pub record_id_len: u8,
+    /// Number of channel groups sharing this group's data group.
+    pub dg_channel_groups: usize,
     pub data_block_addr: u64,
     pub cg: ChannelGroupBlock,
     pub cg_name: Option<String>,
@@ -57,15 +62,28 @@ where
     let hd_bytes = reader.read_range(ID_BLOCK_LEN, HD_BLOCK_LEN)?;
     let header = HeaderBlock::from_bytes(&hd_bytes)?;
 
+    // Malformed files may link blocks in a loop.
+    let mut visited = HashSet::new();
+    let mut check_cycle = |addr: u64| {
+        if visited.insert(addr) {
+            Ok(())
+        } else {
+            Err(MdfError::BlockLinkError(format!("link cycle through the block at {addr:#x}")))
+        }
+    };
+
     let mut groups = Vec::new();
     let mut dg_addr = header.first_dg_addr;
     while dg_addr != 0 {
+        check_cycle(dg_addr)?;
         let dg_bytes = reader.read_range(dg_addr, DG_BLOCK_LEN)?;
         let dg = DataGroupBlock::from_bytes(&dg_bytes)?;
         let next_dg_addr = dg.next_dg_addr;
         let mut cg_addr = dg.first_cg_addr;
+        let dg_start = groups.len();
 
         while cg_addr != 0 {
+            check_cycle(cg_addr)?;
             let cg_bytes = reader.read_range(cg_addr, CG_BLOCK_LEN)?;
             let cg = ChannelGroupBlock::from_bytes(&cg_bytes)?;
             let next_cg_addr = cg.next_cg_addr;
@@ -76,12 +94,14 @@ where
             let mut channels = Vec::new();
             let mut ch_addr = cg.first_ch_addr;
             while ch_addr != 0 {
+                check_cycle(ch_addr)?;
                 let cn_bytes = reader.read_range(ch_addr, CN_BLOCK_LEN)?;
                 let cn = ChannelBlock::from_bytes(&cn_bytes)?;
                 let next_ch_addr = cn.next_ch_addr;
 
                 let name = read_string_block_via_reader(reader, cn.name_addr)?;
                 let unit = read_string_block_via_reader(reader, cn.unit_addr)?;
+                let comment = read_string_block_via_reader(reader, cn.comment_addr)?;
 
                 let conversion = if cn.conversion_addr != 0 {
                     let cc_header_bytes = reader.read_range(cn.conversion_addr, 24)?;
@@ -106,6 +126,7 @@ where
                     block: cn,
                     name,
                     unit,
+                    comment,
                     conversion,
                 });
 
@@ -114,6 +135,7 @@ where
 
             groups.push(WalkedGroup {
                 record_id_len: dg.record_id_len,
+                dg_channel_groups: 0,
                 data_block_addr: dg.data_block_addr,
                 cg,
                 cg_name,
@@ -124,6 +146,11 @@ where
             cg_addr = next_cg_addr;
         }
 
+        let dg_channel_groups = groups.len() - dg_start;
+        for group in &mut groups[dg_start..] {
+            group.dg_channel_groups = dg_channel_groups;
+        }
+
         dg_addr = next_dg_addr;
     }
 
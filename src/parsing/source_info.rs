@@ -3,7 +3,10 @@ use crate::blocks::source_block::{read_source_block, SourceBlock};
 use crate::error::MdfError;
 
 /// Ergonomic view of an SIBLOCK: human‐readable source name, path, comment.
-#[derive(Debug)]
+///
+/// The same struct describes a source to write, e.g. through
+/// [`ChannelGroupBlock::acq_source`](crate::blocks::channel_group_block::ChannelGroupBlock::acq_source).
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SourceInfo {
     /// The “source name” (si_tx_name)
     pub name:    Option<String>,
@@ -11,6 +14,11 @@ pub struct SourceInfo {
     pub path:    Option<String>,
     /// Any extended comment/XML (si_md_comment)
     pub comment: Option<String>,
+    /// Source type (si_type: 0=OTHER, 1=ECU, 2=BUS, 3=I/O, 4=TOOL, 5=USER)
+    pub source_type: u8,
+    /// Bus type (si_bus_type: 0=NONE, 1=OTHER, 2=CAN, 3=LIN, 4=MOST,
+    /// 5=FLEXRAY, 6=K_LINE, 7=ETHERNET, 8=USB)
+    pub bus_type: u8,
 }
 
 impl SourceInfo {
@@ -34,6 +42,12 @@ impl SourceInfo {
         let name: Option<String>    = read_string_block(mmap, sb.name_addr)?;
         let path: Option<String>    = read_string_block(mmap, sb.path_addr)?;
         let comment: Option<String> = read_string_block(mmap, sb.comment_addr)?;
-        Ok(Some(SourceInfo { name, path, comment }))
+        Ok(Some(SourceInfo {
+            name,
+            path,
+            comment,
+            source_type: sb.source_type,
+            bus_type: sb.bus_type,
+        }))
     }
 }
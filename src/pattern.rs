@@ -0,0 +1,140 @@
+//! Channel name patterns for
+//! [`MDF::find_channels_matching`](crate::api::mdf::MDF::find_channels_matching)
+//! and [`MdfIndex::find_channels_matching`](crate::index::MdfIndex::find_channels_matching).
+//!
+//! A `&str` converts to a shell-style glob. With the `regex` feature a
+//! [`regex::Regex`] converts to a pattern as well.
+
+/// A pattern channel names are matched against.
+///
+/// Globs match the whole name: `*` matches any sequence of characters
+/// (including the `.` of hierarchical names such as `ECU1.Engine.Speed`),
+/// `?` any single character, and `[abc]`, `[a-z]` or `[!a-z]` one character
+/// of (or not of) a set; every other character matches itself. Regexes
+/// match anywhere in the name unless anchored with `^` / `$`.
+#[derive(Debug, Clone)]
+pub struct NamePattern(Matcher);
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Glob(Vec<char>),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl NamePattern {
+    /// A shell-style glob pattern.
+    pub fn glob(pattern: &str) -> Self {
+        NamePattern(Matcher::Glob(pattern.chars().collect()))
+    }
+
+    /// A regular expression pattern.
+    #[cfg(feature = "regex")]
+    pub fn regex(regex: regex::Regex) -> Self {
+        NamePattern(Matcher::Regex(regex))
+    }
+
+    /// `true` if `name` matches the pattern.
+    pub fn is_match(&self, name: &str) -> bool {
+        match &self.0 {
+            Matcher::Glob(pattern) => glob_match(pattern, &name.chars().collect::<Vec<_>>()),
+            #[cfg(feature = "regex")]
+            Matcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl From<&str> for NamePattern {
+    fn from(pattern: &str) -> Self {
+        NamePattern::glob(pattern)
+    }
+}
+
+impl From<&String> for NamePattern {
+    fn from(pattern: &String) -> Self {
+        NamePattern::glob(pattern)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<regex::Regex> for NamePattern {
+    fn from(regex: regex::Regex) -> Self {
+        NamePattern::regex(regex)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<&regex::Regex> for NamePattern {
+    fn from(regex: &regex::Regex) -> Self {
+        NamePattern::regex(regex.clone())
+    }
+}
+
+/// Match `name` against the glob `pattern`, backtracking to the last `*`
+/// on a mismatch.
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if pattern.get(p) == Some(&'*') {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some(next) = match_one(pattern, p, name[n]) {
+            p = next;
+            n += 1;
+        } else if let Some((after_star, from)) = star {
+            // Let the last `*` swallow one more character.
+            star = Some((after_star, from + 1));
+            p = after_star;
+            n = from + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// If the pattern element at `p` (anything but `*`) matches `c`, the index
+/// of the element after it.
+fn match_one(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    match *pattern.get(p)? {
+        '?' => Some(p + 1),
+        '[' => match match_class(pattern, p, c) {
+            Some((true, next)) => Some(next),
+            Some((false, _)) => None,
+            // An unterminated `[` is a literal.
+            None => (c == '[').then_some(p + 1),
+        },
+        literal => (literal == c).then_some(p + 1),
+    }
+}
+
+/// Match `c` against the character class starting with the `[` at `p`:
+/// whether it matches and the index after the closing `]`, or `None` if the
+/// class is not terminated.
+fn match_class(pattern: &[char], p: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = p + 1;
+    let negate = matches!(pattern.get(i), Some('!' | '^'));
+    if negate {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let lo = *pattern.get(i)?;
+        if lo == ']' && !first {
+            return Some((matched != negate, i + 1));
+        }
+        first = false;
+        match (pattern.get(i + 1), pattern.get(i + 2)) {
+            (Some('-'), Some(&hi)) if hi != ']' => {
+                matched |= (lo..=hi).contains(&c);
+                i += 3;
+            }
+            _ => {
+                matched |= lo == c;
+                i += 1;
+            }
+        }
+    }
+}
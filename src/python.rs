@@ -14,10 +14,14 @@ use pyo3_stub_gen::derive::{
 };
 use pyo3_stub_gen::define_stub_info_gatherer;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
 
+use crate::api::channel_group::ChannelGroup;
 use crate::api::mdf::MDF;
-use crate::writer::{MdfWriter, ColumnData};
-use crate::index::{IndexedChannel, MdfIndex};
+use crate::writer::{ChannelGroupId, ChannelId, MdfWriter, ColumnData};
+use crate::index::{IndexedChannel, MdfIndex, RangeRequest};
 use crate::blocks::common::DataType;
 use crate::parsing::decoder::DecodedValue;
 use crate::error::MdfError;
@@ -201,6 +205,8 @@ impl From<DecodedValue> for PyDecodedValue {
             DecodedValue::ByteArray(v) => PyDecodedValue::ByteArray { value: v },
             DecodedValue::MimeSample(v) => PyDecodedValue::ByteArray { value: v },
             DecodedValue::MimeStream(v) => PyDecodedValue::ByteArray { value: v },
+            DecodedValue::CanOpenDate(v) => PyDecodedValue::String { value: v.to_string() },
+            DecodedValue::CanOpenTime(v) => PyDecodedValue::String { value: v.to_string() },
             DecodedValue::Unknown => PyDecodedValue::Unknown { },
         }
     }
@@ -230,14 +236,74 @@ fn decoded_value_to_pyobject(dv: DecodedValue, py: Python) -> PyObject {
         DecodedValue::ByteArray(v) | DecodedValue::MimeSample(v) | DecodedValue::MimeStream(v) => {
             v.to_object(py)
         }
+        DecodedValue::CanOpenDate(v) => v.to_string().to_object(py),
+        DecodedValue::CanOpenTime(v) => v.to_string().to_object(py),
         DecodedValue::Unknown => py.None(),
     }
 }
 
+/// The name and decoded samples of every channel of `g`, in channel order:
+/// a zero-copy numpy array for primitive columns, else the converted values.
+fn group_columns(py: Python, g: &ChannelGroup) -> PyResult<Vec<(Option<String>, PyObject)>> {
+    let mut columns = Vec::new();
+    for ch in g.channels() {
+        let float = matches!(ch.block().data_type, DataType::FloatLE | DataType::FloatBE);
+        // Release the GIL while decoding.
+        let u64s = py.allow_threads(|| ch.primitive_column_u64())?;
+        let f64s = match u64s.is_none() && float {
+            true => py.allow_threads(|| ch.primitive_column_f64())?,
+            false => None,
+        };
+        let array: PyObject = match (u64s, f64s) {
+            (Some(column), _) => PyArray1::from_vec_bound(py, column).into(),
+            (None, Some(column)) => PyArray1::from_vec_bound(py, column).into(),
+            (None, None) => values_to_numpy(py, py.allow_threads(|| ch.values())?),
+        };
+        columns.push((ch.name()?, array));
+    }
+    Ok(columns)
+}
+
+/// Samples as a numpy array: ``int64`` when every sample is a valid integer
+/// that fits, ``float64`` with ``NaN`` for invalid samples when every valid
+/// sample is numeric, otherwise a list with ``None`` for invalid samples.
+fn values_to_numpy(py: Python, values: Vec<Option<DecodedValue>>) -> PyObject {
+    let ints: Option<Vec<i64>> = values
+        .iter()
+        .map(|v| match v {
+            Some(DecodedValue::SignedInteger(i)) => Some(*i),
+            Some(DecodedValue::UnsignedInteger(u)) => i64::try_from(*u).ok(),
+            _ => None,
+        })
+        .collect();
+    if let Some(ints) = ints {
+        return PyArray1::from_vec_bound(py, ints).into();
+    }
+    let floats: Option<Vec<f64>> = values
+        .iter()
+        .map(|v| match v {
+            None => Some(f64::NAN),
+            Some(DecodedValue::Float(f)) => Some(*f),
+            Some(DecodedValue::SignedInteger(i)) => Some(*i as f64),
+            Some(DecodedValue::UnsignedInteger(u)) => Some(*u as f64),
+            Some(_) => None,
+        })
+        .collect();
+    if let Some(floats) = floats {
+        return PyArray1::from_vec_bound(py, floats).into();
+    }
+    values
+        .into_iter()
+        .map(|o| o.map(|dv| decoded_value_to_pyobject(dv, py)).unwrap_or_else(|| py.None()))
+        .collect::<Vec<_>>()
+        .to_object(py)
+}
+
 /// Read-only metadata describing a single channel.
 ///
 /// Found on :py:attr:`GroupInfo.channels`, and returned by
-/// :py:meth:`Mdf.channel` / :py:meth:`MdfIndex.channel`.
+/// :py:meth:`Mdf.channel` / :py:meth:`MdfIndex.channel` /
+/// :py:meth:`MdfIndex.channel_descriptor`.
 ///
 /// Attributes
 /// ----------
@@ -246,12 +312,15 @@ fn decoded_value_to_pyobject(dv: DecodedValue, py: Python) -> PyObject {
 /// unit : Optional[str]
 ///     Engineering unit string, or ``None``.
 /// comment : Optional[str]
-///     Free-form comment, or ``None``. Always ``None`` when obtained via the
-///     index (not stored in `IndexedChannel`).
+///     Free-form comment, or ``None``. Indexes saved before comments were
+///     stored report ``None``.
 /// data_type : DataType
 ///     The MDF data type of the raw samples.
 /// bit_count : int
 ///     Width of the raw value in bits (e.g. 32 for f32, 64 for f64/u64).
+/// conversion : Optional[str]
+///     One-line summary of the conversion (e.g. ``"linear: 3 + 0.5 * x"``),
+///     or ``None`` if the channel has none.
 #[gen_stub_pyclass]
 #[pyclass(name = "ChannelInfo")]
 #[derive(Debug, Clone)]
@@ -272,6 +341,8 @@ pub struct PyChannelInfo {
     /// True if this is a variable-length (VLSD) channel.
     #[pyo3(get)]
     pub is_vlsd: bool,
+    #[pyo3(get)]
+    pub conversion: Option<String>,
 }
 
 #[gen_stub_pymethods]
@@ -299,19 +370,22 @@ impl PyChannelInfo {
             bit_count: block.bit_count,
             is_master: block.channel_type == 2,
             is_vlsd: block.channel_type == 1 && block.data != 0,
+            conversion: block.conversion.as_ref().map(|c| c.summary()),
         })
     }
 
-    /// Build from an indexed channel (comments are not stored in the index).
+    /// Build from an indexed channel.
     fn from_indexed(channel: &IndexedChannel) -> Self {
+        let descriptor = channel.descriptor();
         PyChannelInfo {
-            name: channel.name.clone(),
-            unit: channel.unit.clone(),
-            comment: None,
-            data_type: PyDataType::from(&channel.data_type),
-            bit_count: channel.bit_count,
-            is_master: channel.is_master(),
+            name: descriptor.name,
+            unit: descriptor.unit,
+            comment: descriptor.comment,
+            data_type: PyDataType::from(&descriptor.data_type),
+            bit_count: descriptor.bit_count,
+            is_master: descriptor.is_master,
             is_vlsd: channel.is_vlsd(),
+            conversion: descriptor.conversion,
         }
     }
 }
@@ -542,8 +616,13 @@ impl PyMDF {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     ///     Path to a ``.mf4`` / ``.mdf`` file. Must be MDF version >= 4.10.
+    /// mmap : bool, optional
+    ///     Memory map the file (default ``True``). With ``False`` the file is
+    ///     read with buffered reads instead, so files larger than the address
+    ///     space of a 32-bit process can be opened; MDF 3.x and unfinalized
+    ///     files need the memory map.
     ///
     /// Raises
     /// ------
@@ -551,9 +630,13 @@ impl PyMDF {
     ///     If the file does not exist, has the wrong magic bytes, an
     ///     unsupported version, or contains malformed blocks.
     #[new]
-    fn new(path: &str) -> PyResult<Self> {
-        let mdf = Box::new(MDF::from_file(path)?);
-        Ok(PyMDF { mdf, path: path.to_string() })
+    fn new(path: PathBuf, mmap: Option<bool>) -> PyResult<Self> {
+        let options = crate::api::mdf::OpenOptions {
+            mmap: mmap.unwrap_or(true),
+            ..crate::api::mdf::OpenOptions::default()
+        };
+        let mdf = Box::new(MDF::open_with(&path, options)?);
+        Ok(PyMDF { mdf, path: path.to_string_lossy().into_owned() })
     }
 
     /// The file path this reader was opened from.
@@ -597,24 +680,18 @@ impl PyMDF {
 
     /// Find a channel group by name (first match), or ``None``.
     fn group(&self, name: &str) -> PyResult<Option<PyChannelGroupInfo>> {
-        for g in self.mdf.channel_groups() {
-            if g.name()?.as_deref() == Some(name) {
-                return Ok(Some(PyChannelGroupInfo::from_group(&g)?));
-            }
-        }
-        Ok(None)
+        self.mdf
+            .channel_group_by_name(name)
+            .map(|g| PyChannelGroupInfo::from_group(&g))
+            .transpose()
     }
 
     /// Find a channel by name across all groups (first match), or ``None``.
     fn channel(&self, name: &str) -> PyResult<Option<PyChannelInfo>> {
-        for g in self.mdf.channel_groups() {
-            for ch in g.channels() {
-                if ch.name()?.as_deref() == Some(name) {
-                    return Ok(Some(PyChannelInfo::from_channel(&ch)?));
-                }
-            }
-        }
-        Ok(None)
+        self.mdf
+            .channel(name)
+            .map(|ch| PyChannelInfo::from_channel(&ch))
+            .transpose()
     }
 
     /// Names of every named channel across all groups (duplicates kept).
@@ -631,6 +708,15 @@ impl PyMDF {
         Ok(names)
     }
 
+    /// Channels whose name matches the glob ``pattern`` as
+    /// ``[(group_index, channel_index, name), ...]`` in file order.
+    ///
+    /// ``*`` matches any characters (including ``.``), ``?`` one character
+    /// and ``[a-z]`` / ``[!a-z]`` one character of (or not of) a set.
+    fn find_channels_matching(&self, pattern: &str) -> PyResult<Vec<(usize, usize, String)>> {
+        Ok(self.mdf.find_channels_matching(pattern)?)
+    }
+
     /// Read a channel as a ``pandas.Series`` of values indexed by timestamps.
     ///
     /// This is the primary read: the channel's samples (with all conversions
@@ -687,6 +773,72 @@ impl PyMDF {
         Ok(PyArray1::from_vec_bound(py, values).into())
     }
 
+    /// Decode every channel of a group into numpy arrays in one call.
+    ///
+    /// Decoding happens in Rust and each column is handed to numpy without a
+    /// copy, so no Python object is created per sample. The arrays implement
+    /// the buffer protocol; ``pandas.DataFrame(arrays)`` or
+    /// ``polars.DataFrame(arrays)`` build a frame from them directly.
+    ///
+    /// Unsigned integer channels without conversion are ``uint64``, other
+    /// integer channels ``int64`` and numeric channels ``float64`` with
+    /// ``NaN`` for invalid samples; channels with text or byte values are
+    /// lists with ``None`` for invalid samples. Unnamed channels are keyed
+    /// ``channel_<n>`` by their position in the group, as are channels whose
+    /// name an earlier channel of the group already has; use
+    /// :py:meth:`get_group_as_arrays` to keep the names as they are.
+    ///
+    /// Parameters
+    /// ----------
+    /// group : str
+    ///     Name of the channel group.
+    ///
+    /// Returns
+    /// -------
+    /// dict[str, numpy.ndarray | list]
+    ///     Channel name to samples, in channel order.
+    fn group_arrays(&self, py: Python, group: &str) -> PyResult<PyObject> {
+        let g = self
+            .mdf
+            .group(group)
+            .ok_or_else(|| MdfException::new_err(format!("Channel group '{}' not found", group)))?;
+        let arrays = pyo3::types::PyDict::new_bound(py);
+        for (i, (name, array)) in group_columns(py, &g)?.into_iter().enumerate() {
+            let key = match name {
+                Some(name) if !arrays.contains(&name)? => name,
+                _ => format!("channel_{i}"),
+            };
+            arrays.set_item(key, array)?;
+        }
+        Ok(arrays.into())
+    }
+
+    /// Decode every channel of the group at ``group_index`` into numpy
+    /// arrays in one call, as :py:meth:`group_arrays` does.
+    ///
+    /// The columns are returned as ``(name, array)`` pairs in channel order,
+    /// so channels sharing a name are all kept; unnamed channels have the
+    /// name ``None``.
+    ///
+    /// Parameters
+    /// ----------
+    /// group_index : int
+    ///     Position of the channel group in the file, counted from zero as
+    ///     in :py:attr:`groups`.
+    ///
+    /// Returns
+    /// -------
+    /// list[tuple[Optional[str], numpy.ndarray | list]]
+    fn get_group_as_arrays(&self, py: Python, group_index: usize) -> PyResult<Vec<(Option<String>, PyObject)>> {
+        let groups = self.mdf.channel_groups();
+        let g = groups.get(group_index).ok_or(MdfError::InvalidIndex {
+            kind: "group",
+            index: group_index,
+            len: groups.len(),
+        })?;
+        group_columns(py, g)
+    }
+
     /// ``mdf["Speed"]`` — shorthand for :py:meth:`read` (timestamp-indexed Series).
     ///
     /// Pass a ``(name, group)`` tuple to disambiguate a channel name shared by
@@ -704,6 +856,13 @@ impl PyMDF {
         let layout = self.mdf.file_layout()?;
         Ok(PyFileLayout { inner: layout })
     }
+
+    /// Describe the whole file as a JSON string — groups, channels, data
+    /// types, units, conversions with their parameters, sources and
+    /// attachments — without reading any sample data.
+    fn metadata_json(&self) -> PyResult<String> {
+        Ok(self.mdf.metadata_json()?)
+    }
 }
 
 /// Streaming writer for MDF 4 files.
@@ -728,6 +887,10 @@ impl PyMDF {
 /// them. Channels are auto-linked into a per-group linked list in the order
 /// they were added.
 ///
+/// The writer is not tied to the thread that created it, so it can be
+/// handed to a logging thread; calls from two threads at the same time
+/// raise ``RuntimeError`` (the object is already borrowed).
+///
 /// Example
 /// -------
 /// >>> w = mf4_rs.MdfWriter("demo.mf4")
@@ -744,16 +907,59 @@ impl PyMDF {
 /// >>> w.finish_data_block(cg)
 /// >>> w.finalize()
 #[gen_stub_pyclass]
-#[pyclass(unsendable, name = "MdfWriter")]
+#[pyclass(name = "MdfWriter")]
 pub struct PyMdfWriter {
-    writer: Option<MdfWriter>,
-    channel_groups: HashMap<String, String>, // Maps Python ID to Rust ID
-    channels: HashMap<String, String>,       // Maps Python ID to Rust ID
+    writer: Option<MdfWriter<BufWriter<File>>>,
+    channel_groups: HashMap<String, ChannelGroupId>, // Maps Python ID to Rust handle
+    channels: HashMap<String, ChannelId>,            // Maps Python ID to Rust handle
     // Track the last channel added for each channel group (for automatic linking)
     last_channels: HashMap<String, String>,   // Maps channel group ID to last channel ID
     next_id: usize,
 }
 
+/// A numpy column borrowed for the duration of a columnar write, typed by
+/// its ``dtypes`` entry.
+enum OwnedArray<'py> {
+    F64(PyReadonlyArray1<'py, f64>),
+    F32(PyReadonlyArray1<'py, f32>),
+    U64(PyReadonlyArray1<'py, u64>),
+    I64(PyReadonlyArray1<'py, i64>),
+}
+
+/// Extract `columns` as the numpy arrays named by `dtypes`.
+fn extract_columns<'py>(columns: &[Bound<'py, PyAny>], dtypes: &[String]) -> PyResult<Vec<OwnedArray<'py>>> {
+    if columns.len() != dtypes.len() {
+        return Err(MdfException::new_err(format!(
+            "columns length ({}) must match dtypes length ({})",
+            columns.len(), dtypes.len()
+        )));
+    }
+    columns.iter().zip(dtypes.iter())
+        .map(|(col, dtype)| match dtype.as_str() {
+            "f64" => col.extract::<PyReadonlyArray1<f64>>().map(OwnedArray::F64),
+            "f32" => col.extract::<PyReadonlyArray1<f32>>().map(OwnedArray::F32),
+            "u64" => col.extract::<PyReadonlyArray1<u64>>().map(OwnedArray::U64),
+            "i64" => col.extract::<PyReadonlyArray1<i64>>().map(OwnedArray::I64),
+            other => Err(MdfException::new_err(format!(
+                "Unknown dtype '{}'; expected one of: f64, f32, u64, i64", other
+            ))),
+        })
+        .collect()
+}
+
+/// Borrow the extracted arrays as [`ColumnData`] slices.
+fn column_slices<'a>(owned: &'a [OwnedArray<'_>]) -> PyResult<Vec<ColumnData<'a>>> {
+    owned.iter()
+        .map(|arr| match arr {
+            OwnedArray::F64(a) => a.as_slice().map(ColumnData::F64),
+            OwnedArray::F32(a) => a.as_slice().map(ColumnData::F32),
+            OwnedArray::U64(a) => a.as_slice().map(ColumnData::U64),
+            OwnedArray::I64(a) => a.as_slice().map(ColumnData::I64),
+        }
+        .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e))))
+        .collect()
+}
+
 impl PyMdfWriter {
     fn add_channel_with_bits(&mut self, group_id: &str, name: &str, data_type: PyDataType, bit_count: u32) -> PyResult<String> {
         if let Some(ref mut writer) = self.writer {
@@ -763,7 +969,7 @@ impl PyMdfWriter {
                 .and_then(|py_id| self.channels.get(py_id))
                 .cloned();
             let rust_data_type = DataType::from(data_type);
-            let ch_id = writer.add_channel(cg_id, prev_channel_id.as_ref().map(|s| s.as_str()), |ch| {
+            let ch_id = writer.add_channel(cg_id, prev_channel_id.as_ref(), |ch| {
                 ch.data_type = rust_data_type.clone();
                 ch.name = Some(name.to_string());
                 ch.bit_count = bit_count;
@@ -777,6 +983,19 @@ impl PyMdfWriter {
             Err(MdfException::new_err("Writer has been finalized"))
         }
     }
+
+    /// The open writer and the Rust handle of `group_id`.
+    fn writer_and_group(&mut self, group_id: &str) -> PyResult<(&mut MdfWriter<BufWriter<File>>, ChannelGroupId)> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| MdfException::new_err("Writer has been finalized"))?;
+        let cg_id = *self
+            .channel_groups
+            .get(group_id)
+            .ok_or_else(|| MdfException::new_err("Channel group not found"))?;
+        Ok((writer, cg_id))
+    }
 }
 
 #[gen_stub_pymethods]
@@ -789,7 +1008,7 @@ impl PyMdfWriter {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     ///     Output filesystem path.
     ///
     /// Raises
@@ -797,8 +1016,11 @@ impl PyMdfWriter {
     /// MdfException
     ///     If the file cannot be created.
     #[new]
-    fn new(path: &str) -> PyResult<Self> {
-        let writer = MdfWriter::new(path)?;
+    fn new(path: PathBuf) -> PyResult<Self> {
+        // A typed sink keeps the writer `Send`, so the object can move
+        // between Python threads.
+        let file = File::create(path).map_err(MdfError::from)?;
+        let writer = MdfWriter::from_writer(BufWriter::with_capacity(1_048_576, file));
         Ok(PyMdfWriter {
             writer: Some(writer),
             channel_groups: HashMap::new(),
@@ -860,11 +1082,10 @@ impl PyMdfWriter {
             .writer
             .as_mut()
             .ok_or_else(|| MdfException::new_err("Writer has been finalized"))?;
-        let rust_id = self
+        let rust_id = *self
             .channel_groups
             .get(group_id)
-            .ok_or_else(|| MdfException::new_err(format!("Unknown group_id: {}", group_id)))?
-            .clone();
+            .ok_or_else(|| MdfException::new_err(format!("Unknown group_id: {}", group_id)))?;
         writer.set_channel_group_comment(&rust_id, comment)?;
         Ok(())
     }
@@ -1012,6 +1233,83 @@ impl PyMdfWriter {
             Err(MdfException::new_err("Writer has been finalized"))
         }
     }
+
+    /// Append several records at once, each given as in
+    /// :py:meth:`write_record`.
+    ///
+    /// The encoded records are buffered and written in one go, which saves
+    /// the per-call overhead of looping over :py:meth:`write_record`.
+    fn write_records(&mut self, group_id: &str, records: Vec<Vec<PyDecodedValue>>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let records: Vec<Vec<DecodedValue>> = records
+            .into_iter()
+            .map(|record| record.into_iter().map(DecodedValue::from).collect())
+            .collect();
+        writer.write_records(&cg_id, records.iter().map(Vec::as_slice))?;
+        Ok(())
+    }
+
+    /// Append a single record of a group whose channels are all unsigned
+    /// integers, skipping :class:`DecodedValue` conversion.
+    ///
+    /// Raises
+    /// ------
+    /// MdfException
+    ///     If a channel is not an unsigned integer or the value count does
+    ///     not match the channel count.
+    fn write_record_u64(&mut self, group_id: &str, values: Vec<u64>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        writer.write_record_u64(&cg_id, &values)?;
+        Ok(())
+    }
+
+    /// Set the record template of the group's open data block: the bytes
+    /// each record starts from before its values are encoded.
+    ///
+    /// ``values`` holds one entry per channel; ``DecodedValue.Unknown()``
+    /// leaves a channel zeroed. A channel given ``DecodedValue.Unknown()`` in
+    /// :py:meth:`write_record` / :py:meth:`write_records` keeps its template
+    /// value. Call after :py:meth:`start_data_block`; the template lasts
+    /// until :py:meth:`finish_data_block`.
+    fn set_record_template(&mut self, group_id: &str, values: Vec<PyDecodedValue>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let values: Vec<DecodedValue> = values.into_iter().map(DecodedValue::from).collect();
+        writer.set_record_template(&cg_id, &values)?;
+        Ok(())
+    }
+
+    /// Fix channels of a group to constant values for the rest of its open
+    /// data block.
+    ///
+    /// ``constants`` holds one entry per channel: a :class:`DecodedValue`
+    /// for a constant channel, ``None`` for a varying one. Afterwards
+    /// :py:meth:`write_varying_records` and :py:meth:`write_varying_columns`
+    /// take values for the varying channels only.
+    ///
+    /// Raises
+    /// ------
+    /// MdfException
+    ///     If the entry count does not match the channel count or a VLSD
+    ///     channel is given a constant.
+    fn set_constant_channels(&mut self, group_id: &str, constants: Vec<Option<PyDecodedValue>>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let constants: Vec<Option<DecodedValue>> =
+            constants.into_iter().map(|value| value.map(DecodedValue::from)).collect();
+        writer.set_constant_channels(&cg_id, &constants)?;
+        Ok(())
+    }
+
+    /// Append records giving values for the varying channels only (see
+    /// :py:meth:`set_constant_channels`), in channel order.
+    fn write_varying_records(&mut self, group_id: &str, records: Vec<Vec<PyDecodedValue>>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let records: Vec<Vec<DecodedValue>> = records
+            .into_iter()
+            .map(|record| record.into_iter().map(DecodedValue::from).collect())
+            .collect();
+        writer.write_varying_records(&cg_id, records.iter().map(Vec::as_slice))?;
+        Ok(())
+    }
     
     /// Close the open ``##DT`` block for a channel group.
     ///
@@ -1052,9 +1350,8 @@ impl PyMdfWriter {
     ///     mismatch.
     fn write_columns_f64(&mut self, _py: Python<'_>, group_id: &str, columns: Vec<Bound<'_, PyAny>>) -> PyResult<()> {
         if let Some(ref mut writer) = self.writer {
-            let cg_id = self.channel_groups.get(group_id)
-                .ok_or_else(|| MdfException::new_err("Channel group not found"))?
-                .clone();
+            let cg_id = *self.channel_groups.get(group_id)
+                .ok_or_else(|| MdfException::new_err("Channel group not found"))?;
 
             let arrays: Vec<PyReadonlyArray1<f64>> = columns.iter()
                 .map(|c| c.extract::<PyReadonlyArray1<f64>>())
@@ -1092,56 +1389,12 @@ impl PyMdfWriter {
     ///     numpy array's element type doesn't match the dtype string, the
     ///     two list lengths differ, or any array is non-contiguous.
     fn write_columns(&mut self, _py: Python<'_>, group_id: &str, columns: Vec<Bound<'_, PyAny>>, dtypes: Vec<String>) -> PyResult<()> {
-        if columns.len() != dtypes.len() {
-            return Err(MdfException::new_err(format!(
-                "columns length ({}) must match dtypes length ({})",
-                columns.len(), dtypes.len()
-            )));
-        }
-
         if let Some(ref mut writer) = self.writer {
-            let cg_id = self.channel_groups.get(group_id)
-                .ok_or_else(|| MdfException::new_err("Channel group not found"))?
-                .clone();
-
-            // We need to keep the extracted arrays alive for the duration of the call.
-            // Use an enum to hold each typed array so lifetimes work out.
-            enum OwnedArray<'py> {
-                F64(PyReadonlyArray1<'py, f64>),
-                F32(PyReadonlyArray1<'py, f32>),
-                U64(PyReadonlyArray1<'py, u64>),
-                I64(PyReadonlyArray1<'py, i64>),
-            }
-
-            let owned: Vec<OwnedArray<'_>> = columns.iter().zip(dtypes.iter())
-                .map(|(col, dtype)| match dtype.as_str() {
-                    "f64" => col.extract::<PyReadonlyArray1<f64>>().map(OwnedArray::F64),
-                    "f32" => col.extract::<PyReadonlyArray1<f32>>().map(OwnedArray::F32),
-                    "u64" => col.extract::<PyReadonlyArray1<u64>>().map(OwnedArray::U64),
-                    "i64" => col.extract::<PyReadonlyArray1<i64>>().map(OwnedArray::I64),
-                    other => Err(MdfException::new_err(format!(
-                        "Unknown dtype '{}'; expected one of: f64, f32, u64, i64", other
-                    ))),
-                })
-                .collect::<PyResult<Vec<_>>>()?;
-
-            let column_data: Vec<ColumnData<'_>> = owned.iter()
-                .map(|arr| match arr {
-                    OwnedArray::F64(a) => a.as_slice()
-                        .map(ColumnData::F64)
-                        .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e))),
-                    OwnedArray::F32(a) => a.as_slice()
-                        .map(ColumnData::F32)
-                        .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e))),
-                    OwnedArray::U64(a) => a.as_slice()
-                        .map(ColumnData::U64)
-                        .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e))),
-                    OwnedArray::I64(a) => a.as_slice()
-                        .map(ColumnData::I64)
-                        .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e))),
-                })
-                .collect::<PyResult<Vec<_>>>()?;
+            let cg_id = *self.channel_groups.get(group_id)
+                .ok_or_else(|| MdfException::new_err("Channel group not found"))?;
 
+            let owned = extract_columns(&columns, &dtypes)?;
+            let column_data = column_slices(&owned)?;
             writer.write_columns(&cg_id, &column_data)?;
             Ok(())
         } else {
@@ -1149,6 +1402,71 @@ impl PyMdfWriter {
         }
     }
 
+    /// Bulk-write a group from a numpy time base and numpy data columns.
+    ///
+    /// Like :py:meth:`write_columns`, but the master channel's samples are
+    /// passed on their own as ``time`` and ``columns`` holds the other
+    /// channels, in channel order.
+    ///
+    /// Parameters
+    /// ----------
+    /// group_id : str
+    /// time : numpy.ndarray
+    ///     Contiguous 1-D ``float64`` array of master channel values.
+    /// columns : list[numpy.ndarray]
+    ///     One contiguous 1-D array per non-master channel, each as long as
+    ///     ``time``.
+    /// dtypes : list[str]
+    ///     One entry per column: ``"f64"``, ``"f32"``, ``"u64"`` or ``"i64"``.
+    ///
+    /// Raises
+    /// ------
+    /// MdfException
+    ///     If the group has no master channel, or on any error
+    ///     :py:meth:`write_columns` would raise.
+    fn write_columns_with_time(
+        &mut self,
+        _py: Python<'_>,
+        group_id: &str,
+        time: Bound<'_, PyAny>,
+        columns: Vec<Bound<'_, PyAny>>,
+        dtypes: Vec<String>,
+    ) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let time = time.extract::<PyReadonlyArray1<f64>>()?;
+        let time = time
+            .as_slice()
+            .map_err(|e| MdfException::new_err(format!("Array not contiguous: {}", e)))?;
+        let owned = extract_columns(&columns, &dtypes)?;
+        let column_data = column_slices(&owned)?;
+        writer.write_columns_with_time(&cg_id, time, &column_data)?;
+        Ok(())
+    }
+
+    /// Bulk-write the varying channels of a group from numpy arrays.
+    ///
+    /// Like :py:meth:`write_columns`, but with one column per channel left
+    /// ``None`` in :py:meth:`set_constant_channels` (all channels if it was
+    /// not called), in channel order. The constant channels are copied from
+    /// the record template, so a mostly static group costs little more than
+    /// a memory copy per record.
+    ///
+    /// Parameters
+    /// ----------
+    /// group_id : str
+    /// columns : list[numpy.ndarray]
+    ///     One contiguous 1-D array per varying channel, all of identical
+    ///     length.
+    /// dtypes : list[str]
+    ///     One entry per column: ``"f64"``, ``"f32"``, ``"u64"`` or ``"i64"``.
+    fn write_varying_columns(&mut self, _py: Python<'_>, group_id: &str, columns: Vec<Bound<'_, PyAny>>, dtypes: Vec<String>) -> PyResult<()> {
+        let (writer, cg_id) = self.writer_and_group(group_id)?;
+        let owned = extract_columns(&columns, &dtypes)?;
+        let column_data = column_slices(&owned)?;
+        writer.write_varying_columns(&cg_id, &column_data)?;
+        Ok(())
+    }
+
     /// Flush all buffered bytes to disk and close the file.
     ///
     /// After this returns, the writer is consumed: any subsequent method
@@ -1173,7 +1491,8 @@ impl PyMdfWriter {
 /// byte-range request happens on :py:meth:`read` / :py:meth:`values`, never at
 /// build time.
 ///
-/// **Limitation:** compressed (``##DZ``) data blocks are not supported.
+/// Compressed (``##DZ``) data blocks are inflated on read, but
+/// :py:meth:`byte_ranges` cannot address samples inside them.
 ///
 /// Example
 /// -------
@@ -1200,10 +1519,10 @@ impl PyMdfIndex {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     ///     Path to a ``.mf4`` file.
     #[staticmethod]
-    fn from_file(path: &str) -> PyResult<Self> {
+    fn from_file(path: PathBuf) -> PyResult<Self> {
         Ok(PyMdfIndex { index: MdfIndex::from_file(path)? })
     }
 
@@ -1212,7 +1531,7 @@ impl PyMdfIndex {
     /// The original MDF file is only needed later, when you actually read
     /// values via :py:meth:`open`.
     #[staticmethod]
-    fn load(path: &str) -> PyResult<Self> {
+    fn load(path: PathBuf) -> PyResult<Self> {
         Ok(PyMdfIndex { index: MdfIndex::load_from_file(path)? })
     }
 
@@ -1242,7 +1561,7 @@ impl PyMdfIndex {
     }
 
     /// Serialize the index to JSON at ``path`` (dependency-free).
-    fn save(&self, path: &str) -> PyResult<()> {
+    fn save(&self, path: PathBuf) -> PyResult<()> {
         self.index.save_to_file(path)?;
         Ok(())
     }
@@ -1266,12 +1585,38 @@ impl PyMdfIndex {
         self.index.channel(name).map(PyChannelInfo::from_indexed)
     }
 
+    /// ``(first, last)`` master (time) value of the group called ``group``,
+    /// stored when the index was built, or ``None`` if the group is unknown
+    /// or has no master channel or records. Reads no sample data.
+    fn time_range(&self, group: &str) -> Option<(f64, f64)> {
+        self.index.time_range(group)
+    }
+
+    /// Metadata of channel ``index`` of group ``group`` (both counted from
+    /// zero), or ``None`` if either is out of range.
+    fn channel_descriptor(&self, group: usize, index: usize) -> Option<PyChannelInfo> {
+        self.index
+            .groups()
+            .get(group)
+            .and_then(|g| g.channels.get(index))
+            .map(PyChannelInfo::from_indexed)
+    }
+
     /// Names of every named channel across all groups (duplicates kept).
     #[getter]
     fn channel_names(&self) -> Vec<String> {
         self.index.channel_names().into_iter().map(String::from).collect()
     }
 
+    /// Channels whose name matches the glob ``pattern`` as
+    /// ``[(group_index, channel_index, name), ...]`` in file order.
+    ///
+    /// ``*`` matches any characters (including ``.``), ``?`` one character
+    /// and ``[a-z]`` / ``[!a-z]`` one character of (or not of) a set.
+    fn find_channels_matching(&self, pattern: &str) -> Vec<(usize, usize, String)> {
+        self.index.find_channels_matching(pattern)
+    }
+
     /// Names of the groups that contain a channel called ``name``.
     ///
     /// Use this to disambiguate a channel name shared by several groups, then
@@ -1294,21 +1639,36 @@ impl PyMdfIndex {
     /// name : str
     /// group : Optional[str]
     ///     Disambiguate by group when the name is not unique.
-    fn byte_ranges(&self, name: &str, group: Option<&str>) -> PyResult<Vec<(u64, u64)>> {
-        Ok(match group {
-            Some(g) => self.index.byte_ranges_in(g, name)?,
-            None => self.index.byte_ranges(name)?,
-        })
+    /// invalidation : Optional[bool]
+    ///     Also cover the channel's invalidation byte in every record, so the
+    ///     samples' validity can be read from the fetched bytes.
+    fn byte_ranges(&self, name: &str, group: Option<&str>, invalidation: Option<bool>) -> PyResult<Vec<(u64, u64)>> {
+        let mut request = RangeRequest::channel(name);
+        if let Some(g) = group {
+            request = request.in_group(g);
+        }
+        if invalidation.unwrap_or(false) {
+            request = request.with_invalidation();
+        }
+        let plan = self.index.plan_reads(&[request], 0)?;
+        Ok(plan.requests[0].iter().map(|r| (r.offset, r.length)).collect())
     }
 
     /// Byte ranges covering a record window ``[start, start+count)``.
+    ///
+    /// ``invalidation`` is as in :py:meth:`byte_ranges`.
     fn byte_ranges_for_records(
         &self,
         name: &str,
         start_record: u64,
         record_count: u64,
+        invalidation: Option<bool>,
     ) -> PyResult<Vec<(u64, u64)>> {
-        Ok(self.index.byte_ranges_for_records(name, start_record, record_count)?)
+        Ok(if invalidation.unwrap_or(false) {
+            self.index.byte_ranges_for_records_with_invalidation(name, start_record, record_count)?
+        } else {
+            self.index.byte_ranges_for_records(name, start_record, record_count)?
+        })
     }
 
     /// Inspect the conversion attached to a channel (by name).
@@ -1443,6 +1803,43 @@ impl PyMdfIndex {
         Ok(PyArray1::from_vec_bound(py, values).into())
     }
 
+    /// Read records ``start .. start + count`` of a channel as a numpy array.
+    ///
+    /// Lazy partial read: only the bytes of the requested records are fetched
+    /// from uncompressed data blocks, so large channels can be paged through.
+    /// Integer channels come back as ``int64``, other numeric channels as
+    /// ``float64`` with ``NaN`` for invalid samples; channels with text or
+    /// byte values come back as a list with ``None`` for invalid samples.
+    ///
+    /// Parameters
+    /// ----------
+    /// group : str
+    ///     Name of the channel group.
+    /// channel : str
+    ///     Name of the channel within the group.
+    /// start : int
+    ///     First record to read.
+    /// count : int
+    ///     Number of records to read.
+    ///
+    /// Raises
+    /// ------
+    /// MdfException
+    ///     If no source is attached, the channel is missing or VLSD, or the
+    ///     records are out of range.
+    fn read_channel_slice(
+        &self,
+        py: Python,
+        group: &str,
+        channel: &str,
+        start: u64,
+        count: u64,
+    ) -> PyResult<PyObject> {
+        // Release the GIL during the (potentially blocking, e.g. HTTP) read.
+        let values = py.allow_threads(|| self.index.read_records_in(group, channel, start, count))?;
+        Ok(values_to_numpy(py, values))
+    }
+
     /// ``index["Speed"]`` — shorthand for :py:meth:`read` (timestamp-indexed Series).
     ///
     /// Pass a ``(name, group)`` tuple to disambiguate a channel name shared by
@@ -1637,9 +2034,9 @@ impl PyFileLayout {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     #[staticmethod]
-    fn from_file(path: &str) -> PyResult<Self> {
+    fn from_file(path: PathBuf) -> PyResult<Self> {
         let inner = FileLayout::from_file(path)?;
         Ok(PyFileLayout { inner })
     }
@@ -1681,19 +2078,19 @@ impl PyFileLayout {
     }
 
     /// Write the flat text listing (see :py:meth:`to_text`) to ``path``.
-    fn write_text_to_file(&self, path: &str) -> PyResult<()> {
+    fn write_text_to_file(&self, path: PathBuf) -> PyResult<()> {
         self.inner.write_text_to_file(path)?;
         Ok(())
     }
 
     /// Write the indented tree view (see :py:meth:`to_tree`) to ``path``.
-    fn write_tree_to_file(&self, path: &str) -> PyResult<()> {
+    fn write_tree_to_file(&self, path: PathBuf) -> PyResult<()> {
         self.inner.write_tree_to_file(path)?;
         Ok(())
     }
 
     /// Write the JSON representation (see :py:meth:`to_json`) to ``path``.
-    fn write_json_to_file(&self, path: &str) -> PyResult<()> {
+    fn write_json_to_file(&self, path: PathBuf) -> PyResult<()> {
         self.inner.write_json_to_file(path)?;
         Ok(())
     }
@@ -1714,14 +2111,14 @@ impl PyFileLayout {
 ///
 /// Parameters
 /// ----------
-/// path : str
+/// path : str or os.PathLike
 ///
 /// Returns
 /// -------
 /// FileLayout
 #[gen_stub_pyfunction]
 #[pyfunction]
-fn file_layout_from_file(path: &str) -> PyResult<PyFileLayout> {
+fn file_layout_from_file(path: PathBuf) -> PyResult<PyFileLayout> {
     PyFileLayout::from_file(path)
 }
 
@@ -1736,24 +2133,41 @@ fn file_layout_from_file(path: &str) -> PyResult<PyFileLayout> {
 ///
 /// Parameters
 /// ----------
-/// input_path : str
+/// input_path : str or os.PathLike
 ///     Path to the source MF4 file.
-/// output_path : str
+/// output_path : str or os.PathLike
 ///     Destination path for the trimmed file.
 /// start_time : float
 ///     Start of the window in seconds (inclusive).
 /// end_time : float
 ///     End of the window in seconds (inclusive).
+/// rename : dict[str, str], optional
+///     Source channel name → output channel name.
+/// units : dict[str, str], optional
+///     Output channel name → unit, replacing the source unit.
+/// preserve_blocks : bool, optional
+///     Also copy the header comment, file history, events, attachments and
+///     blocks the library does not interpret, rewiring their links to the
+///     output channels. Defaults to ``False``.
 #[gen_stub_pyfunction]
 #[pyfunction]
-#[pyo3(signature = (input_path, output_path, start_time, end_time))]
 fn cut_mdf_by_time(
-    input_path: &str,
-    output_path: &str,
+    input_path: PathBuf,
+    output_path: PathBuf,
     start_time: f64,
     end_time: f64,
+    rename: Option<HashMap<String, String>>,
+    units: Option<HashMap<String, String>>,
+    preserve_blocks: Option<bool>,
 ) -> PyResult<()> {
-    crate::cut::cut_mdf_by_time(input_path, output_path, start_time, end_time)?;
+    let options = crate::cut::CutOptions {
+        mapping: crate::cut::ChannelMapping {
+            names: rename.unwrap_or_default(),
+            units: units.unwrap_or_default(),
+        },
+        preserve_blocks: preserve_blocks.unwrap_or(false),
+    };
+    crate::cut::cut_mdf_by_time_with_options(input_path, output_path, start_time, end_time, &options)?;
     Ok(())
 }
 
@@ -1832,8 +2246,8 @@ fn ensure_utc_aware<'py>(
 #[pyo3(signature = (input_path, output_path, start_utc, end_utc))]
 fn cut_mdf_by_utc(
     py: Python<'_>,
-    input_path: &str,
-    output_path: &str,
+    input_path: PathBuf,
+    output_path: PathBuf,
     start_utc: &Bound<'_, PyAny>,
     end_utc: &Bound<'_, PyAny>,
 ) -> PyResult<()> {
@@ -1843,6 +2257,51 @@ fn cut_mdf_by_utc(
     Ok(())
 }
 
+/// Rewrite an MDF file so that every data group holds a single channel group.
+///
+/// Unsorted data groups, whose records of several channel groups are
+/// interleaved and told apart by a record ID, are split into one data group
+/// per channel group. Records are streamed from the input, so files larger
+/// than memory can be sorted.
+///
+/// Parameters
+/// ----------
+/// input_path : str or os.PathLike
+///     Path to the source MF4 file.
+/// output_path : str or os.PathLike
+///     Destination path for the sorted file.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path))]
+fn sort_mdf(input_path: PathBuf, output_path: PathBuf) -> PyResult<()> {
+    crate::sort::sort_mdf(input_path, output_path)?;
+    Ok(())
+}
+
+/// Decode the raw CAN frames of a recording with a DBC file.
+///
+/// Every ``CAN_DataFrame`` group of the input is decoded and the output gets
+/// one channel group per bus channel and DBC message, named after the
+/// message, with a ``Timestamp`` master and one float channel per signal.
+/// Multiplexed signals absent from a frame are stored as invalid samples.
+///
+/// Parameters
+/// ----------
+/// input_path : str or os.PathLike
+///     Path to the MF4 file holding raw CAN frames.
+/// dbc_path : str or os.PathLike
+///     Path to the DBC file with the message and signal definitions.
+/// output_path : str or os.PathLike
+///     Destination path for the decoded signals.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (input_path, dbc_path, output_path))]
+fn extract_bus_logging(input_path: PathBuf, dbc_path: PathBuf, output_path: PathBuf) -> PyResult<()> {
+    let dbc = crate::dbc::Dbc::from_file(dbc_path)?;
+    crate::dbc::extract_bus_logging(input_path, &dbc, output_path)?;
+    Ok(())
+}
+
 // Helper functions
 
 /// Wrap a Python ``float`` in a :class:`DecodedValue` of variant ``Float``.
@@ -1921,14 +2380,109 @@ fn create_data_type_string_utf8() -> PyDataType {
 ///
 /// Parameters
 /// ----------
-/// output : str
+/// output : str or os.PathLike
 ///     Destination path for the merged file.
-/// first, second : str
-///     Source file paths. Must be uncompressed MDF 4.10+ files.
+/// first, second : str or os.PathLike
+///     Source file paths. Must be MDF 4.10+ files.
+/// rename : dict[str, str], optional
+///     Source channel name → output channel name, applied before the group
+///     layouts are compared.
+/// units : dict[str, str], optional
+///     Output channel name → unit.
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn merge_files(
+    output: PathBuf,
+    first: PathBuf,
+    second: PathBuf,
+    rename: Option<HashMap<String, String>>,
+    units: Option<HashMap<String, String>>,
+) -> PyResult<()> {
+    let mapping = crate::cut::ChannelMapping {
+        names: rename.unwrap_or_default(),
+        units: units.unwrap_or_default(),
+    };
+    crate::merge::merge_files_with_mapping(output, first, second, &mapping)?;
+    Ok(())
+}
+
+/// Merge any number of MDF files into a new file at ``output``.
+///
+/// Like :py:func:`merge_files` for a list of inputs, merged in the order
+/// given: each group is concatenated to the first earlier group with the same
+/// layout, or appended as a separate group.
+///
+/// Parameters
+/// ----------
+/// output : str or os.PathLike
+///     Destination path for the merged file.
+/// inputs : list[str or os.PathLike]
+///     Source file paths. Must be MDF 4.10+ files.
+/// rename : dict[str, str], optional
+///     Source channel name → output channel name, applied before the group
+///     layouts are compared.
+/// units : dict[str, str], optional
+///     Output channel name → unit.
+/// clock_corrections : list[tuple[float, float]], optional
+///     ``(offset, factor)`` per input, in input order: the master channel
+///     values ``t`` of that input are written as ``offset + factor * t``, to
+///     line up logs of devices with offset or drifting clocks. Inputs past
+///     the end of the list are merged unchanged.
+/// preserve_blocks : bool, optional
+///     Also copy each input's header comment, file history, events,
+///     attachments and uninterpreted blocks, rewiring their links to the
+///     merged channels. Defaults to ``False``.
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn merge_many(
+    py: Python,
+    output: PathBuf,
+    inputs: Vec<PathBuf>,
+    rename: Option<HashMap<String, String>>,
+    units: Option<HashMap<String, String>>,
+    clock_corrections: Option<Vec<(f64, f64)>>,
+    preserve_blocks: Option<bool>,
+) -> PyResult<()> {
+    let options = crate::merge::MergeOptions {
+        mapping: crate::cut::ChannelMapping {
+            names: rename.unwrap_or_default(),
+            units: units.unwrap_or_default(),
+        },
+        clock_corrections: clock_corrections
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(offset, factor)| crate::merge::ClockCorrection::linear(offset, factor))
+            .collect(),
+        preserve_blocks: preserve_blocks.unwrap_or(false),
+    };
+    py.allow_threads(|| crate::merge::merge_many_with_options(output, &inputs, &options))?;
+    Ok(())
+}
+
+/// Concatenate MDF files recorded one after another into ``output``.
+///
+/// Unlike :py:func:`merge_many`, every input must have the same channel
+/// groups, in the same order, with the same channel layouts; group ``k`` of
+/// the output holds the records of group ``k`` of every input. Metadata and
+/// the start time come from the first input, and the master channels of
+/// later inputs are shifted by the difference of their start times so the
+/// time stays continuous.
+///
+/// Parameters
+/// ----------
+/// inputs : list[str or os.PathLike]
+///     Source file paths, in recording order.
+/// output : str or os.PathLike
+///     Destination path for the concatenated file.
+///
+/// Raises
+/// ------
+/// MdfException
+///     If the channel group layouts of the inputs differ.
 #[gen_stub_pyfunction]
 #[pyfunction]
-fn merge_files(output: &str, first: &str, second: &str) -> PyResult<()> {
-    crate::merge::merge_files(output, first, second)?;
+fn concatenate(py: Python, inputs: Vec<PathBuf>, output: PathBuf) -> PyResult<()> {
+    py.allow_threads(|| crate::merge::concatenate(&inputs, &output))?;
     Ok(())
 }
 
@@ -1976,8 +2530,12 @@ pub fn init_mf4_rs_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(create_data_type_string_utf8, m)?)?;
     m.add_function(wrap_pyfunction!(file_layout_from_file, m)?)?;
     m.add_function(wrap_pyfunction!(merge_files, m)?)?;
+    m.add_function(wrap_pyfunction!(merge_many, m)?)?;
+    m.add_function(wrap_pyfunction!(concatenate, m)?)?;
     m.add_function(wrap_pyfunction!(cut_mdf_by_time, m)?)?;
     m.add_function(wrap_pyfunction!(cut_mdf_by_utc, m)?)?;
+    m.add_function(wrap_pyfunction!(sort_mdf, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_bus_logging, m)?)?;
 
     Ok(())
 }
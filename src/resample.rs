@@ -0,0 +1,336 @@
+//! Resampling of several channel groups onto one common time base.
+//!
+//! Groups recorded at different rates (e.g. a 10 ms and a 100 ms group) are
+//! combined into a single channel group of a new file. Its master channel is
+//! the common time base and every numeric channel of the input groups is
+//! interpolated onto it (see [`Signal::resample`]).
+//!
+//! [`decimate`] instead writes a reduced copy of a file, keeping a subset of
+//! the records of each group as they are, e.g. for quick-look files.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::api::channel::Channel;
+use crate::api::channel_group::ChannelGroup;
+use crate::api::mdf::MDF;
+use crate::blocks::channel_block::CN_FLAG_INVAL_BIT_VALID;
+use crate::blocks::common::DataType;
+use crate::blocks::conversion::ConversionType;
+use crate::cut::{
+    copy_dg_comment, copy_group_records, copy_group_structure, map_group, source_groups, ChannelMapping,
+    HeaderChains, RecordAction,
+};
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+use crate::parsing::mdf_file::MdfFile;
+use crate::signal::{fixed_grid, Interpolation, InvalidationPolicy, Signal};
+use crate::writer::MdfWriter;
+
+/// The common time base of [`resample_groups`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeBase {
+    /// The master values of the input group with the highest sampling rate.
+    #[default]
+    Fastest,
+    /// The master values of the channel group with this index (in file
+    /// order, as returned by [`MDF::channel_groups`]).
+    Group(usize),
+    /// A fixed interval (in master units, usually seconds) from the earliest
+    /// to the latest master value of the input groups.
+    Interval(f64),
+}
+
+/// Options for [`resample_groups`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResampleOptions {
+    pub time_base: TimeBase,
+    pub interpolation: Interpolation,
+    /// How invalid samples affect interpolated values.
+    pub invalidation_policy: InvalidationPolicy,
+}
+
+/// How [`decimate`] reduces the records of each channel group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decimation {
+    /// Keep every `n`th record, starting with the first.
+    Factor(u64),
+    /// Keep the record whose master value is nearest to each point of a grid
+    /// with this interval (in master units, usually seconds), from the
+    /// group's first to its last master value; e.g. `0.1` for 10 Hz. Groups
+    /// without a master channel are copied in full.
+    Interval(f64),
+}
+
+/// Resample channel groups `groups` of `mdf` (indices in file order, as
+/// returned by [`MDF::channel_groups`]) onto one time base and write them as
+/// a single channel group to a new file at `output`.
+///
+/// The output group has a `Time` master followed by every numeric channel of
+/// the input groups in order, stored as 64-bit floats with conversions
+/// applied and units and comments copied. Channels with text or binary
+/// values are skipped. A channel name that is already taken gets the suffix
+/// `_{group index}`. Samples outside a channel's time range or computed from
+/// invalid samples are written as invalid.
+///
+/// Every input group needs a master channel.
+///
+/// # Returns
+/// The number of records written.
+pub fn resample_groups(
+    mdf: &MDF,
+    groups: &[usize],
+    output: impl AsRef<Path>,
+    options: &ResampleOptions,
+) -> Result<u64, MdfError> {
+    let inputs = groups
+        .iter()
+        .map(|&index| {
+            let (group, times) = group_with_times(mdf, index)?;
+            Ok((index, group, times))
+        })
+        .collect::<Result<Vec<_>, MdfError>>()?;
+
+    let grid = match options.time_base {
+        TimeBase::Fastest => inputs
+            .iter()
+            .max_by(|a, b| sampling_rate(&a.2).total_cmp(&sampling_rate(&b.2)))
+            .map(|(_, _, times)| times.clone())
+            .unwrap_or_default(),
+        TimeBase::Group(index) => group_with_times(mdf, index)?.1,
+        TimeBase::Interval(interval) => {
+            let times = inputs.iter().flat_map(|(_, _, times)| times.iter().copied());
+            // NaN (invalid) master values are ignored by min and max.
+            let start = times.clone().fold(f64::INFINITY, f64::min);
+            let end = times.fold(f64::NEG_INFINITY, f64::max);
+            if start <= end { fixed_grid(start, end, interval)? } else { Vec::new() }
+        }
+    };
+
+    let mut writer = MdfWriter::new(output)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+
+    let mut names: Vec<String> = vec!["Time".into()];
+    let mut columns = Vec::new();
+    let mut last = time;
+    let mut block_cache = HashMap::new();
+    for (index, group, times) in &inputs {
+        for channel in group.channels() {
+            if channel.block().channel_type == 2 || !is_numeric(&channel) {
+                continue;
+            }
+            let Some(mut name) = channel.name()? else { continue };
+            if names.contains(&name) {
+                name = format!("{name}_{index}");
+            }
+            let signal = Signal {
+                name: name.clone(),
+                unit: None,
+                timestamps: times.clone(),
+                values: channel.values()?,
+            };
+            let resampled = signal.resample(&grid, options.interpolation, options.invalidation_policy);
+
+            let bit = columns.len() as u32;
+            let cn = writer.add_channel(&cg, Some(&last), |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(name.clone());
+                ch.bit_count = 64;
+                ch.flags |= CN_FLAG_INVAL_BIT_VALID;
+                ch.pos_invalidation_bit = bit;
+            })?;
+            // Unit and comment; link offsets 72 and 80 of the ##CN block.
+            let mmap = mdf.mmap();
+            for (offset, src) in [(72, channel.block().unit_addr), (80, channel.block().comment_addr)] {
+                let addr = writer.copy_linked_block(&|a| mmap.get(a as usize..), src, &mut block_cache)?;
+                if addr != 0 {
+                    let cn_pos = writer.get_block_position(cn).ok_or_else(|| {
+                        MdfError::BlockLinkError(format!("cn '{}' not found", cn))
+                    })?;
+                    writer.update_link(cn_pos + offset, addr)?;
+                }
+            }
+            names.push(name);
+            columns.push(resampled.values);
+            last = cn;
+        }
+    }
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    let mut record = Vec::with_capacity(columns.len() + 1);
+    for (row, &t) in grid.iter().enumerate() {
+        record.clear();
+        record.push(Some(DecodedValue::Float(t)));
+        record.extend(columns.iter().map(|column| match column[row] {
+            Some(DecodedValue::Float(v)) => Some(DecodedValue::Float(v)),
+            Some(DecodedValue::UnsignedInteger(v)) => Some(DecodedValue::Float(v as f64)),
+            Some(DecodedValue::SignedInteger(v)) => Some(DecodedValue::Float(v as f64)),
+            _ => None,
+        }));
+        writer.write_record_with_validity(&cg, &record)?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    Ok(grid.len() as u64)
+}
+
+/// Channel group `index` of `mdf` with its master values.
+fn group_with_times(mdf: &MDF, index: usize) -> Result<(ChannelGroup<'_>, Vec<f64>), MdfError> {
+    let group = mdf
+        .iter_channel_groups()
+        .nth(index)
+        .ok_or_else(|| MdfError::InvalidIndex { kind: "group", index, len: mdf.iter_channel_groups().count() })?;
+    let times = group.time_values()?.ok_or_else(|| {
+        MdfError::BlockSerializationError(format!("channel group {} has no master channel", index))
+    })?;
+    Ok((group, times))
+}
+
+/// Samples per master unit, from the first to the last master value.
+fn sampling_rate(times: &[f64]) -> f64 {
+    match (times.first(), times.last()) {
+        (Some(first), Some(last)) if times.len() > 1 && last > first => {
+            (times.len() - 1) as f64 / (last - first)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Whether the physical values of `channel` are numbers.
+fn is_numeric(channel: &Channel<'_>) -> bool {
+    let block = channel.block();
+    let text = matches!(
+        block.conversion.as_ref().map(|cc| &cc.cc_type),
+        Some(
+            ConversionType::ValueToText
+                | ConversionType::RangeToText
+                | ConversionType::TextToText
+                | ConversionType::BitfieldText
+        )
+    );
+    !text
+        && matches!(
+            block.data_type,
+            DataType::UnsignedIntegerLE
+                | DataType::UnsignedIntegerBE
+                | DataType::SignedIntegerLE
+                | DataType::SignedIntegerBE
+                | DataType::FloatLE
+                | DataType::FloatBE
+        )
+}
+
+/// Write a reduced copy of the file at `input` to `output`, keeping the
+/// records of every channel group selected by `decimation`.
+///
+/// Kept records are copied as they are, so channels keep their data types,
+/// conversions, units and comments, and VLSD channels their entries. The
+/// header, file history, events, attachments and data group comments are
+/// carried over as with
+/// [`CutOptions::preserve_blocks`](crate::cut::CutOptions::preserve_blocks).
+///
+/// ```no_run
+/// # use mf4_rs::resample::{decimate, Decimation};
+/// // A 10 Hz quick-look copy of a 1 kHz recording.
+/// decimate("drive.mf4", "drive_10hz.mf4", Decimation::Interval(0.1))?;
+/// # Ok::<(), mf4_rs::error::MdfError>(())
+/// ```
+///
+/// # Returns
+/// The number of records written, over all channel groups.
+pub fn decimate(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    decimation: Decimation,
+) -> Result<u64, MdfError> {
+    let valid = match decimation {
+        Decimation::Factor(n) => n > 0,
+        Decimation::Interval(interval) => interval.is_finite() && interval > 0.0,
+    };
+    if !valid {
+        return Err(MdfError::BlockSerializationError(format!("invalid decimation {decimation:?}")));
+    }
+    let mdf = MdfFile::parse_from_file(input)?;
+    let mut writer = MdfWriter::new(output)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        mdf.header.abs_time,
+        mdf.header.tz_offset,
+        mdf.header.daylight_save_time,
+        mdf.header.time_flags,
+        mdf.header.time_quality,
+    )?;
+
+    let mut block_cache = HashMap::new();
+    let mut written = 0u64;
+    let sources = source_groups(&mdf);
+    for (dg, dg_sources) in mdf.data_groups.iter().zip(&sources) {
+        for (cg, source) in dg.channel_groups.iter().zip(dg_sources) {
+            // Records kept in `Interval` mode, `None` to decide by position.
+            let keep = match decimation {
+                Decimation::Interval(interval) => {
+                    match ChannelGroup::new(dg, cg, &mdf.mmap).time_values()? {
+                        Some(times) => Some(nearest_to_grid(&times, interval)?),
+                        None => None,
+                    }
+                }
+                Decimation::Factor(_) => None,
+            };
+            let factor = match decimation {
+                Decimation::Factor(n) => n,
+                Decimation::Interval(_) => 1,
+            };
+
+            let (cg_id, out_channels) =
+                copy_group_structure(&mut writer, &mdf.mmap, cg, &ChannelMapping::default(), &mut block_cache)?;
+            let cn_ids: Vec<_> = out_channels.iter().map(|&(id, _, _)| id).collect();
+            map_group(&writer, &mut block_cache, source, &cg_id, &cn_ids);
+            copy_dg_comment(&mut writer, &mdf.mmap, source, &cg_id, &mut block_cache)?;
+
+            let mut index = 0u64;
+            copy_group_records(&mut writer, &mdf.mmap, dg, cg, &cg_id, &out_channels, 0, |_, _| {
+                let kept = match &keep {
+                    Some(keep) => keep.get(index as usize).copied().unwrap_or(false),
+                    None => index.is_multiple_of(factor),
+                };
+                index += 1;
+                if kept {
+                    written += 1;
+                    Ok(RecordAction::Keep)
+                } else {
+                    Ok(RecordAction::Skip)
+                }
+            })?;
+        }
+    }
+
+    HeaderChains::new(&writer)?.append(&mut writer, &mdf, &mut block_cache)?;
+    writer.finalize()?;
+    Ok(written)
+}
+
+/// Which of the records with master values `times` are nearest to a grid
+/// of `interval` from the first to the last value. Records with invalid
+/// (NaN) master values are never kept; ties go to the earlier record.
+fn nearest_to_grid(times: &[f64], interval: f64) -> Result<Vec<bool>, MdfError> {
+    let mut keep = vec![false; times.len()];
+    let valid: Vec<(usize, f64)> = times.iter().copied().enumerate().filter(|(_, t)| !t.is_nan()).collect();
+    let (Some(&(_, start)), Some(&(_, end))) = (valid.first(), valid.last()) else {
+        return Ok(keep);
+    };
+    let mut j = 0;
+    for point in fixed_grid(start, end, interval)? {
+        while j + 1 < valid.len() && (valid[j + 1].1 - point).abs() < (valid[j].1 - point).abs() {
+            j += 1;
+        }
+        keep[valid[j].0] = true;
+    }
+    Ok(keep)
+}
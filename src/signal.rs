@@ -6,6 +6,7 @@
 //!
 //! [`MDF::signal`]: crate::api::mdf::MDF::signal
 
+use crate::error::MdfError;
 use crate::parsing::decoder::DecodedValue;
 
 /// A channel's samples together with the group's master (time) axis.
@@ -42,12 +43,208 @@ impl Signal {
         !self.timestamps.is_empty()
     }
 
+    /// Validity mask, one entry per sample: `false` where `values` holds
+    /// `None`, as expected by plotting and array libraries that keep data
+    /// and mask apart.
+    pub fn validity(&self) -> Vec<bool> {
+        self.values.iter().map(Option::is_some).collect()
+    }
+
+    /// Number of valid samples.
+    pub fn valid_count(&self) -> usize {
+        self.values.iter().filter(|v| v.is_some()).count()
+    }
+
     /// Values as `f64`, with `NaN` for invalid or non-numeric samples.
     pub fn values_f64(&self) -> Vec<f64> {
         self.values.iter().map(decoded_opt_to_f64).collect()
     }
 }
 
+/// How invalid input samples affect a time-aligned output sample.
+///
+/// Used by [`Signal::align_to`] when a channel is resampled onto another
+/// time axis (e.g. to combine channels from different groups or files).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidationPolicy {
+    /// The output sample is invalid if any input sample contributing to it
+    /// is invalid.
+    #[default]
+    AnyInvalid,
+    /// Invalid input samples are skipped; the output is computed from the
+    /// nearest valid samples and is only invalid if none are available.
+    SkipInvalid,
+}
+
+/// How a value between two input samples is computed when a signal is
+/// resampled with [`Signal::resample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Linear interpolation between the bracketing samples; non-numeric
+    /// values hold the previous sample.
+    #[default]
+    Linear,
+    /// Zero-order hold: the previous sample, also after the last one.
+    ZeroOrderHold,
+}
+
+impl Signal {
+    /// Resample this signal onto `timestamps`, propagating invalidity.
+    ///
+    /// Numeric samples are linearly interpolated between the two input
+    /// samples bracketing each target time (the result is a
+    /// [`DecodedValue::Float`]); other values hold the previous sample. An
+    /// exact timestamp match contributes only that sample. Targets outside
+    /// the signal's time range, and every target of a signal without a
+    /// master axis, are invalid. `policy` decides whether invalid
+    /// contributing samples invalidate the output or are skipped.
+    pub fn align_to(&self, timestamps: &[f64], policy: InvalidationPolicy) -> Signal {
+        self.resample(timestamps, Interpolation::Linear, policy)
+    }
+
+    /// Resample this signal onto `timestamps` with the given interpolation.
+    ///
+    /// [`Interpolation::Linear`] behaves as [`align_to`](Self::align_to).
+    /// With [`Interpolation::ZeroOrderHold`] every target takes the last
+    /// sample at or before it unchanged, so only targets before the first
+    /// sample are invalid.
+    pub fn resample(
+        &self,
+        timestamps: &[f64],
+        interpolation: Interpolation,
+        policy: InvalidationPolicy,
+    ) -> Signal {
+        let points: Vec<(f64, Option<&DecodedValue>)> = self
+            .timestamps
+            .iter()
+            .zip(self.values.iter())
+            .map(|(t, v)| (*t, v.as_ref()))
+            .filter(|(_, v)| policy == InvalidationPolicy::AnyInvalid || v.is_some())
+            .collect();
+
+        let values = timestamps
+            .iter()
+            .map(|&t| {
+                let i = points.partition_point(|(pt, _)| *pt <= t);
+                if i == 0 {
+                    return None;
+                }
+                let (t0, v0) = points[i - 1];
+                if t0 == t || interpolation == Interpolation::ZeroOrderHold {
+                    return v0.cloned();
+                }
+                let (t1, v1) = *points.get(i)?;
+                let (v0, v1) = (v0?, v1?);
+                match (value_as_f64(v0), value_as_f64(v1)) {
+                    (Some(y0), Some(y1)) => {
+                        Some(DecodedValue::Float(y0 + (y1 - y0) * (t - t0) / (t1 - t0)))
+                    }
+                    _ => Some(v0.clone()),
+                }
+            })
+            .collect();
+
+        Signal {
+            name: self.name.clone(),
+            unit: self.unit.clone(),
+            timestamps: timestamps.to_vec(),
+            values,
+        }
+    }
+}
+
+/// Summary of a channel's numeric samples, computed in one pass by
+/// [`Channel::statistics`] and [`crate::index::MdfIndex::channel_statistics`].
+///
+/// Only valid numeric samples count; invalid, non-numeric and `NaN` samples
+/// are skipped. Without any such sample `count` is 0 and the other fields are
+/// `NaN`.
+///
+/// [`Channel::statistics`]: crate::api::channel::Channel::statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelStatistics {
+    /// Number of samples the statistics are computed from.
+    pub count: u64,
+    /// Smallest sample.
+    pub min: f64,
+    /// Largest sample.
+    pub max: f64,
+    /// Arithmetic mean.
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+}
+
+/// Accumulates [`ChannelStatistics`] with Welford's algorithm, which stays
+/// accurate for long channels with a large offset.
+pub(crate) struct StatisticsAccumulator {
+    count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl StatisticsAccumulator {
+    pub(crate) fn new() -> Self {
+        StatisticsAccumulator {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Add one sample; `NaN` is skipped.
+    pub(crate) fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub(crate) fn finish(&self) -> ChannelStatistics {
+        if self.count == 0 {
+            return ChannelStatistics { count: 0, min: f64::NAN, max: f64::NAN, mean: f64::NAN, std: f64::NAN };
+        }
+        ChannelStatistics {
+            count: self.count,
+            min: self.min,
+            max: self.max,
+            mean: self.mean,
+            std: (self.m2 / self.count as f64).sqrt(),
+        }
+    }
+}
+
+/// `start, start + interval, ...` up to `end`.
+pub(crate) fn fixed_grid(start: f64, end: f64, interval: f64) -> Result<Vec<f64>, MdfError> {
+    if !(interval.is_finite() && interval > 0.0) {
+        return Err(MdfError::BlockSerializationError(format!(
+            "resample interval must be positive, got {}",
+            interval
+        )));
+    }
+    // Tolerate rounding so that an end on the grid is kept.
+    let steps = ((end - start) / interval + 1e-9).floor().max(0.0) as usize;
+    Ok((0..=steps).map(|i| start + i as f64 * interval).collect())
+}
+
+fn value_as_f64(v: &DecodedValue) -> Option<f64> {
+    match v {
+        DecodedValue::Float(f) => Some(*f),
+        DecodedValue::UnsignedInteger(u) => Some(*u as f64),
+        DecodedValue::SignedInteger(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
 /// Map an optional decoded value to `f64` (`NaN` for `None`/non-numeric).
 pub(crate) fn decoded_opt_to_f64(v: &Option<DecodedValue>) -> f64 {
     match v {
@@ -0,0 +1,241 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::blocks::channel_group_block::{ChannelGroupBlock, CG_FLAG_VLSD};
+use crate::blocks::common::BlockParse;
+use crate::cut::{copy_group_structure, ChannelMapping};
+use crate::error::MdfError;
+use crate::parsing::mdf_file::MdfFile;
+use crate::parsing::raw_channel::{RawChannel, Records};
+use crate::parsing::raw_channel_group::RawChannelGroup;
+use crate::parsing::raw_data_group::{RawDataGroup, RecordStream};
+use crate::writer::{ChannelId, MdfWriter};
+
+/// Sequential reader over the records of one data group, yielding the
+/// records of a single channel group.
+///
+/// The data blocks are read one at a time and walked in place; only a
+/// record straddling two blocks is copied. Each call to
+/// [`next_record`](Self::next_record) scans forward to the next record
+/// carrying `record_id`, so several readers can walk the same data group in
+/// lockstep (e.g. a channel group and the VLSD channel group holding its
+/// signal data).
+pub(crate) struct RecordReader<'a> {
+    stream: RecordStream<'a>,
+    record_id_len: usize,
+    /// Record ID → payload length, or `None` for VLSD channel groups whose
+    /// records carry their own `u32` length prefix.
+    lengths: HashMap<u64, Option<usize>>,
+    record_id: u64,
+}
+
+impl<'a> RecordReader<'a> {
+    pub(crate) fn new(dg: &'a RawDataGroup, mmap: &'a [u8], cg: &ChannelGroupBlock) -> Result<Self, MdfError> {
+        let record_length = |cg: &ChannelGroupBlock| {
+            if cg.flags & CG_FLAG_VLSD != 0 {
+                None
+            } else {
+                Some(cg.samples_byte_nr as usize + cg.invalidation_bytes_nr as usize)
+            }
+        };
+        let record_id_len = dg.block.record_id_len as usize;
+        // Without record IDs the data holds this group's records only.
+        let lengths = if record_id_len == 0 {
+            HashMap::from([(cg.record_id, record_length(cg))])
+        } else {
+            dg.channel_groups
+                .iter()
+                .map(|g| (g.block.record_id, record_length(&g.block)))
+                .collect()
+        };
+        Ok(RecordReader {
+            stream: RecordStream::new(dg.data_blocks(mmap)),
+            record_id_len,
+            lengths,
+            record_id: cg.record_id,
+        })
+    }
+
+    /// The payload (without record ID, and without length prefix for VLSD
+    /// records) of the next record of this reader's channel group.
+    pub(crate) fn next_record(&mut self) -> Option<Result<&[u8], MdfError>> {
+        self.read_record().transpose()
+    }
+
+    fn read_record(&mut self) -> Result<Option<&[u8]>, MdfError> {
+        loop {
+            let record_id = if self.record_id_len == 0 {
+                self.record_id
+            } else {
+                let Some(id_bytes) = self.stream.take(self.record_id_len)? else {
+                    return Ok(None);
+                };
+                let mut buf = [0u8; 8];
+                buf[..self.record_id_len].copy_from_slice(id_bytes);
+                u64::from_le_bytes(buf)
+            };
+            let len = match self.lengths.get(&record_id) {
+                Some(Some(len)) => *len,
+                Some(None) => {
+                    let Some(len_bytes) = self.stream.take(4)? else {
+                        return Ok(None);
+                    };
+                    u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize
+                }
+                None => {
+                    return Err(MdfError::BlockLinkError(format!(
+                        "record ID {} matches no channel group",
+                        record_id
+                    )));
+                }
+            };
+            // A trailing partial record is dropped, as when reading.
+            if record_id == self.record_id {
+                return self.stream.take(len);
+            }
+            if self.stream.take(len)?.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// Where the VLSD entries of one output channel come from.
+pub(crate) enum VlsdSource<'a> {
+    /// A VLSD channel group of the same data group.
+    Group(RecordReader<'a>),
+    /// A `##SD` chain.
+    Signal(Records<'a>),
+}
+
+impl<'a> VlsdSource<'a> {
+    /// The entries of the VLSD channel `channel` of `cg`, in record order.
+    pub(crate) fn open(
+        dg: &'a RawDataGroup,
+        cg: &'a RawChannelGroup,
+        channel: &'a RawChannel,
+        mmap: &'a [u8],
+    ) -> Result<Self, MdfError> {
+        let data = channel.block.data;
+        Ok(if mmap.get(data as usize..data as usize + 4) == Some(b"##CG") {
+            let vlsd_group = ChannelGroupBlock::from_bytes(&mmap[data as usize..])?;
+            VlsdSource::Group(RecordReader::new(dg, mmap, &vlsd_group)?)
+        } else {
+            VlsdSource::Signal(channel.records(dg, cg, mmap)?)
+        })
+    }
+
+    /// The next entry, erroring when there are fewer entries than records.
+    pub(crate) fn next_entry(&mut self) -> Result<&[u8], MdfError> {
+        let entry = match self {
+            VlsdSource::Group(reader) => reader.next_record().transpose()?,
+            VlsdSource::Signal(records) => records.next_record().transpose()?,
+        };
+        entry.ok_or_else(|| MdfError::BlockSerializationError(
+            "VLSD entry count fewer than parent records".into(),
+        ))
+    }
+}
+
+/// Rewrite an MDF file so that every data group holds exactly one channel
+/// group and its records in contiguous data blocks.
+///
+/// Unsorted data groups interleave the records of several channel groups,
+/// told apart by a leading record ID. Each of their channel groups is
+/// written to a data group of its own, with the record IDs stripped. VLSD
+/// channel groups are folded into `##SD` blocks of the channels referencing
+/// them, with the inline offsets of the parent records patched to match.
+/// Channel groups of data groups that are already sorted are copied as they
+/// are. Channel and channel-group names, sources, conversions, units and
+/// comments are carried over, as in [`cut_mdf_by_time`](crate::cut::cut_mdf_by_time).
+///
+/// Records are streamed from the memory-mapped input one channel group at a
+/// time, so the input may be larger than the available memory. The VLSD
+/// payloads of a channel group are buffered until its data block is closed.
+///
+/// # Arguments
+/// * `input_path` - Path to the (possibly unsorted) source MF4 file
+/// * `output_path` - Destination path for the sorted file
+///
+/// # Returns
+/// `Ok(())` on success or an [`MdfError`] if reading or writing fails.
+pub fn sort_mdf(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<(), MdfError> {
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let mmap: &[u8] = &mdf.mmap;
+    let mut writer = MdfWriter::new(output_path)?;
+    writer.init_mdf_file()?;
+    writer.set_start_time(
+        mdf.header.abs_time,
+        mdf.header.tz_offset,
+        mdf.header.daylight_save_time,
+        mdf.header.time_flags,
+        mdf.header.time_quality,
+    )?;
+
+    let mut block_cache: HashMap<u64, u64> = HashMap::new();
+
+    for dg in &mdf.data_groups {
+        let record_id_len = dg.block.record_id_len;
+        if !matches!(record_id_len, 0 | 1 | 2 | 4 | 8) {
+            return Err(MdfError::BlockSerializationError(format!(
+                "record ID length must be 0, 1, 2, 4 or 8 bytes, found {}",
+                record_id_len
+            )));
+        }
+        for cg in &dg.channel_groups {
+            if cg.block.flags & CG_FLAG_VLSD != 0 {
+                // Written as the ##SD data of the channels referencing it.
+                continue;
+            }
+            let (cg_id, out_channels) = copy_group_structure(&mut writer, mmap, cg, &ChannelMapping::default(), &mut block_cache)?;
+            writer.start_data_block_for_cg_raw(
+                &cg_id,
+                0,
+                cg.block.samples_byte_nr,
+                cg.block.invalidation_bytes_nr,
+            )?;
+
+            // Per VLSD channel: output channel, inline offset slot in the
+            // parent record, running offset into the new ##SD data and the
+            // source of its entries.
+            let mut vlsd: Vec<(ChannelId, usize, usize, u64, VlsdSource)> = Vec::new();
+            for (cn_id, src_idx, is_vlsd) in &out_channels {
+                if !*is_vlsd {
+                    continue;
+                }
+                let raw_channel = &cg.raw_channels[*src_idx];
+                let source = VlsdSource::open(dg, cg, raw_channel, mmap)?;
+                writer.start_signal_data_block(cn_id)?;
+                vlsd.push((
+                    *cn_id,
+                    raw_channel.block.byte_offset as usize,
+                    (raw_channel.block.bit_count / 8).min(8) as usize,
+                    0,
+                    source,
+                ));
+            }
+
+            let mut records = RecordReader::new(dg, mmap, &cg.block)?;
+            while let Some(record) = records.next_record() {
+                let mut record = Cow::Borrowed(record?);
+                for (cn_id, slot_off, slot_size, next_offset, source) in vlsd.iter_mut() {
+                    let entry = source.next_entry()?;
+                    if let Some(slot) = record.to_mut().get_mut(*slot_off..*slot_off + *slot_size) {
+                        slot.copy_from_slice(&next_offset.to_le_bytes()[..*slot_size]);
+                    }
+                    writer.write_signal_data(cn_id, entry)?;
+                    *next_offset += 4 + entry.len() as u64;
+                }
+                writer.write_raw_record(&cg_id, &record)?;
+            }
+
+            writer.finish_data_block(&cg_id)?;
+            for (cn_id, ..) in &vlsd {
+                writer.finish_signal_data_block(cn_id)?;
+            }
+        }
+    }
+
+    writer.finalize()
+}
@@ -0,0 +1,136 @@
+//! Re-creation of a file from its [`MdfIndex`] and a data source.
+//!
+//! [`transcode`] rebuilds the structure recorded in an index and streams the
+//! records of every channel group from a [`ByteRangeReader`] into a new file,
+//! so a remote recording can be turned into a local (e.g. compressed) copy
+//! without downloading it to a temporary file first.
+
+use std::path::Path;
+
+use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::text_block::TextBlock;
+use crate::error::MdfError;
+use crate::index::{ByteRangeReader, IndexedChannel, MdfIndex};
+use crate::writer::{ChannelGroupId, ChannelId, CompressionMethod, MdfWriter};
+
+/// Options for [`transcode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TranscodeOptions {
+    /// Compression of the output's data blocks.
+    pub compression: CompressionMethod,
+    /// Data section size of the output's data blocks, see
+    /// [`MdfWriter::set_data_block_size`]; `None` keeps the writer default.
+    pub data_block_size: Option<usize>,
+}
+
+/// Write a new file at `output_path` holding the channel groups described by
+/// `index`, with their records read from `reader`.
+///
+/// Every channel group gets a data group of its own, with its channels in
+/// index order at their original record positions. Names, units, comments
+/// and conversions of groups and channels, and the measurement start time,
+/// are taken from the index; other metadata (sources, attachments, events,
+/// header comment) is not part of an index and is not carried over. Records
+/// are copied verbatim, invalidation bytes included, one data block at a
+/// time; record IDs are dropped.
+///
+/// Returns an error for channel groups with VLSD channels, whose signal data
+/// an index does not describe, or if `reader` does not hold the file the
+/// index was built from ([`MdfError::StaleIndex`]).
+pub fn transcode<R: ByteRangeReader<Error = MdfError>>(
+    index: &MdfIndex,
+    reader: &mut R,
+    options: &TranscodeOptions,
+    output_path: impl AsRef<Path>,
+) -> Result<(), MdfError> {
+    if let Some(channel) = index.groups().iter().flat_map(|g| &g.channels).find(|c| c.is_vlsd()) {
+        return Err(MdfError::UnsupportedFeature(format!(
+            "VLSD channel '{}' cannot be transcoded from an index",
+            channel.name.as_deref().unwrap_or("<unnamed>")
+        )));
+    }
+
+    let mut writer = MdfWriter::new(output_path)?;
+    writer.init_mdf_file()?;
+    if let Some(start_time_ns) = index.start_time_ns {
+        writer.set_start_time(start_time_ns, 0, 0, 0, 0)?;
+    }
+    writer.set_compression(options.compression)?;
+    if let Some(bytes) = options.data_block_size {
+        writer.set_data_block_size(bytes)?;
+    }
+
+    for group in index.groups() {
+        let cg_id = writer.add_channel_group(None, |_| {})?;
+        if let Some(name) = &group.name {
+            writer.set_channel_group_name(&cg_id, name)?;
+        }
+        if let Some(comment) = &group.comment {
+            writer.set_channel_group_comment(&cg_id, comment)?;
+        }
+        let mut prev_cn: Option<ChannelId> = None;
+        for channel in &group.channels {
+            prev_cn = Some(copy_channel(&mut writer, &cg_id, prev_cn.as_ref(), channel)?);
+        }
+
+        // Records without a single byte cannot be stored in a data block.
+        if group.record_size + group.invalidation_bytes > 0 {
+            writer.start_data_block_for_cg_raw(&cg_id, 0, group.record_size, group.invalidation_bytes)?;
+            let id_len = group.record_id_len as usize;
+            let record_size = id_len + group.record_size as usize + group.invalidation_bytes as usize;
+            for data_block in &group.data_blocks {
+                let data = MdfIndex::read_block_data(data_block, reader)?;
+                let records = &data[..data.len() - data.len() % record_size];
+                if id_len == 0 {
+                    writer.write_raw_records(&cg_id, records)?;
+                } else {
+                    let stripped: Vec<u8> =
+                        records.chunks_exact(record_size).flat_map(|r| &r[id_len..]).copied().collect();
+                    writer.write_raw_records(&cg_id, &stripped)?;
+                }
+            }
+            writer.finish_data_block(&cg_id)?;
+        }
+    }
+    writer.finalize()
+}
+
+/// Add `channel` to `cg_id`, with its unit, comment and conversion.
+fn copy_channel(
+    writer: &mut MdfWriter,
+    cg_id: &ChannelGroupId,
+    prev_cn: Option<&ChannelId>,
+    channel: &IndexedChannel,
+) -> Result<ChannelId, MdfError> {
+    let cn_id = writer.add_channel(cg_id, prev_cn, |ch| {
+        *ch = ChannelBlock {
+            channel_type: channel.channel_type,
+            // Masters are taken to be time masters.
+            sync_type: u8::from(matches!(channel.channel_type, 2 | 3)),
+            data_type: channel.data_type.clone(),
+            bit_offset: channel.bit_offset,
+            byte_offset: channel.byte_offset,
+            bit_count: channel.bit_count,
+            flags: channel.flags,
+            pos_invalidation_bit: channel.pos_invalidation_bit,
+            name: channel.name.clone(),
+            ..ChannelBlock::default()
+        };
+    })?;
+
+    // Channel block link offsets: conversion 56, unit 72, comment 80.
+    let cn_pos = writer.get_block_position(cn_id).ok_or_else(|| {
+        MdfError::BlockLinkError(format!("cn '{}' not found", cn_id))
+    })?;
+    if let Some(conversion) = &channel.conversion {
+        let cc_pos = writer.write_resolved_conversion(conversion, None)?;
+        writer.update_link(cn_pos + 56, cc_pos)?;
+    }
+    for (offset, text) in [(72, &channel.unit), (80, &channel.comment)] {
+        if let Some(text) = text {
+            let tx_pos = writer.write_block(&TextBlock::new(text).to_bytes()?)?;
+            writer.update_link(cn_pos + offset, tx_pos)?;
+        }
+    }
+    Ok(cn_id)
+}
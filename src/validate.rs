@@ -0,0 +1,437 @@
+//! Structural validation of MDF 4 files.
+//!
+//! [`validate_file`] walks every block reachable from the header and reports
+//! broken links, inconsistent block lengths, misaligned blocks, unknown block
+//! IDs and data blocks that do not hold the records announced by the channel
+//! groups' cycle counts. Unlike the parser, the walk does not stop at the
+//! first problem, so a single report lists everything wrong with a file.
+//!
+//! [`record_statistics`] goes one level deeper for files that parse: it
+//! walks the record stream of every data group and counts the records per
+//! record ID, showing where an unsorted stream stops matching its channel
+//! groups.
+
+use std::collections::HashSet;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+
+use byteorder::{ByteOrder, LittleEndian};
+use serde::{Deserialize, Serialize};
+
+use crate::api::mdf::MDF;
+use crate::blocks::channel_group_block::CG_FLAG_VLSD;
+use crate::blocks::common::BlockHeader;
+use crate::blocks::data_zipped_block::DataZippedBlock;
+use crate::error::MdfError;
+use crate::parsing::raw_data_group::RawDataGroup;
+
+/// Block IDs defined by MDF 4.x.
+const KNOWN_BLOCK_IDS: [&str; 25] = [
+    "##HD", "##MD", "##TX", "##FH", "##CH", "##AT", "##EV", "##DG", "##CG", "##SI", "##CN", "##CC",
+    "##CA", "##DT", "##SR", "##RD", "##SD", "##DL", "##LD", "##DZ", "##HL", "##RV", "##RI", "##DV",
+    "##DI",
+];
+
+/// Minimum `block_len` of blocks with a fixed data section.
+const MIN_BLOCK_LEN: [(&str, u64); 6] =
+    [("##HD", 104), ("##FH", 56), ("##DG", 64), ("##CG", 104), ("##SI", 56), ("##CN", 160)];
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    /// The file deviates from the specification but can still be read.
+    Warning,
+    /// The file is broken; readers will fail or return wrong data.
+    Error,
+}
+
+/// A problem found by [`validate_file`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// File offset of the block the issue concerns.
+    pub offset: u64,
+    pub message: String,
+}
+
+/// Result of [`validate_file`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Number of blocks with a valid header, including the identification
+    /// block.
+    pub blocks_checked: usize,
+    /// Issues in the order they were found.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// `true` if no issue has [`Severity::Error`].
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Issues with [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    /// Issues with [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, severity: Severity, offset: u64, message: String) {
+        self.issues.push(ValidationIssue { severity, offset, message });
+    }
+}
+
+/// Validate the MDF 4 file at `path`.
+///
+/// Problems with the file's contents are returned in the report; `Err` is
+/// only returned when the file cannot be read.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn validate_file(path: impl AsRef<Path>) -> Result<ValidationReport, MdfError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    Ok(validate_bytes(&mmap))
+}
+
+/// Validate an in-memory MDF 4 image; see [`validate_file`].
+pub fn validate_bytes(data: &[u8]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    if data.len() < 64 || &data[0..8] != b"MDF     " {
+        report.push(Severity::Error, 0, "missing \"MDF\" file identifier".into());
+        return report;
+    }
+    let version = LittleEndian::read_u16(&data[28..30]);
+    if version < 400 {
+        report.push(Severity::Error, 0, format!("version {version} is not MDF 4.x"));
+        return report;
+    }
+    if LittleEndian::read_u16(&data[60..62]) != 0 {
+        report.push(Severity::Warning, 0, "file is not finalized".into());
+    }
+
+    let blocks = walk_blocks(data, &mut report);
+    report.blocks_checked = blocks.len() + 1;
+    check_cycle_counts(data, &blocks, &mut report);
+    report
+}
+
+/// Visit every block reachable from `##HD` and check its header and links.
+/// Returns the addresses of the blocks with a valid header.
+fn walk_blocks(data: &[u8], report: &mut ValidationReport) -> HashSet<u64> {
+    let mut valid = HashSet::new();
+    let mut visited = HashSet::new();
+    // (address, offset of the block holding the link)
+    let mut pending = vec![(64u64, 0u64)];
+    while let Some((addr, from)) = pending.pop() {
+        if !visited.insert(addr) {
+            continue;
+        }
+        if addr % 8 != 0 {
+            report.push(Severity::Warning, addr, "block is not 8-byte aligned".into());
+        }
+        let Some(header) = header_at(data, addr) else {
+            report.push(
+                Severity::Error,
+                from,
+                format!("link to 0x{addr:x} points past the end of the file"),
+            );
+            continue;
+        };
+        if !KNOWN_BLOCK_IDS.contains(&header.id.as_str()) {
+            report.push(
+                Severity::Error,
+                from,
+                format!("link to 0x{addr:x} targets unknown block ID {:?}", header.id),
+            );
+            continue;
+        }
+        if addr == 64 && header.id != "##HD" {
+            report.push(Severity::Error, addr, format!("expected ##HD, found {}", header.id));
+            continue;
+        }
+
+        let links_end = header.links_nr.saturating_mul(8).saturating_add(24);
+        if header.block_len < links_end {
+            report.push(
+                Severity::Error,
+                addr,
+                format!(
+                    "{} block length {} is shorter than its {} links",
+                    header.id, header.block_len, header.links_nr
+                ),
+            );
+            continue;
+        }
+        if let Some(&(_, min)) = MIN_BLOCK_LEN.iter().find(|(id, _)| *id == header.id)
+            && header.block_len < min
+        {
+            report.push(
+                Severity::Error,
+                addr,
+                format!("{} block length {} is below the minimum of {}", header.id, header.block_len, min),
+            );
+            continue;
+        }
+        if addr.saturating_add(header.block_len) > data.len() as u64 {
+            report.push(
+                Severity::Error,
+                addr,
+                format!("{} block of {} bytes extends past the end of the file", header.id, header.block_len),
+            );
+            continue;
+        }
+        valid.insert(addr);
+        for i in 0..header.links_nr {
+            let target = read_u64(data, addr + 24 + i * 8);
+            if target != 0 {
+                pending.push((target, addr));
+            }
+        }
+    }
+    valid
+}
+
+/// Compare the data bytes of every data group with the cycle counts of its
+/// channel groups.
+fn check_cycle_counts(data: &[u8], valid: &HashSet<u64>, report: &mut ValidationReport) {
+    let mut visited = HashSet::new();
+    let mut dg = read_u64(data, 64 + 24);
+    while dg != 0 && valid.contains(&dg) && visited.insert(dg) {
+        let Some(dg_header) = header_at(data, dg) else { break };
+        let record_id_len = read_uint(data, dg + 24 + dg_header.links_nr * 8, 1);
+
+        let mut expected = 0u64;
+        let mut variable = false;
+        let mut cg = read_u64(data, dg + 32);
+        while cg != 0 && valid.contains(&cg) && visited.insert(cg) {
+            let Some(cg_header) = header_at(data, cg) else { break };
+            let fields = cg + 24 + cg_header.links_nr * 8;
+            let cycles = read_u64(data, fields + 8);
+            let flags = read_uint(data, fields + 16, 2);
+            let data_bytes = read_uint(data, fields + 24, 4);
+            let inval_bytes = read_uint(data, fields + 28, 4);
+            variable |= flags & u64::from(CG_FLAG_VLSD) != 0;
+            expected = expected.saturating_add(cycles.saturating_mul(record_id_len + data_bytes + inval_bytes));
+            cg = read_u64(data, cg + 24);
+        }
+
+        if !variable {
+            let actual = data_size(data, valid, read_u64(data, dg + 40), &mut HashSet::new());
+            if actual < expected {
+                report.push(
+                    Severity::Error,
+                    dg,
+                    format!("cycle counts need {expected} bytes of records but the data blocks hold {actual}"),
+                );
+            } else if actual > expected {
+                report.push(
+                    Severity::Warning,
+                    dg,
+                    format!("data blocks hold {actual} bytes but the cycle counts only cover {expected}"),
+                );
+            }
+        }
+        dg = read_u64(data, dg + 24);
+    }
+}
+
+/// Number of (uncompressed) record bytes stored in the data block or list at
+/// `addr`.
+fn data_size(data: &[u8], valid: &HashSet<u64>, addr: u64, visited: &mut HashSet<u64>) -> u64 {
+    if addr == 0 || !valid.contains(&addr) || !visited.insert(addr) {
+        return 0;
+    }
+    let Some(header) = header_at(data, addr) else { return 0 };
+    match header.id.as_str() {
+        "##DT" | "##RD" | "##DV" | "##SD" => header.block_len - 24,
+        "##DZ" => DataZippedBlock::read_org_data_length(&data[addr as usize..]).unwrap_or(0),
+        // ##HL links its first ##DL; a ##DL links the next ##DL and its
+        // data blocks.
+        "##HL" => data_size(data, valid, read_u64(data, addr + 24), visited),
+        "##DL" => (0..header.links_nr)
+            .map(|i| data_size(data, valid, read_u64(data, addr + 24 + i * 8), visited))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// The block header at `addr`, if one fits into the file there.
+fn header_at(data: &[u8], addr: u64) -> Option<BlockHeader> {
+    let start = usize::try_from(addr).ok()?;
+    BlockHeader::from_bytes(data.get(start..start.checked_add(24)?)?).ok()
+}
+
+fn read_u64(data: &[u8], pos: u64) -> u64 {
+    read_uint(data, pos, 8)
+}
+
+/// The little-endian unsigned integer of `len` bytes at `pos`, or `0` past
+/// the end of the file.
+fn read_uint(data: &[u8], pos: u64, len: usize) -> u64 {
+    usize::try_from(pos)
+        .ok()
+        .and_then(|p| data.get(p..p.checked_add(len)?))
+        .map_or(0, |bytes| LittleEndian::read_uint(bytes, len))
+}
+
+/// Records found for one record ID of a data group; see
+/// [`record_statistics`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordIdStatistics {
+    pub record_id: u64,
+    /// Position of the channel group with this record ID within the data
+    /// group, `None` if no channel group declares it.
+    pub channel_group: Option<usize>,
+    /// Number of records found.
+    pub count: u64,
+    /// Bytes taken by the records found, including record IDs and the
+    /// length prefixes of VLSD records.
+    pub bytes: u64,
+    /// Cycle count declared by the channel group.
+    pub declared_cycles: Option<u64>,
+    /// Bytes the declared cycle count needs, for groups with fixed-length
+    /// records.
+    pub declared_bytes: Option<u64>,
+}
+
+/// The records of one data group, walked record by record; see
+/// [`record_statistics`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataGroupRecords {
+    /// Position of the data group in the file, counted from zero.
+    pub data_group: usize,
+    /// Length of the record IDs in bytes (0 for sorted groups without IDs).
+    pub record_id_len: u8,
+    /// Record bytes stored in the data blocks.
+    pub data_bytes: u64,
+    /// Bytes taken by complete records of known record IDs, from the start
+    /// of the data.
+    pub consumed_bytes: u64,
+    /// Every declared record ID in channel group order, followed by the
+    /// unknown one that ended the walk, if any.
+    pub records: Vec<RecordIdStatistics>,
+    /// Why the walk ended before the end of the data, e.g. an unknown
+    /// record ID or a truncated record.
+    pub stopped: Option<String>,
+}
+
+impl DataGroupRecords {
+    /// `true` if the whole data was consumed and every record ID was found
+    /// as often as its channel group declares.
+    pub fn is_consistent(&self) -> bool {
+        self.stopped.is_none()
+            && self.consumed_bytes == self.data_bytes
+            && self.records.iter().all(|r| Some(r.count) == r.declared_cycles)
+    }
+}
+
+/// Walk the records of every data group of `mdf` and count them per record
+/// ID, to debug unsorted files whose record stream does not match their
+/// channel groups.
+///
+/// The walk of a data group ends at the first record with an unknown
+/// record ID or that does not fit into the remaining data, which is
+/// reported in [`DataGroupRecords::stopped`]. `Err` is only returned when
+/// the data blocks cannot be read.
+pub fn record_statistics(mdf: &MDF) -> Result<Vec<DataGroupRecords>, MdfError> {
+    mdf.raw()
+        .data_groups
+        .iter()
+        .enumerate()
+        .map(|(index, dg)| data_group_records(index, dg, mdf.mmap()))
+        .collect()
+}
+
+fn data_group_records(index: usize, dg: &RawDataGroup, mmap: &[u8]) -> Result<DataGroupRecords, MdfError> {
+    let record_id_len = dg.block.record_id_len;
+    let mut records: Vec<RecordIdStatistics> = dg
+        .channel_groups
+        .iter()
+        .enumerate()
+        .map(|(i, cg)| {
+            let vlsd = cg.block.flags & CG_FLAG_VLSD != 0;
+            let record_len = u64::from(record_id_len)
+                + u64::from(cg.block.samples_byte_nr)
+                + u64::from(cg.block.invalidation_bytes_nr);
+            RecordIdStatistics {
+                record_id: cg.block.record_id,
+                channel_group: Some(i),
+                count: 0,
+                bytes: 0,
+                declared_cycles: Some(cg.block.cycles_nr),
+                declared_bytes: (!vlsd).then(|| cg.block.cycles_nr.saturating_mul(record_len)),
+            }
+        })
+        .collect();
+
+    let mut stream = Vec::new();
+    for block in dg.data_blocks(mmap) {
+        stream.extend_from_slice(&block?.data);
+    }
+    let id_len = record_id_len as usize;
+    let mut pos = 0;
+    let mut stopped = None;
+    if !matches!(id_len, 0 | 1 | 2 | 4 | 8) {
+        stopped = Some(format!("record ID length {id_len} is not 0, 1, 2, 4 or 8 bytes"));
+    } else if id_len == 0 && dg.channel_groups.len() > 1 {
+        stopped = Some(format!("{} channel groups share the data without record IDs", dg.channel_groups.len()));
+    }
+    while stopped.is_none() && pos < stream.len() {
+        let record_id = if id_len == 0 {
+            dg.channel_groups.first().map_or(0, |cg| cg.block.record_id)
+        } else {
+            let Some(bytes) = stream.get(pos..pos + id_len) else {
+                stopped = Some(format!("truncated record ID at data offset {pos}"));
+                break;
+            };
+            LittleEndian::read_uint(bytes, id_len)
+        };
+        let Some(i) = dg.channel_groups.iter().position(|cg| cg.block.record_id == record_id) else {
+            records.push(RecordIdStatistics {
+                record_id,
+                channel_group: None,
+                count: 1,
+                bytes: 0,
+                declared_cycles: None,
+                declared_bytes: None,
+            });
+            stopped = Some(format!("record ID {record_id} at data offset {pos} matches no channel group"));
+            break;
+        };
+        let cg = &dg.channel_groups[i].block;
+        let end = if cg.flags & CG_FLAG_VLSD != 0 {
+            let start = pos + id_len;
+            stream
+                .get(start..start + 4)
+                .map(|len| start + 4 + LittleEndian::read_u32(len) as usize)
+        } else {
+            Some(pos + id_len + cg.samples_byte_nr as usize + cg.invalidation_bytes_nr as usize)
+        };
+        match end {
+            Some(end) if end <= stream.len() => {
+                records[i].count += 1;
+                records[i].bytes += (end - pos) as u64;
+                pos = end;
+            }
+            _ => {
+                stopped = Some(format!(
+                    "record ID {record_id} at data offset {pos} is truncated ({} bytes left)",
+                    stream.len() - pos
+                ));
+            }
+        }
+    }
+
+    Ok(DataGroupRecords {
+        data_group: index,
+        record_id_len,
+        data_bytes: stream.len() as u64,
+        consumed_bytes: pos as u64,
+        records,
+        stopped,
+    })
+}
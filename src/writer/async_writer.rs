@@ -0,0 +1,216 @@
+//! Async front end of [`MdfWriter`] for tokio-based loggers.
+//!
+//! [`AsyncMdfWriter`] drives an ordinary [`MdfWriter`], so all block layout
+//! and bookkeeping is shared with the sync writer. The inner writer's output
+//! goes to an in-memory spool of positioned writes, which is written to a
+//! [`tokio::fs::File`] without blocking the runtime.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::error::MdfError;
+use crate::parsing::decoder::DecodedValue;
+use crate::writer::mdf_writer::WriteSeek;
+use crate::writer::{ChannelGroupId, MdfWriter};
+
+/// Spooled bytes after which [`AsyncMdfWriter`] writes them to the file.
+const DEFAULT_SPOOL_CAPACITY: usize = 1_048_576;
+
+/// Writes of the inner writer not yet applied to the file.
+#[derive(Default)]
+struct SpoolState {
+    pos: u64,
+    len: u64,
+    /// `(file offset, bytes)` in write order; consecutive appends are merged.
+    pending: Vec<(u64, Vec<u8>)>,
+    pending_bytes: usize,
+}
+
+/// `Write + Seek` sink handed to the inner [`MdfWriter`].
+#[derive(Clone, Default)]
+struct Spool(Arc<Mutex<SpoolState>>);
+
+impl Spool {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SpoolState> {
+        // A poisoned lock only means a panic elsewhere; the state is still
+        // a consistent list of writes.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn take(&self) -> Vec<(u64, Vec<u8>)> {
+        let mut state = self.lock();
+        state.pending_bytes = 0;
+        std::mem::take(&mut state.pending)
+    }
+
+    fn pending_bytes(&self) -> usize {
+        self.lock().pending_bytes
+    }
+}
+
+impl Write for Spool {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self.lock();
+        let pos = state.pos;
+        match state.pending.last_mut() {
+            Some((start, bytes)) if *start + bytes.len() as u64 == pos => bytes.extend_from_slice(buf),
+            _ => state.pending.push((pos, buf.to_vec())),
+        }
+        state.pos += buf.len() as u64;
+        state.len = state.len.max(state.pos);
+        state.pending_bytes += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Spool {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let mut state = self.lock();
+        let new_pos = match pos {
+            SeekFrom::Start(x) => Some(x),
+            SeekFrom::End(x) => state.len.checked_add_signed(x),
+            SeekFrom::Current(x) => state.pos.checked_add_signed(x),
+        };
+        state.pos = new_pos.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid seek")
+        })?;
+        Ok(state.pos)
+    }
+}
+
+/// MDF writer whose record writes are async and go to a
+/// [`tokio::fs::File`].
+///
+/// Configure the file through [`writer_mut`](Self::writer_mut) (channel
+/// groups, channels, data blocks) and append records with the async
+/// methods. Output is spooled in memory and written to the file once it
+/// exceeds the spool capacity, on [`flush`](Self::flush) and on
+/// [`finalize`](Self::finalize).
+///
+/// # Example
+/// ```no_run
+/// # async fn log() -> Result<(), mf4_rs::error::MdfError> {
+/// use mf4_rs::parsing::decoder::DecodedValue;
+/// use mf4_rs::writer::AsyncMdfWriter;
+///
+/// let mut w = AsyncMdfWriter::create("log.mf4").await?;
+/// let cg = {
+///     let w = w.writer_mut();
+///     w.init_mdf_file()?;
+///     let cg = w.add_channel_group(None, |_| {})?;
+///     w.add_channel(&cg, None, |ch| ch.name = Some("Speed".into()))?;
+///     w.start_data_block_for_cg(&cg, 0)?;
+///     cg
+/// };
+/// w.write_record(&cg, &[DecodedValue::UnsignedInteger(42)]).await?;
+/// w.finish_data_block(&cg).await?;
+/// w.finalize().await
+/// # }
+/// ```
+pub struct AsyncMdfWriter {
+    inner: MdfWriter<Box<dyn WriteSeek + Send>>,
+    spool: Spool,
+    file: tokio::fs::File,
+    capacity: usize,
+}
+
+impl AsyncMdfWriter {
+    /// Create (or truncate) the file at `path`.
+    pub async fn create(path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        Ok(Self::from_file(tokio::fs::File::create(path).await?))
+    }
+
+    /// Write into an already opened file, starting at offset 0.
+    pub fn from_file(file: tokio::fs::File) -> Self {
+        Self::with_capacity(file, DEFAULT_SPOOL_CAPACITY)
+    }
+
+    /// Like [`from_file`](Self::from_file) with a custom spool capacity in
+    /// bytes.
+    pub fn with_capacity(file: tokio::fs::File, capacity: usize) -> Self {
+        let spool = Spool::default();
+        AsyncMdfWriter {
+            inner: MdfWriter::from_writer(Box::new(spool.clone())),
+            spool,
+            file,
+            capacity,
+        }
+    }
+
+    /// The inner sync writer, for everything besides appending records.
+    ///
+    /// Its output is spooled and reaches the file with the next async call.
+    pub fn writer_mut(&mut self) -> &mut MdfWriter<Box<dyn WriteSeek + Send>> {
+        &mut self.inner
+    }
+
+    /// Async version of [`MdfWriter::write_record`].
+    pub async fn write_record(&mut self, cg_id: &ChannelGroupId, values: &[DecodedValue]) -> Result<(), MdfError> {
+        self.inner.write_record(cg_id, values)?;
+        self.flush_if_full().await
+    }
+
+    /// Async version of [`MdfWriter::write_records`].
+    pub async fn write_records<'a, I>(&mut self, cg_id: &ChannelGroupId, records: I) -> Result<(), MdfError>
+    where
+        I: IntoIterator<Item = &'a [DecodedValue]>,
+    {
+        self.inner.write_records(cg_id, records)?;
+        self.flush_if_full().await
+    }
+
+    /// Async version of [`MdfWriter::write_record_with_validity`].
+    pub async fn write_record_with_validity(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        values: &[Option<DecodedValue>],
+    ) -> Result<(), MdfError> {
+        self.inner.write_record_with_validity(cg_id, values)?;
+        self.flush_if_full().await
+    }
+
+    /// Async version of [`MdfWriter::finish_data_block`].
+    pub async fn finish_data_block(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        self.inner.finish_data_block(cg_id)?;
+        self.flush_if_full().await
+    }
+
+    /// Write everything spooled so far, including the inner writer's queued
+    /// header patches (see [`MdfWriter::flush`]), to the file.
+    pub async fn flush(&mut self) -> Result<(), MdfError> {
+        self.inner.flush()?;
+        write_spool(&self.spool, &mut self.file).await
+    }
+
+    /// Async version of [`MdfWriter::finalize`]: run the final checks, then
+    /// write the remaining output and flush the file.
+    pub async fn finalize(self) -> Result<(), MdfError> {
+        let AsyncMdfWriter { inner, spool, mut file, .. } = self;
+        inner.finalize()?;
+        write_spool(&spool, &mut file).await
+    }
+
+    async fn flush_if_full(&mut self) -> Result<(), MdfError> {
+        if self.spool.pending_bytes() >= self.capacity {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply the spooled writes to `file` in order and flush it.
+async fn write_spool(spool: &Spool, file: &mut tokio::fs::File) -> Result<(), MdfError> {
+    for (pos, bytes) in spool.take() {
+        file.seek(SeekFrom::Start(pos)).await?;
+        file.write_all(&bytes).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,339 @@
+// Reopening an existing MDF 4 file to continue writing it
+use super::*;
+use super::handles::Handle;
+use std::collections::HashSet;
+use std::path::Path;
+use byteorder::{ByteOrder, LittleEndian};
+use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::common::{BlockHeader, BlockParse, HD_OFFSET};
+use crate::blocks::channel_group_block::ChannelGroupBlock;
+use crate::blocks::data_group_block::DataGroupBlock;
+use crate::blocks::data_list_block::DataListBlock;
+use crate::blocks::data_zipped_block::DataZippedBlock;
+
+impl MdfWriter {
+    /// Reopen a finished MDF 4 file to continue writing it.
+    ///
+    /// The block structure of the file is read back so the writer handles
+    /// and IDs work as if the file had been written by this writer: data
+    /// groups, channel groups and channels get the handles with index `N`
+    /// for the `N`th block of their type in file order (see
+    /// [`channel_group_id`](MdfWriter::channel_group_id)), file history and
+    /// event blocks the IDs `fh_N` and `ev_N`. New blocks are appended at
+    /// the end of the file.
+    ///
+    /// [`add_channel_group`](MdfWriter::add_channel_group) chains new data
+    /// groups after the existing ones. Opening a data block for an existing
+    /// channel group (e.g. with
+    /// [`start_data_block_for_cg`](MdfWriter::start_data_block_for_cg))
+    /// continues its records: on finish, a `##DL` lists the group's old and
+    /// new data blocks and the cycle count covers both. This is limited to
+    /// groups that own their data group, without VLSD channels, and to the
+    /// record layout the group already has. Until the new data block is
+    /// finished the file still shows the old records only.
+    ///
+    /// Unfinalized files must be finalized first (see
+    /// [`crate::finalize::finalize_file`]).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_append(path: impl AsRef<Path>) -> Result<Self, MdfError> {
+        Self::open_append_with_capacity(path, 1_048_576)
+    }
+
+    /// Like [`open_append`](Self::open_append) with the specified
+    /// `BufWriter` capacity.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_append_with_capacity(path: impl AsRef<Path>, capacity: usize) -> Result<Self, MdfError> {
+        use std::fs::OpenOptions;
+        use std::io::{BufWriter, SeekFrom};
+
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        check_identification(&mmap)?;
+
+        let mut sink = file.try_clone()?;
+        sink.seek(SeekFrom::End(0))?;
+        let mut writer = MdfWriter::new_from_writer(BufWriter::with_capacity(capacity, sink));
+        writer.offset = mmap.len() as u64;
+        writer.restore_blocks(&mmap)?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Register the blocks of the existing file `data` under writer IDs.
+    fn restore_blocks(&mut self, data: &[u8]) -> Result<(), MdfError> {
+        self.block_positions.insert("id_block".into(), 0);
+        self.block_positions.insert("hd_block".into(), HD_OFFSET);
+        // ##HD links: 32 = first file history, 56 = first event.
+        for (prefix, link) in [("fh", 32), ("ev", 56)] {
+            for addr in chain(data, read_u64(data, HD_OFFSET + link)?)? {
+                let id = self.next_block_id(prefix);
+                self.block_positions.insert(id, addr);
+            }
+        }
+
+        let mut cg_addrs = Vec::new();
+        for dg_addr in chain(data, read_u64(data, HD_OFFSET + 24)?)? {
+            self.dg_positions.push(dg_addr);
+            let dg_id = DataGroupId::from_index(self.dg_positions.len() - 1);
+            self.last_dg = Some(dg_id);
+            let dg = DataGroupBlock::from_bytes(block_slice(data, dg_addr)?)?;
+            let cgs = chain(data, dg.first_cg_addr)?;
+            let mut blocks = Vec::new();
+            if dg.data_block_addr != 0 {
+                data_blocks(data, dg.data_block_addr, &mut blocks, &mut HashSet::new())?;
+            }
+
+            for &cg_addr in &cgs {
+                cg_addrs.push(cg_addr);
+                let cg = ChannelGroupBlock::from_bytes(block_slice(data, cg_addr)?)?;
+                self.cg_positions.push(cg_addr);
+                let cg_id = ChannelGroupId::from_index(self.cg_positions.len() - 1);
+                self.cg_to_dg.insert(cg_id, dg_id);
+                self.cg_next_bit.insert(cg_id, cg.samples_byte_nr as usize * 8);
+                let mut channels = Vec::new();
+                let mut ids = Vec::new();
+                for cn_addr in chain(data, cg.first_ch_addr)? {
+                    let mut ch = ChannelBlock::from_bytes(block_slice(data, cn_addr)?)?;
+                    ch.resolve_name(data)?;
+                    self.cn_positions.push(cn_addr);
+                    let cn_id = ChannelId::from_index(self.cn_positions.len() - 1);
+                    self.channel_map.insert(cn_id, (cg_id, channels.len()));
+                    channels.push(ch);
+                    ids.push(cn_id);
+                }
+                self.cg_channels.insert(cg_id, channels);
+                self.cg_channel_ids.insert(cg_id, ids);
+                if dg.data_block_addr != 0 {
+                    self.previous_data.insert(
+                        cg_id,
+                        PreviousData {
+                            blocks: blocks.clone(),
+                            cycles: cg.cycles_nr,
+                            record_id_len: dg.record_id_len,
+                            data_bytes: cg.samples_byte_nr,
+                            invalidation_bytes: cg.invalidation_bytes_nr,
+                            shared: cgs.len() > 1,
+                        },
+                    );
+                }
+            }
+        }
+
+        // Copies of the metadata blocks reachable from the channel groups,
+        // as `write_block` keeps them for blocks written by this writer.
+        let mut visited = HashSet::new();
+        let mut pending = cg_addrs;
+        while let Some(addr) = pending.pop() {
+            if addr == 0 || !visited.insert(addr) {
+                continue;
+            }
+            let block = block_slice(data, addr)?;
+            if !super::io::METADATA_BLOCK_IDS.contains(&&block[..4]) {
+                continue;
+            }
+            let header = BlockHeader::from_bytes(block)?;
+            for i in 0..header.links_nr {
+                pending.push(read_u64(data, addr + 24 + i * 8)?);
+            }
+            if self.keeps_copy(block) {
+                self.metadata_blocks.insert(addr, block.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a data block with the given record layout may be opened
+    /// for `cg_id`. Returns `true` if the group has records from before the
+    /// file was reopened, which the new data blocks continue.
+    pub(super) fn check_continued_group(
+        &self,
+        cg_id: &ChannelGroupId,
+        record_id_len: u8,
+        data_bytes: u32,
+        invalidation_bytes: u32,
+    ) -> Result<bool, MdfError> {
+        let Some(previous) = self.previous_data.get(cg_id) else {
+            return Ok(false);
+        };
+        let message = |reason: &str| format!("cannot continue channel group '{cg_id}' of the reopened file: {reason}");
+        let reject = |reason: &str| Err(MdfError::UnsupportedFeature(message(reason)));
+        if previous.shared {
+            return reject("its data group holds other channel groups");
+        }
+        if self.live_update.is_some() {
+            return reject("live updates are enabled");
+        }
+        let channels = self.cg_channels.get(cg_id).map(Vec::as_slice).unwrap_or_default();
+        if channels.iter().any(|ch| ch.channel_type == 1 && ch.data != 0) {
+            return reject("it has VLSD channels");
+        }
+        if (record_id_len, data_bytes, invalidation_bytes)
+            != (previous.record_id_len, previous.data_bytes, previous.invalidation_bytes)
+        {
+            return Err(MdfError::LayoutMismatch(message("the record layout differs from the existing records")));
+        }
+        Ok(true)
+    }
+
+    /// `true` if `cg_id` continues records written before the file was
+    /// reopened.
+    pub(super) fn is_continued_group(&self, cg_id: &ChannelGroupId) -> bool {
+        self.previous_data.contains_key(cg_id)
+    }
+
+    /// Uncompressed length of the records a continued group had before the
+    /// file was reopened; 0 for other groups.
+    pub(super) fn continued_data_len(&self, cg_id: &ChannelGroupId) -> u64 {
+        self.previous_data.get(cg_id).map_or(0, |p| p.blocks.iter().map(|&(_, len)| len).sum())
+    }
+
+    /// Write a `##DL` listing the earlier data blocks of a continued group
+    /// and link it from the data group, so the group's new blocks can be
+    /// chained after it. Returns its position, or `None` for other groups.
+    pub(super) fn continued_data_list(&mut self, cg_id: &ChannelGroupId) -> Result<Option<u64>, MdfError> {
+        let Some(previous) = self.previous_data.get(cg_id) else {
+            return Ok(None);
+        };
+        let blocks = previous.blocks.clone();
+        self.write_data_list(cg_id, &blocks).map(Some)
+    }
+
+    /// Write a `##DL` listing `blocks` (position and data length) and link
+    /// it from the data group of `cg_id`. Returns its position.
+    pub(super) fn write_data_list(&mut self, cg_id: &ChannelGroupId, blocks: &[(u64, u64)]) -> Result<u64, MdfError> {
+        let mut offset = 0;
+        let mut links = Vec::with_capacity(blocks.len());
+        let mut offsets = Vec::with_capacity(blocks.len());
+        for &(pos, len) in blocks {
+            links.push(pos);
+            offsets.push(offset);
+            offset += len;
+        }
+        let dl_id = self.next_block_id("dl");
+        let dl_bytes = DataListBlock::new_variable(links, offsets).to_bytes()?;
+        let dl_pos = self.write_block_with_id(&dl_bytes, &dl_id)?;
+        let dg_id = self.cg_to_dg[cg_id];
+        let dg_data_link_offset = 40;
+        self.update_block_link(dg_id, dg_data_link_offset, &dl_id)?;
+        Ok(dl_pos)
+    }
+
+    /// Link the old data blocks of a continued group and the ones of `dt`
+    /// through a new `##DL` from the data group. Returns the group's total
+    /// record count.
+    pub(super) fn link_continued_data(&mut self, cg_id: &ChannelGroupId, dt: &OpenDataBlock) -> Result<u64, MdfError> {
+        let previous = self.previous_data.get_mut(cg_id).unwrap();
+        previous
+            .blocks
+            .extend(dt.dt_positions.iter().zip(&dt.dt_sizes).map(|(&pos, &size)| (pos, size - 24)));
+        previous.cycles += dt.total_record_count;
+        let cycles = previous.cycles;
+
+        let blocks = previous.blocks.clone();
+        self.write_data_list(cg_id, &blocks)?;
+        Ok(cycles)
+    }
+}
+
+/// Reject files this writer cannot continue.
+#[cfg(not(target_arch = "wasm32"))]
+fn check_identification(data: &[u8]) -> Result<(), MdfError> {
+    if data.len() < (HD_OFFSET + 24) as usize {
+        return Err(MdfError::TooShortBuffer {
+            actual: data.len(),
+            expected: (HD_OFFSET + 24) as usize,
+            file: file!(),
+            line: line!(),
+        });
+    }
+    if crate::finalize::is_unfinalized(data) {
+        return Err(MdfError::InvalidWriterState(
+            "file is not finalized; finalize it before reopening".into(),
+        ));
+    }
+    if &data[0..8] != b"MDF     " {
+        return Err(MdfError::FileIdentifierError(String::from_utf8_lossy(&data[0..8]).into_owned()));
+    }
+    let version = LittleEndian::read_u16(&data[28..30]);
+    if version < 400 {
+        return Err(MdfError::FileVersioningError(version.to_string()));
+    }
+    Ok(())
+}
+
+/// The bytes of the block at `addr`, as far as its header says.
+fn block_slice(data: &[u8], addr: u64) -> Result<&[u8], MdfError> {
+    let start = addr as usize;
+    let header = data
+        .get(start..)
+        .filter(|rest| rest.len() >= 24)
+        .map(BlockHeader::from_bytes)
+        .transpose()?
+        .ok_or_else(|| MdfError::BlockLinkError(format!("link to 0x{addr:x} points past the end of the file")))?;
+    data.get(start..start + header.block_len as usize)
+        .ok_or_else(|| MdfError::BlockLinkError(format!("block at 0x{addr:x} extends past the end of the file")))
+}
+
+fn read_u64(data: &[u8], pos: u64) -> Result<u64, MdfError> {
+    let start = pos as usize;
+    data.get(start..start + 8)
+        .map(LittleEndian::read_u64)
+        .ok_or_else(|| MdfError::BlockLinkError(format!("link at 0x{pos:x} is past the end of the file")))
+}
+
+/// Addresses of the blocks chained through their first link (`next`),
+/// starting at `first`.
+fn chain(data: &[u8], first: u64) -> Result<Vec<u64>, MdfError> {
+    let mut addrs = Vec::new();
+    let mut addr = first;
+    while addr != 0 {
+        if addrs.contains(&addr) {
+            return Err(MdfError::BlockLinkError(format!("block chain loops back to 0x{addr:x}")));
+        }
+        addrs.push(addr);
+        addr = read_u64(data, addr + 24)?;
+    }
+    Ok(addrs)
+}
+
+/// Collect the data blocks linked at `addr` (a data block, `##DL` or
+/// `##HL`) in order, with the length of their uncompressed data.
+fn data_blocks(
+    data: &[u8],
+    addr: u64,
+    blocks: &mut Vec<(u64, u64)>,
+    visited: &mut HashSet<u64>,
+) -> Result<(), MdfError> {
+    if addr == 0 {
+        return Ok(());
+    }
+    if !visited.insert(addr) {
+        return Err(MdfError::BlockLinkError(format!("data list loops back to 0x{addr:x}")));
+    }
+    let block = block_slice(data, addr)?;
+    let header = BlockHeader::from_bytes(block)?;
+    let fields = addr + 24 + header.links_nr * 8;
+    match header.id.as_str() {
+        "##HL" => data_blocks(data, read_u64(data, addr + 24)?, blocks, visited),
+        "##DL" => {
+            let dl = DataListBlock::from_bytes(block)?;
+            for &link in &dl.data_links {
+                data_blocks(data, link, blocks, visited)?;
+            }
+            data_blocks(data, dl.next, blocks, visited)
+        }
+        "##DZ" => {
+            blocks.push((addr, DataZippedBlock::read_org_data_length(block)?));
+            Ok(())
+        }
+        "##DT" | "##DV" => {
+            blocks.push((addr, header.block_len - (fields - addr)));
+            Ok(())
+        }
+        id => Err(MdfError::BlockIDError {
+            actual: id.to_string(),
+            expected: "##DT / ##DV / ##DZ / ##DL / ##HL".to_string(),
+        }),
+    }
+}
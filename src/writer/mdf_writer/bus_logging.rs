@@ -0,0 +1,178 @@
+// Bus logging channel groups (CAN_DataFrame, LIN_Frame, FR_Frame, ...) for MdfWriter
+use super::*;
+use crate::blocks::common::DataType;
+use crate::blocks::text_block::TextBlock;
+use crate::bus_logging::{
+    BusFrameType, CanFrame, FlexRayFrame, LinFrame, CG_FLAG_BUS_EVENT, CG_FLAG_PLAIN_BUS_EVENT, CN_FLAG_BUS_EVENT,
+    SOURCE_TYPE_BUS,
+};
+use crate::parsing::source_info::SourceInfo;
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Add a `CAN_DataFrame` channel group for CAN bus `bus_channel`, laid
+    /// out as in the ASAM MDF bus logging standard.
+    ///
+    /// The group gets a `Timestamp` master channel and a `CAN_DataFrame`
+    /// channel whose members are `BusChannel`, `ID`, `IDE`, `DLC`,
+    /// `DataLength`, `Dir`, `EDL`, `BRS`, `ESI` and a 64 byte `DataBytes`
+    /// field. Its acquisition source is a bus source named `CAN<n>` with bus
+    /// type CAN. Open the data block with
+    /// [`start_data_block_for_cg`](Self::start_data_block_for_cg) and add
+    /// frames with [`write_can_frame`](Self::write_can_frame).
+    ///
+    /// # Returns
+    /// The new channel group handle.
+    pub fn add_can_frame_group(&mut self, bus_channel: u8) -> Result<ChannelGroupId, MdfError> {
+        self.add_bus_frame_group(BusFrameType::CanDataFrame, bus_channel)
+    }
+
+    /// Add a `CAN_ErrorFrame` channel group for CAN bus `bus_channel`; like
+    /// [`add_can_frame_group`](Self::add_can_frame_group) with an additional
+    /// `ErrorType` member.
+    pub fn add_can_error_frame_group(&mut self, bus_channel: u8) -> Result<ChannelGroupId, MdfError> {
+        self.add_bus_frame_group(BusFrameType::CanErrorFrame, bus_channel)
+    }
+
+    /// Append `frame` to a group created by
+    /// [`add_can_frame_group`](Self::add_can_frame_group) or
+    /// [`add_can_error_frame_group`](Self::add_can_error_frame_group).
+    ///
+    /// The group's bus channel is written in place of `frame.bus_channel`.
+    pub fn write_can_frame(&mut self, cg_id: &ChannelGroupId, frame: &CanFrame) -> Result<(), MdfError> {
+        let (frame_type, bus_channel) = self.bus_group(cg_id)?;
+        if !matches!(frame_type, BusFrameType::CanDataFrame | BusFrameType::CanErrorFrame) {
+            return Err(MdfError::ValueTypeMismatch(format!(
+                "channel group '{}' holds {} frames, not CAN frames",
+                cg_id,
+                frame_type.name()
+            )));
+        }
+        let record = frame.to_record(frame_type, bus_channel)?;
+        self.write_raw_record(cg_id, &record)
+    }
+
+    /// Add a `LIN_Frame` channel group for LIN bus `bus_channel`.
+    ///
+    /// The frame channel's members are `BusChannel`, `ID`, `Dir`,
+    /// `DataLength`, `Checksum` and an 8 byte `DataBytes` field; the
+    /// acquisition source is a bus source named `LIN<n>` with bus type LIN.
+    /// Add frames with [`write_lin_frame`](Self::write_lin_frame).
+    pub fn add_lin_frame_group(&mut self, bus_channel: u8) -> Result<ChannelGroupId, MdfError> {
+        self.add_bus_frame_group(BusFrameType::LinFrame, bus_channel)
+    }
+
+    /// Add a `FR_Frame` channel group for FlexRay cluster `bus_channel`.
+    ///
+    /// The frame channel's members are `BusChannel`, `ID`,
+    /// `FlexRayChannel`, `Dir`, `CycleCount`, `DataLength`, `HeaderCRC` and
+    /// a 254 byte `DataBytes` field; the acquisition source is a bus source
+    /// named `FlexRay<n>` with bus type FlexRay. Add frames with
+    /// [`write_flexray_frame`](Self::write_flexray_frame).
+    pub fn add_flexray_frame_group(&mut self, bus_channel: u8) -> Result<ChannelGroupId, MdfError> {
+        self.add_bus_frame_group(BusFrameType::FlexRayFrame, bus_channel)
+    }
+
+    /// Append `frame` to a group created by
+    /// [`add_lin_frame_group`](Self::add_lin_frame_group).
+    pub fn write_lin_frame(&mut self, cg_id: &ChannelGroupId, frame: &LinFrame) -> Result<(), MdfError> {
+        let bus_channel = self.bus_group_of_type(cg_id, BusFrameType::LinFrame)?;
+        let record = frame.to_record(bus_channel)?;
+        self.write_raw_record(cg_id, &record)
+    }
+
+    /// Append `frame` to a group created by
+    /// [`add_flexray_frame_group`](Self::add_flexray_frame_group).
+    pub fn write_flexray_frame(&mut self, cg_id: &ChannelGroupId, frame: &FlexRayFrame) -> Result<(), MdfError> {
+        let bus_channel = self.bus_group_of_type(cg_id, BusFrameType::FlexRayFrame)?;
+        let record = frame.to_record(bus_channel)?;
+        self.write_raw_record(cg_id, &record)
+    }
+
+    /// The bus channel of a bus logging group holding `frame_type` frames.
+    fn bus_group_of_type(&self, cg_id: &ChannelGroupId, frame_type: BusFrameType) -> Result<u8, MdfError> {
+        let (actual, bus_channel) = self.bus_group(cg_id)?;
+        if actual != frame_type {
+            return Err(MdfError::ValueTypeMismatch(format!(
+                "channel group '{}' holds {} frames, not {} frames",
+                cg_id,
+                actual.name(),
+                frame_type.name()
+            )));
+        }
+        Ok(bus_channel)
+    }
+
+    /// The frame type and bus channel of a bus logging group.
+    pub(super) fn bus_group(&self, cg_id: &ChannelGroupId) -> Result<(BusFrameType, u8), MdfError> {
+        self.bus_groups.get(cg_id).copied().ok_or_else(|| {
+            MdfError::InvalidWriterState(format!(
+                "channel group '{}' is not a bus logging group",
+                cg_id
+            ))
+        })
+    }
+
+    /// Create the channel group, source, `Timestamp` master and frame
+    /// channel with its member composition for `frame_type`.
+    pub(super) fn add_bus_frame_group(
+        &mut self,
+        frame_type: BusFrameType,
+        bus_channel: u8,
+    ) -> Result<ChannelGroupId, MdfError> {
+        let name = frame_type.name();
+        let cg_id = self.add_channel_group(None, |cg| {
+            cg.flags = CG_FLAG_BUS_EVENT | CG_FLAG_PLAIN_BUS_EVENT;
+            cg.path_separator = b'.' as u16;
+            cg.acq_name = Some(name.to_string());
+            cg.acq_source = Some(SourceInfo {
+                name: Some(format!("{}{}", frame_type.bus_name(), bus_channel)),
+                path: Some(format!("{}{}", frame_type.bus_name(), bus_channel)),
+                source_type: SOURCE_TYPE_BUS,
+                bus_type: frame_type.bus_type(),
+                ..SourceInfo::default()
+            });
+        })?;
+
+        let time_id = self.add_channel(&cg_id, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.bit_count = 64;
+            ch.name = Some("Timestamp".to_string());
+        })?;
+        self.set_time_channel(&time_id)?;
+        let frame_id = self.add_channel(&cg_id, Some(&time_id), |ch| {
+            ch.data_type = DataType::ByteArray;
+            ch.byte_offset = 8;
+            ch.bit_count = frame_type.frame_bytes() * 8;
+            ch.flags = CN_FLAG_BUS_EVENT;
+            ch.name = Some(name.to_string());
+        })?;
+
+        // The members are only reachable through the frame channel's
+        // composition link, so they are written outside `add_channel`.
+        let mut prev: Option<String> = None;
+        for member in frame_type.members() {
+            let block = ChannelBlock {
+                data_type: member.data_type,
+                byte_offset: member.byte_offset,
+                bit_offset: member.bit_offset,
+                bit_count: member.bit_count,
+                flags: CN_FLAG_BUS_EVENT,
+                ..ChannelBlock::default()
+            };
+            let member_id = format!("{}_{}", frame_id, member.name);
+            let cn_pos = self.write_block_with_id(&block.to_bytes()?, &member_id)?;
+            let tx_bytes = TextBlock::new(&format!("{}.{}", name, member.name)).to_bytes()?;
+            let tx_pos = self.write_block_with_id(&tx_bytes, &format!("tx_name_{}", member_id))?;
+            self.update_link(cn_pos + 40, tx_pos)?;
+            match &prev {
+                // cn_composition of the frame channel.
+                None => self.update_block_link(frame_id, 32, &member_id)?,
+                Some(prev) => self.update_block_link(prev, 24, &member_id)?,
+            }
+            prev = Some(member_id);
+        }
+
+        self.bus_groups.insert(cg_id, (frame_type, bus_channel));
+        Ok(cg_id)
+    }
+}
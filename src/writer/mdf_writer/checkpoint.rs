@@ -0,0 +1,100 @@
+// Checkpoints: making the file on disk valid while data blocks are open
+use super::*;
+use std::time::{Duration, Instant};
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Bring the file on disk into a valid state without closing anything.
+    ///
+    /// Every channel group with records written since its last checkpoint
+    /// closes its current data block, patching the block length and the
+    /// group's `cycles_nr`, and continues in a new one. The group's data
+    /// blocks are chained through DL blocks from its data group, as with
+    /// [`MdfWriter::set_live_update`]. Signal data of VLSD channels, which is
+    /// kept in memory until the block is finished, is written as a `##SD`
+    /// snapshot. If the process dies before
+    /// [`finish_data_block`](MdfWriter::finish_data_block), the file reads as
+    /// it was at the last checkpoint. The channel groups of unsorted data
+    /// groups are left out; see
+    /// [`start_unsorted_data_block`](MdfWriter::start_unsorted_data_block).
+    ///
+    /// The sink is flushed, which hands the data to the operating system;
+    /// syncing it to the storage device is up to the caller.
+    pub fn checkpoint(&mut self) -> Result<(), MdfError> {
+        // Compressed groups write their blocks on close, so they go before
+        // an uncompressed group continues at the end of the file.
+        let mut cg_ids: Vec<(bool, ChannelGroupId)> = self
+            .open_dts
+            .iter()
+            .filter(|(_, dt)| !self.unsorted_streams.contains_key(&dt.dg_id))
+            .map(|(id, dt)| (dt.zip_buffer.is_none(), id))
+            .collect();
+        cg_ids.sort();
+        // Records first, then the blocks describing them.
+        self.file.flush()?;
+        for (_, cg_id) in &cg_ids {
+            self.checkpoint_group(cg_id)?;
+        }
+        self.flush()?;
+        if self.checkpoint_interval.is_some() {
+            self.last_checkpoint = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Call [`checkpoint`](MdfWriter::checkpoint) from the write calls once
+    /// `interval` passed since the last checkpoint; `None` turns automatic
+    /// checkpoints off. Not supported on `wasm32-unknown-unknown`.
+    pub fn set_auto_checkpoint(&mut self, interval: Option<Duration>) -> Result<(), MdfError> {
+        if cfg!(target_arch = "wasm32") && interval.is_some() {
+            return Err(MdfError::UnsupportedFeature(
+                "automatic checkpoints are not available on wasm32".into(),
+            ));
+        }
+        self.checkpoint_interval = interval;
+        self.last_checkpoint = interval.map(|_| Instant::now());
+        Ok(())
+    }
+
+    /// Checkpoint if the automatic checkpoint interval elapsed.
+    pub(super) fn maybe_checkpoint(&mut self) -> Result<(), MdfError> {
+        let due = self
+            .checkpoint_interval
+            .zip(self.last_checkpoint)
+            .is_some_and(|(every, last)| last.elapsed() >= every);
+        if due {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Close the group's current data block and chain it, with everything
+    /// before it, through DL blocks from the data group; then continue in a
+    /// new data block linked the same way. This is how data blocks are
+    /// chained for live readers (see [`MdfWriter::set_live_update`]), so from
+    /// here on every closed block of the group stays reachable.
+    fn checkpoint_group(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        let dt = &self.open_dts[cg_id];
+        let total = dt.total_record_count + dt.record_count;
+        if total == dt.published_records {
+            return Ok(());
+        }
+        let snapshots: Vec<(ChannelId, Vec<u8>)> = dt
+            .vlsd_channel_ids
+            .iter()
+            .zip(&dt.vlsd_payloads)
+            .filter_map(|(id, payload)| Some(((*id)?, payload.clone()?)))
+            .collect();
+
+        self.close_dt_fragment(cg_id)?;
+        self.chain_live_data_blocks(cg_id)?;
+        for (cn_id, payload) in snapshots {
+            self.write_vlsd_payload(&cn_id, &payload)?;
+        }
+        let cycles = self.previous_data.get(cg_id).map_or(0, |p| p.cycles) + total;
+        self.update_block_u64(cg_id, 80, cycles)?;
+        let dl_pos = self.append_next_data_list(cg_id)?;
+        self.open_dt_fragment(cg_id, dl_pos)?;
+        self.open_dts.get_mut(cg_id).unwrap().published_records = total;
+        Ok(())
+    }
+}
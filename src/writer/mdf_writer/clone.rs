@@ -0,0 +1,381 @@
+// Copying configured metadata blocks into an MdfWriter
+use super::*;
+use crate::blocks::channel_group_block::ChannelGroupBlock;
+use crate::blocks::common::{BlockHeader, BlockParse};
+use crate::blocks::conversion::{ConversionBlock, ConversionType};
+use crate::blocks::text_block::TextBlock;
+use crate::blocks::source_block::SourceBlock;
+use byteorder::{ByteOrder, LittleEndian};
+
+/// Blocks that make up the structure and data of a file. Copies of other
+/// blocks link to them only through the address map passed to
+/// [`MdfWriter::append_block_chain`].
+const STRUCTURAL_BLOCK_IDS: [&str; 16] = [
+    "##HD", "##DG", "##CG", "##CN", "##CA", "##SR", "##DT", "##DZ", "##DL", "##HL", "##LD", "##SD",
+    "##RD", "##RV", "##RI", "##DV",
+];
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Append copies of the blocks chained through their first link,
+    /// starting at `src_addr`, behind the link at file offset `link_pos`;
+    /// for blocks the writer does not model (events, attachments, file
+    /// history, channel hierarchies, vendor blocks).
+    ///
+    /// `##TX`, `##MD`, `##SI` and `##CC` blocks are copied as by
+    /// [`copy_linked_block`](Self::copy_linked_block). Other blocks are
+    /// copied verbatim with every link rewired to a copy of its target:
+    /// links to structural blocks (groups, channels, data) resolve through
+    /// `cache`, which the caller fills with the new addresses of the
+    /// re-created groups and channels, and become null links otherwise.
+    ///
+    /// Returns the position of the last copy's first link, where the next
+    /// chain can be appended (`link_pos` if nothing was copied).
+    pub(crate) fn append_block_chain<'a, F>(
+        &mut self,
+        fetch: &F,
+        src_addr: u64,
+        cache: &mut HashMap<u64, u64>,
+        mut link_pos: u64,
+    ) -> Result<u64, MdfError>
+    where
+        F: Fn(u64) -> Option<&'a [u8]>,
+    {
+        let mut seen = std::collections::HashSet::new();
+        let mut addr = src_addr;
+        while addr != 0 && seen.insert(addr) {
+            let next = fetch(addr).and_then(|b| b.get(24..32)).map_or(0, LittleEndian::read_u64);
+            // Chain members are linked here, not through recursion.
+            let dst = self.copy_opaque_block(fetch, addr, cache, false)?;
+            if dst != 0 {
+                self.update_link(link_pos, dst)?;
+                link_pos = dst + 24;
+            }
+            addr = next;
+        }
+        Ok(link_pos)
+    }
+
+    /// Copy the block at `src_addr` and, recursively, the blocks it links
+    /// to; see [`append_block_chain`](Self::append_block_chain). Returns the
+    /// file offset of the copy, or `Ok(0)` for null, unknown and unmapped
+    /// structural addresses.
+    fn copy_opaque_block<'a, F>(
+        &mut self,
+        fetch: &F,
+        src_addr: u64,
+        cache: &mut HashMap<u64, u64>,
+        follow_first_link: bool,
+    ) -> Result<u64, MdfError>
+    where
+        F: Fn(u64) -> Option<&'a [u8]>,
+    {
+        if src_addr == 0 {
+            return Ok(0);
+        }
+        if let Some(&dst) = cache.get(&src_addr) {
+            return Ok(dst);
+        }
+        let bytes = match fetch(src_addr) {
+            Some(b) if b.len() >= 24 => b,
+            _ => return Ok(0),
+        };
+        let header = BlockHeader::from_bytes(&bytes[..24])?;
+        match header.id.as_str() {
+            "##TX" | "##MD" | "##SI" | "##CC" => return self.copy_linked_block(fetch, src_addr, cache),
+            id if STRUCTURAL_BLOCK_IDS.contains(&id) => return Ok(0),
+            _ => {}
+        }
+        let total_len = header.block_len as usize;
+        let links_end = usize::try_from(header.links_nr).ok().and_then(|n| n.checked_mul(8)).map(|len| 24 + len);
+        let Some(links_end) = links_end.filter(|&end| end <= total_len && total_len <= bytes.len()) else {
+            return Ok(0);
+        };
+
+        // Write the block with null links first and patch them afterwards,
+        // so links back to it (e.g. an event's parent) resolve to the copy.
+        let mut block = bytes[..total_len].to_vec();
+        block[24..links_end].fill(0);
+        let dst = self.write_block(&block)?;
+        cache.insert(src_addr, dst);
+        for (i, link) in bytes[24..links_end].chunks_exact(8).enumerate() {
+            if i == 0 && !follow_first_link {
+                continue;
+            }
+            let new_addr = self.copy_opaque_block(fetch, LittleEndian::read_u64(link), cache, true)?;
+            if new_addr != 0 {
+                self.update_link(dst + 24 + 8 * i as u64, new_addr)?;
+            }
+        }
+        Ok(dst)
+    }
+
+    /// Recursively copy a referenced block (`##TX`, `##MD`, `##SI`, or `##CC`)
+    /// into this writer, rewriting any link fields so the new block points at
+    /// freshly written copies of its dependencies.
+    ///
+    /// `fetch` returns the bytes starting at a source address (at least the
+    /// whole block), or `None` if the address is unknown. Returns the file
+    /// offset of the new block, or `Ok(0)` when `src_addr` is `0`, cannot be
+    /// fetched, or the block type is not one of the handled kinds.
+    /// Already-cloned source addresses are deduplicated through `cache`.
+    pub(crate) fn copy_linked_block<'a, F>(
+        &mut self,
+        fetch: &F,
+        src_addr: u64,
+        cache: &mut HashMap<u64, u64>,
+    ) -> Result<u64, MdfError>
+    where
+        F: Fn(u64) -> Option<&'a [u8]>,
+    {
+        if src_addr == 0 {
+            return Ok(0);
+        }
+        if let Some(&dst) = cache.get(&src_addr) {
+            return Ok(dst);
+        }
+        let bytes = match fetch(src_addr) {
+            Some(b) if b.len() >= 24 => b,
+            _ => return Ok(0),
+        };
+        let header = BlockHeader::from_bytes(&bytes[..24])?;
+        let total_len = header.block_len as usize;
+        if total_len < 24 || total_len > bytes.len() {
+            return Ok(0);
+        }
+        let bytes = &bytes[..total_len];
+
+        let dst = match header.id.as_str() {
+            "##TX" | "##MD" => {
+                // Leaf blocks with no outgoing links: copy raw bytes verbatim.
+                self.write_block(bytes)?
+            }
+            "##SI" => {
+                let src_block = SourceBlock::from_bytes(bytes)?;
+                // Reserve the cache slot before recursing to break cycles.
+                cache.insert(src_addr, 0);
+                let new_name = self.copy_linked_block(fetch, src_block.name_addr, cache)?;
+                let new_path = self.copy_linked_block(fetch, src_block.path_addr, cache)?;
+                let new_comment = self.copy_linked_block(fetch, src_block.comment_addr, cache)?;
+                // SourceBlock has no `to_bytes`, so patch the original block's
+                // bytes in place. The link layout is fixed: name/path/comment at
+                // offsets 24/32/40 (only the slots actually referenced by
+                // `header.links_nr` are touched).
+                let mut bytes = bytes.to_vec();
+                let link_count = header.links_nr as usize;
+                if link_count >= 1 {
+                    bytes[24..32].copy_from_slice(&new_name.to_le_bytes());
+                }
+                if link_count >= 2 {
+                    bytes[32..40].copy_from_slice(&new_path.to_le_bytes());
+                }
+                if link_count >= 3 {
+                    bytes[40..48].copy_from_slice(&new_comment.to_le_bytes());
+                }
+                self.write_block(&bytes)?
+            }
+            "##CC" => {
+                let src_block = ConversionBlock::from_bytes(bytes)?;
+                cache.insert(src_addr, 0);
+                let new_tx_name =
+                    self.copy_linked_block(fetch, src_block.cc_tx_name.unwrap_or(0), cache)?;
+                let new_md_unit =
+                    self.copy_linked_block(fetch, src_block.cc_md_unit.unwrap_or(0), cache)?;
+                let new_md_comment =
+                    self.copy_linked_block(fetch, src_block.cc_md_comment.unwrap_or(0), cache)?;
+                let new_cc_inverse =
+                    self.copy_linked_block(fetch, src_block.cc_cc_inverse.unwrap_or(0), cache)?;
+                let mut new_refs = Vec::with_capacity(src_block.cc_ref.len());
+                for &r in &src_block.cc_ref {
+                    new_refs.push(self.copy_linked_block(fetch, r, cache)?);
+                }
+                let new_cc = ConversionBlock {
+                    header: src_block.header.clone(),
+                    cc_tx_name: (new_tx_name != 0).then_some(new_tx_name),
+                    cc_md_unit: (new_md_unit != 0).then_some(new_md_unit),
+                    cc_md_comment: (new_md_comment != 0).then_some(new_md_comment),
+                    cc_cc_inverse: (new_cc_inverse != 0).then_some(new_cc_inverse),
+                    cc_ref: new_refs,
+                    cc_type: src_block.cc_type,
+                    cc_precision: src_block.cc_precision,
+                    cc_flags: src_block.cc_flags,
+                    cc_ref_count: src_block.cc_ref_count,
+                    cc_val_count: src_block.cc_val_count,
+                    cc_phy_range_min: src_block.cc_phy_range_min,
+                    cc_phy_range_max: src_block.cc_phy_range_max,
+                    cc_val: src_block.cc_val.clone(),
+                    formula: None,
+                    resolved_texts: None,
+                    resolved_conversions: None,
+                    default_conversion: None,
+                };
+                self.write_block(&new_cc.to_bytes()?)?
+            }
+            _ => 0,
+        };
+
+        if dst != 0 {
+            cache.insert(src_addr, dst);
+        } else {
+            // Drop the cycle-breaker placeholder if cloning ultimately failed.
+            cache.remove(&src_addr);
+        }
+        Ok(dst)
+    }
+
+    /// Write `cc`, a conversion whose references were resolved when it was
+    /// read (see [`ConversionBlock::resolve_all_dependencies`]), together with
+    /// fresh `##TX` and `##CC` blocks for its texts, formula and nested
+    /// conversions. Returns the file offset of the new `##CC` block.
+    ///
+    /// References that were not resolved are written as null links; the
+    /// name, unit, comment and inverse links are not carried over. `name`
+    /// becomes the `cc_tx_name` of the new block (bitfield text tables keep
+    /// the names of their nested conversions this way).
+    pub(crate) fn write_resolved_conversion(
+        &mut self,
+        cc: &ConversionBlock,
+        name: Option<&str>,
+    ) -> Result<u64, MdfError> {
+        let last = cc.cc_ref.len().checked_sub(1);
+        let mut refs = Vec::with_capacity(cc.cc_ref.len());
+        for i in 0..cc.cc_ref.len() {
+            let text = cc.resolved_texts.as_ref().and_then(|texts| texts.get(&i));
+            let nested = cc
+                .resolved_conversions
+                .as_ref()
+                .and_then(|nested| nested.get(&i))
+                .or(cc.default_conversion.as_ref().filter(|_| Some(i) == last));
+            let addr = match (nested, text) {
+                _ if i == 0 && cc.cc_type == ConversionType::Algebraic => match &cc.formula {
+                    Some(formula) => self.write_block(&TextBlock::new(formula).to_bytes()?)?,
+                    None => 0,
+                },
+                (Some(nested), text) => {
+                    // Texts stored next to a nested conversion are its name.
+                    self.write_resolved_conversion(nested, text.map(String::as_str))?
+                }
+                (None, Some(text)) => self.write_block(&TextBlock::new(text).to_bytes()?)?,
+                (None, None) => 0,
+            };
+            refs.push(addr);
+        }
+        let tx_name = match name {
+            Some(name) => Some(self.write_block(&TextBlock::new(name).to_bytes()?)?),
+            None => None,
+        };
+        let block = ConversionBlock {
+            header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+            cc_tx_name: tx_name,
+            cc_md_unit: None,
+            cc_md_comment: None,
+            cc_cc_inverse: None,
+            cc_ref: refs,
+            cc_type: cc.cc_type,
+            cc_precision: cc.cc_precision,
+            cc_flags: cc.cc_flags,
+            cc_ref_count: cc.cc_ref_count,
+            cc_val_count: cc.cc_val_count,
+            cc_phy_range_min: cc.cc_phy_range_min,
+            cc_phy_range_max: cc.cc_phy_range_max,
+            cc_val: cc.cc_val.clone(),
+            formula: None,
+            resolved_texts: None,
+            resolved_conversions: None,
+            default_conversion: None,
+        };
+        self.write_block(&block.to_bytes()?)
+    }
+
+    /// Copy a fully configured channel group from another writer.
+    ///
+    /// The group is added to this writer in a new data group, together with
+    /// its channels (in the same order) and every conversion, unit, comment,
+    /// source and name block linked from the group or its channels. No
+    /// samples are copied. Returns the new channel group handle and the new
+    /// channel handles in channel order.
+    ///
+    /// Useful for file rotation: configure a group once, then repeat the same
+    /// structure in each new file.
+    ///
+    /// The blocks are copied from `source`'s in-memory copies, so `source`
+    /// needs [`WriterConfig::keep_metadata`] set before the group was added.
+    pub fn clone_channel_group<S: Write + Seek>(
+        &mut self,
+        source: &MdfWriter<S>,
+        cg_id: &ChannelGroupId,
+    ) -> Result<(ChannelGroupId, Vec<ChannelId>), MdfError> {
+        if !source.config.keep_metadata {
+            return Err(MdfError::InvalidWriterState(
+                "cloning a channel group needs WriterConfig::keep_metadata on the source writer".into(),
+            ));
+        }
+        let fetch = |addr: u64| source.metadata_blocks.get(&addr).map(Vec::as_slice);
+        let block_at = |id: BlockRef<'_>| {
+            source
+                .get_block_position(id)
+                .and_then(fetch)
+                .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", id)))
+        };
+
+        let src_cg = ChannelGroupBlock::from_bytes(block_at(cg_id.into())?)?;
+        let new_cg_id = self.add_channel_group(None, |cg| {
+            cg.record_id = src_cg.record_id;
+            cg.flags = src_cg.flags;
+            cg.path_separator = src_cg.path_separator;
+        })?;
+
+        // Link offsets in the ##CG block: 40 = acq_name_addr,
+        // 48 = acq_source_addr, 64 = comment_addr.
+        let mut cache = HashMap::new();
+        let cg_pos = self.get_block_position(new_cg_id).unwrap();
+        for (offset, src_addr) in [
+            (40, src_cg.acq_name_addr),
+            (48, src_cg.acq_source_addr),
+            (64, src_cg.comment_addr),
+        ] {
+            let new_addr = self.copy_linked_block(&fetch, src_addr, &mut cache)?;
+            if new_addr != 0 {
+                self.update_link(cg_pos + offset, new_addr)?;
+            }
+        }
+
+        let src_ids = source.cg_channel_ids.get(cg_id).cloned().unwrap_or_default();
+        let src_channels = source.cg_channels.get(cg_id).cloned().unwrap_or_default();
+        let mut new_ids = Vec::with_capacity(src_ids.len());
+        for (src_cn_id, configured) in src_ids.iter().zip(src_channels) {
+            let mut block = ChannelBlock::from_bytes(block_at(src_cn_id.into())?)?;
+            let links = [
+                (48, block.source_addr),
+                (56, block.conversion_addr),
+                (72, block.unit_addr),
+                (80, block.comment_addr),
+            ];
+
+            // The name is written by `add_channel`; every other link is
+            // re-created below. A VLSD channel keeps the writer's non-zero
+            // `data` marker so its new ##SD block gets linked on finish.
+            block.name = configured.name;
+            block.name_addr = 0;
+            block.next_ch_addr = 0;
+            block.component_addr = 0;
+            block.source_addr = 0;
+            block.conversion_addr = 0;
+            block.unit_addr = 0;
+            block.comment_addr = 0;
+            block.data = u64::from(block.channel_type == 1 && block.data != 0);
+
+            let cn_id = self.add_channel(&new_cg_id, new_ids.last(), |c| {
+                *c = block;
+            })?;
+            let cn_pos = self.get_block_position(cn_id).unwrap();
+            for (offset, src_addr) in links {
+                let new_addr = self.copy_linked_block(&fetch, src_addr, &mut cache)?;
+                if new_addr != 0 {
+                    self.update_link(cn_pos + offset, new_addr)?;
+                }
+            }
+            new_ids.push(cn_id);
+        }
+        Ok((new_cg_id, new_ids))
+    }
+}
@@ -0,0 +1,199 @@
+// Opt-in checks rejecting configurations that MDF 4.1 cannot represent
+use super::*;
+use crate::blocks::common::{BlockHeader, DataType};
+use crate::blocks::file_history_block::FileHistoryBlock;
+use crate::blocks::metadata_block::MetadataBlock;
+use crate::metadata::xml_escape;
+
+/// How strictly the writer validates its configuration.
+///
+/// In [`Compliance::Lenient`] mode (the default) the writer accepts any
+/// block layout it can serialize. [`Compliance::Strict41`] additionally
+/// rejects configurations other MDF 4.1 tools refuse to read, at the call
+/// that introduces them:
+///
+/// * `add_channel` rejects data type / bit count combinations 4.1 does not
+///   define (e.g. 16-bit floats, complex numbers, odd-sized strings) and
+///   out-of-range channel or sync types.
+/// * Opening a data block requires exactly one master channel in the group
+///   and at least one `##FH` block (see [`MdfWriter::add_file_history`]).
+/// * `finalize` requires at least one `##FH` block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compliance {
+    #[default]
+    Lenient,
+    Strict41,
+}
+
+fn violation(msg: String) -> MdfError {
+    MdfError::ComplianceViolation(msg)
+}
+
+/// Validate a single channel against the MDF 4.1 data type table.
+fn check_channel(ch: &ChannelBlock) -> Result<(), MdfError> {
+    let name = ch.name.as_deref().unwrap_or("<unnamed>");
+    let bits = ch.bit_count;
+    let ok = match ch.data_type {
+        DataType::UnsignedIntegerLE
+        | DataType::UnsignedIntegerBE
+        | DataType::SignedIntegerLE
+        | DataType::SignedIntegerBE => (1..=64).contains(&bits),
+        DataType::FloatLE | DataType::FloatBE => {
+            (bits == 32 || bits == 64) && ch.bit_offset == 0
+        }
+        DataType::StringLatin1
+        | DataType::StringUtf8
+        | DataType::ByteArray
+        | DataType::MimeSample
+        | DataType::MimeStream => bits != 0 && bits.is_multiple_of(8) && ch.bit_offset == 0,
+        DataType::StringUtf16LE | DataType::StringUtf16BE => {
+            bits != 0 && bits.is_multiple_of(16) && ch.bit_offset == 0
+        }
+        DataType::CanOpenDate => bits == 56 && ch.bit_offset == 0,
+        DataType::CanOpenTime => bits == 48 && ch.bit_offset == 0,
+        DataType::ComplexLE | DataType::ComplexBE => {
+            return Err(violation(format!(
+                "channel '{name}': complex data types were introduced in MDF 4.2"
+            )));
+        }
+        DataType::Unknown(()) => {
+            return Err(violation(format!("channel '{name}': unknown data type")));
+        }
+    };
+    if !ok {
+        return Err(violation(format!(
+            "channel '{name}': {:?} with bit_count {bits} and bit_offset {} is not defined by MDF 4.1",
+            ch.data_type, ch.bit_offset
+        )));
+    }
+    if ch.bit_offset > 7 {
+        return Err(violation(format!(
+            "channel '{name}': bit_offset {} exceeds 7", ch.bit_offset
+        )));
+    }
+    if ch.channel_type > 6 {
+        return Err(violation(format!(
+            "channel '{name}': channel_type {} is not defined by MDF 4.1", ch.channel_type
+        )));
+    }
+    if ch.sync_type > 4 {
+        return Err(violation(format!(
+            "channel '{name}': sync_type {} is not defined by MDF 4.1", ch.sync_type
+        )));
+    }
+    if matches!(ch.channel_type, 2 | 3) && ch.sync_type == 0 {
+        return Err(violation(format!(
+            "channel '{name}': master channels need a non-zero sync_type"
+        )));
+    }
+    Ok(())
+}
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Select how strictly the writer validates its configuration.
+    ///
+    /// Switching to [`Compliance::Strict41`] re-checks every channel added so
+    /// far and fails on the first violation, leaving the mode unchanged.
+    pub fn set_compliance(&mut self, compliance: Compliance) -> Result<(), MdfError> {
+        if compliance == Compliance::Strict41 {
+            for channels in self.cg_channels.values() {
+                for ch in channels {
+                    check_channel(ch)?;
+                }
+            }
+        }
+        self.compliance = compliance;
+        Ok(())
+    }
+
+    /// Returns the current compliance mode.
+    pub fn compliance(&self) -> Compliance {
+        self.compliance
+    }
+
+    /// Append a `##FH` file history entry recording the tool that wrote the
+    /// file.
+    ///
+    /// `time_ns` is the change time in nanoseconds since the Unix epoch.
+    /// The tool identification is stored in an `<FHcomment>` `##MD` block.
+    /// The first entry is linked from the header (offset 32), later ones
+    /// from the previous entry's `fh_fh_next`. Returns the entry's block ID
+    /// (`fh_N`).
+    pub fn add_file_history(
+        &mut self,
+        time_ns: u64,
+        tool_id: &str,
+        tool_vendor: &str,
+        tool_version: &str,
+        comment: &str,
+    ) -> Result<String, MdfError> {
+        let fh_count = self.block_counts.get("fh").copied().unwrap_or(0);
+        let fh_id = self.next_block_id("fh");
+
+        let xml = format!(
+            "<FHcomment><TX>{}</TX><tool_id>{}</tool_id><tool_vendor>{}</tool_vendor><tool_version>{}</tool_version></FHcomment>",
+            xml_escape(comment),
+            xml_escape(tool_id),
+            xml_escape(tool_vendor),
+            xml_escape(tool_version),
+        );
+        let md_id = format!("md_{fh_id}");
+        let md_bytes = MetadataBlock::new(&xml).to_bytes()?;
+        let md_pos = self.write_block_with_id(&md_bytes, &md_id)?;
+
+        let fh = FileHistoryBlock {
+            header: BlockHeader { id: "##FH".into(), reserved0: 0, block_len: 56, links_nr: 2 },
+            comment_addr: md_pos,
+            time_ns,
+            ..FileHistoryBlock::default()
+        };
+        self.write_block_with_id(&fh.to_bytes()?, &fh_id)?;
+
+        if fh_count == 0 {
+            let hd_fh_link_offset = 32;
+            self.update_block_link("hd_block", hd_fh_link_offset, &fh_id)?;
+        } else {
+            let prev = format!("fh_{}", fh_count - 1);
+            let fh_next_link_offset = 24;
+            self.update_block_link(&prev, fh_next_link_offset, &fh_id)?;
+        }
+        Ok(fh_id)
+    }
+
+    /// Strict-mode check for a channel about to be added.
+    pub(super) fn check_channel_compliance(&self, ch: &ChannelBlock) -> Result<(), MdfError> {
+        match self.compliance {
+            Compliance::Lenient => Ok(()),
+            Compliance::Strict41 => check_channel(ch),
+        }
+    }
+
+    /// Strict-mode check that the file carries at least one `##FH` block.
+    pub(super) fn check_file_history_compliance(&self) -> Result<(), MdfError> {
+        if self.compliance == Compliance::Strict41 && !self.block_positions.contains_key("fh_0") {
+            return Err(violation(
+                "MDF 4.1 requires at least one file history (##FH) block; call add_file_history".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Strict-mode checks run when a data block is opened for `cg_id`.
+    pub(super) fn check_group_compliance(
+        &self,
+        cg_id: &ChannelGroupId,
+        channels: &[ChannelBlock],
+    ) -> Result<(), MdfError> {
+        if self.compliance == Compliance::Lenient {
+            return Ok(());
+        }
+        self.check_file_history_compliance()?;
+        let masters = channels.iter().filter(|c| matches!(c.channel_type, 2 | 3)).count();
+        if masters != 1 {
+            return Err(violation(format!(
+                "channel group '{cg_id}' has {masters} master channels; MDF 4.1 requires exactly one"
+            )));
+        }
+        Ok(())
+    }
+}
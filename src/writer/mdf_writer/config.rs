@@ -0,0 +1,127 @@
+// Identification block fields chosen by the writer's user
+use super::*;
+use crate::blocks::identification_block::IdentificationBlock;
+
+/// Fields of the identification block (`##ID`) written by
+/// [`MdfWriter::init_mdf_file`].
+///
+/// Some consumers key behavior off the version and program identifier, so
+/// they can be set to match what a reader expects. Non-zero unfinalized
+/// flags mark the file as unfinalized (identifier `UnFinMDF`) while it is
+/// being written; [`MdfWriter::finalize`] clears them, so only a writer that
+/// is interrupted leaves them in the file, where readers such as
+/// [`crate::finalize`] use them to repair it.
+///
+/// ```
+/// # use mf4_rs::writer::{MdfWriter, WriterConfig};
+/// let mut writer = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+/// writer.set_config(WriterConfig { version: 420, program_identifier: "logger".into(), ..Default::default() })?;
+/// writer.init_mdf_file()?;
+/// let bytes = writer.into_inner()?.into_inner();
+/// assert_eq!(&bytes[8..24], b"4.20    logger  ");
+/// # Ok::<(), mf4_rs::error::MdfError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterConfig {
+    /// MDF version number, e.g. `410` for 4.10; also written as the version
+    /// string (`"4.10"`). Must be a 4.x version.
+    pub version: u16,
+    /// Program identifier (`id_prog`), at most 8 ASCII characters.
+    pub program_identifier: String,
+    /// Standard unfinalized flags (`id_unfin_flags`).
+    pub standard_unfinalized_flags: u16,
+    /// Vendor-specific unfinalized flags (`id_custom_unfin_flags`).
+    pub custom_unfinalized_flags: u16,
+    /// Keep in-memory copies of the channel, conversion, source, text and
+    /// metadata blocks written from now on, which
+    /// [`MdfWriter::clone_channel_group`] copies from. Off by default; set it
+    /// before adding the channel groups to be cloned.
+    pub keep_metadata: bool,
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        let id = IdentificationBlock::default();
+        WriterConfig {
+            version: id.version_number,
+            program_identifier: id.program_identifier.trim_end().to_string(),
+            standard_unfinalized_flags: 0,
+            custom_unfinalized_flags: 0,
+            keep_metadata: false,
+        }
+    }
+}
+
+impl WriterConfig {
+    /// `true` if the configuration marks the file as unfinalized.
+    fn unfinalized(&self) -> bool {
+        self.standard_unfinalized_flags != 0 || self.custom_unfinalized_flags != 0
+    }
+
+    /// The identification block for this configuration.
+    pub(super) fn identification_block(&self) -> IdentificationBlock {
+        let unfinalized = self.unfinalized();
+        IdentificationBlock {
+            file_identifier: if unfinalized { "UnFinMDF" } else { "MDF     " }.to_string(),
+            version_identifier: format!("{}.{:02}", self.version / 100, self.version % 100),
+            program_identifier: self.program_identifier.clone(),
+            version_number: self.version,
+            standard_unfinalized_flags: self.standard_unfinalized_flags,
+            custom_unfinalized_flags: self.custom_unfinalized_flags,
+        }
+    }
+
+    fn check(&self) -> Result<(), MdfError> {
+        if !(400..500).contains(&self.version) {
+            return Err(MdfError::InvalidVersionString(format!(
+                "writer version {} is not an MDF 4.x version",
+                self.version
+            )));
+        }
+        if self.program_identifier.len() > 8 || !self.program_identifier.is_ascii() {
+            return Err(MdfError::BlockSerializationError(format!(
+                "program identifier '{}' is not at most 8 ASCII characters",
+                self.program_identifier
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Set the identification block fields; see [`WriterConfig`].
+    ///
+    /// Takes effect in [`init_mdf_file`](Self::init_mdf_file), or rewrites
+    /// the identification block if it was already written.
+    pub fn set_config(&mut self, config: WriterConfig) -> Result<(), MdfError> {
+        config.check()?;
+        if let Some(pos) = self.get_block_position("id_block") {
+            let bytes = config.identification_block().to_bytes()?;
+            self.queue_patch(pos, &bytes);
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Returns the current identification block configuration.
+    pub fn config(&self) -> &WriterConfig {
+        &self.config
+    }
+
+    /// Mark the file as finalized if the configuration flagged it as
+    /// unfinalized.
+    pub(super) fn clear_unfinalized_flags(&mut self) -> Result<(), MdfError> {
+        if !self.config.unfinalized() {
+            return Ok(());
+        }
+        if let Some(pos) = self.get_block_position("id_block") {
+            let finalized = WriterConfig {
+                standard_unfinalized_flags: 0,
+                custom_unfinalized_flags: 0,
+                ..self.config.clone()
+            };
+            self.queue_patch(pos, &finalized.identification_block().to_bytes()?);
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 // Handling of DT blocks and record writing
 use super::*;
 use std::io::Write;
+use crate::blocks::channel_block::CN_FLAG_INVAL_BIT_VALID;
 use crate::blocks::common::{BlockHeader, DataType};
 use crate::blocks::data_list_block::DataListBlock;
+use crate::blocks::data_zipped_block::{DataZippedBlock, ZIP_TYPE_DEFLATE, ZIP_TYPE_TRANSPOSE_DEFLATE};
+use crate::parsing::canopen::{CanOpenDate, CanOpenTime};
 use crate::parsing::decoder::DecodedValue;
 
 /// Column data for use with [`MdfWriter::write_columns`].
@@ -11,6 +14,7 @@ use crate::parsing::decoder::DecodedValue;
 /// columns passed to `write_columns` must have the same length (number of
 /// records). The encoder for each channel must match the corresponding
 /// `ColumnData` variant.
+#[derive(Clone, Copy)]
 pub enum ColumnData<'a> {
     /// 64-bit IEEE 754 float values.
     F64(&'a [f64]),
@@ -22,12 +26,64 @@ pub enum ColumnData<'a> {
     I64(&'a [i64]),
 }
 
+/// Progress publication policy for files that are read while being written.
+///
+/// Once set with [`MdfWriter::set_live_update`], a write call that brings a
+/// channel group past either threshold flushes the written records and
+/// patches the open DT block's length and the group's `cycles_nr`, so a
+/// reader following the growing file sees every complete record without
+/// waiting for [`MdfWriter::finish_data_block`]. The DT blocks of a group are
+/// then chained through one DL block each, written as soon as a DT block is
+/// closed, instead of a single DL written at the end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiveUpdate {
+    /// Publish once this many records were written since the last publication.
+    pub every_records: Option<u64>,
+    /// Publish once this much time passed since the last publication.
+    /// Not supported on `wasm32-unknown-unknown`.
+    pub interval: Option<std::time::Duration>,
+}
+
+/// Compression applied to the data blocks of channel groups; see
+/// [`MdfWriter::set_compression`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompressionMethod {
+    /// Plain `##DT` blocks.
+    #[default]
+    None,
+    /// `##DZ` blocks holding the deflated `##DT` data.
+    Zlib,
+    /// `##DZ` blocks holding the `##DT` data transposed record-column-wise,
+    /// then deflated. Usually the smallest output for measurement data.
+    TransposedZlib,
+}
+
+#[derive(Clone)]
 pub(super) enum ChannelEncoder {
     UInt { offset: usize, bytes: usize },
     Int { offset: usize, bytes: usize },
     F32 { offset: usize },
     F64 { offset: usize },
+    /// IEEE 754 half precision, rounded to nearest even.
+    F16 { offset: usize },
+    /// Big-endian counterparts of the five variants above.
+    UIntBE { offset: usize, bytes: usize },
+    IntBE { offset: usize, bytes: usize },
+    F32BE { offset: usize },
+    F64BE { offset: usize },
+    F16BE { offset: usize },
     Bytes { offset: usize, bytes: usize },
+    /// Fixed-length string field of `bytes` bytes in the encoding of
+    /// `data_type`, zero-padded after the text.
+    Str { offset: usize, bytes: usize, data_type: DataType },
+    /// CANopen `DATE` (7 bytes) and `TIME` (6 bytes) structures.
+    CanOpenDate { offset: usize },
+    CanOpenTime { offset: usize },
+    /// Integer channel that does not fill whole bytes: its `bit_count` bits
+    /// start `bit_offset` bits into the byte at `offset` and are written with
+    /// a masked read-modify-write, so neighbouring fields sharing those bytes
+    /// keep their bits.
+    Packed { offset: usize, bit_offset: u8, bit_count: u32, signed: bool, big_endian: bool },
     /// VLSD channel: writes a 64-bit running offset into the DT record at
     /// `offset`, and appends `[u32 length][payload]` to
     /// `OpenDataBlock::vlsd_payloads[channel_index]`. Encoded by an inline
@@ -38,7 +94,7 @@ pub(super) enum ChannelEncoder {
 }
 
 impl ChannelEncoder {
-    fn encode(&self, buf: &mut [u8], value: &DecodedValue) {
+    pub(super) fn encode(&self, buf: &mut [u8], value: &DecodedValue) -> Result<(), MdfError> {
         match (self, value) {
             (ChannelEncoder::UInt { offset, bytes }, DecodedValue::UnsignedInteger(v)) => {
                 let b = v.to_le_bytes();
@@ -54,6 +110,26 @@ impl ChannelEncoder {
             (ChannelEncoder::F64 { offset }, DecodedValue::Float(v)) => {
                 buf[*offset..*offset + 8].copy_from_slice(&v.to_le_bytes());
             }
+            (ChannelEncoder::F16 { offset }, DecodedValue::Float(v)) => {
+                buf[*offset..*offset + 2].copy_from_slice(&f64_to_half(*v).to_le_bytes());
+            }
+            (ChannelEncoder::F16BE { offset }, DecodedValue::Float(v)) => {
+                buf[*offset..*offset + 2].copy_from_slice(&f64_to_half(*v).to_be_bytes());
+            }
+            (ChannelEncoder::UIntBE { offset, bytes }, DecodedValue::UnsignedInteger(v)) => {
+                let b = v.to_be_bytes();
+                buf[*offset..*offset + *bytes].copy_from_slice(&b[8 - *bytes..]);
+            }
+            (ChannelEncoder::IntBE { offset, bytes }, DecodedValue::SignedInteger(v)) => {
+                let b = v.to_be_bytes();
+                buf[*offset..*offset + *bytes].copy_from_slice(&b[8 - *bytes..]);
+            }
+            (ChannelEncoder::F32BE { offset }, DecodedValue::Float(v)) => {
+                buf[*offset..*offset + 4].copy_from_slice(&(*v as f32).to_be_bytes());
+            }
+            (ChannelEncoder::F64BE { offset }, DecodedValue::Float(v)) => {
+                buf[*offset..*offset + 8].copy_from_slice(&v.to_be_bytes());
+            }
             (ChannelEncoder::Bytes { offset, bytes }, DecodedValue::ByteArray(data))
             | (ChannelEncoder::Bytes { offset, bytes }, DecodedValue::MimeSample(data))
             | (ChannelEncoder::Bytes { offset, bytes }, DecodedValue::MimeStream(data)) => {
@@ -61,94 +137,438 @@ impl ChannelEncoder {
                 let n = data.len().min(*bytes);
                 buf[*offset..*offset + n].copy_from_slice(&data[..n]);
             }
+            (
+                ChannelEncoder::Packed { offset, bit_offset, bit_count, signed: false, big_endian },
+                DecodedValue::UnsignedInteger(v),
+            ) => {
+                write_bits(buf, *offset, *bit_offset, *bit_count, *big_endian, *v);
+            }
+            (
+                ChannelEncoder::Packed { offset, bit_offset, bit_count, signed: true, big_endian },
+                DecodedValue::SignedInteger(v),
+            ) => {
+                write_bits(buf, *offset, *bit_offset, *bit_count, *big_endian, *v as u64);
+            }
+            (ChannelEncoder::Str { offset, bytes, data_type }, DecodedValue::String(text)) => {
+                encode_string(&mut buf[*offset..*offset + *bytes], data_type, text)?;
+            }
+            (ChannelEncoder::CanOpenDate { offset }, DecodedValue::CanOpenDate(date)) => {
+                buf[*offset..*offset + CanOpenDate::BYTES].copy_from_slice(&date.to_bytes());
+            }
+            (ChannelEncoder::CanOpenTime { offset }, DecodedValue::CanOpenTime(time)) => {
+                buf[*offset..*offset + CanOpenTime::BYTES].copy_from_slice(&time.to_bytes());
+            }
             _ => {}
         }
+        Ok(())
     }
 
     fn encode_u64(&self, buf: &mut [u8], value: u64) {
-        if let ChannelEncoder::UInt { offset, bytes } = self {
-            let b = value.to_le_bytes();
-            buf[*offset..*offset + *bytes].copy_from_slice(&b[..*bytes]);
+        match self {
+            ChannelEncoder::UInt { offset, bytes } => {
+                let b = value.to_le_bytes();
+                buf[*offset..*offset + *bytes].copy_from_slice(&b[..*bytes]);
+            }
+            ChannelEncoder::UIntBE { offset, bytes } => {
+                let b = value.to_be_bytes();
+                buf[*offset..*offset + *bytes].copy_from_slice(&b[8 - *bytes..]);
+            }
+            ChannelEncoder::Packed { offset, bit_offset, bit_count, signed: false, big_endian } => {
+                write_bits(buf, *offset, *bit_offset, *bit_count, *big_endian, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether [`encode_u64`](Self::encode_u64) writes this channel.
+    fn takes_u64(&self) -> bool {
+        matches!(
+            self,
+            ChannelEncoder::UInt { .. } | ChannelEncoder::UIntBE { .. } | ChannelEncoder::Packed { signed: false, .. }
+        )
+    }
+}
+
+/// Narrow `value` to IEEE 754 half precision, rounding to nearest even.
+/// Values beyond the half range become infinite, values below half the
+/// smallest subnormal become zero.
+fn f64_to_half(value: f64) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 48 & 0x8000) as u16;
+    let exponent = (bits >> 52 & 0x7FF) as i32;
+    let mantissa = bits & 0x000F_FFFF_FFFF_FFFF;
+    if exponent == 0x7FF {
+        return sign | 0x7C00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 1023 + 15;
+    if half_exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+    // Significand bits dropped when narrowing: 42 for normal results, more
+    // for subnormal ones, which lose the exponent range too.
+    let (significand, shift) = if half_exponent > 0 {
+        (((half_exponent as u64) << 52) | mantissa, 42)
+    } else if half_exponent >= -10 {
+        (mantissa | 1 << 52, (43 - half_exponent) as u32)
+    } else {
+        return sign;
+    };
+    let mut half = significand >> shift;
+    let rest = significand & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    // A carry out of the mantissa moves into the exponent, up to infinity.
+    if rest > halfway || rest == halfway && half & 1 == 1 {
+        half += 1;
+    }
+    sign | half as u16
+}
+
+/// Store the low `bit_count` bits of `value`, starting `bit_offset` bits
+/// into the `big_endian` or little-endian field at `buf[offset]`, leaving
+/// all other bits untouched. `bit_offset + bit_count` must not exceed 64.
+fn write_bits(buf: &mut [u8], offset: usize, bit_offset: u8, bit_count: u32, big_endian: bool, value: u64) {
+    let end = bit_offset as u32 + bit_count;
+    let bytes = end.div_ceil(8) as usize;
+    let field = &mut buf[offset..offset + bytes];
+    let mask = (u64::MAX >> (64 - bit_count)) << bit_offset;
+    let mut word = [0u8; 8];
+    let current = if big_endian {
+        word[8 - bytes..].copy_from_slice(field);
+        u64::from_be_bytes(word)
+    } else {
+        word[..bytes].copy_from_slice(field);
+        u64::from_le_bytes(word)
+    };
+    let merged = (current & !mask) | ((value << bit_offset) & mask);
+    if big_endian {
+        field.copy_from_slice(&merged.to_be_bytes()[8 - bytes..]);
+    } else {
+        field.copy_from_slice(&merged.to_le_bytes()[..bytes]);
+    }
+}
+
+/// Write `text` into the fixed-length string `field` in the encoding of
+/// `data_type`, zero-padding the rest. Fails if the encoded text is longer
+/// than the field or, for Latin-1, contains characters above U+00FF.
+fn encode_string(field: &mut [u8], data_type: &DataType, text: &str) -> Result<(), MdfError> {
+    let too_long = |len: usize| {
+        MdfError::ValueTypeMismatch(format!(
+            "string of {len} bytes does not fit the {}-byte {data_type:?} field",
+            field.len()
+        ))
+    };
+    let mut encoded = Vec::with_capacity(field.len());
+    match data_type {
+        DataType::StringLatin1 => {
+            for c in text.chars() {
+                let b = u8::try_from(u32::from(c)).map_err(|_| {
+                    MdfError::ValueTypeMismatch(format!("character {c:?} is not representable in Latin-1"))
+                })?;
+                encoded.push(b);
+            }
+        }
+        DataType::StringUtf8 => encoded.extend_from_slice(text.as_bytes()),
+        DataType::StringUtf16LE => encoded.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+        DataType::StringUtf16BE => encoded.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        _ => unreachable!("only string data types get a Str encoder"),
+    }
+    if encoded.len() > field.len() {
+        return Err(too_long(encoded.len()));
+    }
+    field[..encoded.len()].copy_from_slice(&encoded);
+    field[encoded.len()..].fill(0);
+    Ok(())
+}
+
+/// Whether `start_data_block` has an encoder for fixed-size samples of
+/// `data_type`.
+fn has_encoder(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::UnsignedIntegerLE
+            | DataType::UnsignedIntegerBE
+            | DataType::SignedIntegerLE
+            | DataType::SignedIntegerBE
+            | DataType::FloatLE
+            | DataType::FloatBE
+            | DataType::StringLatin1
+            | DataType::StringUtf8
+            | DataType::StringUtf16LE
+            | DataType::StringUtf16BE
+            | DataType::ByteArray
+            | DataType::MimeSample
+            | DataType::MimeStream
+            | DataType::CanOpenDate
+            | DataType::CanOpenTime
+    )
+}
+
+/// Whether an integer channel has to go through [`ChannelEncoder::Packed`].
+fn is_packed(ch: &ChannelBlock) -> bool {
+    ch.bit_offset != 0 || !ch.bit_count.is_multiple_of(8)
+}
+
+/// Reject channel layouts the encoders cannot write faithfully: data types
+/// without an encoder, packed integers wider than the 64-bit window, and
+/// channels whose bits overlap another channel's in the record.
+fn check_record_layout(channels: &[ChannelBlock]) -> Result<(), MdfError> {
+    let name = |ch: &ChannelBlock| ch.name.clone().unwrap_or_else(|| "<unnamed>".into());
+    let mut ranges = Vec::with_capacity(channels.len());
+    for ch in channels {
+        // Virtual channels (master or data) take no record bytes.
+        if ch.channel_type == 3 || ch.channel_type == 6 {
+            continue;
+        }
+        let is_vlsd = ch.channel_type == 1 && ch.data != 0;
+        if !is_vlsd && !has_encoder(&ch.data_type) {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "channel '{}': writing {:?} samples is not supported",
+                name(ch), ch.data_type
+            )));
+        }
+        if matches!(ch.data_type, DataType::FloatLE | DataType::FloatBE)
+            && (!matches!(ch.bit_count, 16 | 32 | 64) || ch.bit_offset != 0)
+        {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "channel '{}': {:?} needs bit_count 16, 32 or 64 and bit_offset 0",
+                name(ch), ch.data_type
+            )));
+        }
+        let canopen_bits = match ch.data_type {
+            DataType::CanOpenDate => Some(CanOpenDate::BYTES as u32 * 8),
+            DataType::CanOpenTime => Some(CanOpenTime::BYTES as u32 * 8),
+            _ => None,
+        };
+        if let Some(bits) = canopen_bits
+            && (ch.bit_count != bits || ch.bit_offset != 0)
+        {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "channel '{}': {:?} needs bit_count {bits} and bit_offset 0",
+                name(ch), ch.data_type
+            )));
+        }
+        let integer = matches!(
+            ch.data_type,
+            DataType::UnsignedIntegerLE
+                | DataType::UnsignedIntegerBE
+                | DataType::SignedIntegerLE
+                | DataType::SignedIntegerBE
+        );
+        if !is_vlsd
+            && integer
+            && is_packed(ch)
+            && (ch.bit_count == 0 || ch.bit_offset as u32 + ch.bit_count > 64)
+        {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "channel '{}': bit_offset {} with bit_count {} does not fit a 64-bit window",
+                name(ch), ch.bit_offset, ch.bit_count
+            )));
+        }
+        let bits = if is_vlsd { 64 } else { ch.bit_count as u64 };
+        if bits == 0 {
+            continue;
         }
+        let start = ch.byte_offset as u64 * 8 + ch.bit_offset as u64;
+        ranges.push((start, start + bits, ch));
     }
+    ranges.sort_by_key(|&(start, _, _)| start);
+    for pair in ranges.windows(2) {
+        let (prev_start, prev_end, prev) = pair[0];
+        let (start, end, ch) = pair[1];
+        if start < prev_end {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "channels '{}' and '{}' overlap in the record layout (bits {prev_start}..{prev_end} and {start}..{end})",
+                name(prev), name(ch)
+            )));
+        }
+    }
+    Ok(())
+}
 
+/// Reject record ID lengths other than the 0, 1, 2, 4 or 8 bytes allowed
+/// for `dg_rec_id_size`.
+fn check_record_id_len(record_id_len: u8) -> Result<(), MdfError> {
+    if !matches!(record_id_len, 0 | 1 | 2 | 4 | 8) {
+        return Err(MdfError::InvalidRecordLayout(format!(
+            "record ID length must be 0, 1, 2, 4 or 8 bytes, found {record_id_len}"
+        )));
+    }
+    Ok(())
 }
 
 const MAX_DT_BLOCK_SIZE: usize = 4 * 1024 * 1024;
 
+/// Default data section size of a `##DT` block: [`MAX_DT_BLOCK_SIZE`]
+/// including the block header.
+pub(super) const DEFAULT_DATA_BLOCK_SIZE: usize = MAX_DT_BLOCK_SIZE - 24;
 
-fn encode_values(encoders: &[ChannelEncoder], buf: &mut [u8], values: &[DecodedValue]) {
+/// Maximum `##DT` block length (header included) used for a group whose
+/// records are `record_size` bytes wide, given a data section size of
+/// `data_block_size`.
+///
+/// A single record wider than the data section (e.g. a large byte-array
+/// channel) would otherwise never fit and the split logic would emit an
+/// empty block per record. Such groups get a cap of exactly one record per
+/// block instead.
+fn dt_block_limit(record_size: usize, data_block_size: usize) -> usize {
+    24 + data_block_size.max(record_size)
+}
+
+
+/// Append encoded record bytes to a group's open data block: straight to the
+/// file, or to `zip_buffer` when the block is written as `##DZ` on close.
+pub(super) fn append_data(
+    file: &mut dyn Write,
+    offset: &mut u64,
+    zip_buffer: &mut Option<Vec<u8>>,
+    bytes: &[u8],
+) -> Result<(), MdfError> {
+    match zip_buffer {
+        Some(buf) => buf.extend_from_slice(bytes),
+        None => {
+            file.write_all(bytes)?;
+            *offset += bytes.len() as u64;
+        }
+    }
+    Ok(())
+}
+
+fn encode_values(encoders: &[ChannelEncoder], buf: &mut [u8], values: &[DecodedValue]) -> Result<(), MdfError> {
     for (enc, val) in encoders.iter().zip(values.iter()) {
-        enc.encode(buf, val);
+        enc.encode(buf, val)?;
     }
+    Ok(())
 }
 
 /// Encode a record, handling VLSD channels by appending payloads to the
 /// per-channel buffers in `dt.vlsd_payloads` and writing the running offset
 /// into `dt.record_buf`. Non-VLSD channels are encoded in-place via
 /// `ChannelEncoder::encode`.
-fn encode_record(dt: &mut super::OpenDataBlock, values: &[DecodedValue]) {
+fn encode_record(dt: &mut super::OpenDataBlock, values: &[DecodedValue]) -> Result<(), MdfError> {
     for (i, val) in values.iter().enumerate() {
-        match &dt.encoders[i] {
-            ChannelEncoder::VlsdOffset { offset, channel_index } => {
-                let off = *offset;
-                let ch_idx = *channel_index;
-                let buf = dt.vlsd_payloads[ch_idx]
-                    .as_mut()
-                    .expect("VLSD encoder requires payload buffer");
-                let cur = buf.len() as u64;
-                dt.record_buf[off..off + 8].copy_from_slice(&cur.to_le_bytes());
-                let bytes: &[u8] = match val {
-                    DecodedValue::ByteArray(b)
-                    | DecodedValue::MimeSample(b)
-                    | DecodedValue::MimeStream(b) => b.as_slice(),
-                    DecodedValue::String(s) => s.as_bytes(),
-                    _ => &[],
-                };
-                buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
-                buf.extend_from_slice(bytes);
-            }
-            enc => enc.encode(&mut dt.record_buf, val),
+        encode_channel(dt, i, val)?;
+    }
+    Ok(())
+}
+
+/// Encode the values of the varying channels (see
+/// [`MdfWriter::set_constant_channels`]) over the template in
+/// `dt.record_buf`.
+fn encode_varying(dt: &mut super::OpenDataBlock, values: &[DecodedValue]) -> Result<(), MdfError> {
+    for (k, val) in values.iter().enumerate() {
+        let i = dt.varying_channels[k];
+        encode_channel(dt, i, val)?;
+    }
+    Ok(())
+}
+
+/// Encode `val` as the sample of channel `i` into `dt.record_buf`.
+fn encode_channel(dt: &mut super::OpenDataBlock, i: usize, val: &DecodedValue) -> Result<(), MdfError> {
+    match &dt.encoders[i] {
+        ChannelEncoder::VlsdOffset { offset, channel_index } => {
+            let off = *offset;
+            let ch_idx = *channel_index;
+            let buf = dt.vlsd_payloads[ch_idx]
+                .as_mut()
+                .expect("VLSD encoder requires payload buffer");
+            let cur = buf.len() as u64;
+            dt.record_buf[off..off + 8].copy_from_slice(&cur.to_le_bytes());
+            let bytes: &[u8] = match val {
+                DecodedValue::ByteArray(b)
+                | DecodedValue::MimeSample(b)
+                | DecodedValue::MimeStream(b) => b.as_slice(),
+                DecodedValue::String(s) => s.as_bytes(),
+                _ => &[],
+            };
+            buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
         }
+        enc => enc.encode(&mut dt.record_buf, val)?,
     }
+    Ok(())
 }
 
-impl MdfWriter {
+impl<W: Write + Seek> MdfWriter<W> {
+    /// An all-zero record of `record_size` bytes starting with the
+    /// `cg_record_id` of `cg_id` in its first `record_id_len` bytes
+    /// (little endian).
+    fn record_with_id(&self, cg_id: &ChannelGroupId, record_id_len: u8, record_size: usize) -> Result<Vec<u8>, MdfError> {
+        check_record_id_len(record_id_len)?;
+        let record_id = self.cg_record_id(cg_id);
+        let id_len = record_id_len as usize;
+        if id_len < 8 && record_id >> (8 * id_len) != 0 {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "record ID {record_id} of channel group '{cg_id}' does not fit {record_id_len} bytes"
+            )));
+        }
+        let mut record = vec![0u8; record_size];
+        record[..id_len].copy_from_slice(&record_id.to_le_bytes()[..id_len]);
+        Ok(record)
+    }
+
     /// Start writing a DTBLOCK for the given data group.
+    ///
+    /// `record_id_len` (0, 1, 2, 4 or 8) bytes lead every record and hold
+    /// the channel group's `record_id`.
     pub fn start_data_block(
         &mut self,
-        dg_id: &str,
-        cg_id: &str,
+        dg_id: &DataGroupId,
+        cg_id: &ChannelGroupId,
         record_id_len: u8,
         channels: &[ChannelBlock],
     ) -> Result<(), MdfError> {
         if self.open_dts.contains_key(cg_id) {
-            return Err(MdfError::BlockSerializationError("data block already open for this channel group".into()));
+            return Err(MdfError::InvalidWriterState("data block already open for this channel group".into()));
         }
+        self.check_group_compliance(cg_id, channels)?;
+        check_record_layout(channels)?;
 
         let mut record_bytes = 0usize;
+        let mut invalidation_bytes = 0usize;
+        let mut invalidation_bits = Vec::with_capacity(channels.len());
         for ch in channels {
             let byte_end = ch.byte_offset as usize + ((ch.bit_offset as usize + ch.bit_count as usize + 7) / 8);
             record_bytes = record_bytes.max(byte_end);
+            if ch.flags & CN_FLAG_INVAL_BIT_VALID != 0 {
+                invalidation_bytes = invalidation_bytes.max((ch.pos_invalidation_bit as usize >> 3) + 1);
+                invalidation_bits.push(Some(ch.pos_invalidation_bit));
+            } else {
+                invalidation_bits.push(None);
+            }
+        }
+        let record_size = record_bytes + record_id_len as usize + invalidation_bytes;
+        if record_size == 0 {
+            return Err(MdfError::InvalidRecordLayout(
+                "channel group has an empty record layout (no channels with data bytes)".into(),
+            ));
+        }
+        if record_bytes > u32::MAX as usize {
+            return Err(MdfError::InvalidRecordLayout(format!(
+                "record data size of {record_bytes} bytes exceeds the u32 cg_data_bytes field"
+            )));
         }
-        let record_size = record_bytes + record_id_len as usize;
 
+        let record_template = self.record_with_id(cg_id, record_id_len, record_size)?;
         let cg_channel_ids = self.cg_channel_ids.get(cg_id).cloned().unwrap_or_default();
-
-        let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-        let header_bytes = header.to_bytes()?;
-        let dt_id = format!("dt_{}", self.dt_counter);
-        self.dt_counter += 1;
-        let dt_pos = self.write_block_with_id(&header_bytes, &dt_id)?;
-
-        let dg_data_link_offset = 40;
-        self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        let continued =
+            self.check_continued_group(cg_id, record_id_len, record_bytes as u32, invalidation_bytes as u32)?;
+
+        let compression = self.compression;
+        // The groups of an unsorted data group buffer their records for the
+        // shared stream instead of writing data blocks of their own.
+        let unsorted = self.unsorted_streams.contains_key(dg_id);
+        let (dt_id, dt_pos) = if unsorted { (String::new(), 0) } else { self.begin_data_block(compression)? };
+        if dt_pos != 0 && !continued {
+            let dg_data_link_offset = 40;
+            self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        }
         self.update_block_u8(dg_id, 56, record_id_len)?;
         self.update_block_u32(cg_id, 96, record_bytes as u32)?;
+        self.update_block_u32(cg_id, 100, invalidation_bytes as u32)?;
 
         let mut encoders = Vec::new();
         let mut vlsd_payloads: Vec<Option<Vec<u8>>> = Vec::with_capacity(channels.len());
-        let mut vlsd_channel_ids: Vec<Option<String>> = Vec::with_capacity(channels.len());
+        let mut vlsd_channel_ids: Vec<Option<ChannelId>> = Vec::with_capacity(channels.len());
         for (i, ch) in channels.iter().enumerate() {
             let offset = record_id_len as usize + ch.byte_offset as usize;
             let bytes = ((ch.bit_count + 7) / 8) as usize;
@@ -157,18 +577,49 @@ impl MdfWriter {
                 ChannelEncoder::VlsdOffset { offset, channel_index: i }
             } else {
                 match ch.data_type {
-                    DataType::UnsignedIntegerLE => ChannelEncoder::UInt { offset, bytes },
-                    DataType::SignedIntegerLE => ChannelEncoder::Int { offset, bytes },
-                    DataType::FloatLE => {
-                        if ch.bit_count == 32 {
-                            ChannelEncoder::F32 { offset }
-                        } else {
-                            ChannelEncoder::F64 { offset }
+                    DataType::UnsignedIntegerLE
+                    | DataType::UnsignedIntegerBE
+                    | DataType::SignedIntegerLE
+                    | DataType::SignedIntegerBE
+                        if is_packed(ch) =>
+                    {
+                        ChannelEncoder::Packed {
+                            offset,
+                            bit_offset: ch.bit_offset,
+                            bit_count: ch.bit_count,
+                            signed: matches!(ch.data_type, DataType::SignedIntegerLE | DataType::SignedIntegerBE),
+                            big_endian: matches!(ch.data_type, DataType::UnsignedIntegerBE | DataType::SignedIntegerBE),
                         }
                     }
+                    DataType::UnsignedIntegerLE => ChannelEncoder::UInt { offset, bytes },
+                    DataType::SignedIntegerLE => ChannelEncoder::Int { offset, bytes },
+                    DataType::UnsignedIntegerBE => ChannelEncoder::UIntBE { offset, bytes },
+                    DataType::SignedIntegerBE => ChannelEncoder::IntBE { offset, bytes },
+                    DataType::FloatLE => match ch.bit_count {
+                        16 => ChannelEncoder::F16 { offset },
+                        32 => ChannelEncoder::F32 { offset },
+                        _ => ChannelEncoder::F64 { offset },
+                    },
+                    DataType::FloatBE => match ch.bit_count {
+                        16 => ChannelEncoder::F16BE { offset },
+                        32 => ChannelEncoder::F32BE { offset },
+                        _ => ChannelEncoder::F64BE { offset },
+                    },
+                    DataType::StringLatin1
+                    | DataType::StringUtf8
+                    | DataType::StringUtf16LE
+                    | DataType::StringUtf16BE => ChannelEncoder::Str {
+                        offset,
+                        bytes,
+                        data_type: ch.data_type.clone(),
+                    },
                     DataType::ByteArray | DataType::MimeSample | DataType::MimeStream => {
                         ChannelEncoder::Bytes { offset, bytes }
                     }
+                    DataType::CanOpenDate => ChannelEncoder::CanOpenDate { offset },
+                    DataType::CanOpenTime => ChannelEncoder::CanOpenTime { offset },
+                    // Virtual channels; every other type was rejected by
+                    // `check_record_layout`.
                     _ => ChannelEncoder::Skip,
                 }
             };
@@ -183,12 +634,13 @@ impl MdfWriter {
         }
 
         self.open_dts.insert(
-            cg_id.to_string(),
+            *cg_id,
             OpenDataBlock {
-                dg_id: dg_id.to_string(),
+                dg_id: *dg_id,
                 dt_id: dt_id.clone(),
                 start_pos: dt_pos,
                 record_size,
+                max_block_size: dt_block_limit(record_size, self.data_block_size),
                 record_count: 0,
                 total_record_count: 0,
                 channels: channels.to_vec(),
@@ -196,23 +648,32 @@ impl MdfWriter {
                 dt_positions: vec![dt_pos],
                 dt_sizes: Vec::new(),
                 record_buf: vec![0u8; record_size],
-                record_template: vec![0u8; record_size],
+                record_template,
+                record_id_len: record_id_len as usize,
+                varying_channels: (0..encoders.len()).collect(),
                 encoders,
+                invalidation_offset: record_id_len as usize + record_bytes,
+                invalidation_bits,
                 vlsd_payloads,
                 vlsd_channel_ids,
+                live_dl_positions: Vec::new(),
+                published_records: 0,
+                last_publish: self.live_publish_clock(),
+                compression,
+                zip_buffer: (compression != CompressionMethod::None || unsorted).then(Vec::new),
             },
         );
-        Ok(())
+        self.flush_for_live_readers()
     }
 
     /// Convenience wrapper to start a data block for a channel group without specifying its data group explicitly.
     pub fn start_data_block_for_cg(
         &mut self,
-        cg_id: &str,
+        cg_id: &ChannelGroupId,
         record_id_len: u8,
     ) -> Result<(), MdfError> {
-        let dg = self.cg_to_dg.get(cg_id).ok_or_else(|| MdfError::BlockSerializationError("unknown channel group".into()))?.clone();
-        let channels = self.cg_channels.get(cg_id).ok_or_else(|| MdfError::BlockSerializationError("no channels for channel group".into()))?.clone();
+        let dg = *self.cg_to_dg.get(cg_id).ok_or_else(|| MdfError::InvalidWriterState("unknown channel group".into()))?;
+        let channels = self.cg_channels.get(cg_id).ok_or_else(|| MdfError::InvalidWriterState("no channels for channel group".into()))?.clone();
         self.start_data_block(&dg, cg_id, record_id_len, &channels)
     }
 
@@ -230,39 +691,45 @@ impl MdfWriter {
     /// trailers) verbatim from a source file.
     pub fn start_data_block_for_cg_raw(
         &mut self,
-        cg_id: &str,
+        cg_id: &ChannelGroupId,
         record_id_len: u8,
         data_bytes: u32,
         invalidation_bytes: u32,
     ) -> Result<(), MdfError> {
         if self.open_dts.contains_key(cg_id) {
-            return Err(MdfError::BlockSerializationError(
+            return Err(MdfError::InvalidWriterState(
                 "data block already open for this channel group".into(),
             ));
         }
-        let dg_id = self
+        let dg_id = *self
             .cg_to_dg
             .get(cg_id)
-            .ok_or_else(|| MdfError::BlockSerializationError("unknown channel group".into()))?
-            .clone();
+            .ok_or_else(|| MdfError::InvalidWriterState("unknown channel group".into()))?;
         let channels = self
             .cg_channels
             .get(cg_id)
-            .ok_or_else(|| MdfError::BlockSerializationError("no channels for channel group".into()))?
+            .ok_or_else(|| MdfError::InvalidWriterState("no channels for channel group".into()))?
             .clone();
+        self.check_group_compliance(cg_id, &channels)?;
 
         let record_size =
             record_id_len as usize + data_bytes as usize + invalidation_bytes as usize;
+        if record_size == 0 {
+            return Err(MdfError::InvalidRecordLayout(
+                "channel group has an empty record layout (no channels with data bytes)".into(),
+            ));
+        }
 
-        let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-        let header_bytes = header.to_bytes()?;
-        let dt_id = format!("dt_{}", self.dt_counter);
-        self.dt_counter += 1;
-        let dt_pos = self.write_block_with_id(&header_bytes, &dt_id)?;
+        let record_template = self.record_with_id(cg_id, record_id_len, record_size)?;
+        let continued = self.check_continued_group(cg_id, record_id_len, data_bytes, invalidation_bytes)?;
 
-        let dg_data_link_offset = 40;
-        self.update_block_link(&dg_id, dg_data_link_offset, &dt_id)?;
-        self.update_block_u8(&dg_id, 56, record_id_len)?;
+        let compression = self.compression;
+        let (dt_id, dt_pos) = self.begin_data_block(compression)?;
+        if dt_pos != 0 && !continued {
+            let dg_data_link_offset = 40;
+            self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        }
+        self.update_block_u8(dg_id, 56, record_id_len)?;
         // Patch CG.samples_byte_nr (offset 96) and CG.invalidation_bytes_nr (offset 100).
         self.update_block_u32(cg_id, 96, data_bytes)?;
         self.update_block_u32(cg_id, 100, invalidation_bytes)?;
@@ -274,12 +741,13 @@ impl MdfWriter {
             channels.iter().map(|_| ChannelEncoder::Skip).collect();
 
         self.open_dts.insert(
-            cg_id.to_string(),
+            *cg_id,
             OpenDataBlock {
                 dg_id,
                 dt_id: dt_id.clone(),
                 start_pos: dt_pos,
                 record_size,
+                max_block_size: dt_block_limit(record_size, self.data_block_size),
                 record_count: 0,
                 total_record_count: 0,
                 channels,
@@ -287,82 +755,203 @@ impl MdfWriter {
                 dt_positions: vec![dt_pos],
                 dt_sizes: Vec::new(),
                 record_buf: vec![0u8; record_size],
-                record_template: vec![0u8; record_size],
+                record_template,
+                record_id_len: record_id_len as usize,
                 encoders,
+                varying_channels: (0..channel_count).collect(),
+                invalidation_offset: record_id_len as usize + data_bytes as usize,
+                invalidation_bits: vec![None; channel_count],
                 vlsd_payloads: vec![None; channel_count],
                 vlsd_channel_ids: vec![None; channel_count],
+                live_dl_positions: Vec::new(),
+                published_records: 0,
+                last_publish: self.live_publish_clock(),
+                compression,
+                zip_buffer: (compression != CompressionMethod::None).then(Vec::new),
             },
         );
-        Ok(())
+        self.flush_for_live_readers()
     }
 
-    /// Precomputes constant values for a channel group. The provided slice must
-    /// have the same length as the channel list and will be encoded into the
-    /// internal record template used for each record.
+    /// Set the record template of the open data block of `cg_id`: the bytes
+    /// every record starts from before its values are encoded.
+    ///
+    /// `values` holds one value per channel; [`DecodedValue::Unknown`]
+    /// leaves a channel zeroed. Every record write still encodes all the
+    /// values it is given, but a channel passed `Unknown` in
+    /// [`write_record`](Self::write_record) or
+    /// [`write_records`](Self::write_records) keeps its template value, and
+    /// the raw fast paths ([`write_record_u64`](Self::write_record_u64),
+    /// [`write_columns`](Self::write_columns), ...) keep the template bytes
+    /// of any padding. To leave constant channels out of the per-record
+    /// values altogether, use
+    /// [`set_constant_channels`](Self::set_constant_channels).
+    ///
+    /// The template lasts until the data block is finished, across
+    /// `##DT` splits.
     pub fn set_record_template(
         &mut self,
-        cg_id: &str,
+        cg_id: &ChannelGroupId,
         values: &[DecodedValue],
     ) -> Result<(), MdfError> {
         let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
-            MdfError::BlockSerializationError("no open DT block for this channel group".into())
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
         })?;
         if values.len() != dt.channels.len() {
-            return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+            return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: values.len() });
         }
-        dt.record_template.fill(0);
-        encode_values(&dt.encoders, &mut dt.record_template, values);
+        dt.record_template[dt.record_id_len..].fill(0);
+        encode_values(&dt.encoders, &mut dt.record_template, values)?;
         Ok(())
     }
 
-    /// Append one record to the currently open DTBLOCK for the given channel group.
-    pub fn write_record(&mut self, cg_id: &str, values: &[DecodedValue]) -> Result<(), MdfError> {
+    /// Fix the channels of `cg_id` given `Some` value in `constants` (one
+    /// entry per channel) to that value for the rest of the open data block.
+    ///
+    /// The constants are encoded once into the record template (replacing
+    /// any earlier [`set_record_template`](Self::set_record_template)), and
+    /// the `None` channels become the *varying* channels: the only ones
+    /// [`write_varying_record`](Self::write_varying_record),
+    /// [`write_varying_records`](Self::write_varying_records) and
+    /// [`write_varying_columns`](Self::write_varying_columns) take values
+    /// for, so a mostly static group is written as little more than a copy
+    /// of the template per record. VLSD channels cannot be constant.
+    pub fn set_constant_channels(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        constants: &[Option<DecodedValue>],
+    ) -> Result<(), MdfError> {
+        let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?;
+        if constants.len() != dt.channels.len() {
+            return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: constants.len() });
+        }
+        if let Some(i) = constants
+            .iter()
+            .zip(&dt.vlsd_payloads)
+            .position(|(constant, vlsd)| constant.is_some() && vlsd.is_some())
+        {
+            return Err(MdfError::UnsupportedFeature(format!(
+                "channel {i} is a VLSD channel and cannot be constant"
+            )));
+        }
+        dt.record_template[dt.record_id_len..].fill(0);
+        for (enc, constant) in dt.encoders.iter().zip(constants) {
+            if let Some(value) = constant {
+                enc.encode(&mut dt.record_template, value)?;
+            }
+        }
+        dt.varying_channels = constants
+            .iter()
+            .enumerate()
+            .filter_map(|(i, constant)| constant.is_none().then_some(i))
+            .collect();
+        Ok(())
+    }
+
+    /// Append one record giving values for the varying channels only (all
+    /// channels unless [`set_constant_channels`](Self::set_constant_channels)
+    /// was called), in channel order; the constant channels keep their
+    /// template values.
+    pub fn write_varying_record(&mut self, cg_id: &ChannelGroupId, values: &[DecodedValue]) -> Result<(), MdfError> {
         let potential_new_block = {
-            let dt = self.open_dts.get(cg_id).ok_or_else(|| MdfError::BlockSerializationError("no open DT block for this channel group".into()))?;
-            if values.len() != dt.channels.len() {
-                return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| MdfError::InvalidWriterState("no open DT block for this channel group".into()))?;
+            if values.len() != dt.varying_channels.len() {
+                return Err(MdfError::ValueCountMismatch { expected: dt.varying_channels.len(), actual: values.len() });
             }
-            24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+            24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
         };
 
         if potential_new_block {
-            let (start_pos, record_count, record_size) = {
-                let dt = self.open_dts.get(cg_id).unwrap();
-                (dt.start_pos, dt.record_count, dt.record_size)
-            };
-            let size = 24 + record_size * record_count as usize;
-            self.update_link(start_pos + 8, size as u64)?;
-            {
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.total_record_count += record_count;
-                dt.dt_sizes.push(size as u64);
+            self.split_dt_block(cg_id, &mut Vec::new())?;
+        }
+
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.record_buf.copy_from_slice(&dt.record_template);
+        encode_varying(dt, values)?;
+
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, &dt.record_buf)?;
+        dt.record_count += 1;
+        self.maybe_publish_progress(cg_id)
+    }
+
+    /// Append one record to the currently open DTBLOCK for the given channel group.
+    pub fn write_record(&mut self, cg_id: &ChannelGroupId, values: &[DecodedValue]) -> Result<(), MdfError> {
+        let potential_new_block = {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| MdfError::InvalidWriterState("no open DT block for this channel group".into()))?;
+            if values.len() != dt.channels.len() {
+                return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: values.len() });
             }
-            let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-            let header_bytes = header.to_bytes()?;
-            let new_dt_id = format!("dt_{}", self.dt_counter);
-            self.dt_counter += 1;
-            let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
+            24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
+        };
 
-            let dt = self.open_dts.get_mut(cg_id).unwrap();
-            dt.dt_id = new_dt_id.clone();
-            dt.start_pos = new_dt_pos;
-            dt.record_count = 0;
-            dt.dt_ids.push(new_dt_id);
-            dt.dt_positions.push(new_dt_pos);
+        if potential_new_block {
+            self.split_dt_block(cg_id, &mut Vec::new())?;
         }
 
         let dt = self.open_dts.get_mut(cg_id).unwrap();
         if values.len() != dt.channels.len() {
-            return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+            return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: values.len() });
         }
 
         dt.record_buf.copy_from_slice(&dt.record_template);
-        encode_record(dt, values);
+        encode_record(dt, values)?;
 
-        self.file.write_all(&dt.record_buf)?;
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, &dt.record_buf)?;
         dt.record_count += 1;
-        self.offset += dt.record_buf.len() as u64;
-        Ok(())
+        self.maybe_publish_progress(cg_id)
+    }
+
+    /// Append one record whose samples may be invalid.
+    ///
+    /// `None` entries are written as zeroed samples with the channel's
+    /// invalidation bit set, so readers decode them as invalid. Only channels
+    /// configured with an invalidation bit (`cn_flags` bit 1 and
+    /// `pos_invalidation_bit`) before [`start_data_block`] may receive `None`.
+    pub fn write_record_with_validity(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        values: &[Option<DecodedValue>],
+    ) -> Result<(), MdfError> {
+        let potential_new_block = {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| MdfError::InvalidWriterState("no open DT block for this channel group".into()))?;
+            if values.len() != dt.channels.len() {
+                return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: values.len() });
+            }
+            if let Some(i) = values
+                .iter()
+                .zip(dt.invalidation_bits.iter())
+                .position(|(v, bit)| v.is_none() && bit.is_none())
+            {
+                return Err(MdfError::InvalidRecordLayout(format!(
+                    "channel {i} has no invalidation bit and cannot store an invalid sample"
+                )));
+            }
+            24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
+        };
+
+        if potential_new_block {
+            self.split_dt_block(cg_id, &mut Vec::new())?;
+        }
+
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        let decoded: Vec<DecodedValue> = values
+            .iter()
+            .map(|v| v.clone().unwrap_or(DecodedValue::Unknown))
+            .collect();
+        dt.record_buf.copy_from_slice(&dt.record_template);
+        encode_record(dt, &decoded)?;
+        for (v, bit) in values.iter().zip(dt.invalidation_bits.iter()) {
+            if let (None, Some(pos)) = (v, bit) {
+                let byte = dt.invalidation_offset + (*pos as usize >> 3);
+                dt.record_buf[byte] |= 1 << (pos & 0x07);
+            }
+        }
+
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, &dt.record_buf)?;
+        dt.record_count += 1;
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Append one record to the open DTBLOCK as a verbatim byte copy.
@@ -373,206 +962,196 @@ impl MdfWriter {
     /// used by the cut/merge code to preserve raw record content (including
     /// invalidation bits and any unencoded channel layouts) without going
     /// through the decode/re-encode round trip.
-    pub fn write_raw_record(&mut self, cg_id: &str, raw: &[u8]) -> Result<(), MdfError> {
+    pub fn write_raw_record(&mut self, cg_id: &ChannelGroupId, raw: &[u8]) -> Result<(), MdfError> {
         let potential_new_block = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError(
+                MdfError::InvalidWriterState(
                     "no open DT block for this channel group".into(),
                 )
             })?;
             if raw.len() != dt.record_size {
-                return Err(MdfError::BlockSerializationError(
-                    "raw record size mismatch".into(),
-                ));
+                return Err(MdfError::LayoutMismatch(format!(
+                    "raw record of {} bytes, expected {}",
+                    raw.len(),
+                    dt.record_size
+                )));
             }
-            24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+            24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
         };
 
         if potential_new_block {
-            let (start_pos, record_count, record_size) = {
-                let dt = self.open_dts.get(cg_id).unwrap();
-                (dt.start_pos, dt.record_count, dt.record_size)
-            };
-            let size = 24 + record_size * record_count as usize;
-            self.update_link(start_pos + 8, size as u64)?;
-            {
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.total_record_count += record_count;
-                dt.dt_sizes.push(size as u64);
-            }
-            let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-            let header_bytes = header.to_bytes()?;
-            let new_dt_id = format!("dt_{}", self.dt_counter);
-            self.dt_counter += 1;
-            let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
-
-            let dt = self.open_dts.get_mut(cg_id).unwrap();
-            dt.dt_id = new_dt_id.clone();
-            dt.start_pos = new_dt_pos;
-            dt.record_count = 0;
-            dt.dt_ids.push(new_dt_id);
-            dt.dt_positions.push(new_dt_pos);
+            self.split_dt_block(cg_id, &mut Vec::new())?;
         }
 
-        self.file.write_all(raw)?;
         let dt = self.open_dts.get_mut(cg_id).unwrap();
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, raw)?;
         dt.record_count += 1;
-        self.offset += raw.len() as u64;
-        Ok(())
+        self.maybe_publish_progress(cg_id)
+    }
+
+    /// Append records already encoded back to back in `bytes`, splitting
+    /// data blocks as [`write_records`](MdfWriter::write_records) does.
+    pub(crate) fn write_raw_records(&mut self, cg_id: &ChannelGroupId, bytes: &[u8]) -> Result<(), MdfError> {
+        let record_size = self.open_dts.get(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?.record_size;
+        if !bytes.len().is_multiple_of(record_size) {
+            return Err(MdfError::LayoutMismatch(format!(
+                "{} bytes of raw records are not a multiple of the {record_size}-byte record size",
+                bytes.len()
+            )));
+        }
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let dt = self.open_dts.get(cg_id).unwrap();
+            let room = ((dt.max_block_size - 24) / record_size).saturating_sub(dt.record_count as usize);
+            if room == 0 {
+                self.split_dt_block(cg_id, &mut Vec::new())?;
+                continue;
+            }
+            let (chunk, tail) = rest.split_at((room * record_size).min(rest.len()));
+            self.write_data(cg_id, chunk)?;
+            self.open_dts.get_mut(cg_id).unwrap().record_count += (chunk.len() / record_size) as u64;
+            rest = tail;
+        }
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Fast path for uniform unsigned integer channel groups.
-    pub fn write_record_u64(&mut self, cg_id: &str, values: &[u64]) -> Result<(), MdfError> {
+    pub fn write_record_u64(&mut self, cg_id: &ChannelGroupId, values: &[u64]) -> Result<(), MdfError> {
         let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
-            MdfError::BlockSerializationError("no open DT block for this channel group".into())
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
         })?;
         if values.len() != dt.encoders.len() {
-            return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+            return Err(MdfError::ValueCountMismatch { expected: dt.encoders.len(), actual: values.len() });
         }
-        if !dt.encoders.iter().all(|e| matches!(e, ChannelEncoder::UInt { .. })) {
-            return Err(MdfError::BlockSerializationError("channel types not unsigned".into()));
+        if !dt.encoders.iter().all(ChannelEncoder::takes_u64) {
+            return Err(MdfError::ValueTypeMismatch("channel types not unsigned".into()));
         }
         dt.record_buf.copy_from_slice(&dt.record_template);
         for (enc, &v) in dt.encoders.iter().zip(values.iter()) {
             enc.encode_u64(&mut dt.record_buf, v);
         }
-        self.file.write_all(&dt.record_buf)?;
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, &dt.record_buf)?;
         dt.record_count += 1;
-        self.offset += dt.record_buf.len() as u64;
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Append multiple records sequentially for the specified channel group.
     /// The provided iterator yields record value slices. All encoded bytes are
     /// buffered and written in a single call to reduce I/O overhead.
-    pub fn write_records<'a, I>(&mut self, cg_id: &str, records: I) -> Result<(), MdfError>
+    pub fn write_records<'a, I>(&mut self, cg_id: &ChannelGroupId, records: I) -> Result<(), MdfError>
     where
         I: IntoIterator<Item = &'a [DecodedValue]>,
     {
         let record_size = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?.record_size;
             dt
         };
-        let max_records = (MAX_DT_BLOCK_SIZE - 24) / record_size;
+        let max_records = (self.open_dts[cg_id].max_block_size - 24) / record_size;
         let mut buffer = Vec::with_capacity(record_size * max_records);
         for record in records {
             let potential_new_block = {
                 let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                    MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                    MdfError::InvalidWriterState("no open DT block for this channel group".into())
                 })?;
                 if record.len() != dt.channels.len() {
-                    return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+                    return Err(MdfError::ValueCountMismatch { expected: dt.channels.len(), actual: record.len() });
                 }
-                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+                24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
             };
 
             if potential_new_block {
-                self.file.write_all(&buffer)?;
-                self.offset += buffer.len() as u64;
-                buffer.clear();
+                self.split_dt_block(cg_id, &mut buffer)?;
+            }
 
-                let (start_pos, record_count, record_size) = {
-                    let dt = self.open_dts.get(cg_id).unwrap();
-                    (dt.start_pos, dt.record_count, dt.record_size)
-                };
-                let size = 24 + record_size * record_count as usize;
-                self.update_link(start_pos + 8, size as u64)?;
-                {
-                    let dt = self.open_dts.get_mut(cg_id).unwrap();
-                    dt.total_record_count += record_count;
-                    dt.dt_sizes.push(size as u64);
+            let dt = self.open_dts.get_mut(cg_id).unwrap();
+            dt.record_buf.copy_from_slice(&dt.record_template);
+            encode_record(dt, record)?;
+            buffer.extend_from_slice(&dt.record_buf);
+            dt.record_count += 1;
+        }
+
+        if !buffer.is_empty() {
+            self.write_data(cg_id, &buffer)?;
+        }
+        self.maybe_publish_progress(cg_id)
+    }
+
+    /// Batch version of [`write_varying_record`](Self::write_varying_record),
+    /// buffered like [`write_records`](Self::write_records).
+    pub fn write_varying_records<'a, I>(&mut self, cg_id: &ChannelGroupId, records: I) -> Result<(), MdfError>
+    where
+        I: IntoIterator<Item = &'a [DecodedValue]>,
+    {
+        let record_size = self.open_dts.get(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?.record_size;
+        let max_records = (self.open_dts[cg_id].max_block_size - 24) / record_size;
+        let mut buffer = Vec::with_capacity(record_size * max_records);
+        for record in records {
+            let potential_new_block = {
+                let dt = &self.open_dts[cg_id];
+                if record.len() != dt.varying_channels.len() {
+                    return Err(MdfError::ValueCountMismatch { expected: dt.varying_channels.len(), actual: record.len() });
                 }
-                let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-                let header_bytes = header.to_bytes()?;
-                let new_dt_id = format!("dt_{}", self.dt_counter);
-                self.dt_counter += 1;
-                let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
+                24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
+            };
 
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.dt_id = new_dt_id.clone();
-                dt.start_pos = new_dt_pos;
-                dt.record_count = 0;
-                dt.dt_ids.push(new_dt_id);
-                dt.dt_positions.push(new_dt_pos);
+            if potential_new_block {
+                self.split_dt_block(cg_id, &mut buffer)?;
             }
 
             let dt = self.open_dts.get_mut(cg_id).unwrap();
             dt.record_buf.copy_from_slice(&dt.record_template);
-            encode_record(dt, record);
+            encode_varying(dt, record)?;
             buffer.extend_from_slice(&dt.record_buf);
             dt.record_count += 1;
         }
 
         if !buffer.is_empty() {
-            self.file.write_all(&buffer)?;
-            self.offset += buffer.len() as u64;
+            self.write_data(cg_id, &buffer)?;
         }
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Batch write for uniform unsigned integer channel groups.
-    pub fn write_records_u64<'a, I>(&mut self, cg_id: &str, records: I) -> Result<(), MdfError>
+    pub fn write_records_u64<'a, I>(&mut self, cg_id: &ChannelGroupId, records: I) -> Result<(), MdfError>
     where
         I: IntoIterator<Item = &'a [u64]>,
     {
         let record_size = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?.record_size;
             dt
         };
         // Check ONCE that all encoders are unsigned integer type
         {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?;
-            if !dt.encoders.iter().all(|e| matches!(e, ChannelEncoder::UInt { .. })) {
-                return Err(MdfError::BlockSerializationError("channel types not unsigned".into()));
+            if !dt.encoders.iter().all(ChannelEncoder::takes_u64) {
+                return Err(MdfError::ValueTypeMismatch("channel types not unsigned".into()));
             }
         }
-        let max_records = (MAX_DT_BLOCK_SIZE - 24) / record_size;
+        let max_records = (self.open_dts[cg_id].max_block_size - 24) / record_size;
         let mut buffer = Vec::with_capacity(record_size * max_records);
         for rec in records {
             let potential_new_block = {
                 let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                    MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                    MdfError::InvalidWriterState("no open DT block for this channel group".into())
                 })?;
                 if rec.len() != dt.encoders.len() {
-                    return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+                    return Err(MdfError::ValueCountMismatch { expected: dt.encoders.len(), actual: rec.len() });
                 }
-                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+                24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
             };
 
             if potential_new_block {
-                self.file.write_all(&buffer)?;
-                self.offset += buffer.len() as u64;
-                buffer.clear();
-
-                let (start_pos, record_count, record_size) = {
-                    let dt = self.open_dts.get(cg_id).unwrap();
-                    (dt.start_pos, dt.record_count, dt.record_size)
-                };
-                let size = 24 + record_size * record_count as usize;
-                self.update_link(start_pos + 8, size as u64)?;
-                {
-                    let dt = self.open_dts.get_mut(cg_id).unwrap();
-                    dt.total_record_count += record_count;
-                    dt.dt_sizes.push(size as u64);
-                }
-                let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-                let header_bytes = header.to_bytes()?;
-                let new_dt_id = format!("dt_{}", self.dt_counter);
-                self.dt_counter += 1;
-                let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
-
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.dt_id = new_dt_id.clone();
-                dt.start_pos = new_dt_pos;
-                dt.record_count = 0;
-                dt.dt_ids.push(new_dt_id);
-                dt.dt_positions.push(new_dt_pos);
+                self.split_dt_block(cg_id, &mut buffer)?;
             }
 
             let dt = self.open_dts.get_mut(cg_id).unwrap();
@@ -585,37 +1164,71 @@ impl MdfWriter {
         }
 
         if !buffer.is_empty() {
-            self.file.write_all(&buffer)?;
-            self.offset += buffer.len() as u64;
+            self.write_data(cg_id, &buffer)?;
         }
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Helper: finalize the current DT block fragment, update its size, and start a new one.
-    /// Called internally when a DT block would exceed MAX_DT_BLOCK_SIZE.
-    fn split_dt_block(&mut self, cg_id: &str, buffer: &mut Vec<u8>) -> Result<(), MdfError> {
+    /// Called internally when a DT block would exceed the group's block limit.
+    pub(super) fn split_dt_block(&mut self, cg_id: &ChannelGroupId, buffer: &mut Vec<u8>) -> Result<(), MdfError> {
         // Flush pending bytes first
         if !buffer.is_empty() {
-            self.file.write_all(buffer)?;
-            self.offset += buffer.len() as u64;
+            self.write_data(cg_id, buffer)?;
             buffer.clear();
         }
-        let (start_pos, record_count, record_size) = {
-            let dt = self.open_dts.get(cg_id).unwrap();
-            (dt.start_pos, dt.record_count, dt.record_size)
+        if self.hand_over_unsorted(cg_id)? {
+            return Ok(());
+        }
+        self.close_dt_fragment(cg_id)?;
+
+        // Live readers must be able to reach the next DT block as soon as it
+        // exists, so chain the closed blocks and write the DL for the new one
+        // (patched below) before the new DT header. Once started, the chain
+        // is kept up even if live updates are switched off again.
+        let chaining = self.live_update.is_some() || !self.open_dts[cg_id].live_dl_positions.is_empty();
+        let live_dl_pos = if chaining {
+            self.chain_live_data_blocks(cg_id)?;
+            self.append_next_data_list(cg_id)?
+        } else {
+            None
         };
-        let size = 24 + record_size * record_count as usize;
-        self.update_link(start_pos + 8, size as u64)?;
-        {
-            let dt = self.open_dts.get_mut(cg_id).unwrap();
-            dt.total_record_count += record_count;
-            dt.dt_sizes.push(size as u64);
+        self.open_dt_fragment(cg_id, live_dl_pos)
+    }
+
+    /// Close the group's current data block fragment (see
+    /// [`MdfWriter::end_data_block`]) and account for its records.
+    pub(super) fn close_dt_fragment(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        let size = self.end_data_block(cg_id)?;
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.total_record_count += dt.record_count;
+        dt.dt_sizes.push(size);
+        Ok(())
+    }
+
+    /// Write the DL block for the group's next data block fragment, which
+    /// [`MdfWriter::open_dt_fragment`] links once the fragment exists.
+    /// Compressed fragments are only written on close, so they get their DL
+    /// from [`MdfWriter::chain_live_data_blocks`] afterwards instead.
+    pub(super) fn append_next_data_list(&mut self, cg_id: &ChannelGroupId) -> Result<Option<u64>, MdfError> {
+        let dt = &self.open_dts[cg_id];
+        if dt.zip_buffer.is_some() {
+            return Ok(None);
+        }
+        let data_offset = dt.dt_sizes.iter().map(|s| s - 24).sum();
+        self.append_live_data_list(cg_id, 0, data_offset).map(Some)
+    }
+
+    /// Start the group's next data block fragment after
+    /// [`MdfWriter::close_dt_fragment`], linking it from the DL block at
+    /// `dl_pos` if given.
+    pub(super) fn open_dt_fragment(&mut self, cg_id: &ChannelGroupId, dl_pos: Option<u64>) -> Result<(), MdfError> {
+        let compression = self.open_dts[cg_id].compression;
+        let (new_dt_id, new_dt_pos) = self.begin_data_block(compression)?;
+        if let Some(dl_pos) = dl_pos {
+            // First data link of the DL block, after its header and `next` link.
+            self.update_link(dl_pos + 32, new_dt_pos)?;
         }
-        let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
-        let header_bytes = header.to_bytes()?;
-        let new_dt_id = format!("dt_{}", self.dt_counter);
-        self.dt_counter += 1;
-        let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
 
         let dt = self.open_dts.get_mut(cg_id).unwrap();
         dt.dt_id = new_dt_id.clone();
@@ -626,45 +1239,312 @@ impl MdfWriter {
         Ok(())
     }
 
+    /// Append bytes to the group's open data block; see [`append_data`].
+    fn write_data(&mut self, cg_id: &ChannelGroupId, bytes: &[u8]) -> Result<(), MdfError> {
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        append_data(&mut self.file, &mut self.offset, &mut dt.zip_buffer, bytes)
+    }
+
+    /// Start a new data block fragment and return its block ID and position.
+    ///
+    /// An uncompressed fragment gets its `##DT` header written now and its
+    /// length patched on close. A compressed fragment is only written on
+    /// close (see [`MdfWriter::end_data_block`]), so its position is 0 until
+    /// then.
+    pub(super) fn begin_data_block(&mut self, compression: CompressionMethod) -> Result<(String, u64), MdfError> {
+        let n = self.dt_counter;
+        self.dt_counter += 1;
+        if compression != CompressionMethod::None {
+            return Ok((format!("dz_{}", n), 0));
+        }
+        let header = BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 };
+        let dt_id = format!("dt_{}", n);
+        let dt_pos = self.write_block_with_id(&header.to_bytes()?, &dt_id)?;
+        Ok((dt_id, dt_pos))
+    }
+
+    /// Close the group's current data block fragment: patch the `##DT`
+    /// length, or compress the buffered records into a `##DZ` block (a bare
+    /// `##DT` header if there are none). The first block of a group becomes
+    /// the DG data link; a DL replaces it once the group spans several
+    /// blocks. Returns the uncompressed block length.
+    fn end_data_block(&mut self, cg_id: &ChannelGroupId) -> Result<u64, MdfError> {
+        let continued = self.is_continued_group(cg_id);
+        let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?;
+        let size = 24 + dt.record_size as u64 * dt.record_count;
+        let Some(buffer) = dt.zip_buffer.as_mut() else {
+            let start_pos = dt.start_pos;
+            self.update_link(start_pos + 8, size)?;
+            return Ok(size);
+        };
+        let data = std::mem::take(buffer);
+        let dt_id = dt.dt_id.clone();
+        let dg_id = dt.dg_id;
+        // A continued group keeps its old data linked until it is finished.
+        let first = dt.dt_positions.len() == 1 && !continued;
+        let bytes = match dt.compression {
+            _ if data.is_empty() => {
+                BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 }.to_bytes()?
+            }
+            CompressionMethod::TransposedZlib => {
+                DataZippedBlock::compress("DT", &data, ZIP_TYPE_TRANSPOSE_DEFLATE, dt.record_size as u32)?.to_bytes()?
+            }
+            _ => DataZippedBlock::compress("DT", &data, ZIP_TYPE_DEFLATE, 0)?.to_bytes()?,
+        };
+        let pos = self.write_block_with_id(&bytes, &dt_id)?;
+        if first {
+            let dg_data_link_offset = 40;
+            self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        }
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.start_pos = pos;
+        *dt.dt_positions.last_mut().unwrap() = pos;
+        Ok(size)
+    }
+
+    /// Select the compression used for data blocks opened from now on.
+    ///
+    /// With [`CompressionMethod::Zlib`] or [`CompressionMethod::TransposedZlib`]
+    /// the records of each data block are kept in memory and written as a
+    /// `##DZ` block when the block is split or finished. Not combinable with
+    /// live updates ([`MdfWriter::set_live_update`]), since a compressed block
+    /// cannot be read before it is complete.
+    pub fn set_compression(&mut self, method: CompressionMethod) -> Result<(), MdfError> {
+        if method != CompressionMethod::None && self.live_update.is_some() {
+            return Err(MdfError::CompressedDataUnsupported("with live updates".into()));
+        }
+        self.compression = method;
+        Ok(())
+    }
+
+    /// Select the data section size of data blocks opened from now on
+    /// (default: 4 MiB including the 24-byte block header).
+    ///
+    /// A group's records are split over several data blocks of at most
+    /// `bytes` each, rounded down to whole records (but at least one record
+    /// per block), and chained by a `##DL` block. Smaller blocks let readers
+    /// fetch or decompress a part of the data without the rest.
+    pub fn set_data_block_size(&mut self, bytes: usize) -> Result<(), MdfError> {
+        if bytes == 0 {
+            return Err(MdfError::BlockSerializationError(
+                "data block size must be at least one byte".into(),
+            ));
+        }
+        self.data_block_size = bytes;
+        Ok(())
+    }
+
+    /// Write a DL block for every DT block of the group that is not yet
+    /// chained for live readers. The first one replaces the DG data link.
+    pub(super) fn chain_live_data_blocks(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        let dt = &self.open_dts[cg_id];
+        let pending: Vec<(u64, u64)> = dt.dt_positions[dt.live_dl_positions.len()..]
+            .iter()
+            .zip(dt.dt_sizes[dt.live_dl_positions.len()..].iter())
+            .map(|(&pos, &size)| (pos, size))
+            .collect();
+        let mut data_offset: u64 = dt.dt_sizes[..dt.live_dl_positions.len()].iter().map(|s| s - 24).sum();
+        for (dt_pos, dt_size) in pending {
+            self.append_live_data_list(cg_id, dt_pos, data_offset)?;
+            data_offset += dt_size - 24;
+        }
+        Ok(())
+    }
+
+    /// Append a single-entry DL block pointing at `dt_pos` and link it into
+    /// the group's live DL chain. Returns the DL block's position.
+    fn append_live_data_list(&mut self, cg_id: &ChannelGroupId, dt_pos: u64, data_offset: u64) -> Result<u64, MdfError> {
+        let dl_id = self.next_block_id("dl");
+        let data_offset = data_offset + self.continued_data_len(cg_id);
+        let dl_bytes = DataListBlock::new_variable(vec![dt_pos], vec![data_offset]).to_bytes()?;
+        let dl_pos = self.write_block_with_id(&dl_bytes, &dl_id)?;
+
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        let prev = dt.live_dl_positions.last().copied();
+        dt.live_dl_positions.push(dl_pos);
+        // A continued group's chain starts with a DL of its earlier blocks.
+        let prev = match prev {
+            Some(prev_pos) => Some(prev_pos),
+            None => self.continued_data_list(cg_id)?,
+        };
+        match prev {
+            Some(prev_pos) => self.update_link(prev_pos + 24, dl_pos)?,
+            None => {
+                let dg_id = self.open_dts[cg_id].dg_id;
+                self.update_block_link(dg_id, 40, &dl_id)?;
+            }
+        }
+        Ok(dl_pos)
+    }
+
+    /// Set (or clear, with `None`) the progress publication policy used for
+    /// live readers. Takes effect for data blocks already open as well.
+    pub fn set_live_update(&mut self, policy: Option<LiveUpdate>) -> Result<(), MdfError> {
+        if let Some(p) = policy {
+            if !self.unsorted_streams.is_empty() {
+                return Err(MdfError::UnsupportedFeature(
+                    "live updates cannot be combined with unsorted data groups".into(),
+                ));
+            }
+            if self.compression != CompressionMethod::None
+                || self.open_dts.values().any(|dt| dt.zip_buffer.is_some())
+            {
+                return Err(MdfError::CompressedDataUnsupported("with live updates".into()));
+            }
+            if p.every_records == Some(0) {
+                return Err(MdfError::BlockSerializationError(
+                    "live update record threshold must be at least 1".into(),
+                ));
+            }
+            if cfg!(target_arch = "wasm32") && p.interval.is_some() {
+                return Err(MdfError::UnsupportedFeature(
+                    "time-based live updates are not available on wasm32".into(),
+                ));
+            }
+            if self.open_dts.keys().any(|cg_id| self.is_continued_group(&cg_id)) {
+                return Err(MdfError::UnsupportedFeature(
+                    "live updates cannot be combined with continued channel groups".into(),
+                ));
+            }
+        }
+        self.live_update = policy;
+        let clock = self.live_publish_clock();
+        for dt in self.open_dts.values_mut() {
+            dt.last_publish = clock;
+        }
+        Ok(())
+    }
+
+    /// With live updates, write the queued patches so readers see the
+    /// configured groups as soon as a data block opens.
+    fn flush_for_live_readers(&mut self) -> Result<(), MdfError> {
+        if self.live_update.is_some() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Current time if the live update policy publishes on an interval.
+    fn live_publish_clock(&self) -> Option<std::time::Instant> {
+        self.live_update
+            .and_then(|p| p.interval)
+            .map(|_| std::time::Instant::now())
+    }
+
+    /// Make every record written so far to the group's open data block
+    /// visible to readers of the growing file: flush buffered bytes, then
+    /// patch the DT block length and the channel group's `cycles_nr`.
+    ///
+    /// Called automatically according to [`MdfWriter::set_live_update`], but
+    /// may be called at any time.
+    pub fn publish_progress(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        let (start_pos, size, total) = {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
+            })?;
+            if self.unsorted_streams.contains_key(&dt.dg_id) {
+                return Err(MdfError::UnsupportedFeature("publishing progress of unsorted data groups".into()));
+            }
+            if dt.zip_buffer.is_some() {
+                return Err(MdfError::CompressedDataUnsupported("when publishing progress".into()));
+            }
+            (
+                dt.start_pos,
+                24 + dt.record_size as u64 * dt.record_count,
+                dt.total_record_count + dt.record_count,
+            )
+        };
+        // Records first, then the headers describing them.
+        self.file.flush()?;
+        self.update_link(start_pos + 8, size)?;
+        self.update_block_u64(cg_id, 80, total)?;
+        self.flush()?;
+
+        let clock = self.live_publish_clock();
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.published_records = total;
+        dt.last_publish = clock;
+        Ok(())
+    }
+
+    /// Publish progress if the live update policy says it is due, and
+    /// checkpoint if the automatic checkpoint interval elapsed. Called at the
+    /// end of every write call, which is also when a group of an unsorted
+    /// data group hands its records over to the shared stream.
+    fn maybe_publish_progress(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        self.hand_over_unsorted(cg_id)?;
+        self.maybe_checkpoint()?;
+        let Some(policy) = self.live_update else {
+            return Ok(());
+        };
+        let due = match self.open_dts.get(cg_id) {
+            Some(dt) => {
+                let pending = dt.total_record_count + dt.record_count - dt.published_records;
+                policy.every_records.is_some_and(|n| pending >= n)
+                    || policy
+                        .interval
+                        .zip(dt.last_publish)
+                        .is_some_and(|(every, last)| pending > 0 && last.elapsed() >= every)
+            }
+            None => false,
+        };
+        if due {
+            self.publish_progress(cg_id)?;
+        }
+        Ok(())
+    }
+
     /// Batch write for uniform f64/f32 channel groups.
     ///
     /// Each item yielded by `records` is a slice of `f64` values — one per
     /// channel — for a single record. This avoids the `DecodedValue`
     /// allocation overhead of `write_records` while still supporting mixed
-    /// 32-/64-bit float groups: channels whose encoder is `F32` will have
-    /// their value narrowed to `f32` automatically.
+    /// 16-/32-/64-bit float groups: values of `F16` and `F32` channels are
+    /// narrowed automatically.
     ///
     /// Returns an error if any encoder is not a float type.
-    pub fn write_records_f64<'a, I>(&mut self, cg_id: &str, records: I) -> Result<(), MdfError>
+    pub fn write_records_f64<'a, I>(&mut self, cg_id: &ChannelGroupId, records: I) -> Result<(), MdfError>
     where
         I: IntoIterator<Item = &'a [f64]>,
     {
         let record_size = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?.record_size;
             dt
         };
-        // Check ONCE that all encoders are float types (F32 or F64).
+        // Check ONCE that all encoders are float types.
         {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?;
-            if !dt.encoders.iter().all(|e| matches!(e, ChannelEncoder::F32 { .. } | ChannelEncoder::F64 { .. })) {
-                return Err(MdfError::BlockSerializationError("channel types not float".into()));
+            if !dt.encoders.iter().all(|e| {
+                matches!(
+                    e,
+                    ChannelEncoder::F16 { .. }
+                        | ChannelEncoder::F32 { .. }
+                        | ChannelEncoder::F64 { .. }
+                        | ChannelEncoder::F16BE { .. }
+                        | ChannelEncoder::F32BE { .. }
+                        | ChannelEncoder::F64BE { .. }
+                )
+            }) {
+                return Err(MdfError::ValueTypeMismatch("channel types not float".into()));
             }
         }
-        let max_records = (MAX_DT_BLOCK_SIZE - 24) / record_size;
+        let max_records = (self.open_dts[cg_id].max_block_size - 24) / record_size;
         let mut buffer = Vec::with_capacity(record_size * max_records);
         for rec in records {
             let potential_new_block = {
                 let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                    MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                    MdfError::InvalidWriterState("no open DT block for this channel group".into())
                 })?;
                 if rec.len() != dt.encoders.len() {
-                    return Err(MdfError::BlockSerializationError("value count mismatch".into()));
+                    return Err(MdfError::ValueCountMismatch { expected: dt.encoders.len(), actual: rec.len() });
                 }
-                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+                24 + dt.record_size * (dt.record_count as usize + 1) > dt.max_block_size
             };
 
             if potential_new_block {
@@ -681,7 +1561,7 @@ impl MdfWriter {
                     ChannelEncoder::F32 { offset } => {
                         dt.record_buf[*offset..*offset + 4].copy_from_slice(&(v as f32).to_le_bytes());
                     }
-                    _ => {}
+                    enc => enc.encode(&mut dt.record_buf, &DecodedValue::Float(v))?,
                 }
             }
             buffer.extend_from_slice(&dt.record_buf);
@@ -689,10 +1569,9 @@ impl MdfWriter {
         }
 
         if !buffer.is_empty() {
-            self.file.write_all(&buffer)?;
-            self.offset += buffer.len() as u64;
+            self.write_data(cg_id, &buffer)?;
         }
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Columnar write for uniform f64 channel groups.
@@ -703,21 +1582,21 @@ impl MdfWriter {
     /// record buffer, then flushed to disk in one `write_all` per DT chunk.
     /// This eliminates per-record overhead and is the fastest available write
     /// path for f64-only groups.
-    pub fn write_columns_f64(&mut self, cg_id: &str, columns: &[&[f64]]) -> Result<(), MdfError> {
+    pub fn write_columns_f64(&mut self, cg_id: &ChannelGroupId, columns: &[&[f64]]) -> Result<(), MdfError> {
         // Validate inputs and extract metadata once.
         let (offsets, record_size, nrows, need_template, template) = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
+                MdfError::InvalidWriterState("no open DT block for this channel group".into())
             })?;
             if columns.len() != dt.encoders.len() {
-                return Err(MdfError::BlockSerializationError("column count does not match encoder count".into()));
+                return Err(MdfError::ValueCountMismatch { expected: dt.encoders.len(), actual: columns.len() });
             }
             if !dt.encoders.iter().all(|e| matches!(e, ChannelEncoder::F64 { .. })) {
-                return Err(MdfError::BlockSerializationError("channel types not f64".into()));
+                return Err(MdfError::ValueTypeMismatch("channel types not f64".into()));
             }
             let nrows = columns.first().map(|c| c.len()).unwrap_or(0);
-            if columns.iter().any(|c| c.len() != nrows) {
-                return Err(MdfError::BlockSerializationError("column length mismatch".into()));
+            if let Some(c) = columns.iter().find(|c| c.len() != nrows) {
+                return Err(MdfError::ColumnLengthMismatch { expected: nrows, actual: c.len() });
             }
             let offsets: Vec<usize> = dt.encoders.iter().map(|e| match e {
                 ChannelEncoder::F64 { offset } => *offset,
@@ -733,7 +1612,7 @@ impl MdfWriter {
             return Ok(());
         }
 
-        let max_per_dt = (MAX_DT_BLOCK_SIZE - 24) / record_size;
+        let max_per_dt = (self.open_dts[cg_id].max_block_size - 24) / record_size;
         let ncols = columns.len();
         let record_f64s = record_size / 8;
         // Check if channels are tightly packed f64 values (common case: no gaps, 8-byte aligned).
@@ -747,7 +1626,7 @@ impl MdfWriter {
         while row < nrows {
             let records_in_current = {
                 let dt = &self.open_dts[cg_id];
-                let capacity = (MAX_DT_BLOCK_SIZE - 24) / dt.record_size;
+                let capacity = (dt.max_block_size - 24) / dt.record_size;
                 capacity.saturating_sub(dt.record_count as usize)
             };
             let chunk_size = (nrows - row).min(records_in_current).min(max_per_dt);
@@ -788,15 +1667,14 @@ impl MdfWriter {
                 }
             }
 
-            self.file.write_all(&buf[..buf_len])?;
-            self.offset += buf_len as u64;
+            self.write_data(cg_id, &buf[..buf_len])?;
             {
                 let dt = self.open_dts.get_mut(cg_id).unwrap();
                 dt.record_count += chunk_size as u64;
             }
             row += chunk_size;
         }
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Columnar write for mixed-type channel groups.
@@ -806,15 +1684,76 @@ impl MdfWriter {
     /// channel's encoder type. Values are written column-by-column into a
     /// pre-allocated record buffer and flushed in large chunks, avoiding
     /// per-record dispatch overhead.
-    pub fn write_columns(&mut self, cg_id: &str, columns: &[ColumnData<'_>]) -> Result<(), MdfError> {
+    pub fn write_columns(&mut self, cg_id: &ChannelGroupId, columns: &[ColumnData<'_>]) -> Result<(), MdfError> {
+        let channel_count = self.open_dts.get(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?.encoders.len();
+        if columns.len() != channel_count {
+            return Err(MdfError::ValueCountMismatch { expected: channel_count, actual: columns.len() });
+        }
+        self.write_channel_columns(cg_id, &(0..channel_count).collect::<Vec<_>>(), columns)
+    }
+
+    /// Columnar write with the master channel given apart: `time` holds the
+    /// master (`channel_type` 2) samples and `columns` one [`ColumnData`]
+    /// per other channel, in channel order, as in
+    /// [`write_columns`](Self::write_columns).
+    ///
+    /// Suits sources that keep the time base separate from the signal
+    /// buffers (DAQ buffers, a `DataFrame` index). The master must be a
+    /// 64-bit (or half precision) float channel.
+    pub fn write_columns_with_time(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        time: &[f64],
+        columns: &[ColumnData<'_>],
+    ) -> Result<(), MdfError> {
+        let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?;
+        let master = dt.channels.iter().position(|ch| ch.channel_type == 2).ok_or_else(|| {
+            MdfError::InvalidWriterState("channel group has no master channel".into())
+        })?;
+        if columns.len() + 1 != dt.channels.len() {
+            return Err(MdfError::ValueCountMismatch { expected: dt.channels.len() - 1, actual: columns.len() });
+        }
+        let channels: Vec<usize> = std::iter::once(master)
+            .chain((0..dt.channels.len()).filter(|&i| i != master))
+            .collect();
+        let all_columns: Vec<ColumnData<'_>> = std::iter::once(ColumnData::F64(time))
+            .chain(columns.iter().copied())
+            .collect();
+        self.write_channel_columns(cg_id, &channels, &all_columns)
+    }
+
+    /// Columnar write of the varying channels only: `columns` holds one
+    /// [`ColumnData`] per channel not fixed by
+    /// [`set_constant_channels`](Self::set_constant_channels), in channel
+    /// order, as in [`write_columns`](Self::write_columns). The constant
+    /// channels are copied from the template.
+    pub fn write_varying_columns(&mut self, cg_id: &ChannelGroupId, columns: &[ColumnData<'_>]) -> Result<(), MdfError> {
+        let varying = self.open_dts.get(cg_id).ok_or_else(|| {
+            MdfError::InvalidWriterState("no open DT block for this channel group".into())
+        })?.varying_channels.clone();
+        if columns.len() != varying.len() {
+            return Err(MdfError::ValueCountMismatch { expected: varying.len(), actual: columns.len() });
+        }
+        self.write_channel_columns(cg_id, &varying, columns)
+    }
+
+    /// [`write_columns`](Self::write_columns) for the channels at
+    /// `channels`, one column each; other channels keep their template
+    /// bytes.
+    fn write_channel_columns(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        channels: &[usize],
+        columns: &[ColumnData<'_>],
+    ) -> Result<(), MdfError> {
         // Validate and extract metadata once.
-        let (nrows, enc_info, record_size, need_template, template) = {
-            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                MdfError::BlockSerializationError("no open DT block for this channel group".into())
-            })?;
-            if columns.len() != dt.encoders.len() {
-                return Err(MdfError::BlockSerializationError("column count does not match encoder count".into()));
-            }
+        let (nrows, enc_info, encoders, record_size, need_template, template) = {
+            let dt = &self.open_dts[cg_id];
+            let encoders: Vec<ChannelEncoder> = channels.iter().map(|&i| dt.encoders[i].clone()).collect();
             let nrows = match columns.first() {
                 Some(ColumnData::F64(s)) => s.len(),
                 Some(ColumnData::F32(s)) => s.len(),
@@ -823,7 +1762,7 @@ impl MdfWriter {
                 None => 0,
             };
             let mut total_channel_bytes = 0usize;
-            for (col, enc) in columns.iter().zip(dt.encoders.iter()) {
+            for (col, enc) in columns.iter().zip(encoders.iter()) {
                 let col_len = match col {
                     ColumnData::F64(s) => s.len(),
                     ColumnData::F32(s) => s.len(),
@@ -831,47 +1770,65 @@ impl MdfWriter {
                     ColumnData::I64(s) => s.len(),
                 };
                 if col_len != nrows {
-                    return Err(MdfError::BlockSerializationError("column length mismatch".into()));
+                    return Err(MdfError::ColumnLengthMismatch { expected: nrows, actual: col_len });
                 }
                 let type_ok = match (col, enc) {
                     (ColumnData::F64(_), ChannelEncoder::F64 { .. }) => true,
                     (ColumnData::F32(_), ChannelEncoder::F32 { .. }) => true,
                     (ColumnData::U64(_), ChannelEncoder::UInt { .. }) => true,
                     (ColumnData::I64(_), ChannelEncoder::Int { .. }) => true,
+                    (ColumnData::F64(_), ChannelEncoder::F64BE { .. }) => true,
+                    (ColumnData::F32(_) | ColumnData::F64(_), ChannelEncoder::F16 { .. } | ChannelEncoder::F16BE { .. }) => true,
+                    (ColumnData::F32(_), ChannelEncoder::F32BE { .. }) => true,
+                    (ColumnData::U64(_), ChannelEncoder::UIntBE { .. }) => true,
+                    (ColumnData::I64(_), ChannelEncoder::IntBE { .. }) => true,
+                    (ColumnData::U64(_), ChannelEncoder::Packed { signed: false, .. }) => true,
+                    (ColumnData::I64(_), ChannelEncoder::Packed { signed: true, .. }) => true,
                     _ => false,
                 };
                 if !type_ok {
-                    return Err(MdfError::BlockSerializationError("column type does not match encoder type".into()));
+                    return Err(MdfError::ValueTypeMismatch("column type does not match encoder type".into()));
                 }
             }
-            let enc_info: Vec<(usize, usize)> = dt.encoders.iter().map(|e| match e {
-                ChannelEncoder::F64 { offset } => (*offset, 8usize),
-                ChannelEncoder::F32 { offset } => (*offset, 4usize),
-                ChannelEncoder::UInt { offset, bytes } => (*offset, *bytes),
-                ChannelEncoder::Int { offset, bytes } => (*offset, *bytes),
-                ChannelEncoder::Bytes { offset, bytes } => (*offset, *bytes),
+            let enc_info: Vec<(usize, usize)> = encoders.iter().map(|e| match e {
+                ChannelEncoder::F64 { offset } | ChannelEncoder::F64BE { offset } => (*offset, 8usize),
+                ChannelEncoder::F32 { offset } | ChannelEncoder::F32BE { offset } => (*offset, 4usize),
+                ChannelEncoder::F16 { offset } | ChannelEncoder::F16BE { offset } => (*offset, 2usize),
+                ChannelEncoder::UInt { offset, bytes } | ChannelEncoder::UIntBE { offset, bytes } => (*offset, *bytes),
+                ChannelEncoder::Int { offset, bytes } | ChannelEncoder::IntBE { offset, bytes } => (*offset, *bytes),
+                ChannelEncoder::Bytes { offset, bytes } | ChannelEncoder::Str { offset, bytes, .. } => {
+                    (*offset, *bytes)
+                }
+                ChannelEncoder::CanOpenDate { offset } => (*offset, CanOpenDate::BYTES),
+                ChannelEncoder::CanOpenTime { offset } => (*offset, CanOpenTime::BYTES),
+                ChannelEncoder::Packed { offset, bit_offset, bit_count, .. } => {
+                    (*offset, (*bit_offset as u32 + *bit_count).div_ceil(8) as usize)
+                }
                 ChannelEncoder::VlsdOffset { .. } | ChannelEncoder::Skip => (0, 0),
             }).collect();
             for &(_, nbytes) in &enc_info {
                 total_channel_bytes += nbytes;
             }
-            let need_template = total_channel_bytes < dt.record_size;
+            // Packed channels only write their own bits; the rest of their
+            // bytes must come from the template.
+            let packed = encoders.iter().any(|e| matches!(e, ChannelEncoder::Packed { .. }));
+            let need_template = packed || total_channel_bytes < dt.record_size;
             let template = dt.record_template.clone();
-            (nrows, enc_info, dt.record_size, need_template, template)
+            (nrows, enc_info, encoders, dt.record_size, need_template, template)
         };
 
         if nrows == 0 {
             return Ok(());
         }
 
-        let max_per_dt = (MAX_DT_BLOCK_SIZE - 24) / record_size;
+        let max_per_dt = (self.open_dts[cg_id].max_block_size - 24) / record_size;
         let mut buf = vec![0u8; max_per_dt * record_size];
 
         let mut row = 0usize;
         while row < nrows {
             let records_in_current = {
                 let dt = &self.open_dts[cg_id];
-                let capacity = (MAX_DT_BLOCK_SIZE - 24) / dt.record_size;
+                let capacity = (dt.max_block_size - 24) / dt.record_size;
                 capacity.saturating_sub(dt.record_count as usize)
             };
             let chunk_size = (nrows - row).min(records_in_current).min(max_per_dt);
@@ -893,6 +1850,23 @@ impl MdfWriter {
                 if nbytes == 0 {
                     continue;
                 }
+                let enc = &encoders[col_idx];
+                if !matches!(
+                    enc,
+                    ChannelEncoder::F64 { .. } | ChannelEncoder::F32 { .. } | ChannelEncoder::UInt { .. } | ChannelEncoder::Int { .. }
+                ) {
+                    // Big-endian and packed fields go through the per-value encoder.
+                    for r in 0..chunk_size {
+                        let value = match col {
+                            ColumnData::F64(vals) => DecodedValue::Float(vals[row + r]),
+                            ColumnData::F32(vals) => DecodedValue::Float(vals[row + r] as f64),
+                            ColumnData::U64(vals) => DecodedValue::UnsignedInteger(vals[row + r]),
+                            ColumnData::I64(vals) => DecodedValue::SignedInteger(vals[row + r]),
+                        };
+                        enc.encode(&mut buf[r * record_size..(r + 1) * record_size], &value)?;
+                    }
+                    continue;
+                }
                 match col {
                     ColumnData::F64(vals) => {
                         for r in 0..chunk_size {
@@ -923,57 +1897,96 @@ impl MdfWriter {
                 }
             }
 
-            self.file.write_all(&buf[..buf_len])?;
-            self.offset += buf_len as u64;
+            self.write_data(cg_id, &buf[..buf_len])?;
             {
                 let dt = self.open_dts.get_mut(cg_id).unwrap();
                 dt.record_count += chunk_size as u64;
             }
             row += chunk_size;
         }
-        Ok(())
+        self.maybe_publish_progress(cg_id)
     }
 
     /// Finalize the currently open DTBLOCK for a given channel group and patch its size field.
-    pub fn finish_data_block(&mut self, cg_id: &str) -> Result<(), MdfError> {
-        let mut dt = self.open_dts.remove(cg_id).ok_or_else(|| MdfError::BlockSerializationError("no open DT block for this channel group".into()))?;
-        let size = 24 + dt.record_size as u64 * dt.record_count;
-        self.update_link(dt.start_pos + 8, size)?;
-        dt.dt_sizes.push(size);
-        dt.total_record_count += dt.record_count;
-        self.update_block_u64(cg_id, 80, dt.total_record_count)?;
-
-        if dt.dt_ids.len() > 1 {
-            let dl_count = self.block_positions.keys().filter(|k| k.starts_with("dl_")).count();
-            let dl_id = format!("dl_{}", dl_count);
-            let common_len = *dt.dt_sizes.first().unwrap_or(&size);
-            let dl_block = DataListBlock::new_equal(dt.dt_positions.clone(), common_len);
+    pub fn finish_data_block(&mut self, cg_id: &ChannelGroupId) -> Result<(), MdfError> {
+        if self.open_dts.get(cg_id).is_some_and(|dt| self.unsorted_streams.contains_key(&dt.dg_id)) {
+            return Err(MdfError::InvalidWriterState(
+                "the channel group shares an unsorted data stream; finish it with finish_unsorted_data_block".into(),
+            ));
+        }
+        self.close_dt_fragment(cg_id)?;
+        if !self.open_dts[cg_id].live_dl_positions.is_empty() {
+            // Compressed blocks closed since the last checkpoint are not
+            // chained yet.
+            self.chain_live_data_blocks(cg_id)?;
+        }
+        let mut dt = self.open_dts.remove(cg_id).unwrap();
+
+        let continued = self.is_continued_group(cg_id);
+        let cycles = if continued { self.link_continued_data(cg_id, &dt)? } else { dt.total_record_count };
+        self.update_block_u64(cg_id, 80, cycles)?;
+
+        if continued {
+            // The DL written by `link_continued_data` covers every data block.
+        } else if !dt.live_dl_positions.is_empty() {
+            // The DL chain for live readers already covers every closed DT
+            // block, including the one just finished.
+            debug_assert_eq!(dt.live_dl_positions.len(), dt.dt_positions.len());
+        } else if dt.dt_ids.len() > 1 {
+            let dl_id = self.next_block_id("dl");
+            // The equal-length form only fits when every data section has
+            // the same length; the last block is usually shorter.
+            let lens: Vec<u64> = dt.dt_sizes.iter().map(|size| size - 24).collect();
+            let dl_block = if lens.iter().all(|&len| len == lens[0]) {
+                DataListBlock::new_equal(dt.dt_positions.clone(), lens[0])
+            } else {
+                let offsets = lens
+                    .iter()
+                    .scan(0u64, |offset, &len| {
+                        let start = *offset;
+                        *offset += len;
+                        Some(start)
+                    })
+                    .collect();
+                DataListBlock::new_variable(dt.dt_positions.clone(), offsets)
+            };
             let dl_bytes = dl_block.to_bytes()?;
             let _pos = self.write_block_with_id(&dl_bytes, &dl_id)?;
             let dg_data_link_offset = 40;
-            self.update_block_link(&dt.dg_id, dg_data_link_offset, &dl_id)?;
+            self.update_block_link(dt.dg_id, dg_data_link_offset, &dl_id)?;
         }
 
+        self.write_vlsd_payloads(&mut dt)
+    }
+
+    /// Write the signal data collected for the VLSD channels of a finished
+    /// data block.
+    pub(super) fn write_vlsd_payloads(&mut self, dt: &mut OpenDataBlock) -> Result<(), MdfError> {
         for i in 0..dt.vlsd_payloads.len() {
             let payload = match dt.vlsd_payloads[i].take() {
                 Some(p) => p,
                 None => continue,
             };
-            let cn_id = match dt.vlsd_channel_ids[i].clone() {
+            let cn_id = match dt.vlsd_channel_ids[i] {
                 Some(id) => id,
                 None => continue,
             };
-            let block_len = 24u64 + payload.len() as u64;
-            let header = BlockHeader { id: "##SD".to_string(), reserved0: 0, block_len, links_nr: 0 };
-            let mut sd_bytes = header.to_bytes()?;
-            sd_bytes.extend_from_slice(&payload);
-
-            let sd_count = self.block_positions.keys().filter(|k| k.starts_with("sd_")).count();
-            let sd_id = format!("sd_{}", sd_count);
-            self.write_block_with_id(&sd_bytes, &sd_id)?;
-            let cn_data_offset = 64u64;
-            self.update_block_link(&cn_id, cn_data_offset, &sd_id)?;
+            self.write_vlsd_payload(&cn_id, &payload)?;
         }
         Ok(())
     }
+
+    /// Write `payload` as a `##SD` block and link it as the signal data of
+    /// the VLSD channel `cn_id`.
+    pub(super) fn write_vlsd_payload(&mut self, cn_id: &ChannelId, payload: &[u8]) -> Result<(), MdfError> {
+        let block_len = 24u64 + payload.len() as u64;
+        let header = BlockHeader { id: "##SD".to_string(), reserved0: 0, block_len, links_nr: 0 };
+        let mut sd_bytes = header.to_bytes()?;
+        sd_bytes.extend_from_slice(payload);
+
+        let sd_id = self.next_block_id("sd");
+        self.write_block_with_id(&sd_bytes, &sd_id)?;
+        let cn_data_offset = 64u64;
+        self.update_block_link(cn_id, cn_data_offset, &sd_id)
+    }
 }
@@ -0,0 +1,76 @@
+// Event (##EV) blocks: markers and triggers attached to the file
+use super::*;
+use crate::blocks::common::BlockHeader;
+use crate::blocks::event_block::{EventBlock, EventType};
+use crate::blocks::text_block::TextBlock;
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Write a time-synchronized `##EV` block and append it to the event
+    /// chain of the header.
+    ///
+    /// `time` is in seconds relative to the measurement start and is stored
+    /// with nanosecond resolution. `scope` lists the channel groups or
+    /// channels the event applies to (e.g. `&[cg.into(), cn.into()]`);
+    /// leave it empty for an event covering the whole file.
+    ///
+    /// Returns the writer ID of the event, for [`MdfWriter::set_event_comment`].
+    pub fn add_event(
+        &mut self,
+        name: &str,
+        time: f64,
+        event_type: EventType,
+        scope: &[BlockRef<'_>],
+    ) -> Result<String, MdfError> {
+        let ev_count = self.block_counts.get("ev").copied().unwrap_or(0);
+        let ev_id = self.next_block_id("ev");
+
+        let scope_addrs = scope
+            .iter()
+            .map(|&id| {
+                self.get_block_position(id).ok_or_else(|| {
+                    MdfError::BlockLinkError(format!("event scope block '{}' not found", id))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx_bytes = TextBlock::new(name).to_bytes()?;
+        let name_addr = self.write_block_with_id(&tx_bytes, &format!("tx_name_{ev_id}"))?;
+
+        let links_nr = 5 + scope_addrs.len();
+        let ev = EventBlock {
+            header: BlockHeader {
+                id: "##EV".into(),
+                reserved0: 0,
+                block_len: 24 + links_nr as u64 * 8 + 32,
+                links_nr: links_nr as u64,
+            },
+            name_addr,
+            scope_addrs,
+            event_type,
+            sync_base_value: (time * 1e9).round() as i64,
+            sync_factor: 1e-9,
+            ..EventBlock::default()
+        };
+        self.write_block_with_id(&ev.to_bytes()?, &ev_id)?;
+
+        if ev_count == 0 {
+            let hd_ev_link_offset = 56;
+            self.update_block_link("hd_block", hd_ev_link_offset, &ev_id)?;
+        } else {
+            let prev = format!("ev_{}", ev_count - 1);
+            let ev_next_link_offset = 24;
+            self.update_block_link(&prev, ev_next_link_offset, &ev_id)?;
+        }
+        Ok(ev_id)
+    }
+
+    /// Write a `##TX` block holding `comment` and link it as the event's
+    /// `ev_md_comment` (offset 56 inside the `##EV` block).
+    pub fn set_event_comment(&mut self, ev_id: &str, comment: &str) -> Result<(), MdfError> {
+        let tx_id = format!("tx_comment_{ev_id}");
+        let tx_bytes = TextBlock::new(comment).to_bytes()?;
+        self.write_block_with_id(&tx_bytes, &tx_id)?;
+        let comment_link_offset = 56;
+        self.update_block_link(ev_id, comment_link_offset, &tx_id)
+    }
+}
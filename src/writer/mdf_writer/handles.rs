@@ -0,0 +1,198 @@
+// Typed handles for the blocks created by MdfWriter
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Handle of a data group (`##DG`) created by
+/// [`MdfWriter::add_data_group`](super::MdfWriter::add_data_group).
+///
+/// Handles are numbered in creation order per block type, starting at 0,
+/// and are only meaningful for the writer that returned them. They print
+/// as the writer's block names, e.g. `dg_0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DataGroupId(usize);
+
+/// Handle of a channel group (`##CG`) created by
+/// [`MdfWriter::add_channel_group`](super::MdfWriter::add_channel_group);
+/// see [`DataGroupId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChannelGroupId(usize);
+
+/// Handle of a channel (`##CN`) created by
+/// [`MdfWriter::add_channel`](super::MdfWriter::add_channel);
+/// see [`DataGroupId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChannelId(usize);
+
+/// Index-based handle of a block type, backing [`HandleMap`].
+pub(super) trait Handle: Copy {
+    fn from_index(index: usize) -> Self;
+    fn index(self) -> usize;
+}
+
+macro_rules! handle {
+    ($name:ident, $prefix:literal, $variant:ident) => {
+        impl $name {
+            /// Creation index of the block among the writer's blocks of its type.
+            pub fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl Handle for $name {
+            fn from_index(index: usize) -> Self {
+                $name(index)
+            }
+            fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!($prefix, "_{}"), self.0)
+            }
+        }
+
+        impl From<$name> for BlockRef<'_> {
+            fn from(id: $name) -> Self {
+                BlockRef::$variant(id)
+            }
+        }
+
+        impl From<&$name> for BlockRef<'_> {
+            fn from(id: &$name) -> Self {
+                BlockRef::$variant(*id)
+            }
+        }
+    };
+}
+
+handle!(DataGroupId, "dg", DataGroup);
+handle!(ChannelGroupId, "cg", ChannelGroup);
+handle!(ChannelId, "cn", Channel);
+
+/// A block written by an [`MdfWriter`](super::MdfWriter), as accepted by
+/// [`get_block_position`](super::MdfWriter::get_block_position) and
+/// [`update_block_link`](super::MdfWriter::update_block_link): either a
+/// typed handle or the name given to
+/// [`write_block_with_id`](super::MdfWriter::write_block_with_id)
+/// (`"hd_block"`, the IDs returned for conversions, ...).
+///
+/// Both convert with `into()`, so `&cg`, `"hd_block"` and `&name` can be
+/// passed directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockRef<'a> {
+    Named(&'a str),
+    DataGroup(DataGroupId),
+    ChannelGroup(ChannelGroupId),
+    Channel(ChannelId),
+}
+
+impl fmt::Display for BlockRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockRef::Named(name) => f.write_str(name),
+            BlockRef::DataGroup(id) => id.fmt(f),
+            BlockRef::ChannelGroup(id) => id.fmt(f),
+            BlockRef::Channel(id) => id.fmt(f),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for BlockRef<'a> {
+    fn from(name: &'a str) -> Self {
+        BlockRef::Named(name)
+    }
+}
+
+impl<'a> From<&'a String> for BlockRef<'a> {
+    fn from(name: &'a String) -> Self {
+        BlockRef::Named(name)
+    }
+}
+
+/// Map from handles to values stored in a `Vec` indexed by the handle, in
+/// place of a `HashMap` keyed by block name.
+pub(super) struct HandleMap<K, V> {
+    slots: Vec<Option<V>>,
+    _key: PhantomData<K>,
+}
+
+impl<K, V> Default for HandleMap<K, V> {
+    fn default() -> Self {
+        HandleMap { slots: Vec::new(), _key: PhantomData }
+    }
+}
+
+impl<K: Handle, V> HandleMap<K, V> {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn get(&self, key: &K) -> Option<&V> {
+        self.slots.get(key.index()).and_then(Option::as_ref)
+    }
+
+    pub(super) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.slots.get_mut(key.index()).and_then(Option::as_mut)
+    }
+
+    pub(super) fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    pub(super) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index = key.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        self.slots[index].replace(value)
+    }
+
+    pub(super) fn remove(&mut self, key: &K) -> Option<V> {
+        self.slots.get_mut(key.index()).and_then(Option::take)
+    }
+
+    /// The value of `key`, inserting `V::default()` if there is none.
+    pub(super) fn get_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default,
+    {
+        if !self.contains_key(&key) {
+            self.insert(key, V::default());
+        }
+        self.slots[key.index()].as_mut().unwrap()
+    }
+
+    /// Entries in handle order.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (K, &V)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|v| (K::from_index(i), v)))
+    }
+
+    pub(super) fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub(super) fn values(&self) -> impl Iterator<Item = &V> {
+        self.slots.iter().flatten()
+    }
+
+    pub(super) fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.slots.iter_mut().flatten()
+    }
+}
+
+impl<K: Handle, V> std::ops::Index<&K> for HandleMap<K, V> {
+    type Output = V;
+
+    fn index(&self, key: &K) -> &V {
+        self.get(key).expect("no entry for handle")
+    }
+}
@@ -1,22 +1,65 @@
 // Functions for creating and linking MDF structure blocks
 use super::*;
-use crate::blocks::channel_block::ChannelBlock;
+use crate::blocks::channel_block::{ChannelBlock, CN_FLAG_LIMIT_RANGE_VALID};
 use crate::blocks::channel_group_block::ChannelGroupBlock;
-use crate::blocks::conversion::{ConversionBlock, ConversionType};
+use crate::blocks::conversion::simplify::CC_FLAG_PHYSICAL_RANGE_VALID;
+use crate::blocks::conversion::{check_algebraic_formula, ConversionBlock, ConversionType};
 use crate::blocks::data_group_block::DataGroupBlock;
 use crate::blocks::header_block::HeaderBlock;
-use crate::blocks::identification_block::IdentificationBlock;
+use crate::blocks::source_block::SourceBlock;
 use crate::blocks::text_block::TextBlock;
-use crate::blocks::common::BlockHeader;
+use crate::blocks::common::{BlockHeader, DataType};
+use crate::blocks::metadata_block::MetadataBlock;
+use crate::metadata::{HeaderInfo, HeaderMetadata};
+use crate::parsing::source_info::SourceInfo;
 
-impl MdfWriter {
+/// Place `ch` at `next_bit` of its record if it has no `byte_offset` (see
+/// [`MdfWriter::add_channel`]) and advance `next_bit` past its bits.
+fn place_channel(ch: &mut ChannelBlock, next_bit: &mut usize) {
+    // Virtual channels (master or data) take no record bytes.
+    if ch.channel_type == 3 || ch.channel_type == 6 {
+        return;
+    }
+    if ch.byte_offset == 0 {
+        let integer = matches!(
+            ch.data_type,
+            DataType::UnsignedIntegerLE
+                | DataType::UnsignedIntegerBE
+                | DataType::SignedIntegerLE
+                | DataType::SignedIntegerBE
+        );
+        let vlsd = ch.channel_type == 1 && ch.data != 0;
+        // Packed integers are encoded through a 64-bit window.
+        let packed = integer
+            && !vlsd
+            && ch.bit_offset == 0
+            && !ch.bit_count.is_multiple_of(8)
+            && *next_bit % 8 + ch.bit_count as usize <= 64;
+        if packed {
+            ch.byte_offset = (*next_bit / 8) as u32;
+            ch.bit_offset = (*next_bit % 8) as u8;
+        } else {
+            ch.byte_offset = next_bit.div_ceil(8) as u32;
+        }
+    }
+    let end = ch.byte_offset as usize * 8 + ch.bit_offset as usize + ch.bit_count as usize;
+    *next_bit = (*next_bit).max(end);
+}
+
+impl<W: Write + Seek> MdfWriter<W> {
     /// Initializes a new MDF 4.1 file with identification and header blocks.
+    ///
+    /// The header's start time defaults to the current system time (UTC);
+    /// use [`set_start_time`](Self::set_start_time) to anchor it elsewhere.
     pub fn init_mdf_file(&mut self) -> Result<(u64, u64), MdfError> {
-        let id_block = IdentificationBlock::default();
+        let id_block = self.config.identification_block();
         let id_bytes = id_block.to_bytes()?;
         let id_pos = self.write_block_with_id(&id_bytes, "id_block")?;
 
-        let hd_block = HeaderBlock::default();
+        let mut hd_block = HeaderBlock::default();
+        if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hd_block.abs_time = now.as_nanos() as u64;
+        }
         let hd_bytes = hd_block.to_bytes()?;
         let hd_pos = self.write_block_with_id(&hd_bytes, "hd_block")?;
         Ok((id_pos, hd_pos))
@@ -24,7 +67,7 @@ impl MdfWriter {
 
     /// Overwrite the start-time fields of the file's `##HD` block.
     ///
-    /// `init_mdf_file` writes default time metadata, which loses the
+    /// `init_mdf_file` stamps the current system time, which loses the
     /// wall-clock anchor when authoring a file derived from another (for
     /// example, [`crate::cut::cut_mdf_by_time`] copying a window out of a
     /// source MF4). Call this after `init_mdf_file` to anchor the output
@@ -53,75 +96,138 @@ impl MdfWriter {
         Ok(())
     }
 
+    /// Write `metadata` as an `<HDcomment>` `##MD` block and link it as the
+    /// header's `comment_addr` (offset 64 inside the `##HD` block).
+    pub fn set_header_metadata(&mut self, metadata: &HeaderMetadata) -> Result<(), MdfError> {
+        let md_bytes = MetadataBlock::new(&metadata.to_xml()).to_bytes()?;
+        self.write_block_with_id(&md_bytes, "md_hd_comment")?;
+        let hd_comment_link_offset = 64;
+        self.update_block_link("hd_block", hd_comment_link_offset, "md_hd_comment")
+    }
+
+    /// Write the start time (if set) and header comment of `info`; see
+    /// [`set_start_time`](Self::set_start_time) and
+    /// [`set_header_metadata`](Self::set_header_metadata).
+    pub fn set_header_info(&mut self, info: &HeaderInfo) -> Result<(), MdfError> {
+        if let Some(t) = &info.start_time {
+            self.set_start_time(t.abs_time_ns, t.tz_offset_min, t.dst_offset_min, t.time_flags, t.time_quality)?;
+        }
+        self.set_header_metadata(&info.metadata)
+    }
+
     /// Adds a data group block to the file and links it from the header block.
-    pub fn add_data_group(&mut self, prev_dg_id: Option<&str>) -> Result<String, MdfError> {
-        let dg_count = self.block_positions.keys().filter(|k| k.starts_with("dg_")).count();
-        let dg_id = format!("dg_{}", dg_count);
+    pub fn add_data_group(&mut self, prev_dg_id: Option<&DataGroupId>) -> Result<DataGroupId, MdfError> {
         let dg_block = DataGroupBlock::default();
         let dg_bytes = dg_block.to_bytes()?;
-        let _pos = self.write_block_with_id(&dg_bytes, &dg_id)?;
+        let dg_id = self.write_dg_block(&dg_bytes)?;
 
         if prev_dg_id.is_none() {
             let hd_dg_link_offset = 24;
-            self.update_block_link("hd_block", hd_dg_link_offset, &dg_id)?;
+            self.update_block_link("hd_block", hd_dg_link_offset, dg_id)?;
         } else {
             let prev = prev_dg_id.unwrap();
             let prev_off = 24;
-            self.update_block_link(prev, prev_off, &dg_id)?;
+            self.update_block_link(prev, prev_off, dg_id)?;
         }
         Ok(dg_id)
     }
 
     /// Adds a channel group block to the specified data group and links it.
+    ///
+    /// An `acq_name` or `acq_source` set by `configure` is written as
+    /// `##TX` / `##SI` blocks and linked from the new `##CG`.
     pub fn add_channel_group_with_dg<F>(
         &mut self,
-        dg_id: &str,
-        prev_cg_id: Option<&str>,
+        dg_id: &DataGroupId,
+        prev_cg_id: Option<&ChannelGroupId>,
         configure: F,
-    ) -> Result<String, MdfError>
+    ) -> Result<ChannelGroupId, MdfError>
     where
         F: FnOnce(&mut ChannelGroupBlock),
     {
-        let cg_count = self.block_positions.keys().filter(|k| k.starts_with("cg_")).count();
-        let cg_id = format!("cg_{}", cg_count);
-
         let mut cg_block = ChannelGroupBlock::default();
         configure(&mut cg_block);
 
         let cg_bytes = cg_block.to_bytes()?;
-        let _pos = self.write_block_with_id(&cg_bytes, &cg_id)?;
+        let cg_id = self.write_cg_block(&cg_bytes)?;
+        if let Some(name) = &cg_block.acq_name {
+            self.set_channel_group_name(&cg_id, name)?;
+        }
+        if let Some(source) = &cg_block.acq_source {
+            self.set_channel_group_source(&cg_id, source)?;
+        }
 
         if prev_cg_id.is_none() {
             let dg_cg_link_offset = 32;
-            self.update_block_link(dg_id, dg_cg_link_offset, &cg_id)?;
+            self.update_block_link(dg_id, dg_cg_link_offset, cg_id)?;
         } else {
             let prev = prev_cg_id.unwrap();
             let prev_cg_off = 24;
-            self.update_block_link(prev, prev_cg_off, &cg_id)?;
+            self.update_block_link(prev, prev_cg_off, cg_id)?;
         }
+        self.cg_to_dg.insert(cg_id, *dg_id);
+        self.cg_next_bit.insert(cg_id, 0);
+        self.cg_channels.insert(cg_id, Vec::new());
+        self.cg_channel_ids.insert(cg_id, Vec::new());
         Ok(cg_id)
     }
 
     /// Adds a channel group and automatically creates a new data group for it.
     pub fn add_channel_group<F>(
         &mut self,
-        prev_cg_id: Option<&str>,
+        prev_cg_id: Option<&ChannelGroupId>,
         configure: F,
-    ) -> Result<String, MdfError>
+    ) -> Result<ChannelGroupId, MdfError>
     where
         F: FnOnce(&mut ChannelGroupBlock),
     {
-        let dg_id = match self.last_dg.clone() {
-            Some(last) => self.add_data_group(Some(&last))?,
-            None => self.add_data_group(None)?,
-        };
-        self.last_dg = Some(dg_id.clone());
-        let cg_id = self.add_channel_group_with_dg(&dg_id, prev_cg_id, configure)?;
-        self.cg_to_dg.insert(cg_id.clone(), dg_id);
-        self.cg_offsets.insert(cg_id.clone(), 0);
-        self.cg_channels.insert(cg_id.clone(), Vec::new());
-        self.cg_channel_ids.insert(cg_id.clone(), Vec::new());
-        Ok(cg_id)
+        let last_dg = self.last_dg;
+        let dg_id = self.add_data_group(last_dg.as_ref())?;
+        self.last_dg = Some(dg_id);
+        self.add_channel_group_with_dg(&dg_id, prev_cg_id, configure)
+    }
+
+    /// Adds a channel group to the data group of `sibling`, after the last
+    /// channel group added to it, which makes the data group unsorted.
+    ///
+    /// The channel groups of an unsorted data group are told apart by their
+    /// `record_id`: any group of the data group still at 0 (the one it was
+    /// created with, or the new one if `configure` leaves it) gets the next
+    /// free ID. Their records are written to one shared data stream, see
+    /// [`start_unsorted_data_block`](Self::start_unsorted_data_block).
+    pub fn add_unsorted_channel_group<F>(
+        &mut self,
+        sibling: &ChannelGroupId,
+        configure: F,
+    ) -> Result<ChannelGroupId, MdfError>
+    where
+        F: FnOnce(&mut ChannelGroupBlock),
+    {
+        let dg_id = *self
+            .cg_to_dg
+            .get(sibling)
+            .ok_or_else(|| MdfError::InvalidWriterState("unknown channel group".into()))?;
+        if self.unsorted_streams.contains_key(&dg_id) {
+            return Err(MdfError::InvalidWriterState(
+                "data group already has an open data stream".into(),
+            ));
+        }
+        let members = self.data_group_members(&dg_id);
+        let mut next_id = members.iter().map(|cg| self.cg_record_id(cg)).max().unwrap_or(0) + 1;
+        let cg_record_id_offset = 72;
+        for cg in &members {
+            if self.cg_record_id(cg) == 0 {
+                self.update_block_u64(cg, cg_record_id_offset, next_id)?;
+                next_id += 1;
+            }
+        }
+        let last = members.last().copied();
+        self.add_channel_group_with_dg(&dg_id, last.as_ref(), |cg| {
+            configure(cg);
+            if cg.record_id == 0 {
+                cg.record_id = next_id;
+            }
+        })
     }
 
     /// Creates and writes a simple value-to-text conversion block.
@@ -129,10 +235,9 @@ impl MdfWriter {
         &mut self,
         mapping: &[(i64, &str)],
         default_text: &str,
-        channel_id: Option<&str>,
+        channel_id: Option<&ChannelId>,
     ) -> Result<(String, u64), MdfError> {
-        let cc_count = self.block_positions.keys().filter(|k| k.starts_with("cc_")).count();
-        let cc_id = format!("cc_{}", cc_count);
+        let cc_id = self.next_block_id("cc");
 
         let mut refs = Vec::new();
         for (idx, (_, txt)) in mapping.iter().enumerate() {
@@ -180,6 +285,151 @@ impl MdfWriter {
         Ok((cc_id, pos))
     }
 
+    /// Creates a linear conversion block, `phys = a + b * raw`, and links it
+    /// to `channel_id` if given.
+    ///
+    /// A `unit` is written as a `##TX` block linked both as the conversion's
+    /// unit and as the channel's (offset 72 inside the `##CN` block), so the
+    /// physical values keep their unit whichever one a reader looks at.
+    pub fn add_linear_conversion(
+        &mut self,
+        a: f64,
+        b: f64,
+        unit: Option<&str>,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<(String, u64), MdfError> {
+        self.add_numeric_conversion(ConversionType::Linear, vec![a, b], &[], unit, channel_id)
+    }
+
+    /// Creates a rational conversion block and links it to `channel_id` if
+    /// given; `unit` is handled as in
+    /// [`add_linear_conversion`](Self::add_linear_conversion).
+    ///
+    /// With `params = [p1, .., p6]` the physical value is
+    /// `(p1 * raw² + p2 * raw + p3) / (p4 * raw² + p5 * raw + p6)`.
+    pub fn add_rational_conversion(
+        &mut self,
+        params: [f64; 6],
+        unit: Option<&str>,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<(String, u64), MdfError> {
+        self.add_numeric_conversion(ConversionType::Rational, params.to_vec(), &[], unit, channel_id)
+    }
+
+    /// Creates an algebraic conversion block computing the physical value
+    /// from the raw value `X` with `formula`, e.g. `"X * 0.1 + sqrt(X)"`,
+    /// and links it to `channel_id` if given; `unit` is handled as in
+    /// [`add_linear_conversion`](Self::add_linear_conversion).
+    ///
+    /// The formula is stored in a `##TX` block. It is checked against the
+    /// grammar the reader evaluates (see
+    /// [`check_algebraic_formula`](crate::blocks::conversion::check_algebraic_formula))
+    /// and rejected before anything is written if it does not parse or uses
+    /// variables other than `X`.
+    pub fn add_algebraic_conversion(
+        &mut self,
+        formula: &str,
+        unit: Option<&str>,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<(String, u64), MdfError> {
+        check_algebraic_formula(formula)?;
+        self.add_numeric_conversion(ConversionType::Algebraic, Vec::new(), &[formula], unit, channel_id)
+    }
+
+    /// Write a conversion block of `cc_type` with parameters `vals` and the
+    /// `##TX` references `texts`, plus its unit.
+    fn add_numeric_conversion(
+        &mut self,
+        cc_type: ConversionType,
+        vals: Vec<f64>,
+        texts: &[&str],
+        unit: Option<&str>,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<(String, u64), MdfError> {
+        let cc_id = self.next_block_id("cc");
+
+        let mut refs = Vec::with_capacity(texts.len());
+        for (idx, txt) in texts.iter().enumerate() {
+            let tx_bytes = TextBlock::new(txt).to_bytes()?;
+            refs.push(self.write_block_with_id(&tx_bytes, &format!("tx_{}_{}", cc_id, idx))?);
+        }
+        let unit_pos = match unit {
+            Some(unit) => {
+                let tx_bytes = TextBlock::new(unit).to_bytes()?;
+                Some(self.write_block_with_id(&tx_bytes, &format!("tx_{}_unit", cc_id))?)
+            }
+            None => None,
+        };
+        let mut block = ConversionBlock {
+            header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+            cc_tx_name: None,
+            cc_md_unit: unit_pos,
+            cc_md_comment: None,
+            cc_cc_inverse: None,
+            cc_ref_count: refs.len() as u16,
+            cc_ref: refs,
+            cc_type,
+            cc_precision: 0,
+            cc_flags: 0,
+            cc_val_count: vals.len() as u16,
+            cc_phy_range_min: Some(0.0),
+            cc_phy_range_max: Some(0.0),
+            cc_val: vals,
+            formula: None,
+            resolved_texts: None,
+            resolved_conversions: None,
+            default_conversion: None,
+        };
+        // With the raw range of the channel known, the physical range is
+        // stored in the conversion and as the channel's limits.
+        let phy_range = channel_id
+            .and_then(|cn| self.cached_channel(cn))
+            .and_then(ChannelBlock::raw_range)
+            .and_then(|(raw_min, raw_max)| block.simplify().physical_range(raw_min, raw_max));
+        if let Some((min, max)) = phy_range {
+            block.cc_phy_range_min = Some(min);
+            block.cc_phy_range_max = Some(max);
+            block.cc_flags |= CC_FLAG_PHYSICAL_RANGE_VALID;
+        }
+        let cc_bytes = block.to_bytes()?;
+        let pos = self.write_block_with_id(&cc_bytes, &cc_id)?;
+
+        if let Some(cn) = channel_id {
+            let conv_offset = 56u64;
+            self.update_block_link(cn, conv_offset, &cc_id)?;
+            if unit.is_some() {
+                let unit_offset = 72u64;
+                self.update_block_link(cn, unit_offset, &format!("tx_{}_unit", cc_id))?;
+            }
+            if let Some((min, max)) = phy_range {
+                self.set_channel_limits(cn, min, max)?;
+            }
+        }
+        Ok((cc_id, pos))
+    }
+
+    /// The cached `##CN` block of `cn_id`.
+    fn cached_channel(&self, cn_id: &ChannelId) -> Option<&ChannelBlock> {
+        let &(cg, idx) = self.channel_map.get(cn_id)?;
+        self.cg_channels.get(&cg)?.get(idx)
+    }
+
+    /// Store `[min, max]` as the limit range of a channel and flag it valid.
+    fn set_channel_limits(&mut self, cn_id: &ChannelId, min: f64, max: f64) -> Result<(), MdfError> {
+        const FLAGS_OFFSET: u64 = 100;
+        const LOWER_LIMIT_OFFSET: u64 = 128;
+        const UPPER_LIMIT_OFFSET: u64 = 136;
+        let Some(&(cg, idx)) = self.channel_map.get(cn_id) else { return Ok(()) };
+        let Some(ch) = self.cg_channels.get_mut(&cg).and_then(|chs| chs.get_mut(idx)) else { return Ok(()) };
+        ch.lower_limit = min;
+        ch.upper_limit = max;
+        ch.flags |= CN_FLAG_LIMIT_RANGE_VALID;
+        let flags = ch.flags;
+        self.update_block_u64(cn_id, LOWER_LIMIT_OFFSET, min.to_bits())?;
+        self.update_block_u64(cn_id, UPPER_LIMIT_OFFSET, max.to_bits())?;
+        self.update_block_u32(cn_id, FLAGS_OFFSET, flags)
+    }
+
     /// Write a `##TX` block holding `name` and link it as the channel group's
     /// `acq_name_addr`.
     ///
@@ -187,7 +437,7 @@ impl MdfWriter {
     /// (the third link slot) inside the `##CG` block.
     pub fn set_channel_group_name(
         &mut self,
-        cg_id: &str,
+        cg_id: &ChannelGroupId,
         name: &str,
     ) -> Result<(), MdfError> {
         let tx_id = format!("tx_cg_name_{cg_id}");
@@ -198,13 +448,64 @@ impl MdfWriter {
         self.update_block_link(cg_id, acq_name_link_offset, &tx_id)
     }
 
+    /// Write a `##SI` block describing `source` and link it as the channel
+    /// group's `acq_source_addr` (offset 48 inside the `##CG` block).
+    ///
+    /// The source's name, path and comment are stored in `##TX` blocks.
+    pub fn set_channel_group_source(
+        &mut self,
+        cg_id: &ChannelGroupId,
+        source: &SourceInfo,
+    ) -> Result<(), MdfError> {
+        let si_id = self.write_source_block(cg_id, source)?;
+        let acq_source_link_offset = 48;
+        self.update_block_link(cg_id, acq_source_link_offset, &si_id)
+    }
+
+    /// Write a `##SI` block describing `source` and link it as the channel's
+    /// `source_addr` (offset 48 inside the `##CN` block), e.g. to record the
+    /// ECU, bus or message a signal was acquired from.
+    ///
+    /// The source's name, path and comment are stored in `##TX` blocks.
+    pub fn set_channel_source(&mut self, cn_id: &ChannelId, source: &SourceInfo) -> Result<(), MdfError> {
+        let si_id = self.write_source_block(cn_id, source)?;
+        let source_link_offset = 48;
+        self.update_block_link(cn_id, source_link_offset, &si_id)
+    }
+
+    /// Write the `##SI` block (and its `##TX` blocks) for `source` on behalf
+    /// of block `owner_id`, returning the SI block's ID.
+    fn write_source_block(&mut self, owner_id: &dyn std::fmt::Display, source: &SourceInfo) -> Result<String, MdfError> {
+        let mut si_block = SourceBlock {
+            source_type: source.source_type,
+            bus_type: source.bus_type,
+            ..SourceBlock::default()
+        };
+        let texts = [
+            ("name", &source.name),
+            ("path", &source.path),
+            ("comment", &source.comment),
+        ];
+        let mut text_addrs = [0u64; 3];
+        for ((kind, text), addr) in texts.into_iter().zip(&mut text_addrs) {
+            if let Some(text) = text {
+                let tx_bytes = TextBlock::new(text).to_bytes()?;
+                *addr = self.write_block_with_id(&tx_bytes, &format!("tx_si_{kind}_{owner_id}"))?;
+            }
+        }
+        [si_block.name_addr, si_block.path_addr, si_block.comment_addr] = text_addrs;
+        let si_id = format!("si_{owner_id}");
+        self.write_block_with_id(&si_block.to_bytes()?, &si_id)?;
+        Ok(si_id)
+    }
+
     /// Write a `##TX` block holding `comment` and link it as the channel
     /// group's `comment_addr`.
     ///
     /// The comment link is at offset 64 inside the `##CG` block.
     pub fn set_channel_group_comment(
         &mut self,
-        cg_id: &str,
+        cg_id: &ChannelGroupId,
         comment: &str,
     ) -> Result<(), MdfError> {
         let tx_id = format!("tx_cg_comment_{cg_id}");
@@ -216,29 +517,36 @@ impl MdfWriter {
     }
 
     /// Adds a channel block to the specified channel group and links it.
+    ///
+    /// A channel left at `byte_offset` 0 is placed after the channels added
+    /// before it: integers narrower than their bytes (e.g. 1 or 12 bits,
+    /// `bit_offset` 0) are packed right after the previous channel's bits,
+    /// everything else starts at the next whole byte. Virtual channels take
+    /// no record bytes. Overlapping channels are rejected when the data
+    /// block is started.
+    ///
+    /// A `source` set by `configure` is written as a `##SI` block and linked
+    /// from the new `##CN`.
     pub fn add_channel<F>(
         &mut self,
-        cg_id: &str,
-        prev_cn_id: Option<&str>,
+        cg_id: &ChannelGroupId,
+        prev_cn_id: Option<&ChannelId>,
         configure: F,
-    ) -> Result<String, MdfError>
+    ) -> Result<ChannelId, MdfError>
     where
         F: FnOnce(&mut ChannelBlock),
     {
-        let cn_count = self.block_positions.keys().filter(|k| k.starts_with("cn_")).count();
-        let cn_id = format!("cn_{}", cn_count);
-
         let mut ch = ChannelBlock::default();
         configure(&mut ch);
         if ch.bit_count == 0 { ch.bit_count = ch.data_type.default_bits(); }
-        if let Some(off) = self.cg_offsets.get_mut(cg_id) {
-            if ch.byte_offset == 0 { ch.byte_offset = *off as u32; }
-            let used = ((ch.bit_offset as usize + ch.bit_count as usize + 7) / 8) as usize;
-            *off = ch.byte_offset as usize + used;
+        self.check_channel_compliance(&ch)?;
+        if let Some(next_bit) = self.cg_next_bit.get_mut(cg_id) {
+            place_channel(&mut ch, next_bit);
         }
 
         let cn_bytes = ch.to_bytes()?;
-        let cn_pos = self.write_block_with_id(&cn_bytes, &cn_id)?;
+        let cn_id = self.write_cn_block(&cn_bytes)?;
+        let cn_pos = self.cn_positions[cn_id.index()];
         if let Some(channel_name) = &ch.name {
             let tx_id = format!("tx_name_{}", cn_id);
             let tx_block = TextBlock::new(channel_name);
@@ -247,38 +555,39 @@ impl MdfWriter {
             let name_link_offset = 40;
             self.update_link(cn_pos + name_link_offset, tx_pos)?;
         }
+        if let Some(source) = &ch.source {
+            self.set_channel_source(&cn_id, source)?;
+        }
 
-        let entry = self.cg_channels.entry(cg_id.to_string()).or_default();
+        let entry = self.cg_channels.get_or_default(*cg_id);
         entry.push(ch.clone());
         let idx = entry.len() - 1;
-        self.cg_channel_ids.entry(cg_id.to_string()).or_default().push(cn_id.clone());
-        self.channel_map.insert(cn_id.clone(), (cg_id.to_string(), idx));
+        self.cg_channel_ids.get_or_default(*cg_id).push(cn_id);
+        self.channel_map.insert(cn_id, (*cg_id, idx));
 
         if prev_cn_id.is_none() {
             let cg_cn_link_offset = 32;
-            self.update_block_link(cg_id, cg_cn_link_offset, &cn_id)?;
+            self.update_block_link(cg_id, cg_cn_link_offset, cn_id)?;
         } else {
             let prev_cn = prev_cn_id.unwrap();
             let prev_cn_next_link_offset = 24;
-            self.update_block_link(prev_cn, prev_cn_next_link_offset, &cn_id)?;
+            self.update_block_link(prev_cn, prev_cn_next_link_offset, cn_id)?;
         }
         Ok(cn_id)
     }
 
     /// Mark an existing channel as the time (master) channel.
-    pub fn set_time_channel(&mut self, cn_id: &str) -> Result<(), MdfError> {
+    pub fn set_time_channel(&mut self, cn_id: &ChannelId) -> Result<(), MdfError> {
         const CHANNEL_TYPE_OFFSET: u64 = 88;
         const SYNC_TYPE_OFFSET: u64 = 89;
         self.update_block_u8(cn_id, CHANNEL_TYPE_OFFSET, 2)?;
         self.update_block_u8(cn_id, SYNC_TYPE_OFFSET, 1)?;
 
-        if let Some((cg, idx)) = self.channel_map.get(cn_id).cloned() {
-            if let Some(chs) = self.cg_channels.get_mut(&cg) {
-                if let Some(ch) = chs.get_mut(idx) {
-                    ch.channel_type = 2;
-                    ch.sync_type = 1;
-                }
-            }
+        if let Some(&(cg, idx)) = self.channel_map.get(cn_id)
+            && let Some(ch) = self.cg_channels.get_mut(&cg).and_then(|chs| chs.get_mut(idx))
+        {
+            ch.channel_type = 2;
+            ch.sync_type = 1;
         }
         Ok(())
     }
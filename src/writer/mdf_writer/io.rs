@@ -1,8 +1,9 @@
 // Low level file and block handling utilities for MdfWriter
 use super::*;
-use std::collections::HashMap;
+use super::handles::Handle;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Seek, SeekFrom, Write};
-use byteorder::{LittleEndian, WriteBytesExt};
+use std::path::Path;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
@@ -19,7 +20,7 @@ struct MmapWriter {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl MmapWriter {
-    fn new(path: &str, size: usize) -> Result<Self, MdfError> {
+    fn new(path: impl AsRef<Path>, size: usize) -> Result<Self, MdfError> {
         use std::fs::OpenOptions;
         let file = OpenOptions::new().read(true).write(true).create(true).open(path)?;
         file.set_len(size as u64)?;
@@ -60,27 +61,21 @@ impl Seek for MmapWriter {
     }
 }
 
+/// Block types kept in `MdfWriter::metadata_blocks` with
+/// [`WriterConfig::keep_metadata`]; `##CG` blocks are always kept.
+pub(super) const METADATA_BLOCK_IDS: [&[u8]; 6] = [b"##CG", b"##CN", b"##CC", b"##SI", b"##TX", b"##MD"];
+
 impl MdfWriter {
-    /// Creates a new MdfWriter from any `Write + Seek` backend.
+    /// Creates a new MdfWriter from any `Write + Seek` backend, boxed so the
+    /// writer has the default [`MdfWriter`] type.
     ///
     /// This is the only constructor available on `wasm32-unknown-unknown`.
     /// On native targets you can pass a `std::io::Cursor<Vec<u8>>` to produce
-    /// an in-memory MDF file, or a `BufWriter<File>` for on-disk output.
+    /// an in-memory MDF file, or a `BufWriter<File>` for on-disk output. Use
+    /// [`from_writer`](MdfWriter::from_writer) to get the sink back with
+    /// [`into_inner`](MdfWriter::into_inner).
     pub fn new_from_writer(w: impl Write + Seek + 'static) -> Self {
-        MdfWriter {
-            file: Box::new(w),
-            offset: 0,
-            block_positions: HashMap::new(),
-            open_dts: HashMap::new(),
-            sd_buffers: HashMap::new(),
-            dt_counter: 0,
-            last_dg: None,
-            cg_to_dg: HashMap::new(),
-            cg_offsets: HashMap::new(),
-            cg_channels: HashMap::new(),
-            cg_channel_ids: HashMap::new(),
-            channel_map: HashMap::new(),
-        }
+        MdfWriter::from_writer(Box::new(w) as Box<dyn WriteSeek>)
     }
 
     /// Creates a new MdfWriter for the given file path using a 1 MB internal
@@ -88,7 +83,7 @@ impl MdfWriter {
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`new_from_writer`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn new(path: &str) -> Result<Self, MdfError> {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, MdfError> {
         Self::new_with_capacity(path, 1_048_576)
     }
 
@@ -96,45 +91,68 @@ impl MdfWriter {
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`new_from_writer`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn new_with_capacity(path: &str, capacity: usize) -> Result<Self, MdfError> {
+    pub fn new_with_capacity(path: impl AsRef<Path>, capacity: usize) -> Result<Self, MdfError> {
         let file = File::create(path)?;
-        let file = BufWriter::with_capacity(capacity, file);
-        Ok(MdfWriter {
-            file: Box::new(file),
-            offset: 0,
-            block_positions: HashMap::new(),
-            open_dts: HashMap::new(),
-            sd_buffers: HashMap::new(),
-            dt_counter: 0,
-            last_dg: None,
-            cg_to_dg: HashMap::new(),
-            cg_offsets: HashMap::new(),
-            cg_channels: HashMap::new(),
-            cg_channel_ids: HashMap::new(),
-            channel_map: HashMap::new(),
-        })
+        Ok(Self::new_from_writer(BufWriter::with_capacity(capacity, file)))
     }
 
     /// Creates a new MdfWriter backed by a memory-mapped file of the given size.
     ///
     /// Not available on `wasm32-unknown-unknown`; use [`new_from_writer`] instead.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn new_mmap(path: &str, size: usize) -> Result<Self, MdfError> {
-        let writer = MmapWriter::new(path, size)?;
-        Ok(MdfWriter {
-            file: Box::new(writer),
+    pub fn new_mmap(path: impl AsRef<Path>, size: usize) -> Result<Self, MdfError> {
+        Ok(Self::new_from_writer(MmapWriter::new(path, size)?))
+    }
+
+    /// Creates a new MdfWriter for multi-GB recordings that grows the file
+    /// in `extent_size` steps (64 MiB if 0) and writes with positioned
+    /// writes; see [`PreallocatedFile`](super::PreallocatedFile). The file
+    /// is cut to its content when the writer is finalized.
+    ///
+    /// Not available on `wasm32-unknown-unknown`; use [`new_from_writer`] instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_preallocated(path: impl AsRef<Path>, extent_size: u64) -> Result<Self, MdfError> {
+        Ok(Self::new_from_writer(super::PreallocatedFile::create(path, extent_size)?))
+    }
+}
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Creates a new MdfWriter writing into `w`, starting at offset 0.
+    ///
+    /// Unlike [`new_from_writer`](MdfWriter::new_from_writer) the sink keeps
+    /// its type, so [`into_inner`](Self::into_inner) can return it, e.g. the
+    /// finished file as a `Vec<u8>` from a `Cursor`.
+    pub fn from_writer(w: W) -> Self {
+        MdfWriter {
+            file: w,
             offset: 0,
             block_positions: HashMap::new(),
-            open_dts: HashMap::new(),
-            sd_buffers: HashMap::new(),
+            dg_positions: Vec::new(),
+            cg_positions: Vec::new(),
+            cn_positions: Vec::new(),
+            block_counts: HashMap::new(),
+            open_dts: HandleMap::new(),
+            sd_buffers: HandleMap::new(),
             dt_counter: 0,
             last_dg: None,
-            cg_to_dg: HashMap::new(),
-            cg_offsets: HashMap::new(),
-            cg_channels: HashMap::new(),
-            cg_channel_ids: HashMap::new(),
-            channel_map: HashMap::new(),
-        })
+            cg_to_dg: HandleMap::new(),
+            cg_next_bit: HandleMap::new(),
+            cg_channels: HandleMap::new(),
+            cg_channel_ids: HandleMap::new(),
+            channel_map: HandleMap::new(),
+            live_update: None,
+            metadata_blocks: BTreeMap::new(),
+            compliance: Compliance::Lenient,
+            config: WriterConfig::default(),
+            compression: CompressionMethod::None,
+            data_block_size: super::data::DEFAULT_DATA_BLOCK_SIZE,
+            bus_groups: HandleMap::new(),
+            previous_data: HandleMap::new(),
+            unsorted_streams: HandleMap::new(),
+            checkpoint_interval: None,
+            last_checkpoint: None,
+            pending_patches: BTreeMap::new(),
+        }
     }
 
     /// Writes a block to the file, aligning to 8 bytes and zero-padding as needed.
@@ -150,9 +168,18 @@ impl MdfWriter {
         self.file.write_all(block_bytes)?;
         let block_start = self.offset;
         self.offset += block_bytes.len() as u64;
+        if self.keeps_copy(block_bytes) {
+            self.metadata_blocks.insert(block_start, block_bytes.to_vec());
+        }
         Ok(block_start)
     }
 
+    /// `true` if a copy of `block` belongs in `metadata_blocks`.
+    pub(super) fn keeps_copy(&self, block: &[u8]) -> bool {
+        block.starts_with(b"##CG")
+            || self.config.keep_metadata && block.len() >= 4 && METADATA_BLOCK_IDS.contains(&&block[..4])
+    }
+
     /// Writes a block to the file and tracks its position with the given ID.
     pub fn write_block_with_id(&mut self, block_bytes: &[u8], block_id: &str) -> Result<u64, MdfError> {
         let block_start = self.write_block(block_bytes)?;
@@ -160,69 +187,177 @@ impl MdfWriter {
         Ok(block_start)
     }
 
-    /// Retrieves the file position of a previously written block.
-    pub fn get_block_position(&self, block_id: &str) -> Option<u64> {
-        self.block_positions.get(block_id).copied()
+    /// Retrieves the file position of a previously written block, given by
+    /// handle or by name.
+    pub fn get_block_position<'a>(&self, block: impl Into<BlockRef<'a>>) -> Option<u64> {
+        match block.into() {
+            BlockRef::Named(name) => self.block_positions.get(name).copied(),
+            BlockRef::DataGroup(id) => self.dg_positions.get(id.index()).copied(),
+            BlockRef::ChannelGroup(id) => self.cg_positions.get(id.index()).copied(),
+            BlockRef::Channel(id) => self.cn_positions.get(id.index()).copied(),
+        }
+    }
+
+    /// Handle of the `index`th channel group (in creation order, or file
+    /// order for the groups of a file reopened with
+    /// [`open_append`](MdfWriter::open_append)).
+    pub fn channel_group_id(&self, index: usize) -> Option<ChannelGroupId> {
+        (index < self.cg_positions.len()).then(|| ChannelGroupId::from_index(index))
+    }
+
+    /// Handle of the `index`th channel; see
+    /// [`channel_group_id`](Self::channel_group_id).
+    pub fn channel_id(&self, index: usize) -> Option<ChannelId> {
+        (index < self.cn_positions.len()).then(|| ChannelId::from_index(index))
     }
 
     /// Updates a link (u64 address) at a specific offset in the file.
+    ///
+    /// Like every patch of an already written block, the write is queued
+    /// and only reaches the sink on [`flush`](Self::flush).
     pub fn update_link(&mut self, offset: u64, address: u64) -> Result<(), MdfError> {
-        let current_pos = self.offset;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_u64::<LittleEndian>(address)?;
-        self.file.seek(SeekFrom::Start(current_pos))?;
+        self.queue_patch(offset, &address.to_le_bytes());
         Ok(())
     }
 
-    /// Updates a link using block IDs instead of raw offsets.
-    pub fn update_block_link(&mut self, source_id: &str, link_offset: u64, target_id: &str) -> Result<(), MdfError> {
-        let source_pos = self.get_block_position(source_id)
-            .ok_or_else(|| MdfError::BlockLinkError(format!("Source block '{}' not found", source_id)))?;
-        let target_pos = self.get_block_position(target_id)
-            .ok_or_else(|| MdfError::BlockLinkError(format!("Target block '{}' not found", target_id)))?;
+    /// Updates a link using block handles or names instead of raw offsets.
+    pub fn update_block_link<'a, 'b>(
+        &mut self,
+        source: impl Into<BlockRef<'a>>,
+        link_offset: u64,
+        target: impl Into<BlockRef<'b>>,
+    ) -> Result<(), MdfError> {
+        let (source, target) = (source.into(), target.into());
+        let source_pos = self.get_block_position(source)
+            .ok_or_else(|| MdfError::BlockLinkError(format!("Source block '{}' not found", source)))?;
+        let target_pos = self.get_block_position(target)
+            .ok_or_else(|| MdfError::BlockLinkError(format!("Target block '{}' not found", target)))?;
         let link_pos = source_pos + link_offset;
         self.update_link(link_pos, target_pos)
     }
 
+    /// Write a `##DG`, `##CG` or `##CN` block and record its position under
+    /// a new handle.
+    pub(super) fn write_dg_block(&mut self, block_bytes: &[u8]) -> Result<DataGroupId, MdfError> {
+        let pos = self.write_block(block_bytes)?;
+        self.dg_positions.push(pos);
+        Ok(DataGroupId::from_index(self.dg_positions.len() - 1))
+    }
+
+    pub(super) fn write_cg_block(&mut self, block_bytes: &[u8]) -> Result<ChannelGroupId, MdfError> {
+        let pos = self.write_block(block_bytes)?;
+        self.cg_positions.push(pos);
+        Ok(ChannelGroupId::from_index(self.cg_positions.len() - 1))
+    }
+
+    pub(super) fn write_cn_block(&mut self, block_bytes: &[u8]) -> Result<ChannelId, MdfError> {
+        let pos = self.write_block(block_bytes)?;
+        self.cn_positions.push(pos);
+        Ok(ChannelId::from_index(self.cn_positions.len() - 1))
+    }
+
+    /// Next ID of the named blocks with `prefix`, e.g. `cc_3`, numbered in
+    /// creation order.
+    pub(super) fn next_block_id(&mut self, prefix: &'static str) -> String {
+        let count = self.block_counts.entry(prefix).or_default();
+        let id = format!("{prefix}_{count}");
+        *count += 1;
+        id
+    }
+
     fn update_u32(&mut self, offset: u64, value: u32) -> Result<(), MdfError> {
-        let current_pos = self.offset;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_u32::<LittleEndian>(value)?;
-        self.file.seek(SeekFrom::Start(current_pos))?;
+        self.queue_patch(offset, &value.to_le_bytes());
         Ok(())
     }
 
     fn update_u64(&mut self, offset: u64, value: u64) -> Result<(), MdfError> {
-        let current_pos = self.offset;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_u64::<LittleEndian>(value)?;
-        self.file.seek(SeekFrom::Start(current_pos))?;
+        self.queue_patch(offset, &value.to_le_bytes());
         Ok(())
     }
 
     fn update_u8(&mut self, offset: u64, value: u8) -> Result<(), MdfError> {
-        let current_pos = self.offset;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_u8(value)?;
-        self.file.seek(SeekFrom::Start(current_pos))?;
+        self.queue_patch(offset, &[value]);
         Ok(())
     }
 
-    pub(super) fn update_block_u32(&mut self, block_id: &str, field_offset: u64, value: u32) -> Result<(), MdfError> {
-        let block_pos = self.get_block_position(block_id)
-            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block_id)))?;
+    /// Queue a patch of already written bytes. Overlapping and adjacent
+    /// patches are merged into one run, later bytes winning, so
+    /// [`flush`](Self::flush) needs a single seek per run instead of a
+    /// seek-write-seek per field.
+    pub(super) fn queue_patch(&mut self, offset: u64, bytes: &[u8]) {
+        self.patch_metadata_copy(offset, bytes);
+        let end = offset + bytes.len() as u64;
+        // Runs are disjoint and not adjacent, so the ones touching the new
+        // range are the last runs starting at or before its end.
+        let touching: Vec<u64> = self
+            .pending_patches
+            .range(..=end)
+            .rev()
+            .take_while(|&(&start, run)| start + run.len() as u64 >= offset)
+            .map(|(&start, _)| start)
+            .collect();
+        let run_start = touching.last().map_or(offset, |&start| start.min(offset));
+        let run_end = touching
+            .iter()
+            .map(|start| start + self.pending_patches[start].len() as u64)
+            .fold(end, u64::max);
+        let mut run = vec![0u8; (run_end - run_start) as usize];
+        for start in touching {
+            let old = self.pending_patches.remove(&start).unwrap();
+            let at = (start - run_start) as usize;
+            run[at..at + old.len()].copy_from_slice(&old);
+        }
+        let at = (offset - run_start) as usize;
+        run[at..at + bytes.len()].copy_from_slice(bytes);
+        self.pending_patches.insert(run_start, run);
+    }
+
+    /// Write the queued patches to the sink, in file order, and flush it.
+    ///
+    /// Called by [`checkpoint`](Self::checkpoint),
+    /// [`publish_progress`](Self::publish_progress) and
+    /// [`finalize`](Self::finalize); call it directly to make the sink
+    /// consistent at any other point.
+    pub fn flush(&mut self) -> Result<(), MdfError> {
+        if !self.pending_patches.is_empty() {
+            for (offset, bytes) in std::mem::take(&mut self.pending_patches) {
+                self.file.seek(SeekFrom::Start(offset))?;
+                self.file.write_all(&bytes)?;
+            }
+            self.file.seek(SeekFrom::Start(self.offset))?;
+        }
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// Mirror an in-place patch into the copy of the metadata block it hits.
+    fn patch_metadata_copy(&mut self, offset: u64, bytes: &[u8]) {
+        if let Some((&start, block)) = self.metadata_blocks.range_mut(..=offset).next_back() {
+            let at = (offset - start) as usize;
+            if at + bytes.len() <= block.len() {
+                block[at..at + bytes.len()].copy_from_slice(bytes);
+            }
+        }
+    }
+
+    pub(super) fn update_block_u32<'a>(&mut self, block: impl Into<BlockRef<'a>>, field_offset: u64, value: u32) -> Result<(), MdfError> {
+        let block = block.into();
+        let block_pos = self.get_block_position(block)
+            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block)))?;
         self.update_u32(block_pos + field_offset, value)
     }
 
-    pub(super) fn update_block_u8(&mut self, block_id: &str, field_offset: u64, value: u8) -> Result<(), MdfError> {
-        let block_pos = self.get_block_position(block_id)
-            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block_id)))?;
+    pub(super) fn update_block_u8<'a>(&mut self, block: impl Into<BlockRef<'a>>, field_offset: u64, value: u8) -> Result<(), MdfError> {
+        let block = block.into();
+        let block_pos = self.get_block_position(block)
+            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block)))?;
         self.update_u8(block_pos + field_offset, value)
     }
 
-    pub(super) fn update_block_u64(&mut self, block_id: &str, field_offset: u64, value: u64) -> Result<(), MdfError> {
-        let block_pos = self.get_block_position(block_id)
-            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block_id)))?;
+    pub(super) fn update_block_u64<'a>(&mut self, block: impl Into<BlockRef<'a>>, field_offset: u64, value: u64) -> Result<(), MdfError> {
+        let block = block.into();
+        let block_pos = self.get_block_position(block)
+            .ok_or_else(|| MdfError::BlockLinkError(format!("Block '{}' not found", block)))?;
         self.update_u64(block_pos + field_offset, value)
     }
 
@@ -230,8 +365,16 @@ impl MdfWriter {
     pub fn offset(&self) -> u64 { self.offset }
 
     /// Finalizes the file (flushes all data to disk).
-    pub fn finalize(mut self) -> Result<(), MdfError> {
-        self.file.flush()?;
-        Ok(())
+    pub fn finalize(self) -> Result<(), MdfError> {
+        self.into_inner().map(drop)
+    }
+
+    /// Finalizes the file like [`finalize`](Self::finalize) and returns the
+    /// flushed sink.
+    pub fn into_inner(mut self) -> Result<W, MdfError> {
+        self.check_file_history_compliance()?;
+        self.clear_unfinalized_flags()?;
+        self.flush()?;
+        Ok(self.file)
     }
 }
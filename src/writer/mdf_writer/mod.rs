@@ -2,25 +2,50 @@
 
 use std::io::{Write, Seek};
 
-trait WriteSeek: Write + Seek {}
+/// Object-safe `Write + Seek` sink behind the default [`MdfWriter`] type,
+/// which the path-based constructors return.
+pub trait WriteSeek: Write + Seek {}
 impl<T: Write + Seek> WriteSeek for T {}
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::blocks::channel_block::ChannelBlock;
 use crate::error::MdfError;
-use crate::writer::mdf_writer::data::ChannelEncoder;
+use crate::writer::mdf_writer::data::{ChannelEncoder, CompressionMethod, LiveUpdate};
 
 mod io;
 mod init;
 pub mod data;
 mod vlsd;
+mod clone;
+mod compliance;
+mod config;
+mod events;
+mod bus_logging;
+mod append;
+mod checkpoint;
+mod unsorted;
+mod handles;
+#[cfg(not(target_arch = "wasm32"))]
+mod pool;
+#[cfg(not(target_arch = "wasm32"))]
+mod prealloc;
+pub use compliance::Compliance;
+pub use config::WriterConfig;
+pub use handles::{BlockRef, ChannelGroupId, ChannelId, DataGroupId};
+use handles::HandleMap;
+#[cfg(not(target_arch = "wasm32"))]
+pub use pool::WriterPool;
+#[cfg(not(target_arch = "wasm32"))]
+pub use prealloc::{PreallocatedFile, DEFAULT_EXTENT_SIZE};
 
 /// Helper structure tracking an open DTBLOCK during writing
 struct OpenDataBlock {
-    dg_id: String,
+    dg_id: DataGroupId,
     dt_id: String,
     start_pos: u64,
     record_size: usize,
+    /// Maximum DT block length for this group; see `data::dt_block_limit`.
+    max_block_size: usize,
     record_count: u64,
     /// Total number of records written across all DT blocks for this group
     total_record_count: u64,
@@ -30,41 +55,151 @@ struct OpenDataBlock {
     dt_sizes: Vec<u64>,
     /// Scratch buffer reused for record encoding
     record_buf: Vec<u8>,
-    /// Template filled with constant values used to initialise each record
+    /// Template filled with constant values used to initialise each record.
+    /// Its first `record_id_len` bytes hold the group's record ID.
     record_template: Vec<u8>,
+    /// Number of record ID bytes leading each record.
+    record_id_len: usize,
     /// Precomputed per-channel encoders
     encoders: Vec<ChannelEncoder>,
+    /// Indices of the channels not fixed by `set_constant_channels`, in
+    /// channel order; every channel until it is called.
+    varying_channels: Vec<usize>,
+    /// Offset of the invalidation byte region within a record
+    /// (record id + data bytes).
+    invalidation_offset: usize,
+    /// Per-channel `pos_invalidation_bit` for channels whose `cn_flags`
+    /// mark the invalidation bit as valid, `None` otherwise.
+    invalidation_bits: Vec<Option<u32>>,
     /// Per-channel VLSD payload accumulator. `Some(buf)` for VLSD channels
     /// (channel_type == 1 && data != 0), `None` otherwise. The buffer holds
     /// the running [u32 length][bytes] stream that will be emitted as a ##SD
     /// block in `finish_data_block`.
     vlsd_payloads: Vec<Option<Vec<u8>>>,
-    /// Writer-side channel IDs (cn_*) for VLSD channels, used to patch the
+    /// Writer-side channel handles of VLSD channels, used to patch the
     /// `cn_data` link to the SD block in `finish_data_block`.
-    vlsd_channel_ids: Vec<Option<String>>,
+    vlsd_channel_ids: Vec<Option<ChannelId>>,
+    /// Positions of the single-entry DL blocks chaining `dt_positions` while
+    /// live updates are enabled (one per DT, in order). Empty otherwise, in
+    /// which case `finish_data_block` writes one DL for all DT blocks.
+    live_dl_positions: Vec<u64>,
+    /// Total record count made visible by the last `publish_progress` or
+    /// `checkpoint`.
+    published_records: u64,
+    /// Time of the last `publish_progress`; only tracked when the live
+    /// update policy has an interval.
+    last_publish: Option<std::time::Instant>,
+    /// Compression of this group's data blocks, fixed when the block opens.
+    compression: CompressionMethod,
+    /// Records of the current block when it is written as `##DZ` on close;
+    /// `None` for uncompressed blocks, whose records go straight to the file.
+    zip_buffer: Option<Vec<u8>>,
+}
+
+/// Data stream shared by the channel groups of an unsorted data group,
+/// opened by [`MdfWriter::start_unsorted_data_block`]. Each write call of a
+/// member group buffers its records in the group's `zip_buffer` and hands
+/// them over here, so the stream holds the records in call order.
+struct UnsortedStream {
+    /// Member channel groups, in the order they were added.
+    cg_ids: Vec<ChannelGroupId>,
+    dt_id: String,
+    start_pos: u64,
+    /// Record bytes in the current data block.
+    len: usize,
+    /// Data section size of the stream's data blocks.
+    data_block_size: usize,
+    /// Closed data blocks with their data length, in order.
+    blocks: Vec<(u64, u64)>,
+    compression: CompressionMethod,
+    /// Records of the current block when it is written as `##DZ` on close.
+    zip_buffer: Option<Vec<u8>>,
+}
+
+/// Records a channel group had when its file was reopened with
+/// [`MdfWriter::open_append`]; new data blocks of the group continue them.
+struct PreviousData {
+    /// Data blocks of the group in order, with their uncompressed data length.
+    blocks: Vec<(u64, u64)>,
+    cycles: u64,
+    record_id_len: u8,
+    data_bytes: u32,
+    invalidation_bytes: u32,
+    /// The data group holds other channel groups as well.
+    shared: bool,
 }
 
 
 /// Writer for MDF blocks, ensuring 8-byte alignment and zero padding.
 /// Tracks block positions and supports updating links at a later stage.
-pub struct MdfWriter {
-    file: Box<dyn WriteSeek>,
+///
+/// The output goes to any `Write + Seek` sink `W`: [`MdfWriter::new`] and
+/// friends box a file writer, [`MdfWriter::from_writer`] keeps a concrete
+/// sink (e.g. `Cursor<Vec<u8>>`) that [`into_inner`](MdfWriter::into_inner)
+/// hands back once the file is complete.
+///
+/// Patches of blocks already written (links, lengths, counters) are queued
+/// and written in file order by [`flush`](MdfWriter::flush), which
+/// [`checkpoint`](MdfWriter::checkpoint) and [`finalize`](MdfWriter::finalize)
+/// call, so a writer dropped without finalizing leaves them unpatched.
+pub struct MdfWriter<W = Box<dyn WriteSeek>> {
+    file: W,
     offset: u64,
+    /// Positions of the blocks written with [`MdfWriter::write_block_with_id`].
     block_positions: HashMap<String, u64>,
-    open_dts: HashMap<String, OpenDataBlock>,
-    /// In-memory VLSD payload buffers keyed by channel id. Each entry holds
+    /// Positions of the `##DG`, `##CG` and `##CN` blocks, indexed by handle.
+    dg_positions: Vec<u64>,
+    cg_positions: Vec<u64>,
+    cn_positions: Vec<u64>,
+    /// Number of IDs handed out per prefix by `next_block_id`.
+    block_counts: HashMap<&'static str, usize>,
+    open_dts: HandleMap<ChannelGroupId, OpenDataBlock>,
+    /// In-memory VLSD payload buffers keyed by channel. Each entry holds
     /// the concatenated `[u32 length][bytes]…` stream collected between
     /// `start_signal_data_block` and `finish_signal_data_block`. Buffers are
     /// flushed to ##SD blocks (chained via ##DL when large) on finish.
-    sd_buffers: HashMap<String, Vec<u8>>,
+    sd_buffers: HandleMap<ChannelId, Vec<u8>>,
     dt_counter: usize,
-    last_dg: Option<String>,
-    cg_to_dg: HashMap<String, String>,
-    cg_offsets: HashMap<String, usize>,
-    cg_channels: HashMap<String, Vec<ChannelBlock>>,
-    /// Parallel to `cg_channels`: writer-side channel ids (cn_*) per channel
-    /// group, in the same order. Used to look up VLSD channel ids when the
+    last_dg: Option<DataGroupId>,
+    cg_to_dg: HandleMap<ChannelGroupId, DataGroupId>,
+    /// Next free bit of each group's record, where a channel added without
+    /// an offset is placed; see [`MdfWriter::add_channel`].
+    cg_next_bit: HandleMap<ChannelGroupId, usize>,
+    cg_channels: HandleMap<ChannelGroupId, Vec<ChannelBlock>>,
+    /// Parallel to `cg_channels`: writer-side channel handles per channel
+    /// group, in the same order. Used to look up VLSD channels when the
     /// open DT block emits its SD block.
-    cg_channel_ids: HashMap<String, Vec<String>>,
-    channel_map: HashMap<String, (String, usize)>,
+    cg_channel_ids: HandleMap<ChannelGroupId, Vec<ChannelId>>,
+    channel_map: HandleMap<ChannelId, (ChannelGroupId, usize)>,
+    /// Progress publication policy for live readers; see [`LiveUpdate`].
+    live_update: Option<LiveUpdate>,
+    /// Copies of the `##CG` blocks written so far and, with
+    /// [`WriterConfig::keep_metadata`], of the `##CN`, `##CC`, `##SI`, `##TX`
+    /// and `##MD` blocks, keyed by file offset and kept in sync with later
+    /// link/field patches, so record IDs can be looked up and a configured
+    /// channel group cloned into another writer without reading the output
+    /// back.
+    metadata_blocks: BTreeMap<u64, Vec<u8>>,
+    /// Configuration validation level; see [`Compliance`].
+    compliance: Compliance,
+    /// Identification block fields; see [`WriterConfig`].
+    config: WriterConfig,
+    /// Compression for data blocks opened from now on; see [`CompressionMethod`].
+    compression: CompressionMethod,
+    /// Data section size of the data blocks opened from now on; see
+    /// [`MdfWriter::set_data_block_size`].
+    data_block_size: usize,
+    /// Frame type and bus channel of the bus logging channel groups.
+    bus_groups: HandleMap<ChannelGroupId, (crate::bus_logging::BusFrameType, u8)>,
+    /// Channel groups of a file reopened with [`MdfWriter::open_append`]
+    /// that already hold records.
+    previous_data: HandleMap<ChannelGroupId, PreviousData>,
+    /// Open data streams of unsorted data groups.
+    unsorted_streams: HandleMap<DataGroupId, UnsortedStream>,
+    /// Interval of automatic checkpoints; see [`MdfWriter::set_auto_checkpoint`].
+    checkpoint_interval: Option<std::time::Duration>,
+    last_checkpoint: Option<std::time::Instant>,
+    /// Patches of already written bytes, keyed by file offset, merged into
+    /// runs and written on [`MdfWriter::flush`].
+    pending_patches: BTreeMap<u64, Vec<u8>>,
 }
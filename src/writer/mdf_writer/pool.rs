@@ -0,0 +1,203 @@
+// Encoding records on worker threads for a single writing thread
+use super::*;
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::parsing::decoder::DecodedValue;
+
+/// Jobs and encoded batches in flight per worker thread.
+const QUEUE_DEPTH: usize = 4;
+
+/// How the workers encode the records of one channel group.
+struct GroupLayout {
+    channel_count: usize,
+    /// Template and encoders of the group; `None` for groups with VLSD
+    /// channels, whose records are encoded in order on the writing thread.
+    encoding: Option<(Vec<u8>, Vec<ChannelEncoder>)>,
+}
+
+/// Records of one group handed to the workers, numbered in call order.
+struct Job {
+    seq: u64,
+    cg_id: ChannelGroupId,
+    records: Vec<Vec<DecodedValue>>,
+}
+
+enum Encoded {
+    /// Records encoded back to back.
+    Raw(Vec<u8>),
+    /// Records left to [`MdfWriter::write_records`].
+    Values(Vec<Vec<DecodedValue>>),
+}
+
+type Batch = (u64, ChannelGroupId, Result<Encoded, MdfError>);
+
+/// Record writer encoding records on worker threads; see
+/// [`MdfWriter::writer_pool`].
+///
+/// Records are written in the order of the `write_*` calls across all
+/// groups. Errors, including those of the encoding such as a value count
+/// mismatch, stop the writing thread and are returned by
+/// [`finish`](WriterPool::finish); later `write_*` calls fail.
+pub struct WriterPool<W: Write + Seek + Send + 'static> {
+    jobs: Option<SyncSender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    writer: JoinHandle<Result<MdfWriter<W>, MdfError>>,
+    next_seq: u64,
+}
+
+impl<W: Write + Seek + Send + 'static> MdfWriter<W> {
+    /// Hand the writer to a pipeline that encodes records on `n_threads`
+    /// worker threads (at least one), while one thread writes them to the
+    /// file in order.
+    ///
+    /// The data blocks of the groups to write must be open (see
+    /// [`start_data_block_for_cg`](MdfWriter::start_data_block_for_cg)); the
+    /// record template and encoders are fixed at this point.
+    /// [`WriterPool::finish`] returns the writer to finish the data blocks
+    /// and finalize the file. Groups with VLSD channels are accepted, but
+    /// their records are encoded on the writing thread.
+    ///
+    /// The sink moves to the writing thread, so it must be `Send`: create
+    /// the writer with [`from_writer`](MdfWriter::from_writer), e.g. from a
+    /// `BufWriter<File>`, rather than with a path-based constructor.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::{fs::File, io::BufWriter};
+    /// # use mf4_rs::writer::{ChannelGroupId, MdfWriter};
+    /// # fn log(mut w: MdfWriter<BufWriter<File>>, cg: &ChannelGroupId) -> Result<(), mf4_rs::error::MdfError> {
+    /// use mf4_rs::parsing::decoder::DecodedValue;
+    ///
+    /// w.start_data_block_for_cg(cg, 0)?;
+    /// let mut pool = w.writer_pool(4);
+    /// for i in 0..1_000u64 {
+    ///     let batch = (0..1_000).map(|j| vec![DecodedValue::UnsignedInteger(i * 1_000 + j)]).collect();
+    ///     pool.write_records(cg, batch)?;
+    /// }
+    /// let mut w = pool.finish()?;
+    /// w.finish_data_block(cg)?;
+    /// w.finalize()
+    /// # }
+    /// ```
+    pub fn writer_pool(self, n_threads: usize) -> WriterPool<W> {
+        let n_threads = n_threads.max(1);
+        let layouts: HandleMap<ChannelGroupId, GroupLayout> = self
+            .open_dts
+            .iter()
+            .map(|(cg_id, dt)| {
+                let vlsd = dt.encoders.iter().any(|e| matches!(e, ChannelEncoder::VlsdOffset { .. }));
+                let layout = GroupLayout {
+                    channel_count: dt.channels.len(),
+                    encoding: (!vlsd).then(|| (dt.record_template.clone(), dt.encoders.clone())),
+                };
+                (cg_id, layout)
+            })
+            .fold(HandleMap::new(), |mut layouts, (cg_id, layout)| {
+                layouts.insert(cg_id, layout);
+                layouts
+            });
+        let layouts = Arc::new(layouts);
+
+        let (job_tx, job_rx) = sync_channel::<Job>(QUEUE_DEPTH * n_threads);
+        let (batch_tx, batch_rx) = sync_channel::<Batch>(QUEUE_DEPTH * n_threads);
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let workers = (0..n_threads)
+            .map(|_| {
+                let (jobs, batches, layouts) = (job_rx.clone(), batch_tx.clone(), layouts.clone());
+                std::thread::spawn(move || encode_jobs(&jobs, &batches, &layouts))
+            })
+            .collect();
+        let writer = std::thread::spawn(move || write_batches(self, batch_rx));
+        WriterPool { jobs: Some(job_tx), workers, writer, next_seq: 0 }
+    }
+}
+
+impl<W: Write + Seek + Send + 'static> WriterPool<W> {
+    /// Queue one record of the group `cg_id`; see [`MdfWriter::write_record`].
+    pub fn write_record(&mut self, cg_id: &ChannelGroupId, values: Vec<DecodedValue>) -> Result<(), MdfError> {
+        self.write_records(cg_id, vec![values])
+    }
+
+    /// Queue a batch of records of the group `cg_id`, encoded together by
+    /// one worker. Batches of a few thousand records keep the queues cheap
+    /// compared to the encoding.
+    pub fn write_records(&mut self, cg_id: &ChannelGroupId, records: Vec<Vec<DecodedValue>>) -> Result<(), MdfError> {
+        let job = Job { seq: self.next_seq, cg_id: *cg_id, records };
+        self.next_seq += 1;
+        let sent = self.jobs.as_ref().is_some_and(|jobs| jobs.send(job).is_ok());
+        if !sent {
+            return Err(MdfError::InvalidWriterState(
+                "writer pool stopped; see WriterPool::finish for the error".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Wait until all queued records are written and return the writer.
+    pub fn finish(mut self) -> Result<MdfWriter<W>, MdfError> {
+        self.jobs = None;
+        let panicked = || MdfError::InvalidWriterState("writer pool thread panicked".into());
+        for worker in self.workers {
+            worker.join().map_err(|_| panicked())?;
+        }
+        self.writer.join().map_err(|_| panicked())?
+    }
+}
+
+/// Worker thread: encode jobs until the pool stops taking records.
+fn encode_jobs(jobs: &Mutex<Receiver<Job>>, batches: &SyncSender<Batch>, layouts: &HandleMap<ChannelGroupId, GroupLayout>) {
+    loop {
+        // The lock is only held while waiting for the next job.
+        let job = match jobs.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        let encoded = encode_job(&job, layouts);
+        if batches.send((job.seq, job.cg_id, encoded)).is_err() {
+            return;
+        }
+    }
+}
+
+fn encode_job(job: &Job, layouts: &HandleMap<ChannelGroupId, GroupLayout>) -> Result<Encoded, MdfError> {
+    let layout = layouts.get(&job.cg_id).ok_or_else(|| {
+        MdfError::InvalidWriterState("no open DT block for this channel group".into())
+    })?;
+    if let Some(record) = job.records.iter().find(|r| r.len() != layout.channel_count) {
+        return Err(MdfError::ValueCountMismatch { expected: layout.channel_count, actual: record.len() });
+    }
+    let Some((template, encoders)) = &layout.encoding else {
+        return Ok(Encoded::Values(job.records.clone()));
+    };
+    let mut bytes = Vec::with_capacity(template.len() * job.records.len());
+    for record in &job.records {
+        let start = bytes.len();
+        bytes.extend_from_slice(template);
+        for (enc, value) in encoders.iter().zip(record) {
+            enc.encode(&mut bytes[start..], value)?;
+        }
+    }
+    Ok(Encoded::Raw(bytes))
+}
+
+/// Writing thread: write the encoded batches in call order.
+fn write_batches<W: Write + Seek>(
+    mut writer: MdfWriter<W>,
+    batches: Receiver<Batch>,
+) -> Result<MdfWriter<W>, MdfError> {
+    let mut next = 0;
+    let mut waiting = BTreeMap::new();
+    for (seq, cg_id, encoded) in batches {
+        waiting.insert(seq, (cg_id, encoded));
+        while let Some((cg_id, encoded)) = waiting.remove(&next) {
+            match encoded? {
+                Encoded::Raw(bytes) => writer.write_raw_records(&cg_id, &bytes)?,
+                Encoded::Values(records) => writer.write_records(&cg_id, records.iter().map(Vec::as_slice))?,
+            }
+            next += 1;
+        }
+    }
+    Ok(writer)
+}
@@ -0,0 +1,241 @@
+// Preallocating file sink for large recordings
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Default growth step of a [`PreallocatedFile`]: 64 MiB.
+pub const DEFAULT_EXTENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Size of the write buffer of a [`PreallocatedFile`].
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+/// File sink for multi-GB recordings that grows the file in large extents
+/// and writes with positioned writes.
+///
+/// Appending through a `BufWriter<File>` extends the file on every buffer
+/// flush, and every seek (each link patch of [`MdfWriter::flush`]) is a
+/// syscall that also drains the buffer. This sink instead:
+///
+/// * allocates the file one extent at a time (`fallocate` on Linux,
+///   `set_len` elsewhere), so the filesystem lays it out in few, large
+///   pieces and the writes into an extent skip block allocation;
+/// * buffers sequential output and writes it with a single positioned
+///   write (`pwrite`) per megabyte;
+/// * seeks without a syscall, and applies patches that fall into the
+///   buffer in memory.
+///
+/// The file is longer than its content while it is written; the trailing
+/// zeros are cut off by [`finish`](Self::finish), or on drop. A file left
+/// behind by a crash keeps them, which readers ignore as they only follow
+/// links. Use [`MdfWriter::new_preallocated`] for a path-based writer, or
+/// [`MdfWriter::from_writer`] and [`finish`](Self::finish) the sink returned
+/// by [`into_inner`](MdfWriter::into_inner) to see errors of the final
+/// truncation.
+///
+/// Not available on `wasm32-unknown-unknown`.
+///
+/// [`MdfWriter`]: super::MdfWriter
+/// [`MdfWriter::flush`]: super::MdfWriter::flush
+/// [`MdfWriter::new_preallocated`]: super::MdfWriter::new_preallocated
+/// [`MdfWriter::from_writer`]: super::MdfWriter::from_writer
+/// [`into_inner`]: super::MdfWriter::into_inner
+pub struct PreallocatedFile {
+    /// `None` once [`finish`](Self::finish) took the file.
+    file: Option<File>,
+    extent_size: u64,
+    /// Current length of the file on disk.
+    allocated: u64,
+    /// End of the content written so far.
+    len: u64,
+    pos: u64,
+    /// Pending output, to be written at `buf_start`.
+    buf: Vec<u8>,
+    buf_start: u64,
+}
+
+impl PreallocatedFile {
+    /// Create (or truncate) the file at `path`, growing it by `extent_size`
+    /// bytes at a time ([`DEFAULT_EXTENT_SIZE`] if 0).
+    pub fn create(path: impl AsRef<Path>, extent_size: u64) -> io::Result<Self> {
+        Ok(Self::from_file(File::create(path)?, extent_size))
+    }
+
+    /// Write into `file` from offset 0, growing it by `extent_size` bytes
+    /// at a time ([`DEFAULT_EXTENT_SIZE`] if 0). Existing content past the
+    /// written bytes is cut off on [`finish`](Self::finish).
+    pub fn from_file(file: File, extent_size: u64) -> Self {
+        let allocated = file.metadata().map_or(0, |m| m.len());
+        PreallocatedFile {
+            file: Some(file),
+            extent_size: if extent_size == 0 { DEFAULT_EXTENT_SIZE } else { extent_size },
+            allocated,
+            len: 0,
+            pos: 0,
+            buf: Vec::with_capacity(BUFFER_SIZE),
+            buf_start: 0,
+        }
+    }
+
+    /// Number of bytes written, i.e. the length of the finished file.
+    pub fn content_len(&self) -> u64 {
+        self.len
+    }
+
+    /// Length of the file on disk, including the unused part of the last
+    /// extent.
+    pub fn allocated_len(&self) -> u64 {
+        self.allocated
+    }
+
+    /// Write the buffered output, cut the file to its content and return it.
+    pub fn finish(mut self) -> io::Result<File> {
+        self.truncate_to_content()?;
+        Ok(self.file.take().expect("file present until finish"))
+    }
+
+    fn file(&self) -> &File {
+        self.file.as_ref().expect("file present until finish")
+    }
+
+    fn truncate_to_content(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        if self.allocated != self.len {
+            self.file().set_len(self.len)?;
+            self.allocated = self.len;
+        }
+        Ok(())
+    }
+
+    /// Grow the file by whole extents until `end` fits.
+    fn reserve(&mut self, end: u64) -> io::Result<()> {
+        if end > self.allocated {
+            let extents = (end - self.allocated).div_ceil(self.extent_size);
+            let new_len = self.allocated + extents * self.extent_size;
+            allocate(self.file(), self.allocated, new_len)?;
+            self.allocated = new_len;
+        }
+        Ok(())
+    }
+
+    fn write_at(&mut self, bytes: &[u8], offset: u64) -> io::Result<()> {
+        self.reserve(offset + bytes.len() as u64)?;
+        write_all_at(self.file(), bytes, offset)
+    }
+
+    fn write_buffer(&mut self) -> io::Result<()> {
+        if !self.buf.is_empty() {
+            let buf = std::mem::take(&mut self.buf);
+            let result = self.write_at(&buf, self.buf_start);
+            self.buf = buf;
+            self.buf.clear();
+            result?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for PreallocatedFile {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let buf_end = self.buf_start + self.buf.len() as u64;
+        if self.pos >= self.buf_start && self.pos <= buf_end && !self.buf.is_empty() {
+            // Overwrite and/or extend the buffered output in memory.
+            let at = (self.pos - self.buf_start) as usize;
+            let overlap = bytes.len().min(self.buf.len() - at);
+            self.buf[at..at + overlap].copy_from_slice(&bytes[..overlap]);
+            let rest = &bytes[overlap..];
+            if self.buf.len() + rest.len() <= BUFFER_SIZE {
+                self.buf.extend_from_slice(rest);
+            } else {
+                self.write_buffer()?;
+                let offset = self.pos + overlap as u64;
+                self.write_at(rest, offset)?;
+            }
+        } else {
+            self.write_buffer()?;
+            if bytes.len() < BUFFER_SIZE {
+                self.buf_start = self.pos;
+                self.buf.extend_from_slice(bytes);
+            } else {
+                self.write_at(bytes, self.pos)?;
+            }
+        }
+        self.pos += bytes.len() as u64;
+        self.len = self.len.max(self.pos);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()
+    }
+}
+
+impl Seek for PreallocatedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(x) => Some(x),
+            SeekFrom::End(x) => self.len.checked_add_signed(x),
+            SeekFrom::Current(x) => self.pos.checked_add_signed(x),
+        };
+        self.pos = new_pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek"))?;
+        Ok(self.pos)
+    }
+}
+
+impl Drop for PreallocatedFile {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            let _ = self.truncate_to_content();
+        }
+    }
+}
+
+/// Grow `file` from `len` to `new_len` bytes, allocating the new blocks
+/// where the file system supports it.
+#[cfg(target_os = "linux")]
+fn allocate(file: &File, len: u64, new_len: u64) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let (Ok(offset), Ok(size)) = (libc::off_t::try_from(len), libc::off_t::try_from(new_len - len)) else {
+        // Beyond the `off_t` range of 32-bit targets.
+        return file.set_len(new_len);
+    };
+    // SAFETY: fallocate only takes the descriptor, which `file` keeps open.
+    if unsafe { libc::fallocate(file.as_raw_fd(), 0, offset, size) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        // Sparse extents on file systems without fallocate.
+        Some(libc::EOPNOTSUPP | libc::ENOSYS) => file.set_len(new_len),
+        _ => Err(err),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allocate(file: &File, _len: u64, new_len: u64) -> io::Result<()> {
+    file.set_len(new_len)
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, bytes: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, bytes, offset)
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, mut bytes: &[u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !bytes.is_empty() {
+        let n = file.seek_write(bytes, offset)?;
+        if n == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        bytes = &bytes[n..];
+        offset += n as u64;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn write_all_at(mut file: &File, bytes: &[u8], offset: u64) -> io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(bytes)
+}
@@ -0,0 +1,202 @@
+// Unsorted data groups: several channel groups writing to one data stream
+use super::*;
+use super::data::append_data;
+use crate::blocks::common::BlockHeader;
+use crate::blocks::data_zipped_block::{DataZippedBlock, ZIP_TYPE_DEFLATE};
+
+impl<W: Write + Seek> MdfWriter<W> {
+    /// Handle of the data group holding channel group `cg_id`.
+    pub fn data_group_of(&self, cg_id: &ChannelGroupId) -> Option<DataGroupId> {
+        self.cg_to_dg.get(cg_id).copied()
+    }
+
+    /// Channel groups of `dg_id`, in the order they were added.
+    pub(super) fn data_group_members(&self, dg_id: &DataGroupId) -> Vec<ChannelGroupId> {
+        self.cg_to_dg.iter().filter(|(_, dg)| *dg == dg_id).map(|(cg, _)| cg).collect()
+    }
+
+    /// `cg_record_id` (offset 72 inside the `##CG` block) of `cg_id`.
+    pub(super) fn cg_record_id(&self, cg_id: &ChannelGroupId) -> u64 {
+        let cg_record_id_offset = 72;
+        self.get_block_position(cg_id)
+            .and_then(|pos| self.metadata_blocks.get(&pos))
+            .and_then(|cg| cg.get(cg_record_id_offset..cg_record_id_offset + 8))
+            .map_or(0, |bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Open the data stream of the unsorted data group `dg_id` and a data
+    /// block for each of its channel groups.
+    ///
+    /// Records are then written per channel group with the usual calls
+    /// ([`write_record`](Self::write_record),
+    /// [`write_records`](Self::write_records), ...) and end up in one
+    /// stream in the order of the calls, each led by its group's record ID
+    /// in `record_id_len` (1, 2, 4 or 8) bytes. The channel groups need
+    /// distinct record IDs, see
+    /// [`add_unsorted_channel_group`](Self::add_unsorted_channel_group).
+    ///
+    /// With compression the stream's blocks are deflated without
+    /// transposition, as their records differ in size. Live updates and
+    /// checkpoints do not cover unsorted data groups; their records become
+    /// readable with
+    /// [`finish_unsorted_data_block`](Self::finish_unsorted_data_block).
+    pub fn start_unsorted_data_block(&mut self, dg_id: &DataGroupId, record_id_len: u8) -> Result<(), MdfError> {
+        let cg_ids = self.data_group_members(dg_id);
+        if cg_ids.is_empty() {
+            return Err(MdfError::InvalidWriterState("data group has no channel groups".into()));
+        }
+        if self.unsorted_streams.contains_key(dg_id) || cg_ids.iter().any(|cg| self.open_dts.contains_key(cg)) {
+            return Err(MdfError::InvalidWriterState("data block already open for this data group".into()));
+        }
+        if record_id_len == 0 {
+            return Err(MdfError::InvalidRecordLayout(
+                "records of an unsorted data group need a record ID".into(),
+            ));
+        }
+        if self.live_update.is_some() {
+            return Err(MdfError::UnsupportedFeature(
+                "live updates cannot be combined with unsorted data groups".into(),
+            ));
+        }
+        let mut record_ids: Vec<u64> = cg_ids.iter().map(|cg| self.cg_record_id(cg)).collect();
+        record_ids.sort_unstable();
+        if record_ids.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(MdfError::InvalidRecordLayout(
+                "channel groups of an unsorted data group need distinct record IDs".into(),
+            ));
+        }
+
+        let compression = self.compression;
+        let (dt_id, start_pos) = self.begin_data_block(compression)?;
+        if start_pos != 0 {
+            let dg_data_link_offset = 40;
+            self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        }
+        self.unsorted_streams.insert(
+            *dg_id,
+            UnsortedStream {
+                cg_ids: cg_ids.clone(),
+                dt_id,
+                start_pos,
+                len: 0,
+                data_block_size: self.data_block_size,
+                blocks: Vec::new(),
+                compression,
+                zip_buffer: (compression != CompressionMethod::None).then(Vec::new),
+            },
+        );
+        for cg_id in &cg_ids {
+            if let Err(e) = self.start_data_block_for_cg(cg_id, record_id_len) {
+                for cg_id in &cg_ids {
+                    self.open_dts.remove(cg_id);
+                }
+                self.unsorted_streams.remove(dg_id);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Finish the data blocks of every channel group of the unsorted data
+    /// group `dg_id` and close its data stream, linking it from the data
+    /// group (through a `##DL` block if it spans several data blocks).
+    pub fn finish_unsorted_data_block(&mut self, dg_id: &DataGroupId) -> Result<(), MdfError> {
+        let cg_ids = self
+            .unsorted_streams
+            .get(dg_id)
+            .ok_or_else(|| MdfError::InvalidWriterState("no open data stream for this data group".into()))?
+            .cg_ids
+            .clone();
+        for cg_id in &cg_ids {
+            self.hand_over_unsorted(cg_id)?;
+        }
+        self.close_unsorted_block(dg_id)?;
+        let stream = self.unsorted_streams.remove(dg_id).unwrap();
+        if stream.blocks.len() > 1 {
+            self.write_data_list(&cg_ids[0], &stream.blocks)?;
+        }
+        for cg_id in &cg_ids {
+            let mut dt = self.open_dts.remove(cg_id).unwrap();
+            self.update_block_u64(cg_id, 80, dt.total_record_count)?;
+            self.write_vlsd_payloads(&mut dt)?;
+        }
+        Ok(())
+    }
+
+    /// Move the records buffered by a channel group of an unsorted data
+    /// group to the data group's stream. Returns whether `cg_id` belongs to
+    /// an unsorted data group.
+    pub(super) fn hand_over_unsorted(&mut self, cg_id: &ChannelGroupId) -> Result<bool, MdfError> {
+        let Some(dt) = self.open_dts.get_mut(cg_id) else {
+            return Ok(false);
+        };
+        if !self.unsorted_streams.contains_key(&dt.dg_id) {
+            return Ok(false);
+        }
+        let records = dt.zip_buffer.as_mut().map(std::mem::take).unwrap_or_default();
+        dt.total_record_count += dt.record_count;
+        dt.record_count = 0;
+        let (dg_id, record_size) = (dt.dg_id, dt.record_size);
+
+        let mut rest = records.as_slice();
+        while !rest.is_empty() {
+            let stream = &self.unsorted_streams[&dg_id];
+            // At least one record per block, as for sorted groups.
+            let room = stream.data_block_size.max(record_size).saturating_sub(stream.len) / record_size;
+            if room == 0 {
+                self.close_unsorted_block(&dg_id)?;
+                self.open_unsorted_block(&dg_id)?;
+                continue;
+            }
+            let (chunk, tail) = rest.split_at((room * record_size).min(rest.len()));
+            let stream = self.unsorted_streams.get_mut(&dg_id).unwrap();
+            append_data(&mut self.file, &mut self.offset, &mut stream.zip_buffer, chunk)?;
+            stream.len += chunk.len();
+            rest = tail;
+        }
+        Ok(true)
+    }
+
+    /// Close the current data block of the stream of `dg_id`: patch the
+    /// `##DT` length, or write the buffered records as a `##DZ` block.
+    fn close_unsorted_block(&mut self, dg_id: &DataGroupId) -> Result<(), MdfError> {
+        let stream = self.unsorted_streams.get_mut(dg_id).unwrap();
+        let len = stream.len as u64;
+        let pos = match stream.zip_buffer.as_mut() {
+            None => {
+                let pos = stream.start_pos;
+                self.update_link(pos + 8, 24 + len)?;
+                pos
+            }
+            Some(buffer) => {
+                let data = std::mem::take(buffer);
+                let dt_id = stream.dt_id.clone();
+                let first = stream.blocks.is_empty();
+                let bytes = if data.is_empty() {
+                    BlockHeader { id: "##DT".to_string(), reserved0: 0, block_len: 24, links_nr: 0 }.to_bytes()?
+                } else {
+                    DataZippedBlock::compress("DT", &data, ZIP_TYPE_DEFLATE, 0)?.to_bytes()?
+                };
+                let pos = self.write_block_with_id(&bytes, &dt_id)?;
+                if first {
+                    let dg_data_link_offset = 40;
+                    self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+                }
+                pos
+            }
+        };
+        self.unsorted_streams.get_mut(dg_id).unwrap().blocks.push((pos, len));
+        Ok(())
+    }
+
+    /// Start the next data block of the stream of `dg_id`.
+    fn open_unsorted_block(&mut self, dg_id: &DataGroupId) -> Result<(), MdfError> {
+        let compression = self.unsorted_streams[dg_id].compression;
+        let (dt_id, start_pos) = self.begin_data_block(compression)?;
+        let stream = self.unsorted_streams.get_mut(dg_id).unwrap();
+        stream.dt_id = dt_id;
+        stream.start_pos = start_pos;
+        stream.len = 0;
+        Ok(())
+    }
+}
@@ -13,27 +13,27 @@ use crate::blocks::data_list_block::DataListBlock;
 /// `data.rs`.
 const MAX_SD_BLOCK_SIZE: u64 = 4 * 1024 * 1024;
 
-impl MdfWriter {
+impl<W: Write + Seek> MdfWriter<W> {
     /// Begin recording a ##SD chain for the given VLSD channel.
     ///
     /// VLSD entries appended via [`write_signal_data`] are buffered in memory
     /// until [`finish_signal_data_block`] is called; the actual ##SD blocks
     /// are emitted to disk only on finish, after the parent DT block has
     /// been closed. This keeps the on-disk DT block contiguous.
-    pub fn start_signal_data_block(&mut self, cn_id: &str) -> Result<(), MdfError> {
+    pub fn start_signal_data_block(&mut self, cn_id: &ChannelId) -> Result<(), MdfError> {
         if self.sd_buffers.contains_key(cn_id) {
-            return Err(MdfError::BlockSerializationError(
+            return Err(MdfError::InvalidWriterState(
                 "signal data block already open for this channel".into(),
             ));
         }
-        self.sd_buffers.insert(cn_id.to_string(), Vec::new());
+        self.sd_buffers.insert(*cn_id, Vec::new());
         Ok(())
     }
 
     /// Buffer one VLSD entry for the channel.
-    pub fn write_signal_data(&mut self, cn_id: &str, payload: &[u8]) -> Result<(), MdfError> {
+    pub fn write_signal_data(&mut self, cn_id: &ChannelId, payload: &[u8]) -> Result<(), MdfError> {
         let buf = self.sd_buffers.get_mut(cn_id).ok_or_else(|| {
-            MdfError::BlockSerializationError(
+            MdfError::InvalidWriterState(
                 "no open signal data block for this channel".into(),
             )
         })?;
@@ -48,9 +48,9 @@ impl MdfWriter {
     ///
     /// Must be called after the parent DT block has been finalized so the
     /// emitted ##SD blocks land cleanly after the DT block in the file.
-    pub fn finish_signal_data_block(&mut self, cn_id: &str) -> Result<(), MdfError> {
+    pub fn finish_signal_data_block(&mut self, cn_id: &ChannelId) -> Result<(), MdfError> {
         let buffer = self.sd_buffers.remove(cn_id).ok_or_else(|| {
-            MdfError::BlockSerializationError(
+            MdfError::InvalidWriterState(
                 "no open signal data block for this channel".into(),
             )
         })?;
@@ -102,7 +102,7 @@ impl MdfWriter {
         let mut sd_sizes: Vec<u64> = Vec::with_capacity(fragments.len());
         for fragment in &fragments {
             let block_len = 24u64 + fragment.len() as u64;
-            let sd_id = self.next_sd_id();
+            let sd_id = self.next_block_id("sd");
             let header = BlockHeader {
                 id: "##SD".to_string(),
                 reserved0: 0,
@@ -133,7 +133,7 @@ impl MdfWriter {
                 // section to the concatenated stream.
                 acc = acc.saturating_add(block_len.saturating_sub(24));
             }
-            let dl_id = self.next_dl_id();
+            let dl_id = self.next_block_id("dl");
             let dl_block = DataListBlock::new_variable(sd_positions, virtual_offsets);
             let dl_bytes = dl_block.to_bytes()?;
             let _ = self.write_block_with_id(&dl_bytes, &dl_id)?;
@@ -143,21 +143,4 @@ impl MdfWriter {
         Ok(())
     }
 
-    fn next_sd_id(&self) -> String {
-        let n = self
-            .block_positions
-            .keys()
-            .filter(|k| k.starts_with("sd_"))
-            .count();
-        format!("sd_{}", n)
-    }
-
-    fn next_dl_id(&self) -> String {
-        let n = self
-            .block_positions
-            .keys()
-            .filter(|k| k.starts_with("dl_"))
-            .count();
-        format!("dl_{}", n)
-    }
 }
@@ -4,5 +4,12 @@
 //! guaranteeing little-endian encoding, 8-byte alignment, and zero-padding.
 
 pub mod mdf_writer;
-pub use mdf_writer::MdfWriter;
-pub use mdf_writer::data::ColumnData;
+/// Async writer front end (requires the `async` feature).
+#[cfg(feature = "async")]
+pub mod async_writer;
+pub use mdf_writer::{BlockRef, ChannelGroupId, ChannelId, Compliance, DataGroupId, MdfWriter, WriterConfig};
+#[cfg(not(target_arch = "wasm32"))]
+pub use mdf_writer::{PreallocatedFile, WriterPool};
+pub use mdf_writer::data::{ColumnData, CompressionMethod, LiveUpdate};
+#[cfg(feature = "async")]
+pub use async_writer::AsyncMdfWriter;
@@ -1,7 +1,9 @@
-use mf4_rs::writer::MdfWriter;
+use mf4_rs::writer::{CompressionMethod, MdfWriter, WriterConfig};
 use mf4_rs::api::mdf::MDF;
 use mf4_rs::parsing::decoder::{decode_channel_value, DecodedValue};
 use mf4_rs::blocks::channel_block::ChannelBlock;
+use mf4_rs::blocks::common::BlockParse;
+use mf4_rs::blocks::data_list_block::DataListBlock;
 use mf4_rs::blocks::common::DataType;
 use mf4_rs::error::MdfError;
 
@@ -328,3 +330,996 @@ fn cut_does_not_double_apply_conversions() -> Result<(), MdfError> {
     std::fs::remove_file(output)?;
     Ok(())
 }
+
+#[test]
+fn writer_record_larger_than_dt_cap() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("huge_record_test.mf4");
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+
+    // A single 5 MiB byte-array record does not fit the 4 MiB DT cap, so the
+    // writer must place one record per DT block instead of looping forever.
+    const WIDTH: usize = 5 * 1024 * 1024;
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.bit_count = (WIDTH * 8) as u32;
+        ch.name = Some("Blob".to_string());
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..3u8 {
+        writer.write_record(&cg_id, &[DecodedValue::ByteArray(vec![i; WIDTH])])?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let vals = groups[0].channels()[0].values()?;
+    assert_eq!(vals.len(), 3);
+    for (i, v) in vals.iter().enumerate() {
+        match v {
+            Some(DecodedValue::ByteArray(b)) => {
+                assert_eq!(b.len(), WIDTH);
+                assert!(b.iter().all(|&x| x == i as u8));
+            }
+            other => panic!("unexpected value {:?}", other),
+        }
+    }
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn writer_into_inner_returns_in_memory_file() -> Result<(), MdfError> {
+    let mut writer = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 16;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..5 {
+        writer.write_record(&cg, &[DecodedValue::UnsignedInteger(i)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    let bytes = writer.into_inner()?.into_inner();
+
+    let mdf = MDF::from_bytes(bytes)?;
+    let counter = mdf.channel("Counter").unwrap().values_f64()?;
+    assert_eq!(counter, (0..5).map(|i| Some(i as f64)).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn mdf_from_reader_and_seek_range_reader() -> Result<(), MdfError> {
+    use mf4_rs::index::{MdfIndex, SeekRangeReader};
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    let mut writer = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for v in [1.5, 2.5, 3.5] {
+        writer.write_record(&cg, &[DecodedValue::Float(v)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    let mut source = writer.into_inner()?;
+
+    // The reader is rewound before parsing.
+    source.seek(SeekFrom::End(0))?;
+    let mdf = MDF::from_reader(source.clone())?;
+    assert_eq!(
+        mdf.channel("Speed").unwrap().values_f64()?,
+        vec![Some(1.5), Some(2.5), Some(3.5)]
+    );
+
+    let file_size = source.get_ref().len() as u64;
+    let mut ranges = SeekRangeReader::new(source);
+    let index = MdfIndex::from_range_reader(&mut ranges, file_size)?;
+    assert_eq!(index.open(ranges).values_f64("Speed")?, vec![1.5, 2.5, 3.5]);
+    Ok(())
+}
+
+#[test]
+fn writer_rejects_empty_record_layout() -> Result<(), MdfError> {
+    let mut writer = MdfWriter::new_from_writer(std::io::Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let err = writer.start_data_block_for_cg(&cg_id, 0).unwrap_err();
+    assert!(matches!(err, MdfError::InvalidRecordLayout(_)));
+    Ok(())
+}
+
+#[test]
+fn clone_channel_group_between_writers() -> Result<(), MdfError> {
+    use mf4_rs::blocks::text_block::TextBlock;
+
+    let first = std::env::temp_dir().join("clone_cg_first.mf4");
+    let second = std::env::temp_dir().join("clone_cg_second.mf4");
+
+    let mut writer = MdfWriter::new(first.to_str().unwrap())?;
+    writer.set_config(WriterConfig { keep_metadata: true, ..WriterConfig::default() })?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, "Engine")?;
+    writer.set_channel_group_comment(&cg, "rotated")?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let rpm = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Rpm".into());
+        ch.bit_count = 16;
+    })?;
+    let unit_pos = writer.write_block(&TextBlock::new("1/min").to_bytes()?)?;
+    let rpm_pos = writer.get_block_position(&rpm).unwrap();
+    writer.update_link(rpm_pos + 72, unit_pos)?;
+    let gear = writer.add_channel(&cg, Some(&rpm), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "N"), (1, "First")], "?", Some(&gear))?;
+
+    let mut rotated = MdfWriter::new(second.to_str().unwrap())?;
+    rotated.init_mdf_file()?;
+    let mut plain = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    plain.init_mdf_file()?;
+    let plain_cg = plain.add_channel_group(None, |_| {})?;
+    let err = rotated.clone_channel_group(&plain, &plain_cg).unwrap_err();
+    assert!(matches!(err, MdfError::InvalidWriterState(_)), "{err:?}");
+    let (new_cg, new_channels) = rotated.clone_channel_group(&writer, &cg)?;
+    assert_eq!(new_channels.len(), 3);
+    rotated.start_data_block_for_cg(&new_cg, 0)?;
+    rotated.write_record(&new_cg, &[
+        DecodedValue::Float(0.5),
+        DecodedValue::UnsignedInteger(900),
+        DecodedValue::UnsignedInteger(1),
+    ])?;
+    rotated.finish_data_block(&new_cg)?;
+    rotated.finalize()?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(second.to_str().unwrap())?;
+    let group = mdf.group("Engine").expect("cloned group name");
+    assert_eq!(group.comment()?.as_deref(), Some("rotated"));
+    let channels = group.channels();
+    let names: Vec<_> = channels.iter().map(|c| c.name().unwrap()).collect();
+    assert_eq!(names, [Some("Time".into()), Some("Rpm".into()), Some("Gear".into())]);
+    assert!(channels[0].block().channel_type == 2);
+    assert_eq!(channels[1].unit()?.as_deref(), Some("1/min"));
+    assert_eq!(channels[1].values()?, vec![Some(DecodedValue::UnsignedInteger(900))]);
+    assert_eq!(channels[2].values()?, vec![Some(DecodedValue::String("First".into()))]);
+
+    std::fs::remove_file(first)?;
+    std::fs::remove_file(second)?;
+    Ok(())
+}
+
+#[test]
+fn channel_group_acquisition_name_and_source() -> Result<(), MdfError> {
+    use mf4_rs::parsing::source_info::SourceInfo;
+
+    let path = std::env::temp_dir().join("cg_acq_source.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |cg| {
+        cg.acq_name = Some("CAN1 frames".into());
+        cg.acq_source = Some(SourceInfo {
+            name: Some("EngineECU".into()),
+            path: Some("/vehicle/can1".into()),
+            source_type: 1,
+            bus_type: 2,
+            ..Default::default()
+        });
+    })?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record(&cg, &[DecodedValue::Float(0.0)])?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.name()?.as_deref(), Some("CAN1 frames"));
+    let source = group.source()?.expect("group source");
+    assert_eq!(source, SourceInfo {
+        name: Some("EngineECU".into()),
+        path: Some("/vehicle/can1".into()),
+        comment: None,
+        source_type: 1,
+        bus_type: 2,
+    });
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn channel_source_round_trip() -> Result<(), MdfError> {
+    use mf4_rs::parsing::source_info::SourceInfo;
+
+    let ecu = SourceInfo {
+        name: Some("EngineECU".into()),
+        path: Some("/vehicle/can1/EngineData".into()),
+        comment: Some("0x1A0".into()),
+        source_type: 1,
+        bus_type: 2,
+    };
+    let path = std::env::temp_dir().join("cn_source.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let rpm = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("EngineSpeed".into());
+        ch.bit_count = 16;
+        ch.source = Some(ecu.clone());
+    })?;
+    let temp = writer.add_channel(&cg, Some(&rpm), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("CoolantTemp".into());
+        ch.bit_count = 8;
+    })?;
+    let lin = SourceInfo {
+        name: Some("ThermoModule".into()),
+        source_type: 2,
+        bus_type: 3,
+        ..Default::default()
+    };
+    writer.set_channel_source(&temp, &lin)?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record(&cg, &[
+        DecodedValue::Float(0.0),
+        DecodedValue::UnsignedInteger(800),
+        DecodedValue::UnsignedInteger(90),
+    ])?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.channel("Time").unwrap().source()?, None);
+    assert_eq!(mdf.channel("EngineSpeed").unwrap().source()?, Some(ecu));
+    assert_eq!(mdf.channel("CoolantTemp").unwrap().source()?, Some(lin));
+    assert_eq!(mdf.channel("EngineSpeed").unwrap().values_u64()?, vec![Some(800)]);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn typed_column_accessors() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("typed_columns.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let offset = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Offset".into());
+        ch.bit_count = 32;
+    })?;
+    let gear = writer.add_channel(&cg, Some(&offset), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "N"), (1, "First")], "?", Some(&gear))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..3u64 {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(i as f64 * 0.5),
+            DecodedValue::SignedInteger(i as i64 - 1),
+            DecodedValue::UnsignedInteger(i),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let time = mdf.channel("Time").unwrap();
+    assert_eq!(time.values_f64()?, vec![Some(0.0), Some(0.5), Some(1.0)]);
+    assert_eq!(time.values_i64()?, vec![None, None, None]);
+
+    let offset = mdf.channel("Offset").unwrap();
+    assert_eq!(offset.values_i64()?, vec![Some(-1), Some(0), Some(1)]);
+    assert_eq!(offset.values_f64()?, vec![Some(-1.0), Some(0.0), Some(1.0)]);
+
+    let gear = mdf.channel("Gear").unwrap();
+    assert_eq!(
+        gear.values_string()?,
+        vec![Some("N".to_string()), Some("First".to_string()), Some("?".to_string())]
+    );
+    assert_eq!(gear.values_f64()?, vec![None, None, None]);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn streaming_iterators_match_collected_values() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("streaming_iterators.mf4");
+    // Uncompressed and `##DZ` blocks small enough that the records span several.
+    for compression in [CompressionMethod::None, CompressionMethod::Zlib] {
+        let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+        writer.init_mdf_file()?;
+        writer.set_compression(compression)?;
+        writer.set_data_block_size(256)?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let t = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&t)?;
+        let counter = writer.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".into());
+            ch.bit_count = 16;
+        })?;
+        writer.add_channel(&cg, Some(&counter), |ch| {
+            ch.data_type = DataType::ByteArray;
+            ch.name = Some("Payload".into());
+            ch.bit_count = 64;
+            ch.channel_type = 1;
+            ch.data = 1;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..50u64 {
+            writer.write_record(&cg, &[
+                DecodedValue::Float(i as f64 * 0.01),
+                DecodedValue::UnsignedInteger(i * 3),
+                DecodedValue::ByteArray(vec![i as u8; i as usize % 5 + 1]),
+            ])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()?;
+
+        let mdf = MDF::from_file(path.to_str().unwrap())?;
+        let group = &mdf.channel_groups()[0];
+        let channels = group.channels();
+        let columns = channels
+            .iter()
+            .map(|ch| ch.values())
+            .collect::<Result<Vec<_>, _>>()?;
+        for (channel, column) in channels.iter().zip(&columns) {
+            let streamed = channel.iter_values()?.collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(&streamed, column);
+        }
+
+        let rows = group.iter_records()?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(rows.len(), 50);
+        for (i, row) in rows.iter().enumerate() {
+            let expected: Vec<_> = columns.iter().map(|column| column[i].clone()).collect();
+            assert_eq!(row, &expected);
+        }
+        assert_eq!(rows[7][2], Some(DecodedValue::ByteArray(vec![7; 3])));
+        assert_eq!(rows[49][1], Some(DecodedValue::UnsignedInteger(147)));
+    }
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn values_with_validity_keeps_invalid_samples() -> Result<(), MdfError> {
+    use mf4_rs::index::MdfIndex;
+
+    let path = std::env::temp_dir().join("values_with_validity.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let speed = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    // cn_flags bit 0: every sample of this channel is invalid.
+    writer.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Broken".into());
+        ch.byte_offset = 4;
+        ch.bit_count = 32;
+        ch.flags = 0x01;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for speed in [Some(10), None, Some(30)] {
+        writer.write_record_with_validity(&cg, &[
+            speed.map(DecodedValue::UnsignedInteger),
+            Some(DecodedValue::UnsignedInteger(7)),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let speed = mdf.channel("Speed").unwrap().values_with_validity()?;
+    assert_eq!(speed.iter().map(|v| v.is_valid).collect::<Vec<_>>(), vec![true, false, true]);
+    assert_eq!(speed[0].value, DecodedValue::UnsignedInteger(10));
+    assert_eq!(speed[2].value, DecodedValue::UnsignedInteger(30));
+    let broken = mdf.channel("Broken").unwrap().values_with_validity()?;
+    assert!(broken.iter().all(|v| !v.is_valid && v.value == DecodedValue::UnsignedInteger(7)));
+
+    let index = MdfIndex::from_file(path.to_str().unwrap())?;
+    let mut reader = index.open_file(path.to_str().unwrap())?;
+    assert_eq!(reader.values_with_validity("Speed")?, speed);
+    assert_eq!(reader.values_with_validity("Broken")?, broken);
+    let speed_f64 = reader.values_f64("Speed")?;
+    assert_eq!(speed_f64[0], 10.0);
+    assert!(speed_f64[1].is_nan());
+    assert!(reader.values_f64("Broken")?.iter().all(|v| v.is_nan()));
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn lazy_group_and_channel_lookup() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("lazy_group_lookup.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    // Three groups that each have a `Time` master and a `Value` channel.
+    for (g, name) in ["Engine", "Brakes", "Body"].into_iter().enumerate() {
+        let cg = writer.add_channel_group(None, |cg| cg.acq_name = Some(name.into()))?;
+        let t = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&t)?;
+        writer.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Value".into());
+            ch.byte_offset = 8;
+            ch.bit_count = 32;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        writer.write_record(&cg, &[DecodedValue::Float(0.0), DecodedValue::UnsignedInteger(g as u64)])?;
+        writer.finish_data_block(&cg)?;
+    }
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.iter_channel_groups().count(), 3);
+    let brakes = mdf.channel_group_by_name("Brakes").expect("Brakes group");
+    assert_eq!(brakes.name()?.as_deref(), Some("Brakes"));
+    assert!(mdf.channel_group_by_name("Brake").is_none());
+
+    let value = mdf.find_channel("Body", "Value").expect("Body.Value");
+    assert_eq!(value.values()?, vec![Some(DecodedValue::UnsignedInteger(2))]);
+    assert_eq!(mdf.channel("Value").unwrap().values()?, vec![Some(DecodedValue::UnsignedInteger(0))]);
+    assert!(mdf.find_channel("Body", "Missing").is_none());
+    assert!(mdf.find_channel("Missing", "Value").is_none());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn primitive_column_fast_path() -> Result<(), MdfError> {
+    use mf4_rs::parsing::decoder::{extend_u64_column, PrimitiveLayout, PrimitiveType};
+
+    let path = std::env::temp_dir().join("primitive_columns.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let counter = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 16;
+    })?;
+    let delta = writer.add_channel(&cg, Some(&counter), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Delta".into());
+        ch.bit_count = 8;
+    })?;
+    let gear = writer.add_channel(&cg, Some(&delta), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 32;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "N"), (1, "First")], "?", Some(&gear))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..5u64 {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(i as f64 * 0.5),
+            DecodedValue::UnsignedInteger(60_000 + i),
+            DecodedValue::SignedInteger(i as i64 - 2),
+            DecodedValue::UnsignedInteger(i),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let time = mdf.channel("Time").unwrap();
+    assert_eq!(time.primitive_column_f64()?, Some(vec![0.0, 0.5, 1.0, 1.5, 2.0]));
+    assert_eq!(time.primitive_column_u64()?, None);
+
+    let counter = mdf.channel("Counter").unwrap();
+    let expected: Vec<u64> = (60_000..60_005).collect();
+    assert_eq!(counter.primitive_column_u64()?, Some(expected.clone()));
+    assert_eq!(counter.values_u64()?, expected.iter().copied().map(Some).collect::<Vec<_>>());
+    assert_eq!(counter.values_f64()?[4], Some(60_004.0));
+
+    let delta = mdf.channel("Delta").unwrap();
+    assert_eq!(delta.primitive_column_f64()?, Some(vec![-2.0, -1.0, 0.0, 1.0, 2.0]));
+    assert_eq!(delta.values_u64()?, vec![None, None, Some(0), Some(1), Some(2)]);
+
+    // Conversions take the regular path; values_as_f64 ignores them.
+    let gear = mdf.channel("Gear").unwrap();
+    assert_eq!(gear.primitive_column_u64()?, None);
+    assert_eq!(gear.values_u64()?[1], None);
+    assert_eq!(gear.values_as_f64()?, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+    // The decoder entry points work on any record buffer.
+    let block = counter.block();
+    let layout = PrimitiveLayout::of(block, 0, 19).unwrap();
+    assert_eq!(layout, PrimitiveLayout { offset: 8, ty: PrimitiveType::U16 });
+    assert!(PrimitiveLayout::of(block, 0, 9).is_none());
+    let records: Vec<u8> = [7u16, 9].iter().flat_map(|v| {
+        let mut rec = vec![0u8; 19];
+        rec[8..10].copy_from_slice(&v.to_le_bytes());
+        rec
+    }).collect();
+    let mut out = Vec::new();
+    assert!(extend_u64_column(&records, 19, layout, &mut out));
+    assert_eq!(out, vec![7, 9]);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn master_channel_and_time_lookup() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("master_channel.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let value = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Value".into());
+        ch.bit_count = 16;
+    })?;
+    let t = writer.add_channel(&cg, Some(&value), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let plain = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&plain, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 8;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..4u64 {
+        writer.write_record(&cg, &[DecodedValue::UnsignedInteger(i), DecodedValue::Float(1.0 + i as f64 * 0.5)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let group = &groups[0];
+    assert_eq!(group.master_channel().unwrap().name()?.as_deref(), Some("Time"));
+    assert_eq!(group.time_values()?, Some(vec![1.0, 1.5, 2.0, 2.5]));
+    assert_eq!(group.time_at(2)?, Some(2.0));
+    assert_eq!(group.time_at(4)?, None);
+    assert_eq!(group.record_index_at(0.5)?, None);
+    assert_eq!(group.record_index_at(1.0)?, Some(0));
+    assert_eq!(group.record_index_at(2.2)?, Some(2));
+    assert_eq!(group.record_index_at(10.0)?, Some(3));
+    assert_eq!(group.signal("Value")?.unwrap().timestamps, vec![1.0, 1.5, 2.0, 2.5]);
+
+    assert!(groups[1].master_channel().is_none());
+    assert_eq!(groups[1].time_values()?, None);
+    assert_eq!(groups[1].record_index_at(1.0)?, None);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn writer_linear_and_rational_conversions() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("conversions.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let temp = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 16;
+    })?;
+    // phys = -40 + 0.5 * raw
+    writer.add_linear_conversion(-40.0, 0.5, Some("degC"), Some(&temp))?;
+    let ratio = writer.add_channel(&cg, Some(&temp), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Ratio".into());
+        ch.bit_count = 8;
+    })?;
+    // phys = (2 * raw + 1) / (raw + 1)
+    writer.add_rational_conversion([0.0, 2.0, 1.0, 0.0, 1.0, 1.0], None, Some(&ratio))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..4u64 {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(i as f64),
+            DecodedValue::UnsignedInteger(100 + i),
+            DecodedValue::UnsignedInteger(i),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let report = mf4_rs::validate::validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    let temp = mdf.channel("Temp").unwrap();
+    assert_eq!(temp.unit()?.as_deref(), Some("degC"));
+    assert_eq!(temp.values_f64()?, vec![Some(10.0), Some(10.5), Some(11.0), Some(11.5)]);
+    let ratio = mdf.channel("Ratio").unwrap();
+    assert_eq!(ratio.unit()?, None);
+    assert_eq!(ratio.values_f64()?, vec![Some(1.0), Some(1.5), Some(5.0 / 3.0), Some(1.75)]);
+    Ok(())
+}
+
+#[test]
+fn writer_algebraic_conversion_is_validated() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("algebraic.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let pressure = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Pressure".into());
+        ch.bit_count = 16;
+    })?;
+    assert!(writer.add_algebraic_conversion("X +* 2", None, Some(&pressure)).is_err());
+    assert!(writer.add_algebraic_conversion("Y * 2", None, Some(&pressure)).is_err());
+    writer.add_algebraic_conversion("X * X / 4 + sqrt(X)", Some("bar"), Some(&pressure))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for (i, raw) in [0u64, 4, 16].into_iter().enumerate() {
+        writer.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(raw)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let report = mf4_rs::validate::validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    let pressure = mdf.channel("Pressure").unwrap();
+    assert_eq!(pressure.unit()?.as_deref(), Some("bar"));
+    assert_eq!(pressure.values_f64()?, vec![Some(0.0), Some(6.0), Some(68.0)]);
+    Ok(())
+}
+
+#[test]
+fn values_between_records_and_times() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("paged.mf4");
+    let path = path.to_str().unwrap();
+
+    // ~1 KiB records so the 4 MiB DT cap splits the data into two blocks.
+    const PAD: usize = 1000;
+    const RECORDS: u64 = 6000;
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let counter = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 32;
+    })?;
+    writer.add_channel(&cg, Some(&counter), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Pad".into());
+        ch.bit_count = (PAD * 8) as u32;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for r in 0..RECORDS {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(r as f64 * 0.01),
+            DecodedValue::UnsignedInteger(r),
+            DecodedValue::ByteArray(vec![0; PAD]),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    let group = &mdf.channel_groups()[0];
+    let all = mdf.channel("Counter").unwrap().values()?;
+
+    let page = group.values_between_records(4100, 100)?;
+    assert_eq!(page.len(), 3);
+    assert_eq!(page[1], all[4100..4200]);
+    assert_eq!(group.values_between_records(5990, 100)?[1], all[5990..]);
+    assert!(group.values_between_records(7000, 10)?[1].is_empty());
+
+    let window = group.values_between_times(10.0, 10.5)?.unwrap();
+    assert_eq!(window[1], all[1000..=1050]);
+    assert!(group.values_between_times(100.0, 200.0)?.unwrap()[0].is_empty());
+    Ok(())
+}
+
+#[test]
+fn header_start_time_defaults_to_now_and_round_trips() -> Result<(), MdfError> {
+    let write = |path: &str, start: Option<u64>| -> Result<(), MdfError> {
+        let mut writer = MdfWriter::new(path)?;
+        writer.init_mdf_file()?;
+        if let Some(ns) = start {
+            writer.set_start_time(ns, 60, 60, 0x02, 10)?;
+        }
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".into());
+            ch.bit_count = 8;
+        })?;
+        writer.finalize()
+    };
+    let dir = tempfile::tempdir()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64;
+
+    let default_path = dir.path().join("now.mf4");
+    write(default_path.to_str().unwrap(), None)?;
+    let start = MDF::from_file(default_path.to_str().unwrap())?.start_time().unwrap();
+    assert!(start.abs_time_ns >= now && start.abs_time_ns - now < 60_000_000_000);
+    assert!(!start.is_local_time());
+    assert_eq!(start.utc_offset_min(), None);
+
+    let explicit_path = dir.path().join("explicit.mf4");
+    write(explicit_path.to_str().unwrap(), Some(1_700_000_000_000_000_000))?;
+    let start = MDF::from_file(explicit_path.to_str().unwrap())?.start_time().unwrap();
+    assert_eq!(start.abs_time_ns, 1_700_000_000_000_000_000);
+    assert_eq!(start.utc_offset_min(), Some(120));
+    assert_eq!(start.time_quality, 10);
+    Ok(())
+}
+
+#[test]
+fn writer_stamps_record_ids_of_every_width() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let write = |path: &str, record_id: u64, record_id_len: u8| -> Result<(), MdfError> {
+        let mut writer = MdfWriter::new(path)?;
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |cg| cg.record_id = record_id)?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".into());
+            ch.bit_count = 16;
+        })?;
+        writer.start_data_block_for_cg(&cg, record_id_len)?;
+        for i in 0..3u64 {
+            writer.write_record(&cg, &[DecodedValue::UnsignedInteger(i)])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()
+    };
+
+    for (record_id_len, record_id) in [(1u8, 0xAB_u64), (2, 0xABCD), (4, 0xABCD_EF01), (8, u64::MAX - 1)] {
+        let path = dir.path().join(format!("record_id_{record_id_len}.mf4"));
+        let path = path.to_str().unwrap();
+        write(path, record_id, record_id_len)?;
+        let mdf = MDF::from_file(path)?;
+        assert_eq!(
+            mdf.channel("Counter").unwrap().values_f64()?,
+            vec![Some(0.0), Some(1.0), Some(2.0)]
+        );
+        let group = &mdf.channel_groups()[0];
+        let blocks = group.raw_data_group().data_blocks(group.mmap()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(blocks.len(), 1);
+        for record in blocks[0].data.chunks(record_id_len as usize + 2) {
+            let mut id = [0u8; 8];
+            id[..record_id_len as usize].copy_from_slice(&record[..record_id_len as usize]);
+            assert_eq!(u64::from_le_bytes(id), record_id);
+        }
+    }
+
+    let path = dir.path().join("record_id_bad.mf4");
+    let path = path.to_str().unwrap();
+    assert!(write(path, 1, 3).is_err());
+    assert!(write(path, 0x1_00, 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn data_list_uses_equal_length_only_for_equal_blocks() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let write = |path: &str, records: u64| -> Result<(), MdfError> {
+        let mut writer = MdfWriter::new(path)?;
+        writer.init_mdf_file()?;
+        assert!(writer.set_data_block_size(0).is_err());
+        // 100 bytes hold 12 records of 8 bytes.
+        writer.set_data_block_size(100)?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..records {
+            writer.write_record(&cg, &[DecodedValue::Float(i as f64)])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()
+    };
+    let data_list = |path: &str| -> Result<DataListBlock, MdfError> {
+        let bytes = std::fs::read(path)?;
+        let mdf = MDF::from_file(path)?;
+        let addr = mdf.channel_groups()[0].raw_data_group().block.data_block_addr as usize;
+        DataListBlock::from_bytes(&bytes[addr..])
+    };
+
+    let path = dir.path().join("dl_equal.mf4");
+    let path = path.to_str().unwrap();
+    write(path, 24)?;
+    let dl = data_list(path)?;
+    assert_eq!((dl.flags, dl.data_block_len, dl.data_links.len()), (1, Some(96), 2));
+
+    let path = dir.path().join("dl_variable.mf4");
+    let path = path.to_str().unwrap();
+    write(path, 25)?;
+    let dl = data_list(path)?;
+    assert_eq!(dl.flags, 0);
+    assert_eq!(dl.offsets, Some(vec![0, 96, 192]));
+    let values = MDF::from_file(path)?.channel("Time").unwrap().values_f64()?;
+    assert_eq!(values, (0..25).map(|i| Some(i as f64)).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn errors_are_structured() -> Result<(), MdfError> {
+    use mf4_rs::index::MdfIndex;
+
+    let mut writer = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    let err = writer.write_record(&cg, &[]).unwrap_err();
+    assert!(matches!(err, MdfError::ValueCountMismatch { expected: 1, actual: 0 }), "{err}");
+    writer.write_record(&cg, &[DecodedValue::Float(1.0)])?;
+    writer.finish_data_block(&cg)?;
+    let mut bytes = writer.into_inner()?.into_inner();
+
+    let index = MdfIndex::from_bytes(bytes.clone())?;
+    let err = index.byte_ranges("Missing").unwrap_err();
+    assert!(matches!(&err, MdfError::ChannelNotFound { name, group: None } if name == "Missing"), "{err}");
+    let err = index.byte_ranges_for_records("Speed", 1, 2).unwrap_err();
+    assert!(matches!(err, MdfError::InvalidRecordRange { start: 1, end: 3, total: 1 }), "{err}");
+
+    // The ##HD block at 64 links its first ##DG right after its header.
+    let past_end = bytes.len() as u64 + 100;
+    bytes[88..96].copy_from_slice(&past_end.to_le_bytes());
+    let err = MDF::from_bytes(bytes).unwrap_err();
+    assert!(
+        matches!(&err, MdfError::LinkOutOfBounds { block_id, offset, .. } if block_id == "##DG" && *offset == past_end),
+        "{err}"
+    );
+    Ok(())
+}
+
+#[test]
+fn writer_handles_are_numbered_per_block_type() -> Result<(), MdfError> {
+    let mut writer = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    let cg0 = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg0, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+    })?;
+    // Conversions and other named blocks do not use up channel handles.
+    writer.add_linear_conversion(0.0, 2.0, Some("m"), Some(&t))?;
+    let cg1 = writer.add_channel_group(Some(&cg0), |_| {})?;
+    let v = writer.add_channel(&cg1, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Value".into());
+    })?;
+
+    assert_eq!((cg0.index(), cg1.index()), (0, 1));
+    assert_eq!((t.index(), v.index()), (0, 1));
+    assert_eq!((cg1.to_string(), v.to_string()), ("cg_1".to_string(), "cn_1".to_string()));
+    assert_eq!(writer.channel_group_id(1), Some(cg1));
+    assert_eq!(writer.channel_id(2), None);
+    assert!(writer.get_block_position(&cg1) > writer.get_block_position(&t));
+    assert!(writer.get_block_position("cg_1").is_none());
+    Ok(())
+}
+
+#[test]
+fn writer_accepts_sinks_that_are_not_send() -> Result<(), MdfError> {
+    use std::cell::RefCell;
+    use std::io::{Cursor, Seek, SeekFrom, Write};
+    use std::rc::Rc;
+
+    /// A sink shared with the test through an `Rc`, so it is not `Send`.
+    struct SharedSink(Rc<RefCell<Cursor<Vec<u8>>>>);
+    impl Write for SharedSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    impl Seek for SharedSink {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.borrow_mut().seek(pos)
+        }
+    }
+
+    let bytes = Rc::new(RefCell::new(Cursor::new(Vec::new())));
+    let mut writer = MdfWriter::new_from_writer(SharedSink(bytes.clone()));
+    writer.init_mdf_file()?;
+    writer.finalize()?;
+    assert_eq!(&bytes.borrow().get_ref()[0..8], b"MDF     ");
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Continuing an existing file with `MdfWriter::open_append`.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::validate::validate_file;
+use mf4_rs::writer::{ChannelGroupId, CompressionMethod, MdfWriter};
+
+/// Append `Time`/`Speed` records `from..to` to the group `cg`.
+fn write_speed(w: &mut MdfWriter, cg: &ChannelGroupId, from: u64, to: u64) -> Result<(), MdfError> {
+    w.start_data_block_for_cg(cg, 0)?;
+    for i in from..to {
+        w.write_record(cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(10 * i)])?;
+    }
+    w.finish_data_block(cg)
+}
+
+#[test]
+fn append_across_sessions() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("session.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 16;
+    })?;
+    write_speed(&mut w, &cg, 0, 5)?;
+    w.finalize()?;
+
+    // Second session: continue the existing group and add a new one.
+    let mut w = MdfWriter::open_append(path)?;
+    let speed_cg = w.channel_group_id(0).unwrap();
+    assert!(w.channel_group_id(1).is_none());
+    assert!(w.start_data_block_for_cg(&speed_cg, 1).is_err());
+    w.start_data_block_for_cg(&speed_cg, 0)?;
+    w.write_record(&speed_cg, &[DecodedValue::Float(5.0), DecodedValue::UnsignedInteger(50)])?;
+    // A checkpoint chains the new block after the existing records.
+    w.checkpoint()?;
+    assert_eq!(MDF::from_file(path)?.channel("Speed").unwrap().values_u64()?.len(), 6);
+    for i in 6..8u64 {
+        w.write_record(&speed_cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(10 * i)])?;
+    }
+    w.finish_data_block(&speed_cg)?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let gear = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    assert_eq!((cg.index(), gear.index()), (1, 2));
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(&cg, &[DecodedValue::UnsignedInteger(3)])?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    // Third session: compressed blocks join the same list.
+    let mut w = MdfWriter::open_append(path)?;
+    w.set_compression(CompressionMethod::Zlib)?;
+    let speed_cg = w.channel_group_id(0).unwrap();
+    write_speed(&mut w, &speed_cg, 8, 10)?;
+    w.finalize()?;
+
+    let report = validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].raw_channel_group().block.cycles_nr, 10);
+    let speed = mdf.channel("Speed").unwrap().values_u64()?;
+    assert_eq!(speed, (0..10).map(|i| Some(10 * i)).collect::<Vec<_>>());
+    assert_eq!(mdf.channel("Gear").unwrap().values_u64()?, vec![Some(3)]);
+    Ok(())
+}
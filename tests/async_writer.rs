@@ -0,0 +1,77 @@
+//! AsyncMdfWriter produces the same file as the sync writer it wraps.
+#![cfg(feature = "async")]
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use std::io::{Seek, Write};
+
+use mf4_rs::writer::{AsyncMdfWriter, ChannelGroupId, MdfWriter};
+
+/// Channel group with a `Time` master and a `u32` `Counter`.
+fn configure<W: Write + Seek>(w: &mut MdfWriter<W>) -> Result<ChannelGroupId, MdfError> {
+    w.init_mdf_file()?;
+    // A fixed start time, so both files do not carry the time they were
+    // written at.
+    w.set_start_time(1_700_000_000_000_000_000, 0, 0, 0, 0)?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 32;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    Ok(cg)
+}
+
+fn record(i: u64) -> Vec<DecodedValue> {
+    vec![DecodedValue::Float(i as f64 * 0.01), DecodedValue::UnsignedInteger(i)]
+}
+
+async fn write_async(path: String) -> Result<(), MdfError> {
+    let file = tokio::fs::File::create(&path).await?;
+    // A tiny spool forces many partial flushes, including link patches to
+    // already written blocks.
+    let mut w = AsyncMdfWriter::with_capacity(file, 256);
+    let cg = configure(w.writer_mut())?;
+    for i in 0..500 {
+        w.write_record(&cg, &record(i)).await?;
+    }
+    let batch: Vec<_> = (500..1000).map(record).collect();
+    w.write_records(&cg, batch.iter().map(Vec::as_slice)).await?;
+    w.finish_data_block(&cg).await?;
+    w.finalize().await
+}
+
+#[tokio::test]
+async fn async_writer_matches_sync_writer() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let async_path = dir.path().join("async.mf4").to_str().unwrap().to_string();
+    let sync_path = dir.path().join("sync.mf4").to_str().unwrap().to_string();
+
+    // Spawning requires the writer future to be `Send`.
+    tokio::spawn(write_async(async_path.clone())).await.unwrap()?;
+
+    let mut w = MdfWriter::new(&sync_path)?;
+    let cg = configure(&mut w)?;
+    for i in 0..1000 {
+        w.write_record(&cg, &record(i))?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    assert_eq!(std::fs::read(&async_path)?, std::fs::read(&sync_path)?);
+    let mdf = MDF::from_file(&async_path)?;
+    let counter = mdf.channel("Counter").unwrap().values_f64()?;
+    assert_eq!(counter.len(), 1000);
+    assert_eq!(counter[999], Some(999.0));
+    Ok(())
+}
@@ -4,7 +4,7 @@
 use mf4_rs::api::mdf::MDF;
 use mf4_rs::blocks::common::DataType;
 use mf4_rs::error::MdfError;
-use mf4_rs::writer::MdfWriter;
+use mf4_rs::writer::{ChannelGroupId, MdfWriter};
 
 fn temp_path(name: &str) -> std::path::PathBuf {
     std::env::temp_dir().join(format!("mf4rs_chunked_{}.mf4", name))
@@ -14,7 +14,7 @@ fn cleanup(path: &std::path::Path) {
     let _ = std::fs::remove_file(path);
 }
 
-fn setup_f64_writer(path: &std::path::Path) -> Result<(MdfWriter, String), MdfError> {
+fn setup_f64_writer(path: &std::path::Path) -> Result<(MdfWriter, ChannelGroupId), MdfError> {
     let mut w = MdfWriter::new(path.to_str().unwrap())?;
     w.init_mdf_file()?;
     let cg = w.add_channel_group(None, |_| {})?;
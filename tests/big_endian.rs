@@ -0,0 +1,123 @@
+//! Big-endian channels are encoded in their declared byte order.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, ColumnData, MdfWriter};
+
+/// Group with a little-endian `Time` master followed by big-endian
+/// `U16`, `I32`, `F32`, `F64` and a 12-bit `Packed` field.
+fn be_group(w: &mut MdfWriter<Cursor<Vec<u8>>>) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let mut prev = t;
+    for (name, data_type, bits, bit_offset) in [
+        ("U16", DataType::UnsignedIntegerBE, 16, 0),
+        ("I32", DataType::SignedIntegerBE, 32, 0),
+        ("F32", DataType::FloatBE, 32, 0),
+        ("F64", DataType::FloatBE, 64, 0),
+        ("Packed", DataType::UnsignedIntegerBE, 12, 2),
+    ] {
+        prev = w.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = data_type;
+            ch.name = Some(name.into());
+            ch.bit_count = bits;
+            ch.bit_offset = bit_offset;
+        })?;
+    }
+    Ok(cg)
+}
+
+fn check_values(mdf: &MDF) -> Result<(), MdfError> {
+    assert_eq!(mdf.channel("U16").unwrap().values_u64()?, vec![Some(0x1234), Some(0xFFFF)]);
+    assert_eq!(
+        mdf.channel("I32").unwrap().values()?,
+        vec![Some(DecodedValue::SignedInteger(-2)), Some(DecodedValue::SignedInteger(0x0102_0304))]
+    );
+    assert_eq!(mdf.channel("F32").unwrap().values_f64()?, vec![Some(1.5), Some(-0.25)]);
+    assert_eq!(mdf.channel("F64").unwrap().values_f64()?, vec![Some(1e-3), Some(42.0)]);
+    assert_eq!(mdf.channel("Packed").unwrap().values_u64()?, vec![Some(0xABC), Some(0x001)]);
+    Ok(())
+}
+
+#[test]
+fn big_endian_channels_round_trip_through_write_record() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = be_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(
+        &cg,
+        &[
+            DecodedValue::Float(0.0),
+            DecodedValue::UnsignedInteger(0x1234),
+            DecodedValue::SignedInteger(-2),
+            DecodedValue::Float(1.5),
+            DecodedValue::Float(1e-3),
+            DecodedValue::UnsignedInteger(0xABC),
+        ],
+    )?;
+    w.write_record(
+        &cg,
+        &[
+            DecodedValue::Float(1.0),
+            DecodedValue::UnsignedInteger(0xFFFF),
+            DecodedValue::SignedInteger(0x0102_0304),
+            DecodedValue::Float(-0.25),
+            DecodedValue::Float(42.0),
+            DecodedValue::UnsignedInteger(0x001),
+        ],
+    )?;
+    w.finish_data_block(&cg)?;
+
+    let bytes = w.into_inner()?.into_inner();
+    // The first record's `U16` follows the 8-byte master, most significant byte first.
+    let record = bytes.windows(10).position(|win| win == [0, 0, 0, 0, 0, 0, 0, 0, 0x12, 0x34]);
+    assert!(record.is_some(), "big-endian U16 not found after the master");
+    check_values(&MDF::from_bytes(bytes)?)
+}
+
+#[test]
+fn big_endian_channels_round_trip_through_write_columns() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = be_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_columns(
+        &cg,
+        &[
+            ColumnData::F64(&[0.0, 1.0]),
+            ColumnData::U64(&[0x1234, 0xFFFF]),
+            ColumnData::I64(&[-2, 0x0102_0304]),
+            ColumnData::F32(&[1.5, -0.25]),
+            ColumnData::F64(&[1e-3, 42.0]),
+            ColumnData::U64(&[0xABC, 0x001]),
+        ],
+    )?;
+    w.finish_data_block(&cg)?;
+
+    check_values(&MDF::from_bytes(w.into_inner()?.into_inner())?)
+}
+
+#[test]
+fn unsupported_data_types_are_rejected() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::ComplexLE;
+        ch.name = Some("Complex".into());
+        ch.bit_count = 128;
+    })?;
+    let err = w.start_data_block_for_cg(&cg, 0).unwrap_err();
+    assert!(err.to_string().contains("'Complex'"), "{err}");
+    Ok(())
+}
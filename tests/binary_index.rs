@@ -0,0 +1,84 @@
+//! Binary index format: round trip against JSON and header checks.
+#![cfg(feature = "binary-index")]
+
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::{IndexOptions, MdfIndex, BINARY_INDEX_MAGIC, BINARY_INDEX_VERSION};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn write_file(path: &str) -> Result<(), MdfError> {
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, "Vehicle")?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let mut prev = time;
+    for i in 0..50 {
+        prev = writer.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(format!("Signal_{i}"));
+            ch.bit_count = 16;
+        })?;
+        writer.add_linear_conversion(i as f64, 0.5, Some("V"), Some(&prev))?;
+    }
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for r in 0..20u64 {
+        let mut row = vec![DecodedValue::Float(r as f64)];
+        row.extend((0..50).map(|i| DecodedValue::UnsignedInteger(r * i)));
+        writer.write_record(&cg, &row)?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()
+}
+
+#[test]
+fn binary_index_round_trips_and_is_smaller() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let mdf = dir.path().join("binary_index.mf4");
+    let mdf = mdf.to_str().unwrap();
+    write_file(mdf)?;
+
+    let index = MdfIndex::from_file_with_options(mdf, IndexOptions { hash_data_blocks: true })?;
+    let bin_path = dir.path().join("index.bin");
+    let bin_path = bin_path.to_str().unwrap();
+    index.save_to_file_binary(bin_path)?;
+    let loaded = MdfIndex::load_from_file_binary(bin_path)?;
+
+    assert_eq!(loaded.to_json()?, index.to_json()?);
+    assert!(index.to_binary()?.len() < index.to_json()?.len() / 2);
+
+    let mut loaded = loaded;
+    loaded.set_file(mdf);
+    let values = loaded.read("Signal_3")?.values;
+    assert_eq!(values.len(), 20);
+    assert_eq!(values[2], Some(DecodedValue::Float(6.0)));
+    Ok(())
+}
+
+#[test]
+fn binary_index_rejects_foreign_data_and_other_versions() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let mdf = dir.path().join("binary_index_header.mf4");
+    let mdf = mdf.to_str().unwrap();
+    write_file(mdf)?;
+    let bytes = MdfIndex::from_file(mdf)?.to_binary()?;
+    assert!(bytes.starts_with(BINARY_INDEX_MAGIC));
+
+    assert!(MdfIndex::from_binary(b"{\"file_size\": 1}").is_err());
+    assert!(MdfIndex::from_binary(&bytes[..4]).is_err());
+
+    let mut newer = bytes.clone();
+    let at = BINARY_INDEX_MAGIC.len();
+    newer[at..at + 4].copy_from_slice(&(BINARY_INDEX_VERSION + 1).to_le_bytes());
+    match MdfIndex::from_binary(&newer) {
+        Err(MdfError::UnsupportedFeature(msg)) => assert!(msg.contains("version")),
+        other => panic!("expected a version error, got {other:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Generic block graph traversal with `blocks::walk` and typed `parse_at`.
+
+use std::collections::BTreeSet;
+
+use mf4_rs::block_layout::FileLayout;
+use mf4_rs::blocks;
+use mf4_rs::blocks::channel_block::ChannelBlock;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::blocks::walk::{links_at, parse_at};
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// A file with a time master, a converted channel with a unit, and data.
+fn sample() -> Result<Vec<u8>, MdfError> {
+    let mut w = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&cg, "Engine")?;
+    let time = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let temp = w.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_linear_conversion(-40.0, 0.5, Some("degC"), Some(&temp))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(100 + i)])?;
+    }
+    w.finish_data_block(&cg)?;
+    Ok(w.into_inner()?.into_inner())
+}
+
+#[test]
+fn walk_visits_every_linked_block_once() -> Result<(), MdfError> {
+    let data = sample()?;
+    let blocks: Vec<_> = blocks::walk(&data).collect::<Result<_, _>>()?;
+    assert_eq!(blocks[0].0, 64);
+    assert_eq!(blocks[0].1.id, "##HD");
+
+    let offsets: BTreeSet<u64> = blocks.iter().map(|(offset, _)| *offset).collect();
+    assert_eq!(offsets.len(), blocks.len());
+    let layout = FileLayout::from_bytes(&data)?;
+    let expected: BTreeSet<u64> =
+        layout.blocks.iter().filter(|b| b.block_type != "##ID").map(|b| b.offset).collect();
+    assert_eq!(offsets, expected);
+    for id in ["##DG", "##CG", "##CN", "##CC", "##TX", "##DT"] {
+        assert!(blocks.iter().any(|(_, h)| h.id == id), "{id}");
+    }
+
+    // Channel names through the typed helpers.
+    let mut names = Vec::new();
+    for (offset, header) in blocks.iter().filter(|(_, h)| h.id == "##CN") {
+        let channel: ChannelBlock = parse_at(&data, *offset)?;
+        assert_eq!(links_at(&data, *offset)?.len() as u64, header.links_nr);
+        assert_eq!(links_at(&data, *offset)?[2], channel.name_addr);
+        names.push(parse_at::<TextBlock>(&data, channel.name_addr)?.text);
+    }
+    assert_eq!(names, ["Time", "Temp"]);
+
+    let err = parse_at::<ChannelBlock>(&data, 64).unwrap_err();
+    assert!(matches!(err, MdfError::BlockIDError { .. }), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn walk_reports_broken_links_and_continues() -> Result<(), MdfError> {
+    let mut data = sample()?;
+    let blocks: Vec<_> = blocks::walk(&data).collect::<Result<_, _>>()?;
+    let (cn, _) = blocks.iter().find(|(_, h)| h.id == "##CN").unwrap();
+    // Point the first channel's comment link (link 7) past the end.
+    let at = (*cn + 24 + 8 * 7) as usize;
+    let past_end = data.len() as u64 + 8;
+    data[at..at + 8].copy_from_slice(&past_end.to_le_bytes());
+
+    let visited: Vec<_> = blocks::walk(&data).collect();
+    let errors: Vec<_> = visited.iter().filter_map(|v| v.as_ref().err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], MdfError::LinkOutOfBounds { .. }), "{:?}", errors[0]);
+    // The data group's other blocks are still visited.
+    assert!(visited.iter().flatten().any(|(_, h)| h.id == "##DT"));
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Reading files without a memory map through `MDF::open_with`.
+
+use mf4_rs::api::mdf::{OpenOptions, MDF};
+use mf4_rs::blocks;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::event_block::EventType;
+use mf4_rs::error::MdfError;
+use mf4_rs::metadata::HeaderMetadata;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+const RECORDS: u64 = 2_000;
+
+/// A file with a `Fast` group split over many small data blocks (or `##DZ`
+/// blocks with `compression`), a `Log` group with a VLSD byte channel, a
+/// header comment and an event.
+fn write_file(name: &str, compression: CompressionMethod) -> Result<String, MdfError> {
+    let path = std::env::temp_dir().join(name).to_str().unwrap().to_string();
+    let mut w = MdfWriter::new(&path)?;
+    w.init_mdf_file()?;
+    w.set_compression(compression)?;
+    w.set_data_block_size(1024)?;
+    w.set_header_metadata(&HeaderMetadata { comment: Some("bench run".into()), ..Default::default() })?;
+
+    let fast = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&fast, "Fast")?;
+    let time = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let counter = w.add_channel(&fast, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 32;
+    })?;
+    w.add_linear_conversion(1.0, 0.5, Some("rpm"), Some(&counter))?;
+    w.start_data_block_for_cg(&fast, 0)?;
+    for i in 0..RECORDS {
+        w.write_record(&fast, &[DecodedValue::Float(i as f64 * 0.01), DecodedValue::UnsignedInteger(i % 1000)])?;
+    }
+    w.finish_data_block(&fast)?;
+    w.add_event("halfway", 10.0, EventType::Marker, &[(&fast).into()])?;
+
+    w.set_compression(CompressionMethod::None)?;
+    let log = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&log, "Log")?;
+    let log_time = w.add_channel(&log, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("LogTime".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&log_time)?;
+    let payload = w.add_channel(&log, Some(&log_time), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Payload".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+    })?;
+    w.start_data_block_for_cg_raw(&log, 0, 16, 0)?;
+    w.start_signal_data_block(&payload)?;
+    for i in 0..20u64 {
+        let mut record = (i as f64).to_le_bytes().to_vec();
+        record.extend_from_slice(&[0; 8]);
+        w.write_raw_record(&log, &record)?;
+        w.write_signal_data(&payload, format!("message {i}").as_bytes())?;
+    }
+    w.finish_signal_data_block(&payload)?;
+    w.finish_data_block(&log)?;
+    w.finalize()?;
+    Ok(path)
+}
+
+/// Every channel's values, by group and channel name.
+fn all_values(mdf: &MDF) -> Result<Vec<(String, Vec<Option<DecodedValue>>)>, MdfError> {
+    let mut out = Vec::new();
+    for group in mdf.channel_groups() {
+        for channel in group.channels() {
+            let name = format!("{}/{}", group.name()?.unwrap_or_default(), channel.name()?.unwrap_or_default());
+            out.push((name, channel.values()?));
+        }
+    }
+    Ok(out)
+}
+
+#[test]
+fn buffered_reads_match_mapped_reads() -> Result<(), MdfError> {
+    for (name, compression) in [
+        ("buffered_read_plain.mf4", CompressionMethod::None),
+        ("buffered_read_zlib.mf4", CompressionMethod::Zlib),
+    ] {
+        let path = write_file(name, compression)?;
+        let mapped = MDF::from_file(&path)?;
+        let expected = all_values(&mapped)?;
+        assert_eq!(expected.len(), 4);
+        assert_eq!(expected[1].1.len(), RECORDS as usize);
+        assert_eq!(expected[1].1[3], Some(DecodedValue::Float(2.5)));
+        assert_eq!(expected[3].1[2], Some(DecodedValue::ByteArray(b"message 2".to_vec())));
+
+        // A one-byte buffer reads every block separately.
+        for buffer_size in [1, 64 * 1024] {
+            let buffered = MDF::open_with(&path, OpenOptions { mmap: false, buffer_size })?;
+            assert_eq!(all_values(&buffered)?, expected, "{name}, buffer of {buffer_size} bytes");
+            assert_eq!(buffered.header_metadata()?.unwrap().comment.as_deref(), Some("bench run"));
+            let events = buffered.events()?;
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].name.as_deref(), Some("halfway"));
+            assert_eq!(
+                buffered.signal("Counter")?.unwrap().timestamps,
+                mapped.signal("Counter")?.unwrap().timestamps
+            );
+        }
+        // The default options map the file.
+        assert_eq!(all_values(&MDF::open_with(&path, OpenOptions::default())?)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn data_blocks_are_read_on_every_access() -> Result<(), MdfError> {
+    let path = write_file("buffered_read_lazy.mf4", CompressionMethod::None)?;
+    let mdf = MDF::open_with(&path, OpenOptions { mmap: false, ..OpenOptions::default() })?;
+    assert_eq!(mdf.channel("Counter").unwrap().values()?[3], Some(DecodedValue::Float(2.5)));
+    // Zero the records in the file: the metadata is already in memory, the
+    // records are not, not even after they have been decoded once.
+    let mut bytes = std::fs::read(&path)?;
+    for (offset, header) in blocks::walk(&bytes.clone()).collect::<Result<Vec<_>, _>>()? {
+        if header.id == "##DT" {
+            bytes[offset as usize + 24..(offset + header.block_len) as usize].fill(0);
+        }
+    }
+    std::fs::write(&path, bytes)?;
+    let counter = mdf.channel("Counter").unwrap();
+    assert_eq!(counter.unit()?.as_deref(), Some("rpm"));
+    let values = counter.values()?;
+    assert_eq!(values.len(), RECORDS as usize);
+    assert!(values.iter().all(|v| *v == Some(DecodedValue::Float(1.0))));
+    Ok(())
+}
+
+#[test]
+fn unfinalized_files_need_a_memory_map() -> Result<(), MdfError> {
+    let path = write_file("buffered_read_unfinalized.mf4", CompressionMethod::None)?;
+    let mut bytes = std::fs::read(&path)?;
+    bytes[..8].copy_from_slice(b"UnFinMDF");
+    std::fs::write(&path, bytes)?;
+    let err = MDF::open_with(&path, OpenOptions { mmap: false, ..OpenOptions::default() }).unwrap_err();
+    assert!(matches!(err, MdfError::UnsupportedFeature(_)), "{err:?}");
+    assert_eq!(MDF::from_file(&path)?.channel("Counter").unwrap().values()?.len(), RECORDS as usize);
+    Ok(())
+}
+
+#[test]
+fn broken_links_are_reported() -> Result<(), MdfError> {
+    let path = write_file("buffered_read_broken.mf4", CompressionMethod::None)?;
+    let mut bytes = std::fs::read(&path)?;
+    // Point the header's first data group link (link 0 of ##HD) past the end.
+    let past_end = bytes.len() as u64 + 64;
+    bytes[64 + 24..64 + 32].copy_from_slice(&past_end.to_le_bytes());
+    let broken = std::env::temp_dir().join("buffered_read_broken_link.mf4");
+    std::fs::write(&broken, bytes)?;
+    let err = MDF::open_with(broken.to_str().unwrap(), OpenOptions { mmap: false, ..OpenOptions::default() })
+        .unwrap_err();
+    assert!(matches!(err, MdfError::LinkOutOfBounds { .. }), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! CAN, LIN and FlexRay bus logging groups laid out per the ASAM MDF bus
+//! logging standard.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::bus_logging::{
+    CanFrame, FlexRayFrame, LinFrame, BUS_TYPE_CAN, BUS_TYPE_FLEXRAY, BUS_TYPE_LIN, SOURCE_TYPE_BUS,
+};
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn frames() -> Vec<CanFrame> {
+    vec![
+        CanFrame {
+            timestamp: 0.001,
+            id: 0x123,
+            dlc: 8,
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+            ..Default::default()
+        },
+        CanFrame {
+            timestamp: 0.002,
+            id: 0x18FF_0A01,
+            extended: true,
+            dlc: 2,
+            data: vec![0xAA, 0x55],
+            tx: true,
+            ..Default::default()
+        },
+        CanFrame {
+            timestamp: 0.003,
+            id: 0x7FF,
+            dlc: 9,
+            data: (0..12).collect(),
+            edl: true,
+            brs: true,
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn can_frames_roundtrip() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("bus_logging_can.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let data = w.add_can_frame_group(1)?;
+    w.start_data_block_for_cg(&data, 0)?;
+    for frame in frames() {
+        w.write_can_frame(&data, &frame)?;
+    }
+    w.finish_data_block(&data)?;
+    let errors = w.add_can_error_frame_group(1)?;
+    w.start_data_block_for_cg(&errors, 0)?;
+    w.write_can_frame(&errors, &CanFrame {
+        timestamp: 0.0025,
+        id: 0x100,
+        error_type: Some(4),
+        ..Default::default()
+    })?;
+    w.finish_data_block(&errors)?;
+    w.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    let group = mdf.group("CAN_DataFrame").unwrap();
+    let source = group.source()?.unwrap();
+    assert_eq!(source.name.as_deref(), Some("CAN1"));
+    assert_eq!((source.source_type, source.bus_type), (SOURCE_TYPE_BUS, BUS_TYPE_CAN));
+
+    let expected: Vec<_> = frames()
+        .into_iter()
+        .map(|f| CanFrame { bus_channel: 1, ..f })
+        .collect();
+    assert_eq!(group.can_frames()?, Some(expected));
+    // The frame channel is a plain byte array to generic readers.
+    assert!(matches!(
+        group.channel("CAN_DataFrame").unwrap().values()?[0],
+        Some(DecodedValue::ByteArray(_))
+    ));
+
+    let errors = mdf.group("CAN_ErrorFrame").unwrap().can_frames()?.unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!((errors[0].id, errors[0].error_type), (0x100, Some(4)));
+
+    assert_eq!(mdf.group("CAN_DataFrame").unwrap().channel("Timestamp").unwrap().values_f64()?.len(), 3);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn frame_members_are_components_of_the_frame_channel() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("bus_logging_components.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let data = w.add_can_frame_group(2)?;
+    w.start_data_block_for_cg(&data, 0)?;
+    for frame in frames() {
+        w.write_can_frame(&data, &frame)?;
+    }
+    w.finish_data_block(&data)?;
+    w.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    let group = mdf.group("CAN_DataFrame").unwrap();
+    let frame = group.channel("CAN_DataFrame").unwrap();
+    assert!(frame.is_structure());
+    let names = frame
+        .components()
+        .iter()
+        .map(|member| member.name().map(Option::unwrap_or_default))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(&names[..2], ["CAN_DataFrame.BusChannel", "CAN_DataFrame.ID"]);
+    assert!(names.contains(&"CAN_DataFrame.DataBytes".to_string()));
+
+    let ids = frame.component("CAN_DataFrame.ID").unwrap().values_u64()?;
+    assert_eq!(ids, frames().iter().map(|f| Some(f.id as u64)).collect::<Vec<_>>());
+    let bus = frame.component("CAN_DataFrame.BusChannel").unwrap();
+    assert!(!bus.is_structure() && bus.components().is_empty());
+    assert_eq!(bus.values_u64()?, vec![Some(2); 3]);
+    // Members are only reachable through their structure.
+    assert!(group.channel("CAN_DataFrame.ID").is_none());
+    assert!(!group.channel("Timestamp").unwrap().is_structure());
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn non_bus_groups_have_no_can_frames() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("bus_logging_plain.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| ch.name = Some("Speed".into()))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(&cg, &[DecodedValue::UnsignedInteger(1)])?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    assert_eq!(mdf.channel_groups()[0].can_frames()?, None);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn lin_and_flexray_frames_roundtrip() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("bus_logging_lin_flexray.mf4");
+    let path = path.to_str().unwrap();
+    let lin_frames = vec![
+        LinFrame { timestamp: 0.01, id: 0x21, data: vec![1, 2, 3, 4], checksum: 0x5A, ..Default::default() },
+        LinFrame { timestamp: 0.02, id: 0x3C, data: vec![0xFF; 8], checksum: 0x01, tx: true, ..Default::default() },
+    ];
+    let fr_frames = vec![
+        FlexRayFrame {
+            timestamp: 0.005,
+            id: 0x7FF,
+            cycle: 63,
+            data: (0..254).map(|i| i as u8).collect(),
+            header_crc: 0x4D3,
+            ..Default::default()
+        },
+        FlexRayFrame { timestamp: 0.006, channel_b: true, id: 12, data: vec![9; 16], tx: true, ..Default::default() },
+    ];
+
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let lin = w.add_lin_frame_group(2)?;
+    w.start_data_block_for_cg(&lin, 0)?;
+    for frame in &lin_frames {
+        w.write_lin_frame(&lin, frame)?;
+    }
+    assert!(w.write_can_frame(&lin, &CanFrame::default()).is_err());
+    w.finish_data_block(&lin)?;
+    let fr = w.add_flexray_frame_group(1)?;
+    w.start_data_block_for_cg(&fr, 0)?;
+    for frame in &fr_frames {
+        w.write_flexray_frame(&fr, frame)?;
+    }
+    assert!(w.write_flexray_frame(&fr, &FlexRayFrame { data: vec![0; 255], ..Default::default() }).is_err());
+    w.finish_data_block(&fr)?;
+    w.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    let group = mdf.group("LIN_Frame").unwrap();
+    let source = group.source()?.unwrap();
+    assert_eq!(source.name.as_deref(), Some("LIN2"));
+    assert_eq!((source.source_type, source.bus_type), (SOURCE_TYPE_BUS, BUS_TYPE_LIN));
+    let expected: Vec<_> = lin_frames.into_iter().map(|f| LinFrame { bus_channel: 2, ..f }).collect();
+    assert_eq!(group.lin_frames()?, Some(expected));
+    assert_eq!(group.can_frames()?, None);
+
+    let group = mdf.group("FR_Frame").unwrap();
+    let source = group.source()?.unwrap();
+    assert_eq!(source.name.as_deref(), Some("FlexRay1"));
+    assert_eq!(source.bus_type, BUS_TYPE_FLEXRAY);
+    let expected: Vec<_> = fr_frames.into_iter().map(|f| FlexRayFrame { bus_channel: 1, ..f }).collect();
+    assert_eq!(group.flexray_frames()?, Some(expected));
+    assert_eq!(group.lin_frames()?, None);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
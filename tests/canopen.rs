@@ -0,0 +1,87 @@
+//! CANopen `DATE` and `TIME` channels decode to calendar values and are
+//! written back bit-exactly.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::canopen::{CanOpenDate, CanOpenTime};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// 2024-05-01T12:30:15.250, a Wednesday, in summer time.
+const DATE: CanOpenDate = CanOpenDate {
+    ms: 15_250,
+    minute: 30,
+    hour: 12,
+    summer_time: true,
+    day: 1,
+    day_of_week: 3,
+    month: 5,
+    year: 24,
+};
+/// The same instant as a CANopen `TIME`.
+const TIME: CanOpenTime = CanOpenTime { ms: 45_015_250, days: 14_731 };
+const UNIX_NANOS: i64 = 1_714_566_615_250_000_000;
+
+#[test]
+fn structures_match_the_canopen_layout() {
+    let bytes = DATE.to_bytes();
+    assert_eq!(bytes, [0x92, 0x3B, 30, 0x80 | 12, 3 << 5 | 1, 5, 24]);
+    assert_eq!(CanOpenDate::from_bytes(&bytes), DATE);
+    assert_eq!(DATE.unix_nanos(), UNIX_NANOS);
+    assert_eq!(DATE.to_string(), "2024-05-01T12:30:15.250");
+
+    let bytes = TIME.to_bytes();
+    assert_eq!(CanOpenTime::from_bytes(&bytes), TIME);
+    assert_eq!(TIME.unix_nanos(), UNIX_NANOS);
+    assert_eq!(TIME.to_string(), "2024-05-01T12:30:15.250");
+}
+
+#[test]
+fn canopen_channels_round_trip() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let date = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::CanOpenDate;
+        ch.name = Some("Date".into());
+    })?;
+    w.add_channel(&cg, Some(&date), |ch| {
+        ch.data_type = DataType::CanOpenTime;
+        ch.name = Some("TimeOfDay".into());
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(
+        &cg,
+        &[DecodedValue::Float(0.0), DecodedValue::CanOpenDate(DATE), DecodedValue::CanOpenTime(TIME)],
+    )?;
+    w.finish_data_block(&cg)?;
+
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+    assert_eq!(mdf.channel("Date").unwrap().values()?, vec![Some(DecodedValue::CanOpenDate(DATE))]);
+    assert_eq!(mdf.channel("TimeOfDay").unwrap().values()?, vec![Some(DecodedValue::CanOpenTime(TIME))]);
+    Ok(())
+}
+
+#[test]
+fn canopen_channels_need_their_fixed_size() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::CanOpenTime;
+        ch.name = Some("TimeOfDay".into());
+        ch.bit_count = 64;
+    })?;
+    let err = w.start_data_block_for_cg(&cg, 0).unwrap_err();
+    assert!(err.to_string().contains("bit_count 48"), "{err}");
+    Ok(())
+}
@@ -0,0 +1,125 @@
+//! Array channels: a `##CA` block linked from the channel's composition link
+//! describes the dimensions of a matrix sample stored in one record.
+
+use mf4_rs::api::channel::NestedValue;
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::channel_array_block::{
+    ChannelArrayBlock, CA_FLAG_AXIS, CA_FLAG_FIXED_AXES, CA_FLAG_INVERSE_LAYOUT,
+};
+use mf4_rs::blocks::common::{BlockHeader, BlockParse, DataType};
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// Write a group with a `Time` master and a 2×3 `f32` array channel `Map`
+/// whose element `[i][j]` of record `r` is `r * 100 + i * 10 + j`.
+fn write_array_file(path: &str, flags: u32) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let map = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Map".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 32;
+    })?;
+
+    let ca = ChannelArrayBlock {
+        header: BlockHeader {
+            id: "##CA".into(),
+            reserved0: 0,
+            block_len: 24 + 8 + 16 + 2 * 8,
+            links_nr: 1,
+        },
+        flags,
+        byte_offset_base: 4,
+        dim_sizes: vec![2, 3],
+        ..Default::default()
+    };
+    w.write_block_with_id(&ca.to_bytes()?, "ca_map")?;
+    // cn_composition is the second link of the ##CN block.
+    w.update_block_link(&map, 32, "ca_map")?;
+
+    w.start_data_block_for_cg_raw(&cg, 0, 8 + 6 * 4, 0)?;
+    for r in 0..4u32 {
+        let mut record = (r as f64 * 0.1).to_le_bytes().to_vec();
+        let mut elements = [0f32; 6];
+        for i in 0..2 {
+            for j in 0..3 {
+                let slot = if flags & CA_FLAG_INVERSE_LAYOUT != 0 { j * 2 + i } else { i * 3 + j };
+                elements[slot] = (r * 100 + i as u32 * 10 + j as u32) as f32;
+            }
+        }
+        for e in elements {
+            record.extend_from_slice(&e.to_le_bytes());
+        }
+        w.write_raw_record(&cg, &record)?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+#[test]
+fn array_channel_values() -> Result<(), MdfError> {
+    for (name, flags) in [("row", 0), ("column", CA_FLAG_INVERSE_LAYOUT)] {
+        let path = std::env::temp_dir().join(format!("channel_array_{name}.mf4"));
+        let path = path.to_str().unwrap();
+        write_array_file(path, flags)?;
+
+        let mdf = MDF::from_file(path)?;
+        let map = mdf.channel("Map").unwrap();
+        assert_eq!(map.array_shape()?, Some(vec![2, 3]));
+        assert_eq!(mdf.channel("Time").unwrap().array_shape()?, None);
+
+        let samples = map.array_values()?;
+        assert_eq!(samples.len(), 4);
+        let expected: Vec<_> = [300.0, 301.0, 302.0, 310.0, 311.0, 312.0]
+            .into_iter()
+            .map(|v| Some(DecodedValue::Float(v)))
+            .collect();
+        assert_eq!(samples[3].values, expected, "{name} layout");
+        assert_eq!(samples[2].get(&[1, 2]), Some(&DecodedValue::Float(212.0)));
+        assert_eq!(samples[2].get(&[2, 0]), None);
+
+        let scalar = |v: f64| NestedValue::Scalar(Some(DecodedValue::Float(v)));
+        assert_eq!(
+            samples[1].to_nested(),
+            NestedValue::Array(vec![
+                NestedValue::Array(vec![scalar(100.0), scalar(101.0), scalar(102.0)]),
+                NestedValue::Array(vec![scalar(110.0), scalar(111.0), scalar(112.0)]),
+            ])
+        );
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn channel_array_block_fixed_axes_roundtrip() -> Result<(), MdfError> {
+    let ca = ChannelArrayBlock {
+        header: BlockHeader {
+            id: "##CA".into(),
+            reserved0: 0,
+            block_len: 24 + 8 + 16 + (2 + 2 + 3) * 8,
+            links_nr: 1,
+        },
+        array_type: 2,
+        flags: CA_FLAG_AXIS | CA_FLAG_FIXED_AXES,
+        byte_offset_base: 8,
+        dim_sizes: vec![2, 3],
+        axis_values: vec![vec![1000.0, 2000.0], vec![0.0, 0.5, 1.0]],
+        ..Default::default()
+    };
+    let parsed = ChannelArrayBlock::from_bytes(&ca.to_bytes()?)?;
+    assert_eq!(parsed.array_type, 2);
+    assert_eq!(parsed.dim_sizes, vec![2, 3]);
+    assert_eq!(parsed.axis_values, ca.axis_values);
+    assert_eq!(parsed.element_count(), 6);
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Channel renames and unit overrides while cutting and merging.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::cut::{cut_mdf_by_time_with_mapping, ChannelMapping};
+use mf4_rs::error::MdfError;
+use mf4_rs::merge::merge_files_with_mapping;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// `Time` and a u16 speed channel called `name` with unit `kph`.
+fn write_input(path: &str, name: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let speed = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(name.into());
+        ch.bit_count = 16;
+    })?;
+    w.write_block_with_id(&TextBlock::new("kph").to_bytes()?, "tx_unit")?;
+    w.update_block_link(&speed, 72, "tx_unit")?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..5u64 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+#[test]
+fn cut_and_merge_with_mapping() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+    write_input(&path("a.mf4"), "VehSpd")?;
+    write_input(&path("b.mf4"), "Speed")?;
+
+    let mut mapping = ChannelMapping::default();
+    mapping.names.insert("VehSpd".into(), "Speed".into());
+    mapping.units.insert("Speed".into(), "km/h".into());
+
+    cut_mdf_by_time_with_mapping(&path("a.mf4"), &path("cut.mf4"), 1.0, 3.0, &mapping)?;
+    let cut = MDF::from_file(&path("cut.mf4"))?;
+    assert!(cut.channel("VehSpd").is_none());
+    let speed = cut.channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.values_u64()?, vec![Some(1), Some(2), Some(3)]);
+    assert_eq!(cut.channel("Time").unwrap().unit()?, None);
+
+    // After renaming, both inputs have the same layout and are concatenated.
+    merge_files_with_mapping(&path("merged.mf4"), &path("a.mf4"), &path("b.mf4"), &mapping)?;
+    let merged = MDF::from_file(&path("merged.mf4"))?;
+    assert_eq!(merged.channel_groups().len(), 1);
+    let speed = merged.channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.values_u64()?.len(), 10);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Hierarchical channel names split at the groups' path separators.
+
+use std::path::Path;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// A group with `.` as path separator and a group without one.
+fn write_file(path: &Path) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    for (separator, names) in [
+        (Some('.'), ["Time", "ECU1.Engine.Speed", "ECU1.Engine.Torque", "ECU1.Gear"]),
+        (None, ["Time", "ECU2.Engine.Speed", "Comment", "Status"]),
+    ] {
+        let cg = w.add_channel_group(None, |cg| {
+            cg.path_separator = separator.map_or(0, |sep| sep as u16);
+        })?;
+        let mut prev = None;
+        for (i, name) in names.iter().enumerate() {
+            let id = w.add_channel(&cg, prev.as_ref(), |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(name.to_string());
+                ch.byte_offset = 8 * i as u32;
+                ch.bit_count = 64;
+            })?;
+            if i == 0 {
+                w.set_time_channel(&id)?;
+            }
+            prev = Some(id);
+        }
+        w.start_data_block_for_cg(&cg, 0)?;
+        w.write_record(&cg, &vec![DecodedValue::Float(0.0); 4])?;
+        w.finish_data_block(&cg)?;
+    }
+    w.finalize()
+}
+
+#[test]
+fn names_are_split_at_the_group_separator() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("channel_tree.mf4");
+    write_file(&path)?;
+    let mdf = MDF::from_file(&path)?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups[0].path_separator(), Some('.'));
+    assert_eq!(groups[1].path_separator(), None);
+
+    let tree = mdf.channel_tree()?;
+    assert_eq!(tree.name, "");
+    let root: Vec<&str> = tree.channels.iter().map(|(_, _, n)| n.as_str()).collect();
+    assert_eq!(root, ["Time", "Time", "ECU2.Engine.Speed", "Comment", "Status"]);
+    assert_eq!(tree.children.len(), 1);
+    let engine = tree.get(&["ECU1", "Engine"]).unwrap();
+    assert_eq!(
+        engine.channels,
+        vec![(0, 1, "ECU1.Engine.Speed".to_string()), (0, 2, "ECU1.Engine.Torque".to_string())]
+    );
+    assert_eq!(tree.child("ECU1").unwrap().channels, vec![(0, 3, "ECU1.Gear".to_string())]);
+    assert_eq!(tree.child("ECU1").unwrap().all_channels().len(), 3);
+    assert!(tree.get(&["ECU1", "Gear"]).is_none());
+
+    // Forcing a separator splits the names of the second group too.
+    let tree = mdf.channel_tree_with_separator('.')?;
+    let ecus: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(ecus, ["ECU1", "ECU2"]);
+    assert_eq!(tree.get(&["ECU2", "Engine"]).unwrap().channels, vec![(1, 1, "ECU2.Engine.Speed".to_string())]);
+    assert_eq!(tree.all_channels().len(), 8);
+
+    // Copies keep the separator.
+    let copy = std::env::temp_dir().join("channel_tree_cut.mf4");
+    mf4_rs::cut::cut_mdf_by_time(&path, &copy, 0.0, 1.0)?;
+    assert_eq!(MDF::from_file(&copy)?.channel_tree()?, mdf.channel_tree()?);
+
+    for p in [&path, &copy] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
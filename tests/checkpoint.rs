@@ -0,0 +1,99 @@
+//! `MdfWriter::checkpoint` keeps the file on disk readable while data blocks
+//! are still open.
+
+use std::time::Duration;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::validate::validate_file;
+use mf4_rs::writer::{ChannelGroupId, CompressionMethod, MdfWriter};
+
+/// Adds `Time` and a second channel configured by `second` in a new group.
+fn add_group<F>(w: &mut MdfWriter, second: F) -> Result<ChannelGroupId, MdfError>
+where
+    F: FnOnce(&mut mf4_rs::blocks::channel_block::ChannelBlock),
+{
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), second)?;
+    Ok(cg)
+}
+
+/// Value counts of the second channel of each group as a reader sees them.
+fn visible(path: &str) -> Result<Vec<usize>, MdfError> {
+    let report = validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    mdf.channel_groups().iter().map(|g| Ok(g.channels()[1].values()?.len())).collect()
+}
+
+#[test]
+fn checkpoint_makes_open_file_readable() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("checkpoint.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let counter = add_group(&mut w, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 64;
+    })?;
+    let blob = add_group(&mut w, |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Blob".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    w.start_data_block_for_cg(&counter, 0)?;
+    w.set_compression(CompressionMethod::Zlib)?;
+    w.start_data_block_for_cg(&blob, 0)?;
+
+    let mut written = 0u64;
+    let mut write = |w: &mut MdfWriter, n: u64| -> Result<(), MdfError> {
+        for _ in 0..n {
+            let t = DecodedValue::Float(written as f64);
+            w.write_record(&counter, &[t.clone(), DecodedValue::UnsignedInteger(written)])?;
+            if written.is_multiple_of(1000) {
+                let payload = vec![written as u8; (written / 1000) as usize + 1];
+                w.write_record(&blob, &[t, DecodedValue::ByteArray(payload)])?;
+            }
+            written += 1;
+        }
+        Ok(())
+    };
+
+    write(&mut w, 10)?;
+    w.checkpoint()?;
+    assert_eq!(visible(path)?, vec![10, 1]);
+
+    // Cross a DT block boundary (16-byte records, 4 MiB blocks) and close a
+    // compressed block early.
+    write(&mut w, 300_000)?;
+    w.checkpoint()?;
+    assert_eq!(visible(path)?, vec![300_010, 301]);
+
+    w.set_auto_checkpoint(Some(Duration::ZERO))?;
+    write(&mut w, 1_000)?;
+    assert_eq!(visible(path)?, vec![301_010, 302]);
+
+    w.finish_data_block(&counter)?;
+    w.finish_data_block(&blob)?;
+    w.finalize()?;
+    assert_eq!(visible(path)?, vec![301_010, 302]);
+    let mdf = MDF::from_file(path)?;
+    let blobs = mdf.channel("Blob").unwrap().values()?;
+    assert_eq!(blobs[301], Some(DecodedValue::ByteArray(vec![301_000u64 as u8; 302])));
+    let counters = mdf.channel("Counter").unwrap().values_u64()?;
+    assert_eq!(counters[301_009], Some(301_009));
+    Ok(())
+}
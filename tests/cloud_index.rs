@@ -22,7 +22,7 @@ use mf4_rs::blocks::common::DataType;
 use mf4_rs::error::MdfError;
 use mf4_rs::index::{CachingRangeReader, HttpRangeReader, MdfIndex};
 use mf4_rs::parsing::decoder::DecodedValue;
-use mf4_rs::writer::MdfWriter;
+use mf4_rs::writer::{ChannelGroupId, ChannelId, MdfWriter};
 
 const GROUPS: usize = 10;
 const CHANNELS_PER_GROUP: usize = 10; // 1 master + 9 data
@@ -32,8 +32,8 @@ fn build_fixture(path: &Path) -> Result<(), MdfError> {
     let mut writer = MdfWriter::new(path.to_str().unwrap())?;
     writer.init_mdf_file()?;
 
-    let mut cg_ids: Vec<String> = Vec::new();
-    let mut cn_ids_per_group: Vec<Vec<String>> = Vec::new();
+    let mut cg_ids: Vec<ChannelGroupId> = Vec::new();
+    let mut cn_ids_per_group: Vec<Vec<ChannelId>> = Vec::new();
 
     for g in 0..GROUPS {
         // Each add_channel_group creates a new ##DG, so prev_cg_id stays None
@@ -0,0 +1,86 @@
+//! Columnar writes with the time base passed apart from the data columns.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::writer::{ColumnData, MdfWriter};
+
+#[test]
+fn time_column_is_written_to_the_master_channel() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    w.set_data_block_size(24 + 22 * 100)?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    // The master does not have to be the first channel.
+    let count = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Count".into());
+        ch.bit_count = 16;
+    })?;
+    let t = w.add_channel(&cg, Some(&count), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let level = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Level".into());
+        ch.bit_count = 32;
+    })?;
+    w.add_channel(&cg, Some(&level), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Offset".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+
+    let n = 1000;
+    let time: Vec<f64> = (0..n).map(|i| i as f64 * 0.01).collect();
+    let counts: Vec<u64> = (0..n as u64).collect();
+    let levels: Vec<f32> = (0..n).map(|i| i as f32 * 0.5).collect();
+    let offsets: Vec<i64> = (0..n as i64).map(|i| -i).collect();
+    let columns = [ColumnData::U64(&counts), ColumnData::F32(&levels), ColumnData::I64(&offsets)];
+    w.write_columns_with_time(&cg, &time[..400], &[
+        ColumnData::U64(&counts[..400]),
+        ColumnData::F32(&levels[..400]),
+        ColumnData::I64(&offsets[..400]),
+    ])?;
+    w.write_columns_with_time(&cg, &time[400..], &[
+        ColumnData::U64(&counts[400..]),
+        ColumnData::F32(&levels[400..]),
+        ColumnData::I64(&offsets[400..]),
+    ])?;
+    // One column per non-master channel, all as long as the time base.
+    assert!(w.write_columns_with_time(&cg, &time[..1], &columns[..2]).is_err());
+    assert!(w.write_columns_with_time(&cg, &time[..1], &columns).is_err());
+    w.finish_data_block(&cg)?;
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.master_channel().unwrap().values_f64()?, time.iter().map(|&t| Some(t)).collect::<Vec<_>>());
+    assert_eq!(group.channel("Count").unwrap().values_u64()?, counts.iter().map(|&c| Some(c)).collect::<Vec<_>>());
+    assert_eq!(group.channel("Level").unwrap().values_f64()?[999], Some(499.5));
+    assert_eq!(group.channel("Offset").unwrap().values_i64()?[999], Some(-999));
+    Ok(())
+}
+
+#[test]
+fn groups_without_a_master_are_rejected() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Value".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    assert!(matches!(
+        w.write_columns_with_time(&cg, &[0.0], &[]),
+        Err(MdfError::InvalidWriterState(_))
+    ));
+    Ok(())
+}
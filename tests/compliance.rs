@@ -0,0 +1,122 @@
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockParse, DataType};
+use mf4_rs::blocks::file_history_block::FileHistoryBlock;
+use mf4_rs::blocks::header_block::HeaderBlock;
+use mf4_rs::blocks::metadata_block::MetadataBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{Compliance, MdfWriter};
+
+fn strict_writer(path: &std::path::Path) -> Result<MdfWriter, MdfError> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_compliance(Compliance::Strict41)?;
+    Ok(writer)
+}
+
+fn is_violation<T>(result: Result<T, MdfError>) -> bool {
+    matches!(result, Err(MdfError::ComplianceViolation(_)))
+}
+
+#[test]
+fn strict_writer_produces_readable_file_with_history() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("compliance_strict_ok.mf4");
+    let mut writer = strict_writer(&path)?;
+    writer.add_file_history(1_700_000_000_000_000_000, "mf4-rs", "<acme & co>", "2.0", "created")?;
+    writer.add_file_history(1_700_000_001_000_000_000, "mf4-rs", "acme", "2.0", "appended")?;
+
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 12;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        writer.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let bytes = std::fs::read(&path)?;
+    let hd = HeaderBlock::from_bytes(&bytes[64..])?;
+    let fh = FileHistoryBlock::from_bytes(&bytes[hd.file_history_addr as usize..])?;
+    assert_eq!(fh.time_ns, 1_700_000_000_000_000_000);
+    let md = MetadataBlock::from_bytes(&bytes[fh.comment_addr as usize..])?;
+    assert!(md.xml.contains("<tool_vendor>&lt;acme &amp; co&gt;</tool_vendor>"));
+    let next = FileHistoryBlock::from_bytes(&bytes[fh.next_fh_addr as usize..])?;
+    assert_eq!(next.time_ns, 1_700_000_001_000_000_000);
+    assert_eq!(next.next_fh_addr, 0);
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.channel_groups()[0].channels()[1].values()?.len(), 10);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn strict_writer_rejects_unrepresentable_channels() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("compliance_strict_channels.mf4");
+    let mut writer = strict_writer(&path)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    assert!(is_violation(writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 16;
+    })));
+    assert!(is_violation(writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::StringUtf16LE;
+        ch.bit_count = 24;
+    })));
+    assert!(is_violation(writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::ComplexLE;
+        ch.bit_count = 64;
+    })));
+    assert!(is_violation(writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 65;
+    })));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn strict_writer_requires_master_and_history() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("compliance_strict_group.mf4");
+    let mut writer = strict_writer(&path)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+    })?;
+    // No ##FH block yet.
+    assert!(is_violation(writer.start_data_block_for_cg(&cg, 0)));
+    writer.add_file_history(0, "mf4-rs", "", "", "")?;
+    // No master channel.
+    assert!(is_violation(writer.start_data_block_for_cg(&cg, 0)));
+    drop(writer);
+
+    let lenient = std::env::temp_dir().join("compliance_lenient.mf4");
+    let mut writer = MdfWriter::new(lenient.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 16;
+    })?;
+    // Switching modes re-checks the channels already configured.
+    assert!(is_violation(writer.set_compliance(Compliance::Strict41)));
+    assert_eq!(writer.compliance(), Compliance::Lenient);
+    writer.finalize()?;
+
+    let writer = strict_writer(&path)?;
+    assert!(is_violation(writer.finalize()));
+    std::fs::remove_file(&path)?;
+    std::fs::remove_file(&lenient)?;
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockHeader, BlockParse, DataType};
+use mf4_rs::blocks::data_group_block::DataGroupBlock;
+use mf4_rs::blocks::data_list_block::DataListBlock;
+use mf4_rs::blocks::data_zipped_block::DataZippedBlock;
+use mf4_rs::blocks::header_block::HeaderBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::MdfIndex;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{CompressionMethod, LiveUpdate, MdfWriter};
+
+const RECORDS: usize = 300_000;
+
+/// Write a time + counter group (16-byte records, more than one 4 MiB block)
+/// and return the file bytes.
+fn write_file(name: &str, compression: CompressionMethod) -> Result<Vec<u8>, MdfError> {
+    let path = std::env::temp_dir().join(name);
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_compression(compression)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..RECORDS {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(i as f64 * 0.001),
+            DecodedValue::UnsignedInteger((i % 256) as u64),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    let bytes = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(bytes)
+}
+
+/// Concatenated data sections of the first data group, inflating `##DZ`.
+fn data_section(bytes: &[u8]) -> Result<(Vec<u8>, Vec<String>), MdfError> {
+    let hd = HeaderBlock::from_bytes(&bytes[64..])?;
+    let dg = DataGroupBlock::from_bytes(&bytes[hd.first_dg_addr as usize..])?;
+    let at = |addr: u64| &bytes[addr as usize..];
+    let links = match BlockHeader::from_bytes(&at(dg.data_block_addr)[..24])?.id.as_str() {
+        "##DL" => DataListBlock::from_bytes(at(dg.data_block_addr))?.data_links,
+        _ => vec![dg.data_block_addr],
+    };
+    let mut data = Vec::new();
+    let mut ids = Vec::new();
+    for link in links {
+        let header = BlockHeader::from_bytes(&at(link)[..24])?;
+        if header.id == "##DZ" {
+            let dz = DataZippedBlock::from_bytes(at(link))?;
+            assert_eq!(dz.org_block_type, "DT");
+            let inflated = miniz_oxide::inflate::decompress_to_vec_zlib(&dz.data).unwrap();
+            assert_eq!(inflated.len() as u64, dz.org_data_length);
+            if dz.zip_type == 1 {
+                let cols = dz.zip_parameter as usize;
+                let rows = inflated.len() / cols;
+                let mut plain = vec![0u8; inflated.len()];
+                for c in 0..cols {
+                    for r in 0..rows {
+                        plain[r * cols + c] = inflated[c * rows + r];
+                    }
+                }
+                data.extend_from_slice(&plain);
+            } else {
+                data.extend_from_slice(&inflated);
+            }
+        } else {
+            data.extend_from_slice(&at(link)[24..header.block_len as usize]);
+        }
+        ids.push(header.id);
+    }
+    Ok((data, ids))
+}
+
+#[test]
+fn compressed_blocks_hold_the_same_records() -> Result<(), MdfError> {
+    let plain = write_file("compression_plain.mf4", CompressionMethod::None)?;
+    let (plain_data, plain_ids) = data_section(&plain)?;
+    assert_eq!(plain_data.len(), RECORDS * 16);
+    assert_eq!(plain_ids, ["##DT", "##DT"]);
+
+    for (name, method) in [
+        ("compression_zlib.mf4", CompressionMethod::Zlib),
+        ("compression_transposed.mf4", CompressionMethod::TransposedZlib),
+    ] {
+        let zipped = write_file(name, method)?;
+        let (data, ids) = data_section(&zipped)?;
+        assert_eq!(ids, ["##DZ", "##DZ"]);
+        assert!(data == plain_data, "{method:?} data differs from the uncompressed file");
+        assert!(zipped.len() * 3 < plain.len(), "{method:?} output is not smaller");
+    }
+    Ok(())
+}
+
+#[test]
+fn compression_excludes_live_updates() -> Result<(), MdfError> {
+    let mut writer = MdfWriter::new_from_writer(std::io::Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    writer.set_compression(CompressionMethod::Zlib)?;
+    let live = LiveUpdate { every_records: Some(10), interval: None };
+    assert!(writer.set_live_update(Some(live)).is_err());
+
+    writer.set_compression(CompressionMethod::None)?;
+    writer.set_live_update(Some(live))?;
+    assert!(writer.set_compression(CompressionMethod::TransposedZlib).is_err());
+    Ok(())
+}
+
+#[test]
+fn compressed_files_read_back() -> Result<(), MdfError> {
+    for (name, method) in [
+        ("compression_read_zlib.mf4", CompressionMethod::Zlib),
+        ("compression_read_transposed.mf4", CompressionMethod::TransposedZlib),
+    ] {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, write_file(name, method)?)?;
+        let expected: Vec<f64> = (0..RECORDS).map(|i| (i % 256) as f64).collect();
+
+        let mdf = MDF::from_file(path.to_str().unwrap())?;
+        let counter = mdf.channel("Counter").unwrap();
+        assert_eq!(counter.values_as_f64()?, expected);
+        let values = counter.values()?;
+        assert_eq!(values.len(), RECORDS);
+        assert_eq!(values[RECORDS - 1], Some(DecodedValue::UnsignedInteger(((RECORDS - 1) % 256) as u64)));
+
+        let index = MdfIndex::from_file(path.to_str().unwrap())?;
+        assert_eq!(index.open_file(path.to_str().unwrap())?.values_f64("Counter")?, expected);
+        assert_eq!(index.read("Time")?.len(), RECORDS);
+        assert!(index.byte_ranges("Counter").is_err(), "DZ samples have no file byte range");
+        std::fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn compressed_signal_data_reads_back() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("compression_vlsd.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Payload".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    let payloads: Vec<Vec<u8>> = (0..20).map(|i| vec![i as u8; i + 1]).collect();
+    for (i, p) in payloads.iter().enumerate() {
+        writer.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::ByteArray(p.clone())])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    // Replace the ##SD block by an equivalent ##DZ appended to the file.
+    let mut bytes = std::fs::read(&path)?;
+    let sd_pos = (0..bytes.len()).step_by(8).find(|&p| &bytes[p..p + 4] == b"##SD").unwrap();
+    let sd_len = BlockHeader::from_bytes(&bytes[sd_pos..sd_pos + 24])?.block_len as usize;
+    let dz = DataZippedBlock::compress("SD", &bytes[sd_pos + 24..sd_pos + sd_len], 0, 0)?;
+    let dz_pos = bytes.len().next_multiple_of(8);
+    bytes.resize(dz_pos, 0);
+    bytes.extend_from_slice(&dz.to_bytes()?);
+    let cn_pos = (0..bytes.len())
+        .step_by(8)
+        .find(|&p| &bytes[p..p + 4] == b"##CN" && bytes[p + 64..p + 72] == (sd_pos as u64).to_le_bytes())
+        .unwrap();
+    bytes[cn_pos + 64..cn_pos + 72].copy_from_slice(&(dz_pos as u64).to_le_bytes());
+    std::fs::write(&path, &bytes)?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let values = mdf.channel("Payload").unwrap().values()?;
+    let expected: Vec<Option<DecodedValue>> =
+        payloads.into_iter().map(|p| Some(DecodedValue::ByteArray(p))).collect();
+    assert_eq!(values, expected);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
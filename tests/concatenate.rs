@@ -0,0 +1,140 @@
+//! `merge::concatenate` appends the records of same-structured files.
+
+use std::path::{Path, PathBuf};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::merge::concatenate;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+const START_NS: u64 = 1_700_000_000_000_000_000;
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(name)
+}
+
+/// A file starting at `start_ns` with a `Fast` group (float master,
+/// counter with a linear conversion) and a `Log` group (integer master in
+/// milliseconds, VLSD string), `records` records each.
+fn write_file(path: &Path, start_ns: u64, records: u64) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    w.set_start_time(start_ns, 0, 0, 0, 0)?;
+
+    let fast = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&fast, "Fast")?;
+    let time = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let counter = w.add_channel(&fast, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_linear_conversion(0.0, 2.0, Some("rpm"), Some(&counter))?;
+    w.start_data_block_for_cg(&fast, 0)?;
+    for i in 0..records {
+        w.write_record(&fast, &[DecodedValue::Float(i as f64 * 0.5), DecodedValue::UnsignedInteger(i)])?;
+    }
+    w.finish_data_block(&fast)?;
+
+    let log = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&log, "Log")?;
+    let ticks = w.add_channel(&log, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Ticks".into());
+        ch.bit_count = 32;
+    })?;
+    w.set_time_channel(&ticks)?;
+    w.add_linear_conversion(0.0, 0.001, Some("s"), Some(&ticks))?;
+    w.add_channel(&log, Some(&ticks), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Message".into());
+        ch.channel_type = 1;
+        ch.data = 1;
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&log, 0)?;
+    for i in 0..records {
+        w.write_record(&log, &[DecodedValue::UnsignedInteger(i * 500), DecodedValue::String(format!("msg {i}"))])?;
+    }
+    w.finish_data_block(&log)?;
+    w.finalize()
+}
+
+#[test]
+fn records_are_appended_with_continuous_time() -> Result<(), MdfError> {
+    let inputs = [temp("concat_a.mf4"), temp("concat_b.mf4"), temp("concat_c.mf4")];
+    let out = temp("concat_out.mf4");
+    // Each file holds 4 records 0.5 s apart and starts 2 s after the last.
+    write_file(&inputs[0], START_NS, 4)?;
+    write_file(&inputs[1], START_NS + 2_000_000_000, 4)?;
+    write_file(&inputs[2], START_NS + 4_000_000_000, 4)?;
+
+    concatenate(&inputs, &out)?;
+
+    let mdf = MDF::from_file(&out)?;
+    assert_eq!(mdf.start_time_ns(), Some(START_NS));
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].name()?.as_deref(), Some("Fast"));
+    assert_eq!(groups[0].raw_channel_group().block.cycles_nr, 12);
+    assert_eq!(groups[1].raw_channel_group().block.cycles_nr, 12);
+
+    let expected: Vec<f64> = (0..12).map(|i| i as f64 * 0.5).collect();
+    assert_eq!(groups[0].time_values()?, Some(expected.clone()));
+    let counter = groups[0].channel("Counter").unwrap();
+    assert_eq!(counter.unit()?.as_deref(), Some("rpm"));
+    let counts: Vec<Option<f64>> = (0..12).map(|i| Some((i % 4) as f64 * 2.0)).collect();
+    assert_eq!(counter.values_f64()?, counts);
+
+    let log_time = groups[1].time_values()?.unwrap();
+    for (got, want) in log_time.iter().zip(&expected) {
+        assert!((got - want).abs() < 1e-9, "{got} != {want}");
+    }
+    let messages = groups[1].channel("Message").unwrap().values_string()?;
+    let want: Vec<Option<String>> = (0..12).map(|i| Some(format!("msg {}", i % 4))).collect();
+    assert_eq!(messages, want);
+
+    for p in inputs.iter().chain([&out]) {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn different_layouts_are_rejected() -> Result<(), MdfError> {
+    let first = temp("concat_layout_a.mf4");
+    let second = temp("concat_layout_b.mf4");
+    let out = temp("concat_layout_out.mf4");
+    write_file(&first, START_NS, 2)?;
+
+    let mut w = MdfWriter::new(&second)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(&cg, &[DecodedValue::Float(0.0)])?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    match concatenate(&[&first, &second], &out) {
+        Err(MdfError::LayoutMismatch(message)) => assert!(message.contains("1 channel groups, expected 2"), "{message}"),
+        other => panic!("expected a layout mismatch, got {other:?}"),
+    }
+    assert!(matches!(concatenate(&[] as &[&Path], &out), Err(MdfError::InvalidIndex { .. })));
+
+    for p in [&first, &second] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Decoding channels of one open file from several threads at once.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+const RECORDS: u64 = 50_000;
+
+/// Two groups, the second one in compressed blocks so the threads race for
+/// the lazily inflated data.
+fn sample_file() -> Result<Vec<u8>, MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    w.set_data_block_size(64 * 1024)?;
+    for (group, compression) in [("Plain", CompressionMethod::None), ("Zipped", CompressionMethod::Zlib)] {
+        w.set_compression(compression)?;
+        let cg = w.add_channel_group(None, |cg| cg.acq_name = Some(group.into()))?;
+        let t = w.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(format!("{group}Time"));
+            ch.bit_count = 64;
+        })?;
+        w.set_time_channel(&t)?;
+        let a = w.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(format!("{group}A"));
+            ch.bit_count = 32;
+        })?;
+        w.add_channel(&cg, Some(&a), |ch| {
+            ch.data_type = DataType::SignedIntegerLE;
+            ch.name = Some(format!("{group}B"));
+            ch.bit_count = 16;
+        })?;
+        w.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..RECORDS {
+            w.write_record(
+                &cg,
+                &[
+                    DecodedValue::Float(i as f64 * 0.01),
+                    DecodedValue::UnsignedInteger(i * 3),
+                    DecodedValue::SignedInteger((i % 1000) as i64 - 500),
+                ],
+            )?;
+        }
+        w.finish_data_block(&cg)?;
+    }
+    Ok(w.into_inner()?.into_inner())
+}
+
+const CHANNELS: [&str; 6] = ["PlainTime", "PlainA", "PlainB", "ZippedTime", "ZippedA", "ZippedB"];
+
+#[test]
+fn channel_readers_decode_in_parallel() -> Result<(), MdfError> {
+    let bytes = sample_file()?;
+    let expected: Vec<Vec<Option<f64>>> = {
+        let mdf = MDF::from_bytes(bytes.clone())?;
+        CHANNELS.iter().map(|name| mdf.channel(name).unwrap().values_f64()).collect::<Result<_, _>>()?
+    };
+
+    // A fresh file, so the compressed blocks are inflated by the threads.
+    let mdf = Arc::new(MDF::from_bytes(bytes)?);
+    let threads: Vec<_> = CHANNELS
+        .iter()
+        .chain(&CHANNELS)
+        .map(|name| {
+            let mut reader = mdf.channel_reader(name).unwrap();
+            std::thread::spawn(move || -> Result<Vec<Option<f64>>, MdfError> {
+                assert_eq!(reader.record_count(), RECORDS as usize);
+                let mut values = Vec::new();
+                loop {
+                    let chunk = reader.read_f64(7_000)?;
+                    if chunk.is_empty() {
+                        return Ok(values);
+                    }
+                    values.extend(chunk);
+                }
+            })
+        })
+        .collect();
+    for (i, thread) in threads.into_iter().enumerate() {
+        assert_eq!(thread.join().unwrap()?, expected[i % CHANNELS.len()], "{}", CHANNELS[i % CHANNELS.len()]);
+    }
+    Ok(())
+}
+
+#[test]
+fn readers_keep_their_own_position() -> Result<(), MdfError> {
+    let mdf = Arc::new(MDF::from_bytes(sample_file()?)?);
+    let mut first = mdf.channel_reader("ZippedA").unwrap();
+    let mut second = first.clone();
+    assert_eq!(first.channel().name()?.as_deref(), Some("ZippedA"));
+
+    first.seek(RECORDS as usize - 2);
+    assert_eq!(first.read(10)?, vec![Some(DecodedValue::UnsignedInteger((RECORDS - 2) * 3)), Some(DecodedValue::UnsignedInteger((RECORDS - 1) * 3))]);
+    assert_eq!(first.position(), RECORDS as usize);
+    assert!(first.read(10)?.is_empty());
+
+    assert_eq!(second.read_f64(2)?, vec![Some(0.0), Some(3.0)]);
+    assert_eq!(second.position(), 2);
+    assert!(mdf.channel_reader("Missing").is_none());
+
+    // Plain references work with scoped threads, without an Arc.
+    let sums: Vec<f64> = std::thread::scope(|s| {
+        let handles: Vec<_> = ["PlainA", "ZippedA"]
+            .map(|name| s.spawn(|| mdf.channel(name).unwrap().values_f64().map(|v| v.into_iter().flatten().sum())))
+            .into_iter()
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect::<Result<_, MdfError>>()
+    })?;
+    assert_eq!(sums[0], sums[1]);
+    Ok(())
+}
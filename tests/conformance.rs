@@ -0,0 +1,201 @@
+//! Golden-file conformance harness.
+//!
+//! Parses every `.mf4` / `.mdf` file of a sample directory and compares a
+//! snapshot of it — the complete [`FileMetadata`] plus the sample count and
+//! first/last sample of every channel — with the `<file>.json` snapshot next
+//! to it. Reference files from other tools (e.g. the files of the asammdf
+//! test suites) exercise block layouts mf4-rs never writes itself, so a
+//! snapshot mismatch points at a parser regression.
+//!
+//! Environment:
+//! * `MF4RS_CONFORMANCE_DIR` — sample directory; the test is skipped if unset.
+//! * `MF4RS_CONFORMANCE_URLS` — whitespace-separated URLs of sample files to
+//!   download into the directory first (missing files only; needs the `http`
+//!   feature).
+//! * `MF4RS_UPDATE_SNAPSHOTS=1` — (re)write the snapshots instead of
+//!   comparing. Snapshots missing on a normal run are written as well.
+//!
+//! ```bash
+//! MF4RS_CONFORMANCE_DIR=~/mdf-samples cargo test --test conformance
+//! ```
+//!
+//! [`FileMetadata`]: mf4_rs::metadata::FileMetadata
+
+use std::path::{Path, PathBuf};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+use serde_json::{json, Value};
+
+/// Snapshot of `path`: its metadata and, per group, the sample count and
+/// first/last sample of each channel (`Debug`-formatted, so NaN, byte
+/// arrays and invalid samples compare exactly).
+fn snapshot(path: &Path) -> Result<Value, MdfError> {
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let metadata = serde_json::to_value(mdf.metadata()?)
+        .map_err(|e| MdfError::BlockSerializationError(format!("metadata to JSON: {e}")))?;
+    let mut groups = Vec::new();
+    for group in mdf.channel_groups() {
+        let mut channels = Vec::new();
+        for channel in group.channels() {
+            let values = channel.values()?;
+            channels.push(json!({
+                "name": channel.name()?,
+                "count": values.len(),
+                "first": values.first().map(|v| format!("{v:?}")),
+                "last": values.last().map(|v| format!("{v:?}")),
+            }));
+        }
+        groups.push(json!({ "name": group.name()?, "channels": channels }));
+    }
+    Ok(json!({ "metadata": metadata, "samples": groups }))
+}
+
+fn snapshot_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap().to_os_string();
+    name.push(".json");
+    path.with_file_name(name)
+}
+
+/// Compare `path` with its snapshot, writing the snapshot if it is missing
+/// or `update` is set. Returns a description of the mismatch, if any.
+fn check_file(path: &Path, update: bool) -> Result<Option<String>, MdfError> {
+    let actual = snapshot(path)?;
+    let expected_path = snapshot_path(path);
+    if update || !expected_path.exists() {
+        let text = serde_json::to_string_pretty(&actual)
+            .map_err(|e| MdfError::BlockSerializationError(format!("snapshot to JSON: {e}")))?;
+        std::fs::write(&expected_path, text + "\n")?;
+        return Ok(None);
+    }
+    let expected: Value = serde_json::from_str(&std::fs::read_to_string(&expected_path)?)
+        .map_err(|e| MdfError::BlockSerializationError(format!("{}: {e}", expected_path.display())))?;
+    Ok(first_difference(&expected, &actual, "$"))
+}
+
+/// JSON path and values of the first difference between two snapshots.
+fn first_difference(expected: &Value, actual: &Value, at: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            let keys: std::collections::BTreeSet<&String> = e.keys().chain(a.keys()).collect();
+            keys.into_iter().find_map(|key| {
+                let (e, a) = (e.get(key).unwrap_or(&Value::Null), a.get(key).unwrap_or(&Value::Null));
+                first_difference(e, a, &format!("{at}.{key}"))
+            })
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => e
+            .iter()
+            .zip(a)
+            .enumerate()
+            .find_map(|(i, (e, a))| first_difference(e, a, &format!("{at}[{i}]"))),
+        _ if expected == actual => None,
+        _ => Some(format!("{at}: expected {expected}, found {actual}")),
+    }
+}
+
+/// Download the files named by `MF4RS_CONFORMANCE_URLS` into `dir`.
+#[cfg(feature = "http")]
+fn download_samples(dir: &Path) -> Result<(), MdfError> {
+    use mf4_rs::index::{ByteRangeReader, HttpRangeReader};
+
+    let urls = std::env::var("MF4RS_CONFORMANCE_URLS").unwrap_or_default();
+    for url in urls.split_whitespace() {
+        let name = url.rsplit('/').next().unwrap_or(url).split('?').next().unwrap_or_default();
+        let target = dir.join(name);
+        if name.is_empty() || target.exists() {
+            continue;
+        }
+        let mut reader = HttpRangeReader::new(url)?;
+        let size = reader.probe_size()?;
+        std::fs::write(&target, reader.read_range(0, size)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+fn download_samples(_dir: &Path) -> Result<(), MdfError> {
+    if std::env::var_os("MF4RS_CONFORMANCE_URLS").is_some() {
+        eprintln!("MF4RS_CONFORMANCE_URLS ignored: downloading samples needs the `http` feature");
+    }
+    Ok(())
+}
+
+/// Check every sample file of `dir`, returning the mismatches.
+fn check_dir(dir: &Path, update: bool) -> Result<Vec<String>, MdfError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    files.retain(|p| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("mf4") || e.eq_ignore_ascii_case("mdf"))
+    });
+    files.sort();
+
+    let mut failures = Vec::new();
+    for file in &files {
+        match check_file(file, update) {
+            Ok(None) => {}
+            Ok(Some(diff)) => failures.push(format!("{}: {diff}", file.display())),
+            Err(e) => failures.push(format!("{}: failed to parse: {e}", file.display())),
+        }
+    }
+    Ok(failures)
+}
+
+#[test]
+fn reference_samples_match_snapshots() -> Result<(), MdfError> {
+    let Some(dir) = std::env::var_os("MF4RS_CONFORMANCE_DIR").map(PathBuf::from) else {
+        eprintln!("MF4RS_CONFORMANCE_DIR not set; skipping the golden-file conformance test");
+        return Ok(());
+    };
+    std::fs::create_dir_all(&dir)?;
+    download_samples(&dir)?;
+    let update = std::env::var("MF4RS_UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1");
+    let failures = check_dir(&dir, update)?;
+    assert!(failures.is_empty(), "{} sample(s) differ from their snapshot:\n{}", failures.len(), failures.join("\n"));
+    Ok(())
+}
+
+/// The harness itself, on a file written by mf4-rs: the first run writes the
+/// snapshot, an unchanged file matches it and a changed sample is reported.
+#[test]
+fn harness_detects_changed_samples() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("sample.mf4");
+    let write = |last: f64| -> Result<(), MdfError> {
+        let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+        writer.init_mdf_file()?;
+        writer.set_start_time(1_700_000_000_000_000_000, 0, 0, 0, 0)?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let time = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time)?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for t in [0.0, 0.5, last] {
+            writer.write_record(&cg, &[DecodedValue::Float(t)])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()
+    };
+
+    write(1.0)?;
+    assert!(check_dir(dir.path(), false)?.is_empty());
+    let snapshot: Value = serde_json::from_str(&std::fs::read_to_string(snapshot_path(&path))?).unwrap();
+    assert_eq!(snapshot["samples"][0]["channels"][0]["last"], json!("Some(Float(1.0))"));
+    assert!(check_dir(dir.path(), false)?.is_empty());
+
+    write(2.0)?;
+    let failures = check_dir(dir.path(), false)?;
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].contains("channels[0].last"), "{}", failures[0]);
+    assert!(check_dir(dir.path(), true)?.is_empty());
+    assert!(check_dir(dir.path(), false)?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! Record templates and constant channels: mostly static groups written by
+//! giving values for the varying channels only.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, ColumnData, MdfWriter};
+
+/// `Time` (f64), `Gear` (u8), `Speed` (f32) and `Vin` (8-byte array).
+fn group(w: &mut MdfWriter<Cursor<Vec<u8>>>) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let gear = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    let speed = w.add_channel(&cg, Some(&gear), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 32;
+    })?;
+    w.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Vin".into());
+        ch.bit_count = 64;
+    })?;
+    Ok(cg)
+}
+
+#[test]
+fn constant_channels_are_copied_from_the_template() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    // Small blocks, so the template has to survive `##DT` splits.
+    w.set_data_block_size(24 + 21 * 8)?;
+    let cg = group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.set_constant_channels(&cg, &[
+        None,
+        Some(DecodedValue::UnsignedInteger(3)),
+        None,
+        Some(DecodedValue::ByteArray(b"WVWZZZ1K".to_vec())),
+    ])?;
+    w.write_varying_record(&cg, &[DecodedValue::Float(0.0), DecodedValue::Float(10.0)])?;
+    let records: Vec<Vec<DecodedValue>> = (1..20)
+        .map(|i| vec![DecodedValue::Float(i as f64 * 0.1), DecodedValue::Float(10.0 + i as f64)])
+        .collect();
+    w.write_varying_records(&cg, records.iter().map(Vec::as_slice))?;
+    let time: Vec<f64> = (20..30).map(|i| i as f64 * 0.1).collect();
+    let speed: Vec<f32> = (20..30).map(|i| 10.0 + i as f32).collect();
+    w.write_varying_columns(&cg, &[ColumnData::F64(&time), ColumnData::F32(&speed)])?;
+    assert!(matches!(
+        w.write_varying_record(&cg, &[DecodedValue::Float(0.0)]),
+        Err(MdfError::ValueCountMismatch { expected: 2, actual: 1 })
+    ));
+    // Full records still work and may override a constant.
+    w.write_record(&cg, &[
+        DecodedValue::Float(3.0),
+        DecodedValue::UnsignedInteger(4),
+        DecodedValue::Float(40.0),
+        DecodedValue::Unknown,
+    ])?;
+    w.finish_data_block(&cg)?;
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.channel("Time").unwrap().values_f64()?.len(), 31);
+    let mut gears = vec![Some(3); 30];
+    gears.push(Some(4));
+    assert_eq!(group.channel("Gear").unwrap().values_u64()?, gears);
+    let mut speeds: Vec<_> = (0..30).map(|i| Some(10.0 + i as f64)).collect();
+    speeds.push(Some(40.0));
+    assert_eq!(group.channel("Speed").unwrap().values_f64()?, speeds);
+    let vins = group.channel("Vin").unwrap().values()?;
+    // `Unknown` keeps the template bytes.
+    assert!(vins.iter().all(|v| v == &Some(DecodedValue::ByteArray(b"WVWZZZ1K".to_vec()))));
+    Ok(())
+}
+
+#[test]
+fn record_template_fills_unknown_values() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.set_record_template(&cg, &[
+        DecodedValue::Unknown,
+        DecodedValue::UnsignedInteger(7),
+        DecodedValue::Float(1.5),
+        DecodedValue::Unknown,
+    ])?;
+    // Without constant channels every channel is varying.
+    w.write_varying_record(&cg, &[
+        DecodedValue::Float(0.0),
+        DecodedValue::UnsignedInteger(1),
+        DecodedValue::Unknown,
+        DecodedValue::ByteArray(vec![1; 8]),
+    ])?;
+    w.write_record(&cg, &[DecodedValue::Float(0.1), DecodedValue::Unknown, DecodedValue::Float(2.5), DecodedValue::Unknown])?;
+    assert!(w.set_record_template(&cg, &[DecodedValue::Unknown]).is_err());
+    assert!(w.write_varying_columns(&cg, &[ColumnData::F64(&[0.2])]).is_err());
+    w.finish_data_block(&cg)?;
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.channel("Gear").unwrap().values_u64()?, vec![Some(1), Some(7)]);
+    assert_eq!(group.channel("Speed").unwrap().values_f64()?, vec![Some(1.5), Some(2.5)]);
+    assert_eq!(group.channel("Vin").unwrap().values()?[1], Some(DecodedValue::ByteArray(vec![0; 8])));
+    Ok(())
+}
+
+#[test]
+fn vlsd_channels_cannot_be_constant() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Log".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    assert!(matches!(
+        w.set_constant_channels(&cg, &[None, Some(DecodedValue::String("x".into()))]),
+        Err(MdfError::UnsupportedFeature(_))
+    ));
+    w.set_constant_channels(&cg, &[Some(DecodedValue::Float(1.0)), None])?;
+    w.write_varying_record(&cg, &[DecodedValue::String("started".into())])?;
+    w.write_varying_record(&cg, &[DecodedValue::String("done".into())])?;
+    w.finish_data_block(&cg)?;
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.channel("Time").unwrap().values_f64()?, vec![Some(1.0); 2]);
+    assert_eq!(
+        group.channel("Log").unwrap().values()?,
+        vec![Some(DecodedValue::String("started".into())), Some(DecodedValue::String("done".into()))]
+    );
+    Ok(())
+}
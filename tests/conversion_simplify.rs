@@ -0,0 +1,167 @@
+//! Simplified conversion evaluators and the physical ranges derived from
+//! them.
+
+use std::collections::HashMap;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::blocks::conversion::{ConversionBlock, ConversionType, SimplifiedConversion};
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn conversion(cc_type: ConversionType, cc_val: Vec<f64>) -> ConversionBlock {
+    ConversionBlock {
+        header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+        cc_tx_name: None,
+        cc_md_unit: None,
+        cc_md_comment: None,
+        cc_cc_inverse: None,
+        cc_ref: Vec::new(),
+        cc_type,
+        cc_precision: 0,
+        cc_flags: 0,
+        cc_ref_count: 0,
+        cc_val_count: cc_val.len() as u16,
+        cc_phy_range_min: None,
+        cc_phy_range_max: None,
+        cc_val,
+        formula: None,
+        resolved_texts: None,
+        resolved_conversions: None,
+        default_conversion: None,
+    }
+}
+
+fn float(value: DecodedValue) -> f64 {
+    match value {
+        DecodedValue::Float(v) => v,
+        other => panic!("expected a float, got {other:?}"),
+    }
+}
+
+#[test]
+fn linear_and_rational_stages_fold_into_one() -> Result<(), MdfError> {
+    let inner = SimplifiedConversion::Linear { offset: 1.0, factor: 2.0 };
+    let outer = SimplifiedConversion::Linear { offset: -3.0, factor: 0.5 };
+    let folded = inner.clone().then(outer.clone());
+    assert!(matches!(folded, SimplifiedConversion::Linear { offset, factor } if offset == -2.5 && factor == 1.0));
+
+    let rational = SimplifiedConversion::Rational([1.0, 0.0, 2.0, 0.0, 1.0, 4.0]);
+    for stages in [
+        vec![rational.clone(), outer.clone()],
+        vec![inner.clone(), rational.clone()],
+        vec![inner.clone(), rational.clone(), SimplifiedConversion::Identity, outer.clone()],
+    ] {
+        let folded = stages.iter().cloned().reduce(SimplifiedConversion::then).unwrap();
+        assert!(matches!(folded, SimplifiedConversion::Rational(_)), "{folded:?}");
+        for raw in [0.0, 1.5, 7.0, 100.0] {
+            let mut expected = DecodedValue::Float(raw);
+            for stage in &stages {
+                expected = stage.apply(expected)?;
+            }
+            let got = float(folded.apply(DecodedValue::Float(raw))?);
+            assert!((got - float(expected)).abs() < 1e-9, "{raw}: {got}");
+        }
+    }
+
+    // A rational with a unit denominator is simplified to a linear one.
+    let linear = conversion(ConversionType::Rational, vec![0.0, 3.0, 1.0, 0.0, 0.0, 1.0]).simplify();
+    assert!(matches!(linear, SimplifiedConversion::Linear { offset, factor } if offset == 1.0 && factor == 3.0));
+    assert!(SimplifiedConversion::Identity.then(SimplifiedConversion::Identity).is_identity());
+    Ok(())
+}
+
+#[test]
+fn simplified_text_tables_match_the_block() -> Result<(), MdfError> {
+    // [0, 10) -> "low", [10, 20) -> 100 + 2x, otherwise -x.
+    let mut table = conversion(ConversionType::RangeToText, vec![0.0, 10.0, 10.0, 20.0]);
+    table.cc_ref = vec![0x100, 0x200, 0x300];
+    table.cc_ref_count = 3;
+    let default = conversion(ConversionType::Linear, vec![0.0, -1.0]);
+    table.resolved_texts = Some(HashMap::from([(0, "low".to_string())]));
+    table.resolved_conversions = Some(HashMap::from([
+        (1, Box::new(conversion(ConversionType::Linear, vec![100.0, 2.0]))),
+        (2, Box::new(default.clone())),
+    ]));
+    table.default_conversion = Some(Box::new(default));
+
+    let simplified = table.simplify();
+    assert!(matches!(simplified, SimplifiedConversion::RangeToText { .. }));
+    let raws = (-5..25)
+        .map(DecodedValue::SignedInteger)
+        .chain((0..25).map(DecodedValue::UnsignedInteger))
+        .chain((-10..50).map(|i| DecodedValue::Float(i as f64 * 0.5)))
+        .chain([DecodedValue::String("text".into())]);
+    for raw in raws {
+        assert_eq!(simplified.apply(raw.clone())?, table.apply_decoded(raw.clone(), &[])?, "{raw:?}");
+    }
+    // Text outputs are left out of the physical range; the default counts
+    // over the whole raw range.
+    assert_eq!(simplified.physical_range(0.0, 15.0), Some((-15.0, 130.0)));
+    assert_eq!(simplified.physical_range(2.0, 8.0), Some((-8.0, -2.0)));
+    Ok(())
+}
+
+#[test]
+fn physical_range_is_written_for_numeric_conversions() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("conversion_simplify_range.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let mut prev = None;
+    let mut channels = Vec::new();
+    for (name, data_type, bits) in [
+        ("Temp", DataType::UnsignedIntegerLE, 16),
+        ("Ratio", DataType::UnsignedIntegerLE, 8),
+        ("Pole", DataType::SignedIntegerLE, 8),
+    ] {
+        let cn = w.add_channel(&cg, prev.as_ref(), |ch| {
+            ch.data_type = data_type;
+            ch.name = Some(name.into());
+            ch.bit_count = bits;
+        })?;
+        prev = Some(cn);
+        channels.push(cn);
+    }
+    w.add_linear_conversion(-40.0, 0.5, Some("degC"), Some(&channels[0]))?;
+    // (2x + 1) / (x + 1) rises from 1 towards 2.
+    w.add_rational_conversion([0.0, 2.0, 1.0, 0.0, 1.0, 1.0], None, Some(&channels[1]))?;
+    // 1 / (x - 10) has its pole inside [-128, 127].
+    w.add_rational_conversion([0.0, 0.0, 1.0, 0.0, 1.0, -10.0], None, Some(&channels[2]))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(&cg, &[
+        DecodedValue::UnsignedInteger(100),
+        DecodedValue::UnsignedInteger(3),
+        DecodedValue::SignedInteger(12),
+    ])?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    let report = mf4_rs::validate::validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    for (name, expected) in [("Temp", Some((-40.0, 32727.5))), ("Ratio", Some((1.0, 511.0 / 256.0))), ("Pole", None)] {
+        let channel = mdf.channel(name).unwrap();
+        assert_eq!(channel.physical_range(), expected, "{name}");
+        let block = channel.block();
+        let cc = block.conversion.as_ref().unwrap();
+        match expected {
+            Some((min, max)) => {
+                assert_eq!((cc.cc_phy_range_min, cc.cc_phy_range_max), (Some(min), Some(max)));
+                assert_ne!(cc.cc_flags & 0x02, 0);
+                assert_eq!((block.lower_limit, block.upper_limit), (min, max));
+                assert_ne!(block.flags & 0x10, 0);
+            }
+            None => {
+                assert_eq!(cc.cc_flags & 0x02, 0);
+                assert_eq!(block.flags & 0x10, 0);
+            }
+        }
+    }
+    assert_eq!(mdf.channel("Temp").unwrap().values_f64()?, vec![Some(10.0)]);
+    assert_eq!(mdf.channel("Pole").unwrap().values_f64()?, vec![Some(0.5)]);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! CSV export of selected channels on a shared (optionally resampled) time base.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::export::csv::{write_group_to_csv, write_group_to_csv_writer, CsvExportOptions};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// `Time` = 0.0, 0.5, ..., 2.0; `Speed` = 10 * i (invalid at i = 2);
+/// `Gear` = value-to-text of i % 2.
+fn write_source(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let speed = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    let gear = w.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    w.add_value_to_text_conversion(&[(0, "N"), (1, "First, low")], "?", Some(&gear))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..5u64 {
+        w.write_record_with_validity(&cg, &[
+            Some(DecodedValue::Float(i as f64 * 0.5)),
+            (i != 2).then_some(DecodedValue::UnsignedInteger(i * 10)),
+            Some(DecodedValue::UnsignedInteger(i % 2)),
+        ])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+fn export(mdf: &MDF, options: &CsvExportOptions) -> Result<String, MdfError> {
+    let mut out = Vec::new();
+    write_group_to_csv_writer(mdf, 0, &mut out, options)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn csv_export_records_and_resampled() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("csv_source.mf4");
+    write_source(path.to_str().unwrap())?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+
+    assert_eq!(
+        export(&mdf, &CsvExportOptions::default())?,
+        "Time,Speed,Gear\n0,0,N\n0.5,10,\"First, low\"\n1,,N\n1.5,30,\"First, low\"\n2,40,N\n"
+    );
+
+    let resampled = CsvExportOptions {
+        channels: vec!["Speed".into()],
+        resample_interval: Some(0.75),
+        float_precision: Some(2),
+        delimiter: ';',
+        ..Default::default()
+    };
+    // 0.75 lies between 10 and an invalid sample; exact hits keep integers.
+    assert_eq!(export(&mdf, &resampled)?, "Time;Speed\n0.00;0\n0.75;\n1.50;30\n");
+
+    let csv_path = dir.path().join("out.csv");
+    let options = CsvExportOptions { channels: vec!["Gear".into(), "Time".into()], ..Default::default() };
+    assert_eq!(write_group_to_csv(&mdf, 0, csv_path.to_str().unwrap(), &options)?, 5);
+    let text = std::fs::read_to_string(&csv_path)?;
+    assert_eq!(text.lines().nth(4), Some("1.5,\"First, low\",1.5"));
+
+    let missing = CsvExportOptions { channels: vec!["Missing".into()], ..Default::default() };
+    assert!(export(&mdf, &missing).is_err());
+    let bad_interval = CsvExportOptions { resample_interval: Some(0.0), ..Default::default() };
+    assert!(export(&mdf, &bad_interval).is_err());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! `cut_mdf_by_time` takes its window in seconds and resolves it against the
+//! master channel of every channel group separately, so groups recorded at
+//! different rates each keep exactly the records inside the window.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::cut::cut_mdf_by_time;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// Add a `Time` + `Value` group sampled every `period_ms` for `records` records.
+fn write_group(w: &mut MdfWriter, value_name: &str, period_ms: u64, records: u64) -> Result<(), MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |c| {
+        c.data_type = DataType::FloatLE;
+        c.bit_count = 64;
+        c.name = Some("Time".into());
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), |c| {
+        c.data_type = DataType::UnsignedIntegerLE;
+        c.bit_count = 32;
+        c.name = Some(value_name.into());
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..records {
+        w.write_record(&cg, &[
+            DecodedValue::Float((i * period_ms) as f64 / 1000.0),
+            DecodedValue::UnsignedInteger(i),
+        ])?;
+    }
+    w.finish_data_block(&cg)
+}
+
+#[test]
+fn cut_by_time_handles_groups_with_different_rates() -> Result<(), MdfError> {
+    let tmp = std::env::temp_dir();
+    let inp = tmp.join("cut_rates_in.mf4");
+    let out = tmp.join("cut_rates_out.mf4");
+
+    let mut w = MdfWriter::new(inp.to_str().unwrap())?;
+    w.init_mdf_file()?;
+    write_group(&mut w, "Fast", 10, 1000)?;
+    write_group(&mut w, "Slow", 100, 100)?;
+    w.finalize()?;
+
+    cut_mdf_by_time(inp.to_str().unwrap(), out.to_str().unwrap(), 1.25, 8.0)?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    // 1.25 s ..= 8.0 s: records 125..=800 at 10 ms, 13..=80 at 100 ms.
+    for (name, first, last) in [("Fast", 125u64, 800u64), ("Slow", 13, 80)] {
+        let values = mdf.channel(name).unwrap().values()?;
+        let expected: Vec<_> = (first..=last)
+            .map(|i| Some(DecodedValue::UnsignedInteger(i)))
+            .collect();
+        assert_eq!(values, expected, "{name} group");
+    }
+    for group in mdf.channel_groups() {
+        let times = group.channels()[0].values_as_f64()?;
+        assert!(times.iter().all(|t| (1.25..=8.0).contains(t)));
+    }
+
+    std::fs::remove_file(&inp)?;
+    std::fs::remove_file(&out)?;
+    Ok(())
+}
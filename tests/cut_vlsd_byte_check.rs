@@ -99,9 +99,10 @@ fn build_source(path: &str) -> Result<Vec<Vec<u8>>, MdfError> {
 /// fragments referenced by its `##DT`/`##DL` chain.
 fn collect_records(mdf: &MdfFile, dg_idx: usize, record_size: usize) -> Vec<Vec<u8>> {
     let dg = &mdf.data_groups[dg_idx];
-    let blocks = dg.data_blocks(&mdf.mmap).expect("data_blocks");
+    let blocks = dg.data_blocks(&mdf.mmap);
     let mut out = Vec::new();
     for db in blocks {
+        let db = db.expect("data_blocks");
         for chunk in db.data.chunks_exact(record_size) {
             out.push(chunk.to_vec());
         }
@@ -0,0 +1,112 @@
+//! Cutting by a condition on record values with `cut::cut_where`.
+
+use std::path::{Path, PathBuf};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::cut::cut_where;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(name)
+}
+
+/// An `Engine` group with a converted `EngineSpeed` that is 0 for every
+/// third record and a VLSD `Note`, and a `Gps` group without `EngineSpeed`.
+fn write_file(path: &Path) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+
+    let engine = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&engine, "Engine")?;
+    let time = w.add_channel(&engine, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let speed = w.add_channel(&engine, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("EngineSpeed".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_linear_conversion(0.0, 10.0, Some("rpm"), Some(&speed))?;
+    w.add_channel(&engine, Some(&speed), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Note".into());
+        ch.channel_type = 1;
+        ch.data = 1;
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&engine, 0)?;
+    for i in 0..9u64 {
+        let raw = if i % 3 == 0 { 0 } else { i };
+        w.write_record(
+            &engine,
+            &[
+                DecodedValue::Float(i as f64),
+                DecodedValue::UnsignedInteger(raw),
+                DecodedValue::String(format!("note {i}")),
+            ],
+        )?;
+    }
+    w.finish_data_block(&engine)?;
+
+    let gps = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&gps, "Gps")?;
+    w.add_channel(&gps, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Latitude".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&gps, 0)?;
+    for i in 0..5 {
+        w.write_record(&gps, &[DecodedValue::Float(48.0 + i as f64)])?;
+    }
+    w.finish_data_block(&gps)?;
+    w.finalize()
+}
+
+#[test]
+fn keeps_records_matching_the_predicate() -> Result<(), MdfError> {
+    let input = temp("cut_where_in.mf4");
+    let output = temp("cut_where_out.mf4");
+    write_file(&input)?;
+
+    cut_where(&input, &output, &["EngineSpeed"], |r| r.f64("EngineSpeed").is_some_and(|rpm| rpm > 0.0))?;
+
+    let mdf = MDF::from_file(&output)?;
+    let groups = mdf.channel_groups();
+    let engine = &groups[0];
+    let kept = [1u64, 2, 4, 5, 7, 8];
+    assert_eq!(engine.raw_channel_group().block.cycles_nr, kept.len() as u64);
+    assert_eq!(engine.time_values()?, Some(kept.iter().map(|&i| i as f64).collect()));
+    let speed = engine.channel("EngineSpeed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("rpm"));
+    assert_eq!(speed.values_f64()?, kept.iter().map(|&i| Some(i as f64 * 10.0)).collect::<Vec<_>>());
+    let notes = engine.channel("Note").unwrap().values_string()?;
+    assert_eq!(notes, kept.iter().map(|i| Some(format!("note {i}"))).collect::<Vec<_>>());
+
+    // The group without `EngineSpeed` is copied in full.
+    assert_eq!(groups[1].raw_channel_group().block.cycles_nr, 5);
+
+    for p in [&input, &output] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn unknown_channels_are_rejected() -> Result<(), MdfError> {
+    let input = temp("cut_where_unknown_in.mf4");
+    let output = temp("cut_where_unknown_out.mf4");
+    write_file(&input)?;
+
+    let result = cut_where(&input, &output, &["EngineSpeed", "Throttle"], |_| true);
+    assert!(matches!(result, Err(MdfError::ChannelNotFound { ref name, .. }) if name == "Throttle"));
+
+    std::fs::remove_file(&input)?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! `dataset::Dataset` reads files recorded back-to-back as one recording.
+
+use std::path::{Path, PathBuf};
+
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::dataset::Dataset;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+const START_NS: u64 = 1_700_000_000_000_000_000;
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(name)
+}
+
+/// A file starting at `start_ns` with one `Engine` group holding a float
+/// master and a linearly converted `Speed` for the master values `times`.
+fn write_file(path: &Path, start_ns: u64, times: &[f64]) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    w.set_start_time(start_ns, 0, 0, 0, 0)?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&cg, "Engine")?;
+    let time = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let speed = w.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_linear_conversion(0.0, 2.0, Some("km/h"), Some(&speed))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for &t in times {
+        w.write_record(&cg, &[DecodedValue::Float(t), DecodedValue::UnsignedInteger(t as u64)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+#[test]
+fn files_are_stitched_on_the_first_files_time_base() -> Result<(), MdfError> {
+    let paths = [temp("dataset_a.mf4"), temp("dataset_b.mf4")];
+    // The second file starts 3 s later and repeats the last record of the
+    // first one (at 3 s in the first file's time base).
+    write_file(&paths[0], START_NS, &[0.0, 1.0, 2.0, 3.0])?;
+    write_file(&paths[1], START_NS + 3_000_000_000, &[0.0, 1.0, 2.0])?;
+
+    let dataset = Dataset::open(&paths)?;
+    assert_eq!(dataset.start_time_ns(), Some(START_NS));
+    assert_eq!(dataset.time_range()?, Some((0.0, 5.0)));
+
+    let groups = dataset.channel_groups()?;
+    assert_eq!(groups.len(), 1);
+    let engine = &groups[0];
+    assert_eq!(engine.name()?.as_deref(), Some("Engine"));
+    assert_eq!(engine.parts().len(), 2);
+    assert_eq!(engine.record_count(), 6);
+    assert_eq!(engine.time_values()?, Some(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]));
+
+    let speed = engine.channel("Speed").unwrap();
+    assert!(!speed.is_master());
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.values_f64()?, [0.0, 2.0, 4.0, 6.0, 2.0, 4.0].map(Some).to_vec());
+
+    let signal = engine.signal("Speed")?.unwrap();
+    assert_eq!(signal.timestamps, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+    assert_eq!(signal.values.len(), 6);
+    assert!(engine.signal("Throttle")?.is_none());
+
+    let time = dataset.channel("Time")?.unwrap();
+    assert!(time.is_master());
+    assert_eq!(time.values()?[4], Some(DecodedValue::Float(4.0)));
+
+    for p in &paths {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn different_channel_names_are_rejected() -> Result<(), MdfError> {
+    let first = temp("dataset_layout_a.mf4");
+    let second = temp("dataset_layout_b.mf4");
+    write_file(&first, START_NS, &[0.0])?;
+
+    let mut w = MdfWriter::new(&second)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_record(&cg, &[DecodedValue::Float(0.0)])?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    match Dataset::open(&[&first, &second]) {
+        Err(MdfError::LayoutMismatch(message)) => assert!(message.starts_with("file 1: group 0"), "{message}"),
+        Err(other) => panic!("expected a layout mismatch, got {other:?}"),
+        Ok(_) => panic!("expected a layout mismatch"),
+    }
+    assert!(matches!(Dataset::open(&[] as &[&Path]), Err(MdfError::InvalidIndex { .. })));
+
+    for p in [&first, &second] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! DBC decoding of raw CAN frames into per-message channel groups.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::bus_logging::CanFrame;
+use mf4_rs::dbc::{extract_bus_logging, Dbc, Multiplexing, SignalByteOrder};
+use mf4_rs::error::MdfError;
+use mf4_rs::writer::MdfWriter;
+
+const DBC: &str = r#"
+VERSION ""
+
+BU_: ECU
+
+BO_ 291 Engine: 8 ECU
+ SG_ Speed : 0|16@1+ (0.25,0) [0|16383.75] "rpm" Vector__XXX
+ SG_ Temp : 16|8@1- (1,-40) [-40|87] "degC" Vector__XXX
+ SG_ Pressure : 31|12@0+ (0.1,0) [0|409.5] "bar" Vector__XXX
+
+BO_ 2566851073 Diag: 4 ECU
+ SG_ Page M : 0|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ Volts m1 : 8|16@1+ (0.001,0) [0|65.535] "V" Vector__XXX
+ SG_ Amps m2 : 8|16@1- (0.01,0) [-327.68|327.67] "A" Vector__XXX
+
+CM_ SG_ 291 Speed "Engine speed";
+VAL_ 2566851073 Page 1 "Volts" 2 "Amps" ;
+"#;
+
+#[test]
+fn parse_dbc_messages_and_signals() -> Result<(), MdfError> {
+    let dbc = Dbc::parse(DBC)?;
+    assert_eq!(dbc.messages.len(), 2);
+
+    let engine = dbc.message(0x123, false).unwrap();
+    assert_eq!((engine.name.as_str(), engine.size), ("Engine", 8));
+    let pressure = &engine.signals[2];
+    assert_eq!(pressure.byte_order, SignalByteOrder::BigEndian);
+    assert_eq!((pressure.start_bit, pressure.length, pressure.factor), (31, 12, 0.1));
+    assert!(engine.signals[1].signed);
+    assert_eq!(engine.signals[1].unit, "degC");
+
+    let diag = dbc.message(0x18FF_0A01, true).unwrap();
+    assert_eq!(diag.signals[0].multiplexing, Multiplexing::Multiplexor);
+    assert_eq!(diag.signals[2].multiplexing, Multiplexing::Multiplexed(2));
+
+    // Speed 0x0FA0 = 4000 * 0.25, Temp -10 - 40, Pressure 0x3E8 = 1000 * 0.1.
+    let data = [0xA0, 0x0F, 0xF6, 0x3E, 0x80, 0, 0, 0];
+    assert_eq!(engine.decode(&data), vec![Some(1000.0), Some(-50.0), Some(100.0)]);
+    assert_eq!(diag.decode(&[2, 0x18, 0xFC, 0]), vec![Some(2.0), None, Some(-10.0)]);
+
+    assert!(matches!(
+        Dbc::parse("BO_ 1 Broken: 8 ECU\n SG_ X : 0|8@2+ (1,0) [0|1] \"\" ECU"),
+        Err(MdfError::DbcParseError { line: 2, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn extract_bus_logging_per_message_groups() -> Result<(), MdfError> {
+    let input = std::env::temp_dir().join("dbc_raw_can.mf4");
+    let input = input.to_str().unwrap();
+    let output = std::env::temp_dir().join("dbc_decoded.mf4");
+    let output = output.to_str().unwrap();
+
+    let mut w = MdfWriter::new(input)?;
+    w.init_mdf_file()?;
+    let cg = w.add_can_frame_group(1)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    let frames = [
+        (0.1, 0x123, false, vec![0xA0, 0x0F, 0xF6, 0x3E, 0x80, 0, 0, 0]),
+        (0.2, 0x18FF_0A01, true, vec![1, 0x88, 0x13, 0]),
+        (0.3, 0x123, false, vec![0x40, 0x1F, 0x28, 0x00, 0x00, 0, 0, 0]),
+        (0.4, 0x18FF_0A01, true, vec![2, 0x18, 0xFC, 0]),
+        // Not in the DBC.
+        (0.5, 0x456, false, vec![0xFF]),
+    ];
+    for (timestamp, id, extended, data) in frames {
+        w.write_can_frame(&cg, &CanFrame {
+            timestamp,
+            id,
+            extended,
+            dlc: data.len() as u8,
+            data,
+            ..Default::default()
+        })?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    extract_bus_logging(input, &Dbc::parse(DBC)?, output)?;
+
+    let mdf = MDF::from_file(output)?;
+    assert_eq!(mdf.channel_groups().len(), 2);
+    let engine = mdf.group("Engine").unwrap();
+    assert_eq!(engine.source()?.unwrap().name.as_deref(), Some("CAN1"));
+    assert_eq!(engine.channel("Timestamp").unwrap().values_f64()?, vec![Some(0.1), Some(0.3)]);
+    let speed = engine.channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("rpm"));
+    assert_eq!(speed.values_f64()?, vec![Some(1000.0), Some(2000.0)]);
+    assert_eq!(engine.channel("Temp").unwrap().values_f64()?, vec![Some(-50.0), Some(0.0)]);
+    assert_eq!(engine.channel("Pressure").unwrap().values_f64()?, vec![Some(100.0), Some(0.0)]);
+
+    let diag = mdf.group("Diag").unwrap();
+    let volts: Vec<_> = diag
+        .channel("Volts")
+        .unwrap()
+        .values_with_validity()?
+        .into_iter()
+        .map(|v| v.is_valid)
+        .collect();
+    assert_eq!(volts, vec![true, false]);
+    assert_eq!(diag.channel("Volts").unwrap().values_f64()?[0], Some(5.0));
+    assert_eq!(diag.channel("Amps").unwrap().values_f64()?[1], Some(-10.0));
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
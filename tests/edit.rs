@@ -0,0 +1,101 @@
+//! In-place metadata edits with `MdfEditor`.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::blocks::conversion::{ConversionBlock, ConversionType};
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::edit::MdfEditor;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn linear(offset: f64, factor: f64) -> ConversionBlock {
+    ConversionBlock {
+        header: BlockHeader { id: "##CC".into(), reserved0: 0, block_len: 0, links_nr: 0 },
+        cc_tx_name: None,
+        cc_md_unit: None,
+        cc_md_comment: None,
+        cc_cc_inverse: None,
+        cc_ref: Vec::new(),
+        cc_type: ConversionType::Linear,
+        cc_precision: 0,
+        cc_flags: 0,
+        cc_ref_count: 0,
+        cc_val_count: 2,
+        cc_phy_range_min: None,
+        cc_phy_range_max: None,
+        cc_val: vec![offset, factor],
+        formula: None,
+        resolved_texts: None,
+        resolved_conversions: None,
+        default_conversion: None,
+    }
+}
+
+/// `Spd` and `Rpm` (u16) sharing one `rpm` unit block.
+fn write_input(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let spd = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Spd".into());
+        ch.bit_count = 16;
+    })?;
+    let rpm = w.add_channel(&cg, Some(&spd), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Rpm".into());
+        ch.bit_count = 16;
+    })?;
+    w.write_block_with_id(&TextBlock::new("rpm").to_bytes()?, "tx_unit")?;
+    w.update_block_link(&spd, 72, "tx_unit")?;
+    w.update_block_link(&rpm, 72, "tx_unit")?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..4 {
+        w.write_record(&cg, &[DecodedValue::UnsignedInteger(i), DecodedValue::UnsignedInteger(10 * i)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+#[test]
+fn edit_channel_metadata_in_place() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("edit.mf4");
+    let path = path.to_str().unwrap();
+    write_input(path)?;
+    let size = std::fs::metadata(path)?.len();
+
+    // "Speed" fits into the padded block of "Spd".
+    let mut editor = MdfEditor::open(path)?;
+    editor.set_channel_name(0, "Spd", "Speed")?;
+    assert_eq!(std::fs::metadata(path)?.len(), size);
+
+    // The shared unit block is left alone for the other channel.
+    editor.set_channel_unit(0, "Speed", "km/h")?;
+    editor.set_channel_comment(0, "Speed", "Vehicle speed over ground")?;
+    editor.set_channel_conversion(0, "Rpm", Some(&linear(0.0, 2.0)))?;
+    assert!(std::fs::metadata(path)?.len() > size);
+    assert!(editor.set_channel_name(0, "Spd", "X").is_err());
+    assert!(editor.set_channel_name(1, "Speed", "X").is_err());
+
+    let mdf = MDF::from_file(path)?;
+    let speed = mdf.channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.comment()?.as_deref(), Some("Vehicle speed over ground"));
+    assert_eq!(speed.values_u64()?, vec![Some(0), Some(1), Some(2), Some(3)]);
+    let rpm = mdf.channel("Rpm").unwrap();
+    assert_eq!(rpm.unit()?.as_deref(), Some("rpm"));
+    assert_eq!(rpm.values_f64()?[3], Some(60.0));
+    drop(mdf);
+
+    // The conversion is now only used by Rpm and is replaced in place.
+    let size = std::fs::metadata(path)?.len();
+    let mut editor = MdfEditor::open(path)?;
+    editor.set_channel_conversion(0, "Rpm", Some(&linear(1.0, 1.0)))?;
+    assert_eq!(std::fs::metadata(path)?.len(), size);
+    assert_eq!(MDF::from_file(path)?.channel("Rpm").unwrap().values_f64()?[3], Some(31.0));
+    editor.set_channel_conversion(0, "Rpm", None)?;
+    assert_eq!(MDF::from_file(path)?.channel("Rpm").unwrap().values_f64()?[3], Some(30.0));
+    Ok(())
+}
@@ -253,6 +253,7 @@ fn test_index_serialization_with_resolved_data() -> Result<(), MdfError> {
     let indexed_channel = IndexedChannel {
         name: Some("Test Channel".to_string()),
         unit: Some("V".to_string()),
+        comment: None,
         data_type: DataType::FloatLE,
         byte_offset: 0,
         bit_offset: 0,
@@ -273,10 +274,12 @@ fn test_index_serialization_with_resolved_data() -> Result<(), MdfError> {
         record_count: 1,
         channels: vec![indexed_channel],
         data_blocks: vec![],
+        time_range: None,
     };
     
     let index = MdfIndex {
         file_size: 1024,
+        header_digest: None,
         start_time_ns: None,
         channel_groups: vec![indexed_group],
         source: None,
@@ -0,0 +1,59 @@
+use mf4_rs::api::event::EventScope;
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::event_block::EventType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+#[test]
+fn events_round_trip() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("events_round_trip.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |cg| cg.acq_name = Some("Engine".into()))?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    let rpm = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Rpm".into());
+        ch.bit_count = 16;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        writer.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i * 100)])?;
+    }
+    writer.finish_data_block(&cg)?;
+
+    let lap = writer.add_event("Lap 1", 12.5, EventType::Marker, &[])?;
+    writer.set_event_comment(&lap, "driver pressed the lap button")?;
+    writer.add_event("Overspeed", 80.25, EventType::Trigger, &[cg.into(), rpm.into()])?;
+    assert!(writer.add_event("Bad scope", 1.0, EventType::Marker, &["cn_99".into()]).is_err());
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let events = mdf.events()?;
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].name.as_deref(), Some("Lap 1"));
+    assert_eq!(events[0].comment.as_deref(), Some("driver pressed the lap button"));
+    assert_eq!(events[0].event_type, EventType::Marker);
+    assert_eq!(events[0].sync_type, 1);
+    assert!((events[0].time - 12.5).abs() < 1e-9);
+    assert!(events[0].scope.is_empty());
+
+    assert_eq!(events[1].name.as_deref(), Some("Overspeed"));
+    assert_eq!(events[1].comment, None);
+    assert_eq!(events[1].event_type, EventType::Trigger);
+    assert!((events[1].time - 80.25).abs() < 1e-9);
+    assert_eq!(events[1].scope, vec![
+        EventScope::ChannelGroup(Some("Engine".into())),
+        EventScope::Channel(Some("Rpm".into())),
+    ]);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Extracting a subset of channels with `filter::extract_channels`.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::filter::{extract_channels, ChannelSelection};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::validate::validate_file;
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+/// Writes an `Engine` group with `Time`, `Rpm`, `Temp` and the VLSD `Frame`,
+/// and a `Gearbox` group with `Time` and `Gear`.
+fn write_source(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let engine = w.add_channel_group(None, |cg| cg.acq_name = Some("Engine".into()))?;
+    let t = w.add_channel(&engine, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let rpm = w.add_channel(&engine, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Rpm".into());
+        ch.bit_count = 16;
+    })?;
+    let temp = w.add_channel(&engine, Some(&rpm), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 8;
+    })?;
+    w.add_channel(&engine, Some(&temp), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Frame".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+
+    let gearbox = w.add_channel_group(None, |cg| cg.acq_name = Some("Gearbox".into()))?;
+    let t = w.add_channel(&gearbox, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&gearbox, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+
+    w.start_data_block_for_cg(&engine, 0)?;
+    // Only one group at a time can stream uncompressed blocks.
+    w.set_compression(CompressionMethod::Zlib)?;
+    w.start_data_block_for_cg(&gearbox, 0)?;
+    for i in 0..100u64 {
+        w.write_record(
+            &engine,
+            &[
+                DecodedValue::Float(i as f64),
+                DecodedValue::UnsignedInteger(1000 + i),
+                DecodedValue::SignedInteger(i as i64 - 50),
+                DecodedValue::ByteArray(vec![i as u8; i as usize % 7]),
+            ],
+        )?;
+        if i % 10 == 0 {
+            w.write_record(&gearbox, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i / 10)])?;
+        }
+    }
+    w.finish_data_block(&engine)?;
+    w.finish_data_block(&gearbox)?;
+    w.finalize()
+}
+
+fn channel_names(mdf: &MDF) -> Result<Vec<Vec<String>>, MdfError> {
+    mdf.channel_groups()
+        .iter()
+        .map(|g| g.channels().iter().map(|c| Ok(c.name()?.unwrap_or_default())).collect())
+        .collect()
+}
+
+#[test]
+fn extract_selected_channels() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let src = dir.path().join("source.mf4");
+    let src = src.to_str().unwrap();
+    let dst = dir.path().join("filtered.mf4");
+    let dst = dst.to_str().unwrap();
+    write_source(src)?;
+
+    extract_channels(src, dst, &ChannelSelection::from_channels(["Temp", "Frame"]))?;
+
+    let report = validate_file(dst)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(dst)?;
+    assert_eq!(channel_names(&mdf)?, vec![vec!["Time", "Temp", "Frame"]]);
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(group.name()?.as_deref(), Some("Engine"));
+    // Time (8 bytes), Temp (1 byte) and the VLSD offset (8 bytes).
+    assert_eq!(group.raw_channel_group().block.samples_byte_nr, 17);
+    let temp = mdf.channel("Temp").unwrap().values()?;
+    assert_eq!(temp, (0..100).map(|i| Some(DecodedValue::SignedInteger(i - 50))).collect::<Vec<_>>());
+    let frames = mdf.channel("Frame").unwrap().values()?;
+    assert_eq!(frames.len(), 100);
+    assert_eq!(frames[13], Some(DecodedValue::ByteArray(vec![13; 6])));
+    assert_eq!(frames[14], Some(DecodedValue::ByteArray(vec![])));
+    let time = mdf.channel("Time").unwrap().values()?;
+    assert_eq!(time[99], Some(DecodedValue::Float(99.0)));
+    Ok(())
+}
+
+#[test]
+fn extract_whole_group() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let src = dir.path().join("source.mf4");
+    let src = src.to_str().unwrap();
+    let dst = dir.path().join("gearbox.mf4");
+    let dst = dst.to_str().unwrap();
+    write_source(src)?;
+
+    let mut selection = ChannelSelection::default();
+    selection.groups.insert("Gearbox".into());
+    extract_channels(src, dst, &selection)?;
+
+    let report = validate_file(dst)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(dst)?;
+    assert_eq!(channel_names(&mdf)?, vec![vec!["Time", "Gear"]]);
+    assert_eq!(mdf.channel("Gear").unwrap().values_u64()?, (0..10).map(Some).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn extract_reports_missing_names() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let src = dir.path().join("source.mf4");
+    let src = src.to_str().unwrap();
+    write_source(src)?;
+
+    let dst = dir.path().join("missing.mf4");
+    let selection = ChannelSelection::from_channels(["Rpm", "Boost"]);
+    let err = extract_channels(src, dst.to_str().unwrap(), &selection).unwrap_err();
+    assert!(err.to_string().contains("Boost"), "{err}");
+    assert!(!dst.exists());
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! Finalization of files left unfinalized by an interrupted logger.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::error::MdfError;
+use mf4_rs::finalize::{finalize_file, is_unfinalized};
+use mf4_rs::validate::validate_file;
+use mf4_rs::writer::MdfWriter;
+
+/// An unsorted data group with record ID length 1, written as a logger
+/// would leave it when interrupted: `Fast` (`Time`, record ID 1) and `Slow`
+/// (`Gear`, record ID 2) with zero cycle counters, a `##DT` block at the end
+/// of the file whose length was never updated, and half a record at its end.
+fn write_unfinalized(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let dg = w.add_data_group(None)?;
+    let fast = w.add_channel_group_with_dg(&dg, None, |cg| {
+        cg.record_id = 1;
+        cg.samples_byte_nr = 8;
+    })?;
+    let slow = w.add_channel_group_with_dg(&dg, Some(&fast), |cg| {
+        cg.record_id = 2;
+        cg.samples_byte_nr = 2;
+    })?;
+    let t = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&slow, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 16;
+    })?;
+    let dg_pos = w.get_block_position(&dg).unwrap() as usize;
+    w.finalize()?;
+
+    let mut bytes = std::fs::read(path)?;
+    // dg_rec_id_size lives at offset 56 of the ##DG block.
+    bytes[dg_pos + 56] = 1;
+    while bytes.len() % 8 != 0 {
+        bytes.push(0);
+    }
+    let dt_pos = bytes.len() as u64;
+    bytes[dg_pos + 40..dg_pos + 48].copy_from_slice(&dt_pos.to_le_bytes());
+    let header = BlockHeader { id: "##DT".into(), reserved0: 0, block_len: 24, links_nr: 0 };
+    bytes.extend_from_slice(&header.to_bytes()?);
+    for i in 0..6u16 {
+        bytes.push(1);
+        bytes.extend_from_slice(&(i as f64 * 0.5).to_le_bytes());
+        if i % 2 == 0 {
+            bytes.push(2);
+            bytes.extend_from_slice(&(i / 2 + 1).to_le_bytes());
+        }
+    }
+    bytes.extend_from_slice(&[1, 0, 0]);
+
+    bytes[0..8].copy_from_slice(b"UnFinMDF");
+    // Cycle counters and the last ##DT length need an update.
+    bytes[60..62].copy_from_slice(&0x05u16.to_le_bytes());
+    std::fs::write(path, &bytes)?;
+    Ok(())
+}
+
+#[test]
+fn finalize_unfinalized_file() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("unfinalized.mf4");
+    let input = input.to_str().unwrap();
+    let output = dir.path().join("finalized.mf4");
+    let output = output.to_str().unwrap();
+    write_unfinalized(input)?;
+    assert!(is_unfinalized(&std::fs::read(input)?));
+
+    finalize_file(input, output)?;
+    let bytes = std::fs::read(output)?;
+    assert!(!is_unfinalized(&bytes));
+    assert_eq!(&bytes[60..64], &[0; 4]);
+    let report = validate_file(output)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+
+    let mdf = MDF::from_file(output)?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups[0].raw_channel_group().block.cycles_nr, 6);
+    assert_eq!(groups[1].raw_channel_group().block.cycles_nr, 3);
+    assert_eq!(groups[0].channel("Time").unwrap().values_f64()?[5], Some(2.5));
+    assert_eq!(groups[1].channel("Gear").unwrap().values_u64()?, vec![Some(1), Some(2), Some(3)]);
+
+    // Unfinalized files are finalized in a copy-on-write mapping when
+    // opened, leaving the file itself unfinalized.
+    let unfinalized = std::fs::read(input)?;
+    let mdf = MDF::from_file(input)?;
+    assert_eq!(mdf.channel("Gear").unwrap().values_u64()?.len(), 3);
+    assert_eq!(mdf.channel_groups()[0].raw_channel_group().block.cycles_nr, 6);
+    drop(mdf);
+    assert_eq!(std::fs::read(input)?, unfinalized);
+
+    // Flags this crate cannot act on are rejected.
+    let mut bytes = std::fs::read(input)?;
+    bytes[60] |= 0x20;
+    std::fs::write(input, &bytes)?;
+    assert!(finalize_file(input, output).is_err());
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Channel name pattern search with `find_channels_matching`.
+
+use std::path::Path;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::MdfIndex;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::pattern::NamePattern;
+use mf4_rs::writer::MdfWriter;
+
+/// Two groups of hierarchically named channels.
+fn write_file(path: &Path) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    for names in [
+        ["Time", "ECU1.Engine.Speed", "ECU1.Engine.Torque"],
+        ["Time", "ECU2.Engine.Speed", "ECU1.Gearbox.Gear"],
+    ] {
+        let cg = w.add_channel_group(None, |_| {})?;
+        let mut prev = None;
+        for (i, name) in names.iter().enumerate() {
+            let id = w.add_channel(&cg, prev.as_ref(), |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(name.to_string());
+                ch.byte_offset = 8 * i as u32;
+                ch.bit_count = 64;
+            })?;
+            if i == 0 {
+                w.set_time_channel(&id)?;
+            }
+            prev = Some(id);
+        }
+        w.start_data_block_for_cg(&cg, 0)?;
+        w.write_record(&cg, &[DecodedValue::Float(0.0), DecodedValue::Float(1.0), DecodedValue::Float(2.0)])?;
+        w.finish_data_block(&cg)?;
+    }
+    w.finalize()
+}
+
+#[test]
+fn globs_match_whole_names() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("find_channels_glob.mf4");
+    write_file(&path)?;
+    let mdf = MDF::from_file(&path)?;
+    let index = MdfIndex::from_file(&path)?;
+
+    let engine = vec![
+        (0, 1, "ECU1.Engine.Speed".to_string()),
+        (0, 2, "ECU1.Engine.Torque".to_string()),
+        (1, 1, "ECU2.Engine.Speed".to_string()),
+    ];
+    assert_eq!(mdf.find_channels_matching("ECU?.Engine.*")?, engine);
+    assert_eq!(index.find_channels_matching("ECU?.Engine.*"), engine);
+
+    let speeds: Vec<String> = mdf.find_channels_matching("*Speed")?.into_iter().map(|(_, _, n)| n).collect();
+    assert_eq!(speeds, ["ECU1.Engine.Speed", "ECU2.Engine.Speed"]);
+    assert_eq!(index.find_channels_matching("ECU[!1].*").len(), 1);
+    assert_eq!(index.find_channels_matching("ECU[1-2].*.Gear")[0].0, 1);
+    assert_eq!(index.find_channels_matching("Time").len(), 2);
+    assert!(index.find_channels_matching("Engine").is_empty());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn glob_syntax() {
+    let matches = |pattern: &str, name: &str| NamePattern::glob(pattern).is_match(name);
+    assert!(matches("*", ""));
+    assert!(matches("a*b*c", "aXXbYYbc"));
+    assert!(!matches("a*b", "aXbY"));
+    assert!(matches("[]x]", "]"));
+    assert!(matches("[a-", "[a-"));
+    assert!(matches("Speed[0-9]", "Speed7"));
+    assert!(!matches("Speed[^0-9]", "Speed7"));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regexes_match_anywhere() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("find_channels_regex.mf4");
+    write_file(&path)?;
+    let mdf = MDF::from_file(&path)?;
+
+    let regex = regex::Regex::new(r"Engine\.(Speed|Torque)$").unwrap();
+    assert_eq!(mdf.find_channels_matching(&regex)?.len(), 3);
+    assert_eq!(MdfIndex::from_file(&path)?.find_channels_matching(regex).len(), 3);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! 16-bit (half precision) float channels.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::writer::{ChannelGroupId, ColumnData, MdfWriter};
+
+/// Inputs and the half-precision values they round to.
+const SAMPLES: [(f64, f64); 8] = [
+    (1.0, 1.0),
+    (-2.5, -2.5),
+    (65504.0, 65504.0),
+    // Nearest half to 0.1.
+    (0.1, 0.0999755859375),
+    // Exactly between 1 and the next half: ties to even.
+    (1.000_488_281_25, 1.0),
+    // Smallest subnormal.
+    (5.960_464_477_539_063e-8, 5.960_464_477_539_063e-8),
+    (1e-9, 0.0),
+    (1e6, f64::INFINITY),
+];
+
+fn half_group(w: &mut MdfWriter<Cursor<Vec<u8>>>) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let le = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("HalfLE".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_channel(&cg, Some(&le), |ch| {
+        ch.data_type = DataType::FloatBE;
+        ch.name = Some("HalfBE".into());
+        ch.bit_count = 16;
+    })?;
+    Ok(cg)
+}
+
+fn check(mdf: &MDF) -> Result<(), MdfError> {
+    let expected: Vec<_> = SAMPLES.iter().map(|&(_, half)| Some(half)).collect();
+    assert_eq!(mdf.channel("HalfLE").unwrap().values_f64()?, expected);
+    assert_eq!(mdf.channel("HalfBE").unwrap().values_f64()?, expected);
+    Ok(())
+}
+
+#[test]
+fn half_floats_round_trip_through_write_records_f64() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = half_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    let records: Vec<[f64; 3]> = SAMPLES.iter().enumerate().map(|(i, &(v, _))| [i as f64, v, v]).collect();
+    w.write_records_f64(&cg, records.iter().map(|r| &r[..]))?;
+    w.finish_data_block(&cg)?;
+
+    let bytes = w.into_inner()?.into_inner();
+    // Record 0: time 0.0, then 1.0 as 0x3C00 little- and big-endian.
+    assert!(bytes.windows(12).any(|win| win == [0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x3C, 0x3C, 0x00]));
+    check(&MDF::from_bytes(bytes)?)
+}
+
+#[test]
+fn half_floats_round_trip_through_write_columns() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = half_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    let time: Vec<f64> = (0..SAMPLES.len()).map(|i| i as f64).collect();
+    let values: Vec<f64> = SAMPLES.iter().map(|&(v, _)| v).collect();
+    w.write_columns(&cg, &[ColumnData::F64(&time), ColumnData::F64(&values), ColumnData::F64(&values)])?;
+    w.finish_data_block(&cg)?;
+
+    check(&MDF::from_bytes(w.into_inner()?.into_inner())?)
+}
@@ -1,7 +1,7 @@
 use mf4_rs::writer::MdfWriter;
 use mf4_rs::blocks::common::DataType;
 use mf4_rs::parsing::decoder::DecodedValue;
-use mf4_rs::index::MdfIndex;
+use mf4_rs::index::{ByteRangeReader, MdfIndex, RangeRequest, SliceRangeReader};
 use mf4_rs::api::mdf::MDF;
 use mf4_rs::error::MdfError;
 use std::fs;
@@ -293,6 +293,257 @@ fn test_byte_ranges() -> Result<(), MdfError> {
     Ok(())
 }
 
+#[test]
+fn test_byte_ranges_with_invalidation() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("byte_ranges_invalidation.mf4");
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let t_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t_id)?;
+    writer.add_channel(&cg_id, Some(&t_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".to_string());
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    let speeds = [Some(10), None, Some(30), None, Some(50)];
+    for (i, speed) in speeds.iter().enumerate() {
+        writer.write_record_with_validity(&cg_id, &[
+            Some(DecodedValue::Float(i as f64)),
+            speed.map(DecodedValue::UnsignedInteger),
+        ])?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    // Records are 8 bytes of time, 4 of speed and 1 invalidation byte.
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let data = index.byte_ranges("Speed")?;
+    let with_inval = index.byte_ranges_with_invalidation("Speed")?;
+    assert_eq!(data.len(), 1);
+    assert_eq!(with_inval[0].0, data[0].0);
+    assert_eq!(with_inval[0].1, data[0].1 + 1);
+    // Time has no invalidation bit, so its ranges do not change.
+    assert_eq!(index.byte_ranges_with_invalidation("Time")?, index.byte_ranges("Time")?);
+
+    // The fetched bytes carry each sample's invalidation bit.
+    let bytes = fs::read(&mdf_path)?;
+    let (offset, length) = with_inval[0];
+    let fetched = &bytes[offset as usize..(offset + length) as usize];
+    let valid: Vec<bool> = (0..speeds.len()).map(|i| fetched[i * 13 + 4] & 1 == 0).collect();
+    assert_eq!(valid, speeds.iter().map(Option::is_some).collect::<Vec<_>>());
+
+    let window = index.byte_ranges_for_records_with_invalidation("Speed", 1, 2)?;
+    assert_eq!(window, vec![(offset + 13, 13 + 5)]);
+    let plan = index.plan_reads(&[RangeRequest::channel("Speed").records(1..3).with_invalidation()], 0)?;
+    assert_eq!(plan.reads, window);
+
+    // The index reader applies the same validity as the mmap reader.
+    let mdf = MDF::from_file(mdf_path.to_str().unwrap())?;
+    let mut reader = index.open_file(mdf_path.to_str().unwrap())?;
+    assert_eq!(reader.values("Speed")?, mdf.channel("Speed").unwrap().values()?);
+    assert_eq!(reader.values_for_records("Speed", 1, 2)?, vec![None, Some(DecodedValue::UnsignedInteger(30))]);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_group_time_range() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("group_time_range.mf4");
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let timed = writer.add_channel_group(None, |cg| cg.acq_name = Some("Timed".into()))?;
+    let t_id = writer.add_channel(&timed, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.set_time_channel(&t_id)?;
+    // Raw ticks of 1 ms.
+    writer.add_linear_conversion(0.0, 0.001, Some("s"), Some(&t_id))?;
+    let untimed = writer.add_channel_group(Some(&timed), |cg| cg.acq_name = Some("Untimed".into()))?;
+    writer.add_channel(&untimed, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 8;
+    })?;
+    let empty = writer.add_channel_group(Some(&untimed), |cg| cg.acq_name = Some("Empty".into()))?;
+    let e_id = writer.add_channel(&empty, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("EmptyTime".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&e_id)?;
+
+    writer.start_data_block_for_cg(&timed, 0)?;
+    for tick in (500..2500).step_by(10) {
+        writer.write_record(&timed, &[DecodedValue::UnsignedInteger(tick)])?;
+    }
+    writer.finish_data_block(&timed)?;
+    writer.start_data_block_for_cg(&untimed, 0)?;
+    writer.write_record(&untimed, &[DecodedValue::UnsignedInteger(1)])?;
+    writer.finish_data_block(&untimed)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    assert_eq!(index.time_range("Timed"), Some((0.5, 2.49)));
+    let duration = index.group("Timed").unwrap().duration().unwrap();
+    assert!((duration - 1.99).abs() < 1e-12);
+    assert_eq!(index.time_range("Untimed"), None);
+    assert_eq!(index.time_range("Empty"), None);
+    assert_eq!(index.time_range("Missing"), None);
+
+    // Stored with the index and built the same way from range reads.
+    let restored = MdfIndex::from_json(&index.to_json()?)?;
+    assert_eq!(restored.time_range("Timed"), Some((0.5, 2.49)));
+    let remote = MdfIndex::from_reader(&mut SliceRangeReader::new(fs::read(&mdf_path)?))?;
+    assert_eq!(remote.time_range("Timed"), Some((0.5, 2.49)));
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_decode_channel_from_fetched_ranges() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("decode_from_ranges.mf4");
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    // Small data blocks, so the channel spans several ranges.
+    writer.set_data_block_size(24 + 4 * 13)?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg_id, "Vehicle")?;
+    let t_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t_id)?;
+    let speed_id = writer.add_channel(&cg_id, Some(&t_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".to_string());
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    writer.add_linear_conversion(0.0, 0.5, Some("km/h"), Some(&speed_id))?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..10u64 {
+        writer.write_record_with_validity(&cg_id, &[
+            Some(DecodedValue::Float(i as f64)),
+            (i % 3 != 1).then_some(DecodedValue::UnsignedInteger(i * 10)),
+        ])?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let path = mdf_path.to_str().unwrap();
+    let index = MdfIndex::from_file(path)?;
+    let data = fs::read(&mdf_path)?;
+    let fetch = |ranges: Vec<(u64, u64)>| -> Vec<((u64, u64), Vec<u8>)> {
+        ranges
+            .into_iter()
+            .map(|(offset, length)| ((offset, length), data[offset as usize..(offset + length) as usize].to_vec()))
+            .collect()
+    };
+
+    // With the invalidation bytes the result matches the reader, validity
+    // and conversion included.
+    let ranges = fetch(index.byte_ranges_with_invalidation("Speed")?);
+    assert!(ranges.len() > 1);
+    let decoded = index.decode_channel_from_ranges(Some("Vehicle"), "Speed", &ranges)?;
+    let mut reader = index.open_file(path)?;
+    assert_eq!(decoded, reader.values("Speed")?);
+    assert_eq!(decoded[1], None);
+
+    // Without them every sample decodes as valid, the invalid one as the
+    // zero stored for it.
+    let window = fetch(index.byte_ranges_for_records("Speed", 3, 3)?);
+    let decoded = index.decode_channel_from_ranges(None, "Speed", &window)?;
+    assert_eq!(decoded, [15.0, 0.0, 25.0].map(|v| Some(DecodedValue::Float(v))));
+
+    // Ranges that do not line up with the channel's records are rejected.
+    let ((offset, length), bytes) = &ranges[0];
+    assert!(index.decode_channel_from_ranges(None, "Speed", &[((offset + 1, length - 1), &bytes[1..])]).is_err());
+    assert!(index.decode_channel_from_ranges(None, "Speed", &[((*offset, *length), &bytes[1..])]).is_err());
+    assert!(index.decode_channel_from_ranges(None, "Speed", &[((0, 4), &data[..4])]).is_err());
+    assert!(index.decode_channel_from_ranges(Some("Nope"), "Speed", &ranges).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_plan_reads_merges_requests() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("plan_reads_test.mf4");
+    let _ = fs::remove_file(&mdf_path);
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let ch1_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Ch1".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.add_channel(&cg_id, Some(&ch1_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Ch2".to_string());
+        ch.bit_count = 16;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..50 {
+        writer.write_record(&cg_id, &[
+            DecodedValue::UnsignedInteger(i * 100),
+            DecodedValue::UnsignedInteger(i * 10),
+        ])?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let requests = [
+        RangeRequest::channel("Ch1"),
+        RangeRequest::channel("Ch2").records(10..20),
+        RangeRequest::channel("Ch1"),
+        RangeRequest::channel("Ch2").records(5..5),
+    ];
+    let plan = index.plan_reads(&requests, 0)?;
+
+    // Every range lives in one contiguous data block: a single read.
+    assert_eq!(plan.reads.len(), 1);
+    assert_eq!(plan.requests.len(), requests.len());
+    assert!(plan.requests[3].is_empty());
+
+    let data = fs::read(&mdf_path)?;
+    let fetched = plan.fetch(&mut SliceRangeReader::new(data.clone()))?;
+    for (i, request) in requests.iter().enumerate() {
+        let expected = match &request.records {
+            None => index.byte_ranges(&request.channel)?,
+            Some(r) if r.is_empty() => Vec::new(),
+            Some(r) => index.byte_ranges_for_records(&request.channel, r.start, r.end - r.start)?,
+        };
+        let got = plan.request_bytes(i, &fetched)?;
+        assert_eq!(got.len(), expected.len());
+        for (bytes, (offset, length)) in got.iter().zip(expected) {
+            assert_eq!(*bytes, &data[offset as usize..(offset + length) as usize]);
+        }
+    }
+
+    assert!(index.plan_reads(&[RangeRequest::channel("Missing")], 0).is_err());
+    assert!(index.plan_reads(&[RangeRequest::channel("Ch1").in_group("Nope")], 0).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
 #[test]
 fn test_byte_ranges_accuracy() -> Result<(), MdfError> {
     let mdf_path = std::env::temp_dir().join("byte_accuracy_test.mf4");
@@ -564,3 +815,344 @@ fn test_channel_group_name_lookup() -> Result<(), MdfError> {
     let _ = fs::remove_file(mdf_path);
     Ok(())
 }
+
+#[test]
+fn test_channel_descriptor() -> Result<(), MdfError> {
+    use mf4_rs::blocks::text_block::TextBlock;
+    use mf4_rs::index::MmapRangeReader;
+
+    let mdf_path = std::env::temp_dir().join("channel_descriptor.mf4");
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let t_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t_id)?;
+    let v_id = writer.add_channel(&cg_id, Some(&t_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Voltage".to_string());
+        ch.bit_count = 16;
+    })?;
+    writer.add_linear_conversion(3.0, 0.5, Some("V"), Some(&v_id))?;
+    writer.write_block_with_id(&TextBlock::new("Battery voltage").to_bytes()?, "tx_v_comment")?;
+    writer.update_block_link(&v_id, 80, "tx_v_comment")?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    writer.write_record(&cg_id, &[DecodedValue::Float(0.0), DecodedValue::UnsignedInteger(4)])?;
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let time = index.channel_descriptor(0, 0).unwrap();
+    assert_eq!(time.name.as_deref(), Some("Time"));
+    assert!(time.is_master);
+    assert_eq!(time.conversion, None);
+
+    let voltage = index.channel_descriptor(0, 1).unwrap();
+    assert_eq!(voltage.unit.as_deref(), Some("V"));
+    assert_eq!(voltage.comment.as_deref(), Some("Battery voltage"));
+    assert_eq!(voltage.data_type, DataType::UnsignedIntegerLE);
+    assert_eq!(voltage.bit_count, 16);
+    assert!(!voltage.is_master);
+    assert_eq!(voltage.conversion.as_deref(), Some("linear: 3 + 0.5 * x"));
+    assert!(index.channel_descriptor(0, 2).is_none());
+    assert!(index.channel_descriptor(1, 0).is_none());
+
+    // The range-reader path stores the same metadata.
+    let remote = MdfIndex::from_reader(&mut MmapRangeReader::new(mdf_path.to_str().unwrap())?)?;
+    assert_eq!(remote.channel_descriptor(0, 1), Some(voltage));
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+fn write_speed_file(path: &std::path::Path, samples: usize) -> Result<(), MdfError> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg_id, "Vehicle")?;
+    let t_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t_id)?;
+    writer.add_channel(&cg_id, Some(&t_id), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Speed".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..samples {
+        writer.write_record(&cg_id, &[
+            DecodedValue::Float(i as f64 * 0.1),
+            DecodedValue::Float(i as f64 * 2.0),
+        ])?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()
+}
+
+#[test]
+fn test_stale_index_after_rewrite() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("stale_index_rewrite.mf4");
+    let _ = fs::remove_file(&mdf_path);
+
+    write_speed_file(&mdf_path, 10)?;
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    assert!(index.header_digest.is_some());
+    assert_eq!(index.read("Speed")?.values.len(), 10);
+
+    // Rewrite the file in place with a different layout.
+    write_speed_file(&mdf_path, 25)?;
+    assert!(matches!(index.read("Speed"), Err(MdfError::StaleIndex(_))));
+    assert!(matches!(
+        index.open_file(mdf_path.to_str().unwrap()),
+        Err(MdfError::StaleIndex(_))
+    ));
+
+    // A freshly built index reads the new file.
+    let fresh = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    assert_eq!(fresh.read("Speed")?.values.len(), 25);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_stale_data_block_header_detected_per_read() -> Result<(), MdfError> {
+    use mf4_rs::index::MmapRangeReader;
+
+    let mdf_path = std::env::temp_dir().join("stale_index_block_header.mf4");
+    let _ = fs::remove_file(&mdf_path);
+
+    write_speed_file(&mdf_path, 10)?;
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+
+    // Clobber the first data block's header without touching the size or
+    // the identification/header blocks.
+    let mut bytes = fs::read(&mdf_path)?;
+    let offset = index.channel_groups[0].data_blocks[0].file_offset as usize;
+    bytes[offset..offset + 4].copy_from_slice(b"##XX");
+    fs::write(&mdf_path, &bytes)?;
+
+    let mut source = MmapRangeReader::new(mdf_path.to_str().unwrap())?;
+    index.verify(&mut source)?;
+    let mut reader = index.open(source);
+    assert!(matches!(reader.values("Speed"), Err(MdfError::StaleIndex(_))));
+    assert!(matches!(reader.values_f64("Speed"), Err(MdfError::StaleIndex(_))));
+    assert!(matches!(index.read("Speed"), Err(MdfError::StaleIndex(_))));
+
+    // Indexes saved without a digest still load and read.
+    let mut json: serde_json::Value = serde_json::from_str(&index.to_json()?).unwrap();
+    json.as_object_mut().unwrap().remove("header_digest");
+    let legacy = MdfIndex::from_json(&json.to_string())?;
+    assert!(legacy.header_digest.is_none());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_data_block_digests_detect_changed_samples() -> Result<(), MdfError> {
+    use mf4_rs::index::{IndexOptions, MmapRangeReader};
+
+    let mdf_path = std::env::temp_dir().join("stale_index_block_digest.mf4");
+    let _ = fs::remove_file(&mdf_path);
+
+    write_speed_file(&mdf_path, 10)?;
+    let path = mdf_path.to_str().unwrap();
+    let hashed = MdfIndex::from_file_with_options(path, IndexOptions { hash_data_blocks: true })?;
+    let plain = MdfIndex::from_file(path)?;
+    assert!(hashed.channel_groups[0].data_blocks.iter().all(|b| b.digest.is_some()));
+    assert!(plain.channel_groups[0].data_blocks.iter().all(|b| b.digest.is_none()));
+    hashed.verify(&mut MmapRangeReader::new(path)?)?;
+
+    // The digests survive a JSON round trip.
+    let restored = MdfIndex::from_json(&hashed.to_json()?)?;
+    restored.verify(&mut MmapRangeReader::new(path)?)?;
+
+    // Change one sample byte; the block header and file size stay the same.
+    let mut bytes = fs::read(&mdf_path)?;
+    let block = &hashed.channel_groups[0].data_blocks[0];
+    let last = (block.file_offset + block.size - 1) as usize;
+    bytes[last] ^= 0xFF;
+    fs::write(&mdf_path, &bytes)?;
+
+    assert!(matches!(
+        restored.verify(&mut MmapRangeReader::new(path)?),
+        Err(MdfError::StaleIndex(_))
+    ));
+    // Without block digests only the header is checked.
+    plain.verify(&mut MmapRangeReader::new(path)?)?;
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+/// Counts the bytes pulled through a [`SliceRangeReader`].
+struct CountingReader {
+    inner: SliceRangeReader,
+    bytes_read: u64,
+}
+
+impl ByteRangeReader for CountingReader {
+    type Error = MdfError;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+        self.bytes_read += length;
+        self.inner.read_range(offset, length)
+    }
+
+    fn size(&mut self) -> Result<Option<u64>, MdfError> {
+        self.inner.size()
+    }
+}
+
+#[test]
+fn test_from_reader_reads_only_metadata() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("streaming_indexer.mf4");
+    write_speed_file(&mdf_path, 100_000)?;
+    let bytes = fs::read(&mdf_path)?;
+    let file_size = bytes.len() as u64;
+
+    let mut reader = CountingReader { inner: SliceRangeReader::new(bytes), bytes_read: 0 };
+    let index = MdfIndex::from_reader(&mut reader)?;
+    assert!(file_size > 1_000_000);
+    assert!(reader.bytes_read < 8 * 1024, "read {} bytes", reader.bytes_read);
+
+    let reference = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    assert_eq!(index.to_json()?, reference.to_json()?);
+    let speeds = index.open(reader.inner).values_f64("Speed")?;
+    assert_eq!(speeds.len(), 100_000);
+    assert_eq!(speeds[99_999], 199_998.0);
+
+    // Readers that cannot report their size need an explicit one.
+    struct NoSize(SliceRangeReader);
+    impl ByteRangeReader for NoSize {
+        type Error = MdfError;
+        fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+            self.0.read_range(offset, length)
+        }
+    }
+    let mut no_size = NoSize(SliceRangeReader::new(fs::read(&mdf_path)?));
+    assert!(MdfIndex::from_reader(&mut no_size).is_err());
+    assert_eq!(MdfIndex::from_range_reader(&mut no_size, file_size)?.to_json()?, reference.to_json()?);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+/// Serves an in-memory file through awaited range reads, counting them.
+struct AsyncSliceReader {
+    data: Vec<u8>,
+    requests: usize,
+}
+
+impl mf4_rs::index::AsyncByteRangeReader for AsyncSliceReader {
+    type Error = MdfError;
+
+    async fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>, MdfError> {
+        tokio::task::yield_now().await;
+        self.requests += 1;
+        self.data
+            .get(offset as usize..(offset + length) as usize)
+            .map(<[u8]>::to_vec)
+            .ok_or(MdfError::TooShortBuffer {
+                actual: self.data.len(),
+                expected: (offset + length) as usize,
+                file: file!(),
+                line: line!(),
+            })
+    }
+}
+
+#[tokio::test]
+async fn test_async_reader_matches_sync_reader() -> Result<(), MdfError> {
+    let mdf_path = std::env::temp_dir().join("async_index_read.mf4");
+    write_speed_file(&mdf_path, 50)?;
+    let data = fs::read(&mdf_path)?;
+    let index = MdfIndex::from_bytes(data.clone())?;
+    let mut sync = index.open(SliceRangeReader::new(data.clone()));
+
+    let mut reader = index.open_async(AsyncSliceReader { data, requests: 0 });
+    assert_eq!(reader.values("Speed").await?, sync.values("Speed")?);
+    assert!(reader.values_f64_in("Missing", "Time").await.is_err());
+    assert_eq!(reader.values_f64("Speed").await?, sync.values_f64("Speed")?);
+    let signal = reader.signal("Speed").await?;
+    assert_eq!(signal.timestamps, sync.signal("Speed")?.timestamps);
+    assert_eq!(signal.values.len(), 50);
+    // One data block per read; the signal fetches it once for both channels.
+    assert_eq!(reader.into_inner().requests, 3);
+    assert!(matches!(
+        index.open_async(AsyncSliceReader { data: Vec::new(), requests: 0 }).values("Speed").await,
+        Err(MdfError::StaleIndex(_))
+    ));
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_record_and_time_window_reads() -> Result<(), MdfError> {
+    use mf4_rs::index::MmapRangeReader;
+
+    // Enough 16-byte records to spill into a second 4 MiB data block.
+    let mdf_path = std::env::temp_dir().join("record_window_read.mf4");
+    write_speed_file(&mdf_path, 300_000)?;
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    assert_eq!(index.channel_groups[0].data_blocks.len(), 2);
+    let mut reader = index.open(MmapRangeReader::new(mdf_path.to_str().unwrap())?);
+
+    // A page straddling the block boundary.
+    let page = reader.values_for_records("Speed", 262_140, 10)?;
+    assert_eq!(page, expected_speeds(262_140..262_150));
+    assert_eq!(reader.values_for_records("Speed", 0, 2)?, expected_speeds(0..2));
+    assert!(reader.values_for_records("Speed", 299_995, 10).is_err());
+    // The same page through the index's own source.
+    assert_eq!(index.read_records_in("Vehicle", "Speed", 262_140, 10)?, page);
+
+    assert_eq!(reader.records_in_time_window("Speed", 0.95, 1.55)?, 10..16);
+    assert_eq!(reader.records_in_time_window("Speed", -5.0, -1.0)?, 0..0);
+    let window = reader.signal_in_time_window("Speed", 26_213.95, 26_215.05)?;
+    assert_eq!(window.values, expected_speeds(262_140..262_151));
+    assert_eq!(window.timestamps.len(), 11);
+    assert!((window.timestamps[0] - 26_214.0).abs() < 1e-6);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+fn expected_speeds(records: std::ops::Range<u64>) -> Vec<Option<DecodedValue>> {
+    records.map(|i| Some(DecodedValue::Float(i as f64 * 2.0))).collect()
+}
+
+#[test]
+fn test_channel_statistics() -> Result<(), MdfError> {
+    // Two data blocks, folded into the statistics one at a time.
+    let mdf_path = std::env::temp_dir().join("channel_statistics.mf4");
+    let samples = 300_000u64;
+    write_speed_file(&mdf_path, samples as usize)?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let stats = index.channel_statistics_in("Vehicle", "Speed")?;
+    assert_eq!(stats.count, samples);
+    assert_eq!((stats.min, stats.max), (0.0, 2.0 * (samples - 1) as f64));
+    assert!((stats.mean - (samples - 1) as f64).abs() < 1e-6);
+    // Speed is 2i, so its variance is 4 (n^2 - 1) / 12.
+    let n = samples as f64;
+    assert!((stats.std - (4.0 * (n * n - 1.0) / 12.0).sqrt()).abs() < 1e-6);
+    assert!(index.channel_statistics("Missing").is_err());
+
+    let mdf = MDF::from_file(mdf_path.to_str().unwrap())?;
+    let direct = mdf.channel("Speed").unwrap().statistics()?;
+    assert_eq!(direct.count, stats.count);
+    assert_eq!((direct.min, direct.max), (stats.min, stats.max));
+    assert!((direct.mean - stats.mean).abs() < 1e-6);
+    assert!((direct.std - stats.std).abs() < 1e-6);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{LiveUpdate, MdfWriter};
+
+/// Record count and decoded value count a reader sees in the file right now.
+fn visible(path: &std::path::Path) -> Result<(u64, usize), MdfError> {
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let group = &mdf.channel_groups()[0];
+    let cycles = group.raw_channel_group().block.cycles_nr;
+    let values = group.channels()[1].values()?;
+    Ok((cycles, values.len()))
+}
+
+#[test]
+fn live_readers_follow_growing_file() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("live_update_follow.mf4");
+    let _ = std::fs::remove_file(&path);
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&t)?;
+    writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_live_update(Some(LiveUpdate { every_records: Some(100), interval: None }))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+
+    let mut written = 0u64;
+    let mut write = |writer: &mut MdfWriter, n: u64| -> Result<(), MdfError> {
+        for _ in 0..n {
+            writer.write_record(&cg, &[
+                DecodedValue::Float(written as f64 * 0.01),
+                DecodedValue::UnsignedInteger(written),
+            ])?;
+            written += 1;
+        }
+        Ok(())
+    };
+
+    // Nothing published until the first threshold.
+    write(&mut writer, 99)?;
+    assert_eq!(visible(&path)?, (0, 0));
+    write(&mut writer, 151)?;
+    assert_eq!(visible(&path)?, (200, 200));
+
+    // Cross a DT block boundary (16-byte records, 4 MiB blocks); the new
+    // block must be reachable before the writer finishes.
+    write(&mut writer, 300_000)?;
+    assert_eq!(visible(&path)?, (300_200, 300_200));
+
+    writer.publish_progress(&cg)?;
+    assert_eq!(visible(&path)?, (300_250, 300_250));
+
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let values = mdf.channel_groups()[0].channels()[1].values()?;
+    assert_eq!(values.len(), 300_250);
+    assert_eq!(values[300_249], Some(DecodedValue::UnsignedInteger(300_249)));
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[test]
+fn live_update_rejects_zero_threshold() -> Result<(), MdfError> {
+    let mut writer = MdfWriter::new_from_writer(std::io::Cursor::new(Vec::new()));
+    assert!(writer
+        .set_live_update(Some(LiveUpdate { every_records: Some(0), interval: None }))
+        .is_err());
+    writer.set_live_update(Some(LiveUpdate {
+        every_records: None,
+        interval: Some(Duration::from_millis(50)),
+    }))?;
+    writer.set_live_update(None)
+}
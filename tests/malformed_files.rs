@@ -0,0 +1,167 @@
+//! Truncated, corrupted and cyclically linked files must fail with an
+//! `MdfError`, never panic or abort the process.
+//!
+//! The corruption test is a fixed-seed stand-in for the cargo-fuzz target in
+//! `fuzz/`, so the cases it finds stay covered on every `cargo test`.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::{MdfIndex, SliceRangeReader};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+/// A small file with a master, a converted, a string and a VLSD channel,
+/// split over several data blocks behind a `##DL` list.
+fn sample_file(compression: Option<CompressionMethod>) -> Result<Vec<u8>, MdfError> {
+    let mut writer = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    writer.init_mdf_file()?;
+    if let Some(method) = compression {
+        writer.set_compression(method)?;
+    }
+    writer.set_data_block_size(64)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let raw = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Raw".into());
+        ch.bit_count = 16;
+    })?;
+    writer.add_linear_conversion(1.0, 2.0, Some("V"), Some(&raw))?;
+    let text = writer.add_channel(&cg, Some(&raw), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Text".into());
+        ch.bit_count = 32;
+    })?;
+    writer.add_channel(&cg, Some(&text), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Payload".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..20u64 {
+        writer.write_record(&cg, &[
+            DecodedValue::Float(i as f64),
+            DecodedValue::UnsignedInteger(i),
+            DecodedValue::String("ab".into()),
+            DecodedValue::ByteArray(vec![1, 2, 3]),
+        ])?;
+    }
+    writer.finish_data_block(&cg)?;
+    Ok(writer.into_inner()?.into_inner())
+}
+
+/// Read everything the file offers through the mmap and the range-reader
+/// paths, discarding the results: only panics matter here.
+fn read_everything(bytes: &[u8]) {
+    if let Ok(index) = MdfIndex::from_bytes(bytes.to_vec()) {
+        let _ = index.to_json();
+    }
+    if let Ok(index) = MdfIndex::from_reader(&mut SliceRangeReader::new(bytes.to_vec())) {
+        let mut reader = index.open(SliceRangeReader::new(bytes.to_vec()));
+        for name in ["Time", "Raw", "Text", "Payload"] {
+            let _ = reader.values(name);
+            let _ = reader.values_f64(name);
+        }
+    }
+    let Ok(mdf) = MDF::from_bytes(bytes.to_vec()) else { return };
+    let _ = mdf.metadata();
+    let _ = mdf.file_layout();
+    let _ = mdf.events();
+    for group in mdf.channel_groups() {
+        let _ = group.name();
+        let _ = group.time_values();
+        for channel in group.channels() {
+            let _ = channel.name();
+            let _ = channel.unit();
+            let _ = channel.values();
+            let _ = channel.values_f64();
+        }
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: u64) -> u64 {
+    let offset = offset as usize;
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn write_u64(bytes: &mut [u8], offset: u64, value: u64) {
+    let offset = offset as usize;
+    bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn truncated_files_do_not_panic() -> Result<(), MdfError> {
+    let bytes = sample_file(None)?;
+    for len in 0..bytes.len() {
+        read_everything(&bytes[..len]);
+    }
+    Ok(())
+}
+
+#[test]
+fn corrupted_files_do_not_panic() -> Result<(), MdfError> {
+    let samples = [sample_file(None)?, sample_file(Some(CompressionMethod::Zlib))?];
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..1500 {
+        let mut bytes = samples[(next() % 2) as usize].clone();
+        for _ in 0..1 + next() % 4 {
+            let pos = (next() % (bytes.len() as u64 - 8)) as usize;
+            match next() % 4 {
+                0 => bytes[pos] ^= 1 << (next() % 8),
+                1 => bytes[pos] = next() as u8,
+                // Plausible small lengths and links as well as wild ones.
+                2 => bytes[pos..pos + 8].copy_from_slice(&(next() % 4096).to_le_bytes()),
+                _ => bytes[pos..pos + 8].copy_from_slice(&next().to_le_bytes()),
+            }
+        }
+        read_everything(&bytes);
+    }
+    Ok(())
+}
+
+#[test]
+fn link_cycles_are_reported() -> Result<(), MdfError> {
+    let bytes = sample_file(None)?;
+    // ##HD at 64 links the first ##DG right after its header; the ##DG
+    // links its first ##CG, which links its first ##CN.
+    let dg = read_u64(&bytes, 64 + 24);
+    let cg = read_u64(&bytes, dg + 24 + 8);
+    let cn = read_u64(&bytes, cg + 24 + 8);
+
+    let mut dg_loop = bytes.clone();
+    write_u64(&mut dg_loop, dg + 24, dg);
+    assert!(matches!(MDF::from_bytes(dg_loop.clone()), Err(MdfError::BlockLinkError(_))));
+    assert!(MdfIndex::from_reader(&mut SliceRangeReader::new(dg_loop)).is_err());
+
+    let mut cn_loop = bytes.clone();
+    write_u64(&mut cn_loop, cn + 24, cn);
+    assert!(matches!(MDF::from_bytes(cn_loop.clone()), Err(MdfError::BlockLinkError(_))));
+    assert!(MdfIndex::from_reader(&mut SliceRangeReader::new(cn_loop)).is_err());
+
+    // The ##DL list of the group's records pointing back at itself.
+    let dl = read_u64(&bytes, dg + 24 + 16);
+    assert_eq!(&bytes[dl as usize..dl as usize + 4], b"##DL");
+    let mut dl_loop = bytes.clone();
+    write_u64(&mut dl_loop, dl + 24, dl);
+    let mdf = MDF::from_bytes(dl_loop.clone())?;
+    let time = &mdf.channel_groups()[0].channels()[0];
+    assert!(matches!(time.values(), Err(MdfError::BlockLinkError(_))));
+    assert!(MdfIndex::from_bytes(dl_loop).is_err());
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! MDF 3.x files are read through the regular `MDF` API.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+
+/// Append `block` to `file` and return its address.
+fn push(file: &mut Vec<u8>, block: Vec<u8>) -> u32 {
+    let addr = file.len() as u32;
+    file.extend(block);
+    addr
+}
+
+/// A block of `size` bytes with its two character `id`.
+fn block(id: &str, size: usize) -> Vec<u8> {
+    let mut b = vec![0u8; size];
+    b[0..2].copy_from_slice(id.as_bytes());
+    b[2..4].copy_from_slice(&(size as u16).to_le_bytes());
+    b
+}
+
+fn put(b: &mut [u8], offset: usize, bytes: &[u8]) {
+    b[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+fn tx(file: &mut Vec<u8>, text: &str) -> u32 {
+    let mut b = block("TX", 4 + text.len() + 1);
+    put(&mut b, 4, text.as_bytes());
+    push(file, b)
+}
+
+/// `(next, cc, type, name, description, start bit, bit count, data type)`
+#[allow(clippy::too_many_arguments)]
+fn cn(file: &mut Vec<u8>, next: u32, cc: u32, kind: u16, name: &str, desc: &str, bit: u16, bits: u16, dt: u16) -> u32 {
+    let mut b = block("CN", 228);
+    put(&mut b, 4, &next.to_le_bytes());
+    put(&mut b, 8, &cc.to_le_bytes());
+    put(&mut b, 24, &kind.to_le_bytes());
+    put(&mut b, 26, name.as_bytes());
+    put(&mut b, 58, desc.as_bytes());
+    put(&mut b, 186, &bit.to_le_bytes());
+    put(&mut b, 188, &bits.to_le_bytes());
+    put(&mut b, 190, &dt.to_le_bytes());
+    push(file, b)
+}
+
+/// An unsorted MDF 3.30 file with two channel groups sharing one data block:
+/// - record ID 1: `Time` (f64) and `Speed` (u16, `0.5 * x + 1` km/h)
+/// - record ID 2: `Time` (f64), `Gear` (4 bits, text table) and `Flag` (1 bit)
+fn mdf3_file() -> Vec<u8> {
+    let mut file = vec![0u8; 64 + 208];
+    put(&mut file, 0, b"MDF     3.30    TEST    ");
+    put(&mut file, 28, &330u16.to_le_bytes());
+
+    let mut linear = block("CC", 46 + 16);
+    put(&mut linear, 22, b"km/h");
+    put(&mut linear, 44, &2u16.to_le_bytes());
+    put(&mut linear, 46, &1.0f64.to_le_bytes());
+    put(&mut linear, 54, &0.5f64.to_le_bytes());
+    let linear = push(&mut file, linear);
+
+    let mut table = block("CC", 46 + 2 * 40);
+    put(&mut table, 42, &11u16.to_le_bytes());
+    put(&mut table, 44, &2u16.to_le_bytes());
+    for (i, (value, text)) in [(1.0f64, "first"), (2.0, "second")].iter().enumerate() {
+        put(&mut table, 46 + i * 40, &value.to_le_bytes());
+        put(&mut table, 54 + i * 40, text.as_bytes());
+    }
+    let table = push(&mut file, table);
+
+    let speed = cn(&mut file, 0, linear, 0, "Speed", "Vehicle speed", 64, 16, 0);
+    let time_a = cn(&mut file, speed, 0, 1, "Time", "", 0, 64, 3);
+    let flag = cn(&mut file, 0, 0, 0, "Flag", "", 68, 1, 0);
+    let gear = cn(&mut file, flag, table, 0, "Gear", "", 64, 4, 0);
+    let time_b = cn(&mut file, gear, 0, 1, "Time", "", 0, 64, 3);
+
+    let comment_b = tx(&mut file, "Gearbox");
+    let mut cg_b = block("CG", 30);
+    put(&mut cg_b, 8, &time_b.to_le_bytes());
+    put(&mut cg_b, 12, &comment_b.to_le_bytes());
+    put(&mut cg_b, 16, &2u16.to_le_bytes());
+    put(&mut cg_b, 18, &3u16.to_le_bytes());
+    put(&mut cg_b, 20, &9u16.to_le_bytes());
+    put(&mut cg_b, 22, &2u32.to_le_bytes());
+    let cg_b = push(&mut file, cg_b);
+    let mut cg_a = block("CG", 30);
+    put(&mut cg_a, 4, &cg_b.to_le_bytes());
+    put(&mut cg_a, 8, &time_a.to_le_bytes());
+    put(&mut cg_a, 16, &1u16.to_le_bytes());
+    put(&mut cg_a, 18, &2u16.to_le_bytes());
+    put(&mut cg_a, 20, &10u16.to_le_bytes());
+    put(&mut cg_a, 22, &3u32.to_le_bytes());
+    let cg_a = push(&mut file, cg_a);
+
+    let mut data = Vec::new();
+    for (t, id, payload) in [
+        (0.0, 1, vec![10, 0]),
+        (0.05, 2, vec![0x11]),
+        (0.1, 1, vec![20, 0]),
+        (0.15, 2, vec![0x02]),
+        (0.2, 1, vec![30, 0]),
+    ] {
+        data.push(id);
+        data.extend_from_slice(&f64::to_le_bytes(t));
+        data.extend(payload);
+    }
+    let data = push(&mut file, data);
+
+    let mut dg = block("DG", 28);
+    put(&mut dg, 8, &cg_a.to_le_bytes());
+    put(&mut dg, 16, &data.to_le_bytes());
+    put(&mut dg, 20, &2u16.to_le_bytes());
+    put(&mut dg, 22, &1u16.to_le_bytes());
+    let dg = push(&mut file, dg);
+
+    let comment = tx(&mut file, "Test drive");
+    let mut hd = block("HD", 208);
+    put(&mut hd, 4, &dg.to_le_bytes());
+    put(&mut hd, 8, &comment.to_le_bytes());
+    put(&mut hd, 16, &1u16.to_le_bytes());
+    put(&mut hd, 18, b"17:10:202612:30:00");
+    put(&mut hd, 36, b"Alice");
+    // 2026-10-17 12:30:00 local time, UTC+2.
+    put(&mut hd, 164, &1_792_240_200_000_000_000u64.to_le_bytes());
+    put(&mut hd, 172, &2i16.to_le_bytes());
+    put(&mut file, 64, &hd);
+    file
+}
+
+#[test]
+fn read_mdf3_file() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("mdf3_read.mdf");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, mdf3_file())?;
+    let mdf = MDF::from_file(path)?;
+
+    assert_eq!(mdf.start_time_ns(), Some(1_792_233_000_000_000_000));
+    let header = mdf.header_metadata()?.unwrap();
+    assert_eq!(header.comment.as_deref(), Some("Test drive"));
+    assert_eq!(header.author.as_deref(), Some("Alice"));
+
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[1].comment()?.as_deref(), Some("Gearbox"));
+
+    let speed = groups[0].channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.comment()?.as_deref(), Some("Vehicle speed"));
+    assert_eq!(speed.values_f64()?, vec![Some(6.0), Some(11.0), Some(16.0)]);
+    let signal = groups[0].signal("Speed")?.unwrap();
+    assert_eq!(signal.timestamps, vec![0.0, 0.1, 0.2]);
+
+    let gear = groups[1].channel("Gear").unwrap().values()?;
+    assert_eq!(
+        gear,
+        vec![Some(DecodedValue::String("first".into())), Some(DecodedValue::String("second".into()))]
+    );
+    assert_eq!(groups[1].channel("Flag").unwrap().values_u64()?, vec![Some(1), Some(0)]);
+    assert_eq!(groups[1].channel("Time").unwrap().block().channel_type, 2);
+
+    // The same translation applies to in-memory data.
+    let mdf = MDF::from_bytes(mdf3_file())?;
+    assert_eq!(mdf.channel("Speed").unwrap().values_f64()?.len(), 3);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
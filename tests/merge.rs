@@ -1,7 +1,7 @@
 use mf4_rs::writer::MdfWriter;
 use mf4_rs::parsing::decoder::DecodedValue;
 use mf4_rs::api::mdf::MDF;
-use mf4_rs::merge::merge_files;
+use mf4_rs::merge::{merge_files, merge_many, merge_many_with_options, ClockCorrection, MergeOptions};
 use mf4_rs::blocks::common::DataType;
 use mf4_rs::error::MdfError;
 
@@ -296,6 +296,36 @@ fn merge_different_files() -> Result<(), MdfError> {
     Ok(())
 }
 
+#[test]
+fn merge_many_files() -> Result<(), MdfError> {
+    let dir = std::env::temp_dir();
+    let inputs: Vec<_> = (0..3).map(|i| dir.join(format!("mf4_merge_many_{i}.mf4"))).collect();
+    let out = dir.join("mf4_merge_many_out.mf4");
+
+    // Channel A in the first and last file, B in the middle one.
+    for (i, (path, name)) in inputs.iter().zip(["A", "B", "A"]).enumerate() {
+        let mut w = MdfWriter::new(path.to_str().unwrap())?;
+        w.init_mdf_file()?;
+        let cg = w.add_channel_group(None, |_| {})?;
+        w.add_channel(&cg, None, |ch| { ch.data_type = DataType::UnsignedIntegerLE; ch.name = Some(name.into()); })?;
+        w.start_data_block_for_cg(&cg, 0)?;
+        w.write_record(&cg, &[DecodedValue::UnsignedInteger(i as u64)])?;
+        w.finish_data_block(&cg)?;
+        w.finalize()?;
+    }
+
+    let paths: Vec<&str> = inputs.iter().map(|p| p.to_str().unwrap()).collect();
+    merge_many(out.to_str().unwrap(), &paths)?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    assert_eq!(mdf.channel_groups().len(), 2);
+    assert_eq!(mdf.channel("A").unwrap().values_u64()?, vec![Some(0), Some(2)]);
+    assert_eq!(mdf.channel("B").unwrap().values_u64()?, vec![Some(1)]);
+
+    for p in inputs.iter().chain([&out]) { std::fs::remove_file(p)?; }
+    Ok(())
+}
+
 fn write_vlsd_file<P: AsRef<std::path::Path>>(
     path: P,
     data_type: DataType,
@@ -401,3 +431,110 @@ fn merge_vlsd_bytearray_files() -> Result<(), MdfError> {
     for p in [&f1, &f2, &out] { std::fs::remove_file(p)?; }
     Ok(())
 }
+
+#[test]
+fn merge_preserves_invalid_samples() -> Result<(), MdfError> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_inval_1.mf4");
+    let f2 = dir.join("mf4_merge_inval_2.mf4");
+    let out = dir.join("mf4_merge_inval_out.mf4");
+    for p in [&f1, &f2, &out] { if p.exists() { std::fs::remove_file(p)?; } }
+
+    // first file carries an invalidation bit with one invalid sample
+    let mut w1 = MdfWriter::new(f1.to_str().unwrap())?;
+    w1.init_mdf_file()?;
+    let cg1 = w1.add_channel_group(None, |_| {})?;
+    w1.add_channel(&cg1, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    w1.start_data_block_for_cg(&cg1, 0)?;
+    w1.write_record_with_validity(&cg1, &[Some(DecodedValue::UnsignedInteger(1))])?;
+    w1.write_record_with_validity(&cg1, &[None])?;
+    w1.finish_data_block(&cg1)?;
+    w1.finalize()?;
+
+    // second file has the same layout without invalidation bits
+    let mut w2 = MdfWriter::new(f2.to_str().unwrap())?;
+    w2.init_mdf_file()?;
+    let cg2 = w2.add_channel_group(None, |_| {})?;
+    w2.add_channel(&cg2, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+    })?;
+    w2.start_data_block_for_cg(&cg2, 0)?;
+    w2.write_record(&cg2, &[DecodedValue::UnsignedInteger(3)])?;
+    w2.finish_data_block(&cg2)?;
+    w2.finalize()?;
+
+    merge_files(out.to_str().unwrap(), f1.to_str().unwrap(), f2.to_str().unwrap())?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 1);
+    let values = groups[0].channels()[0].values()?;
+    assert_eq!(
+        values,
+        vec![
+            Some(DecodedValue::UnsignedInteger(1)),
+            None,
+            Some(DecodedValue::UnsignedInteger(3)),
+        ]
+    );
+
+    for p in [&f1, &f2, &out] { std::fs::remove_file(p)?; }
+    Ok(())
+}
+
+#[test]
+fn merge_many_applies_clock_corrections() -> Result<(), MdfError> {
+    let dir = std::env::temp_dir();
+    let inputs: Vec<_> = (0..2).map(|i| dir.join(format!("mf4_merge_clock_{i}.mf4"))).collect();
+    let out = dir.join("mf4_merge_clock_out.mf4");
+
+    // Both loggers start their clocks at 0 s and sample every second.
+    for (i, path) in inputs.iter().enumerate() {
+        let mut w = MdfWriter::new(path.to_str().unwrap())?;
+        w.init_mdf_file()?;
+        let cg = w.add_channel_group(None, |_| {})?;
+        let time = w.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        w.set_time_channel(&time)?;
+        w.add_channel(&cg, Some(&time), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Value".into());
+            ch.bit_count = 32;
+        })?;
+        w.start_data_block_for_cg(&cg, 0)?;
+        for t in 0..3u64 {
+            w.write_record(&cg, &[DecodedValue::Float(t as f64), DecodedValue::UnsignedInteger(10 * i as u64 + t)])?;
+        }
+        w.finish_data_block(&cg)?;
+        w.finalize()?;
+    }
+
+    // The first logger started 100 s into the session; the second one 200 s
+    // in, with a clock running at half speed.
+    let options = MergeOptions {
+        clock_corrections: vec![ClockCorrection::offset(100.0), ClockCorrection::linear(200.0, 2.0)],
+        ..MergeOptions::default()
+    };
+    let paths: Vec<&str> = inputs.iter().map(|p| p.to_str().unwrap()).collect();
+    merge_many_with_options(out.to_str().unwrap(), &paths, &options)?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    assert_eq!(mdf.channel_groups().len(), 1);
+    assert_eq!(
+        mdf.channel("Time").unwrap().values_f64()?,
+        [100.0, 101.0, 102.0, 200.0, 202.0, 204.0].map(Some).to_vec()
+    );
+    assert_eq!(mdf.channel("Value").unwrap().values_u64()?, [0, 1, 2, 10, 11, 12].map(Some).to_vec());
+
+    for p in inputs.iter().chain([&out]) { std::fs::remove_file(p)?; }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::blocks::conversion::ConversionType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::metadata::{new_measurement_uuid, FileMetadata, HeaderInfo, HeaderMetadata, StartTime};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// Raw `##AT` block with an embedded payload and the given filename link.
+fn attachment_bytes(filename_addr: u64, payload: &[u8]) -> Vec<u8> {
+    let header = BlockHeader {
+        id: "##AT".to_string(),
+        reserved0: 0,
+        block_len: 96 + payload.len() as u64,
+        links_nr: 4,
+    };
+    let mut bytes = header.to_bytes().unwrap();
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&filename_addr.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // embedded
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&[0u8; 4]);
+    bytes.extend_from_slice(&[0u8; 16]);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+#[test]
+fn metadata_json_describes_file_without_samples() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("metadata_json_test.mf4");
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, "Engine")?;
+    let t = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+    })?;
+    writer.set_time_channel(&t)?;
+    let gear = writer.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+        ch.name = Some("Gear".to_string());
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "Neutral"), (1, "First")], "Unknown", Some(&gear))?;
+
+    let tx_pos = writer.write_block(&TextBlock::new("calibration.a2l").to_bytes()?)?;
+    writer.write_block_with_id(&attachment_bytes(tx_pos, b"payload"), "at_0")?;
+    writer.update_block_link("hd_block", 48, "at_0")?;
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record(&cg, &[DecodedValue::Float(0.0), DecodedValue::UnsignedInteger(1)])?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let json = mdf.metadata_json()?;
+    let meta: FileMetadata = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(meta.version, "4.10");
+    assert_eq!(meta.groups.len(), 1);
+    let group = &meta.groups[0];
+    assert_eq!(group.name.as_deref(), Some("Engine"));
+    assert_eq!(group.record_count, 1);
+    assert_eq!(group.channels.len(), 2);
+    assert!(group.channels[0].is_master);
+    assert_eq!(group.channels[1].name.as_deref(), Some("Gear"));
+    let conv = group.channels[1].conversion.as_ref().expect("conversion");
+    assert_eq!(conv.conversion_type, ConversionType::ValueToText);
+    assert_eq!(conv.parameters, vec![0.0, 1.0]);
+    assert_eq!(conv.texts.get(&0).map(String::as_str), Some("Neutral"));
+    assert_eq!(conv.texts.get(&2).map(String::as_str), Some("Unknown"));
+
+    assert_eq!(meta.attachments.len(), 1);
+    let at = &meta.attachments[0];
+    assert_eq!(at.file_name.as_deref(), Some("calibration.a2l"));
+    assert!(at.embedded);
+    assert_eq!(at.embedded_size, 7);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn header_metadata_round_trip() -> Result<(), MdfError> {
+    let meta = HeaderMetadata {
+        comment: Some("Cold start <-20 °C> & soak".into()),
+        author: Some("J. Doe".into()),
+        department: Some("Powertrain".into()),
+        project: Some("P-42".into()),
+        subject: Some("Test vehicle 7".into()),
+        measurement_uuid: None,
+        properties: vec![("ambient".into(), "\"arctic\"".into())],
+    };
+
+    let path = std::env::temp_dir().join("header_metadata.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_header_metadata(&meta)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.header_metadata()?, Some(meta));
+    let xml = mdf.metadata()?.comment.unwrap();
+    assert!(xml.starts_with("<HDcomment"));
+    assert!(xml.contains(r#"<e name="author">J. Doe</e>"#));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn header_info_round_trip() -> Result<(), MdfError> {
+    let uuid = new_measurement_uuid();
+    assert_eq!(uuid.len(), 36);
+    assert_eq!(&uuid[14..15], "4");
+    assert!("89ab".contains(&uuid[19..20]), "{uuid}");
+    assert_ne!(uuid, new_measurement_uuid());
+
+    let info = HeaderInfo {
+        start_time: Some(StartTime {
+            abs_time_ns: 1_700_000_000_000_000_000,
+            tz_offset_min: 60,
+            dst_offset_min: 60,
+            time_flags: 0x02,
+            time_quality: 10,
+        }),
+        metadata: HeaderMetadata {
+            comment: Some("Highway loop".into()),
+            author: Some("J. Doe".into()),
+            department: Some("Vehicle Testing".into()),
+            project: Some("P-42".into()),
+            subject: Some("Test vehicle 7".into()),
+            measurement_uuid: Some(uuid.clone()),
+            ..Default::default()
+        },
+    };
+
+    let path = std::env::temp_dir().join("header_info.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_header_info(&info)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.header_info()?, info);
+    let xml = mdf.metadata()?.comment.unwrap();
+    assert!(xml.contains(&format!(r#"<e name="measurement_uuid">{uuid}</e>"#)));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn header_metadata_parses_foreign_comments() {
+    let xml = r#"<?xml version="1.0"?>
+<HDcomment xmlns="http://www.asam.net/mdf/v4">
+  <TX>drive &#x41;</TX>
+  <common_properties>
+    <e name="author" type="string">Ann</e>
+    <tree name="vehicle"><e name="vin">X1</e></tree>
+    <e name='rig'>B&amp;7</e>
+  </common_properties>
+</HDcomment>"#;
+    let meta = HeaderMetadata::from_xml(xml);
+    assert_eq!(meta.comment.as_deref(), Some("drive A"));
+    assert_eq!(meta.author.as_deref(), Some("Ann"));
+    assert_eq!(meta.properties, vec![("rig".to_string(), "B&7".to_string())]);
+
+    let plain = HeaderMetadata::from_xml("just text");
+    assert_eq!(plain.comment.as_deref(), Some("just text"));
+    assert_eq!(plain.author, None);
+}
@@ -0,0 +1,110 @@
+//! `MimeSample` / `MimeStream` channels read as borrowed samples and
+//! extracted to files named after their MIME type.
+
+use std::borrow::Cow;
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::api::mime::extension_for_mime_type;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelId, MdfWriter};
+
+/// Variable-size "frames", one per record.
+fn frames() -> Vec<Vec<u8>> {
+    (0..5u8).map(|i| vec![0x89, b'P', b'N', b'G', i].repeat(i as usize + 1)).collect()
+}
+
+fn set_unit(w: &mut MdfWriter<Cursor<Vec<u8>>>, channel: &ChannelId, unit: &str) -> Result<(), MdfError> {
+    let unit_pos = w.write_block(&TextBlock::new(unit).to_bytes()?)?;
+    let channel_pos = w.get_block_position(channel).unwrap();
+    w.update_link(channel_pos + 72, unit_pos)
+}
+
+/// A VLSD `Camera` image channel and a 4-byte `Audio` stream channel.
+fn sample_file() -> Result<Vec<u8>, MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let camera = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::MimeSample;
+        ch.name = Some("Front/Camera".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    set_unit(&mut w, &camera, "image/PNG")?;
+    let audio = w.add_channel(&cg, Some(&camera), |ch| {
+        ch.data_type = DataType::MimeStream;
+        ch.name = Some("Audio".into());
+        ch.bit_count = 32;
+    })?;
+    set_unit(&mut w, &audio, "audio/wav; rate=8000")?;
+
+    w.start_data_block_for_cg(&cg, 0)?;
+    for (i, frame) in frames().into_iter().enumerate() {
+        w.write_record(&cg, &[
+            DecodedValue::Float(i as f64 * 0.04),
+            DecodedValue::MimeSample(frame),
+            DecodedValue::MimeStream(vec![i as u8; 4]),
+        ])?;
+    }
+    w.finish_data_block(&cg)?;
+    Ok(w.into_inner()?.into_inner())
+}
+
+#[test]
+fn mime_samples_are_borrowed_per_record() -> Result<(), MdfError> {
+    let mdf = MDF::from_bytes(sample_file()?)?;
+    let camera = mdf.channel("Front/Camera").unwrap();
+    assert_eq!(camera.mime_type()?.as_deref(), Some("image/png"));
+    let samples = camera.mime_samples()?.collect::<Result<Vec<_>, _>>()?;
+    let expected = frames();
+    assert_eq!(samples, expected.iter().map(|f| Some(Cow::Borrowed(f.as_slice()))).collect::<Vec<_>>());
+    assert!(samples.iter().flatten().all(|sample| matches!(sample, Cow::Borrowed(_))));
+
+    let audio = mdf.channel("Audio").unwrap();
+    assert_eq!(audio.mime_type()?.as_deref(), Some("audio/wav"));
+    assert_eq!(audio.mime_samples()?.count(), 5);
+
+    let time = mdf.channel("Time").unwrap();
+    assert_eq!(time.mime_type()?, None);
+    assert!(matches!(time.mime_samples().err(), Some(MdfError::UnsupportedFeature(_))));
+    Ok(())
+}
+
+#[test]
+fn extract_writes_one_file_per_sample_or_one_per_stream() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let mdf = MDF::from_bytes(sample_file()?)?;
+
+    let images = mdf.channel("Front/Camera").unwrap().extract_mime_samples(dir.path().join("out"))?;
+    assert_eq!(images.len(), 5);
+    assert_eq!(images[2], dir.path().join("out").join("Front_Camera_000002.png"));
+    for (path, frame) in images.iter().zip(frames()) {
+        assert_eq!(std::fs::read(path)?, frame);
+    }
+
+    let stream = mdf.channel("Audio").unwrap().extract_mime_samples(dir.path())?;
+    assert_eq!(stream, [dir.path().join("Audio.wav")]);
+    let expected: Vec<u8> = (0..5u8).flat_map(|i| [i; 4]).collect();
+    assert_eq!(std::fs::read(&stream[0])?, expected);
+    Ok(())
+}
+
+#[test]
+fn extensions_follow_the_mime_type() {
+    assert_eq!(extension_for_mime_type("image/jpeg"), "jpg");
+    assert_eq!(extension_for_mime_type("Video/MP4; codecs=avc1"), "mp4");
+    assert_eq!(extension_for_mime_type("<CNunit><TX>video/x-msvideo</TX></CNunit>"), "avi");
+    assert_eq!(extension_for_mime_type("application/x-unknown"), "bin");
+    assert_eq!(extension_for_mime_type(""), "bin");
+}
@@ -0,0 +1,193 @@
+//! Integer channels sharing bytes are written bit-exactly.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, ColumnData, MdfWriter};
+
+/// Group with a `Time` master, then `Flag` (1 bit), `Mode` (3 bits) and
+/// `Delta` (signed, 4 bits) packed into byte 8 and `Raw12` (12 bits) in
+/// bytes 9..11.
+fn packed_group(w: &mut MdfWriter<Cursor<Vec<u8>>>) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let flag = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Flag".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 1;
+    })?;
+    let mode = w.add_channel(&cg, Some(&flag), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Mode".into());
+        ch.byte_offset = 8;
+        ch.bit_offset = 1;
+        ch.bit_count = 3;
+    })?;
+    let delta = w.add_channel(&cg, Some(&mode), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Delta".into());
+        ch.byte_offset = 8;
+        ch.bit_offset = 4;
+        ch.bit_count = 4;
+    })?;
+    w.add_channel(&cg, Some(&delta), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Raw12".into());
+        ch.byte_offset = 9;
+        ch.bit_count = 12;
+    })?;
+    Ok(cg)
+}
+
+fn check_values(mdf: &MDF) -> Result<(), MdfError> {
+    assert_eq!(mdf.channel("Flag").unwrap().values_u64()?, vec![Some(1), Some(0), Some(1)]);
+    assert_eq!(mdf.channel("Mode").unwrap().values_u64()?, vec![Some(5), Some(7), Some(0)]);
+    let delta: Vec<_> = mdf.channel("Delta").unwrap().values()?;
+    assert_eq!(
+        delta,
+        vec![
+            Some(DecodedValue::SignedInteger(-3)),
+            Some(DecodedValue::SignedInteger(7)),
+            Some(DecodedValue::SignedInteger(-8)),
+        ]
+    );
+    assert_eq!(mdf.channel("Raw12").unwrap().values_u64()?, vec![Some(0xABC), Some(0xFFF), Some(1)]);
+    Ok(())
+}
+
+const FLAGS: [u64; 3] = [1, 0, 1];
+const MODES: [u64; 3] = [5, 7, 0];
+const DELTAS: [i64; 3] = [-3, 7, -8];
+const RAW: [u64; 3] = [0xABC, 0xFFF, 1];
+
+#[test]
+fn packed_fields_round_trip_through_write_record() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = packed_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..3 {
+        w.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64),
+                DecodedValue::UnsignedInteger(FLAGS[i]),
+                DecodedValue::UnsignedInteger(MODES[i]),
+                DecodedValue::SignedInteger(DELTAS[i]),
+                DecodedValue::UnsignedInteger(RAW[i]),
+            ],
+        )?;
+    }
+    w.finish_data_block(&cg)?;
+
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+    check_values(&mdf)
+}
+
+#[test]
+fn packed_fields_round_trip_through_write_columns() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = packed_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    w.write_columns(
+        &cg,
+        &[
+            ColumnData::F64(&[0.0, 1.0, 2.0]),
+            ColumnData::U64(&FLAGS),
+            ColumnData::U64(&MODES),
+            ColumnData::I64(&DELTAS),
+            ColumnData::U64(&RAW),
+        ],
+    )?;
+    w.finish_data_block(&cg)?;
+
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+    check_values(&mdf)
+}
+
+#[test]
+fn overlapping_channels_are_rejected() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let wide = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Wide".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_channel(&cg, Some(&wide), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Nibble".into());
+        ch.byte_offset = 1;
+        ch.bit_offset = 4;
+        ch.bit_count = 4;
+    })?;
+    let err = w.start_data_block_for_cg(&cg, 0).unwrap_err();
+    assert!(err.to_string().contains("'Wide' and 'Nibble' overlap"), "{err}");
+    assert!(err.to_string().contains("bits 0..16 and 12..16"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn channels_without_offsets_are_packed_in_order() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let mut prev = None;
+    for (name, data_type, bits, channel_type) in [
+        ("Index", DataType::UnsignedIntegerLE, 64, 3),
+        ("Flag", DataType::UnsignedIntegerLE, 1, 0),
+        ("Mode", DataType::UnsignedIntegerLE, 3, 0),
+        ("Delta", DataType::SignedIntegerLE, 4, 0),
+        ("Raw12", DataType::UnsignedIntegerLE, 12, 0),
+        ("Level", DataType::FloatLE, 32, 0),
+        ("Nibble", DataType::UnsignedIntegerLE, 4, 0),
+    ] {
+        let cn = w.add_channel(&cg, prev.as_ref(), |ch| {
+            ch.data_type = data_type;
+            ch.name = Some(name.into());
+            ch.bit_count = bits;
+            ch.channel_type = channel_type;
+        })?;
+        prev = Some(cn);
+    }
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..3 {
+        w.write_record(&cg, &[
+            DecodedValue::Unknown,
+            DecodedValue::UnsignedInteger(FLAGS[i]),
+            DecodedValue::UnsignedInteger(MODES[i]),
+            DecodedValue::SignedInteger(DELTAS[i]),
+            DecodedValue::UnsignedInteger(RAW[i]),
+            DecodedValue::Float(i as f64),
+            DecodedValue::UnsignedInteger(15 - i as u64),
+        ])?;
+    }
+    w.finish_data_block(&cg)?;
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+
+    // The virtual `Index` takes no bytes; `Level` starts at the next byte
+    // after `Raw12` ends at bit 20.
+    let group = &mdf.channel_groups()[0];
+    let layout: Vec<(u32, u8)> = group
+        .channels()
+        .iter()
+        .skip(1)
+        .map(|ch| (ch.block().byte_offset, ch.block().bit_offset))
+        .collect();
+    assert_eq!(layout, vec![(0, 0), (0, 1), (0, 4), (1, 0), (3, 0), (7, 0)]);
+    assert_eq!(group.channel("Raw12").unwrap().values_u64()?, RAW.map(Some));
+    assert_eq!(group.channel("Delta").unwrap().values_i64()?, DELTAS.map(Some));
+    assert_eq!(group.channel("Nibble").unwrap().values_u64()?, vec![Some(15), Some(14), Some(13)]);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Parquet export of a channel group, read back with the parquet crate.
+#![cfg(feature = "parquet")]
+
+use arrow_array::cast::AsArray;
+use arrow_array::Array;
+use arrow_array::types::{Float64Type, Int64Type, UInt64Type};
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::export::parquet::{write_group_to_parquet, ParquetExportOptions};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+#[test]
+fn group_exported_with_types_units_and_nulls() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let mdf_path = dir.path().join("export.mf4");
+    let parquet_path = dir.path().join("export.parquet");
+
+    let mut w = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let speed = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.bit_count = 32;
+        ch.flags = 0x02;
+        ch.pos_invalidation_bit = 0;
+    })?;
+    // Unit text block (link offset 72 in ##CN).
+    w.write_block_with_id(&TextBlock::new("km/h").to_bytes()?, "tx_speed_unit")?;
+    w.update_block_link(&speed, 72, "tx_speed_unit")?;
+    let offset = w.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Offset".into());
+        ch.bit_count = 16;
+    })?;
+    let gear = w.add_channel(&cg, Some(&offset), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 8;
+    })?;
+    w.add_value_to_text_conversion(&[(0, "N"), (1, "First")], "?", Some(&gear))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        w.write_record_with_validity(&cg, &[
+            Some(DecodedValue::Float(i as f64 * 0.1)),
+            (i != 3).then_some(DecodedValue::UnsignedInteger(i * 10)),
+            Some(DecodedValue::SignedInteger(i as i64 - 5)),
+            Some(DecodedValue::UnsignedInteger(i % 3)),
+        ])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    let mdf = MDF::from_file(mdf_path.to_str().unwrap())?;
+    // Small batches and row groups exercise the streaming path.
+    let options = ParquetExportOptions { row_group_size: 4, batch_size: 3, ..Default::default() };
+    let rows = write_group_to_parquet(&mdf, 0, parquet_path.to_str().unwrap(), &options)?;
+    assert_eq!(rows, 10);
+    let missing = dir.path().join("missing.parquet");
+    assert!(write_group_to_parquet(&mdf, 1, missing.to_str().unwrap(), &options).is_err());
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&parquet_path)?).unwrap();
+    assert_eq!(builder.metadata().num_row_groups(), 3);
+    let schema = builder.schema().clone();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, ["Time", "Speed", "Offset", "Gear"]);
+    assert_eq!(schema.field(1).metadata().get("unit").map(String::as_str), Some("km/h"));
+    assert!(schema.field(0).metadata().is_empty());
+
+    let batches: Vec<_> = builder.with_batch_size(100).build().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    let time = batch.column(0).as_primitive::<Float64Type>();
+    assert!((time.value(9) - 0.9).abs() < 1e-12);
+    let speed = batch.column(1).as_primitive::<UInt64Type>();
+    assert!(speed.is_null(3));
+    assert_eq!(speed.value(4), 40);
+    assert_eq!(batch.column(2).as_primitive::<Int64Type>().value(0), -5);
+    let gear = batch.column(3).as_string::<i32>();
+    assert_eq!((gear.value(0), gear.value(1), gear.value(2)), ("N", "First", "?"));
+    Ok(())
+}
+
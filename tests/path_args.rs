@@ -0,0 +1,76 @@
+//! File paths are accepted as any `AsRef<Path>`, including non-UTF-8 ones.
+
+use std::path::{Path, PathBuf};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::{ByteRangeReader, FileRangeReader, MdfIndex};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn write_file(path: &Path) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let time = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+fn time_values(path: impl AsRef<Path>) -> Result<Vec<f64>, MdfError> {
+    let mdf = MDF::from_file(path)?;
+    let groups = mdf.channel_groups();
+    Ok(groups[0].channels()[0].values_as_f64()?)
+}
+
+#[test]
+fn str_string_and_path_buf_are_accepted() -> Result<(), MdfError> {
+    let path: PathBuf = std::env::temp_dir().join("path_args_plain.mf4");
+    write_file(&path)?;
+    let expected: Vec<f64> = (0..10).map(|i| i as f64).collect();
+
+    assert_eq!(time_values(&path)?, expected);
+    assert_eq!(time_values(path.to_str().unwrap())?, expected);
+    assert_eq!(time_values(path.to_str().unwrap().to_string())?, expected);
+
+    let index = MdfIndex::from_file(&path)?;
+    assert_eq!(index.source_string().as_deref(), path.to_str());
+    let mut reader = FileRangeReader::new(&path)?;
+    assert_eq!(&reader.read_range(0, 3)?, b"MDF");
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths_round_trip() -> Result<(), MdfError> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = std::env::temp_dir().join(OsStr::from_bytes(b"path_args_\xff\xfe"));
+    std::fs::create_dir_all(&dir)?;
+    let input = dir.join(OsStr::from_bytes(b"in_\xe9.mf4"));
+    let output = dir.join(OsStr::from_bytes(b"out_\xe9.mf4"));
+    write_file(&input)?;
+
+    mf4_rs::cut::cut_mdf_by_time(&input, &output, 2.0, 5.0)?;
+    assert_eq!(time_values(&output)?, vec![2.0, 3.0, 4.0, 5.0]);
+
+    let mut index = MdfIndex::from_file(&input)?;
+    index.set_file(input.clone());
+    assert!(index.open_file(&input).is_ok());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
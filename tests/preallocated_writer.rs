@@ -0,0 +1,117 @@
+//! The preallocating file sink writes the same bytes as an in-memory sink
+//! and leaves no trailing extent behind.
+
+use std::io::{Cursor, Seek, SeekFrom, Write};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{MdfWriter, PreallocatedFile};
+
+/// Write 200k records of a time and a value channel into `w`.
+fn write_sample<W: Write + Seek>(w: &mut MdfWriter<W>) -> Result<(), MdfError> {
+    w.init_mdf_file()?;
+    w.set_start_time(1_700_000_000_000_000_000, 0, 0, 0, 0)?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Value".into());
+        ch.bit_count = 64;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..200_000u64 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64 * 0.001), DecodedValue::UnsignedInteger(i)])?;
+    }
+    w.finish_data_block(&cg)
+}
+
+#[test]
+fn preallocated_file_matches_in_memory_output() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("prealloc.mf4");
+
+    let mut expected = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    write_sample(&mut expected)?;
+    let expected = expected.into_inner()?.into_inner();
+
+    let mut w = MdfWriter::from_writer(PreallocatedFile::create(path.to_str().unwrap(), 1 << 20)?);
+    write_sample(&mut w)?;
+    let sink = w.into_inner()?;
+    assert_eq!(sink.content_len(), expected.len() as u64);
+    assert_eq!(sink.allocated_len() % (1 << 20), 0);
+    assert!(sink.allocated_len() >= sink.content_len());
+    sink.finish()?;
+
+    assert_eq!(std::fs::read(&path)?, expected);
+    Ok(())
+}
+
+#[test]
+fn finalize_cuts_the_last_extent() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("prealloc.mf4");
+    let mut w = MdfWriter::new_preallocated(path.to_str().unwrap(), 0)?;
+    write_sample(&mut w)?;
+    let written = w.offset();
+    w.finalize()?;
+
+    assert_eq!(std::fs::metadata(&path)?.len(), written);
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let values = mdf.channel("Value").unwrap().values_f64()?;
+    assert_eq!(values.len(), 200_000);
+    assert_eq!(values[199_999], Some(199_999.0));
+    Ok(())
+}
+
+#[test]
+fn seeks_and_overwrites_match_a_cursor() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("sink.bin");
+    let mut sink = PreallocatedFile::create(path.to_str().unwrap(), 4096)?;
+    let mut model = Cursor::new(Vec::new());
+
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..300 {
+        match next() % 5 {
+            // Mostly appends, of sizes around and above the write buffer.
+            0 | 1 => {
+                let len = [8, 300, 70_000, 1_500_000][(next() % 4) as usize];
+                let bytes = vec![next() as u8; len];
+                sink.write_all(&bytes)?;
+                model.write_all(&bytes)?;
+            }
+            2 => {
+                let end = model.get_ref().len() as u64;
+                let to = SeekFrom::Start(next() % (end + 1));
+                assert_eq!(sink.seek(to)?, model.seek(to)?);
+            }
+            3 => {
+                let to = SeekFrom::End(0);
+                assert_eq!(sink.seek(to)?, model.seek(to)?);
+            }
+            _ => {
+                let bytes = (next() as u64).to_le_bytes();
+                sink.write_all(&bytes)?;
+                model.write_all(&bytes)?;
+            }
+        }
+    }
+    let model = model.into_inner();
+    assert_eq!(sink.content_len(), model.len() as u64);
+    sink.finish()?;
+    assert_eq!(std::fs::read(&path)?, model);
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Blocks outside the channel groups kept by `cut` and `merge` in
+//! block preservation mode.
+
+use mf4_rs::api::event::EventScope;
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks;
+use mf4_rs::blocks::channel_block::ChannelBlock;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::blocks::event_block::EventType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::blocks::walk::{links_at, parse_at};
+use mf4_rs::cut::{cut_mdf_by_time, cut_mdf_by_time_with_options, CutOptions};
+use mf4_rs::error::MdfError;
+use mf4_rs::merge::{merge_many_with_options, MergeOptions};
+use mf4_rs::metadata::HeaderMetadata;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn path(name: &str) -> String {
+    std::env::temp_dir().join(format!("preserve_blocks_{name}")).to_str().unwrap().to_string()
+}
+
+/// A file with a header comment, a file history entry, an event scoped to
+/// the `Engine` group and its `Temp` channel, an embedded attachment and a
+/// channel hierarchy (`##CH`, not modelled by the crate) naming `Temp`.
+fn write_source(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    w.set_header_metadata(&HeaderMetadata { comment: Some("drive".into()), ..Default::default() })?;
+    w.add_file_history(1_700_000_000_000_000_000, "logger", "ACME", "1.0", "recorded")?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&cg, "Engine")?;
+    let time = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let temp = w.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 16;
+    })?;
+    w.add_event("warm", 2.0, EventType::Marker, &[(&cg).into(), (&temp).into()])?;
+
+    let hd_pos = w.get_block_position("hd_block").unwrap();
+    let file_name = w.write_block(&TextBlock::new("setup.txt").to_bytes()?)?;
+    let mut at = BlockHeader { id: "##AT".into(), reserved0: 0, block_len: 96 + 5, links_nr: 4 }.to_bytes()?;
+    at.extend_from_slice(&[0u8; 8]);
+    at.extend_from_slice(&file_name.to_le_bytes());
+    at.extend_from_slice(&[0u8; 16]);
+    at.extend_from_slice(&1u16.to_le_bytes()); // embedded
+    at.extend_from_slice(&[0u8; 22]);
+    at.extend_from_slice(&5u64.to_le_bytes());
+    at.extend_from_slice(&5u64.to_le_bytes());
+    at.extend_from_slice(b"gear=");
+    let at_pos = w.write_block(&at)?;
+    w.update_link(hd_pos + 48, at_pos)?;
+
+    // ##CH with one element: the (dg, cg, cn) triple of `Temp`.
+    let ch_name = w.write_block(&TextBlock::new("Sensors").to_bytes()?)?;
+    let dg_pos = w.get_block_position(w.data_group_of(&cg).unwrap()).unwrap();
+    let element = [dg_pos, w.get_block_position(cg).unwrap(), w.get_block_position(temp).unwrap()];
+    let mut ch = BlockHeader { id: "##CH".into(), reserved0: 0, block_len: 24 + 7 * 8 + 8, links_nr: 7 }.to_bytes()?;
+    for link in [0, 0, ch_name, 0].into_iter().chain(element) {
+        ch.extend_from_slice(&link.to_le_bytes());
+    }
+    ch.extend_from_slice(&1u32.to_le_bytes()); // element count
+    ch.extend_from_slice(&[0u8; 4]); // group type
+    let ch_pos = w.write_block(&ch)?;
+    w.update_link(hd_pos + 40, ch_pos)?;
+
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(20 + i)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+/// Offsets of the blocks of type `id` in `data`.
+fn blocks_of(data: &[u8], id: &str) -> Result<Vec<u64>, MdfError> {
+    let blocks: Vec<_> = blocks::walk(data).collect::<Result<_, _>>()?;
+    Ok(blocks.into_iter().filter(|(_, h)| h.id == id).map(|(offset, _)| offset).collect())
+}
+
+/// Name of the channel the element of the `##CH` block at `ch` refers to.
+fn hierarchy_channel(data: &[u8], ch: u64) -> Result<String, MdfError> {
+    let cn: ChannelBlock = parse_at(data, links_at(data, ch)?[6])?;
+    Ok(parse_at::<TextBlock>(data, cn.name_addr)?.text)
+}
+
+#[test]
+fn cut_preserves_header_chains() -> Result<(), MdfError> {
+    let (source, output) = (path("cut_source.mf4"), path("cut_output.mf4"));
+    write_source(&source)?;
+    let options = CutOptions { preserve_blocks: true, ..CutOptions::default() };
+    cut_mdf_by_time_with_options(&source, &output, 1.0, 5.0, &options)?;
+
+    let report = mf4_rs::validate::validate_file(&output)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(&output)?;
+    assert_eq!(mdf.channel("Temp").unwrap().values_u64()?, [21, 22, 23, 24, 25].map(Some).to_vec());
+    assert_eq!(mdf.header_metadata()?.unwrap().comment.as_deref(), Some("drive"));
+    let events = mdf.events()?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name.as_deref(), Some("warm"));
+    assert_eq!(
+        events[0].scope,
+        [EventScope::ChannelGroup(Some("Engine".into())), EventScope::Channel(Some("Temp".into()))]
+    );
+    let attachments = mdf.metadata()?.attachments;
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].file_name.as_deref(), Some("setup.txt"));
+
+    let data = std::fs::read(&output)?;
+    assert_eq!(blocks_of(&data, "##FH")?.len(), 1);
+    let hierarchies = blocks_of(&data, "##CH")?;
+    assert_eq!(hierarchies.len(), 1);
+    assert_eq!(hierarchy_channel(&data, hierarchies[0])?, "Temp");
+
+    // Without the option these blocks are dropped.
+    cut_mdf_by_time(&source, &output, 1.0, 5.0)?;
+    let mdf = MDF::from_file(&output)?;
+    assert!(mdf.events()?.is_empty());
+    assert!(mdf.metadata()?.attachments.is_empty());
+    Ok(())
+}
+
+#[test]
+fn merge_concatenates_preserved_chains() -> Result<(), MdfError> {
+    let inputs = [path("merge_a.mf4"), path("merge_b.mf4")];
+    let output = path("merge_output.mf4");
+    for input in &inputs {
+        write_source(input)?;
+    }
+    let options = MergeOptions { preserve_blocks: true, ..MergeOptions::default() };
+    let paths: Vec<&str> = inputs.iter().map(String::as_str).collect();
+    merge_many_with_options(&output, &paths, &options)?;
+
+    let report = mf4_rs::validate::validate_file(&output)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(&output)?;
+    assert_eq!(mdf.channel_groups().len(), 1);
+    assert_eq!(mdf.channel("Temp").unwrap().values_u64()?.len(), 20);
+    let events = mdf.events()?;
+    assert_eq!(events.len(), 2);
+    // Both inputs' events point at the merged group.
+    for event in &events {
+        assert_eq!(event.scope[1], EventScope::Channel(Some("Temp".into())));
+    }
+    assert_eq!(mdf.metadata()?.attachments.len(), 2);
+
+    let data = std::fs::read(&output)?;
+    assert_eq!(blocks_of(&data, "##FH")?.len(), 2);
+    let hierarchies = blocks_of(&data, "##CH")?;
+    assert_eq!(hierarchies.len(), 2);
+    for ch in hierarchies {
+        assert_eq!(hierarchy_channel(&data, ch)?, "Temp");
+    }
+    Ok(())
+}
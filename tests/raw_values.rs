@@ -0,0 +1,97 @@
+//! Raw (unconverted) channel values next to the converted ones, from the
+//! file API and from the index reader.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::MdfIndex;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+fn write_file(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let time = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 32;
+    })?;
+    w.set_time_channel(&time)?;
+    let temp = w.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 16;
+    })?;
+    let state = w.add_channel(&cg, Some(&temp), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("State".into());
+        ch.bit_count = 8;
+    })?;
+    // Milliseconds to seconds.
+    w.add_linear_conversion(0.0, 0.001, Some("s"), Some(&time))?;
+    w.add_linear_conversion(-40.0, 0.5, Some("degC"), Some(&temp))?;
+    w.add_value_to_text_conversion(&[(0, "Off"), (1, "On")], "Unknown", Some(&state))?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for (ms, raw_temp, raw_state) in [(0u64, 100u64, 0u64), (10, 120, 1), (20, 140, 7)] {
+        w.write_record(&cg, &[
+            DecodedValue::UnsignedInteger(ms),
+            DecodedValue::UnsignedInteger(raw_temp),
+            DecodedValue::UnsignedInteger(raw_state),
+        ])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+fn unsigned(values: &[u64]) -> Vec<Option<DecodedValue>> {
+    values.iter().map(|v| Some(DecodedValue::UnsignedInteger(*v))).collect()
+}
+
+fn texts(values: &[&str]) -> Vec<Option<DecodedValue>> {
+    values.iter().map(|v| Some(DecodedValue::String(v.to_string()))).collect()
+}
+
+#[test]
+fn channel_raw_values_skip_the_conversion() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("raw_values_channel.mf4");
+    let path = path.to_str().unwrap();
+    write_file(path)?;
+
+    let mdf = MDF::from_file(path)?;
+    let temp = mdf.channel("Temp").unwrap();
+    assert_eq!(temp.raw_values()?, unsigned(&[100, 120, 140]));
+    assert_eq!(
+        temp.values()?,
+        [10.0, 20.0, 30.0].iter().map(|v| Some(DecodedValue::Float(*v))).collect::<Vec<_>>()
+    );
+    let state = mdf.channel("State").unwrap();
+    assert_eq!(state.raw_values()?, unsigned(&[0, 1, 7]));
+    assert_eq!(state.values()?, texts(&["Off", "On", "Unknown"]));
+    Ok(())
+}
+
+#[test]
+fn index_reader_can_skip_conversions() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("raw_values_index.mf4");
+    let path = path.to_str().unwrap();
+    write_file(path)?;
+
+    let index = MdfIndex::from_file(path)?;
+    let mut reader = index.open_file(path)?;
+    assert_eq!(reader.values("State")?, texts(&["Off", "On", "Unknown"]));
+    assert_eq!(reader.values_f64("Temp")?, vec![10.0, 20.0, 30.0]);
+
+    reader.set_raw_values(true);
+    assert_eq!(reader.values("Temp")?, unsigned(&[100, 120, 140]));
+    assert_eq!(reader.values("State")?, unsigned(&[0, 1, 7]));
+    assert_eq!(reader.values_f64("Temp")?, vec![100.0, 120.0, 140.0]);
+    // The time base of a signal stays physical.
+    let signal = reader.signal("Temp")?;
+    assert_eq!(signal.timestamps, vec![0.0, 0.01, 0.02]);
+    assert_eq!(signal.values, unsigned(&[100, 120, 140]));
+
+    reader.set_raw_values(false);
+    assert_eq!(reader.values_f64("Temp")?, vec![10.0, 20.0, 30.0]);
+    Ok(())
+}
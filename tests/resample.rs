@@ -0,0 +1,113 @@
+//! Resampling groups of different rates onto a common time base.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::blocks::text_block::TextBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::resample::{decimate, resample_groups, Decimation, ResampleOptions, TimeBase};
+use mf4_rs::signal::Interpolation;
+use mf4_rs::writer::MdfWriter;
+
+/// A 10 ms group with `Speed = 100 * t` (km/h) over one second and a 100 ms
+/// group with `Gear = 10 * t`.
+fn write_input(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    for (name, step, count) in [("Speed", 0.01, 101u64), ("Gear", 0.1, 11)] {
+        let cg = w.add_channel_group(None, |_| {})?;
+        let t = w.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        w.set_time_channel(&t)?;
+        let cn = w.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(name.into());
+            ch.bit_count = 32;
+        })?;
+        if name == "Speed" {
+            w.write_block_with_id(&TextBlock::new("km/h").to_bytes()?, "tx_speed_unit")?;
+            w.update_block_link(&cn, 72, "tx_speed_unit")?;
+        }
+        w.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..count {
+            w.write_record(&cg, &[DecodedValue::Float(i as f64 * step), DecodedValue::UnsignedInteger(i)])?;
+        }
+        w.finish_data_block(&cg)?;
+    }
+    w.finalize()
+}
+
+#[test]
+fn resample_groups_onto_common_time_base() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("rates.mf4");
+    let input = input.to_str().unwrap();
+    let output = dir.path().join("resampled.mf4");
+    let output = output.to_str().unwrap();
+    write_input(input)?;
+    let mdf = MDF::from_file(input)?;
+
+    // The 10 ms group is the fastest; Gear is interpolated in between.
+    assert_eq!(resample_groups(&mdf, &[0, 1], output, &ResampleOptions::default())?, 101);
+    let out = MDF::from_file(output)?;
+    assert_eq!(out.channel_groups().len(), 1);
+    let speed = out.channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.values_f64()?[37], Some(37.0));
+    let gear = out.channel("Gear").unwrap().values_f64()?;
+    assert!((gear[25].unwrap() - 2.5).abs() < 1e-9);
+    assert_eq!(out.channel("Time").unwrap().values_f64()?[100], Some(1.0));
+
+    let hold = ResampleOptions { interpolation: Interpolation::ZeroOrderHold, ..Default::default() };
+    resample_groups(&mdf, &[0, 1], output, &hold)?;
+    let gear = MDF::from_file(output)?.channel("Gear").unwrap().values_f64()?;
+    assert_eq!((gear[25], gear[29], gear[31]), (Some(2.0), Some(2.0), Some(3.0)));
+
+    // A coarser grid than either group.
+    let grid = ResampleOptions { time_base: TimeBase::Interval(0.25), ..Default::default() };
+    assert_eq!(resample_groups(&mdf, &[1, 0], output, &grid)?, 5);
+    let out = MDF::from_file(output)?;
+    let gear = out.channel("Gear").unwrap().values_f64()?;
+    for (value, expected) in gear.iter().zip([0.0, 2.5, 5.0, 7.5, 10.0]) {
+        assert!((value.unwrap() - expected).abs() < 1e-9);
+    }
+
+    let on_gear = ResampleOptions { time_base: TimeBase::Group(1), ..Default::default() };
+    assert_eq!(resample_groups(&mdf, &[0], output, &on_gear)?, 11);
+    assert_eq!(MDF::from_file(output)?.channel("Speed").unwrap().values_f64()?[5], Some(50.0));
+
+    assert!(resample_groups(&mdf, &[2], output, &ResampleOptions::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn decimate_keeps_every_nth_or_nearest_to_grid() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("rates.mf4");
+    let output = dir.path().join("decimated.mf4");
+    write_input(input.to_str().unwrap())?;
+
+    // 101 + 11 records: every 10th keeps 11 + 2.
+    assert_eq!(decimate(&input, &output, Decimation::Factor(10))?, 13);
+    let out = MDF::from_file(&output)?;
+    let groups = out.channel_groups();
+    let speed = groups[0].channel("Speed").unwrap();
+    assert_eq!(speed.unit()?.as_deref(), Some("km/h"));
+    assert_eq!(speed.values_f64()?, (0..=10).map(|i| Some(i as f64 * 10.0)).collect::<Vec<_>>());
+    assert_eq!(groups[1].channel("Gear").unwrap().values_f64()?, vec![Some(0.0), Some(10.0)]);
+
+    // A 4 Hz grid picks the records nearest to 0, 0.25, ..., 1 s.
+    assert_eq!(decimate(&input, &output, Decimation::Interval(0.25))?, 10);
+    let out = MDF::from_file(&output)?;
+    let groups = out.channel_groups();
+    assert_eq!(groups[0].channel("Speed").unwrap().values_f64()?, [0.0, 25.0, 50.0, 75.0, 100.0].map(Some).to_vec());
+    assert_eq!(groups[1].channel("Gear").unwrap().values_f64()?, [0.0, 2.0, 5.0, 7.0, 10.0].map(Some).to_vec());
+    assert_eq!(groups[1].raw_channel_group().block.cycles_nr, 5);
+
+    assert!(decimate(&input, &output, Decimation::Factor(0)).is_err());
+    assert!(decimate(&input, &output, Decimation::Interval(-1.0)).is_err());
+    Ok(())
+}
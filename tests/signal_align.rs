@@ -0,0 +1,70 @@
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::signal::{InvalidationPolicy, Signal};
+
+fn sample_signal() -> Signal {
+    Signal {
+        name: "Speed".to_string(),
+        unit: Some("km/h".to_string()),
+        timestamps: vec![0.0, 1.0, 2.0, 3.0],
+        values: vec![
+            Some(DecodedValue::Float(0.0)),
+            Some(DecodedValue::Float(10.0)),
+            None,
+            Some(DecodedValue::Float(30.0)),
+        ],
+    }
+}
+
+#[test]
+fn validity_mask_matches_values() {
+    let sig = sample_signal();
+    assert_eq!(sig.validity(), vec![true, true, false, true]);
+    assert_eq!(sig.valid_count(), 3);
+    assert_eq!(sig.validity().len(), sig.timestamps.len());
+}
+
+#[test]
+fn align_any_invalid_marks_contributing_invalid() {
+    let sig = sample_signal();
+    let out = sig.align_to(&[0.5, 1.5, 2.0, 2.5, 3.0, 4.0], InvalidationPolicy::AnyInvalid);
+    assert_eq!(out.timestamps, vec![0.5, 1.5, 2.0, 2.5, 3.0, 4.0]);
+    assert_eq!(out.values[0], Some(DecodedValue::Float(5.0)));
+    assert_eq!(out.values[1], None);
+    assert_eq!(out.values[2], None);
+    assert_eq!(out.values[3], None);
+    assert_eq!(out.values[4], Some(DecodedValue::Float(30.0)));
+    assert_eq!(out.values[5], None);
+    assert_eq!(out.unit.as_deref(), Some("km/h"));
+}
+
+#[test]
+fn align_skip_invalid_uses_nearest_valid_samples() {
+    let sig = sample_signal();
+    let out = sig.align_to(&[1.5, 2.0, 2.5], InvalidationPolicy::SkipInvalid);
+    assert_eq!(out.values[0], Some(DecodedValue::Float(15.0)));
+    assert_eq!(out.values[1], Some(DecodedValue::Float(20.0)));
+    assert_eq!(out.values[2], Some(DecodedValue::Float(25.0)));
+}
+
+#[test]
+fn align_holds_non_numeric_values() {
+    let sig = Signal {
+        name: "State".to_string(),
+        unit: None,
+        timestamps: vec![0.0, 1.0],
+        values: vec![
+            Some(DecodedValue::String("idle".into())),
+            Some(DecodedValue::String("run".into())),
+        ],
+    };
+    let out = sig.align_to(&[0.5], InvalidationPolicy::default());
+    assert_eq!(out.values[0], Some(DecodedValue::String("idle".into())));
+}
+
+#[test]
+fn align_without_master_is_all_invalid() {
+    let mut sig = sample_signal();
+    sig.timestamps.clear();
+    let out = sig.align_to(&[0.0, 1.0], InvalidationPolicy::AnyInvalid);
+    assert!(out.values.iter().all(|v| v.is_none()));
+}
@@ -0,0 +1,224 @@
+//! `sort_mdf` rewrites unsorted data groups into one data group per
+//! channel group, with the record IDs stripped and VLSD channel groups
+//! folded into `##SD` blocks.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::channel_group_block::ChannelGroupBlock;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::blocks::data_list_block::DataListBlock;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::MdfIndex;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::sort::sort_mdf;
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+/// Build a data group, split over two `##DT` blocks, with record ID length 1 holding
+/// * group 1: `Time` (f64) + `Speed` (u16), 10 records
+/// * group 2: `Time` (f64) + `Temp` (i32) + VLSD `Frame`, 5 records
+/// * group 3: the VLSD channel group holding the `Frame` payloads
+fn write_unsorted(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let dg = w.add_data_group(None)?;
+    let fast = w.add_channel_group_with_dg(&dg, None, |cg| {
+        cg.record_id = 1;
+        cg.samples_byte_nr = 10;
+        cg.cycles_nr = 10;
+        cg.acq_name = Some("Fast".into());
+    })?;
+    let slow = w.add_channel_group_with_dg(&dg, Some(&fast), |cg| {
+        cg.record_id = 2;
+        cg.samples_byte_nr = 20;
+        cg.cycles_nr = 5;
+        cg.acq_name = Some("Slow".into());
+    })?;
+    let frames = w.add_channel_group_with_dg(&dg, Some(&slow), |cg| {
+        cg.record_id = 3;
+        cg.flags = 1;
+        cg.cycles_nr = 5;
+    })?;
+
+    let t1 = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t1)?;
+    w.add_channel(&fast, Some(&t1), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 16;
+    })?;
+    let t2 = w.add_channel(&slow, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t2)?;
+    let temp = w.add_channel(&slow, Some(&t2), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 32;
+    })?;
+    let frame = w.add_channel(&slow, Some(&temp), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Frame".into());
+        ch.byte_offset = 12;
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    w.update_block_link(&frame, 64, &frames)?;
+
+    // Interleave: every fast record, a slow record (and its VLSD entry)
+    // after every second one.
+    let mut data = Vec::new();
+    let mut vlsd_offset = 0u64;
+    for i in 0..10u64 {
+        data.push(1);
+        data.extend_from_slice(&(i as f64 * 0.1).to_le_bytes());
+        data.extend_from_slice(&(i as u16 * 10).to_le_bytes());
+        if i % 2 == 1 {
+            let k = i / 2;
+            let payload = vec![k as u8; k as usize + 1];
+            data.push(3);
+            data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            data.extend_from_slice(&payload);
+            data.push(2);
+            data.extend_from_slice(&(i as f64 * 0.1).to_le_bytes());
+            data.extend_from_slice(&(-(k as i32)).to_le_bytes());
+            data.extend_from_slice(&vlsd_offset.to_le_bytes());
+            vlsd_offset += 4 + payload.len() as u64;
+        }
+    }
+    // Split the stream at an odd offset so a record straddles the blocks.
+    let (first, second) = data.split_at(data.len() / 2 + 1);
+    let mut links = Vec::new();
+    for part in [first, second] {
+        let header = BlockHeader {
+            id: "##DT".into(),
+            reserved0: 0,
+            block_len: 24 + part.len() as u64,
+            links_nr: 0,
+        };
+        let mut dt = header.to_bytes()?;
+        dt.extend_from_slice(part);
+        links.push(w.write_block(&dt)?);
+    }
+    let dl = DataListBlock::new_variable(links, vec![0, first.len() as u64]);
+    w.write_block_with_id(&dl.to_bytes()?, "dl_unsorted")?;
+    w.update_block_link(&dg, 40, "dl_unsorted")?;
+    let dg_pos = w.get_block_position(&dg).unwrap() as usize;
+    w.finalize()?;
+
+    // dg_rec_id_size lives at offset 56 of the ##DG block.
+    let mut bytes = std::fs::read(path)?;
+    bytes[dg_pos + 56] = 1;
+    std::fs::write(path, &bytes)?;
+    Ok(())
+}
+
+#[test]
+fn sort_splits_unsorted_groups() -> Result<(), MdfError> {
+    let tmp = std::env::temp_dir();
+    let inp = tmp.join("sort_unsorted_in.mf4");
+    let out = tmp.join("sort_unsorted_out.mf4");
+    write_unsorted(inp.to_str().unwrap())?;
+
+    sort_mdf(inp.to_str().unwrap(), out.to_str().unwrap())?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 2);
+    assert!(groups.iter().all(|g| !g.is_unsorted()));
+
+    let fast = mdf.group("Fast").unwrap();
+    let speed = fast.channel("Speed").unwrap().values_i64()?;
+    assert_eq!(speed, (0..10).map(|i| Some(i * 10)).collect::<Vec<_>>());
+
+    let slow = mdf.group("Slow").unwrap();
+    let temp = slow.channel("Temp").unwrap().values()?;
+    assert_eq!(temp, (0..5).map(|k| Some(DecodedValue::SignedInteger(-k))).collect::<Vec<_>>());
+    let time = slow.channel("Time").unwrap().values_as_f64()?;
+    assert!((time[4] - 0.9).abs() < 1e-12);
+    let frames = slow.channel("Frame").unwrap().values()?;
+    let expected: Vec<_> = (0..5u8)
+        .map(|k| Some(DecodedValue::ByteArray(vec![k; k as usize + 1])))
+        .collect();
+    assert_eq!(frames, expected);
+
+    // Sorted output can be indexed.
+    MdfIndex::from_file(out.to_str().unwrap())?;
+
+    std::fs::remove_file(&inp)?;
+    std::fs::remove_file(&out)?;
+    Ok(())
+}
+
+#[test]
+fn sort_reads_compressed_data_block_by_block() -> Result<(), MdfError> {
+    let tmp = std::env::temp_dir();
+    let inp = tmp.join("sort_compressed_in.mf4");
+    let out = tmp.join("sort_compressed_out.mf4");
+    let mut w = MdfWriter::new(inp.to_str().unwrap())?;
+    w.init_mdf_file()?;
+    w.set_compression(CompressionMethod::Zlib)?;
+    // Room for a handful of records, so records straddle the `##DZ` blocks.
+    w.set_data_block_size(64)?;
+    let mut groups = Vec::new();
+    for (name, value, data_type, bits) in [
+        ("Fast", "Speed", DataType::UnsignedIntegerLE, 16),
+        ("Log", "Message", DataType::StringUtf8, 64),
+    ] {
+        let configure = |cg: &mut ChannelGroupBlock| cg.acq_name = Some(name.into());
+        let cg = match groups.first() {
+            Some(sibling) => w.add_unsorted_channel_group(sibling, configure)?,
+            None => w.add_channel_group(None, configure)?,
+        };
+        let t = w.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        w.set_time_channel(&t)?;
+        w.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = data_type;
+            ch.name = Some(value.into());
+            ch.bit_count = bits;
+            if ch.data_type == DataType::StringUtf8 {
+                ch.channel_type = 1;
+                ch.data = 1;
+            }
+        })?;
+        groups.push(cg);
+    }
+    let dg = w.data_group_of(&groups[0]).unwrap();
+    w.start_unsorted_data_block(&dg, 1)?;
+    for i in 0..100u64 {
+        let t = DecodedValue::Float(i as f64 * 0.01);
+        w.write_record(&groups[0], &[t.clone(), DecodedValue::UnsignedInteger(i)])?;
+        if i % 10 == 9 {
+            w.write_record(&groups[1], &[t, DecodedValue::String(format!("tick {i}"))])?;
+        }
+    }
+    w.finish_unsorted_data_block(&dg)?;
+    w.finalize()?;
+
+    sort_mdf(inp.to_str().unwrap(), out.to_str().unwrap())?;
+
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    assert!(mdf.channel_groups().iter().all(|g| !g.is_unsorted()));
+    let speed = mdf.group("Fast").unwrap().channel("Speed").unwrap().values_u64()?;
+    assert_eq!(speed, (0..100).map(Some).collect::<Vec<_>>());
+    let messages = mdf.group("Log").unwrap().channel("Message").unwrap().values()?;
+    let expected: Vec<_> = (0..10)
+        .map(|k| Some(DecodedValue::String(format!("tick {}", 10 * k + 9))))
+        .collect();
+    assert_eq!(messages, expected);
+
+    std::fs::remove_file(&inp)?;
+    std::fs::remove_file(&out)?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Fixed-length string channels are written in their declared encoding.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, MdfWriter};
+
+const ENCODINGS: [(&str, DataType); 4] = [
+    ("Latin1", DataType::StringLatin1),
+    ("Utf8", DataType::StringUtf8),
+    ("Utf16LE", DataType::StringUtf16LE),
+    ("Utf16BE", DataType::StringUtf16BE),
+];
+
+/// Group with a `Time` master and one 16-byte string channel per encoding.
+fn string_group(w: &mut MdfWriter<Cursor<Vec<u8>>>) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let mut prev = t;
+    for (name, data_type) in ENCODINGS {
+        prev = w.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = data_type;
+            ch.name = Some(name.into());
+            ch.bit_count = 16 * 8;
+        })?;
+    }
+    Ok(cg)
+}
+
+fn record(time: f64, text: &str) -> Vec<DecodedValue> {
+    let mut values = vec![DecodedValue::Float(time)];
+    values.extend(ENCODINGS.iter().map(|_| DecodedValue::String(text.into())));
+    values
+}
+
+#[test]
+fn strings_round_trip_in_every_encoding() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = string_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    // Second record is shorter, so padding must clear the first one's tail.
+    w.write_record(&cg, &record(0.0, "Grüße"))?;
+    w.write_record(&cg, &record(1.0, "ok"))?;
+    w.write_record(&cg, &record(2.0, ""))?;
+    w.finish_data_block(&cg)?;
+
+    let mdf = MDF::from_bytes(w.into_inner()?.into_inner())?;
+    for (name, _) in ENCODINGS {
+        assert_eq!(
+            mdf.channel(name).unwrap().values()?,
+            vec![
+                Some(DecodedValue::String("Grüße".into())),
+                Some(DecodedValue::String("ok".into())),
+                Some(DecodedValue::String(String::new())),
+            ],
+            "{name}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn strings_longer_than_the_field_are_rejected() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let cg = string_group(&mut w)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    // Nine UTF-16 code units take 18 bytes; Latin-1 and UTF-8 fit.
+    let err = w.write_record(&cg, &record(0.0, "123456789")).unwrap_err();
+    assert!(err.to_string().contains("18 bytes"), "{err}");
+    let err = w.write_record(&cg, &record(0.0, "€")).unwrap_err();
+    assert!(err.to_string().contains("Latin-1"), "{err}");
+    Ok(())
+}
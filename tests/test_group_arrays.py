@@ -0,0 +1,90 @@
+"""Python integration test for ``Mdf.get_group_as_arrays`` / ``Mdf.group_arrays``.
+
+Writes a file whose second group has two channels sharing a name and checks
+that ``get_group_as_arrays(group_index)`` keeps both of them while
+``group_arrays(name)`` keys the duplicate by its position.
+
+Exits 0 (skip) if the bindings are not importable so this can sit in CI
+without forcing a maturin build on every runner.
+"""
+
+from __future__ import annotations
+
+import os
+import sys
+import tempfile
+
+try:
+    import numpy as np
+    import mf4_rs
+except ImportError as e:
+    print(f"SKIP: mf4_rs not importable ({e}); run `maturin develop --release` first")
+    sys.exit(0)
+
+
+RECORDS = 50
+
+
+def build_fixture(path: str) -> None:
+    w = mf4_rs.MdfWriter(path)
+    w.init_mdf_file()
+    first = w.add_channel_group("First")
+    w.add_time_channel(first, "Time")
+    second = w.add_channel_group("Second")
+    w.add_time_channel(second, "Time")
+    w.add_float_channel(second, "Speed")
+    w.add_float_channel(second, "Speed")
+
+    w.start_data_block(first)
+    for r in range(RECORDS):
+        w.write_record(first, [mf4_rs.create_float_value(r * 0.1)])
+    w.finish_data_block(first)
+
+    w.start_data_block(second)
+    for r in range(RECORDS):
+        w.write_record(
+            second,
+            [
+                mf4_rs.create_float_value(r * 0.1),
+                mf4_rs.create_float_value(float(r)),
+                mf4_rs.create_float_value(float(-r)),
+            ],
+        )
+    w.finish_data_block(second)
+    w.finalize()
+
+
+def main() -> int:
+    with tempfile.TemporaryDirectory() as tmp:
+        path = os.path.join(tmp, "group_arrays.mf4")
+        build_fixture(path)
+        mdf = mf4_rs.Mdf(path)
+
+        columns = mdf.get_group_as_arrays(1)
+        assert [name for name, _ in columns] == ["Time", "Speed", "Speed"], columns
+        for _, array in columns:
+            assert isinstance(array, np.ndarray) and array.dtype == np.float64
+            assert len(array) == RECORDS
+            # Zero-copy columns expose the buffer protocol.
+            assert memoryview(array).nbytes == RECORDS * 8
+        assert columns[1][1][-1] == RECORDS - 1
+        assert columns[2][1][-1] == -(RECORDS - 1)
+        assert len(mdf.get_group_as_arrays(0)) == 1
+
+        try:
+            mdf.get_group_as_arrays(2)
+        except mf4_rs.MdfException:
+            pass
+        else:
+            raise AssertionError("group index 2 should be out of range")
+
+        arrays = mdf.group_arrays("Second")
+        assert list(arrays) == ["Time", "Speed", "channel_2"], list(arrays)
+        assert arrays["channel_2"][-1] == -(RECORDS - 1)
+
+    print("OK")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
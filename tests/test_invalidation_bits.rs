@@ -38,6 +38,7 @@ fn create_test_channel(flags: u32, pos_invalidation_bit: u32) -> ChannelBlock {
         upper_ext_limit: 0.0,
         name: None,
         conversion: None,
+        source: None,
     }
 }
 
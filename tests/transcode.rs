@@ -0,0 +1,126 @@
+//! Files re-created from an index and a range reader with `transcode`.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::index::{MdfIndex, SliceRangeReader};
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::transcode::{transcode, TranscodeOptions};
+use mf4_rs::writer::{CompressionMethod, MdfWriter};
+
+fn write_source(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    w.set_start_time(1_700_000_000_000_000_000, 0, 0, 0, 0)?;
+
+    let engine = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&engine, "Engine")?;
+    w.set_channel_group_comment(&engine, "engine signals")?;
+    let time = w.add_channel(&engine, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    let temp = w.add_channel(&engine, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.bit_count = 16;
+    })?;
+    let gear = w.add_channel(&engine, Some(&temp), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".into());
+        ch.bit_count = 3;
+    })?;
+    let state = w.add_channel(&engine, Some(&gear), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("State".into());
+        ch.bit_count = 5;
+    })?;
+    w.add_linear_conversion(-40.0, 0.5, Some("degC"), Some(&temp))?;
+    w.add_value_to_text_conversion(&[(0, "Off"), (1, "On")], "Unknown", Some(&state))?;
+    w.start_data_block_for_cg(&engine, 0)?;
+    for i in 0..200u64 {
+        w.write_record(&engine, &[
+            DecodedValue::Float(i as f64 * 0.01),
+            DecodedValue::UnsignedInteger(100 + i),
+            DecodedValue::UnsignedInteger(i % 6),
+            DecodedValue::UnsignedInteger(i % 3),
+        ])?;
+    }
+    w.finish_data_block(&engine)?;
+
+    let gps = w.add_channel_group(None, |_| {})?;
+    w.set_channel_group_name(&gps, "GPS")?;
+    let time = w.add_channel(&gps, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("GpsTime".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&time)?;
+    w.add_channel(&gps, Some(&time), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Altitude".into());
+        ch.bit_count = 32;
+    })?;
+    w.start_data_block_for_cg(&gps, 0)?;
+    for i in 0..20i64 {
+        w.write_record(&gps, &[DecodedValue::Float(i as f64 * 0.1), DecodedValue::SignedInteger(-5 * i)])?;
+    }
+    w.finish_data_block(&gps)?;
+    w.finalize()
+}
+
+#[test]
+fn transcoded_file_matches_the_source() -> Result<(), MdfError> {
+    let source = std::env::temp_dir().join("transcode_source.mf4");
+    let source = source.to_str().unwrap();
+    let output = std::env::temp_dir().join("transcode_output.mf4");
+    let output = output.to_str().unwrap();
+    write_source(source)?;
+
+    // The index and the records both come through a range reader, as for a
+    // remote file.
+    let bytes = std::fs::read(source)?;
+    let index = MdfIndex::from_reader(&mut SliceRangeReader::new(bytes.clone()))?;
+    let options = TranscodeOptions { compression: CompressionMethod::Zlib, data_block_size: Some(1024) };
+    transcode(&index, &mut SliceRangeReader::new(bytes), &options, output)?;
+
+    let report = mf4_rs::validate::validate_file(output)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let copy = MdfIndex::from_file(output)?;
+    assert_eq!(copy.start_time_ns, Some(1_700_000_000_000_000_000));
+    let engine = copy.group("Engine").unwrap();
+    assert_eq!(engine.comment.as_deref(), Some("engine signals"));
+    assert!(engine.data_blocks.len() > 1);
+    assert!(engine.data_blocks.iter().all(|block| block.is_compressed));
+    assert_eq!(copy.channel("Temp").unwrap().unit.as_deref(), Some("degC"));
+
+    let mut original = index.open_file(source)?;
+    let mut copied = copy.open_file(output)?;
+    for name in ["Time", "Temp", "Gear", "State", "GpsTime", "Altitude"] {
+        assert_eq!(copied.values(name)?, original.values(name)?, "{name}");
+    }
+    assert_eq!(copied.signal("Altitude")?.timestamps, original.signal("Altitude")?.timestamps);
+
+    let mdf = MDF::from_file(output)?;
+    let names: Vec<_> = mdf.channel_groups().iter().map(|g| g.name().unwrap()).collect();
+    assert_eq!(names, [Some("Engine".to_string()), Some("GPS".to_string())]);
+    Ok(())
+}
+
+#[test]
+fn transcode_rejects_a_different_source() -> Result<(), MdfError> {
+    let source = std::env::temp_dir().join("transcode_stale_source.mf4");
+    let source = source.to_str().unwrap();
+    let output = std::env::temp_dir().join("transcode_stale_output.mf4");
+    write_source(source)?;
+
+    let index = MdfIndex::from_file(source)?;
+    let mut bytes = std::fs::read(source)?;
+    bytes.truncate(bytes.len() / 2);
+    let err = transcode(&index, &mut SliceRangeReader::new(bytes), &TranscodeOptions::default(), output.to_str().unwrap())
+        .unwrap_err();
+    assert!(matches!(err, MdfError::StaleIndex(_)), "{err:?}");
+    Ok(())
+}
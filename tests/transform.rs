@@ -0,0 +1,80 @@
+//! Derived channels appended to a copy of a file.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::transform::MdfTransformer;
+use mf4_rs::writer::MdfWriter;
+
+/// One group with `Time`, `Voltage` (f64) and `Current` (u16) over 10 records.
+fn write_input(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    let v = w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Voltage".into());
+        ch.bit_count = 64;
+    })?;
+    w.add_channel(&cg, Some(&v), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Current".into());
+        ch.bit_count = 16;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        w.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.1),
+                DecodedValue::Float(12.0 + i as f64),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+#[test]
+fn add_derived_channels() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("drive.mf4");
+    let input = input.to_str().unwrap();
+    let output = dir.path().join("drive_power.mf4");
+    let output = output.to_str().unwrap();
+    write_input(input)?;
+
+    let mut count = 0;
+    MdfTransformer::new(input)
+        .add_derived_channel(0, "Power", |r| r.f64("Voltage").unwrap() * r.f64("Current").unwrap())
+        .add_derived_channel(0, "Index", move |_| {
+            count += 1;
+            count as f64
+        })
+        .write(output)?;
+
+    let out = MDF::from_file(output)?;
+    let names: Vec<_> = out.channel_groups()[0]
+        .channels()
+        .iter()
+        .map(|c| c.name().unwrap().unwrap())
+        .collect();
+    assert_eq!(names, ["Time", "Voltage", "Current", "Power", "Index"]);
+    let power = out.channel("Power").unwrap().values_f64()?;
+    assert_eq!(power.len(), 10);
+    assert_eq!(power[3], Some(45.0));
+    assert_eq!(out.channel("Index").unwrap().values_f64()?[9], Some(10.0));
+    assert_eq!(out.channel("Current").unwrap().values_u64()?[7], Some(7));
+    assert_eq!(out.channel("Voltage").unwrap().values_f64()?[7], Some(19.0));
+
+    assert!(MdfTransformer::new(input).add_derived_channel(1, "X", |_| 0.0).write(output).is_err());
+    Ok(())
+}
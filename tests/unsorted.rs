@@ -0,0 +1,258 @@
+//! Unsorted data groups: several channel groups share one data group and
+//! their records are interleaved, told apart by a leading record ID.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::channel_group_block::ChannelGroupBlock;
+use mf4_rs::blocks::common::{BlockHeader, DataType};
+use mf4_rs::error::MdfError;
+use mf4_rs::index::MdfIndex;
+use mf4_rs::parsing::mdf_file::MdfFile;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, CompressionMethod, MdfWriter};
+
+/// Build a data group with record ID length 1 holding
+/// * group 1: `Time` (f64) + `Speed` (u16), 10 records
+/// * group 2: `Time` (f64) + `Temp` (i32) + VLSD `Frame`, 5 records
+/// * group 3: the VLSD channel group holding the `Frame` payloads
+fn write_unsorted(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let dg = w.add_data_group(None)?;
+    let fast = w.add_channel_group_with_dg(&dg, None, |cg| {
+        cg.record_id = 1;
+        cg.samples_byte_nr = 10;
+        cg.cycles_nr = 10;
+        cg.acq_name = Some("Fast".into());
+    })?;
+    let slow = w.add_channel_group_with_dg(&dg, Some(&fast), |cg| {
+        cg.record_id = 2;
+        cg.samples_byte_nr = 20;
+        cg.cycles_nr = 5;
+        cg.acq_name = Some("Slow".into());
+    })?;
+    let frames = w.add_channel_group_with_dg(&dg, Some(&slow), |cg| {
+        cg.record_id = 3;
+        cg.flags = 1;
+        cg.cycles_nr = 5;
+    })?;
+
+    let t1 = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t1)?;
+    w.add_channel(&fast, Some(&t1), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 16;
+    })?;
+    let t2 = w.add_channel(&slow, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t2)?;
+    let temp = w.add_channel(&slow, Some(&t2), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Temp".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 32;
+    })?;
+    let frame = w.add_channel(&slow, Some(&temp), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Frame".into());
+        ch.byte_offset = 12;
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    w.update_block_link(&frame, 64, &frames)?;
+
+    // Interleave: every fast record, a slow record (and its VLSD entry)
+    // after every second one.
+    let mut data = Vec::new();
+    let mut vlsd_offset = 0u64;
+    for i in 0..10u64 {
+        data.push(1);
+        data.extend_from_slice(&(i as f64 * 0.1).to_le_bytes());
+        data.extend_from_slice(&(i as u16 * 10).to_le_bytes());
+        if i % 2 == 1 {
+            let k = i / 2;
+            let payload = vec![k as u8; k as usize + 1];
+            data.push(3);
+            data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            data.extend_from_slice(&payload);
+            data.push(2);
+            data.extend_from_slice(&(i as f64 * 0.1).to_le_bytes());
+            data.extend_from_slice(&(-(k as i32)).to_le_bytes());
+            data.extend_from_slice(&vlsd_offset.to_le_bytes());
+            vlsd_offset += 4 + payload.len() as u64;
+        }
+    }
+    let header = BlockHeader {
+        id: "##DT".into(),
+        reserved0: 0,
+        block_len: 24 + data.len() as u64,
+        links_nr: 0,
+    };
+    let mut dt = header.to_bytes()?;
+    dt.extend_from_slice(&data);
+    w.write_block_with_id(&dt, "dt_unsorted")?;
+    w.update_block_link(&dg, 40, "dt_unsorted")?;
+    let dg_pos = w.get_block_position(&dg).unwrap() as usize;
+    w.finalize()?;
+
+    // dg_rec_id_size lives at offset 56 of the ##DG block.
+    let mut bytes = std::fs::read(path)?;
+    bytes[dg_pos + 56] = 1;
+    std::fs::write(path, &bytes)?;
+    Ok(())
+}
+
+#[test]
+fn unsorted_groups_are_demultiplexed() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("unsorted_groups.mf4");
+    let path = path.to_str().unwrap();
+    write_unsorted(path)?;
+
+    let mdf = MDF::from_file(path)?;
+    let fast = mdf.group("Fast").unwrap();
+    assert!(fast.is_unsorted());
+    let speed = fast.channel("Speed").unwrap().values_i64()?;
+    assert_eq!(speed, (0..10).map(|i| Some(i * 10)).collect::<Vec<_>>());
+    assert_eq!(fast.channel("Time").unwrap().values_as_f64()?.len(), 10);
+
+    let slow = mdf.group("Slow").unwrap();
+    let temp = slow.channel("Temp").unwrap().values()?;
+    assert_eq!(temp, (0..5).map(|k| Some(DecodedValue::SignedInteger(-k))).collect::<Vec<_>>());
+    let time = slow.channel("Time").unwrap().values_as_f64()?;
+    assert!((time[4] - 0.9).abs() < 1e-12);
+    let frames = slow.channel("Frame").unwrap().values()?;
+    let expected: Vec<_> = (0..5u8)
+        .map(|k| Some(DecodedValue::ByteArray(vec![k; k as usize + 1])))
+        .collect();
+    assert_eq!(frames, expected);
+
+    let rows = slow.iter_records()?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[2][2], Some(DecodedValue::ByteArray(vec![2; 3])));
+
+    assert!(MdfIndex::from_file(path).is_err());
+    let mut reader = mf4_rs::index::MmapRangeReader::new(path)?;
+    assert!(MdfIndex::from_reader(&mut reader).is_err());
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// `Time` (f64) plus `value` in a new channel group of `w`; in the data
+/// group of `sibling` if given.
+fn timed_group(
+    w: &mut MdfWriter<Cursor<Vec<u8>>>,
+    sibling: Option<&ChannelGroupId>,
+    name: &str,
+    value: (&str, DataType, u32),
+) -> Result<ChannelGroupId, MdfError> {
+    let configure = |cg: &mut ChannelGroupBlock| cg.acq_name = Some(name.into());
+    let cg = match sibling {
+        Some(sibling) => w.add_unsorted_channel_group(sibling, configure)?,
+        None => w.add_channel_group(None, configure)?,
+    };
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), |ch| {
+        ch.data_type = value.1;
+        ch.name = Some(value.0.into());
+        ch.bit_count = value.2;
+        if ch.data_type == DataType::StringUtf8 {
+            ch.channel_type = 1;
+            ch.data = 1;
+        }
+    })?;
+    Ok(cg)
+}
+
+#[test]
+fn writer_interleaves_groups_of_one_data_group() -> Result<(), MdfError> {
+    for compression in [CompressionMethod::None, CompressionMethod::Zlib] {
+        let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+        w.init_mdf_file()?;
+        w.set_compression(compression)?;
+        // Room for a handful of records, so the stream spans several blocks.
+        w.set_data_block_size(64)?;
+        let fast = timed_group(&mut w, None, "Fast", ("Speed", DataType::UnsignedIntegerLE, 16))?;
+        let slow = timed_group(&mut w, Some(&fast), "Slow", ("Temp", DataType::SignedIntegerLE, 32))?;
+        let log = timed_group(&mut w, Some(&slow), "Log", ("Message", DataType::StringUtf8, 64))?;
+        let dg = w.data_group_of(&fast).unwrap();
+        assert_eq!(w.data_group_of(&log), Some(dg));
+
+        w.start_unsorted_data_block(&dg, 1)?;
+        assert!(matches!(w.finish_data_block(&fast), Err(MdfError::InvalidWriterState(_))));
+        for i in 0..40u64 {
+            let t = DecodedValue::Float(i as f64 * 0.01);
+            w.write_record(&fast, &[t.clone(), DecodedValue::UnsignedInteger(i)])?;
+            if i % 4 == 3 {
+                w.write_record(&slow, &[t.clone(), DecodedValue::SignedInteger(-(i as i64))])?;
+            }
+            if i % 10 == 9 {
+                w.write_record(&log, &[t, DecodedValue::String(format!("tick {i}"))])?;
+            }
+        }
+        let rows: Vec<Vec<DecodedValue>> = (40..60)
+            .map(|i| vec![DecodedValue::Float(i as f64 * 0.01), DecodedValue::UnsignedInteger(i)])
+            .collect();
+        w.write_records(&fast, rows.iter().map(Vec::as_slice))?;
+        w.finish_unsorted_data_block(&dg)?;
+        let bytes = w.into_inner()?.into_inner();
+
+        // The records are demultiplexed block by block, not into one copy.
+        let raw = MdfFile::parse_from_bytes(bytes.clone())?;
+        let group = &raw.data_groups[0];
+        let blocks = group
+            .channel_group_blocks(&group.channel_groups[0], &raw.mmap)?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(blocks.len() > 1);
+        assert_eq!(blocks.iter().map(|b| b.data.len()).sum::<usize>(), 60 * (1 + 8 + 2));
+
+        let mdf = MDF::from_bytes(bytes)?;
+        let fast = mdf.group("Fast").unwrap();
+        assert!(fast.is_unsorted());
+        assert_eq!(fast.channel("Speed").unwrap().values_u64()?, (0..60).map(Some).collect::<Vec<_>>());
+        let slow = mdf.group("Slow").unwrap();
+        assert_eq!(
+            slow.channel("Temp").unwrap().values_i64()?,
+            (0..10).map(|k| Some(-(4 * k + 3))).collect::<Vec<_>>()
+        );
+        let times = slow.channel("Time").unwrap().values_f64()?;
+        assert!((times[9].unwrap() - 0.39).abs() < 1e-12);
+        let messages = mdf.group("Log").unwrap().channel("Message").unwrap().values()?;
+        assert_eq!(messages, ["tick 9", "tick 19", "tick 29", "tick 39"].map(|m| Some(DecodedValue::String(m.into()))));
+    }
+    Ok(())
+}
+
+#[test]
+fn unsorted_groups_need_distinct_record_ids() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let a = timed_group(&mut w, None, "A", ("X", DataType::UnsignedIntegerLE, 8))?;
+    let b = w.add_unsorted_channel_group(&a, |cg| cg.record_id = 1)?;
+    w.add_channel(&b, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Y".into());
+        ch.bit_count = 8;
+    })?;
+    let dg = w.data_group_of(&a).unwrap();
+    assert!(matches!(w.start_unsorted_data_block(&dg, 0), Err(MdfError::InvalidRecordLayout(_))));
+    // `A` was given record ID 1 when `B` joined it.
+    assert!(matches!(w.start_unsorted_data_block(&dg, 1), Err(MdfError::InvalidRecordLayout(_))));
+    assert!(matches!(w.finish_unsorted_data_block(&dg), Err(MdfError::InvalidWriterState(_))));
+    Ok(())
+}
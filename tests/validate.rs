@@ -0,0 +1,164 @@
+//! Structural validation of written and corrupted files.
+
+use std::io::Cursor;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::block_layout::FileLayout;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::validate::{record_statistics, validate_bytes, validate_file, Severity};
+use mf4_rs::writer::MdfWriter;
+
+fn write_sample(path: &str) -> Result<(), MdfError> {
+    let mut w = MdfWriter::new(path)?;
+    w.init_mdf_file()?;
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10 {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64)])?;
+    }
+    w.finish_data_block(&cg)?;
+    w.finalize()
+}
+
+/// File offset of the first block with ID `id`.
+fn offset_of(bytes: &[u8], id: &str) -> usize {
+    let layout = FileLayout::from_bytes(bytes).unwrap();
+    layout.blocks.iter().find(|b| b.block_type == id).unwrap().offset as usize
+}
+
+#[test]
+fn validate_written_and_corrupted_files() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("valid.mf4");
+    let path = path.to_str().unwrap();
+    write_sample(path)?;
+
+    let report = validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.warnings().count(), 0);
+    assert!(report.blocks_checked > 5);
+    let bytes = std::fs::read(path)?;
+
+    // More cycles than the data block holds.
+    let mut broken = bytes.clone();
+    let cg = offset_of(&bytes, "##CG");
+    broken[cg + 80..cg + 88].copy_from_slice(&11u64.to_le_bytes());
+    let report = validate_bytes(&broken);
+    assert!(!report.is_valid());
+    assert!(report.errors().any(|i| i.message.contains("cycle counts need 88 bytes")));
+
+    // A channel name link past the end of the file; an unknown block ID.
+    let mut broken = bytes.clone();
+    let cn = offset_of(&bytes, "##CN");
+    broken[cn + 40..cn + 48].copy_from_slice(&(bytes.len() as u64 + 8).to_le_bytes());
+    let report = validate_bytes(&broken);
+    assert!(report.errors().any(|i| i.offset == cn as u64 && i.message.contains("past the end")));
+    let mut broken = bytes.clone();
+    let tx = offset_of(&bytes, "##TX");
+    broken[tx..tx + 4].copy_from_slice(b"##XX");
+    let report = validate_bytes(&broken);
+    assert!(report.errors().any(|i| i.message.contains("unknown block ID \"##XX\"")));
+
+    // Misaligned block and a not finalized file.
+    let mut broken = bytes.clone();
+    broken[cn + 40..cn + 48].copy_from_slice(&(tx as u64 + 4).to_le_bytes());
+    broken[60] = 1;
+    let report = validate_bytes(&broken);
+    assert!(report.warnings().any(|i| i.message == "file is not finalized"));
+    assert!(report.issues.iter().any(|i| i.severity == Severity::Warning && i.message.contains("aligned")));
+
+    assert!(!validate_bytes(b"not an mdf file").is_valid());
+    Ok(())
+}
+
+/// An unsorted data group with 1-byte record IDs: four `Fast` records
+/// (`Time` and `Speed`, 11 bytes each with ID), then one `Log` record with a
+/// VLSD string.
+fn write_unsorted() -> Result<Vec<u8>, MdfError> {
+    let mut w = MdfWriter::from_writer(Cursor::new(Vec::new()));
+    w.init_mdf_file()?;
+    let fast = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&fast, Some(&t), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".into());
+        ch.byte_offset = 8;
+        ch.bit_count = 16;
+    })?;
+    let log = w.add_unsorted_channel_group(&fast, |_| {})?;
+    w.add_channel(&log, None, |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Message".into());
+        ch.channel_type = 1;
+        ch.data = 1;
+        ch.bit_count = 64;
+    })?;
+    let dg = w.data_group_of(&fast).unwrap();
+    w.start_unsorted_data_block(&dg, 1)?;
+    for i in 0..4u64 {
+        w.write_record(&fast, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i)])?;
+    }
+    w.write_record(&log, &[DecodedValue::String("hello".into())])?;
+    w.finish_unsorted_data_block(&dg)?;
+    Ok(w.into_inner()?.into_inner())
+}
+
+#[test]
+fn record_statistics_count_records_per_record_id() -> Result<(), MdfError> {
+    let bytes = write_unsorted()?;
+    let stats = record_statistics(&MDF::from_bytes(bytes.clone())?)?;
+    assert_eq!(stats.len(), 1);
+    let dg = &stats[0];
+    assert_eq!(dg.record_id_len, 1);
+    assert!(dg.is_consistent(), "{dg:?}");
+    let counts: Vec<(Option<usize>, u64)> = dg.records.iter().map(|r| (r.channel_group, r.count)).collect();
+    assert_eq!(counts, [(Some(0), 4), (Some(1), 1)]);
+    assert_eq!(dg.records[0].bytes, 44);
+    assert_eq!(dg.records[0].declared_bytes, Some(44));
+    // The `Log` record holds the offset of its string in the `##SD` block.
+    assert_eq!(dg.records[1].bytes, 1 + 8);
+    assert_eq!(dg.consumed_bytes, dg.data_bytes);
+
+    // Corrupt the record ID of the fourth `Fast` record.
+    let mut corrupt = bytes;
+    let data = offset_of(&corrupt, "##DT") + 24;
+    assert_eq!(corrupt[data + 33], 1);
+    corrupt[data + 33] = 7;
+    let stats = record_statistics(&MDF::from_bytes(corrupt)?)?;
+    let dg = &stats[0];
+    assert!(!dg.is_consistent());
+    assert_eq!(dg.consumed_bytes, 33);
+    assert_eq!(dg.records[0].count, 3);
+    assert_eq!(dg.records[0].declared_cycles, Some(4));
+    let unknown = dg.records.last().unwrap();
+    assert_eq!((unknown.record_id, unknown.channel_group), (7, None));
+    assert_eq!(dg.stopped.as_deref(), Some("record ID 7 at data offset 33 matches no channel group"));
+    Ok(())
+}
+
+#[test]
+fn record_statistics_of_sorted_groups() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("sorted.mf4");
+    let path = path.to_str().unwrap();
+    write_sample(path)?;
+    let stats = record_statistics(&MDF::from_file(path)?)?;
+    assert_eq!(stats[0].record_id_len, 0);
+    assert_eq!(stats[0].records[0].count, 10);
+    assert_eq!(stats[0].data_bytes, 80);
+    assert!(stats[0].is_consistent());
+    Ok(())
+}
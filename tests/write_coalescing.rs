@@ -0,0 +1,84 @@
+//! Header patches are queued and written in coalesced runs.
+
+use std::io::{Cursor, Seek, SeekFrom, Write};
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::MdfWriter;
+
+/// In-memory sink counting the seeks it receives.
+#[derive(Default)]
+struct CountingSink {
+    inner: Cursor<Vec<u8>>,
+    seeks: usize,
+}
+
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for CountingSink {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.seeks += 1;
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn patches_are_deferred_and_coalesced() -> Result<(), MdfError> {
+    let mut w = MdfWriter::from_writer(CountingSink::default());
+    w.init_mdf_file()?;
+    for g in 0..50u64 {
+        let cg = w.add_channel_group(None, |_| {})?;
+        let t = w.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".into());
+            ch.bit_count = 64;
+        })?;
+        w.set_time_channel(&t)?;
+        w.add_channel(&cg, Some(&t), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(format!("Signal{g}"));
+            ch.bit_count = 32;
+        })?;
+        w.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..3 {
+            w.write_record(&cg, &[DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(g + i)])?;
+        }
+        w.finish_data_block(&cg)?;
+    }
+
+    let sink = w.into_inner()?;
+    // Nothing is patched before the end. Then one seek per run of adjacent
+    // patches: the ~700 patched fields would take two seeks each on their own.
+    assert!(sink.seeks < 700, "{} seeks", sink.seeks);
+
+    let mdf = MDF::from_bytes(sink.inner.into_inner())?;
+    assert_eq!(mdf.channel_groups().len(), 50);
+    assert_eq!(mdf.channel("Signal49").unwrap().values_u64()?, vec![Some(49), Some(50), Some(51)]);
+    Ok(())
+}
+
+#[test]
+fn flush_writes_queued_patches() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("flush.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new_with_capacity(path, 64)?;
+    w.init_mdf_file()?;
+    w.set_start_time(1_700_000_000_000_000_000, 60, 0, 2, 0)?;
+
+    // ##HD abs_time lives at offset 72 of the block at 64.
+    let abs_time = |bytes: &[u8]| u64::from_le_bytes(bytes[136..144].try_into().unwrap());
+    assert_ne!(abs_time(&std::fs::read(path)?), 1_700_000_000_000_000_000);
+    w.flush()?;
+    assert_eq!(abs_time(&std::fs::read(path)?), 1_700_000_000_000_000_000);
+    w.finalize()
+}
@@ -0,0 +1,87 @@
+//! Identification block fields set through `WriterConfig`.
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::finalize::is_unfinalized;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::writer::{ChannelGroupId, MdfWriter, WriterConfig};
+
+/// Open a group with a float `Time` master and write `count` records.
+fn write_records(w: &mut MdfWriter, count: u64) -> Result<ChannelGroupId, MdfError> {
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..count {
+        w.write_record(&cg, &[DecodedValue::Float(i as f64)])?;
+    }
+    Ok(cg)
+}
+
+#[test]
+fn version_and_program_identifier_are_written() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("writer_config_version.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new(path)?;
+    assert_eq!(w.config().version, 410);
+    w.init_mdf_file()?;
+    // Set after init: the identification block is rewritten.
+    w.set_config(WriterConfig { version: 420, program_identifier: "Logger1".into(), ..WriterConfig::default() })?;
+    let cg = write_records(&mut w, 3)?;
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+
+    let bytes = std::fs::read(path)?;
+    assert_eq!(&bytes[..24], b"MDF     4.20    Logger1 ");
+    assert_eq!(u16::from_le_bytes([bytes[28], bytes[29]]), 420);
+    let metadata = MDF::from_file(path)?.metadata()?;
+    assert_eq!(metadata.version, "4.20");
+    assert_eq!(metadata.program, "Logger1");
+    Ok(())
+}
+
+#[test]
+fn unfinalized_flags_last_until_finalize() -> Result<(), MdfError> {
+    let path = std::env::temp_dir().join("writer_config_unfinalized.mf4");
+    let path = path.to_str().unwrap();
+    let mut w = MdfWriter::new(path)?;
+    w.set_config(WriterConfig {
+        standard_unfinalized_flags: 0x05,
+        custom_unfinalized_flags: 0x100,
+        ..WriterConfig::default()
+    })?;
+    w.init_mdf_file()?;
+    let cg = write_records(&mut w, 4)?;
+    w.flush()?;
+
+    let bytes = std::fs::read(path)?;
+    assert!(is_unfinalized(&bytes));
+    assert_eq!(&bytes[60..64], &[0x05, 0x00, 0x00, 0x01]);
+
+    w.finish_data_block(&cg)?;
+    w.finalize()?;
+    let bytes = std::fs::read(path)?;
+    assert!(!is_unfinalized(&bytes));
+    assert_eq!(&bytes[..8], b"MDF     ");
+    assert_eq!(&bytes[60..64], &[0; 4]);
+    let mdf = MDF::from_file(path)?;
+    assert_eq!(mdf.channel("Time").unwrap().values_f64()?, [0.0, 1.0, 2.0, 3.0].map(Some).to_vec());
+    Ok(())
+}
+
+#[test]
+fn invalid_configurations_are_rejected() {
+    let mut w = MdfWriter::from_writer(std::io::Cursor::new(Vec::new()));
+    let err = w.set_config(WriterConfig { version: 310, ..WriterConfig::default() }).unwrap_err();
+    assert!(matches!(err, MdfError::InvalidVersionString(_)), "{err:?}");
+    let err = w
+        .set_config(WriterConfig { program_identifier: "much too long".into(), ..WriterConfig::default() })
+        .unwrap_err();
+    assert!(matches!(err, MdfError::BlockSerializationError(_)), "{err:?}");
+    assert_eq!(w.config(), &WriterConfig::default());
+}
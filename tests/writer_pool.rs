@@ -0,0 +1,100 @@
+//! Encoding records on worker threads with `MdfWriter::writer_pool`.
+
+use std::fs::File;
+use std::io::BufWriter;
+
+use mf4_rs::api::mdf::MDF;
+use mf4_rs::blocks::common::DataType;
+use mf4_rs::error::MdfError;
+use mf4_rs::parsing::decoder::DecodedValue;
+use mf4_rs::validate::validate_file;
+use mf4_rs::writer::{ChannelGroupId, CompressionMethod, MdfWriter};
+
+type FileWriter = MdfWriter<BufWriter<File>>;
+
+/// Adds `Time` and a second channel configured by `second` in a new group.
+fn add_group<F>(w: &mut FileWriter, second: F) -> Result<ChannelGroupId, MdfError>
+where
+    F: FnOnce(&mut mf4_rs::blocks::channel_block::ChannelBlock),
+{
+    let cg = w.add_channel_group(None, |_| {})?;
+    let t = w.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    w.set_time_channel(&t)?;
+    w.add_channel(&cg, Some(&t), second)?;
+    Ok(cg)
+}
+
+#[test]
+fn pool_writes_records_in_order() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("pool.mf4");
+    let path = path.to_str().unwrap();
+
+    let mut w = MdfWriter::from_writer(BufWriter::new(File::create(path)?));
+    w.init_mdf_file()?;
+    let counter = add_group(&mut w, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 64;
+    })?;
+    let blob = add_group(&mut w, |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Blob".into());
+        ch.bit_count = 64;
+        ch.channel_type = 1;
+        ch.data = 1;
+    })?;
+    w.start_data_block_for_cg(&counter, 0)?;
+    // Only one group at a time can stream uncompressed blocks.
+    w.set_compression(CompressionMethod::Zlib)?;
+    w.start_data_block_for_cg(&blob, 0)?;
+
+    // 300k 16-byte records cross a 4 MiB DT block boundary.
+    let mut pool = w.writer_pool(4);
+    for b in 0..300u64 {
+        let batch = (b * 1000..(b + 1) * 1000)
+            .map(|i| vec![DecodedValue::Float(i as f64), DecodedValue::UnsignedInteger(i)])
+            .collect();
+        pool.write_records(&counter, batch)?;
+        pool.write_record(&blob, vec![DecodedValue::Float(b as f64), DecodedValue::ByteArray(vec![b as u8; 3])])?;
+    }
+    let mut w = pool.finish()?;
+    w.finish_data_block(&counter)?;
+    w.finish_data_block(&blob)?;
+    w.finalize()?;
+
+    let report = validate_file(path)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    let mdf = MDF::from_file(path)?;
+    let counters = mdf.channel("Counter").unwrap().values_u64()?;
+    assert_eq!(counters, (0..300_000).map(Some).collect::<Vec<_>>());
+    let blobs = mdf.channel("Blob").unwrap().values()?;
+    assert_eq!(blobs.len(), 300);
+    assert_eq!(blobs[299], Some(DecodedValue::ByteArray(vec![43; 3])));
+    Ok(())
+}
+
+#[test]
+fn pool_reports_encoding_errors() -> Result<(), MdfError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("pool_error.mf4");
+    let mut w = MdfWriter::from_writer(BufWriter::new(File::create(&path)?));
+    w.init_mdf_file()?;
+    let cg = add_group(&mut w, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".into());
+        ch.bit_count = 32;
+    })?;
+    w.start_data_block_for_cg(&cg, 0)?;
+
+    let mut pool = w.writer_pool(0);
+    pool.write_record(&cg, vec![DecodedValue::Float(0.0), DecodedValue::UnsignedInteger(0)])?;
+    pool.write_record(&cg, vec![DecodedValue::UnsignedInteger(1)])?;
+    let err = pool.finish().err().expect("value count mismatch");
+    assert!(matches!(err, MdfError::ValueCountMismatch { expected: 2, actual: 1 }), "{err}");
+    Ok(())
+}